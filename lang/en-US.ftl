@@ -1,24 +1,107 @@
 ludusavi = Ludusavi
 
-language = Language
-language-font-compatibility = Some languages may require a custom font.
-font = Font
-
 cli-backup-target-already-exists = The backup target already exists ( {$path} ). Either choose a different --path or delete it with --force.
 cli-unrecognized-games = No info for these games:
 cli-confirm-restoration = Do you want to restore from {$path}?
+cli-confirm-mirror-removal =
+    { $count ->
+        [one] The restore will also remove {$count} file that isn't in the backup. Do you want to continue?
+       *[other] The restore will also remove {$count} files that aren't in the backup. Do you want to continue?
+    }
+cli-confirm-undo-restore = Do you want to undo the last restore?
+cli-undo-restore-summary = Files reverted: {$count}
 cli-unable-to-request-confirmation = Unable to request confirmation.
     .winpty-workaround = If you are using a Bash emulator (like Git Bash), try running winpty.
+cli-unable-to-write-output-file = Unable to write the report to {$path}
+cli-config-same-as-default = Your config is the same as the default.
+cli-no-xbox-saves = No Xbox save containers found for this game.
+cli-xbox-container-unsupported = UNSUPPORTED
+xbox-save-file = {$path} (Xbox save: {$name})
+cli-unknown-redirect-game = Warning: Redirects are configured for an unknown game, so they will be ignored: {$name}
+cli-diff-no-changes = The backups are the same.
+cli-attention-nothing = No games need attention.
+cli-confirm-clean = Do you want to remove the folders listed above from {$path}?
+cli-clean-nothing = There are no empty or incomplete game folders to remove.
+cli-clean-summary = Removed folders: {$count} ({$total-size})
+cli-clean-summary-dry-run = Folders that would be removed: {$count} ({$total-size})
+cli-provenance-unknown = Unknown provenance (made before Ludusavi recorded this)
+cli-provenance-version = Ludusavi version: {$version}
+cli-provenance-manifest = Manifest: {$path}
+cli-provenance-manifest-etag = Manifest ETag: {$id}
+cli-provenance-config-hash = Config hash: {$id}
 
 badge-failed = FAILED
+badge-mismatched = MISMATCH
+badge-partial = PARTIAL
+badge-removed = REMOVED
+badge-denied = ACCESS DENIED
+badge-cloud-only = CLOUD ONLY
+badge-empty = EMPTY
 badge-duplicates = DUPLICATES
 badge-duplicated = DUPLICATED
 badge-ignored = IGNORED
+badge-deferred = DEFERRED
+badge-root-unavailable = ROOT UNAVAILABLE
+badge-frozen = FROZEN
+badge-system = SYSTEM
+badge-ownership = OWNERSHIP
+badge-corrupted = CORRUPTED
+badge-orphaned = ORPHANED
+badge-never-backed-up = NEVER BACKED UP
 badge-redirected-from = FROM: {$path}
+badge-restore-create = NEW
+badge-restore-overwrite = OVERWRITE
+badge-restore-skip = IDENTICAL
+badge-diff-added = ADDED
+badge-diff-removed = REMOVED
+badge-diff-modified = MODIFIED
+badge-pass = PASS
+badge-warning = WARNING
 
 some-entries-failed = Some entries failed to process; look for {badge-failed} in the output for details. Double check whether you can access those files or whether their paths are very long.
 
+some-checks-failed = Some checks failed; look for {badge-failed} in the output above for how to fix them.
+
 cli-game-line-item-redirected = Redirected from: {$path}
+cli-game-registry-values = Registry values: {$backed-up} backed up, {$skipped} skipped
+
+failure-reason-permission-denied = permission denied
+failure-reason-not-found = not found
+failure-reason-disk-full = disk full
+failure-reason-already-exists = already exists
+failure-reason-timed-out = timed out
+failure-reason-unknown = unknown reason
+
+warning-denied-file-skipped = {$name}: a file was skipped because access was denied
+warning-deferral-limit-reached = {$name}: backed up even though its saves changed recently, since it was deferred too many times in a row
+warning-alternate-streams-stripped = {$name}: a file's alternate data streams were left out of the backup
+warning-alternate-streams-unsupported = {$name}: a file's alternate data streams were skipped because the destination can't store them
+warning-case-normalized = {$name}: an existing file was renamed to match the case of its name in the backup
+warning-weak-identical-check = {$name}: a file couldn't be read, so it was only compared with the backup by size and modification time
+warning-repeated = (x{$count})
+skip-reason-ignored = {$count} ignored
+skip-reason-deferred = {$count} deferred until their saves stop changing
+skip-reason-cancelled = {$count} cancelled
+skip-reason-root-unavailable = {$count} held back because a root is unavailable
+skip-reason-frozen = {$count} frozen
+warnings-header = Warnings:
+error-report-header = Errors:
+error-report-collapsed = {$count} entries in {$total-games} {$total-games ->
+    [one] game
+    *[other] games
+}, including:
+
+ownership-owner-changed = owner would be user {$after} instead of {$before}
+ownership-mode-changed = mode would change from {$before} to {$after}
+ownership-unprivileged = only root can change the owner
+
+cloud-only-notice = Nothing was found on disk. This game only keeps its saves in the cloud.
+
+empty-game-notice = Nothing was found for this game.
+
+save-locations-header = Detected save locations:
+save-locations-unscanned-notice = Run a preview to see this game's save locations.
+
 cli-summary =
     .succeeded =
         Overall:
@@ -30,6 +113,57 @@ cli-summary =
           Games: {$processed-games} of {$total-games}
           Size: {$processed-size} of {$total-size}
           Location: {$path}
+    .selected =
+        Overall:
+          Games: {$processed-games} of {$available-games} (only the games you selected)
+          Size: {$processed-size}
+          Location: {$path}
+cli-no-games-found = No games were found. Check your roots and make sure the manifest has been downloaded.
+cli-all-games-ignored = All of the games that were found are ignored. Check your ignored games and files.
+cli-summary-skipped-identical = Skipped as {badge-restore-skip}: {$count}
+cli-summary-copied = Written: {$size}
+cli-summary-skipped = Skipped: {$reasons}
+cli-summary-target-succeeded = Target {$path}: {$processed-games} games
+cli-summary-target-failed = Target {$path}: {$count} of {$processed-games} games failed
+cli-summary-target-unusable = Target {$path}: couldn't be used, so nothing was written to it
+cli-game-frozen = {$name} is on the restore freeze list, so it will be skipped. Use --force to restore it anyway.
+cli-summary-dry-run = Dry run: nothing was written or overwritten.
+cli-summary-estimate-files = Files: {$count}
+cli-verify-sample-passed = Checked a sample of {$count} of {$total} restored files (seed: {$id}), and they all matched the backup. With 95% confidence, fewer than {$percent}% of the restored files differ from the backup.
+cli-verify-sample-failed = Checked a sample of {$count} of {$total} restored files (seed: {$id}), and {$mismatched} did not match the backup. About {$percent}% of the restored files may differ from the backup.
+cli-verify-sample-complete = Checked all {$total} restored files (seed: {$id}), and they all matched the backup.
+cli-summary-estimate = Estimate: these totals only come from file sizes on disk. No file contents were read, and nothing was backed up.
+cli-scan-cache-used = Games reused from the last scan: {$count} (use --no-cache to scan them again)
+cli-scan-heartbeat = Scanning {$name}: {$count} files so far
+
+doctor-check-config = Config file
+doctor-check-manifest = Manifest
+doctor-check-root = Root: {$path}
+doctor-check-backup-target = Backup target: {$path}
+doctor-check-registry = Registry access
+doctor-check-manifest-conflicts = Manifest conflicts
+doctor-check-steam-user = Steam account: {$name}
+doctor-hint-manifest-conflicts = These games are defined differently in more than one manifest: {$name}. The main manifest takes precedence, followed by `manifest.secondary` in order, so reorder that list in the config to choose which definition to use.
+doctor-hint-config-missing = There is no config file yet, so the default settings were checked instead.
+doctor-hint-manifest-missing = The manifest has not been downloaded yet. It will be downloaded the next time you back up or restore.
+doctor-hint-root-missing = This folder does not exist. Check the path for typos, or remove the root if you no longer need it.
+doctor-hint-root-unreadable = This folder cannot be read. Check that you have permission to access it.
+doctor-hint-steam-user-missing = None of your Steam roots have saves for this account. Check the account ID in the config, or log in to Steam on this computer.
+
+find-game-manifest = Manifest definition:
+find-game-roots = Roots searched:
+find-game-root-install-dir = install folder: {$name}
+find-game-root-no-install-dir = install folder not found
+find-game-paths = Paths checked:
+find-game-path-matched = {$count ->
+    [one] 1 match
+    *[other] {$count} matches
+}
+find-game-path-no-match = no matches
+find-game-path-unresolved = not applicable to this root
+find-game-path-other-os = skipped because it only applies to another OS
+find-game-path-ignored = skipped by the backup filter
+find-game-nothing-found = Nothing was found.
 
 button-backup = Back up
 button-preview = Preview
@@ -40,7 +174,20 @@ button-nav-custom-games = CUSTOM GAMES
 button-nav-other = OTHER
 button-add-root = Add root
 button-find-roots = Find roots
+button-merge-roots = Merge
+button-skip-root = Skip
 button-add-redirect = Add redirect
+button-browse = Browse
+button-add = Add
+button-remove = Remove
+button-search = Search
+button-customize = Customize
+button-open-backup-folder = Open backup folder
+button-open-wiki = Open wiki
+button-open-folder = Open folder
+button-copy-path = Copy path
+button-increase = Increase
+button-decrease = Decrease
 button-add-game = Add game
 button-continue = Continue
 button-cancel = Cancel
@@ -54,14 +201,74 @@ button-disable-all = Disable all
 no-roots-are-configured = Add some roots to back up even more data.
 
 config-is-invalid = Error: The config file is invalid.
+unknown-profile = Error: There is no profile named "{$name}" in the config file.
 manifest-is-invalid = Error: The manifest file is invalid.
 manifest-cannot-be-updated = Error: Unable to check for an update to the manifest file. Is your Internet connection down?
-cannot-prepare-backup-target = Error: Unable to prepare backup target (either creating or emptying the folder). If you have the folder open in your file browser, try closing it: {$path}
-restoration-source-is-invalid = Error: The restoration source is invalid (either doesn't exist or isn't a directory). Please double check the location: {$path}
+manifest-not-found = Error: The manifest file does not exist: {$path}
+cannot-prepare-backup-target = Error: Unable to prepare backup target (either creating or emptying the folder, or writing the archive). If you have the folder open in your file browser, try closing it: {$path}
+backup-target-locked = Error: Another backup is already running for this target. Wait for it to finish and try again. Lock file: {$path}
+backup-target-locked-stale = Error: The backup target is locked by an earlier backup that did not finish. If no other backup is running, use --force or delete the lock file: {$path}
+restoration-source-is-invalid = Error: The restoration source is invalid (either doesn't exist, isn't a directory, or isn't a readable archive). Please double check the location: {$path}
+backup-case-collision = Error: The backup target doesn't distinguish between uppercase and lowercase letters, and this file can't be given a unique name there: {$path}
+backup-migration-overlap = Error: The migration target can't be the same as the source, inside of it, or contain it, since the target is cleared first: {$path}
+backup-signature-invalid = Error: The backup's signature is missing or doesn't match, so it may have been modified: {$path}
+backup-signing-failed = Error: Unable to sign the backup. It was written, but it will fail signature checks until it's backed up again: {$path}
+signature-key-invalid = Error: The signing key is not a base64-encoded ed25519 key. It came from: {$path}
+signature-key-missing = Error: No signature key is set up. Signing backups needs signature.key or LUDUSAVI_SIGNING_KEY, and checking signatures needs either of those or signature.publicKey or LUDUSAVI_SIGNING_PUBLIC_KEY.
 registry-issue = Error: Some registry entries were skipped.
+registry-file-is-invalid = Error: Unable to read the registry keys from this file: {$path}
 unable-to-browse-file-system = Error: Unable to browse on your system.
 unable-to-open-directory = Error: Unable to open directory:
 unable-to-open-url = Error: Unable to open URL:
+cannot-prepare-undo-snapshot = Error: Unable to save files for undoing the restore: {$path}
+no-undo-snapshot = Error: There is no restore to undo. Make sure that the undo snapshot option is enabled before restoring.
+manifest-updated = Manifest updated: +{$added} games, {$changed} changed, {$removed} removed
+manifest-initial = Downloaded the manifest for the first time.
+cli-manifest-changes-none = The manifest hasn't been updated yet.
+cli-manifest-valid = The manifest file is valid.
+cli-settings-exported = Exported settings to: {$path}
+cli-archive-with-merge = An archive is always a new full backup, so it can't be merged into. Use `--no-merge` or set `backup.merge` to false.
+cli-archive-with-differential = An archive is always a new full backup, so `backup.retention.differential` must be 0 to write one.
+backup-grouping-kept = Warning: This target already has backups with a different grouping, so new backups will follow that instead. To change it, back up without merging or use `migrate-backup`: {$path}
+root-unavailable = Warning: Skipping this root because it doesn't seem to be mounted: {$path}
+backup-target-skipped = Warning: Skipping this backup target, so it won't get this backup: {$path}
+schedule-last-run = Last backup: {$time}
+schedule-last-success = Last successful backup: {$time}
+schedule-next = Next recommended backup: {$time}
+schedule-never = never
+schedule-failed = failed
+schedule-due-now = now
+cli-settings-imported = Imported settings from: {$path}
+cli-game-exported = Exported {$name} to: {$path}
+cli-game-imported = Imported {$name} into: {$path}
+cli-imported-registry-warning = Warning: This game's backup includes registry data from another machine. Check any paths in it before restoring, since they may not exist here.
+manifest-issue-unknown-placeholder = unknown placeholder {$name}
+manifest-issue-malformed-placeholder = "<" or ">" outside of a placeholder
+manifest-issue-malformed-glob = malformed glob
+manifest-conflict = Warning: {$name} is defined differently in more than one manifest. Using the definition from {$path}.
+manifest-cached = Using the cached manifest (updated {$age} ago).
+portable-dir-not-writable = Error: Portable mode is enabled, but Ludusavi cannot write to its folder: {$path}
+game-is-running = Error: These games are running, so restoring their saves now could corrupt them:
+settings-cannot-be-exported = Error: Unable to write the settings archive: {$path}
+settings-cannot-be-imported = Error: Unable to import settings from this archive: {$path}
+game-cannot-be-exported = Error: Unable to write the game archive: {$path}
+game-cannot-be-imported = Error: Unable to import a game from this archive: {$path}
+terminal-ui-failed = Error: The terminal interface stopped unexpectedly.
+tui-help = Up/Down: move, Space: select, a: select all, /: filter, Tab: switch mode, p: preview, Enter: run, q: quit
+tui-filter-help = Type to filter, Enter: keep filter, Esc: clear filter
+tui-filter = Filter: {$name}
+tui-selected = Selected: {$count} of {$total}
+tui-no-games = No games found.
+tui-confirm-hint = Press y to continue, or any other key to cancel.
+tui-press-enter = Press Enter to return.
+tui-fallback-mode = Back up or restore? [b/r, or q to quit]
+tui-fallback-filter = Filter games by name (leave blank to list all):
+tui-fallback-select = Enter the numbers of the games to select, separated by spaces:
+tui-fallback-action = Preview, run, or quit? [p/r/q]
+tui-fallback-confirm = Continue? [y/n]
+copied-to-clipboard = Copied to clipboard: {$path}
+unable-to-copy-to-clipboard = Error: Unable to copy to clipboard: {$path}
+scan-cache-used = Games reused from the last scan: {$count}
 
 processed-games = {$total-games} {$total-games ->
     [one] game
@@ -72,6 +279,8 @@ processed-games-subset = {$processed-games} of {$total-games} {$total-games ->
     *[other] games
 }
 processed-size-subset = {$processed-size} of {$total-size}
+estimated-backup-size = Estimated backup size: {$total-size}
+no-games-selected = No games are selected.
 
 field-backup-target = Back up to:
 toggle-backup-merge = Merge
@@ -80,6 +289,7 @@ field-custom-files = Paths:
 field-custom-registry = Registry:
 field-search = Search:
 field-sort = Sort:
+field-profile = Profile:
 field-redirect-source =
     .placeholder = Source (original location)
 field-redirect-target =
@@ -92,6 +302,7 @@ field-backup-excluded-items = Backup exclusions:
 field-retention-full = Full:
 field-retention-differential = Differential:
 
+store-ea = EA app
 store-epic = Epic
 store-gog = GOG
 store-gog-galaxy = GOG Galaxy
@@ -106,7 +317,16 @@ store-other = Other
 
 sort-name = Name
 sort-size = Size
-sort-reversed = Reversed
+sort-ascending = Ascending
+sort-descending = Descending
+
+profile-base = (Default)
+
+search-scope-name = Name
+search-scope-store = Store
+search-scope-path = Path
+search-scope-all = All
+search-failed-only = Failed only
 
 explanation-for-exclude-other-os-data =
     In backups, exclude save locations that have only been confirmed on another
@@ -122,6 +342,23 @@ explanation-for-exclude-store-screenshots =
     screenshot functionality, this setting will not affect whether those
     screenshots are backed up.
 
+steam-user-all = All users
+steam-user-current = Current user
+steam-user-id = User {$id}
+
+explanation-for-exclude-hidden-files =
+    In backups, exclude hidden files. On Windows, this also excludes files
+    marked as system files. Some games keep their saves in such files,
+    so they're included by default.
+
+explanation-for-included-tag-save =
+    In backups, include locations that the manifest marks as saves.
+explanation-for-included-tag-config =
+    In backups, include locations that the manifest marks as configuration,
+    like settings and key bindings.
+explanation-for-included-tag-other =
+    In backups, include locations that the manifest marks with other tags.
+
 consider-doing-a-preview =
     If you haven't already, consider doing a preview first so that there
     are no surprises.
@@ -145,5 +382,15 @@ confirm-restore =
 
     {consider-doing-a-preview}
 
+confirm-restore-mirror-unsupported =
+    Your config uses the mirror restore mode, but that's only available in the CLI.
+    Files that aren't in the backup will be left alone.
+
 confirm-add-missing-roots = Add these roots?
 no-missing-roots = No additional roots found.
+root-confidence-certain = detected
+root-confidence-likely = likely
+root-confidence-guess = guessed
+root-overlap-duplicate = This is the same folder as another root: {$path}
+root-overlap-inside = This is inside another root, so its saves may be scanned twice: {$path}
+root-overlap-contains = This contains another root, so its saves may be scanned twice: {$path}
@@ -0,0 +1,208 @@
+use crate::{config::AlternateStreamPolicy, path::StrictPath};
+
+/// What happened to a file's NTFS alternate data streams (like `Zone.Identifier`) when it was copied.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum Outcome {
+    /// The file didn't have any.
+    #[default]
+    None,
+    /// These streams were copied along with the file.
+    Kept(Vec<String>),
+    /// The file had streams, but they were left out on purpose.
+    Stripped,
+    /// The file had streams, but the destination can't store them.
+    Unsupported,
+}
+
+/// Names of the file's alternate data streams, not including its main content.
+/// This is always empty outside of Windows.
+#[cfg(target_os = "windows")]
+pub fn list(path: &StrictPath) -> Vec<String> {
+    use std::os::windows::ffi::OsStrExt;
+    use winapi::um::{
+        fileapi::{FindClose, FindFirstStreamW, FindNextStreamW, WIN32_FIND_STREAM_DATA},
+        handleapi::INVALID_HANDLE_VALUE,
+        minwinbase::FindStreamInfoStandard,
+    };
+
+    let wide: Vec<u16> = std::ffi::OsStr::new(&path.interpret())
+        .encode_wide()
+        .chain(Some(0))
+        .collect();
+    let mut names = vec![];
+
+    // Safety: The name is null-terminated, and the handle is closed once we're done with it.
+    unsafe {
+        let mut data: WIN32_FIND_STREAM_DATA = std::mem::zeroed();
+        let handle = FindFirstStreamW(wide.as_ptr(), FindStreamInfoStandard, &mut data as *mut _ as *mut _, 0);
+        if handle == INVALID_HANDLE_VALUE {
+            return names;
+        }
+        loop {
+            let len = data
+                .cStreamName
+                .iter()
+                .position(|x| *x == 0)
+                .unwrap_or(data.cStreamName.len());
+            let name = String::from_utf16_lossy(&data.cStreamName[..len]);
+            // Streams are listed like `:Zone.Identifier:$DATA`, and the main content is `::$DATA`.
+            if let Some(name) = name.strip_prefix(':').and_then(|x| x.strip_suffix(":$DATA")) {
+                if !name.is_empty() {
+                    names.push(name.to_string());
+                }
+            }
+            if FindNextStreamW(handle, &mut data as *mut _ as *mut _) == 0 {
+                break;
+            }
+        }
+        FindClose(handle);
+    }
+
+    names
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn list(_path: &StrictPath) -> Vec<String> {
+    vec![]
+}
+
+/// Whether the file system at `path` (or the nearest folder above it that exists)
+/// can store alternate data streams, like NTFS.
+#[cfg(target_os = "windows")]
+pub fn supported(path: &StrictPath) -> bool {
+    use std::os::windows::ffi::OsStrExt;
+    use winapi::um::{
+        fileapi::{GetVolumeInformationW, GetVolumePathNameW},
+        winnt::FILE_NAMED_STREAMS,
+    };
+
+    let path = path.as_std_path_buf();
+    let existing = match path.ancestors().find(|x| x.exists()) {
+        Some(x) => x,
+        None => return false,
+    };
+    let wide: Vec<u16> = existing.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut volume = [0u16; 261];
+    let mut flags = 0;
+
+    // Safety: The buffer lengths are passed along with the buffers.
+    unsafe {
+        if GetVolumePathNameW(wide.as_ptr(), volume.as_mut_ptr(), volume.len() as u32) == 0 {
+            return false;
+        }
+        if GetVolumeInformationW(
+            volume.as_ptr(),
+            std::ptr::null_mut(),
+            0,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            &mut flags,
+            std::ptr::null_mut(),
+            0,
+        ) == 0
+        {
+            return false;
+        }
+    }
+
+    flags & FILE_NAMED_STREAMS != 0
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn supported(_path: &StrictPath) -> bool {
+    false
+}
+
+fn stream_path(path: &StrictPath, name: &str) -> String {
+    format!("{}:{}", path.interpret(), name)
+}
+
+fn copy(source: &StrictPath, target: &StrictPath, names: &[String]) -> std::io::Result<()> {
+    for name in names {
+        let mut reader = std::fs::File::open(stream_path(source, name))?;
+        let mut writer = std::fs::File::create(stream_path(target, name))?;
+        std::io::copy(&mut reader, &mut writer)?;
+    }
+    Ok(())
+}
+
+/// Handle the streams of `source` after it has been copied to `target` for a backup.
+/// Copying may or may not bring the streams along, depending on how it was done,
+/// so this makes sure that `target` has exactly what the policy asks for.
+pub fn back_up(policy: AlternateStreamPolicy, source: &StrictPath, target: &StrictPath) -> Outcome {
+    let names = list(source);
+    if names.is_empty() {
+        return Outcome::None;
+    }
+
+    match policy {
+        AlternateStreamPolicy::Strip => {
+            for name in list(target) {
+                let _ = std::fs::remove_file(stream_path(target, &name));
+            }
+            Outcome::Stripped
+        }
+        AlternateStreamPolicy::Include => {
+            if supported(target) && copy(source, target, &names).is_ok() {
+                Outcome::Kept(names)
+            } else {
+                Outcome::Unsupported
+            }
+        }
+    }
+}
+
+/// Put back any streams that were stored with the backed up `source`.
+pub fn restore(source: &StrictPath, target: &StrictPath) -> Outcome {
+    let names = list(source);
+    if names.is_empty() {
+        Outcome::None
+    } else if supported(target) && copy(source, target, &names).is_ok() {
+        Outcome::Kept(names)
+    } else {
+        Outcome::Unsupported
+    }
+}
+
+#[cfg(all(test, target_os = "windows"))]
+mod tests {
+    use super::*;
+    use crate::path::TempPath;
+    use pretty_assertions::assert_eq;
+
+    fn read_stream(path: &StrictPath, name: &str) -> String {
+        std::fs::read_to_string(stream_path(path, name)).unwrap()
+    }
+
+    #[test]
+    fn can_round_trip_alternate_streams() {
+        let folder = TempPath::new("ludusavi-test-alternate-streams");
+        std::fs::create_dir_all(folder.interpret()).unwrap();
+        if !supported(&folder) {
+            return;
+        }
+
+        let original = folder.joined("original.sav");
+        let included = folder.joined("included.sav");
+        let stripped = folder.joined("stripped.sav");
+        let restored = folder.joined("restored.sav");
+        std::fs::write(original.interpret(), "content").unwrap();
+        std::fs::write(stream_path(&original, "Zone.Identifier"), "[ZoneTransfer]").unwrap();
+        for target in [&included, &stripped] {
+            std::fs::copy(original.interpret(), target.interpret()).unwrap();
+        }
+        std::fs::write(restored.interpret(), "content").unwrap();
+
+        let included_outcome = back_up(AlternateStreamPolicy::Include, &original, &included);
+        let stripped_outcome = back_up(AlternateStreamPolicy::Strip, &original, &stripped);
+        let restored_outcome = restore(&included, &restored);
+        let stripped_streams = list(&stripped);
+        let restored_stream = read_stream(&restored, "Zone.Identifier");
+
+        assert_eq!(Outcome::Kept(vec!["Zone.Identifier".to_string()]), included_outcome);
+        assert_eq!(Outcome::Stripped, stripped_outcome);
+        assert_eq!(Vec::<String>::new(), stripped_streams);
+        assert_eq!(Outcome::Kept(vec!["Zone.Identifier".to_string()]), restored_outcome);
+        assert_eq!("[ZoneTransfer]", restored_stream);
+    }
+}
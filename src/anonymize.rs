@@ -0,0 +1,113 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::prelude::CASE_INSENSITIVE_OS;
+
+/// Windows security identifiers for user accounts, like in `HKEY_USERS/S-1-5-21-...`.
+static RE_SID: Lazy<Regex> = Lazy::new(|| Regex::new(r"S-1-5-21(-\d+){4}").unwrap());
+
+/// Text that can't continue a path segment or name, so that `/home/al` doesn't match `/home/alice`.
+const BOUNDARY: &str = r"[^\w.\-]";
+
+/// Replaces the current user's home folder, user name, and account SIDs with placeholders,
+/// so that reports can be shared without revealing them.
+#[derive(Clone, Debug)]
+pub struct Anonymizer {
+    home: Option<Regex>,
+    user: Option<Regex>,
+}
+
+impl Anonymizer {
+    pub fn new(home: Option<String>, user: Option<String>) -> Self {
+        let flags = if CASE_INSENSITIVE_OS { "(?mi)" } else { "(?m)" };
+
+        let home = home
+            .map(|x| x.replace('\\', "/").trim_end_matches('/').to_string())
+            // A home folder at the root of the drive would swallow every path.
+            .filter(|x| x.trim_start_matches('/').contains('/'))
+            .map(|x| {
+                let separated = regex::escape(&x).replace('/', r"[/\\]");
+                Regex::new(&format!("{}{}({}|$)", flags, separated, BOUNDARY)).unwrap()
+            });
+        let user = user.filter(|x| !x.trim().is_empty()).map(|x| {
+            Regex::new(&format!(
+                "{}(^|{}){}({}|$)",
+                flags,
+                BOUNDARY,
+                regex::escape(&x),
+                BOUNDARY
+            ))
+            .unwrap()
+        });
+
+        Self { home, user }
+    }
+
+    pub fn current() -> Self {
+        Self::new(
+            dirs::home_dir().map(|x| crate::path::render_pathbuf(&x)),
+            Some(whoami::username()),
+        )
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        if let Some(home) = &self.home {
+            text = home.replace_all(&text, "<HOME>$1").to_string();
+        }
+        if let Some(user) = &self.user {
+            // Adjacent names share a boundary, so one pass might not catch both.
+            loop {
+                let replaced = user.replace_all(&text, "$1<USER>$2").to_string();
+                if replaced == text {
+                    break;
+                }
+                text = replaced;
+            }
+        }
+        RE_SID.replace_all(&text, "<SID>").to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn s(text: &str) -> String {
+        text.to_string()
+    }
+
+    #[test]
+    fn can_anonymize_paths_without_leaking_user_name() {
+        let anonymizer = Anonymizer::new(Some(s("/home/alice")), Some(s("alice")));
+        let text = r#"
+            /home/alice/.local/share/game/save.dat
+            /home/alicea/save.dat
+            /mnt/games/alice/alice/save.dat
+            "C:\\Users\\alice\\save.dat"
+            HKEY_USERS/S-1-5-21-1004336348-1177238915-682003330-1001/Software/Game
+            malice.txt
+        "#;
+
+        let anonymized = anonymizer.apply(text);
+        assert_eq!(
+            r#"
+            <HOME>/.local/share/game/save.dat
+            /home/alicea/save.dat
+            /mnt/games/<USER>/<USER>/save.dat
+            "C:\\Users\\<USER>\\save.dat"
+            HKEY_USERS/<SID>/Software/Game
+            malice.txt
+        "#,
+            anonymized,
+        );
+        assert_eq!(anonymized, anonymizer.apply(&anonymized));
+    }
+
+    #[test]
+    fn ignores_home_at_root_and_blank_user_name() {
+        let anonymizer = Anonymizer::new(Some(s("/")), Some(s("")));
+        assert_eq!("/save.dat", anonymizer.apply("/save.dat"));
+    }
+}
@@ -0,0 +1,323 @@
+use crate::{path::StrictPath, prelude::Error};
+
+/// Whether the archive should be gzip-compressed, based on its file name.
+fn is_compressed(archive: &StrictPath) -> bool {
+    let name = archive.interpret().to_lowercase();
+    name.ends_with(".tar.gz") || name.ends_with(".tgz")
+}
+
+/// Whether an existing archive is gzip-compressed, based on its content,
+/// since the compression of a new archive can come from the config instead of its name.
+fn is_gzip(archive: &StrictPath) -> bool {
+    let mut magic = [0; 2];
+    std::fs::File::open(archive.interpret())
+        .and_then(|mut file| std::io::Read::read_exact(&mut file, &mut magic))
+        .is_ok()
+        && magic == [0x1f, 0x8b]
+}
+
+/// Whether a new backup target should be written as an archive, based on its file name.
+pub fn has_archive_name(target: &StrictPath) -> bool {
+    is_compressed(target) || target.interpret().to_lowercase().ends_with(".tar")
+}
+
+/// Whether a restoration source should be read as an archive instead of a folder.
+pub fn is_archive(source: &StrictPath) -> bool {
+    source.is_file()
+}
+
+/// A folder where the backup is laid out before packing it into an archive,
+/// or where an archive is unpacked before restoring from it.
+/// It's removed once this is dropped, however the operation ends.
+pub struct StagingDir(StrictPath);
+
+impl std::ops::Deref for StagingDir {
+    type Target = StrictPath;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Drop for StagingDir {
+    fn drop(&mut self) {
+        let _ = self.0.remove();
+    }
+}
+
+pub fn staging_dir(purpose: &str) -> StagingDir {
+    StagingDir(StrictPath::from_std_path_buf(&std::env::temp_dir()).joined(&format!(
+        "ludusavi-{}-{}",
+        purpose,
+        std::process::id()
+    )))
+}
+
+/// Pack the contents of `source` into a new archive, keeping the same layout.
+/// With a gzip level from the config, the archive is compressed at that level.
+/// Otherwise, it's only compressed if its name asks for it, at the default level of 6.
+pub fn pack(source: &StrictPath, archive: &StrictPath, level: Option<u32>) -> Result<(), Error> {
+    let fail = || Error::CannotPrepareBackupTarget { path: archive.clone() };
+
+    if let Some(parent) = std::path::Path::new(&archive.interpret()).parent() {
+        std::fs::create_dir_all(parent).map_err(|_| fail())?;
+    }
+    let file = std::fs::File::create(archive.interpret()).map_err(|_| fail())?;
+
+    let compression = match level {
+        Some(level) => Some(flate2::Compression::new(level)),
+        None => is_compressed(archive).then(flate2::Compression::default),
+    };
+    let result = if let Some(level) = compression {
+        let encoder = flate2::write::GzEncoder::new(file, level);
+        let mut builder = tar::Builder::new(encoder);
+        builder
+            .append_dir_all(".", source.interpret())
+            .and_then(|_| builder.into_inner())
+            .and_then(|encoder| encoder.finish())
+            .map(|_| ())
+    } else {
+        let mut builder = tar::Builder::new(file);
+        builder
+            .append_dir_all(".", source.interpret())
+            .and_then(|_| builder.finish())
+    };
+
+    if result.is_err() {
+        // Don't leave a truncated archive that looks like a valid backup.
+        let _ = archive.remove();
+        return Err(fail());
+    }
+    Ok(())
+}
+
+/// Unpack an archive made by `pack` into `target`, replacing anything already there.
+pub fn unpack(archive: &StrictPath, target: &StrictPath) -> Result<(), Error> {
+    let fail = || Error::RestorationSourceInvalid { path: archive.clone() };
+
+    let file = std::fs::File::open(archive.interpret()).map_err(|_| fail())?;
+    if target.exists() {
+        target.remove().map_err(|_| fail())?;
+    }
+    std::fs::create_dir_all(target.interpret()).map_err(|_| fail())?;
+
+    let result = if is_gzip(archive) {
+        tar::Archive::new(flate2::read::GzDecoder::new(file)).unpack(target.interpret())
+    } else {
+        tar::Archive::new(file).unpack(target.interpret())
+    };
+    result.map_err(|_| fail())
+}
+
+/// Lay out an archive made by `pack` in `target` without unpacking the saved files' contents,
+/// which is enough to preview a restore. The backup metadata is unpacked as usual,
+/// but each saved file (anything in a `drive-*` folder) is only created with the right size.
+pub fn unpack_listing(archive: &StrictPath, target: &StrictPath) -> Result<(), Error> {
+    fn lay_out<R: std::io::Read>(mut archive: tar::Archive<R>, target: &std::path::Path) -> std::io::Result<()> {
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?.to_path_buf();
+            let is_saved_file = entry.header().entry_type().is_file()
+                && path
+                    .components()
+                    .any(|x| x.as_os_str().to_string_lossy().starts_with("drive-"));
+            let is_safe = path
+                .components()
+                .all(|x| matches!(x, std::path::Component::Normal(_) | std::path::Component::CurDir));
+            if is_saved_file && is_safe {
+                let file = target.join(&path);
+                if let Some(parent) = file.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::File::create(&file)?.set_len(entry.header().size()?)?;
+            } else {
+                entry.unpack_in(target)?;
+            }
+        }
+        Ok(())
+    }
+
+    let fail = || Error::RestorationSourceInvalid { path: archive.clone() };
+
+    let file = std::fs::File::open(archive.interpret()).map_err(|_| fail())?;
+    if target.exists() {
+        target.remove().map_err(|_| fail())?;
+    }
+    std::fs::create_dir_all(target.interpret()).map_err(|_| fail())?;
+
+    let target = target.as_std_path_buf();
+    let result = if is_gzip(archive) {
+        lay_out(tar::Archive::new(flate2::read::GzDecoder::new(file)), &target)
+    } else {
+        lay_out(tar::Archive::new(file), &target)
+    };
+    result.map_err(|_| fail())
+}
+
+/// Pack individual files into a new archive, each under the given name.
+pub fn pack_files(files: &[(&StrictPath, &str)], archive: &StrictPath) -> std::io::Result<()> {
+    archive.create_parent_dir()?;
+    let file = std::fs::File::create(archive.interpret())?;
+
+    if is_compressed(archive) {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(file, flate2::Compression::default()));
+        for (path, name) in files {
+            builder.append_path_with_name(path.interpret(), name)?;
+        }
+        builder.into_inner()?.finish().map(|_| ())
+    } else {
+        let mut builder = tar::Builder::new(file);
+        for (path, name) in files {
+            builder.append_path_with_name(path.interpret(), name)?;
+        }
+        builder.finish()
+    }
+}
+
+/// Read the content of these files from an archive, without unpacking anything else.
+/// Any that aren't in the archive are left out.
+pub fn read_files(archive: &StrictPath, names: &[&str]) -> std::io::Result<std::collections::HashMap<String, Vec<u8>>> {
+    fn read<R: std::io::Read>(
+        mut archive: tar::Archive<R>,
+        names: &[&str],
+    ) -> std::io::Result<std::collections::HashMap<String, Vec<u8>>> {
+        let mut found = std::collections::HashMap::new();
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?.to_string_lossy().trim_start_matches("./").to_string();
+            if names.contains(&path.as_str()) {
+                let mut content = vec![];
+                std::io::Read::read_to_end(&mut entry, &mut content)?;
+                found.insert(path, content);
+            }
+        }
+        Ok(found)
+    }
+
+    let file = std::fs::File::open(archive.interpret())?;
+    if is_gzip(archive) {
+        read(tar::Archive::new(flate2::read::GzDecoder::new(file)), names)
+    } else {
+        read(tar::Archive::new(file), names)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::TempPath;
+    use crate::{config::Retention, layout::BackupLayout};
+    use pretty_assertions::assert_eq;
+
+    fn repo() -> String {
+        env!("CARGO_MANIFEST_DIR").to_string()
+    }
+
+    fn restorable_game1_files(base: &StrictPath) -> Vec<(Option<StrictPath>, u64)> {
+        let layout = BackupLayout::new(base.clone(), Retention::default());
+        let mut files: Vec<_> = layout
+            .game_layout("game1")
+            .restorable_files()
+            .into_iter()
+            .map(|x| (x.original_path, x.size))
+            .collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn can_round_trip_backup_through_archive() {
+        let source = StrictPath::new(format!("{}/tests/backup", repo()));
+        let original_games = BackupLayout::new(source.clone(), Retention::default()).restorable_games();
+
+        for name in ["ludusavi-test-archive.tar", "ludusavi-test-archive.tar.gz"] {
+            let archive = TempPath::new(name);
+            let unpacked = staging_dir(&format!("test-unpack-{}", name));
+
+            pack(&source, &archive, None).unwrap();
+            assert!(is_archive(&archive));
+            unpack(&archive, &unpacked).unwrap();
+
+            let mut games = BackupLayout::new(unpacked.clone(), Retention::default()).restorable_games();
+            games.sort();
+            let mut expected = original_games.clone();
+            expected.sort();
+            assert_eq!(expected, games);
+            assert_eq!(restorable_game1_files(&source), restorable_game1_files(&unpacked));
+        }
+    }
+
+    #[test]
+    fn can_list_archive_without_unpacking_saved_files() {
+        let source = StrictPath::new(format!("{}/tests/backup", repo()));
+        let archive = TempPath::new("ludusavi-test-archive-listing.tar");
+        let listed = staging_dir("test-list");
+
+        pack(&source, &archive, None).unwrap();
+        unpack_listing(&archive, &listed).unwrap();
+
+        let mut games = BackupLayout::new(listed.clone(), Retention::default()).restorable_games();
+        games.sort();
+        let mut expected = BackupLayout::new(source.clone(), Retention::default()).restorable_games();
+        expected.sort();
+        assert_eq!(expected, games);
+        assert_eq!(restorable_game1_files(&source), restorable_game1_files(&listed));
+
+        // The saved files only have the right size, without their contents.
+        let layout = BackupLayout::new(listed.clone(), Retention::default());
+        for file in layout.game_layout("game1").restorable_files() {
+            let content = std::fs::read(file.path.interpret()).unwrap();
+            assert!(content.iter().all(|x| *x == 0));
+        }
+    }
+
+    #[test]
+    fn staging_dir_is_removed_when_dropped() {
+        let path = {
+            let staging = staging_dir("test-drop");
+            std::fs::create_dir_all(staging.joined("nested").interpret()).unwrap();
+            assert!(staging.is_dir());
+            staging.clone()
+        };
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn honors_compression_level() {
+        let source = StrictPath::new(format!("{}/tests/backup", repo()));
+        let size = |level: u32| {
+            let archive = TempPath::new(&format!("ludusavi-test-archive-level-{}.tar.gz", level));
+            pack(&source, &archive, Some(level)).unwrap();
+            archive.metadata().unwrap().len()
+        };
+
+        assert!(size(0) > size(9));
+    }
+
+    #[test]
+    fn takes_compression_from_level_over_name() {
+        let source = StrictPath::new(format!("{}/tests/backup", repo()));
+        let archive = TempPath::new("ludusavi-test-archive-level-over-name.tar");
+        let unpacked = staging_dir("test-unpack-level-over-name");
+
+        pack(&source, &archive, Some(9)).unwrap();
+        let compressed = is_gzip(&archive);
+        let result = unpack(&archive, &unpacked);
+
+        assert!(compressed);
+        assert_eq!(Ok(()), result);
+        assert_eq!(restorable_game1_files(&source), restorable_game1_files(&unpacked));
+    }
+
+    #[test]
+    fn rejects_invalid_archive() {
+        let archive = TempPath::new("ludusavi-test-archive-invalid.tar");
+        std::fs::write(archive.interpret(), "not a tar file").unwrap();
+        let unpacked = staging_dir("test-unpack-invalid");
+
+        assert_eq!(
+            Err(Error::RestorationSourceInvalid { path: archive.clone() }),
+            unpack(&archive, &unpacked)
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use crate::{
+    layout::{BackupLayout, IndividualMapping},
+    prelude::ScanInfo,
+    signature::{verify_backup, VerifyingKey},
+};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, serde::Serialize)]
+pub enum Concern {
+    /// Some of the game's saves can't be read, so backing them up would fail.
+    #[serde(rename = "failed")]
+    Failed,
+    /// The backup is incomplete, or its signature doesn't match.
+    #[serde(rename = "corrupted")]
+    Corrupted,
+    /// There's a backup, but no saves for the game are found anymore.
+    #[serde(rename = "orphaned")]
+    Orphaned,
+    /// Saves are found, but there's no backup of them.
+    #[serde(rename = "neverBackedUp")]
+    NeverBackedUp,
+}
+
+impl Concern {
+    pub const ALL: &'static [Self] = &[Self::Failed, Self::Corrupted, Self::Orphaned, Self::NeverBackedUp];
+}
+
+/// Every game with something to act on, with all of the reasons that apply to it.
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize)]
+pub struct AttentionReport {
+    pub games: BTreeMap<String, BTreeSet<Concern>>,
+}
+
+impl AttentionReport {
+    fn add(&mut self, game: &str, concern: Concern) {
+        self.games.entry(game.to_string()).or_default().insert(concern);
+    }
+
+    pub fn count(&self, concern: Concern) -> usize {
+        self.games.values().filter(|x| x.contains(&concern)).count()
+    }
+
+    pub fn counts(&self) -> BTreeMap<Concern, usize> {
+        Concern::ALL.iter().map(|x| (*x, self.count(*x))).collect()
+    }
+}
+
+/// Compare a backup scan against what's in the backup target.
+/// Games in `ignored` are left out, since they aren't meant to be backed up.
+/// Signatures are only checked if there's a `key`.
+pub fn find_games_needing_attention(
+    scans: &[ScanInfo],
+    ignored: &HashSet<String>,
+    layout: &BackupLayout,
+    key: Option<&VerifyingKey>,
+) -> AttentionReport {
+    let mut report = AttentionReport::default();
+    let backed_up: BTreeSet<_> = layout.restorable_games().into_iter().collect();
+    let mut found = HashSet::new();
+
+    for scan_info in scans {
+        let name = &scan_info.game_name;
+        if ignored.contains(name) || !scan_info.found_anything_processable() {
+            continue;
+        }
+        found.insert(name.as_str());
+
+        if scan_info
+            .found_files
+            .iter()
+            .any(|x| !x.ignored && std::fs::File::open(x.path.interpret()).is_err())
+        {
+            report.add(name, Concern::Failed);
+        }
+        if !backed_up.contains(name) {
+            report.add(name, Concern::NeverBackedUp);
+        }
+    }
+
+    for name in &backed_up {
+        if ignored.contains(name) {
+            continue;
+        }
+        if !found.contains(name.as_str()) {
+            report.add(name, Concern::Orphaned);
+        }
+        if let (Some(key), Some(folder)) = (key, layout.existing_game_folder(name)) {
+            if verify_backup(&folder, key).is_err() {
+                report.add(name, Concern::Corrupted);
+            }
+        }
+    }
+
+    // Without a readable mapping, the folder name is all we have to go on.
+    for folder in layout.find_prunable_folders() {
+        if folder.bytes > 0 && IndividualMapping::load(&folder.path.joined("mapping.yaml")).is_err() {
+            if let Some(name) = folder.path.as_std_path_buf().file_name() {
+                report.add(&name.to_string_lossy(), Concern::Corrupted);
+            }
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        prelude::{ScannedFile, StrictPath},
+        signature::SigningKey,
+    };
+    use maplit::*;
+    use pretty_assertions::assert_eq;
+
+    fn layout() -> BackupLayout {
+        BackupLayout::new(
+            StrictPath::new(format!("{}/tests/backup", env!("CARGO_MANIFEST_DIR"))),
+            crate::config::Retention::default(),
+        )
+    }
+
+    fn scan(name: &str, files: &[&str]) -> ScanInfo {
+        ScanInfo {
+            game_name: name.to_string(),
+            found_files: files
+                .iter()
+                .map(|x| ScannedFile::new(format!("{}/{}", env!("CARGO_MANIFEST_DIR"), x), 1))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn shows_every_concern_that_applies_to_a_game() {
+        let scans = vec![
+            scan("game1", &["tests/root1/game1/subdir/file2.txt"]),
+            scan("unbacked", &["tests/root1/game1/subdir/file2.txt", "tests/missing.txt"]),
+        ];
+        let key = SigningKey::from_base64("AQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHyA=")
+            .unwrap()
+            .verifying_key();
+
+        let report = find_games_needing_attention(&scans, &HashSet::new(), &layout(), Some(&key));
+
+        assert_eq!(
+            btreeset! {Concern::Failed, Concern::NeverBackedUp},
+            report.games["unbacked"],
+        );
+        assert_eq!(btreeset! {Concern::Corrupted, Concern::Orphaned}, report.games["game3"]);
+        assert_eq!(btreeset! {Concern::Corrupted}, report.games["game1"]);
+        assert_eq!(btreeset! {Concern::Corrupted}, report.games["ignored-invalid-mapping"]);
+        assert_eq!(1, report.count(Concern::Failed));
+        assert_eq!(3, report.count(Concern::Corrupted));
+        assert_eq!(1, report.count(Concern::Orphaned));
+        assert_eq!(1, report.count(Concern::NeverBackedUp));
+    }
+
+    #[test]
+    fn leaves_out_ignored_games() {
+        let scans = vec![scan("unbacked", &["tests/missing.txt"])];
+        let ignored: HashSet<_> = layout()
+            .restorable_games()
+            .into_iter()
+            .chain(["unbacked".to_string()])
+            .collect();
+
+        let report = find_games_needing_attention(&scans, &ignored, &layout(), None);
+
+        assert_eq!(
+            btreemap! {"ignored-invalid-mapping".to_string() => btreeset! {Concern::Corrupted}},
+            report.games,
+        );
+    }
+}
@@ -1,17 +1,40 @@
 use crate::{
-    config::{Config, RedirectConfig, Sort, SortKey},
-    lang::Translator,
-    layout::BackupLayout,
-    manifest::{Manifest, SteamMetadata},
+    anonymize::Anonymizer,
+    attention::find_games_needing_attention,
+    config::{BadgeKind, Config, RedirectConfig, RestoreMode, RootsConfig, SignatureVerification, Sort, SortKey},
+    diff::diff_backups,
+    doctor::CheckStatus,
+    game_export::{export_game, import_game},
+    inactivity::{changed_recently, Deferral, Deferrals},
+    lang::{LineItemBadges, Translator},
+    layout::{BackupLayout, PathAnchors},
+    lock::TargetLock,
+    manifest::{Manifest, ManifestChanges, SteamMetadata},
+    migrate::migrate_backup,
+    ownership::OwnershipMismatch,
     prelude::{
-        app_dir, back_up_game, game_file_restoration_target, prepare_backup_target, restore_game, scan_game_for_backup,
-        scan_game_for_restoration, BackupInfo, DuplicateDetector, Error, InstallDirRanking, OperationStatus,
-        OperationStepDecision, ScanInfo, StrictPath,
+        app_dir, back_up_game_to_targets, diagnose_game_for_backup, find_extra_files, game_file_restoration_target,
+        is_held_back_by_unavailable_roots, manifest_refresh_forced, prepare_backup_target, restore_game,
+        scan_game_for_backup, scan_game_for_backup_with_anchors, scan_game_for_restoration, BackupInfo,
+        DuplicateDetector, Error, ErrorReport, FailedEntry, FailureGroup, FailureReason, InstallDirRanking,
+        OperationStatus, OperationStepDecision, RegistryValueCounts, RestoreAction, RestoreOptions, ScanHeartbeat,
+        ScanInfo, StrictPath, Warning,
     },
+    retry::Retry,
+    scan_cache::{scan_with_cache, ScanCache},
+    schedule::ScheduleState,
+    settings::SettingsFiles,
+    signature::{check_restore, sign_game_backups, SigningKey, VerifyingKey},
+    undo::UndoSnapshot,
+    verify_sample::{choose, random_seed, SampleResult},
 };
 use clap::{CommandFactory, Parser};
-use indicatif::ParallelProgressIterator;
+use indicatif::{ParallelProgressIterator, ProgressBar, ProgressStyle};
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use std::sync::Mutex;
+
+/// How often to update the progress message while scanning a single game.
+const SCAN_HEARTBEAT_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
 
 fn parse_strict_path(path: &str) -> StrictPath {
     StrictPath::new(path.to_owned())
@@ -23,6 +46,13 @@ fn parse_existing_strict_path(path: &str) -> Result<StrictPath, std::io::Error>
     Ok(sp)
 }
 
+fn parse_percent(text: &str) -> Result<f64, String> {
+    match text.parse::<f64>() {
+        Ok(x) if x > 0.0 && x <= 100.0 => Ok(x),
+        _ => Err("must be a number greater than 0 and at most 100".to_string()),
+    }
+}
+
 #[derive(clap::Subcommand, Clone, Debug, PartialEq)]
 pub enum CompletionShell {
     #[clap(about = "Completions for Bash")]
@@ -64,6 +94,91 @@ impl std::str::FromStr for CliSort {
     }
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CliFormat {
+    Json,
+    /// One JSON object per line, printed as each game is reported.
+    JsonLines,
+}
+
+impl CliFormat {
+    pub const ALL: &'static [&'static str] = &["json", "jsonl"];
+}
+
+/// What to write to `--output-file`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CliOutputFormat {
+    /// Only the overall summary.
+    #[default]
+    Summary,
+    /// Everything that's printed to stdout.
+    Full,
+}
+
+impl CliOutputFormat {
+    pub const ALL: &'static [&'static str] = &["summary", "full"];
+}
+
+impl std::str::FromStr for CliOutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "summary" => Ok(Self::Summary),
+            "full" => Ok(Self::Full),
+            _ => Err(format!("invalid output format: {}", s)),
+        }
+    }
+}
+
+/// Options for also writing the report to a file, for reviewing unattended runs later.
+#[derive(clap::Args, Clone, Debug, Default, PartialEq)]
+pub struct CliOutputFile {
+    /// Also write the report to this file, as UTF-8.
+    #[clap(long, parse(from_str = parse_strict_path))]
+    output_file: Option<StrictPath>,
+
+    /// What to write to --output-file: `summary` for only the overall summary,
+    /// or `full` for everything that's printed to stdout, in the same format.
+    /// When not specified, this is `summary`.
+    #[clap(long, possible_values = CliOutputFormat::ALL, requires("output-file"))]
+    output_format: Option<CliOutputFormat>,
+
+    /// Append to --output-file instead of overwriting it.
+    #[clap(long, requires("output-file"))]
+    output_append: bool,
+
+    /// Only write the report to --output-file, instead of also printing it to stdout.
+    #[clap(long, requires("output-file"))]
+    output_only: bool,
+}
+
+/// Write a report to `--output-file`.
+fn write_output_file(file: &StrictPath, content: &str, append: bool) -> std::io::Result<()> {
+    use std::io::Write;
+
+    file.create_parent_dir()?;
+    let mut handle = std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(file.interpret())?;
+    writeln!(handle, "{}", content)
+}
+
+impl std::str::FromStr for CliFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Self::Json),
+            "jsonl" => Ok(Self::JsonLines),
+            _ => Err(format!("invalid format: {}", s)),
+        }
+    }
+}
+
 impl From<CliSort> for Sort {
     fn from(source: CliSort) -> Self {
         match source {
@@ -87,6 +202,18 @@ impl From<CliSort> for Sort {
     }
 }
 
+#[derive(clap::Subcommand, Clone, Debug, PartialEq)]
+pub enum ConfigSubcommand {
+    #[clap(about = "Show the settings that differ from the defaults")]
+    Diff {
+        /// Print information to stdout in machine-readable JSON.
+        #[clap(long)]
+        api: bool,
+    },
+    #[clap(about = "Show the public half of the signing key, to check signatures on another machine")]
+    PublicKey,
+}
+
 #[derive(clap::Subcommand, Clone, Debug, PartialEq)]
 pub enum Subcommand {
     #[clap(about = "Back up data")]
@@ -95,13 +222,28 @@ pub enum Subcommand {
         #[clap(long)]
         preview: bool,
 
+        /// Only add up how many files would be backed up and how big they are,
+        /// without listing them or reading their contents. This implies --preview.
+        #[clap(long)]
+        estimate: bool,
+
         /// Directory in which to create the backup. The directory must not
         /// already exist (unless you use --force), but it will be created if necessary.
         /// When unset, this defaults to the value from Ludusavi's config file.
         #[clap(long, parse(from_str = parse_strict_path))]
         path: Option<StrictPath>,
 
-        /// Delete the target directory if it already exists.
+        /// Write the backup as a single archive file instead of a directory.
+        /// It has the same layout inside, and it is compressed if the name
+        /// ends with .tar.gz or .tgz. An archive is always a new full backup,
+        /// so this can't be combined with merging (use --no-merge if the config
+        /// asks to merge) or with differential backups in the config.
+        #[clap(long, parse(from_str = parse_strict_path), conflicts_with_all(&["path", "merge"]))]
+        archive: Option<StrictPath>,
+
+        /// Delete the target directory (or archive) if it already exists.
+        /// This also overrides a stale lock left behind by an earlier backup
+        /// that did not finish.
         #[clap(long)]
         force: bool,
 
@@ -143,11 +285,47 @@ pub enum Subcommand {
         #[clap(long)]
         api: bool,
 
+        /// Print machine-readable output in this format. `json` is the same as --api.
+        /// `jsonl` prints one line per game as it's reported, then a line with
+        /// `"type": "summary"` for the overall totals.
+        #[clap(long, possible_values = CliFormat::ALL, conflicts_with("api"))]
+        format: Option<CliFormat>,
+
+        #[clap(flatten)]
+        output: CliOutputFile,
+
+        /// Replace your home folder, user name, and Windows account IDs
+        /// with placeholders in the report, so that it's safe to share.
+        #[clap(long)]
+        anonymize: bool,
+
         /// Sort the game list by different criteria.
         /// When not specified, this defers to Ludusavi's config file.
         #[clap(long, possible_values = CliSort::ALL)]
         sort: Option<CliSort>,
 
+        /// Only report games that had failures.
+        /// The summary will then only count those games as well.
+        #[clap(long)]
+        failed_only: bool,
+
+        /// Wrap the human-readable output to this many columns.
+        /// When not specified, this uses the terminal width, if known.
+        /// Use 0 to disable wrapping.
+        #[clap(long, value_name = "COLS")]
+        wrap: Option<usize>,
+
+        /// During a preview, scan every game from scratch. Otherwise, games whose
+        /// folders haven't changed since the last scan with the same settings
+        /// reuse the earlier results.
+        #[clap(long)]
+        no_cache: bool,
+
+        /// Also list games for which nothing was found, to confirm that they were checked.
+        /// These aren't counted in the summary.
+        #[clap(long)]
+        include_empty_games: bool,
+
         /// Only back up these specific games.
         #[clap()]
         games: Vec<String>,
@@ -158,15 +336,76 @@ pub enum Subcommand {
         #[clap(long)]
         preview: bool,
 
-        /// Directory containing a Ludusavi backup. When unset, this
-        /// defaults to the value from Ludusavi's config file.
+        /// Go through the full restoration process and report which files
+        /// would be created, overwritten, or skipped as identical,
+        /// but don't actually write anything. This does not ask for confirmation.
+        #[clap(long, conflicts_with("preview"))]
+        dry_run: bool,
+
+        /// Give restored files the same modification and access times as
+        /// their backed up copies, instead of the time of restoration.
+        /// When not specified, this defers to Ludusavi's config file.
+        #[clap(long)]
+        preserve_timestamps: bool,
+
+        /// After writing the restored files, read them back and check that
+        /// they match the backup. Any mismatches are reported as failures.
+        #[clap(long, conflicts_with_all(&["preview", "dry-run"]))]
+        verify_after_restore: bool,
+
+        /// After writing the restored files, check this percentage of them, chosen at random,
+        /// against the backup by hash, and estimate how many of the rest might not match.
+        /// This is quicker than --verify-after-restore for large restores.
+        /// At least one file is always checked.
+        #[clap(
+            long,
+            value_name = "PERCENT",
+            parse(try_from_str = parse_percent),
+            conflicts_with_all(&["preview", "dry-run", "verify-after-restore"])
+        )]
+        verify_sample: Option<f64>,
+
+        /// Check the same sample as an earlier --verify-sample, using the seed that it reported.
+        #[clap(long, requires("verify-sample"))]
+        verify_sample_seed: Option<u64>,
+
+        /// What to do with files in the game's save folders that aren't in the backup.
+        /// `additive` leaves them alone, and `mirror` removes them, after asking for confirmation
+        /// with a list of what would be removed. Only the folders that the backup writes to
+        /// are checked, not their subfolders.
+        /// When not specified, this defers to Ludusavi's config file.
+        #[clap(long, possible_values = RestoreMode::ALL_NAMES)]
+        mode: Option<RestoreMode>,
+
+        /// Don't write files that already match the backup.
+        /// Files are compared by content, or by size and modification time
+        /// when they can't be read, in which case a warning is shown.
+        /// When not specified, this defers to Ludusavi's config file.
+        #[clap(long)]
+        skip_identical: bool,
+
+        /// Write every file from the backup, even if it already matches.
+        /// When not specified, this defers to Ludusavi's config file.
+        #[clap(long, conflicts_with("skip-identical"))]
+        no_skip_identical: bool,
+
+        /// Directory containing a Ludusavi backup, or an archive made with
+        /// `backup --archive`. When unset, this defaults to the value
+        /// from Ludusavi's config file.
         #[clap(long, parse(try_from_str = parse_existing_strict_path))]
         path: Option<StrictPath>,
 
         /// Don't ask for confirmation.
+        /// This also restores games that are running when `restore.checkRunningGames` is enabled.
         #[clap(long)]
         force: bool,
 
+        /// Ask before restoring each game, instead of once for the whole restore.
+        /// Answer `y` or `n` for each game, `a` to restore all of the remaining games,
+        /// or `q` to skip all of the remaining games. This has no effect with --force.
+        #[clap(long, conflicts_with_all(&["preview", "dry-run"]))]
+        interactive: bool,
+
         /// When naming specific games to process, this means that you'll
         /// provide the Steam IDs instead of the manifest names, and Ludusavi will
         /// look up those IDs in the manifest to find the corresponding names.
@@ -178,15 +417,230 @@ pub enum Subcommand {
         #[clap(long)]
         api: bool,
 
+        /// Print machine-readable output in this format. `json` is the same as --api.
+        /// `jsonl` prints one line per game as it's reported, then a line with
+        /// `"type": "summary"` for the overall totals.
+        #[clap(long, possible_values = CliFormat::ALL, conflicts_with("api"))]
+        format: Option<CliFormat>,
+
+        #[clap(flatten)]
+        output: CliOutputFile,
+
+        /// Replace your home folder, user name, and Windows account IDs
+        /// with placeholders in the report, so that it's safe to share.
+        #[clap(long)]
+        anonymize: bool,
+
         /// Sort the game list by different criteria.
         /// When not specified, this defers to Ludusavi's config file.
         #[clap(long, possible_values = CliSort::ALL)]
         sort: Option<CliSort>,
 
+        /// Only report games that had failures.
+        /// The summary will then only count those games as well.
+        #[clap(long)]
+        failed_only: bool,
+
+        /// Wrap the human-readable output to this many columns.
+        /// When not specified, this uses the terminal width, if known.
+        /// Use 0 to disable wrapping.
+        #[clap(long, value_name = "COLS")]
+        wrap: Option<usize>,
+
         /// Only restore these specific games.
         #[clap()]
         games: Vec<String>,
     },
+    #[clap(about = "Revert the files changed by the last restore")]
+    UndoRestore {
+        /// Don't ask for confirmation.
+        #[clap(long)]
+        force: bool,
+    },
+    #[clap(about = "Check for common configuration problems")]
+    Doctor,
+    #[clap(about = "Show how a single game's saves are detected")]
+    FindGame {
+        /// This means that you'll provide the Steam ID instead of the manifest name,
+        /// and Ludusavi will look up that ID in the manifest to find the corresponding name.
+        #[clap(long)]
+        by_steam_id: bool,
+
+        /// Extra Wine/Proton prefix to check for saves. This should be a folder
+        /// with an immediate child folder named "drive_c" (or another letter).
+        #[clap(long, parse(from_str = parse_strict_path))]
+        wine_prefix: Option<StrictPath>,
+
+        /// The game to look up.
+        #[clap()]
+        name: String,
+    },
+    #[clap(about = "Remove empty or incomplete game folders from the backup target")]
+    Clean {
+        /// List the folders that would be removed, but don't remove anything.
+        /// This does not ask for confirmation.
+        #[clap(long)]
+        dry_run: bool,
+
+        /// Directory to clean. When unset, this defaults to the backup path
+        /// from Ludusavi's config file.
+        #[clap(long, parse(try_from_str = parse_existing_strict_path))]
+        path: Option<StrictPath>,
+
+        /// Don't ask for confirmation.
+        #[clap(long)]
+        force: bool,
+    },
+    #[clap(about = "List the games that need attention, like ones that were never backed up")]
+    Attention {
+        /// Print information to stdout in machine-readable JSON.
+        #[clap(long)]
+        api: bool,
+
+        /// Backup directory to check against. When unset, this defaults to
+        /// the backup path from Ludusavi's config file.
+        #[clap(long, parse(try_from_str = parse_existing_strict_path))]
+        path: Option<StrictPath>,
+    },
+    #[clap(about = "Inspect Ludusavi's config file")]
+    Config {
+        #[clap(subcommand)]
+        sub: ConfigSubcommand,
+    },
+    #[clap(about = "Show which games were added, changed, or removed by the last manifest update")]
+    ManifestChanges {
+        /// Print information to stdout in machine-readable JSON.
+        #[clap(long)]
+        api: bool,
+    },
+    #[clap(about = "Look for roots that aren't configured yet, like game store libraries")]
+    FindRoots {
+        /// Print information to stdout in machine-readable JSON.
+        #[clap(long)]
+        api: bool,
+    },
+    #[clap(about = "Show what produced each of a game's backups, like the manifest version")]
+    Provenance {
+        /// Print information to stdout in machine-readable JSON.
+        #[clap(long)]
+        api: bool,
+
+        /// Directory containing the backups. When unset, this defaults to
+        /// the restore path from Ludusavi's config file.
+        #[clap(long, parse(try_from_str = parse_existing_strict_path))]
+        path: Option<StrictPath>,
+
+        /// The game to look up.
+        #[clap()]
+        name: String,
+    },
+    #[clap(about = "List the save files in a Microsoft Store game's Xbox containers")]
+    XboxSaves {
+        /// Print information to stdout in machine-readable JSON.
+        #[clap(long)]
+        api: bool,
+
+        /// The game to look up.
+        #[clap()]
+        name: String,
+    },
+    #[clap(about = "Show when backups last ran and when the next one is recommended")]
+    Status {
+        /// Print information to stdout in machine-readable JSON.
+        #[clap(long)]
+        api: bool,
+    },
+    #[clap(about = "Bundle Ludusavi's config and cached manifest into one archive, for moving to another machine")]
+    ExportSettings {
+        /// Archive to create, ending in `.tar` or `.tar.gz`.
+        #[clap(parse(from_str = parse_strict_path))]
+        path: StrictPath,
+    },
+    #[clap(about = "Apply Ludusavi's config and cached manifest from an archive made by export-settings")]
+    ImportSettings {
+        /// Archive to import.
+        #[clap(parse(try_from_str = parse_existing_strict_path))]
+        path: StrictPath,
+
+        /// Replace the current config instead of adding the imported roots,
+        /// custom games, and redirects to it.
+        /// The current backup and restore folders are kept either way.
+        #[clap(long)]
+        replace: bool,
+    },
+    #[clap(about = "Check a manifest file for mistakes, like unknown placeholders")]
+    ValidateManifest {
+        /// The manifest file to check.
+        #[clap(parse(try_from_str = parse_existing_strict_path))]
+        path: StrictPath,
+    },
+    #[clap(about = "Compare two backups")]
+    Diff {
+        /// Print information to stdout in machine-readable JSON.
+        #[clap(long)]
+        api: bool,
+
+        /// The older backup folder (or archive) to compare from.
+        #[clap(parse(try_from_str = parse_existing_strict_path))]
+        before: StrictPath,
+
+        /// The newer backup folder (or archive) to compare against.
+        #[clap(parse(try_from_str = parse_existing_strict_path))]
+        after: StrictPath,
+    },
+    #[clap(about = "Copy a backup to a new target, arranged according to the current config")]
+    MigrateBackup {
+        /// The backup folder (or archive) to migrate.
+        #[clap(long, parse(try_from_str = parse_existing_strict_path))]
+        from: StrictPath,
+
+        /// Where to write the migrated backup. This is written as an archive
+        /// if the name ends with .tar, .tar.gz, or .tgz, and it is compressed
+        /// for the latter two. The target must not already exist (unless you use --force).
+        #[clap(long, parse(from_str = parse_strict_path))]
+        to: StrictPath,
+
+        /// Only keep the latest state of each game, as a single full backup,
+        /// instead of its whole history of full and differential backups.
+        #[clap(long)]
+        flatten: bool,
+
+        /// Delete the target if it already exists.
+        #[clap(long)]
+        force: bool,
+    },
+    #[clap(about = "Write one game's latest backup to an archive, for sharing it or importing it elsewhere")]
+    ExportGame {
+        /// Directory containing the backups. When unset, this defaults to
+        /// the restore path from Ludusavi's config file.
+        #[clap(long, parse(try_from_str = parse_existing_strict_path))]
+        path: Option<StrictPath>,
+
+        /// Archive to create, ending in `.tar` or `.tar.gz`.
+        #[clap(long, parse(from_str = parse_strict_path))]
+        to: StrictPath,
+
+        /// The game to export.
+        #[clap()]
+        name: String,
+    },
+    #[clap(about = "Add a game's backup from an archive made by export-game")]
+    ImportGame {
+        /// Directory to add the game's backup to. When unset, this defaults to
+        /// the restore path from Ludusavi's config file.
+        #[clap(long, parse(from_str = parse_strict_path))]
+        path: Option<StrictPath>,
+
+        /// Replace the game's existing backups, if it already has any.
+        #[clap(long)]
+        force: bool,
+
+        /// Archive to import.
+        #[clap(parse(try_from_str = parse_existing_strict_path))]
+        archive: StrictPath,
+    },
+    #[clap(about = "Choose games to back up or restore in an interactive terminal interface")]
+    Tui,
     #[clap(about = "Generate shell completion scripts")]
     Complete {
         #[clap(subcommand)]
@@ -202,6 +656,34 @@ pub enum Subcommand {
     set_term_width = 79
 )]
 pub struct Cli {
+    /// Use this directory for the config file instead of the standard location.
+    #[clap(long, global = true, parse(from_str = parse_strict_path))]
+    pub config_dir: Option<StrictPath>,
+
+    /// Use this directory for the manifest and other data instead of the standard location.
+    #[clap(long, global = true, parse(from_str = parse_strict_path))]
+    pub data_dir: Option<StrictPath>,
+
+    /// Store everything next to the executable, as if there were a
+    /// `ludusavi.portable` file in the same folder.
+    #[clap(long, global = true)]
+    pub portable: bool,
+
+    /// Use this local manifest file instead of downloading one.
+    /// This takes precedence over `manifest.path` in the config file.
+    #[clap(long, global = true, parse(from_str = parse_strict_path))]
+    pub manifest: Option<StrictPath>,
+
+    /// Check for a manifest update now, even if the cached manifest
+    /// is newer than `manifest.refreshInterval` in the config file.
+    #[clap(long, global = true)]
+    pub refresh_manifest: bool,
+
+    /// Apply the overrides from this profile in the config file,
+    /// such as a different backup target, retention, or filter.
+    #[clap(long, global = true)]
+    pub profile: Option<String>,
+
     #[clap(subcommand)]
     pub sub: Option<Subcommand>,
 }
@@ -210,12 +692,107 @@ pub fn parse_cli() -> Cli {
     Cli::from_args()
 }
 
+/// Width of the terminal, or 0 if unknown.
+/// Most shells don't export `$COLUMNS`, so this asks the terminal first.
+/// When the output isn't going to a terminal, like when it's piped to a file, it isn't wrapped.
+fn detect_terminal_width() -> usize {
+    use std::io::IsTerminal;
+
+    if !std::io::stdout().is_terminal() {
+        return 0;
+    }
+    crossterm::terminal::size()
+        .ok()
+        .map(|(columns, _)| columns as usize)
+        .filter(|x| *x > 0)
+        .or_else(|| std::env::var("COLUMNS").ok().and_then(|x| x.trim().parse().ok()))
+        .unwrap_or(0)
+}
+
+/// Registry value counts are only worth showing when some of the game's entries are filtered.
+fn filtered_registry_values(scan_info: &ScanInfo, backup_info: &BackupInfo) -> Option<RegistryValueCounts> {
+    let filtered = scan_info.found_registry_keys.iter().any(|x| !x.filter.is_empty());
+    (filtered && backup_info.registry_values != RegistryValueCounts::default()).then_some(backup_info.registry_values)
+}
+
+/// Wrap a line of human-readable output to fit within `width` columns.
+/// Breaks happen after spaces and path separators, so that a wrapped path
+/// can be pieced back together by removing the continuation indent.
+/// The list marker and any badges stay attached to the item they describe.
+fn wrap_line(line: &str, width: usize) -> String {
+    if width == 0 || line.chars().count() <= width {
+        return line.to_string();
+    }
+
+    let indent = &line[..line.len() - line.trim_start().len()];
+    let continuation = format!("{}    ", indent);
+
+    // Nothing may be broken before the end of the badges.
+    let mut protected = indent.len();
+    if line[protected..].starts_with("- ") {
+        protected += 2;
+    }
+    while line[protected..].starts_with('[') {
+        match line[protected..].find("] ") {
+            Some(end) => protected += end + 2,
+            None => break,
+        }
+    }
+
+    let mut pieces = vec![];
+    let mut start = 0;
+    for (i, c) in line.char_indices() {
+        let end = i + c.len_utf8();
+        if end > protected && end < line.len() && matches!(c, ' ' | '/' | '\\') {
+            pieces.push(&line[start..end]);
+            start = end;
+        }
+    }
+    pieces.push(&line[start..]);
+
+    let mut lines = vec![];
+    let mut current = String::new();
+    for piece in pieces {
+        if current.chars().count() + piece.chars().count() > width && !current.trim().is_empty() {
+            lines.push(current.trim_end().to_string());
+            current = continuation.clone();
+        }
+        current += piece;
+    }
+    lines.push(current.trim_end().to_string());
+    lines.join("\n")
+}
+
 #[derive(Debug, Default, serde::Serialize)]
 struct ApiErrors {
     #[serde(rename = "someGamesFailed", skip_serializing_if = "Option::is_none")]
     some_games_failed: Option<bool>,
     #[serde(rename = "unknownGames", skip_serializing_if = "Option::is_none")]
     unknown_games: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    failures: Vec<ApiFailure>,
+}
+
+/// Entries that failed for the same reason, from `ErrorReport`.
+#[derive(Debug, serde::Serialize)]
+struct ApiFailure {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    count: usize,
+    games: std::collections::BTreeSet<String>,
+    /// Every entry, unless there were more than `ErrorReport::MAX_LISTED`, in which case just the first ones.
+    entries: Vec<FailedEntry>,
+}
+
+impl From<&FailureGroup> for ApiFailure {
+    fn from(group: &FailureGroup) -> Self {
+        Self {
+            reason: group.reason.as_ref().map(|x| x.message.clone()),
+            count: group.count,
+            games: group.games.clone(),
+            entries: group.sample.clone(),
+        }
+    }
 }
 
 #[derive(Debug, Default, serde::Serialize)]
@@ -223,10 +800,24 @@ struct ApiFile {
     #[serde(skip_serializing_if = "crate::serialization::is_false")]
     failed: bool,
     #[serde(skip_serializing_if = "crate::serialization::is_false")]
+    denied: bool,
+    #[serde(skip_serializing_if = "crate::serialization::is_false")]
     ignored: bool,
+    #[serde(skip_serializing_if = "crate::serialization::is_false")]
+    mismatched: bool,
+    #[serde(skip_serializing_if = "crate::serialization::is_false")]
+    partial: bool,
+    #[serde(skip_serializing_if = "crate::serialization::is_false")]
+    system: bool,
+    #[serde(rename = "ownershipMismatch", skip_serializing_if = "Option::is_none")]
+    ownership_mismatch: Option<OwnershipMismatch>,
     bytes: u64,
     #[serde(rename = "originalPath", skip_serializing_if = "Option::is_none")]
     original_path: Option<String>,
+    #[serde(rename = "xboxName", skip_serializing_if = "Option::is_none")]
+    xbox_name: Option<String>,
+    #[serde(rename = "restoreAction", skip_serializing_if = "Option::is_none")]
+    restore_action: Option<RestoreAction>,
     #[serde(
         rename = "duplicatedBy",
         serialize_with = "crate::serialization::ordered_set",
@@ -250,19 +841,119 @@ struct ApiRegistry {
 #[derive(Debug, Default, serde::Serialize)]
 struct ApiGame {
     decision: OperationStepDecision,
+    #[serde(rename = "cloudOnly", skip_serializing_if = "crate::serialization::is_false")]
+    cloud_only: bool,
     #[serde(serialize_with = "crate::serialization::ordered_map")]
     files: std::collections::HashMap<String, ApiFile>,
     #[serde(serialize_with = "crate::serialization::ordered_map")]
     registry: std::collections::HashMap<String, ApiRegistry>,
+    #[serde(rename = "registryValues", skip_serializing_if = "Option::is_none")]
+    registry_values: Option<RegistryValueCounts>,
+    #[serde(rename = "removedFiles", skip_serializing_if = "Vec::is_empty")]
+    removed_files: Vec<String>,
+    #[serde(rename = "failedRemovals", skip_serializing_if = "Vec::is_empty")]
+    failed_removals: Vec<String>,
+}
+
+#[derive(Debug, Default, serde::Serialize)]
+struct ApiEstimate {
+    files: usize,
+}
+
+#[derive(Debug, Default, serde::Serialize)]
+struct ApiVerifySample {
+    /// A string, since it doesn't fit in a JSON number without losing precision.
+    seed: String,
+    total: usize,
+    checked: usize,
+    mismatched: usize,
+    #[serde(rename = "extrapolatedPercent")]
+    extrapolated_percent: f64,
+    /// Where each checked file was restored to, by game.
+    games: std::collections::BTreeMap<String, Vec<String>>,
+}
+
+impl From<&SampleResult> for ApiVerifySample {
+    fn from(sample: &SampleResult) -> Self {
+        Self {
+            seed: sample.seed.to_string(),
+            total: sample.total,
+            checked: sample.checked,
+            mismatched: sample.mismatched,
+            extrapolated_percent: sample.extrapolated_percent(),
+            games: sample
+                .files
+                .iter()
+                .map(|(game, files)| (game.clone(), files.iter().map(|x| x.render()).collect()))
+                .collect(),
+        }
+    }
+}
+
+/// A line of `--format jsonl` output.
+#[derive(Debug, serde::Serialize)]
+#[serde(tag = "type")]
+enum JsonLine<'a> {
+    #[serde(rename = "game")]
+    Game {
+        name: &'a str,
+        #[serde(flatten)]
+        game: &'a ApiGame,
+    },
+    #[serde(rename = "summary")]
+    Summary {
+        #[serde(rename = "dryRun", skip_serializing_if = "crate::serialization::is_false")]
+        dry_run: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        estimate: Option<&'a ApiEstimate>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        errors: Option<&'a ApiErrors>,
+        overall: &'a OperationStatus,
+        #[serde(rename = "verifySample", skip_serializing_if = "Option::is_none")]
+        verify_sample: Option<&'a ApiVerifySample>,
+    },
 }
 
 #[derive(Debug, Default, serde::Serialize)]
 struct JsonOutput {
+    #[serde(rename = "dryRun", skip_serializing_if = "crate::serialization::is_false")]
+    dry_run: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    estimate: Option<ApiEstimate>,
+    #[serde(skip)]
+    failed_only: bool,
+    #[serde(skip)]
+    include_empty: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     errors: Option<ApiErrors>,
     overall: OperationStatus,
     #[serde(serialize_with = "crate::serialization::ordered_map")]
     games: std::collections::HashMap<String, ApiGame>,
+    #[serde(rename = "verifySample", skip_serializing_if = "Option::is_none")]
+    verify_sample: Option<ApiVerifySample>,
+    /// When streaming, games are rendered here instead of going into `games`,
+    /// until they're printed.
+    #[serde(skip)]
+    lines: Option<Vec<String>>,
+    /// When streaming, games that have already been printed.
+    #[serde(skip)]
+    streamed: Vec<String>,
+    #[serde(skip)]
+    anonymizer: Option<Anonymizer>,
+    /// Serialized through `errors`.
+    #[serde(skip)]
+    error_report: ErrorReport,
+}
+
+impl JsonOutput {
+    fn add_game(&mut self, name: &str, game: ApiGame) {
+        match &mut self.lines {
+            Some(lines) => lines.push(serde_json::to_string(&JsonLine::Game { name, game: &game }).unwrap()),
+            None => {
+                self.games.insert(name.to_string(), game);
+            }
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -271,6 +962,17 @@ enum Reporter {
         translator: Translator,
         parts: Vec<String>,
         status: OperationStatus,
+        dry_run: bool,
+        /// How many files were counted, if only estimating.
+        estimate: Option<usize>,
+        failed_only: bool,
+        include_empty: bool,
+        /// Maximum line width, or 0 to not wrap.
+        wrap: usize,
+        badge_order: Vec<BadgeKind>,
+        anonymizer: Option<Anonymizer>,
+        error_report: ErrorReport,
+        verify_sample: Option<SampleResult>,
     },
     Json {
         output: JsonOutput,
@@ -283,6 +985,15 @@ impl Reporter {
             translator,
             parts: vec![],
             status: Default::default(),
+            dry_run: false,
+            estimate: None,
+            failed_only: false,
+            include_empty: false,
+            wrap: 0,
+            badge_order: vec![],
+            anonymizer: None,
+            error_report: Default::default(),
+            verify_sample: None,
         }
     }
 
@@ -292,50 +1003,194 @@ impl Reporter {
         }
     }
 
-    fn trip_some_games_failed(&mut self) {
-        if let Reporter::Json { output, .. } = self {
-            if let Some(errors) = &mut output.errors {
-                errors.some_games_failed = Some(true);
-            } else {
-                output.errors = Some(ApiErrors {
-                    some_games_failed: Some(true),
-                    ..Default::default()
-                });
-            }
+    fn json_lines() -> Self {
+        Self::Json {
+            output: JsonOutput {
+                lines: Some(vec![]),
+                ..Default::default()
+            },
         }
     }
 
-    fn trip_unknown_games(&mut self, games: Vec<String>) {
-        if let Reporter::Json { output, .. } = self {
-            if let Some(errors) = &mut output.errors {
-                errors.unknown_games = Some(games);
-            } else {
-                output.errors = Some(ApiErrors {
-                    unknown_games: Some(games),
-                    ..Default::default()
-                });
-            }
+    fn with_format(format: Option<CliFormat>, translator: Translator) -> Self {
+        match format {
+            Some(CliFormat::Json) => Self::json(),
+            Some(CliFormat::JsonLines) => Self::json_lines(),
+            None => Self::standard(translator),
         }
     }
 
-    fn add_game(
-        &mut self,
-        name: &str,
-        scan_info: &ScanInfo,
-        backup_info: &BackupInfo,
-        decision: &OperationStepDecision,
-        redirects: &[RedirectConfig],
-        duplicate_detector: &DuplicateDetector,
-    ) -> bool {
-        let mut successful = true;
+    fn trip_dry_run(&mut self) {
+        match self {
+            Self::Standard { dry_run, .. } => *dry_run = true,
+            Self::Json { output } => output.dry_run = true,
+        }
+    }
 
+    fn trip_estimate(&mut self) {
         match self {
-            Self::Standard {
-                parts,
-                status,
-                translator,
-            } => {
-                if !scan_info.found_anything() {
+            Self::Standard { estimate, .. } => *estimate = Some(0),
+            Self::Json { output } => output.estimate = Some(ApiEstimate::default()),
+        }
+    }
+
+    fn trip_failed_only(&mut self) {
+        match self {
+            Self::Standard { failed_only, .. } => *failed_only = true,
+            Self::Json { output } => output.failed_only = true,
+        }
+    }
+
+    fn add_verify_sample(&mut self, sample: SampleResult) {
+        match self {
+            Self::Standard { verify_sample, .. } => *verify_sample = Some(sample),
+            Self::Json { output } => output.verify_sample = Some(ApiVerifySample::from(&sample)),
+        }
+    }
+
+    fn add_unusable_target(&mut self, target: &StrictPath) {
+        match self {
+            Self::Standard { status, .. } => status.add_unusable_target(target),
+            Self::Json { output } => output.overall.add_unusable_target(target),
+        }
+    }
+
+    fn trip_include_empty(&mut self) {
+        match self {
+            Self::Standard { include_empty, .. } => *include_empty = true,
+            Self::Json { output } => output.include_empty = true,
+        }
+    }
+
+    fn set_wrap(&mut self, width: usize) {
+        if let Self::Standard { wrap, .. } = self {
+            *wrap = width;
+        }
+    }
+
+    fn set_badge_order(&mut self, order: &[BadgeKind]) {
+        if let Self::Standard { badge_order, .. } = self {
+            *badge_order = order.to_vec();
+        }
+    }
+
+    fn set_anonymizer(&mut self, value: Anonymizer) {
+        match self {
+            Self::Standard { anonymizer, .. } => *anonymizer = Some(value),
+            Self::Json { output } => output.anonymizer = Some(value),
+        }
+    }
+
+    /// Hide personal details in anything that's about to be printed or written.
+    fn anonymize(&self, text: String) -> String {
+        let anonymizer = match self {
+            Self::Standard { anonymizer, .. } => anonymizer,
+            Self::Json { output } => &output.anonymizer,
+        };
+        match anonymizer {
+            Some(anonymizer) => anonymizer.apply(&text),
+            None => text,
+        }
+    }
+
+    fn set_selection(&mut self, available: usize) {
+        match self {
+            Self::Standard { status, .. } => status.available_games = Some(available),
+            Self::Json { output } => output.overall.available_games = Some(available),
+        }
+    }
+
+    fn trip_some_games_failed(&mut self) {
+        if let Reporter::Json { output, .. } = self {
+            if let Some(errors) = &mut output.errors {
+                errors.some_games_failed = Some(true);
+            } else {
+                output.errors = Some(ApiErrors {
+                    some_games_failed: Some(true),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    fn trip_unknown_games(&mut self, games: Vec<String>) {
+        if let Reporter::Json { output, .. } = self {
+            if let Some(errors) = &mut output.errors {
+                errors.unknown_games = Some(games);
+            } else {
+                output.errors = Some(ApiErrors {
+                    unknown_games: Some(games),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    fn report_failures(&mut self, game: &str, failures: Vec<(String, Option<FailureReason>)>) {
+        let error_report = match self {
+            Self::Standard { error_report, .. } => error_report,
+            Self::Json { output } => &mut output.error_report,
+        };
+        for (path, reason) in failures {
+            error_report.add(game, path, reason.as_ref());
+        }
+
+        if let Self::Json { output } = self {
+            let failures = output.error_report.groups.iter().map(ApiFailure::from).collect();
+            output.errors.get_or_insert_with(Default::default).failures = failures;
+        }
+    }
+
+    fn add_game(
+        &mut self,
+        name: &str,
+        scan_info: &ScanInfo,
+        backup_info: &BackupInfo,
+        decision: &OperationStepDecision,
+        redirects: &[RedirectConfig],
+        duplicate_detector: &DuplicateDetector,
+    ) -> bool {
+        let mut successful = true;
+        let mut failures = vec![];
+
+        match self {
+            Self::Standard {
+                status,
+                estimate: Some(files),
+                ..
+            } => {
+                if decision == &OperationStepDecision::Processed {
+                    *files += scan_info.found_files.iter().filter(|x| !x.ignored).count();
+                }
+                status.add_game(scan_info, &None, decision == &OperationStepDecision::Processed);
+            }
+            Self::Standard {
+                parts,
+                status,
+                translator,
+                dry_run,
+                failed_only,
+                include_empty,
+                badge_order,
+                ..
+            } => {
+                if scan_info.cloud_only {
+                    if !*failed_only {
+                        parts.push(translator.cli_game_cloud_only(name));
+                        // Blank line between games.
+                        parts.push("".to_string());
+                    }
+                    return true;
+                }
+                if !scan_info.found_anything() {
+                    if *include_empty && !*failed_only {
+                        parts.push(translator.cli_game_empty(name));
+                        // Blank line between games.
+                        parts.push("".to_string());
+                    }
+                    return true;
+                }
+                if *failed_only && backup_info.successful() {
                     return true;
                 }
 
@@ -344,6 +1199,7 @@ impl Reporter {
                     scan_info.sum_bytes(&Some(backup_info.to_owned())),
                     decision,
                     duplicate_detector.is_game_duplicated(scan_info),
+                    badge_order,
                 ));
                 for entry in itertools::sorted(&scan_info.found_files) {
                     let mut redirected_from = None;
@@ -356,15 +1212,43 @@ impl Reporter {
                     };
 
                     let entry_successful = !backup_info.failed_files.contains(entry);
-                    if !entry_successful {
+                    if !entry_successful && !backup_info.failure_tolerated(entry) {
                         successful = false;
+                        failures.push((readable.render(), backup_info.failure_reasons.get(entry).cloned()));
+                    }
+                    let item = match &entry.xbox_name {
+                        Some(xbox_name) => translator.xbox_save_file(&readable, xbox_name),
+                        None => readable.render(),
+                    };
+                    let line = translator.cli_game_line_item(
+                        &item,
+                        &LineItemBadges {
+                            failed: !entry_successful,
+                            denied: backup_info.denied_files.contains(entry),
+                            ignored: entry.ignored,
+                            duplicated: duplicate_detector.is_file_duplicated(entry),
+                            mismatched: backup_info.mismatched_files.contains(entry),
+                            partial: entry.partial,
+                            system: entry.system,
+                            ownership: backup_info.ownership_mismatches.contains_key(entry),
+                            restore_action: backup_info
+                                .restore_actions
+                                .get(entry)
+                                .copied()
+                                .filter(|x| *dry_run || *x == RestoreAction::Skip),
+                        },
+                        badge_order,
+                    );
+                    match (
+                        backup_info.failure_reasons.get(entry),
+                        backup_info.ownership_mismatches.get(entry),
+                    ) {
+                        (Some(reason), _) if !entry_successful => {
+                            parts.push(translator.cli_game_line_item_failure(&line, reason))
+                        }
+                        (_, Some(mismatch)) => parts.push(translator.cli_game_line_item_ownership(&line, mismatch)),
+                        _ => parts.push(line),
                     }
-                    parts.push(translator.cli_game_line_item(
-                        &readable.render(),
-                        entry_successful,
-                        entry.ignored,
-                        duplicate_detector.is_file_duplicated(entry),
-                    ));
 
                     if let Some(redirected_from) = redirected_from {
                         parts.push(translator.cli_game_line_item_redirected(&redirected_from.render()));
@@ -374,14 +1258,42 @@ impl Reporter {
                     let entry_successful = !backup_info.failed_registry.contains(&entry.path);
                     if !entry_successful {
                         successful = false;
+                        failures.push((entry.path.render(), None));
                     }
                     parts.push(translator.cli_game_line_item(
                         &entry.path.render(),
-                        entry_successful,
-                        entry.ignored,
-                        duplicate_detector.is_registry_duplicated(&entry.path),
+                        &LineItemBadges {
+                            failed: !entry_successful,
+                            ignored: entry.ignored,
+                            duplicated: duplicate_detector.is_registry_duplicated(&entry.path),
+                            ..Default::default()
+                        },
+                        badge_order,
                     ));
                 }
+                for file in itertools::sorted(&backup_info.removed_files) {
+                    parts.push(translator.cli_game_line_item_removed(&file.render()));
+                }
+                for file in itertools::sorted(backup_info.failed_removals(scan_info)) {
+                    successful = false;
+                    let reason = backup_info.failure_reasons.get(file);
+                    failures.push((file.path.render(), reason.cloned()));
+                    let line = translator.cli_game_line_item(
+                        &file.path.render(),
+                        &LineItemBadges {
+                            failed: true,
+                            ..Default::default()
+                        },
+                        badge_order,
+                    );
+                    parts.push(match reason {
+                        Some(reason) => translator.cli_game_line_item_failure(&line, reason),
+                        None => line,
+                    });
+                }
+                if let Some(counts) = filtered_registry_values(scan_info, backup_info) {
+                    parts.push(translator.cli_game_registry_values(&counts));
+                }
 
                 // Blank line between games.
                 parts.push("".to_string());
@@ -391,9 +1303,48 @@ impl Reporter {
                     &Some(backup_info.clone()),
                     decision == &OperationStepDecision::Processed,
                 );
+                status.add_skip(decision);
+            }
+            Self::Json {
+                output:
+                    JsonOutput {
+                        estimate: Some(estimate),
+                        overall,
+                        ..
+                    },
+            } => {
+                if decision == &OperationStepDecision::Processed {
+                    estimate.files += scan_info.found_files.iter().filter(|x| !x.ignored).count();
+                }
+                overall.add_game(scan_info, &None, decision == &OperationStepDecision::Processed);
             }
             Self::Json { output } => {
+                if scan_info.cloud_only {
+                    if !output.failed_only {
+                        output.add_game(
+                            name,
+                            ApiGame {
+                                decision: decision.clone(),
+                                cloud_only: true,
+                                ..Default::default()
+                            },
+                        );
+                    }
+                    return true;
+                }
                 if !scan_info.found_anything() {
+                    if output.include_empty && !output.failed_only {
+                        output.add_game(
+                            name,
+                            ApiGame {
+                                decision: decision.clone(),
+                                ..Default::default()
+                            },
+                        );
+                    }
+                    return true;
+                }
+                if output.failed_only && backup_info.successful() {
                     return true;
                 }
 
@@ -406,9 +1357,20 @@ impl Reporter {
                     let mut api_file = ApiFile {
                         bytes: entry.size,
                         failed: backup_info.failed_files.contains(entry),
+                        denied: backup_info.denied_files.contains(entry),
                         ignored: entry.ignored,
+                        mismatched: backup_info.mismatched_files.contains(entry),
+                        partial: entry.partial,
+                        system: entry.system,
+                        ownership_mismatch: backup_info.ownership_mismatches.get(entry).cloned(),
+                        xbox_name: entry.xbox_name.clone(),
                         ..Default::default()
                     };
+                    api_file.restore_action = backup_info
+                        .restore_actions
+                        .get(entry)
+                        .copied()
+                        .filter(|x| output.dry_run || *x == RestoreAction::Skip);
                     if duplicate_detector.is_file_duplicated(entry) {
                         let mut duplicated_by = duplicate_detector.file(entry);
                         duplicated_by.remove(&scan_info.game_name);
@@ -422,8 +1384,9 @@ impl Reporter {
                     } else {
                         entry.path.to_owned()
                     };
-                    if api_file.failed {
+                    if api_file.failed && !backup_info.failure_tolerated(entry) {
                         successful = false;
+                        failures.push((readable.render(), backup_info.failure_reasons.get(entry).cloned()));
                     }
 
                     api_game.files.insert(readable.render(), api_file);
@@ -441,22 +1404,34 @@ impl Reporter {
                     }
                     if api_registry.failed {
                         successful = false;
+                        failures.push((entry.path.render(), None));
                     }
 
                     api_game.registry.insert(entry.path.render(), api_registry);
                 }
+                api_game.registry_values = filtered_registry_values(scan_info, backup_info);
+                api_game.removed_files = itertools::sorted(&backup_info.removed_files)
+                    .map(|x| x.render())
+                    .collect();
+                for file in itertools::sorted(backup_info.failed_removals(scan_info)) {
+                    successful = false;
+                    failures.push((file.path.render(), backup_info.failure_reasons.get(file).cloned()));
+                    api_game.failed_removals.push(file.path.render());
+                }
 
-                output.games.insert(name.to_string(), api_game);
+                output.add_game(name, api_game);
                 output.overall.add_game(
                     scan_info,
                     &Some(backup_info.clone()),
                     decision == &OperationStepDecision::Processed,
                 );
+                output.overall.add_skip(decision);
             }
         }
 
         if !successful {
             self.trip_some_games_failed();
+            self.report_failures(name, failures);
         }
         successful
     }
@@ -467,34 +1442,438 @@ impl Reporter {
                 parts,
                 status,
                 translator,
-            } => parts.join("\n") + "\n" + &translator.cli_summary(status, path),
-            Self::Json { output } => serde_json::to_string_pretty(&output).unwrap(),
+                dry_run,
+                estimate,
+                wrap,
+                error_report,
+                verify_sample,
+                ..
+            } => {
+                let mut rendered = parts.join("\n") + "\n" + &self.render_summary(path);
+                if let Some(skipped) = translator.skip_summary(&status.skipped) {
+                    rendered += &format!("\n  {}", skipped);
+                }
+                if status.skipped_identical_files > 0 {
+                    rendered += &format!(
+                        "\n  {}",
+                        translator.cli_skipped_identical_summary(status.skipped_identical_files)
+                    );
+                }
+                if status.copied_bytes > 0 {
+                    rendered += &format!("\n  {}", translator.cli_copied_summary(status.copied_bytes));
+                }
+                if status.targets.len() > 1 {
+                    for (target, target_status) in &status.targets {
+                        rendered += &format!("\n  {}", translator.cli_target_summary(target, target_status));
+                    }
+                }
+                if status.total_games > 0 && status.processed_games == 0 && status.deferred_games == 0 {
+                    rendered += &format!("\n\n{}", translator.cli_all_games_ignored());
+                }
+                if !error_report.is_empty() {
+                    rendered += &format!("\n\n{}", translator.cli_error_report(error_report));
+                }
+                if !status.warnings.is_empty() {
+                    rendered += &format!("\n\n{}", translator.cli_warnings(status));
+                }
+                if let Some(files) = estimate {
+                    rendered += &format!("\n  {}", translator.cli_estimate_files_summary(*files));
+                    rendered += &format!("\n\n{}", translator.cli_estimate_summary());
+                }
+                if *dry_run {
+                    rendered += &format!("\n\n{}", translator.cli_dry_run_summary());
+                }
+                if let Some(sample) = verify_sample {
+                    rendered += &format!("\n\n{}", translator.cli_verify_sample(sample));
+                }
+                if *wrap > 0 {
+                    rendered = rendered
+                        .lines()
+                        .map(|line| wrap_line(line, *wrap))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                }
+                rendered
+            }
+            Self::Json { output } => match &output.lines {
+                Some(lines) => {
+                    let summary = JsonLine::Summary {
+                        dry_run: output.dry_run,
+                        estimate: output.estimate.as_ref(),
+                        errors: output.errors.as_ref(),
+                        overall: &output.overall,
+                        verify_sample: output.verify_sample.as_ref(),
+                    };
+                    lines
+                        .iter()
+                        .cloned()
+                        .chain(std::iter::once(serde_json::to_string(&summary).unwrap()))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                }
+                None => serde_json::to_string_pretty(&output).unwrap(),
+            },
+        }
+    }
+
+    /// Just the overall summary, for `--output-format summary`.
+    fn render_summary(&self, path: &StrictPath) -> String {
+        match self {
+            Self::Standard {
+                parts,
+                translator,
+                status,
+                failed_only,
+                ..
+            } => {
+                // With `--failed`, successful games aren't counted, so an empty status doesn't mean much.
+                // Games like cloud-only ones are listed without being counted, so they still count as found.
+                if status.total_games == 0 && parts.is_empty() && !*failed_only {
+                    translator.cli_no_games_found()
+                } else {
+                    translator.cli_summary(status, path)
+                }
+            }
+            Self::Json { output } => match &output.lines {
+                Some(_) => serde_json::to_string(&output.overall).unwrap(),
+                None => serde_json::to_string_pretty(&output.overall).unwrap(),
+            },
+        }
+    }
+
+    /// Everything, including any games that were already streamed, for `--output-format full`.
+    fn render_full(&self, path: &StrictPath) -> String {
+        match self {
+            Self::Json { output } if !output.streamed.is_empty() => {
+                format!("{}\n{}", output.streamed.join("\n"), self.render(path))
+            }
+            _ => self.render(path),
+        }
+    }
+
+    /// Print any games that are waiting to be streamed.
+    fn flush(&mut self, options: &CliOutputFile) {
+        if let Self::Json {
+            output:
+                JsonOutput {
+                    lines: Some(lines),
+                    streamed,
+                    anonymizer,
+                    ..
+                },
+        } = self
+        {
+            for line in lines.drain(..) {
+                if !options.output_only {
+                    match anonymizer {
+                        Some(anonymizer) => println!("{}", anonymizer.apply(&line)),
+                        None => println!("{}", line),
+                    }
+                }
+                streamed.push(line);
+            }
         }
     }
 
-    fn print_failure(&self) {
+    fn print_failure(&self, options: &CliOutputFile) {
         // The standard reporter doesn't need to print on failure because
         // that's handled generically in main.
         if let Self::Json { .. } = self {
-            self.print(&StrictPath::new("".to_string()));
+            self.print(&StrictPath::new("".to_string()), options);
+        }
+    }
+
+    fn print(&self, path: &StrictPath, options: &CliOutputFile) {
+        if !options.output_only {
+            println!("{}", self.anonymize(self.render(path)));
+        }
+
+        if let Some(file) = &options.output_file {
+            let content = self.anonymize(match options.output_format.unwrap_or_default() {
+                CliOutputFormat::Summary => self.render_summary(path),
+                CliOutputFormat::Full => self.render_full(path),
+            });
+            if write_output_file(file, &content, options.output_append).is_err() {
+                // Don't lose the result just because the file couldn't be written.
+                eprintln!("{}", Translator::default().cli_unable_to_write_output_file(file));
+                if options.output_only {
+                    eprintln!("{}", self.anonymize(self.render_full(path)));
+                }
+            }
+        }
+    }
+}
+
+/// An answer to one of the prompts of `restore --interactive`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Confirmation {
+    Yes,
+    No,
+    All,
+    Quit,
+}
+
+impl Confirmation {
+    fn parse(text: &str) -> Option<Self> {
+        match text.trim().to_lowercase().as_str() {
+            "y" | "yes" => Some(Self::Yes),
+            "n" | "no" => Some(Self::No),
+            "a" | "all" => Some(Self::All),
+            "q" | "quit" => Some(Self::Quit),
+            _ => None,
+        }
+    }
+}
+
+/// Whether to restore a game, or else why not.
+/// Naming games explicitly overrides `ignoredGames`, but frozen games also need `--force`.
+fn restore_decision(
+    config: &Config,
+    name: &str,
+    games_specified: bool,
+    force: bool,
+    declined: bool,
+) -> OperationStepDecision {
+    if declined || (!config.is_game_enabled_for_restore(name) && !games_specified) {
+        OperationStepDecision::Ignored
+    } else if config.is_game_frozen_for_restore(name) && !(games_specified && force) {
+        OperationStepDecision::Frozen
+    } else {
+        OperationStepDecision::Processed
+    }
+}
+
+/// An archive is always written as a new full backup,
+/// so it can't be combined with merging or with differential backups.
+fn check_archive_settings(config: &Config, merge: bool, translator: &Translator) -> Result<(), Error> {
+    if merge {
+        return Err(Error::ConfigInvalid {
+            why: translator.cli_archive_with_merge(),
+        });
+    }
+    if config.backup.retention.differential > 0 {
+        return Err(Error::ConfigInvalid {
+            why: translator.cli_archive_with_differential(),
+        });
+    }
+    Ok(())
+}
+
+/// Check a sample of the files that were just restored against the backup,
+/// marking any that don't match as failed.
+fn verify_restored_sample(
+    info: &mut [(&String, ScanInfo, BackupInfo, OperationStepDecision)],
+    config: &Config,
+    percent: f64,
+    seed: u64,
+) -> SampleResult {
+    let mut restored = vec![];
+    for (i, (name, scan_info, backup_info, _)) in info.iter().enumerate() {
+        let redirects = config.get_redirects_for_game(name);
+        for file in &scan_info.found_files {
+            let written = matches!(
+                backup_info.restore_actions.get(file),
+                Some(RestoreAction::Create | RestoreAction::Overwrite)
+            );
+            if !written || backup_info.failed_files.contains(file) {
+                continue;
+            }
+            if let Some(original_path) = &file.original_path {
+                let (target, _) = game_file_restoration_target(original_path, &redirects);
+                restored.push((i, file.clone(), target));
+            }
+        }
+    }
+
+    let total = restored.len();
+    let sample = choose(restored, percent, seed, |(_, _, target)| target.render());
+    let checked = sample.len();
+    let matched: Vec<_> = sample
+        .par_iter()
+        .map(|(_, file, target)| crate::verify_sample::matches(&file.path, target))
+        .collect();
+
+    let mut mismatched = 0;
+    let mut files = std::collections::BTreeMap::<String, Vec<StrictPath>>::new();
+    for ((i, file, target), matched) in sample.into_iter().zip(matched) {
+        files.entry(info[i].0.clone()).or_default().push(target);
+        if !matched {
+            mismatched += 1;
+            info[i].2.mismatched_files.insert(file.clone());
+            info[i].2.failed_files.insert(file);
+        }
+    }
+
+    for targets in files.values_mut() {
+        targets.sort_by_key(|x| x.render());
+    }
+
+    SampleResult {
+        total,
+        checked,
+        mismatched,
+        seed,
+        files,
+    }
+}
+
+/// Ask about each game in turn and return the ones to restore.
+/// Answering "all" accepts the remaining games without asking again,
+/// and "quit" declines them. Unrecognized answers are asked again.
+fn confirm_each_game(
+    games: &[String],
+    prompt: impl Fn(&str) -> String,
+    input: &mut impl std::io::BufRead,
+    output: &mut impl std::io::Write,
+) -> Result<std::collections::HashSet<String>, Error> {
+    let mut confirmed = std::collections::HashSet::new();
+    for (i, game) in games.iter().enumerate() {
+        let answer = loop {
+            let _ = write!(output, "{} [y/n/a/q] ", prompt(game));
+            let _ = output.flush();
+            let mut line = String::new();
+            match input.read_line(&mut line) {
+                Ok(0) | Err(_) => return Err(Error::CliUnableToRequestConfirmation),
+                Ok(_) => {}
+            }
+            if let Some(answer) = Confirmation::parse(&line) {
+                break answer;
+            }
+        };
+        match answer {
+            Confirmation::Yes => {
+                confirmed.insert(game.clone());
+            }
+            Confirmation::No => {}
+            Confirmation::All => {
+                confirmed.extend(games[i..].iter().cloned());
+                break;
+            }
+            Confirmation::Quit => break,
+        }
+    }
+    Ok(confirmed)
+}
+
+/// Find what a backup would include for each game, only from the files' metadata.
+/// Unlike a preview, this doesn't use the scan cache or look at any existing backups,
+/// and it never reads file contents.
+fn estimate_backup(
+    config: &Config,
+    all_games: &Manifest,
+    subjects: &[String],
+    roots: &[RootsConfig],
+    wine_prefix: &Option<StrictPath>,
+    games_specified: bool,
+) -> Vec<(String, ScanInfo, OperationStepDecision)> {
+    let ranking = InstallDirRanking::scan(roots, all_games, subjects);
+    subjects
+        .par_iter()
+        .progress_count(subjects.len() as u64)
+        .map(|name| {
+            let game = &all_games.0[name];
+            let scan_info = scan_game_for_backup(
+                game,
+                name,
+                roots,
+                &StrictPath::from_std_path_buf(&app_dir()),
+                &game.steam.as_ref().and_then(|x| x.id),
+                &config.backup.filter,
+                wine_prefix,
+                &ranking,
+                &config.backup.toggled_paths,
+                &config.backup.toggled_registry,
+            );
+            let decision = if !config.is_game_enabled_for_backup(name) && !games_specified {
+                OperationStepDecision::Ignored
+            } else {
+                OperationStepDecision::Processed
+            };
+            (name.clone(), scan_info, decision)
+        })
+        .collect()
+}
+
+/// The files that currently match each game's save paths, from the manifest or a custom game.
+/// These are what a backup would pick up, so mirror mode only removes files among them.
+fn current_saves(
+    config: &Config,
+    manifest: &Manifest,
+    names: &[String],
+) -> std::collections::HashMap<String, Vec<StrictPath>> {
+    let mut all_games = manifest.clone();
+    for custom_game in &config.custom_games {
+        if custom_game.ignore {
+            continue;
         }
+        all_games.add_custom_game(custom_game.clone());
     }
+    let names: Vec<_> = names.iter().filter(|x| all_games.0.contains_key(*x)).cloned().collect();
+    let ranking = InstallDirRanking::scan(&config.roots, &all_games, &names);
+
+    names
+        .par_iter()
+        .map(|name| {
+            let game = &all_games.0[name];
+            let scan_info = scan_game_for_backup(
+                game,
+                name,
+                &config.roots,
+                &StrictPath::from_std_path_buf(&app_dir()),
+                &game.steam.as_ref().and_then(|x| x.id),
+                &config.backup.filter,
+                &None,
+                &ranking,
+                &config.backup.toggled_paths,
+                &config.backup.toggled_registry,
+            );
+            let saves = scan_info.found_files.into_iter().map(|x| x.path).collect();
+            (name.clone(), saves)
+        })
+        .collect()
+}
 
-    fn print(&self, path: &StrictPath) {
-        println!("{}", self.render(path));
+/// Load the manifest, mentioning when a recent enough cache is used without checking for updates.
+fn load_manifest(config: &mut Config, translator: &Translator, quiet: bool) -> Result<Manifest, Error> {
+    if !quiet && !manifest_refresh_forced() {
+        if let Some(age) = Manifest::fresh_cache_age(config, std::time::SystemTime::now()) {
+            eprintln!("{}", translator.manifest_cached(age));
+        }
+    }
+    let (manifest, conflicts) = Manifest::load_with_conflicts(config, false)?;
+    if !quiet {
+        for conflict in &conflicts {
+            eprintln!("{}", translator.manifest_conflict(conflict));
+        }
     }
+    Ok(manifest)
 }
 
-pub fn run_cli(sub: Subcommand) -> Result<(), Error> {
+pub fn run_cli(sub: Subcommand, profile: Option<String>) -> Result<(), Error> {
     let translator = Translator::default();
-    let mut config = Config::load()?;
+
+    // This needs to run before loading the config so that it can report an invalid config.
+    if sub == Subcommand::Doctor {
+        return run_doctor(&translator);
+    }
+
+    // Manifest updates are saved to the base config, so the profile's
+    // overrides never end up in the config file.
+    let mut base_config = Config::load()?;
+    let config = match &profile {
+        Some(name) => base_config.with_profile(name)?,
+        None => base_config.clone(),
+    };
     let mut failed = false;
     let mut duplicate_detector = DuplicateDetector::default();
+    let retry = Retry::new(&config.runtime);
 
     match sub {
         Subcommand::Backup {
             preview,
+            estimate,
             path,
+            archive,
             force,
             merge,
             no_merge,
@@ -503,50 +1882,151 @@ pub fn run_cli(sub: Subcommand) -> Result<(), Error> {
             by_steam_id,
             wine_prefix,
             api,
+            format,
+            output,
+            anonymize,
             sort,
+            failed_only,
+            wrap,
+            no_cache,
+            include_empty_games,
             games,
         } => {
-            let mut reporter = if api {
-                Reporter::json()
-            } else {
-                Reporter::standard(translator)
+            let format = if api { Some(CliFormat::Json) } else { format };
+            let api = format.is_some();
+            let mut reporter = Reporter::with_format(format, translator);
+            if anonymize {
+                reporter.set_anonymizer(Anonymizer::current());
+            }
+            if failed_only {
+                reporter.trip_failed_only();
+            }
+            if include_empty_games {
+                reporter.trip_include_empty();
+            }
+            reporter.set_wrap(wrap.unwrap_or_else(detect_terminal_width));
+            reporter.set_badge_order(&config.badge_order);
+            if estimate {
+                reporter.trip_estimate();
+            }
+            let preview = preview || estimate;
+
+            if update || try_update {
+                match Manifest::update(&mut base_config) {
+                    Ok(Some(stats)) if !api => eprintln!("{}", translator.manifest_updated(&stats)),
+                    Ok(_) => {}
+                    Err(e) if try_update => eprintln!("{}", translator.handle_error(&e)),
+                    Err(e) => return Err(e),
+                }
+            }
+            let manifest = match load_manifest(&mut base_config, &translator, api || update || try_update) {
+                Ok(x) => x,
+                Err(_) if try_update => Manifest::default(),
+                Err(e) => return Err(e),
             };
 
-            let manifest = if try_update {
-                match Manifest::load(&mut config, true) {
-                    Ok(x) => x,
-                    Err(e) => {
-                        eprintln!("{}", translator.handle_error(&e));
-                        match Manifest::load(&mut config, false) {
-                            Ok(y) => y,
-                            Err(_) => Manifest::default(),
-                        }
-                    }
-                }
+            // Extra targets only apply when backing up to the configured path.
+            let use_extra_targets = path.is_none() && archive.is_none();
+            let backup_dir = match (path, &archive) {
+                (_, Some(archive)) => archive.clone(),
+                (None, None) => config.backup.path.clone(),
+                (Some(p), None) => p,
+            };
+            // Archives are laid out in a fresh folder and packed at the end.
+            let staging = archive.as_ref().map(|_| crate::archive::staging_dir("archive"));
+            let layout_dir = match &staging {
+                Some(staging) => StrictPath::clone(staging),
+                None => backup_dir.clone(),
+            };
+            // A root that isn't really there (like an unmounted drive) would just look empty,
+            // so it's left out instead of treated as if its saves were gone.
+            let unavailable_roots = config.unavailable_roots();
+            for root in &unavailable_roots {
+                eprintln!("{}", translator.root_unavailable(&root.path));
+            }
+            let available_roots: Vec<_> = config
+                .roots
+                .iter()
+                .filter(|x| !unavailable_roots.contains(x))
+                .cloned()
+                .collect();
+            let roots = &available_roots;
+
+            let merge_target = if merge {
+                true
+            } else if no_merge {
+                false
             } else {
-                Manifest::load(&mut config, update)?
+                config.backup.merge
             };
+            if archive.is_some() {
+                check_archive_settings(&config, merge_target, &translator)?;
+            }
 
-            let backup_dir = match path {
-                None => config.backup.path.clone(),
-                Some(p) => p,
+            let _lock = if preview {
+                None
+            } else if !force && !merge && backup_dir.exists() {
+                return Err(crate::prelude::Error::CliBackupTargetExists { path: backup_dir });
+            } else if archive.is_some() {
+                prepare_backup_target(&layout_dir, false)?;
+                None
+            } else {
+                let lock = TargetLock::acquire(&backup_dir, force)?;
+                prepare_backup_target(&backup_dir, merge_target)?;
+                Some(lock)
             };
-            let roots = &config.roots;
 
-            if !preview {
-                if !force && !merge && backup_dir.exists() {
-                    return Err(crate::prelude::Error::CliBackupTargetExists { path: backup_dir });
-                } else if let Err(e) = prepare_backup_target(
-                    &backup_dir,
-                    if merge {
-                        true
-                    } else if no_merge {
-                        false
+            // The manifest may have just been updated in the base config.
+            let provenance = Config {
+                manifest: base_config.manifest.clone(),
+                ..config.clone()
+            };
+            // An estimate doesn't look at the target at all.
+            let mut layouts = if estimate {
+                vec![]
+            } else {
+                vec![BackupLayout::new(layout_dir.clone(), config.backup.retention.clone())
+                    .grouped(config.backup.grouping, roots)
+                    .with_provenance(&provenance)
+                    .with_portable_paths(&config)
+                    .with_alternate_streams(config.backup.alternate_streams)
+                    .with_comparison(config.backup.comparison)]
+            };
+            let mut _extra_locks = vec![];
+            if use_extra_targets && !preview {
+                for (target, retention) in config.backup.targets().into_iter().skip(1) {
+                    let prepared = if !force && !merge && target.exists() {
+                        Err(crate::prelude::Error::CliBackupTargetExists { path: target.clone() })
                     } else {
-                        config.backup.merge
-                    },
-                ) {
-                    return Err(e);
+                        TargetLock::acquire(&target, force).and_then(|lock| {
+                            prepare_backup_target(&target, merge_target)?;
+                            Ok(lock)
+                        })
+                    };
+                    // A target that can't be used is reported, but the others still get the backup.
+                    match prepared {
+                        Ok(lock) => {
+                            _extra_locks.push(lock);
+                            layouts.push(
+                                BackupLayout::new(target, retention)
+                                    .grouped(config.backup.grouping, roots)
+                                    .with_provenance(&provenance)
+                                    .with_portable_paths(&config)
+                                    .with_alternate_streams(config.backup.alternate_streams)
+                                    .with_comparison(config.backup.comparison),
+                            );
+                        }
+                        Err(e) => {
+                            eprintln!("{}", translator.handle_error(&e));
+                            reporter.add_unusable_target(&target);
+                            failed = true;
+                        }
+                    }
+                }
+            }
+            for layout in &layouts {
+                if layout.grouping() != config.backup.grouping {
+                    eprintln!("{}", translator.backup_grouping_kept(&layout.base));
                 }
             }
 
@@ -556,10 +2036,18 @@ pub fn run_cli(sub: Subcommand) -> Result<(), Error> {
                 if custom_game.ignore {
                     continue;
                 }
+                if let Err(e) = custom_game.check_registry_files() {
+                    if !api {
+                        eprintln!("{}", translator.handle_error(&e));
+                    }
+                }
                 all_games.add_custom_game(custom_game.clone());
             }
 
             let games_specified = !games.is_empty();
+            if games_specified {
+                reporter.set_selection(all_games.0.len());
+            }
             let mut invalid_games: Vec<_> = games
                 .iter()
                 .filter_map(|game| {
@@ -584,7 +2072,7 @@ pub fn run_cli(sub: Subcommand) -> Result<(), Error> {
             if !invalid_games.is_empty() {
                 invalid_games.sort();
                 reporter.trip_unknown_games(invalid_games.clone());
-                reporter.print_failure();
+                reporter.print_failure(&output);
                 return Err(crate::prelude::Error::CliUnrecognizedGames { games: invalid_games });
             }
 
@@ -603,91 +2091,209 @@ pub fn run_cli(sub: Subcommand) -> Result<(), Error> {
             };
             subjects.sort();
 
-            let layout = BackupLayout::new(backup_dir.clone(), config.backup.retention.clone());
+            if estimate {
+                let info = estimate_backup(&config, &all_games, &subjects, roots, &wine_prefix, games_specified);
+                for (name, scan_info, decision) in info {
+                    reporter.add_game(
+                        &name,
+                        &scan_info,
+                        &BackupInfo::default(),
+                        &decision,
+                        &[],
+                        &duplicate_detector,
+                    );
+                    reporter.flush(&output);
+                }
+                reporter.print(&backup_dir, &output);
+                return Ok(());
+            }
+
             let filter = config.backup.filter.clone();
             let ranking = InstallDirRanking::scan(roots, &all_games, &subjects);
             let toggled_paths = config.backup.toggled_paths.clone();
             let toggled_registry = config.backup.toggled_registry.clone();
+            let signing_key = if preview {
+                None
+            } else {
+                SigningKey::load(&config.signature)?
+            };
+            let scan_cache = Mutex::new(ScanCache::load(&config, &wine_prefix));
+            let deferrals =
+                (!preview && !config.backup.inactivity.is_disabled()).then(|| Mutex::new(Deferrals::load()));
+
+            // The spinner and message keep moving while a big game is being scanned,
+            // since the bar itself only advances between games.
+            let progress = ProgressBar::new(subjects.len() as u64)
+                .with_style(ProgressStyle::default_bar().template("{spinner} {wide_bar} {pos}/{len} {msg}"));
+            progress.enable_steady_tick(100);
 
             let mut info: Vec<_> = subjects
                 .par_iter()
-                .progress_count(subjects.len() as u64)
+                .progress_with(progress.clone())
                 .map(|name| {
                     let game = &all_games.0[name];
                     let steam_id = &game.steam.clone().unwrap_or(SteamMetadata { id: None }).id;
 
-                    let scan_info = scan_game_for_backup(
-                        game,
-                        name,
-                        roots,
-                        &StrictPath::from_std_path_buf(&app_dir()),
-                        steam_id,
-                        &filter,
-                        &wine_prefix,
-                        &ranking,
-                        &toggled_paths,
-                        &toggled_registry,
-                    );
+                    let scan_info = scan_with_cache(&scan_cache, name, preview && !no_cache, || {
+                        scan_game_for_backup_with_anchors(
+                            game,
+                            name,
+                            roots,
+                            &StrictPath::from_std_path_buf(&app_dir()),
+                            steam_id,
+                            &filter,
+                            &wine_prefix,
+                            &ranking,
+                            &toggled_paths,
+                            &toggled_registry,
+                            &mut ScanHeartbeat::new(SCAN_HEARTBEAT_INTERVAL, |found| {
+                                progress.set_message(translator.cli_scan_heartbeat(name, found));
+                            }),
+                        )
+                    });
                     let ignored = !&config.is_game_enabled_for_backup(name) && !games_specified;
+                    let held_back =
+                        !ignored && is_held_back_by_unavailable_roots(&layouts[0], name, &unavailable_roots);
+                    let deferral = match &deferrals {
+                        Some(deferrals) if !ignored && !held_back => {
+                            let changed = changed_recently(
+                                &scan_info,
+                                config.backup.inactivity.threshold(),
+                                std::time::SystemTime::now(),
+                            );
+                            deferrals
+                                .lock()
+                                .unwrap()
+                                .decide(name, changed, config.backup.inactivity.max_defers)
+                        }
+                        _ => Deferral::BackUp,
+                    };
                     let decision = if ignored {
                         OperationStepDecision::Ignored
+                    } else if held_back {
+                        OperationStepDecision::RootUnavailable
+                    } else if deferral == Deferral::Defer {
+                        OperationStepDecision::Deferred
                     } else {
                         OperationStepDecision::Processed
                     };
-                    let backup_info = if preview || ignored {
+                    let mut backup_info = if preview || decision != OperationStepDecision::Processed {
                         crate::prelude::BackupInfo::default()
                     } else {
-                        back_up_game(&scan_info, name, &layout, config.backup.merge, &chrono::Utc::now())
+                        let mut backup_info = back_up_game_to_targets(
+                            &scan_info,
+                            name,
+                            &layouts,
+                            config.backup.merge && archive.is_none(),
+                            &chrono::Utc::now(),
+                            config.runtime.copy_threads,
+                            &retry,
+                            config.backup.permission_denied,
+                        );
+                        if let Some(key) = &signing_key {
+                            for e in sign_game_backups(&layouts, name, key, &mut backup_info) {
+                                eprintln!("{}", translator.handle_error(&e));
+                            }
+                        }
+                        backup_info
                     };
+                    if deferral == Deferral::Force {
+                        backup_info
+                            .warnings
+                            .push(Warning::DeferralLimitReached { game: name.to_string() });
+                    }
                     (name, scan_info, backup_info, decision)
                 })
                 .collect();
+            progress.finish_with_message("");
+            if let Some(deferrals) = deferrals {
+                deferrals.into_inner().unwrap().save();
+            }
 
             for (_, scan_info, _, _) in info.iter() {
                 duplicate_detector.add_game(scan_info);
             }
+            let scan_cache = scan_cache.into_inner().unwrap();
+            scan_cache.save();
 
             let sort = sort.map(From::from).unwrap_or_else(|| config.backup.sort.clone());
-            match sort.key {
-                SortKey::Name => info.sort_by_key(|(name, _, _, _)| name.to_string()),
-                SortKey::Size => info.sort_by_key(|(name, scan_info, backup_info, _)| {
-                    (scan_info.sum_bytes(&Some(backup_info.clone())), name.to_string())
-                }),
-            }
-            if sort.reversed {
-                info.reverse();
-            }
+            sort.apply(
+                &mut info,
+                |(name, _, _, _)| name.to_string(),
+                |(_, scan_info, backup_info, _)| scan_info.sum_bytes(&Some(backup_info.clone())),
+            );
 
             for (name, scan_info, backup_info, decision) in info {
                 if !reporter.add_game(name, &scan_info, &backup_info, &decision, &[], &duplicate_detector) {
                     failed = true;
                 }
+                reporter.flush(&output);
+            }
+            let packed = if archive.is_some() && !preview {
+                crate::archive::pack(&layout_dir, &backup_dir, config.backup.compression_level)
+            } else {
+                Ok(())
+            };
+            if !preview {
+                ScheduleState::record(!failed && packed.is_ok());
+            }
+            packed?;
+            reporter.print(&backup_dir, &output);
+            if scan_cache.hits() > 0 && !api {
+                eprintln!("{}", translator.cli_scan_cache_used(scan_cache.hits()));
             }
-            reporter.print(&backup_dir);
         }
         Subcommand::Restore {
             preview,
+            dry_run,
+            preserve_timestamps,
+            verify_after_restore,
+            verify_sample,
+            verify_sample_seed,
+            mode,
+            skip_identical,
+            no_skip_identical,
             path,
             force,
+            interactive,
             by_steam_id,
             api,
+            format,
+            output,
+            anonymize,
             sort,
+            failed_only,
+            wrap,
             games,
         } => {
-            let mut reporter = if api {
-                Reporter::json()
-            } else {
-                Reporter::standard(translator)
-            };
+            let format = if api { Some(CliFormat::Json) } else { format };
+            let interactive = interactive && !force;
+            let mut reporter = Reporter::with_format(format, translator);
+            if anonymize {
+                reporter.set_anonymizer(Anonymizer::current());
+            }
+            if failed_only {
+                reporter.trip_failed_only();
+            }
+            reporter.set_wrap(wrap.unwrap_or_else(detect_terminal_width));
+            reporter.set_badge_order(&config.badge_order);
+            if dry_run {
+                reporter.trip_dry_run();
+            }
 
-            let manifest = Manifest::load(&mut config, false)?;
+            let manifest = load_manifest(&mut base_config, &translator, format.is_some())?;
+            if format.is_none() {
+                for game in config.unknown_redirect_games(&manifest) {
+                    eprintln!("{}", translator.cli_unknown_redirect_game(&game));
+                }
+            }
 
             let restore_dir = match path {
                 None => config.restore.path.clone(),
                 Some(p) => p,
             };
 
-            if !preview && !force {
+            if !preview && !dry_run && !force && !interactive {
                 match dialoguer::Confirm::new()
                     .with_prompt(translator.cli_confirm_restoration(&restore_dir))
                     .interact()
@@ -698,12 +2304,37 @@ pub fn run_cli(sub: Subcommand) -> Result<(), Error> {
                 }
             }
 
-            let layout = BackupLayout::new(restore_dir.clone(), config.backup.retention.clone());
+            // A preview only needs to know which files are in an archive, not their contents.
+            let staging = crate::archive::is_archive(&restore_dir).then(|| crate::archive::staging_dir("unpack"));
+            let layout_dir = match &staging {
+                Some(staging) if preview => {
+                    crate::archive::unpack_listing(&restore_dir, staging)?;
+                    StrictPath::clone(staging)
+                }
+                Some(staging) => {
+                    crate::archive::unpack(&restore_dir, staging)?;
+                    StrictPath::clone(staging)
+                }
+                None => restore_dir.clone(),
+            };
+            let layout = BackupLayout::new(layout_dir.clone(), config.backup.retention.clone())
+                .with_anchors(PathAnchors::current(&config.roots));
+
+            let undo = if !preview && !dry_run && config.restore.undo_snapshot {
+                let undo = UndoSnapshot::default();
+                undo.clear();
+                Some(undo)
+            } else {
+                None
+            };
 
             let steam_ids_to_names = &manifest.map_steam_ids_to_names();
             let restorable_names = layout.restorable_games();
 
             let games_specified = !games.is_empty();
+            if games_specified {
+                reporter.set_selection(restorable_names.len());
+            }
             let mut invalid_games: Vec<_> = games
                 .iter()
                 .filter_map(|game| {
@@ -730,7 +2361,7 @@ pub fn run_cli(sub: Subcommand) -> Result<(), Error> {
             if !invalid_games.is_empty() {
                 invalid_games.sort();
                 reporter.trip_unknown_games(invalid_games.clone());
-                reporter.print_failure();
+                reporter.print_failure(&output);
                 return Err(crate::prelude::Error::CliUnrecognizedGames { games: invalid_games });
             }
 
@@ -751,40 +2382,135 @@ pub fn run_cli(sub: Subcommand) -> Result<(), Error> {
             };
             subjects.sort();
 
+            if games_specified && !force && format.is_none() {
+                for game in subjects.iter().filter(|x| config.is_game_frozen_for_restore(x)) {
+                    eprintln!("{}", translator.cli_game_frozen(game));
+                }
+            }
+
+            // The files listed from an archive for a preview have no contents to verify.
+            let listing_only = staging.is_some() && preview;
+            if !listing_only {
+                for warning in check_restore(&layout, &subjects, &config.signature)? {
+                    eprintln!("{}", translator.handle_error(&warning));
+                }
+            }
+            if !preview && !dry_run && config.restore.check_running_games {
+                let running = crate::process::running_process_names();
+                if let Some(warning) = crate::process::check_restore(&manifest, &subjects, &running, force)? {
+                    eprintln!("{}", translator.handle_error(&warning));
+                }
+            }
+
+            let mode = mode.unwrap_or(config.restore.mode);
+            let current_saves = if mode == RestoreMode::Mirror && !preview {
+                current_saves(&config, &manifest, &subjects)
+            } else {
+                Default::default()
+            };
+            if mode == RestoreMode::Mirror && !preview && !dry_run && !force {
+                let extra_files: Vec<_> = subjects
+                    .iter()
+                    .filter(|x| {
+                        restore_decision(&config, x, games_specified, force, false) == OperationStepDecision::Processed
+                    })
+                    .flat_map(|name| {
+                        find_extra_files(
+                            &scan_game_for_restoration(name, &layout),
+                            &config.get_redirects_for_game(name),
+                            current_saves
+                                .get(name.as_str())
+                                .map(|x| x.as_slice())
+                                .unwrap_or_default(),
+                        )
+                    })
+                    .collect();
+                if !extra_files.is_empty() {
+                    for file in &extra_files {
+                        eprintln!("{}", translator.cli_game_line_item_removed(&file.render()));
+                    }
+                    match dialoguer::Confirm::new()
+                        .with_prompt(translator.cli_confirm_mirror_removal(extra_files.len()))
+                        .interact()
+                    {
+                        Ok(true) => (),
+                        Ok(false) => return Ok(()),
+                        Err(_) => return Err(Error::CliUnableToRequestConfirmation),
+                    }
+                }
+            }
+
+            // Games that are declined are reported as ignored.
+            let confirmed = if interactive {
+                let candidates: Vec<_> = subjects
+                    .iter()
+                    .filter(|x| {
+                        restore_decision(&config, x, games_specified, force, false) == OperationStepDecision::Processed
+                    })
+                    .cloned()
+                    .collect();
+                Some(confirm_each_game(
+                    &candidates,
+                    |game| {
+                        translator.cli_confirm_restoration(
+                            &layout.existing_game_folder(game).unwrap_or_else(|| layout_dir.clone()),
+                        )
+                    },
+                    &mut std::io::stdin().lock(),
+                    &mut std::io::stderr(),
+                )?)
+            } else {
+                None
+            };
+
+            let options = RestoreOptions {
+                dry_run,
+                preserve_timestamps: preserve_timestamps || config.restore.preserve_timestamps,
+                verify: verify_after_restore,
+                mode,
+                current_saves,
+                copy_threads: config.runtime.copy_threads,
+                retry: retry.clone(),
+                undo,
+                skip_identical: if skip_identical {
+                    true
+                } else if no_skip_identical {
+                    false
+                } else {
+                    config.restore.skip_identical
+                },
+            };
             let mut info: Vec<_> = subjects
                 .par_iter()
                 .progress_count(subjects.len() as u64)
                 .map(|name| {
                     let scan_info = scan_game_for_restoration(name, &layout);
-                    let ignored = !&config.is_game_enabled_for_restore(name) && !games_specified;
-                    let decision = if ignored {
-                        OperationStepDecision::Ignored
-                    } else {
-                        OperationStepDecision::Processed
-                    };
-                    let restore_info = if preview || ignored {
+                    let declined = confirmed.as_ref().map(|x| !x.contains(name)).unwrap_or(false);
+                    let decision = restore_decision(&config, name, games_specified, force, declined);
+                    let restore_info = if preview || decision != OperationStepDecision::Processed {
                         crate::prelude::BackupInfo::default()
                     } else {
-                        restore_game(&scan_info, &config.get_redirects())
+                        restore_game(&scan_info, &config.get_redirects_for_game(name), &options)
                     };
                     (name, scan_info, restore_info, decision)
                 })
                 .collect();
 
+            if let Some(percent) = verify_sample {
+                let seed = verify_sample_seed.unwrap_or_else(random_seed);
+                reporter.add_verify_sample(verify_restored_sample(&mut info, &config, percent, seed));
+            }
+
             for (_, scan_info, _, _) in info.iter() {
                 duplicate_detector.add_game(scan_info);
             }
 
             let sort = sort.map(From::from).unwrap_or_else(|| config.restore.sort.clone());
-            match sort.key {
-                SortKey::Name => info.sort_by_key(|(name, _, _, _)| name.to_string()),
-                SortKey::Size => info.sort_by_key(|(name, scan_info, backup_info, _)| {
-                    (scan_info.sum_bytes(&Some(backup_info.clone())), name.to_string())
-                }),
-            }
-            if sort.reversed {
-                info.reverse();
-            }
+            sort.apply(
+                &mut info,
+                |(name, _, _, _)| name.to_string(),
+                |(_, scan_info, backup_info, _)| scan_info.sum_bytes(&Some(backup_info.clone())),
+            );
 
             for (name, scan_info, backup_info, decision) in info {
                 if !reporter.add_game(
@@ -792,13 +2518,495 @@ pub fn run_cli(sub: Subcommand) -> Result<(), Error> {
                     &scan_info,
                     &backup_info,
                     &decision,
-                    &config.get_redirects(),
+                    &config.get_redirects_for_game(name),
                     &duplicate_detector,
                 ) {
                     failed = true;
                 }
+                reporter.flush(&output);
+            }
+            reporter.print(&restore_dir, &output);
+        }
+        Subcommand::UndoRestore { force } => {
+            let undo = UndoSnapshot::default();
+            if !undo.exists() {
+                return Err(Error::NoUndoSnapshot);
+            }
+
+            if !force {
+                match dialoguer::Confirm::new()
+                    .with_prompt(translator.cli_confirm_undo_restore())
+                    .interact()
+                {
+                    Ok(true) => (),
+                    Ok(false) => return Ok(()),
+                    Err(_) => return Err(Error::CliUnableToRequestConfirmation),
+                }
+            }
+
+            match undo.undo() {
+                Ok(count) => println!("{}", translator.cli_undo_restore_summary(count)),
+                Err(paths) => {
+                    for path in paths {
+                        println!(
+                            "{}",
+                            translator.cli_game_line_item(
+                                &path.render(),
+                                &LineItemBadges {
+                                    failed: true,
+                                    ..Default::default()
+                                },
+                                &config.badge_order
+                            )
+                        );
+                    }
+                    failed = true;
+                }
+            }
+        }
+        Subcommand::Doctor => unreachable!(),
+        Subcommand::FindGame {
+            by_steam_id,
+            wine_prefix,
+            name,
+        } => {
+            let mut all_games = load_manifest(&mut base_config, &translator, false)?;
+            let steam_ids_to_names = all_games.map_steam_ids_to_names();
+            for custom_game in &config.custom_games {
+                if custom_game.ignore {
+                    continue;
+                }
+                all_games.add_custom_game(custom_game.clone());
+            }
+
+            let resolved = if by_steam_id {
+                name.parse::<u32>()
+                    .ok()
+                    .and_then(|id| steam_ids_to_names.get(&id).cloned())
+            } else if all_games.0.contains_key(&name) {
+                Some(name.clone())
+            } else {
+                None
+            };
+            let resolved = match resolved {
+                Some(x) => x,
+                None => return Err(Error::CliUnrecognizedGames { games: vec![name] }),
+            };
+
+            let game = &all_games.0[&resolved];
+            let steam_id = game.steam.as_ref().and_then(|x| x.id);
+            let subjects = [resolved.clone()];
+            let diagnosis = diagnose_game_for_backup(
+                game,
+                &resolved,
+                &config.roots,
+                &StrictPath::from_std_path_buf(&app_dir()),
+                &steam_id,
+                &config.backup.filter,
+                &wine_prefix,
+                &InstallDirRanking::scan(&config.roots, &all_games, &subjects),
+                &config.backup.toggled_paths,
+                &config.backup.toggled_registry,
+            );
+
+            println!("{}", translator.find_game_manifest());
+            for line in serde_yaml::to_string(game).unwrap_or_default().lines() {
+                if line != "---" {
+                    println!("  {}", line);
+                }
+            }
+
+            println!("\n{}", translator.find_game_roots());
+            for root in &diagnosis.roots {
+                println!("{}", translator.find_game_root(&root.root, &root.install_dir));
+            }
+
+            println!("\n{}", translator.find_game_paths());
+            let mut last_raw = None;
+            for path in &diagnosis.paths {
+                if last_raw != Some(&path.raw) {
+                    println!("  - {}", path.raw);
+                    last_raw = Some(&path.raw);
+                }
+                println!("{}", translator.find_game_path(path));
+            }
+
+            let scan_info = &diagnosis.scan_info;
+            println!();
+            if !scan_info.found_anything() {
+                println!("{}", translator.find_game_nothing_found());
+            } else {
+                println!(
+                    "{}",
+                    translator.cli_game_header(
+                        &resolved,
+                        scan_info.sum_bytes(&None),
+                        &OperationStepDecision::Processed,
+                        false,
+                        &config.badge_order
+                    )
+                );
+                for entry in itertools::sorted(&scan_info.found_files) {
+                    println!(
+                        "{}",
+                        translator.cli_game_line_item(
+                            &entry.path.render(),
+                            &LineItemBadges {
+                                ignored: entry.ignored,
+                                system: entry.system,
+                                ..Default::default()
+                            },
+                            &config.badge_order
+                        )
+                    );
+                }
+                for entry in itertools::sorted(&scan_info.found_registry_keys) {
+                    println!(
+                        "{}",
+                        translator.cli_game_line_item(
+                            &entry.path.render(),
+                            &LineItemBadges {
+                                ignored: entry.ignored,
+                                ..Default::default()
+                            },
+                            &config.badge_order
+                        )
+                    );
+                }
+            }
+        }
+        Subcommand::Clean { dry_run, path, force } => {
+            let target = path.unwrap_or_else(|| config.backup.path.clone());
+            let layout = BackupLayout::new(target.clone(), config.backup.retention.clone());
+            let prunable = layout.find_prunable_folders();
+            if prunable.is_empty() {
+                println!("{}", translator.cli_clean_nothing());
+                return Ok(());
+            }
+
+            for folder in &prunable {
+                println!("{} [{}]", folder.path.render(), translator.adjusted_size(folder.bytes));
+            }
+
+            if !dry_run && !force {
+                match dialoguer::Confirm::new()
+                    .with_prompt(translator.cli_confirm_clean(&target))
+                    .interact()
+                {
+                    Ok(true) => (),
+                    Ok(false) => return Ok(()),
+                    Err(_) => return Err(Error::CliUnableToRequestConfirmation),
+                }
+            }
+
+            let _lock = if dry_run {
+                None
+            } else {
+                Some(TargetLock::acquire(&target, false)?)
+            };
+            let mut removed = 0;
+            let mut freed = 0;
+            for folder in &prunable {
+                if dry_run || folder.path.remove().is_ok() {
+                    removed += 1;
+                    freed += folder.bytes;
+                } else {
+                    println!(
+                        "{}",
+                        translator.cli_game_line_item(
+                            &folder.path.render(),
+                            &LineItemBadges {
+                                failed: true,
+                                ..Default::default()
+                            },
+                            &config.badge_order
+                        )
+                    );
+                    failed = true;
+                }
+            }
+            println!("{}", translator.cli_clean_summary(removed, freed, dry_run));
+        }
+        Subcommand::ManifestChanges { api } => {
+            let changes = ManifestChanges::load();
+            if api {
+                println!("{}", serde_json::to_string_pretty(&changes).unwrap());
+            } else {
+                println!("{}", translator.cli_manifest_changes(changes.as_ref()));
+            }
+        }
+        Subcommand::Config {
+            sub: ConfigSubcommand::Diff { api },
+        } => {
+            let diffs = base_config.diff_from_default();
+            if api {
+                let output = serde_json::json!({ "changes": diffs });
+                println!("{}", serde_json::to_string_pretty(&output).unwrap());
+            } else {
+                println!("{}", translator.cli_config_diff(&diffs));
+            }
+        }
+        Subcommand::Config {
+            sub: ConfigSubcommand::PublicKey,
+        } => match SigningKey::load(&config.signature)? {
+            Some(key) => println!("{}", key.verifying_key().to_base64()),
+            None => return Err(Error::SignatureKeyMissing),
+        },
+        Subcommand::FindRoots { api } => {
+            let candidates = config.find_missing_roots();
+            if api {
+                let output = serde_json::json!({ "roots": candidates });
+                println!("{}", serde_json::to_string_pretty(&output).unwrap());
+            } else {
+                println!("{}", translator.cli_found_roots(&candidates));
+            }
+        }
+        Subcommand::XboxSaves { api, name } => {
+            let mut all_games = load_manifest(&mut base_config, &translator, api)?;
+            for custom_game in &config.custom_games {
+                if custom_game.ignore {
+                    continue;
+                }
+                all_games.add_custom_game(custom_game.clone());
+            }
+            let game = all_games.0.get(&name).ok_or_else(|| Error::CliUnrecognizedGames {
+                games: vec![name.clone()],
+            })?;
+            let containers = crate::wgs::find_containers(game);
+
+            if api {
+                let output = serde_json::json!({ "name": name, "containers": containers });
+                println!("{}", serde_json::to_string_pretty(&output).unwrap());
+            } else {
+                println!("{}", translator.cli_xbox_saves(&containers));
+            }
+        }
+        Subcommand::Status { api } => {
+            let state = ScheduleState::load();
+            let interval = config.backup.schedule_interval_hours.filter(|x| *x > 0);
+            let next = interval.map(|x| state.next_recommended(x));
+
+            if api {
+                let output = serde_json::json!({
+                    "lastRun": state.last_run,
+                    "lastRunSucceeded": state.last_run.map(|_| state.last_run_succeeded),
+                    "lastSuccess": state.last_success,
+                    "intervalHours": interval,
+                    // When there's an interval but no success yet, the next backup is due now.
+                    "nextRecommended": next.map(|x| x.unwrap_or_else(chrono::Utc::now)),
+                });
+                println!("{}", serde_json::to_string_pretty(&output).unwrap());
+            } else {
+                println!("{}", translator.cli_schedule_status(&state, next, chrono::Utc::now()));
+            }
+        }
+        Subcommand::ExportSettings { path } => {
+            // Make sure the config file exists, without any overrides from environment variables.
+            base_config.save();
+            crate::settings::export(&SettingsFiles::current(), &path)?;
+            println!("{}", translator.cli_settings_exported(&path));
+        }
+        Subcommand::ImportSettings { path, replace } => {
+            crate::settings::import(&path, &SettingsFiles::current(), replace)?;
+            println!("{}", translator.cli_settings_imported(&path));
+        }
+        Subcommand::Provenance { api, path, name } => {
+            let layout = BackupLayout::new(
+                path.unwrap_or_else(|| config.restore.path.clone()),
+                config.backup.retention.clone(),
+            );
+            if !layout.restorable_games().contains(&name) {
+                return Err(Error::CliUnrecognizedGames { games: vec![name] });
+            }
+            let backups = layout.game_layout(&name).backup_provenance();
+
+            if api {
+                let output = serde_json::json!({ "name": name, "backups": backups });
+                println!("{}", serde_json::to_string_pretty(&output).unwrap());
+            } else {
+                println!("{}", translator.cli_backup_provenance(&name, &backups));
+            }
+        }
+        Subcommand::ValidateManifest { path } => {
+            let issues = Manifest::load_local(&path)?.find_issues();
+            if !issues.is_empty() {
+                return Err(Error::ManifestInvalid {
+                    why: translator.manifest_issues(&issues),
+                });
+            }
+            println!("{}", translator.cli_manifest_valid());
+        }
+        Subcommand::Attention { api, path } => {
+            let mut all_games = load_manifest(&mut base_config, &translator, api)?;
+            for custom_game in &config.custom_games {
+                if custom_game.ignore {
+                    continue;
+                }
+                all_games.add_custom_game(custom_game.clone());
+            }
+
+            let subjects: Vec<_> = all_games.0.keys().cloned().collect();
+            let ranking = InstallDirRanking::scan(&config.roots, &all_games, &subjects);
+            let scans: Vec<_> = subjects
+                .par_iter()
+                .progress_count(subjects.len() as u64)
+                .map(|name| {
+                    let game = &all_games.0[name];
+                    scan_game_for_backup(
+                        game,
+                        name,
+                        &config.roots,
+                        &StrictPath::from_std_path_buf(&app_dir()),
+                        &game.steam.as_ref().and_then(|x| x.id),
+                        &config.backup.filter,
+                        &None,
+                        &ranking,
+                        &config.backup.toggled_paths,
+                        &config.backup.toggled_registry,
+                    )
+                })
+                .collect();
+            let ignored: std::collections::HashSet<_> = subjects
+                .iter()
+                .filter(|x| !config.is_game_enabled_for_backup(x))
+                .cloned()
+                .collect();
+
+            let layout = BackupLayout::new(
+                path.unwrap_or_else(|| config.backup.path.clone()),
+                config.backup.retention.clone(),
+            );
+            // Backups are only expected to be signed if signatures are being checked.
+            let key = if config.signature.verify == SignatureVerification::Off {
+                None
+            } else {
+                VerifyingKey::load(&config.signature)?
+            };
+            let report = find_games_needing_attention(&scans, &ignored, &layout, key.as_ref());
+
+            if api {
+                let output = serde_json::json!({
+                    "games": report.games,
+                    "counts": report.counts(),
+                });
+                println!("{}", serde_json::to_string_pretty(&output).unwrap());
+            } else if report.games.is_empty() {
+                println!("{}", translator.cli_attention_nothing());
+            } else {
+                for (name, concerns) in &report.games {
+                    println!("{}", translator.cli_attention_game(name, concerns));
+                }
+                println!();
+                println!("{}", translator.cli_attention_summary(&report));
+            }
+        }
+        Subcommand::Diff { api, before, after } => {
+            let mut staged = vec![];
+            let mut layout = |path: StrictPath, purpose: &str| -> Result<BackupLayout, Error> {
+                let path = if crate::archive::is_archive(&path) {
+                    let dir = crate::archive::staging_dir(purpose);
+                    crate::archive::unpack(&path, &dir)?;
+                    let path = StrictPath::clone(&dir);
+                    staged.push(dir);
+                    path
+                } else {
+                    path
+                };
+                Ok(BackupLayout::new(path, config.backup.retention.clone()))
+            };
+            let before = layout(before, "diff-before")?;
+            let after = layout(after, "diff-after")?;
+            let diff = diff_backups(&before, &after);
+
+            if api {
+                println!("{}", serde_json::to_string_pretty(&diff).unwrap());
+            } else if diff.games.is_empty() {
+                println!("{}", translator.cli_diff_no_changes());
+            } else {
+                let mut lines = vec![];
+                for (name, game) in &diff.games {
+                    lines.push(translator.cli_diff_game(name, &game.change));
+                    for (path, file) in &game.files {
+                        lines.push(format!("  - {}", translator.cli_diff_file(path, file)));
+                    }
+                    lines.push("".to_string());
+                }
+                println!("{}", lines.join("\n").trim_end());
+            }
+        }
+        Subcommand::MigrateBackup {
+            from,
+            to,
+            flatten,
+            force,
+        } => {
+            if !force && to.exists() {
+                return Err(Error::CliBackupTargetExists { path: to });
+            }
+            let _lock = if crate::archive::has_archive_name(&to) {
+                None
+            } else {
+                Some(TargetLock::acquire(&to, force)?)
+            };
+
+            let status = migrate_backup(&from, &to, &config, flatten, |name, migrated| {
+                if let Err(e) = migrated {
+                    let line = translator.cli_game_line_item(
+                        name,
+                        &LineItemBadges {
+                            failed: true,
+                            ..Default::default()
+                        },
+                        &config.badge_order,
+                    );
+                    println!(
+                        "{}",
+                        translator.cli_game_line_item_failure(&line, &FailureReason::from(e))
+                    );
+                }
+            })?;
+            if !status.processed_all() {
+                failed = true;
+            }
+            println!("{}", translator.cli_summary(&status, &to));
+        }
+        Subcommand::ExportGame { path, to, name } => {
+            let source = path.unwrap_or_else(|| config.restore.path.clone());
+            export_game(&source, &name, &to, &config)?;
+            println!("{}", translator.cli_game_exported(&name, &to));
+        }
+        Subcommand::ImportGame { path, force, archive } => {
+            let target = path.unwrap_or_else(|| config.restore.path.clone());
+            let _lock = TargetLock::acquire(&target, false)?;
+            let export = import_game(&archive, &target, &config, force)?;
+            if export.registry {
+                eprintln!("{}", translator.cli_imported_registry_warning());
             }
-            reporter.print(&restore_dir);
+            println!("{}", translator.cli_game_imported(&export.name, &target));
+        }
+        Subcommand::Tui => {
+            let manifest = load_manifest(&mut base_config, &translator, false)?;
+            let mut backup_games: Vec<_> = manifest.0.into_keys().collect();
+            backup_games.extend(config.custom_games.iter().filter(|x| !x.ignore).map(|x| x.name.clone()));
+
+            let restore_dir = &config.restore.path;
+            let staging = crate::archive::is_archive(restore_dir).then(|| crate::archive::staging_dir("tui"));
+            let layout_dir = match &staging {
+                Some(staging) => {
+                    crate::archive::unpack_listing(restore_dir, staging)?;
+                    StrictPath::clone(staging)
+                }
+                None => restore_dir.clone(),
+            };
+            // Frozen games can still be restored by name with --force, but not from here.
+            let restore_games: Vec<_> = BackupLayout::new(layout_dir, config.backup.retention.clone())
+                .restorable_games()
+                .into_iter()
+                .filter(|x| !config.is_game_frozen_for_restore(x))
+                .collect();
+            drop(staging);
+
+            crate::tui::run(backup_games, restore_games, &config, &translator, profile.clone())?;
         }
         Subcommand::Complete { shell } => {
             let clap_shell = match shell {
@@ -824,11 +3032,28 @@ pub fn run_cli(sub: Subcommand) -> Result<(), Error> {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+fn run_doctor(translator: &Translator) -> Result<(), Error> {
+    let results = crate::doctor::run_checks(translator);
+    for result in &results {
+        println!("{} {}", translator.doctor_status(result.status), result.label);
+        if let Some(hint) = &result.hint {
+            println!("  - {}", hint);
+        }
+    }
 
-    fn s(text: &str) -> String {
+    if results.iter().any(|x| x.status == CheckStatus::Fail) {
+        Err(Error::SomeChecksFailed)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::TempPath;
+
+    fn s(text: &str) -> String {
         text.to_string()
     }
 
@@ -847,7 +3072,149 @@ mod tests {
 
         #[test]
         fn accepts_cli_without_arguments() {
-            check_args(&["ludusavi"], Cli { sub: None });
+            check_args(
+                &["ludusavi"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: None,
+                },
+            );
+        }
+
+        #[test]
+        fn accepts_cli_with_dir_overrides() {
+            check_args(
+                &[
+                    "ludusavi",
+                    "--config-dir",
+                    "tests/config",
+                    "--data-dir",
+                    "tests/data",
+                    "--portable",
+                ],
+                Cli {
+                    config_dir: Some(StrictPath::new(s("tests/config"))),
+                    data_dir: Some(StrictPath::new(s("tests/data"))),
+                    portable: true,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: None,
+                },
+            );
+        }
+
+        #[test]
+        fn accepts_cli_with_manifest() {
+            check_args(
+                &["ludusavi", "--manifest", "tests/manifest.yaml"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: Some(StrictPath::new(s("tests/manifest.yaml"))),
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: None,
+                },
+            );
+        }
+
+        #[test]
+        fn accepts_cli_with_refresh_manifest() {
+            check_args(
+                &["ludusavi", "--refresh-manifest"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: true,
+                    profile: None,
+                    sub: None,
+                },
+            );
+        }
+
+        #[test]
+        fn accepts_cli_with_profile() {
+            check_args(
+                &["ludusavi", "backup", "--profile", "nas"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: Some(s("nas")),
+                    sub: Some(Subcommand::Backup {
+                        preview: false,
+                        estimate: false,
+                        path: None,
+                        archive: None,
+                        force: false,
+                        merge: false,
+                        no_merge: false,
+                        update: false,
+                        try_update: false,
+                        by_steam_id: false,
+                        wine_prefix: None,
+                        api: false,
+                        format: None,
+                        output: Default::default(),
+                        anonymize: false,
+                        sort: None,
+                        failed_only: false,
+                        wrap: None,
+                        no_cache: false,
+                        include_empty_games: false,
+                        games: vec![],
+                    }),
+                },
+            );
+        }
+
+        #[test]
+        fn accepts_cli_backup_with_jsonl_format() {
+            check_args(
+                &["ludusavi", "backup", "--format", "jsonl"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::Backup {
+                        preview: false,
+                        estimate: false,
+                        path: None,
+                        archive: None,
+                        force: false,
+                        merge: false,
+                        no_merge: false,
+                        update: false,
+                        try_update: false,
+                        by_steam_id: false,
+                        wine_prefix: None,
+                        api: false,
+                        format: Some(CliFormat::JsonLines),
+                        output: Default::default(),
+                        anonymize: false,
+                        sort: None,
+                        failed_only: false,
+                        wrap: None,
+                        no_cache: false,
+                        include_empty_games: false,
+                        games: vec![],
+                    }),
+                },
+            );
         }
 
         #[test]
@@ -855,9 +3222,17 @@ mod tests {
             check_args(
                 &["ludusavi", "backup"],
                 Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
                     sub: Some(Subcommand::Backup {
                         preview: false,
+                        estimate: false,
                         path: None,
+                        archive: None,
                         force: false,
                         merge: false,
                         no_merge: false,
@@ -866,7 +3241,14 @@ mod tests {
                         by_steam_id: false,
                         wine_prefix: None,
                         api: false,
+                        format: None,
+                        output: Default::default(),
+                        anonymize: false,
                         sort: None,
+                        failed_only: false,
+                        wrap: None,
+                        no_cache: false,
+                        include_empty_games: false,
                         games: vec![],
                     }),
                 },
@@ -880,6 +3262,7 @@ mod tests {
                     "ludusavi",
                     "backup",
                     "--preview",
+                    "--estimate",
                     "--path",
                     "tests/backup",
                     "--force",
@@ -891,13 +3274,26 @@ mod tests {
                     "--api",
                     "--sort",
                     "name",
+                    "--failed-only",
+                    "--wrap",
+                    "100",
+                    "--no-cache",
+                    "--include-empty-games",
                     "game1",
                     "game2",
                 ],
                 Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
                     sub: Some(Subcommand::Backup {
                         preview: true,
+                        estimate: true,
                         path: Some(StrictPath::new(s("tests/backup"))),
+                        archive: None,
                         force: true,
                         merge: true,
                         no_merge: false,
@@ -906,7 +3302,14 @@ mod tests {
                         by_steam_id: true,
                         wine_prefix: Some(StrictPath::new(s("tests/wine-prefix"))),
                         api: true,
+                        format: None,
+                        output: Default::default(),
+                        anonymize: false,
                         sort: Some(CliSort::Name),
+                        failed_only: true,
+                        wrap: Some(100),
+                        no_cache: true,
+                        include_empty_games: true,
                         games: vec![s("game1"), s("game2")],
                     }),
                 },
@@ -918,9 +3321,17 @@ mod tests {
             check_args(
                 &["ludusavi", "backup", "--path", "tests/fake"],
                 Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
                     sub: Some(Subcommand::Backup {
                         preview: false,
+                        estimate: false,
                         path: Some(StrictPath::new(s("tests/fake"))),
+                        archive: None,
                         force: false,
                         merge: false,
                         no_merge: false,
@@ -929,7 +3340,14 @@ mod tests {
                         by_steam_id: false,
                         wine_prefix: None,
                         api: false,
+                        format: None,
+                        output: Default::default(),
+                        anonymize: false,
                         sort: None,
+                        failed_only: false,
+                        wrap: None,
+                        no_cache: false,
+                        include_empty_games: false,
                         games: vec![],
                     }),
                 },
@@ -941,9 +3359,17 @@ mod tests {
             check_args(
                 &["ludusavi", "backup", "--no-merge"],
                 Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
                     sub: Some(Subcommand::Backup {
                         preview: false,
+                        estimate: false,
                         path: None,
+                        archive: None,
                         force: false,
                         merge: false,
                         no_merge: true,
@@ -952,7 +3378,14 @@ mod tests {
                         by_steam_id: false,
                         wine_prefix: None,
                         api: false,
+                        format: None,
+                        output: Default::default(),
+                        anonymize: false,
                         sort: None,
+                        failed_only: false,
+                        wrap: None,
+                        no_cache: false,
+                        include_empty_games: false,
                         games: vec![],
                     }),
                 },
@@ -964,9 +3397,17 @@ mod tests {
             check_args(
                 &["ludusavi", "backup", "--try-update"],
                 Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
                     sub: Some(Subcommand::Backup {
                         preview: false,
+                        estimate: false,
                         path: None,
+                        archive: None,
                         force: false,
                         merge: false,
                         no_merge: false,
@@ -975,13 +3416,127 @@ mod tests {
                         by_steam_id: false,
                         wine_prefix: None,
                         api: false,
+                        format: None,
+                        output: Default::default(),
+                        anonymize: false,
+                        sort: None,
+                        failed_only: false,
+                        wrap: None,
+                        no_cache: false,
+                        include_empty_games: false,
+                        games: vec![],
+                    }),
+                },
+            );
+        }
+
+        #[test]
+        fn accepts_cli_backup_with_archive() {
+            check_args(
+                &["ludusavi", "backup", "--archive", "tests/backup.tar.gz"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::Backup {
+                        preview: false,
+                        estimate: false,
+                        path: None,
+                        archive: Some(StrictPath::new(s("tests/backup.tar.gz"))),
+                        force: false,
+                        merge: false,
+                        no_merge: false,
+                        update: false,
+                        try_update: false,
+                        by_steam_id: false,
+                        wine_prefix: None,
+                        api: false,
+                        format: None,
+                        output: Default::default(),
+                        anonymize: false,
+                        sort: None,
+                        failed_only: false,
+                        wrap: None,
+                        no_cache: false,
+                        include_empty_games: false,
+                        games: vec![],
+                    }),
+                },
+            );
+        }
+
+        #[test]
+        fn accepts_cli_backup_with_output_file() {
+            check_args(
+                &[
+                    "ludusavi",
+                    "backup",
+                    "--output-file",
+                    "report.txt",
+                    "--output-format",
+                    "full",
+                    "--output-append",
+                    "--output-only",
+                    "--anonymize",
+                ],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::Backup {
+                        preview: false,
+                        estimate: false,
+                        path: None,
+                        archive: None,
+                        force: false,
+                        merge: false,
+                        no_merge: false,
+                        update: false,
+                        try_update: false,
+                        by_steam_id: false,
+                        wine_prefix: None,
+                        api: false,
+                        format: None,
+                        output: CliOutputFile {
+                            output_file: Some(StrictPath::new(s("report.txt"))),
+                            output_format: Some(CliOutputFormat::Full),
+                            output_append: true,
+                            output_only: true,
+                        },
+                        anonymize: true,
                         sort: None,
+                        failed_only: false,
+                        wrap: None,
+                        no_cache: false,
+                        include_empty_games: false,
                         games: vec![],
                     }),
                 },
             );
         }
 
+        #[test]
+        fn rejects_cli_backup_with_output_options_but_no_output_file() {
+            check_args_err(
+                &["ludusavi", "backup", "--output-only"],
+                clap::ErrorKind::MissingRequiredArgument,
+            );
+        }
+
+        #[test]
+        fn rejects_cli_backup_with_archive_and_merge() {
+            check_args_err(
+                &["ludusavi", "backup", "--archive", "tests/backup.tar", "--merge"],
+                clap::ErrorKind::ArgumentConflict,
+            );
+        }
+
         #[test]
         fn rejects_cli_backup_with_update_and_try_update() {
             check_args_err(
@@ -1003,9 +3558,17 @@ mod tests {
                 check_args(
                     &["ludusavi", "backup", "--sort", value],
                     Cli {
+                        config_dir: None,
+                        data_dir: None,
+                        portable: false,
+                        manifest: None,
+                        refresh_manifest: false,
+                        profile: None,
                         sub: Some(Subcommand::Backup {
                             preview: false,
+                            estimate: false,
                             path: None,
+                            archive: None,
                             force: false,
                             merge: false,
                             no_merge: false,
@@ -1014,7 +3577,14 @@ mod tests {
                             by_steam_id: false,
                             wine_prefix: None,
                             api: false,
+                            format: None,
+                            output: Default::default(),
+                            anonymize: false,
                             sort: Some(sort),
+                            failed_only: false,
+                            wrap: None,
+                            no_cache: false,
+                            include_empty_games: false,
                             games: vec![],
                         }),
                     },
@@ -1027,13 +3597,33 @@ mod tests {
             check_args(
                 &["ludusavi", "restore"],
                 Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
                     sub: Some(Subcommand::Restore {
                         preview: false,
+                        dry_run: false,
+                        preserve_timestamps: false,
+                        verify_after_restore: false,
+                        verify_sample: None,
+                        verify_sample_seed: None,
+                        mode: None,
+                        skip_identical: false,
+                        no_skip_identical: false,
                         path: None,
                         force: false,
+                        interactive: false,
                         by_steam_id: false,
                         api: false,
+                        format: None,
+                        output: Default::default(),
+                        anonymize: false,
                         sort: None,
+                        failed_only: false,
+                        wrap: None,
                         games: vec![],
                     }),
                 },
@@ -1047,6 +3637,7 @@ mod tests {
                     "ludusavi",
                     "restore",
                     "--preview",
+                    "--preserve-timestamps",
                     "--path",
                     "tests/backup",
                     "--force",
@@ -1054,17 +3645,40 @@ mod tests {
                     "--api",
                     "--sort",
                     "name",
+                    "--failed-only",
+                    "--wrap",
+                    "100",
                     "game1",
                     "game2",
                 ],
                 Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
                     sub: Some(Subcommand::Restore {
                         preview: true,
+                        dry_run: false,
+                        preserve_timestamps: true,
+                        verify_after_restore: false,
+                        verify_sample: None,
+                        verify_sample_seed: None,
+                        mode: None,
+                        skip_identical: false,
+                        no_skip_identical: false,
                         path: Some(StrictPath::new(s("tests/backup"))),
                         force: true,
+                        interactive: false,
                         by_steam_id: true,
                         api: true,
+                        format: None,
+                        output: Default::default(),
+                        anonymize: false,
                         sort: Some(CliSort::Name),
+                        failed_only: true,
+                        wrap: Some(100),
                         games: vec![s("game1"), s("game2")],
                     }),
                 },
@@ -1072,223 +3686,1670 @@ mod tests {
         }
 
         #[test]
-        fn rejects_cli_restore_with_nonexistent_path() {
-            check_args_err(
-                &["ludusavi", "restore", "--path", "tests/fake"],
-                clap::ErrorKind::ValueValidation,
+        fn accepts_cli_undo_restore() {
+            check_args(
+                &["ludusavi", "undo-restore", "--force"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::UndoRestore { force: true }),
+                },
             );
         }
 
         #[test]
-        fn accepts_cli_restore_with_sort_variants() {
-            let cases = [
-                ("name", CliSort::Name),
-                ("name-rev", CliSort::NameReversed),
-                ("size", CliSort::Size),
-                ("size-rev", CliSort::SizeReversed),
-            ];
-
-            for (value, sort) in cases {
-                check_args(
-                    &["ludusavi", "restore", "--sort", value],
-                    Cli {
-                        sub: Some(Subcommand::Restore {
-                            preview: false,
-                            path: None,
-                            force: false,
-                            by_steam_id: false,
-                            api: false,
-                            sort: Some(sort),
-                            games: vec![],
-                        }),
-                    },
-                );
-            }
+        fn accepts_cli_find_game() {
+            check_args(
+                &[
+                    "ludusavi",
+                    "find-game",
+                    "--by-steam-id",
+                    "--wine-prefix",
+                    "tests/wine-prefix",
+                    "123",
+                ],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::FindGame {
+                        by_steam_id: true,
+                        wine_prefix: Some(StrictPath::new(s("tests/wine-prefix"))),
+                        name: s("123"),
+                    }),
+                },
+            );
         }
 
         #[test]
-        fn accepts_cli_complete_for_bash() {
+        fn accepts_cli_clean() {
             check_args(
-                &["ludusavi", "complete", "bash"],
+                &["ludusavi", "clean", "--dry-run", "--path", "tests/backup", "--force"],
                 Cli {
-                    sub: Some(Subcommand::Complete {
-                        shell: CompletionShell::Bash,
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::Clean {
+                        dry_run: true,
+                        path: Some(StrictPath::new(s("tests/backup"))),
+                        force: true,
                     }),
                 },
             );
         }
 
         #[test]
-        fn accepts_cli_complete_for_fish() {
+        fn accepts_cli_attention() {
             check_args(
-                &["ludusavi", "complete", "fish"],
+                &["ludusavi", "attention", "--api", "--path", "tests/backup"],
                 Cli {
-                    sub: Some(Subcommand::Complete {
-                        shell: CompletionShell::Fish,
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::Attention {
+                        api: true,
+                        path: Some(StrictPath::new(s("tests/backup"))),
                     }),
                 },
             );
         }
 
         #[test]
-        fn accepts_cli_complete_for_zsh() {
+        fn accepts_cli_manifest_changes() {
             check_args(
-                &["ludusavi", "complete", "zsh"],
+                &["ludusavi", "manifest-changes", "--api"],
                 Cli {
-                    sub: Some(Subcommand::Complete {
-                        shell: CompletionShell::Zsh,
-                    }),
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::ManifestChanges { api: true }),
                 },
             );
         }
 
         #[test]
-        fn accepts_cli_complete_for_powershell() {
+        fn accepts_cli_config_diff() {
             check_args(
-                &["ludusavi", "complete", "powershell"],
+                &["ludusavi", "config", "diff", "--api"],
                 Cli {
-                    sub: Some(Subcommand::Complete {
-                        shell: CompletionShell::PowerShell,
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::Config {
+                        sub: ConfigSubcommand::Diff { api: true },
                     }),
                 },
             );
         }
 
         #[test]
-        fn accepts_cli_complete_for_elvish() {
+        fn accepts_cli_config_public_key() {
             check_args(
-                &["ludusavi", "complete", "elvish"],
+                &["ludusavi", "config", "public-key"],
                 Cli {
-                    sub: Some(Subcommand::Complete {
-                        shell: CompletionShell::Elvish,
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::Config {
+                        sub: ConfigSubcommand::PublicKey,
                     }),
                 },
             );
         }
-    }
 
-    mod reporter {
-        use super::*;
-        use crate::prelude::{RegistryItem, ScannedFile, ScannedRegistry};
-        use maplit::hashset;
-        use pretty_assertions::assert_eq;
-
-        fn drive() -> String {
-            if cfg!(target_os = "windows") {
-                StrictPath::new(s("foo")).render()[..2].to_string()
-            } else {
-                s("")
-            }
+        #[test]
+        fn accepts_cli_xbox_saves() {
+            check_args(
+                &["ludusavi", "xbox-saves", "--api", "game"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::XboxSaves {
+                        api: true,
+                        name: s("game"),
+                    }),
+                },
+            );
         }
 
         #[test]
-        fn can_render_in_standard_mode_with_minimal_input() {
-            let mut reporter = Reporter::standard(Translator::default());
-            reporter.add_game(
-                "foo",
-                &ScanInfo::default(),
-                &BackupInfo::default(),
-                &OperationStepDecision::Processed,
-                &[],
-                &DuplicateDetector::default(),
+        fn accepts_cli_status() {
+            check_args(
+                &["ludusavi", "status", "--api"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::Status { api: true }),
+                },
             );
-            assert_eq!(
-                format!(
-                    r#"
-Overall:
-  Games: 0
-  Size: 0 B
-  Location: {}/dev/null
-                "#,
-                    &drive()
-                )
-                .trim_end(),
-                reporter.render(&StrictPath::new(s("/dev/null")))
-            )
         }
 
         #[test]
-        fn can_render_in_standard_mode_with_one_game_in_backup_mode() {
-            let mut reporter = Reporter::standard(Translator::default());
-
-            reporter.add_game(
-                "foo",
+        fn accepts_cli_import_settings() {
+            check_args(
+                &["ludusavi", "import-settings", "--replace", "tests/root1"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::ImportSettings {
+                        path: StrictPath::new(s("tests/root1")),
+                        replace: true,
+                    }),
+                },
+            );
+        }
+
+        #[test]
+        fn accepts_cli_find_roots() {
+            check_args(
+                &["ludusavi", "find-roots", "--api"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::FindRoots { api: true }),
+                },
+            );
+        }
+
+        #[test]
+        fn accepts_cli_provenance() {
+            check_args(
+                &["ludusavi", "provenance", "--api", "--path", "tests/backup", "game1"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::Provenance {
+                        api: true,
+                        path: Some(StrictPath::new(s("tests/backup"))),
+                        name: s("game1"),
+                    }),
+                },
+            );
+        }
+
+        #[test]
+        fn accepts_cli_validate_manifest() {
+            check_args(
+                &["ludusavi", "validate-manifest", "tests/manifest.yaml"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::ValidateManifest {
+                        path: StrictPath::new(s("tests/manifest.yaml")),
+                    }),
+                },
+            );
+        }
+
+        #[test]
+        fn accepts_cli_diff() {
+            check_args(
+                &["ludusavi", "diff", "--api", "tests/backup", "tests/backup"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::Diff {
+                        api: true,
+                        before: StrictPath::new(s("tests/backup")),
+                        after: StrictPath::new(s("tests/backup")),
+                    }),
+                },
+            );
+        }
+
+        #[test]
+        fn accepts_cli_migrate_backup() {
+            check_args(
+                &[
+                    "ludusavi",
+                    "migrate-backup",
+                    "--from",
+                    "tests/backup",
+                    "--to",
+                    "migrated.tar.gz",
+                    "--flatten",
+                ],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::MigrateBackup {
+                        from: StrictPath::new(s("tests/backup")),
+                        to: StrictPath::new(s("migrated.tar.gz")),
+                        flatten: true,
+                        force: false,
+                    }),
+                },
+            );
+        }
+
+        #[test]
+        fn accepts_cli_export_game() {
+            check_args(
+                &[
+                    "ludusavi",
+                    "export-game",
+                    "--path",
+                    "tests/backup",
+                    "--to",
+                    "game1.tar.gz",
+                    "game1",
+                ],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::ExportGame {
+                        path: Some(StrictPath::new(s("tests/backup"))),
+                        to: StrictPath::new(s("game1.tar.gz")),
+                        name: s("game1"),
+                    }),
+                },
+            );
+        }
+
+        #[test]
+        fn accepts_cli_import_game() {
+            check_args(
+                &["ludusavi", "import-game", "--force", "tests/root1"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::ImportGame {
+                        path: None,
+                        force: true,
+                        archive: StrictPath::new(s("tests/root1")),
+                    }),
+                },
+            );
+        }
+
+        #[test]
+        fn accepts_cli_doctor() {
+            check_args(
+                &["ludusavi", "doctor"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::Doctor),
+                },
+            );
+        }
+
+        #[test]
+        fn accepts_cli_restore_with_dry_run() {
+            check_args(
+                &["ludusavi", "restore", "--dry-run"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::Restore {
+                        preview: false,
+                        dry_run: true,
+                        preserve_timestamps: false,
+                        verify_after_restore: false,
+                        verify_sample: None,
+                        verify_sample_seed: None,
+                        mode: None,
+                        skip_identical: false,
+                        no_skip_identical: false,
+                        path: None,
+                        force: false,
+                        interactive: false,
+                        by_steam_id: false,
+                        api: false,
+                        format: None,
+                        output: Default::default(),
+                        anonymize: false,
+                        sort: None,
+                        failed_only: false,
+                        wrap: None,
+                        games: vec![],
+                    }),
+                },
+            );
+        }
+
+        #[test]
+        fn accepts_cli_restore_with_mirror_mode() {
+            check_args(
+                &["ludusavi", "restore", "--mode", "mirror"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::Restore {
+                        preview: false,
+                        dry_run: false,
+                        preserve_timestamps: false,
+                        verify_after_restore: false,
+                        verify_sample: None,
+                        verify_sample_seed: None,
+                        mode: Some(RestoreMode::Mirror),
+                        skip_identical: false,
+                        no_skip_identical: false,
+                        path: None,
+                        force: false,
+                        interactive: false,
+                        by_steam_id: false,
+                        api: false,
+                        format: None,
+                        output: Default::default(),
+                        anonymize: false,
+                        sort: None,
+                        failed_only: false,
+                        wrap: None,
+                        games: vec![],
+                    }),
+                },
+            );
+        }
+
+        #[test]
+        fn accepts_cli_restore_without_skipping_identical_files() {
+            check_args(
+                &["ludusavi", "restore", "--no-skip-identical"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::Restore {
+                        preview: false,
+                        dry_run: false,
+                        preserve_timestamps: false,
+                        verify_after_restore: false,
+                        verify_sample: None,
+                        verify_sample_seed: None,
+                        mode: None,
+                        skip_identical: false,
+                        no_skip_identical: true,
+                        path: None,
+                        force: false,
+                        interactive: false,
+                        by_steam_id: false,
+                        api: false,
+                        format: None,
+                        output: Default::default(),
+                        anonymize: false,
+                        sort: None,
+                        failed_only: false,
+                        wrap: None,
+                        games: vec![],
+                    }),
+                },
+            );
+        }
+
+        #[test]
+        fn accepts_cli_restore_with_verify_after_restore() {
+            check_args(
+                &["ludusavi", "restore", "--verify-after-restore"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::Restore {
+                        preview: false,
+                        dry_run: false,
+                        preserve_timestamps: false,
+                        verify_after_restore: true,
+                        verify_sample: None,
+                        verify_sample_seed: None,
+                        mode: None,
+                        skip_identical: false,
+                        no_skip_identical: false,
+                        path: None,
+                        force: false,
+                        interactive: false,
+                        by_steam_id: false,
+                        api: false,
+                        format: None,
+                        output: Default::default(),
+                        anonymize: false,
+                        sort: None,
+                        failed_only: false,
+                        wrap: None,
+                        games: vec![],
+                    }),
+                },
+            );
+        }
+
+        #[test]
+        fn accepts_cli_restore_with_verify_sample() {
+            check_args(
+                &[
+                    "ludusavi",
+                    "restore",
+                    "--verify-sample",
+                    "2.5",
+                    "--verify-sample-seed",
+                    "42",
+                ],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::Restore {
+                        preview: false,
+                        dry_run: false,
+                        preserve_timestamps: false,
+                        verify_after_restore: false,
+                        verify_sample: Some(2.5),
+                        verify_sample_seed: Some(42),
+                        mode: None,
+                        skip_identical: false,
+                        no_skip_identical: false,
+                        path: None,
+                        force: false,
+                        interactive: false,
+                        by_steam_id: false,
+                        api: false,
+                        format: None,
+                        output: Default::default(),
+                        anonymize: false,
+                        sort: None,
+                        failed_only: false,
+                        wrap: None,
+                        games: vec![],
+                    }),
+                },
+            );
+        }
+
+        #[test]
+        fn rejects_cli_restore_with_verify_sample_out_of_range() {
+            for percent in ["0", "101", "abc"] {
+                check_args_err(
+                    &["ludusavi", "restore", "--verify-sample", percent],
+                    clap::ErrorKind::ValueValidation,
+                );
+            }
+            check_args_err(
+                &["ludusavi", "restore", "--verify-sample-seed", "42"],
+                clap::ErrorKind::MissingRequiredArgument,
+            );
+        }
+
+        #[test]
+        fn accepts_cli_tui() {
+            check_args(
+                &["ludusavi", "tui"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::Tui),
+                },
+            );
+        }
+
+        #[test]
+        fn accepts_cli_restore_with_interactive() {
+            check_args(
+                &["ludusavi", "restore", "--interactive"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::Restore {
+                        preview: false,
+                        dry_run: false,
+                        preserve_timestamps: false,
+                        verify_after_restore: false,
+                        verify_sample: None,
+                        verify_sample_seed: None,
+                        mode: None,
+                        skip_identical: false,
+                        no_skip_identical: false,
+                        path: None,
+                        force: false,
+                        interactive: true,
+                        by_steam_id: false,
+                        api: false,
+                        format: None,
+                        output: Default::default(),
+                        anonymize: false,
+                        sort: None,
+                        failed_only: false,
+                        wrap: None,
+                        games: vec![],
+                    }),
+                },
+            );
+        }
+
+        #[test]
+        fn rejects_cli_restore_with_interactive_and_dry_run() {
+            check_args_err(
+                &["ludusavi", "restore", "--interactive", "--dry-run"],
+                clap::ErrorKind::ArgumentConflict,
+            );
+        }
+
+        #[test]
+        fn rejects_cli_restore_with_preview_and_dry_run() {
+            check_args_err(
+                &["ludusavi", "restore", "--preview", "--dry-run"],
+                clap::ErrorKind::ArgumentConflict,
+            );
+        }
+
+        #[test]
+        fn rejects_cli_restore_with_nonexistent_path() {
+            check_args_err(
+                &["ludusavi", "restore", "--path", "tests/fake"],
+                clap::ErrorKind::ValueValidation,
+            );
+        }
+
+        #[test]
+        fn accepts_cli_restore_with_sort_variants() {
+            let cases = [
+                ("name", CliSort::Name),
+                ("name-rev", CliSort::NameReversed),
+                ("size", CliSort::Size),
+                ("size-rev", CliSort::SizeReversed),
+            ];
+
+            for (value, sort) in cases {
+                check_args(
+                    &["ludusavi", "restore", "--sort", value],
+                    Cli {
+                        config_dir: None,
+                        data_dir: None,
+                        portable: false,
+                        manifest: None,
+                        refresh_manifest: false,
+                        profile: None,
+                        sub: Some(Subcommand::Restore {
+                            preview: false,
+                            dry_run: false,
+                            preserve_timestamps: false,
+                            verify_after_restore: false,
+                            verify_sample: None,
+                            verify_sample_seed: None,
+                            mode: None,
+                            skip_identical: false,
+                            no_skip_identical: false,
+                            path: None,
+                            force: false,
+                            interactive: false,
+                            by_steam_id: false,
+                            api: false,
+                            format: None,
+                            output: Default::default(),
+                            anonymize: false,
+                            sort: Some(sort),
+                            failed_only: false,
+                            wrap: None,
+                            games: vec![],
+                        }),
+                    },
+                );
+            }
+        }
+
+        #[test]
+        fn accepts_cli_complete_for_bash() {
+            check_args(
+                &["ludusavi", "complete", "bash"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::Complete {
+                        shell: CompletionShell::Bash,
+                    }),
+                },
+            );
+        }
+
+        #[test]
+        fn accepts_cli_complete_for_fish() {
+            check_args(
+                &["ludusavi", "complete", "fish"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::Complete {
+                        shell: CompletionShell::Fish,
+                    }),
+                },
+            );
+        }
+
+        #[test]
+        fn accepts_cli_complete_for_zsh() {
+            check_args(
+                &["ludusavi", "complete", "zsh"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::Complete {
+                        shell: CompletionShell::Zsh,
+                    }),
+                },
+            );
+        }
+
+        #[test]
+        fn accepts_cli_complete_for_powershell() {
+            check_args(
+                &["ludusavi", "complete", "powershell"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::Complete {
+                        shell: CompletionShell::PowerShell,
+                    }),
+                },
+            );
+        }
+
+        #[test]
+        fn accepts_cli_complete_for_elvish() {
+            check_args(
+                &["ludusavi", "complete", "elvish"],
+                Cli {
+                    config_dir: None,
+                    data_dir: None,
+                    portable: false,
+                    manifest: None,
+                    refresh_manifest: false,
+                    profile: None,
+                    sub: Some(Subcommand::Complete {
+                        shell: CompletionShell::Elvish,
+                    }),
+                },
+            );
+        }
+    }
+
+    #[test]
+    fn skips_frozen_games_unless_forced_by_name() {
+        let mut config = Config::default();
+        config.restore.frozen_games.insert(s("frozen"));
+        config.restore.ignored_games.insert(s("ignored"));
+        let decide = |name, games_specified, force| restore_decision(&config, name, games_specified, force, false);
+
+        assert_eq!(OperationStepDecision::Processed, decide("other", false, false));
+        assert_eq!(OperationStepDecision::Frozen, decide("frozen", false, false));
+        assert_eq!(OperationStepDecision::Frozen, decide("frozen", false, true));
+        assert_eq!(OperationStepDecision::Frozen, decide("frozen", true, false));
+        assert_eq!(OperationStepDecision::Processed, decide("frozen", true, true));
+        assert_eq!(OperationStepDecision::Ignored, decide("ignored", false, true));
+        assert_eq!(OperationStepDecision::Processed, decide("ignored", true, false));
+        assert_eq!(
+            OperationStepDecision::Ignored,
+            restore_decision(&config, "other", false, false, true)
+        );
+    }
+
+    #[test]
+    fn rejects_config_that_archive_does_not_follow() {
+        let translator = Translator::default();
+        let mut config = Config::default();
+
+        assert_eq!(Ok(()), check_archive_settings(&config, false, &translator));
+        assert_eq!(
+            Err(Error::ConfigInvalid {
+                why: translator.cli_archive_with_merge()
+            }),
+            check_archive_settings(&config, true, &translator)
+        );
+
+        config.backup.retention.differential = 2;
+        assert_eq!(
+            Err(Error::ConfigInvalid {
+                why: translator.cli_archive_with_differential()
+            }),
+            check_archive_settings(&config, false, &translator)
+        );
+    }
+
+    mod confirmation {
+        use super::*;
+        use maplit::hashset;
+        use pretty_assertions::assert_eq;
+
+        fn confirm(games: &[&str], input: &str) -> (Result<std::collections::HashSet<String>, Error>, String) {
+            let games: Vec<_> = games.iter().map(|x| s(x)).collect();
+            let mut output = vec![];
+            let result = confirm_each_game(
+                &games,
+                |game| format!("Restore {}?", game),
+                &mut input.as_bytes(),
+                &mut output,
+            );
+            (result, String::from_utf8(output).unwrap())
+        }
+
+        #[test]
+        fn can_confirm_or_decline_each_game() {
+            let (result, output) = confirm(&["a", "b", "c"], "y\nn\nyes\n");
+            assert_eq!(Ok(hashset! { s("a"), s("c") }), result);
+            assert_eq!(
+                "Restore a? [y/n/a/q] Restore b? [y/n/a/q] Restore c? [y/n/a/q] ",
+                output
+            );
+        }
+
+        #[test]
+        fn stops_asking_after_all() {
+            let (result, output) = confirm(&["a", "b", "c"], "n\na\n");
+            assert_eq!(Ok(hashset! { s("b"), s("c") }), result);
+            assert_eq!("Restore a? [y/n/a/q] Restore b? [y/n/a/q] ", output);
+        }
+
+        #[test]
+        fn skips_remaining_games_after_quit() {
+            let (result, _) = confirm(&["a", "b", "c"], "y\nq\n");
+            assert_eq!(Ok(hashset! { s("a") }), result);
+        }
+
+        #[test]
+        fn asks_again_after_unrecognized_answer() {
+            let (result, output) = confirm(&["a"], "maybe\nY\n");
+            assert_eq!(Ok(hashset! { s("a") }), result);
+            assert_eq!("Restore a? [y/n/a/q] Restore a? [y/n/a/q] ", output);
+        }
+
+        #[test]
+        fn fails_when_input_runs_out() {
+            let (result, _) = confirm(&["a", "b"], "y\n");
+            assert_eq!(Err(Error::CliUnableToRequestConfirmation), result);
+        }
+    }
+
+    mod reporter {
+        use super::*;
+        use crate::prelude::{RegistryFilter, RegistryItem, ScannedFile, ScannedRegistry, SkipReason};
+        use maplit::{btreemap, hashmap, hashset};
+        use pretty_assertions::assert_eq;
+
+        fn drive() -> String {
+            if cfg!(target_os = "windows") {
+                StrictPath::new(s("foo")).render()[..2].to_string()
+            } else {
+                s("")
+            }
+        }
+
+        #[test]
+        fn can_render_in_standard_mode_with_minimal_input() {
+            let mut reporter = Reporter::standard(Translator::default());
+            reporter.add_game(
+                "foo",
+                &ScanInfo::default(),
+                &BackupInfo::default(),
+                &OperationStepDecision::Processed,
+                &[],
+                &DuplicateDetector::default(),
+            );
+            assert_eq!(
+                r#"
+No games were found. Check your roots and make sure the manifest has been downloaded.
+                "#
+                .trim_end(),
+                reporter.render(&StrictPath::new(s("/dev/null")))
+            )
+        }
+
+        fn render_with_empty_game(include_empty: bool) -> String {
+            let mut reporter = Reporter::standard(Translator::default());
+            if include_empty {
+                reporter.trip_include_empty();
+            }
+            reporter.add_game(
+                "bar",
+                &ScanInfo {
+                    game_name: s("bar"),
+                    ..Default::default()
+                },
+                &BackupInfo::default(),
+                &OperationStepDecision::Processed,
+                &[],
+                &DuplicateDetector::default(),
+            );
+            reporter.add_game(
+                "foo",
+                &ScanInfo {
+                    game_name: s("foo"),
+                    found_files: hashset! {
+                        ScannedFile::new("/file1", 102_400),
+                    },
+                    ..Default::default()
+                },
+                &BackupInfo::default(),
+                &OperationStepDecision::Processed,
+                &[],
+                &DuplicateDetector::default(),
+            );
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        }
+
+        #[test]
+        fn can_render_in_standard_mode_without_empty_games() {
+            assert_eq!(
+                r#"
+foo [100.00 KiB]:
+  - <drive>/file1
+
+Overall:
+  Games: 1
+  Size: 100.00 KiB
+  Location: <drive>/dev/null
+                "#
+                .trim()
+                .replace("<drive>", &drive()),
+                render_with_empty_game(false)
+            )
+        }
+
+        #[test]
+        fn can_render_in_standard_mode_with_empty_games() {
+            assert_eq!(
+                r#"
+bar [0 B] [EMPTY]:
+  Nothing was found for this game.
+
+foo [100.00 KiB]:
+  - <drive>/file1
+
+Overall:
+  Games: 1
+  Size: 100.00 KiB
+  Location: <drive>/dev/null
+                "#
+                .trim()
+                .replace("<drive>", &drive()),
+                render_with_empty_game(true)
+            )
+        }
+
+        #[test]
+        fn can_render_in_standard_mode_with_all_games_ignored() {
+            let mut reporter = Reporter::standard(Translator::default());
+            reporter.add_game(
+                "foo",
+                &ScanInfo {
+                    game_name: s("foo"),
+                    found_files: hashset! {
+                        ScannedFile::new("/file1", 102_400),
+                    },
+                    ..Default::default()
+                },
+                &BackupInfo::default(),
+                &OperationStepDecision::Ignored,
+                &[],
+                &DuplicateDetector::default(),
+            );
+            assert_eq!(
+                r#"
+foo [100.00 KiB] [IGNORED]:
+  - <drive>/file1
+
+Overall:
+  Games: 0 of 1
+  Size: 0 B of 100.00 KiB
+  Location: <drive>/dev/null
+  Skipped: 1 ignored
+
+All of the games that were found are ignored. Check your ignored games and files.
+                "#
+                .trim()
+                .replace("<drive>", &drive()),
+                reporter.render(&StrictPath::new(s("/dev/null")))
+            )
+        }
+
+        #[test]
+        fn can_render_in_standard_mode_with_selected_games() {
+            let mut reporter = Reporter::standard(Translator::default());
+            reporter.set_selection(250);
+            reporter.add_game(
+                "foo",
+                &ScanInfo {
+                    game_name: s("foo"),
+                    found_files: hashset! {
+                        ScannedFile::new("/file1", 102_400),
+                    },
+                    ..Default::default()
+                },
+                &BackupInfo::default(),
+                &OperationStepDecision::Processed,
+                &[],
+                &DuplicateDetector::default(),
+            );
+            assert_eq!(
+                r#"
+foo [100.00 KiB]:
+  - <drive>/file1
+
+Overall:
+  Games: 1 of 250 (only the games you selected)
+  Size: 100.00 KiB
+  Location: <drive>/dev/null
+                "#
+                .trim()
+                .replace("<drive>", &drive()),
+                reporter.render(&StrictPath::new(s("/dev/null")))
+            )
+        }
+
+        #[test]
+        fn estimate_does_not_read_file_contents() {
+            let base = TempPath::new("ludusavi-test-estimate-reads");
+            std::fs::create_dir_all(base.joined("root").interpret()).unwrap();
+            std::fs::write(base.joined("root/save.txt").interpret(), "abc").unwrap();
+            let manifest = Manifest::load_from_string("game1:\n  files:\n    <root>/save.txt: {}").unwrap();
+            let roots = vec![crate::config::RootsConfig {
+                path: base.joined("root"),
+                store: crate::manifest::Store::Other,
+                require: Default::default(),
+            }];
+            let reads_in_base = || {
+                crate::prelude::CONTENT_READS
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .filter(|x| x.interpret().starts_with(&base.interpret()))
+                    .count()
+            };
+            let back_up = || {
+                let scan_info = scan_game_for_backup(
+                    &manifest.0["game1"],
+                    "game1",
+                    &roots,
+                    &StrictPath::new(s(env!("CARGO_MANIFEST_DIR"))),
+                    &None,
+                    &Default::default(),
+                    &None,
+                    &InstallDirRanking::default(),
+                    &Default::default(),
+                    &Default::default(),
+                );
+                let layout = BackupLayout::new(base.joined("backup"), Default::default())
+                    .with_comparison(crate::config::ComparisonStrategy::Hash);
+                back_up_game_to_targets(
+                    &scan_info,
+                    "game1",
+                    &[layout],
+                    true,
+                    &chrono::Utc::now(),
+                    1,
+                    &Retry::default(),
+                    Default::default(),
+                );
+            };
+
+            back_up();
+            let before = reads_in_base();
+            let estimate = estimate_backup(&Config::default(), &manifest, &[s("game1")], &roots, &None, false);
+            let after_estimate = reads_in_base();
+            // A real backup compares the file against the existing one, which the counter should see.
+            back_up();
+            let after_backup = reads_in_base();
+
+            assert_eq!(1, estimate.len());
+            assert_eq!(1, estimate[0].1.found_files.len());
+            assert_eq!(before, after_estimate);
+            assert!(after_backup > after_estimate);
+        }
+
+        #[test]
+        fn can_render_estimate_from_file_sizes() {
+            let repo = env!("CARGO_MANIFEST_DIR").replace('\\', "/");
+            let manifest = Manifest::load_from_string(
+                r#"
+                game1:
+                  files:
+                    <base>/subdir: {}
+                "#,
+            )
+            .unwrap();
+            let roots = vec![crate::config::RootsConfig {
+                path: StrictPath::new(format!("{}/tests/root1", repo)),
+                store: crate::manifest::Store::Other,
+                require: Default::default(),
+            }];
+            let mut reporter = Reporter::standard(Translator::default());
+            reporter.trip_estimate();
+            for (name, scan_info, decision) in
+                estimate_backup(&Config::default(), &manifest, &[s("game1")], &roots, &None, false)
+            {
+                reporter.add_game(
+                    &name,
+                    &scan_info,
+                    &BackupInfo::default(),
+                    &decision,
+                    &[],
+                    &DuplicateDetector::default(),
+                );
+            }
+
+            assert_eq!(
+                format!(
+                    r#"
+Overall:
+  Games: 1
+  Size: 2 B
+  Location: {}/dev/null
+  Files: 1
+
+Estimate: these totals only come from file sizes on disk. No file contents were read, and nothing was backed up.
+                "#,
+                    &drive()
+                )
+                .trim_end(),
+                reporter.render(&StrictPath::new(s("/dev/null")))
+            );
+        }
+
+        #[test]
+        fn can_render_in_standard_mode_with_one_game_in_backup_mode() {
+            let mut reporter = Reporter::standard(Translator::default());
+
+            reporter.add_game(
+                "foo",
+                &ScanInfo {
+                    game_name: s("foo"),
+                    found_files: hashset! {
+                        ScannedFile {
+                            path: StrictPath::new(s("/file1")),
+                            size: 102_400,
+                            original_path: None,
+                            ignored: false,
+                            system: false,
+                            partial: false,
+                            xbox_name: None,
+                        },
+                        ScannedFile {
+                            path: StrictPath::new(s("/file2")),
+                            size: 51_200,
+                            original_path: None,
+                            ignored: false,
+                            system: false,
+                            partial: false,
+                            xbox_name: None,
+                        },
+                    },
+                    found_registry_keys: hashset! {
+                        ScannedRegistry::new("HKEY_CURRENT_USER/Key1"),
+                        ScannedRegistry::new("HKEY_CURRENT_USER/Key2"),
+                    },
+                    registry_file: None,
+                    cloud_only: false,
+                },
+                &BackupInfo {
+                    failed_files: hashset! {
+                        ScannedFile::new("/file2", 51_200),
+                    },
+                    failed_registry: hashset! {
+                        RegistryItem::new(s("HKEY_CURRENT_USER/Key1"))
+                    },
+                    ..Default::default()
+                },
+                &OperationStepDecision::Processed,
+                &[],
+                &DuplicateDetector::default(),
+            );
+            assert_eq!(
+                r#"
+foo [100.00 KiB]:
+  - <drive>/file1
+  - [FAILED] <drive>/file2
+  - [FAILED] HKEY_CURRENT_USER/Key1
+  - HKEY_CURRENT_USER/Key2
+
+Overall:
+  Games: 1 of 1
+  Size: 100.00 KiB of 150.00 KiB
+  Location: <drive>/dev/null
+
+Errors:
+  unknown reason:
+    - foo: <drive>/file2
+    - foo: HKEY_CURRENT_USER/Key1
+                "#
+                .trim()
+                .replace("<drive>", &drive()),
+                reporter.render(&StrictPath::new(s("/dev/null")))
+            );
+        }
+
+        #[test]
+        fn can_render_copied_bytes_in_standard_mode() {
+            let mut reporter = Reporter::standard(Translator::default());
+
+            reporter.add_game(
+                "foo",
+                &ScanInfo {
+                    game_name: s("foo"),
+                    found_files: hashset! {
+                        ScannedFile::new("/file1", 102_400),
+                        ScannedFile::new("/file2", 51_200),
+                    },
+                    ..Default::default()
+                },
+                &BackupInfo {
+                    copied_bytes: 51_200,
+                    ..Default::default()
+                },
+                &OperationStepDecision::Processed,
+                &[],
+                &DuplicateDetector::default(),
+            );
+            assert_eq!(
+                r#"
+foo [150.00 KiB]:
+  - <drive>/file1
+  - <drive>/file2
+
+Overall:
+  Games: 1
+  Size: 150.00 KiB
+  Location: <drive>/dev/null
+  Written: 50.00 KiB
+                "#
+                .trim()
+                .replace("<drive>", &drive()),
+                reporter.render(&StrictPath::new(s("/dev/null")))
+            );
+        }
+
+        #[test]
+        fn can_render_in_standard_mode_with_multiple_targets() {
+            let mut reporter = Reporter::standard(Translator::default());
+            reporter.add_unusable_target(&StrictPath::new(s("/third")));
+
+            reporter.add_game(
+                "foo",
+                &ScanInfo {
+                    game_name: s("foo"),
+                    found_files: hashset! {
+                        ScannedFile::new("/file1", 102_400),
+                    },
+                    ..Default::default()
+                },
+                &BackupInfo {
+                    targets: btreemap! {
+                        StrictPath::new(s("/first")) => true,
+                        StrictPath::new(s("/second")) => false,
+                    },
+                    ..Default::default()
+                },
+                &OperationStepDecision::Processed,
+                &[],
+                &DuplicateDetector::default(),
+            );
+            assert_eq!(
+                r#"
+foo [100.00 KiB]:
+  - <drive>/file1
+
+Overall:
+  Games: 1
+  Size: 100.00 KiB
+  Location: <drive>/dev/null
+  Target <drive>/first: 1 games
+  Target <drive>/second: 1 of 1 games failed
+  Target <drive>/third: couldn't be used, so nothing was written to it
+                "#
+                .trim()
+                .replace("<drive>", &drive()),
+                reporter.render(&StrictPath::new(s("/dev/null")))
+            );
+        }
+
+        #[test]
+        fn can_render_in_standard_mode_with_filtered_registry_values() {
+            let mut reporter = Reporter::standard(Translator::default());
+
+            reporter.add_game(
+                "foo",
+                &ScanInfo {
+                    game_name: s("foo"),
+                    found_registry_keys: hashset! {
+                        ScannedRegistry {
+                            filter: RegistryFilter {
+                                exclude_values: vec![s("Window*")],
+                                ..Default::default()
+                            },
+                            ..ScannedRegistry::new("HKEY_CURRENT_USER/Key1")
+                        },
+                    },
+                    ..Default::default()
+                },
+                &BackupInfo {
+                    registry_values: RegistryValueCounts {
+                        backed_up: 3,
+                        skipped: 2,
+                    },
+                    ..Default::default()
+                },
+                &OperationStepDecision::Processed,
+                &[],
+                &DuplicateDetector::default(),
+            );
+            assert_eq!(
+                r#"
+foo [0 B]:
+  - HKEY_CURRENT_USER/Key1
+  Registry values: 3 backed up, 2 skipped
+
+Overall:
+  Games: 1
+  Size: 0 B
+  Location: <drive>/dev/null
+                "#
+                .trim()
+                .replace("<drive>", &drive()),
+                reporter.render(&StrictPath::new(s("/dev/null")))
+            );
+        }
+
+        #[test]
+        fn can_render_in_standard_mode_with_multiple_games_in_backup_mode() {
+            let mut reporter = Reporter::standard(Translator::default());
+
+            reporter.add_game(
+                "foo",
                 &ScanInfo {
                     game_name: s("foo"),
                     found_files: hashset! {
                         ScannedFile {
                             path: StrictPath::new(s("/file1")),
-                            size: 102_400,
+                            size: 1,
                             original_path: None,
                             ignored: false,
+                            system: false,
+                            partial: false,
+                            xbox_name: None,
                         },
+                    },
+                    found_registry_keys: hashset! {},
+                    registry_file: None,
+                    cloud_only: false,
+                },
+                &BackupInfo {
+                    failed_files: hashset! {},
+                    failed_registry: hashset! {},
+                    ..Default::default()
+                },
+                &OperationStepDecision::Processed,
+                &[],
+                &DuplicateDetector::default(),
+            );
+            reporter.add_game(
+                "bar",
+                &ScanInfo {
+                    game_name: s("bar"),
+                    found_files: hashset! {
                         ScannedFile {
                             path: StrictPath::new(s("/file2")),
-                            size: 51_200,
+                            size: 3,
                             original_path: None,
                             ignored: false,
+                            system: false,
+                            partial: false,
+                            xbox_name: None,
                         },
                     },
-                    found_registry_keys: hashset! {
-                        ScannedRegistry::new("HKEY_CURRENT_USER/Key1"),
-                        ScannedRegistry::new("HKEY_CURRENT_USER/Key2"),
-                    },
+                    found_registry_keys: hashset! {},
                     registry_file: None,
+                    cloud_only: false,
                 },
                 &BackupInfo {
-                    failed_files: hashset! {
-                        ScannedFile::new("/file2", 51_200),
+                    failed_files: hashset! {},
+                    failed_registry: hashset! {},
+                    ..Default::default()
+                },
+                &OperationStepDecision::Processed,
+                &[],
+                &DuplicateDetector::default(),
+            );
+            assert_eq!(
+                r#"
+foo [1 B]:
+  - <drive>/file1
+
+bar [3 B]:
+  - <drive>/file2
+
+Overall:
+  Games: 2
+  Size: 4 B
+  Location: <drive>/dev/null
+                "#
+                .trim()
+                .replace("<drive>", &drive()),
+                reporter.render(&StrictPath::new(s("/dev/null")))
+            );
+        }
+
+        #[test]
+        fn can_render_in_standard_mode_with_one_game_in_restore_mode() {
+            let mut reporter = Reporter::standard(Translator::default());
+
+            reporter.add_game(
+                "foo",
+                &ScanInfo {
+                    game_name: s("foo"),
+                    found_files: hashset! {
+                        ScannedFile {
+                            path: StrictPath::new(format!("{}/backup/file1", drive())),
+                            size: 102_400,
+                            original_path: Some(StrictPath::new(format!("{}/original/file1", drive()))),
+                            ignored: false,
+                            system: false,
+                            partial: false,
+                            xbox_name: None,
+                        },
+                        ScannedFile {
+                            path: StrictPath::new(format!("{}/backup/file2", drive())),
+                            size: 51_200,
+                            original_path: Some(StrictPath::new(format!("{}/original/file2", drive()))),
+                            ignored: false,
+                            system: false,
+                            partial: false,
+                            xbox_name: None,
+                        },
                     },
-                    failed_registry: hashset! {
-                        RegistryItem::new(s("HKEY_CURRENT_USER/Key1"))
+                    found_registry_keys: hashset! {},
+                    registry_file: None,
+                    cloud_only: false,
+                },
+                &BackupInfo::default(),
+                &OperationStepDecision::Processed,
+                &[],
+                &DuplicateDetector::default(),
+            );
+            assert_eq!(
+                r#"
+foo [150.00 KiB]:
+  - <drive>/original/file1
+  - <drive>/original/file2
+
+Overall:
+  Games: 1
+  Size: 150.00 KiB
+  Location: <drive>/dev/null
+                "#
+                .trim()
+                .replace("<drive>", &drive()),
+                reporter.render(&StrictPath::new(s("/dev/null")))
+            );
+        }
+
+        #[test]
+        fn can_render_in_standard_mode_with_skipped_identical_files_in_restore_mode() {
+            let mut reporter = Reporter::standard(Translator::default());
+
+            let file1 = ScannedFile {
+                path: StrictPath::new(format!("{}/backup/file1", drive())),
+                size: 102_400,
+                original_path: Some(StrictPath::new(format!("{}/original/file1", drive()))),
+                ignored: false,
+                system: false,
+                partial: false,
+                xbox_name: None,
+            };
+            let file2 = ScannedFile {
+                path: StrictPath::new(format!("{}/backup/file2", drive())),
+                size: 51_200,
+                original_path: Some(StrictPath::new(format!("{}/original/file2", drive()))),
+                ignored: false,
+                system: false,
+                partial: false,
+                xbox_name: None,
+            };
+            reporter.add_game(
+                "foo",
+                &ScanInfo {
+                    game_name: s("foo"),
+                    found_files: hashset! { file1.clone(), file2.clone() },
+                    found_registry_keys: hashset! {},
+                    registry_file: None,
+                    cloud_only: false,
+                },
+                &BackupInfo {
+                    restore_actions: hashmap! {
+                        file1 => RestoreAction::Skip,
+                        file2 => RestoreAction::Overwrite,
                     },
+                    ..Default::default()
+                },
+                &OperationStepDecision::Processed,
+                &[],
+                &DuplicateDetector::default(),
+            );
+            assert_eq!(
+                r#"
+foo [150.00 KiB]:
+  - [IDENTICAL] <drive>/original/file1
+  - <drive>/original/file2
+
+Overall:
+  Games: 1
+  Size: 150.00 KiB
+  Location: <drive>/dev/null
+  Skipped as IDENTICAL: 1
+                "#
+                .trim()
+                .replace("<drive>", &drive()),
+                reporter.render(&StrictPath::new(s("/dev/null")))
+            );
+        }
+
+        #[test]
+        fn can_collect_and_render_warnings_in_standard_mode() {
+            let mut reporter = Reporter::standard(Translator::default());
+
+            let file1 = ScannedFile::new(format!("{}/file1", drive()), 1);
+            let file2 = ScannedFile::new(format!("{}/file2", drive()), 2);
+            let file3 = ScannedFile::new(format!("{}/file3", drive()), 3);
+            reporter.add_game(
+                "foo",
+                &ScanInfo {
+                    game_name: s("foo"),
+                    found_files: hashset! { file1.clone(), file2.clone(), file3 },
+                    ..Default::default()
+                },
+                &BackupInfo {
+                    failed_files: hashset! { file1.clone(), file2.clone() },
+                    denied_files: hashset! { file1, file2 },
+                    tolerate_denied: true,
+                    ..Default::default()
+                },
+                &OperationStepDecision::Processed,
+                &[],
+                &DuplicateDetector::default(),
+            );
+            reporter.add_game(
+                "bar",
+                &ScanInfo {
+                    game_name: s("bar"),
+                    found_files: hashset! { ScannedFile::new(format!("{}/file4", drive()), 4) },
+                    ..Default::default()
+                },
+                &BackupInfo {
+                    warnings: vec![Warning::DeferralLimitReached { game: s("bar") }],
+                    ..Default::default()
                 },
                 &OperationStepDecision::Processed,
                 &[],
                 &DuplicateDetector::default(),
             );
-            assert_eq!(
-                r#"
-foo [100.00 KiB]:
-  - <drive>/file1
-  - [FAILED] <drive>/file2
-  - [FAILED] HKEY_CURRENT_USER/Key1
-  - HKEY_CURRENT_USER/Key2
 
-Overall:
-  Games: 1 of 1
-  Size: 100.00 KiB of 150.00 KiB
-  Location: <drive>/dev/null
+            let rendered = reporter.render(&StrictPath::new(s("/dev/null")));
+            assert!(rendered.ends_with(
+                &r#"
+Warnings:
+  - foo: a file was skipped because access was denied (x2)
+  - bar: backed up even though its saves changed recently, since it was deferred too many times in a row
                 "#
                 .trim()
-                .replace("<drive>", &drive()),
-                reporter.render(&StrictPath::new(s("/dev/null")))
+            ));
+        }
+
+        #[test]
+        fn can_break_down_skipped_games_by_reason_in_standard_mode() {
+            let mut reporter = Reporter::standard(Translator::default());
+            for (name, decision) in [
+                ("foo", OperationStepDecision::Processed),
+                ("bar", OperationStepDecision::Ignored),
+                ("baz", OperationStepDecision::Deferred),
+                ("qux", OperationStepDecision::Ignored),
+            ] {
+                reporter.add_game(
+                    name,
+                    &ScanInfo {
+                        game_name: s(name),
+                        found_files: hashset! { ScannedFile::new(format!("{}/{}", drive(), name), 1) },
+                        ..Default::default()
+                    },
+                    &BackupInfo::default(),
+                    &decision,
+                    &[],
+                    &DuplicateDetector::default(),
+                );
+            }
+
+            let rendered = reporter.render(&StrictPath::new(s("/dev/null")));
+            assert!(rendered.ends_with("\n  Skipped: 2 ignored, 1 deferred until their saves stop changing"));
+            assert_eq!(
+                Some(s("Skipped: 1 cancelled")),
+                Translator::default().skip_summary(&btreemap! {
+                    SkipReason::Ignored => 0,
+                    SkipReason::Cancelled => 1,
+                }),
+            );
+            assert_eq!(
+                None,
+                Translator::default().skip_summary(&btreemap! { SkipReason::Deferred => 0 })
             );
         }
 
         #[test]
-        fn can_render_in_standard_mode_with_multiple_games_in_backup_mode() {
+        fn can_render_in_standard_mode_with_only_failed_games() {
             let mut reporter = Reporter::standard(Translator::default());
+            reporter.trip_failed_only();
 
             reporter.add_game(
                 "foo",
                 &ScanInfo {
                     game_name: s("foo"),
                     found_files: hashset! {
-                        ScannedFile {
-                            path: StrictPath::new(s("/file1")),
-                            size: 1,
-                            original_path: None,
-                            ignored: false,
-                        },
+                        ScannedFile::new("/file1", 102_400),
                     },
                     found_registry_keys: hashset! {},
                     registry_file: None,
+                    cloud_only: false,
                 },
-                &BackupInfo {
-                    failed_files: hashset! {},
-                    failed_registry: hashset! {},
-                },
+                &BackupInfo::default(),
                 &OperationStepDecision::Processed,
                 &[],
                 &DuplicateDetector::default(),
@@ -1298,19 +5359,23 @@ Overall:
                 &ScanInfo {
                     game_name: s("bar"),
                     found_files: hashset! {
-                        ScannedFile {
-                            path: StrictPath::new(s("/file2")),
-                            size: 3,
-                            original_path: None,
-                            ignored: false,
-                        },
+                        ScannedFile::new("/file2", 51_200),
+                        ScannedFile::new("/file3", 51_200),
                     },
                     found_registry_keys: hashset! {},
                     registry_file: None,
+                    cloud_only: false,
                 },
                 &BackupInfo {
-                    failed_files: hashset! {},
-                    failed_registry: hashset! {},
+                    failed_files: hashset! {
+                        ScannedFile::new("/file3", 51_200),
+                    },
+                    failure_reasons: hashmap! {
+                        ScannedFile::new("/file3", 51_200) => FailureReason::from(
+                            &std::io::Error::from(std::io::ErrorKind::PermissionDenied)
+                        ),
+                    },
+                    ..Default::default()
                 },
                 &OperationStepDecision::Processed,
                 &[],
@@ -1318,16 +5383,18 @@ Overall:
             );
             assert_eq!(
                 r#"
-foo [1 B]:
-  - <drive>/file1
-
-bar [3 B]:
+bar [50.00 KiB]:
   - <drive>/file2
+  - [FAILED] <drive>/file3 (permission denied)
 
 Overall:
-  Games: 2
-  Size: 4 B
+  Games: 1 of 1
+  Size: 50.00 KiB of 100.00 KiB
   Location: <drive>/dev/null
+
+Errors:
+  permission denied:
+    - bar: <drive>/file3
                 "#
                 .trim()
                 .replace("<drive>", &drive()),
@@ -1336,29 +5403,15 @@ Overall:
         }
 
         #[test]
-        fn can_render_in_standard_mode_with_one_game_in_restore_mode() {
+        fn can_render_in_standard_mode_with_cloud_only_game() {
             let mut reporter = Reporter::standard(Translator::default());
 
             reporter.add_game(
                 "foo",
                 &ScanInfo {
                     game_name: s("foo"),
-                    found_files: hashset! {
-                        ScannedFile {
-                            path: StrictPath::new(format!("{}/backup/file1", drive())),
-                            size: 102_400,
-                            original_path: Some(StrictPath::new(format!("{}/original/file1", drive()))),
-                            ignored: false,
-                        },
-                        ScannedFile {
-                            path: StrictPath::new(format!("{}/backup/file2", drive())),
-                            size: 51_200,
-                            original_path: Some(StrictPath::new(format!("{}/original/file2", drive()))),
-                            ignored: false,
-                        },
-                    },
-                    found_registry_keys: hashset! {},
-                    registry_file: None,
+                    cloud_only: true,
+                    ..Default::default()
                 },
                 &BackupInfo::default(),
                 &OperationStepDecision::Processed,
@@ -1367,13 +5420,12 @@ Overall:
             );
             assert_eq!(
                 r#"
-foo [150.00 KiB]:
-  - <drive>/original/file1
-  - <drive>/original/file2
+foo [CLOUD ONLY]:
+  Nothing was found on disk. This game only keeps its saves in the cloud.
 
 Overall:
-  Games: 1
-  Size: 150.00 KiB
+  Games: 0
+  Size: 0 B
   Location: <drive>/dev/null
                 "#
                 .trim()
@@ -1397,6 +5449,7 @@ Overall:
                         ScannedRegistry::new("HKEY_CURRENT_USER/Key1"),
                     },
                     registry_file: None,
+                    cloud_only: false,
                 });
             }
 
@@ -1411,6 +5464,7 @@ Overall:
                         ScannedRegistry::new("HKEY_CURRENT_USER/Key1"),
                     },
                     registry_file: None,
+                    cloud_only: false,
                 },
                 &BackupInfo::default(),
                 &OperationStepDecision::Processed,
@@ -1434,6 +5488,31 @@ Overall:
             );
         }
 
+        #[test]
+        fn can_render_badges_in_custom_order() {
+            let translator = Translator::default();
+            let order = [BadgeKind::Duplicated, BadgeKind::Ignored];
+            let badges = LineItemBadges {
+                failed: true,
+                ignored: true,
+                duplicated: true,
+                ..Default::default()
+            };
+
+            assert_eq!(
+                "foo [0 B] [DUPLICATES] [IGNORED]:",
+                translator.cli_game_header("foo", 0, &OperationStepDecision::Ignored, true, &order),
+            );
+            assert_eq!(
+                "  - [DUPLICATED] [IGNORED] [FAILED] /file1",
+                translator.cli_game_line_item("/file1", &badges, &order),
+            );
+            assert_eq!(
+                "  - [FAILED] [IGNORED] [DUPLICATED] /file1",
+                translator.cli_game_line_item("/file1", &badges, &[]),
+            );
+        }
+
         #[test]
         fn can_render_in_json_mode_with_minimal_input() {
             let mut reporter = Reporter::json();
@@ -1463,6 +5542,260 @@ Overall:
             );
         }
 
+        #[test]
+        fn can_render_verify_sample_in_json_mode() {
+            let mut reporter = Reporter::json();
+            reporter.add_verify_sample(SampleResult {
+                total: 10,
+                checked: 1,
+                mismatched: 0,
+                seed: 12345678901234567890,
+                files: btreemap! {
+                    s("foo") => vec![StrictPath::new(s("/restored/file1.txt"))],
+                },
+            });
+            assert_eq!(
+                r#"
+{
+  "overall": {
+    "totalGames": 0,
+    "totalBytes": 0,
+    "processedGames": 0,
+    "processedBytes": 0
+  },
+  "games": {},
+  "verifySample": {
+    "seed": "12345678901234567890",
+    "total": 10,
+    "checked": 1,
+    "mismatched": 0,
+    "extrapolatedPercent": 95.0,
+    "games": {
+      "foo": [
+        "/restored/file1.txt"
+      ]
+    }
+  }
+}
+                "#
+                .trim(),
+                reporter.render(&StrictPath::new(s("/dev/null")))
+            );
+        }
+
+        #[test]
+        fn can_render_in_json_mode_with_selected_games() {
+            let mut reporter = Reporter::json();
+            reporter.set_selection(250);
+
+            reporter.add_game(
+                "foo",
+                &ScanInfo {
+                    game_name: s("foo"),
+                    found_files: hashset! {
+                        ScannedFile::new("/file1", 100),
+                    },
+                    ..Default::default()
+                },
+                &BackupInfo::default(),
+                &OperationStepDecision::Processed,
+                &[],
+                &DuplicateDetector::default(),
+            );
+            assert_eq!(
+                r#"
+{
+  "overall": {
+    "totalGames": 1,
+    "totalBytes": 100,
+    "processedGames": 1,
+    "processedBytes": 100,
+    "availableGames": 250
+  },
+  "games": {
+    "foo": {
+      "decision": "Processed",
+      "files": {
+        "<drive>/file1": {
+          "bytes": 100
+        }
+      },
+      "registry": {}
+    }
+  }
+}
+                "#
+                .trim()
+                .replace("<drive>", &drive()),
+                reporter.render(&StrictPath::new(s("/dev/null")))
+            );
+        }
+
+        #[test]
+        fn can_render_in_json_lines_mode() {
+            let mut reporter = Reporter::json_lines();
+
+            for name in ["foo", "bar"] {
+                reporter.add_game(
+                    name,
+                    &ScanInfo {
+                        game_name: s(name),
+                        found_files: hashset! {
+                            ScannedFile::new("/file1", 1),
+                        },
+                        ..Default::default()
+                    },
+                    &BackupInfo::default(),
+                    &OperationStepDecision::Processed,
+                    &[],
+                    &DuplicateDetector::default(),
+                );
+            }
+
+            let lines: Vec<serde_json::Value> = reporter
+                .render(&StrictPath::new(s("/dev/null")))
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect();
+            assert_eq!(3, lines.len());
+            assert_eq!("game", lines[0]["type"]);
+            assert_eq!("foo", lines[0]["name"]);
+            assert_eq!("Processed", lines[0]["decision"]);
+            assert_eq!(1, lines[0]["files"][format!("{}/file1", drive())]["bytes"]);
+            assert_eq!("game", lines[1]["type"]);
+            assert_eq!("bar", lines[1]["name"]);
+            assert_eq!("summary", lines[2]["type"]);
+            assert_eq!(2, lines[2]["overall"]["processedGames"]);
+            assert!(lines[2].get("games").is_none());
+
+            // Printed games aren't rendered again.
+            reporter.flush(&CliOutputFile::default());
+            assert_eq!(
+                r#"{"type":"summary","overall":{"totalGames":2,"totalBytes":2,"processedGames":2,"processedBytes":2}}"#,
+                reporter.render(&StrictPath::new(s("/dev/null")))
+            );
+        }
+
+        #[test]
+        fn can_write_summary_to_output_file() {
+            let file = TempPath::new("ludusavi-test-output-summary.txt");
+
+            let mut reporter = Reporter::standard(Translator::default());
+            reporter.add_game(
+                "foo",
+                &ScanInfo {
+                    game_name: s("foo"),
+                    found_files: hashset! {
+                        ScannedFile::new("/file1", 102_400),
+                    },
+                    ..Default::default()
+                },
+                &BackupInfo::default(),
+                &OperationStepDecision::Processed,
+                &[],
+                &DuplicateDetector::default(),
+            );
+
+            let mut options = CliOutputFile {
+                output_file: Some(file.clone()),
+                output_only: true,
+                ..Default::default()
+            };
+            reporter.print(&StrictPath::new(s("/dev/null")), &options);
+            options.output_append = true;
+            reporter.print(&StrictPath::new(s("/dev/null")), &options);
+
+            let content = std::fs::read_to_string(file.interpret()).unwrap();
+            let summary = r#"
+Overall:
+  Games: 1
+  Size: 100.00 KiB
+  Location: <drive>/dev/null
+            "#
+            .trim()
+            .replace("<drive>", &drive());
+            assert_eq!(format!("{}\n{}\n", summary, summary), content);
+        }
+
+        #[test]
+        fn can_write_streamed_json_lines_to_output_file() {
+            let file = TempPath::new("ludusavi-test-output-full.jsonl");
+            let options = CliOutputFile {
+                output_file: Some(file.clone()),
+                output_format: Some(CliOutputFormat::Full),
+                output_only: true,
+                ..Default::default()
+            };
+
+            let mut reporter = Reporter::json_lines();
+            for name in ["foo", "bar"] {
+                reporter.add_game(
+                    name,
+                    &ScanInfo {
+                        game_name: s(name),
+                        found_files: hashset! {
+                            ScannedFile::new("/file1", 1),
+                        },
+                        ..Default::default()
+                    },
+                    &BackupInfo::default(),
+                    &OperationStepDecision::Processed,
+                    &[],
+                    &DuplicateDetector::default(),
+                );
+                reporter.flush(&options);
+            }
+            reporter.print(&StrictPath::new(s("/dev/null")), &options);
+
+            let content = std::fs::read_to_string(file.interpret()).unwrap();
+            let lines: Vec<serde_json::Value> = content
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect();
+            assert_eq!(3, lines.len());
+            assert_eq!("foo", lines[0]["name"]);
+            assert_eq!("bar", lines[1]["name"]);
+            assert_eq!("summary", lines[2]["type"]);
+        }
+
+        #[test]
+        fn can_anonymize_report_in_output_file() {
+            let file = TempPath::new("ludusavi-test-output-anonymized.json");
+            let options = CliOutputFile {
+                output_file: Some(file.clone()),
+                output_format: Some(CliOutputFormat::Full),
+                output_only: true,
+                ..Default::default()
+            };
+
+            let mut reporter = Reporter::json();
+            reporter.set_anonymizer(Anonymizer::new(Some(s("/home/alice")), Some(s("alice"))));
+            reporter.add_game(
+                "foo",
+                &ScanInfo {
+                    game_name: s("foo"),
+                    found_files: hashset! {
+                        ScannedFile::new("/home/alice/.local/share/foo/save.dat", 1),
+                    },
+                    found_registry_keys: hashset! {
+                        ScannedRegistry::new("HKEY_USERS/S-1-5-21-1004336348-1177238915-682003330-1001/Software/foo"),
+                    },
+                    ..Default::default()
+                },
+                &BackupInfo::default(),
+                &OperationStepDecision::Processed,
+                &[],
+                &DuplicateDetector::default(),
+            );
+            reporter.print(&StrictPath::new(s("/mnt/backups/alice")), &options);
+
+            let content = std::fs::read_to_string(file.interpret()).unwrap();
+            assert!(!content.contains("alice"));
+            assert!(!content.contains("1004336348"));
+            assert!(content.contains("<HOME>/.local/share/foo/save.dat"));
+            assert!(content.contains("HKEY_USERS/<SID>/Software/foo"));
+        }
+
         #[test]
         fn can_render_in_json_mode_with_one_game_in_backup_mode() {
             let mut reporter = Reporter::json();
@@ -1480,6 +5813,7 @@ Overall:
                         ScannedRegistry::new("HKEY_CURRENT_USER/Key2")
                     },
                     registry_file: None,
+                    cloud_only: false,
                 },
                 &BackupInfo {
                     failed_files: hashset! {
@@ -1488,6 +5822,7 @@ Overall:
                     failed_registry: hashset! {
                         RegistryItem::new(s("HKEY_CURRENT_USER/Key1"))
                     },
+                    ..Default::default()
                 },
                 &OperationStepDecision::Processed,
                 &[],
@@ -1497,7 +5832,25 @@ Overall:
                 r#"
 {
   "errors": {
-    "someGamesFailed": true
+    "someGamesFailed": true,
+    "failures": [
+      {
+        "count": 2,
+        "games": [
+          "foo"
+        ],
+        "entries": [
+          {
+            "game": "foo",
+            "path": "<drive>/file2"
+          },
+          {
+            "game": "foo",
+            "path": "HKEY_CURRENT_USER/Key1"
+          }
+        ]
+      }
+    ]
   },
   "overall": {
     "totalGames": 1,
@@ -1547,16 +5900,23 @@ Overall:
                             size: 100,
                             original_path: Some(StrictPath::new(format!("{}/original/file1", drive()))),
                             ignored: false,
+                            system: false,
+                            partial: false,
+                            xbox_name: None,
                         },
                         ScannedFile {
                             path: StrictPath::new(format!("{}/backup/file2", drive())),
                             size: 50,
                             original_path: Some(StrictPath::new(format!("{}/original/file2", drive()))),
                             ignored: false,
+                            system: false,
+                            partial: false,
+                            xbox_name: None,
                         },
                     },
                     found_registry_keys: hashset! {},
                     registry_file: None,
+                    cloud_only: false,
                 },
                 &BackupInfo::default(),
                 &OperationStepDecision::Processed,
@@ -1609,6 +5969,7 @@ Overall:
                         ScannedRegistry::new("HKEY_CURRENT_USER/Key1"),
                     },
                     registry_file: None,
+                    cloud_only: false,
                 });
             }
 
@@ -1623,6 +5984,7 @@ Overall:
                         ScannedRegistry::new("HKEY_CURRENT_USER/Key1"),
                     },
                     registry_file: None,
+                    cloud_only: false,
                 },
                 &BackupInfo::default(),
                 &OperationStepDecision::Processed,
@@ -1665,5 +6027,29 @@ Overall:
                 reporter.render(&StrictPath::new(s("/dev/null")))
             );
         }
+
+        #[test]
+        fn can_wrap_long_lines_at_path_separators() {
+            assert_eq!(
+                "  - [FAILED] /games/\n      some/deeply/\n      nested/file.txt",
+                wrap_line("  - [FAILED] /games/some/deeply/nested/file.txt", 24),
+            );
+        }
+
+        #[test]
+        fn keeps_badges_attached_when_wrapping() {
+            assert_eq!(
+                "  - [FAILED] [DUPLICATED] /\n      a",
+                wrap_line("  - [FAILED] [DUPLICATED] /a", 10),
+            );
+        }
+
+        #[test]
+        fn does_not_wrap_when_width_is_zero() {
+            assert_eq!(
+                "  - [FAILED] /games/some/deeply/nested/file.txt",
+                wrap_line("  - [FAILED] /games/some/deeply/nested/file.txt", 0),
+            );
+        }
     }
 }
@@ -1,11 +1,67 @@
 use crate::{
-    manifest::Store,
-    prelude::{app_dir, Error, RegistryItem, StrictPath},
+    manifest::{Manifest, Store, Tag},
+    path::render_pathbuf,
+    prelude::{app_dir, portable_dir, Error, FileAttributes, RegistryItem, StrictPath},
+    registry_compat::{is_reg_file, load_reg_file, RegistryFilter},
 };
 
 const MANIFEST_URL: &str = "https://raw.githubusercontent.com/mtkennerly/ludusavi-manifest/master/data/manifest.yaml";
 
+/// A setting that can be overridden with an environment variable,
+/// e.g. for containers where the config file is read-only.
+struct EnvOverride {
+    name: &'static str,
+    apply: fn(&mut Config, &str) -> Result<(), String>,
+    /// Put back the value from the config file, so that the override isn't saved.
+    reset: fn(&mut Config, &Config),
+}
+
+const ENV_OVERRIDES: &[EnvOverride] = &[
+    EnvOverride {
+        name: "LUDUSAVI_BACKUP_TARGET",
+        apply: |config, value| {
+            config.backup.path = StrictPath::new(value.to_string());
+            Ok(())
+        },
+        reset: |config, stored| config.backup.path = stored.backup.path.clone(),
+    },
+    EnvOverride {
+        name: "LUDUSAVI_RESTORE_SOURCE",
+        apply: |config, value| {
+            config.restore.path = StrictPath::new(value.to_string());
+            Ok(())
+        },
+        reset: |config, stored| config.restore.path = stored.restore.path.clone(),
+    },
+    EnvOverride {
+        name: "LUDUSAVI_MANIFEST_URL",
+        apply: |config, value| {
+            if !value.starts_with("http://") && !value.starts_with("https://") {
+                return Err(format!("Not an HTTP(S) URL: {}", value));
+            }
+            config.manifest.url = value.to_string();
+            Ok(())
+        },
+        reset: |config, stored| config.manifest.url = stored.manifest.url.clone(),
+    },
+    EnvOverride {
+        name: "LUDUSAVI_COPY_THREADS",
+        apply: |config, value| match value.parse::<usize>() {
+            Ok(threads) if threads > 0 => {
+                config.runtime.copy_threads = threads;
+                Ok(())
+            }
+            _ => Err(format!("Expected a whole number greater than 0, but got: {}", value)),
+        },
+        reset: |config, stored| config.runtime.copy_threads = stored.runtime.copy_threads,
+    },
+];
+
 fn default_backup_dir() -> StrictPath {
+    if let Some(portable) = portable_dir() {
+        return StrictPath::relative("ludusavi-backup".to_string(), Some(render_pathbuf(&portable)));
+    }
+
     let mut path = dirs::home_dir().unwrap();
     path.push("ludusavi-backup");
     StrictPath::from_std_path_buf(&path)
@@ -19,18 +75,83 @@ pub struct Config {
     pub restore: RestoreConfig,
     #[serde(default, rename = "customGames")]
     pub custom_games: Vec<CustomGame>,
+    #[serde(default)]
+    pub runtime: RuntimeConfig,
+    #[serde(default, rename = "badgeOrder", skip_serializing_if = "Vec::is_empty")]
+    pub badge_order: Vec<BadgeKind>,
+    /// Named sets of overrides that can be selected for a single operation.
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub profiles: std::collections::BTreeMap<String, ProfileConfig>,
+    #[serde(default, skip_serializing_if = "SignatureConfig::is_default")]
+    pub signature: SignatureConfig,
 }
 
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct ManifestConfig {
     pub url: String,
     pub etag: Option<String>,
+    /// Where to request only the changes since the cached manifest.
+    #[serde(default, rename = "deltaUrl", skip_serializing_if = "Option::is_none")]
+    pub delta_url: Option<String>,
+    /// Use this local file instead of downloading the manifest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<StrictPath>,
+    /// Check for updates when the cached manifest is older than this.
+    #[serde(default, rename = "refreshInterval", skip_serializing_if = "Option::is_none")]
+    pub refresh_interval: Option<Interval>,
+    /// Local manifest files to merge into the main one.
+    /// When several define the same game, the main manifest wins, then these in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub secondary: Vec<StrictPath>,
+}
+
+/// A span of time, written like `90s`, `30m`, `24h`, or `7d`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Interval(pub std::time::Duration);
+
+impl Interval {
+    const UNITS: &'static [(char, u64)] = &[('d', 86_400), ('h', 3_600), ('m', 60), ('s', 1)];
+}
+
+impl TryFrom<String> for Interval {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let invalid = || format!("invalid interval: {}", value);
+        let trimmed = value.trim();
+        let unit = trimmed.chars().last().ok_or_else(invalid)?;
+        let seconds = Self::UNITS
+            .iter()
+            .find(|(x, _)| *x == unit)
+            .map(|(_, seconds)| *seconds)
+            .ok_or_else(invalid)?;
+        let count: u64 = trimmed[..trimmed.len() - 1].parse().map_err(|_| invalid())?;
+        let total = count.checked_mul(seconds).ok_or_else(invalid)?;
+        Ok(Self(std::time::Duration::from_secs(total)))
+    }
+}
+
+impl From<Interval> for String {
+    fn from(value: Interval) -> Self {
+        let total = value.0.as_secs();
+        // Use the biggest unit that can represent the interval exactly.
+        for (unit, seconds) in Interval::UNITS {
+            let (count, rest) = (total / seconds, total % seconds);
+            if count > 0 && rest == 0 {
+                return format!("{}{}", count, unit);
+            }
+        }
+        "0s".to_string()
+    }
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, Hash, serde::Serialize, serde::Deserialize)]
 pub struct RootsConfig {
     pub path: StrictPath,
     pub store: Store,
+    #[serde(default, skip_serializing_if = "RootRequirement::is_empty")]
+    pub require: RootRequirement,
 }
 
 impl RootsConfig {
@@ -42,9 +163,225 @@ impl RootsConfig {
             .map(|path| RootsConfig {
                 path,
                 store: self.store,
+                require: self.require.clone(),
             })
             .collect()
     }
+
+    /// Whether the root meets its requirements, so that finding nothing there
+    /// really means that there's nothing there.
+    pub fn is_available(&self) -> bool {
+        let path = self.path.as_std_path_buf();
+        if let Some(marker) = &self.require.marker {
+            if !path.join(marker).exists() {
+                return false;
+            }
+        }
+        if self.require.mount_point && !is_mount_point(&path) {
+            return false;
+        }
+        true
+    }
+}
+
+/// How to tell that a root is really there, and not just an empty folder
+/// where a network or removable drive would be mounted.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct RootRequirement {
+    /// A file or folder, relative to the root, that must exist.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub marker: Option<String>,
+    /// The root must be where a file system is mounted.
+    #[serde(
+        default,
+        rename = "mountPoint",
+        skip_serializing_if = "crate::serialization::is_false"
+    )]
+    pub mount_point: bool,
+}
+
+impl RootRequirement {
+    pub fn is_empty(&self) -> bool {
+        self.marker.is_none() && !self.mount_point
+    }
+}
+
+/// Whether the folder is on a different file system than its parent.
+#[cfg(unix)]
+fn is_mount_point(path: &std::path::Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    let path = match std::fs::canonicalize(path) {
+        Ok(x) => x,
+        Err(_) => return false,
+    };
+    let device = match std::fs::metadata(&path) {
+        Ok(x) => x.dev(),
+        Err(_) => return false,
+    };
+    match path.parent() {
+        None => true,
+        Some(parent) => std::fs::metadata(parent).map(|x| x.dev() != device).unwrap_or(false),
+    }
+}
+
+/// Other drives get their own letters, so only the top of a drive counts.
+#[cfg(not(unix))]
+fn is_mount_point(path: &std::path::Path) -> bool {
+    match std::fs::canonicalize(path) {
+        Ok(path) => path.parent().is_none(),
+        Err(_) => false,
+    }
+}
+
+/// How sure root detection is that a folder really is a root for its store.
+/// These are ordered from least to most certain.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RootConfidence {
+    /// The folder exists at one of the store's default locations.
+    Guess,
+    /// The folder was inferred from where the store's games are installed.
+    #[allow(dead_code)]
+    Likely,
+    /// The store itself reports the folder as a library.
+    Certain,
+}
+
+/// A folder that root detection proposes adding as a root.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize)]
+pub struct RootCandidate {
+    #[serde(flatten)]
+    pub root: RootsConfig,
+    pub confidence: RootConfidence,
+}
+
+/// Combine the folders proposed by each detector, skipping ones that are already roots
+/// or that don't exist. When several detectors find the same folder, it takes the store
+/// and confidence from the most confident one, or from the first one if they're tied.
+fn merge_root_candidates(
+    existing: &[RootsConfig],
+    detected: Vec<(String, Store, RootConfidence)>,
+) -> Vec<RootCandidate> {
+    let mut checked = std::collections::HashMap::<StrictPath, Option<usize>>::new();
+    let mut candidates: Vec<RootCandidate> = vec![];
+    for (path, store, confidence) in detected {
+        let sp = StrictPath::new(path);
+        if existing.iter().any(|root| root.path.interpret() == sp.interpret()) {
+            continue;
+        }
+        if let Some(seen) = checked.get(&sp.interpreted()) {
+            if let Some(candidate) = seen.and_then(|i| candidates.get_mut(i)) {
+                if confidence > candidate.confidence {
+                    candidate.root.store = store;
+                    candidate.confidence = confidence;
+                }
+            }
+            continue;
+        }
+        if sp.is_dir() {
+            checked.insert(sp.interpreted(), Some(candidates.len()));
+            candidates.push(RootCandidate {
+                root: RootsConfig {
+                    path: sp.rendered(),
+                    store,
+                    require: Default::default(),
+                },
+                confidence,
+            });
+        } else {
+            checked.insert(sp.interpreted(), None);
+        }
+    }
+    candidates
+}
+
+/// One way that a config differs from the default config.
+/// Paths are the dotted names of fields, as written in the config file.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ConfigDiff {
+    Changed {
+        path: String,
+        default: serde_json::Value,
+        value: serde_json::Value,
+    },
+    /// A list entry that isn't in the default list.
+    Added { path: String, value: serde_json::Value },
+    /// An entry from the default list that has been removed.
+    Removed { path: String, value: serde_json::Value },
+}
+
+/// Unset fields, empty lists, and empty maps are all left out of the config file the same way.
+fn is_blank(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Null => true,
+        serde_json::Value::Array(x) => x.is_empty(),
+        serde_json::Value::Object(x) => x.is_empty(),
+        _ => false,
+    }
+}
+
+fn diff_values(path: &str, default: &serde_json::Value, value: &serde_json::Value, diffs: &mut Vec<ConfigDiff>) {
+    use serde_json::Value;
+
+    if default == value || (is_blank(default) && is_blank(value)) {
+        return;
+    }
+    match (default, value) {
+        (Value::Object(default), Value::Object(value)) => {
+            let keys: std::collections::BTreeSet<_> = default.keys().chain(value.keys()).collect();
+            for key in keys {
+                let path = if path.is_empty() {
+                    key.to_string()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                diff_values(
+                    &path,
+                    default.get(key).unwrap_or(&Value::Null),
+                    value.get(key).unwrap_or(&Value::Null),
+                    diffs,
+                );
+            }
+        }
+        (Value::Array(default), Value::Array(value)) => {
+            for entry in value.iter().filter(|x| !default.contains(x)) {
+                diffs.push(ConfigDiff::Added {
+                    path: path.to_string(),
+                    value: entry.clone(),
+                });
+            }
+            for entry in default.iter().filter(|x| !value.contains(x)) {
+                diffs.push(ConfigDiff::Removed {
+                    path: path.to_string(),
+                    value: entry.clone(),
+                });
+            }
+        }
+        (Value::Null, Value::Array(value)) => {
+            diff_values(path, &Value::Array(vec![]), &Value::Array(value.clone()), diffs)
+        }
+        (Value::Array(default), Value::Null) => {
+            diff_values(path, &Value::Array(default.clone()), &Value::Array(vec![]), diffs)
+        }
+        _ => diffs.push(ConfigDiff::Changed {
+            path: path.to_string(),
+            default: default.clone(),
+            value: value.clone(),
+        }),
+    }
+}
+
+/// How one root relates to another that covers some of the same folders.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RootOverlap {
+    /// Both roots are the same folder.
+    Duplicate,
+    /// This root is inside the other one.
+    Inside,
+    /// The other root is inside this one.
+    Contains,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -67,13 +404,60 @@ pub struct BackupFilter {
         rename = "excludeStoreScreenshots"
     )]
     pub exclude_store_screenshots: bool,
+    /// Skip hidden files, and on Windows, system files.
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serialization::is_false",
+        rename = "excludeHiddenFiles"
+    )]
+    pub exclude_hidden_files: bool,
+    #[serde(default, rename = "steamUser", skip_serializing_if = "SteamUser::is_all")]
+    pub steam_user: SteamUser,
     #[serde(default, rename = "ignoredPaths")]
     pub ignored_paths: Vec<StrictPath>,
     #[serde(default, rename = "ignoredRegistry")]
     pub ignored_registry: Vec<RegistryItem>,
+    /// Roots to skip when scanning specific games, keyed by game name.
+    #[serde(
+        default,
+        rename = "excludedRoots",
+        skip_serializing_if = "std::collections::BTreeMap::is_empty"
+    )]
+    pub excluded_roots: std::collections::BTreeMap<String, Vec<StrictPath>>,
+    /// Categories of manifest paths to skip, like `config`.
+    #[serde(default, rename = "excludedTags", skip_serializing_if = "Vec::is_empty")]
+    pub excluded_tags: Vec<Tag>,
 }
 
 impl BackupFilter {
+    /// Whether a manifest entry should be skipped because all of its tags are excluded.
+    /// Untagged entries are always kept.
+    pub fn is_tag_excluded(&self, tags: &Option<Vec<Tag>>) -> bool {
+        match tags {
+            Some(tags) if !tags.is_empty() => tags.iter().all(|x| self.excluded_tags.contains(x)),
+            _ => false,
+        }
+    }
+
+    pub fn set_tag_excluded(&mut self, tag: Tag, excluded: bool) {
+        self.excluded_tags.retain(|x| *x != tag);
+        if excluded {
+            self.excluded_tags.push(tag);
+        }
+    }
+
+    pub fn is_root_excluded(&self, game: &str, root: &RootsConfig) -> bool {
+        let interpreted = root.path.interpret();
+        self.excluded_roots
+            .get(game)
+            .map(|paths| paths.iter().any(|x| x.interpret() == interpreted))
+            .unwrap_or(false)
+    }
+
+    pub fn is_hidden_excluded(&self, attributes: &FileAttributes) -> bool {
+        self.exclude_hidden_files && (attributes.hidden || attributes.system)
+    }
+
     pub fn is_path_ignored(&self, item: &StrictPath) -> bool {
         let interpreted = item.interpret();
         self.ignored_paths
@@ -117,12 +501,248 @@ impl std::fmt::Display for SortKey {
     }
 }
 
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SearchScope {
+    #[default]
+    Name,
+    Store,
+    Path,
+    All,
+}
+
+impl SearchScope {
+    pub const ALL: &'static [Self] = &[Self::Name, Self::Store, Self::Path, Self::All];
+}
+
+impl std::fmt::Display for SearchScope {
+    // This is needed for Iced's PickList.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        // TODO: Use display adapter wrapper struct to respect the active language.
+        writeln!(f, "{}", crate::lang::Translator::default().search_scope(self))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum BadgeKind {
+    #[serde(rename = "failed")]
+    Failed,
+    #[serde(rename = "denied")]
+    Denied,
+    #[serde(rename = "ignored")]
+    Ignored,
+    #[serde(rename = "duplicated")]
+    Duplicated,
+    #[serde(rename = "mismatched")]
+    Mismatched,
+    #[serde(rename = "partial")]
+    Partial,
+    #[serde(rename = "system")]
+    System,
+    #[serde(rename = "ownership")]
+    Ownership,
+    #[serde(rename = "restoreAction")]
+    RestoreAction,
+}
+
+impl BadgeKind {
+    pub const ALL: &'static [Self] = &[
+        Self::Failed,
+        Self::Denied,
+        Self::Ignored,
+        Self::Duplicated,
+        Self::Mismatched,
+        Self::Partial,
+        Self::System,
+        Self::Ownership,
+        Self::RestoreAction,
+    ];
+
+    /// Apply a custom order, with any unlisted badges following in the default order.
+    pub fn ordered(custom: &[Self]) -> Vec<Self> {
+        let mut ordered = vec![];
+        for badge in custom.iter().chain(Self::ALL) {
+            if !ordered.contains(badge) {
+                ordered.push(*badge);
+            }
+        }
+        ordered
+    }
+}
+
+/// Whose per-user saves to back up from Steam's `userdata` folder.
+/// In the config, this is `all`, `current`, or an account ID.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum SteamUser {
+    #[default]
+    All,
+    /// Whoever most recently logged in to Steam.
+    Current,
+    /// The account ID, as used for the `userdata` folder name.
+    Id(u32),
+}
+
+impl SteamUser {
+    pub fn is_all(&self) -> bool {
+        *self == Self::All
+    }
+}
+
+impl TryFrom<String> for SteamUser {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "all" => Ok(Self::All),
+            "current" => Ok(Self::Current),
+            _ => value
+                .parse()
+                .map(Self::Id)
+                .map_err(|_| format!("invalid Steam user: {}", value)),
+        }
+    }
+}
+
+impl From<SteamUser> for String {
+    fn from(value: SteamUser) -> Self {
+        match value {
+            SteamUser::All => "all".to_string(),
+            SteamUser::Current => "current".to_string(),
+            SteamUser::Id(id) => id.to_string(),
+        }
+    }
+}
+
+/// What to do during backup when a file can't be read because of its permissions.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum PermissionDeniedPolicy {
+    /// Leave out the file, but back up the rest of the game.
+    #[default]
+    #[serde(rename = "skip")]
+    Skip,
+    /// Don't back up the game at all.
+    #[serde(rename = "fail")]
+    Fail,
+}
+
+/// What to do with NTFS alternate data streams (like `Zone.Identifier`) on backed up files.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum AlternateStreamPolicy {
+    /// Leave them out of the backup.
+    #[default]
+    #[serde(rename = "strip")]
+    Strip,
+    /// Keep them with the backed up file and restore them along with it.
+    #[serde(rename = "include")]
+    Include,
+}
+
+/// How backup decides whether a file has changed since it was last backed up.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum ComparisonStrategy {
+    /// Only compare sizes. This is the fastest, but it misses changes that keep the same size.
+    #[serde(rename = "sizeOnly")]
+    SizeOnly,
+    /// Compare sizes and modification times.
+    #[default]
+    #[serde(rename = "sizeAndMtime")]
+    SizeAndMtime,
+    /// Compare the full contents. This is the safest, but it has to read every file.
+    #[serde(rename = "hash")]
+    Hash,
+}
+
+/// What restore does with files that are in a game's save folders but not in the backup.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum RestoreMode {
+    /// Leave them alone, so restore only ever creates or overwrites files.
+    #[default]
+    #[serde(rename = "additive")]
+    Additive,
+    /// Remove them, so the save folders end up matching the backup exactly.
+    #[serde(rename = "mirror")]
+    Mirror,
+}
+
+impl RestoreMode {
+    pub const ALL_NAMES: &'static [&'static str] = &["additive", "mirror"];
+}
+
+impl std::str::FromStr for RestoreMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "additive" => Ok(Self::Additive),
+            "mirror" => Ok(Self::Mirror),
+            _ => Err(format!("invalid restore mode: {}", s)),
+        }
+    }
+}
+
+/// How game folders are arranged within a backup target.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum BackupGrouping {
+    /// Every game folder is directly inside the target.
+    #[default]
+    #[serde(rename = "flat")]
+    Flat,
+    /// Game folders are inside a subfolder for the store where their saves were found.
+    #[serde(rename = "byStore")]
+    ByStore,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SortDirection {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl SortDirection {
+    pub const ALL: &'static [Self] = &[Self::Ascending, Self::Descending];
+}
+
+impl std::fmt::Display for SortDirection {
+    // This is needed for Iced's PickList.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "{}", crate::lang::Translator::default().sort_direction(self))
+    }
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Sort {
     pub key: SortKey,
+    /// Descending when set, ascending otherwise.
     pub reversed: bool,
 }
 
+impl Sort {
+    pub fn direction(&self) -> SortDirection {
+        if self.reversed {
+            SortDirection::Descending
+        } else {
+            SortDirection::Ascending
+        }
+    }
+
+    pub fn set_direction(&mut self, direction: SortDirection) {
+        self.reversed = direction == SortDirection::Descending;
+    }
+
+    /// Sort by the key, falling back to the name to break ties,
+    /// so that both directions give a stable order.
+    pub fn apply<T>(&self, items: &mut [T], name: impl Fn(&T) -> String, size: impl Fn(&T) -> u64) {
+        match self.key {
+            SortKey::Name => items.sort_by_key(|x| name(x)),
+            SortKey::Size => items.sort_by_key(|x| (size(x), name(x))),
+        }
+        if self.reversed {
+            items.reverse();
+        }
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Retention {
     pub full: u8,
@@ -159,6 +779,90 @@ pub struct BackupConfig {
     pub sort: Sort,
     #[serde(default)]
     pub retention: Retention,
+    #[serde(default, rename = "permissionDenied")]
+    pub permission_denied: PermissionDeniedPolicy,
+    #[serde(default, rename = "alternateStreams")]
+    pub alternate_streams: AlternateStreamPolicy,
+    #[serde(default)]
+    pub comparison: ComparisonStrategy,
+    #[serde(default)]
+    pub grouping: BackupGrouping,
+    #[serde(default, rename = "extraTargets", skip_serializing_if = "Vec::is_empty")]
+    pub extra_targets: Vec<BackupTarget>,
+    /// Record where each file came from relative to the home folder or a root,
+    /// so that the backup can be restored for a different user or machine.
+    #[serde(
+        default,
+        rename = "portablePaths",
+        skip_serializing_if = "crate::serialization::is_false"
+    )]
+    pub portable_paths: bool,
+    #[serde(default, skip_serializing_if = "Inactivity::is_disabled")]
+    pub inactivity: Inactivity,
+    /// How often backups are meant to run, for recommending when to run the next one.
+    #[serde(default, rename = "scheduleIntervalHours", skip_serializing_if = "Option::is_none")]
+    pub schedule_interval_hours: Option<u32>,
+    /// Gzip level from 0 (fastest) to 9 (smallest) for `.tar.gz` archive backups.
+    #[serde(default, rename = "compressionLevel", skip_serializing_if = "Option::is_none")]
+    pub compression_level: Option<u32>,
+}
+
+/// Put off backing up a game while its saves are still changing,
+/// so that a backup doesn't catch a file that the game is in the middle of writing.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Inactivity {
+    /// How many minutes a game's files must go unchanged before it's backed up. Off when 0.
+    #[serde(default)]
+    pub minutes: u32,
+    /// After deferring a game this many backups in a row, back it up anyway.
+    #[serde(default = "default_max_defers", rename = "maxDefers")]
+    pub max_defers: u32,
+}
+
+const fn default_max_defers() -> u32 {
+    3
+}
+
+impl Default for Inactivity {
+    fn default() -> Self {
+        Self {
+            minutes: 0,
+            max_defers: default_max_defers(),
+        }
+    }
+}
+
+impl Inactivity {
+    pub fn is_disabled(&self) -> bool {
+        self.minutes == 0
+    }
+
+    pub fn threshold(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.minutes as u64 * 60)
+    }
+}
+
+/// Another folder that each backup is written to, besides `backup.path`.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BackupTarget {
+    pub path: StrictPath,
+    /// When not set, `backup.retention` is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retention: Option<Retention>,
+}
+
+impl BackupConfig {
+    /// The main backup path followed by any extra targets, each with its retention.
+    pub fn targets(&self) -> Vec<(StrictPath, Retention)> {
+        std::iter::once((self.path.clone(), self.retention.clone()))
+            .chain(self.extra_targets.iter().map(|x| {
+                (
+                    x.path.clone(),
+                    x.retention.clone().unwrap_or_else(|| self.retention.clone()),
+                )
+            }))
+            .collect()
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -170,10 +874,178 @@ pub struct RestoreConfig {
         serialize_with = "crate::serialization::ordered_set"
     )]
     pub ignored_games: std::collections::HashSet<String>,
+    /// Games whose current saves should never be replaced by a restore.
+    /// Unlike `ignored_games`, naming one of these explicitly doesn't restore it without `--force`.
+    #[serde(
+        default,
+        rename = "frozenGames",
+        serialize_with = "crate::serialization::ordered_set",
+        skip_serializing_if = "crate::serialization::is_empty_set"
+    )]
+    pub frozen_games: std::collections::HashSet<String>,
     #[serde(default)]
     pub redirects: Vec<RedirectConfig>,
     #[serde(default)]
     pub sort: Sort,
+    #[serde(
+        default,
+        rename = "preserveTimestamps",
+        skip_serializing_if = "crate::serialization::is_false"
+    )]
+    pub preserve_timestamps: bool,
+    #[serde(
+        default,
+        rename = "undoSnapshot",
+        skip_serializing_if = "crate::serialization::is_false"
+    )]
+    pub undo_snapshot: bool,
+    /// Refuse to restore games that are running, based on their executables in the manifest.
+    #[serde(
+        default,
+        rename = "checkRunningGames",
+        skip_serializing_if = "crate::serialization::is_false"
+    )]
+    pub check_running_games: bool,
+    /// Redirects that only apply to one game, checked before the global ones.
+    #[serde(
+        default,
+        rename = "gameRedirects",
+        skip_serializing_if = "std::collections::BTreeMap::is_empty"
+    )]
+    pub game_redirects: std::collections::BTreeMap<String, Vec<RedirectConfig>>,
+    #[serde(default)]
+    pub mode: RestoreMode,
+    /// Leave files alone when they already match the backup.
+    #[serde(default = "crate::serialization::default_true", rename = "skipIdentical")]
+    pub skip_identical: bool,
+}
+
+/// Settings that a profile can override. Anything left unset falls back to
+/// the base config, and roots, custom games, and the manifest are always shared.
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ProfileConfig {
+    /// Where to back up to and restore from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<StrictPath>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retention: Option<Retention>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<BackupFilter>,
+    /// Same as `backup.compressionLevel`.
+    #[serde(default, rename = "compressionLevel", skip_serializing_if = "Option::is_none")]
+    pub compression_level: Option<u32>,
+    /// Replaces the whole `signature` section, so one profile can sign with
+    /// a different key or require valid signatures while another doesn't.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<SignatureConfig>,
+}
+
+/// When to check the signatures of backups before restoring them.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum SignatureVerification {
+    #[default]
+    #[serde(rename = "off")]
+    Off,
+    /// Report invalid or missing signatures, but restore anyway.
+    #[serde(rename = "warn")]
+    Warn,
+    /// Refuse to restore if any signature is invalid or missing.
+    #[serde(rename = "require")]
+    Require,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SignatureConfig {
+    /// File with the key used to sign new backups and verify them on restore.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<StrictPath>,
+    /// File with just the public half of the key, for machines that only verify.
+    #[serde(default, rename = "publicKey", skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<StrictPath>,
+    #[serde(default)]
+    pub verify: SignatureVerification,
+}
+
+impl SignatureConfig {
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum ProfileChoice {
+    #[default]
+    Base,
+    Named(String),
+}
+
+impl ProfileChoice {
+    pub fn all(config: &Config) -> Vec<Self> {
+        std::iter::once(Self::Base)
+            .chain(config.profiles.keys().cloned().map(Self::Named))
+            .collect()
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Self::Base => None,
+            Self::Named(name) => Some(name),
+        }
+    }
+}
+
+impl std::fmt::Display for ProfileChoice {
+    // This is needed for Iced's PickList.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "{}", crate::lang::Translator::default().profile_choice(self))
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RuntimeConfig {
+    /// How many files to copy at the same time within a single game.
+    #[serde(default = "default_copy_threads", rename = "copyThreads")]
+    pub copy_threads: usize,
+    /// How many times to retry copying a file after a transient error, like a timeout.
+    #[serde(default = "default_retry_attempts", rename = "retryAttempts")]
+    pub retry_attempts: u32,
+    /// How long to wait before the first retry, doubling for each one after.
+    #[serde(default = "default_retry_delay_ms", rename = "retryDelayMs")]
+    pub retry_delay_ms: u64,
+    /// Most megabytes per second to spend copying files, across all threads. Unlimited when unset.
+    #[serde(default, rename = "copyRateLimit", skip_serializing_if = "Option::is_none")]
+    pub copy_rate_limit: Option<u32>,
+    /// Try to copy files as copy-on-write reflinks, falling back to a normal copy when unsupported.
+    #[serde(
+        default,
+        rename = "useReflinks",
+        skip_serializing_if = "crate::serialization::is_false"
+    )]
+    pub use_reflinks: bool,
+}
+
+const fn default_copy_threads() -> usize {
+    4
+}
+
+const fn default_retry_attempts() -> u32 {
+    3
+}
+
+const fn default_retry_delay_ms() -> u64 {
+    100
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            copy_threads: default_copy_threads(),
+            retry_attempts: default_retry_attempts(),
+            retry_delay_ms: default_retry_delay_ms(),
+            copy_rate_limit: None,
+            use_reflinks: false,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -185,6 +1057,29 @@ pub struct CustomGame {
     pub files: Vec<String>,
     #[serde(default)]
     pub registry: Vec<String>,
+    /// Which values to back up from some of the `registry` entries, keyed by entry.
+    #[serde(
+        default,
+        rename = "registryFilters",
+        skip_serializing_if = "std::collections::HashMap::is_empty"
+    )]
+    pub registry_filters: std::collections::HashMap<String, RegistryFilter>,
+    #[serde(
+        default,
+        rename = "cloudOnly",
+        skip_serializing_if = "crate::serialization::is_false"
+    )]
+    pub cloud_only: bool,
+}
+
+impl CustomGame {
+    /// Make sure that any `.reg` files among the registry entries can be read.
+    pub fn check_registry_files(&self) -> Result<(), Error> {
+        for entry in self.registry.iter().filter(|x| is_reg_file(x)) {
+            load_reg_file(&StrictPath::new(entry.trim().to_string()))?;
+        }
+        Ok(())
+    }
 }
 
 impl Default for ManifestConfig {
@@ -192,6 +1087,10 @@ impl Default for ManifestConfig {
         Self {
             url: MANIFEST_URL.to_string(),
             etag: None,
+            delta_url: None,
+            path: None,
+            refresh_interval: None,
+            secondary: vec![],
         }
     }
 }
@@ -207,96 +1106,526 @@ impl Default for BackupConfig {
             toggled_registry: Default::default(),
             sort: Default::default(),
             retention: Retention::default(),
+            permission_denied: Default::default(),
+            alternate_streams: Default::default(),
+            comparison: Default::default(),
+            grouping: Default::default(),
+            extra_targets: vec![],
+            portable_paths: false,
+            inactivity: Default::default(),
+            schedule_interval_hours: None,
+            compression_level: None,
+        }
+    }
+}
+
+impl Default for RestoreConfig {
+    fn default() -> Self {
+        Self {
+            path: default_backup_dir(),
+            ignored_games: std::collections::HashSet::new(),
+            frozen_games: Default::default(),
+            redirects: vec![],
+            sort: Default::default(),
+            preserve_timestamps: false,
+            undo_snapshot: false,
+            check_running_games: false,
+            game_redirects: Default::default(),
+            mode: Default::default(),
+            skip_identical: true,
+        }
+    }
+}
+
+/// Program Files folders for 32-bit and 64-bit programs, respecting the environment variables.
+fn program_files() -> (String, String) {
+    let mut pf32 = "C:/Program Files (x86)".to_string();
+    let mut pf64 = "C:/Program Files".to_string();
+    if let Ok(x) = std::env::var("ProgramFiles(x86)") {
+        pf32 = x.trim_end_matches("[\\/]").to_string();
+    } else if let Ok(x) = std::env::var("PROGRAMFILES") {
+        pf32 = x.trim_end_matches("[\\/]").to_string();
+    }
+    if let Ok(x) = std::env::var("ProgramW6432") {
+        pf64 = x.trim_end_matches("[\\/]").to_string();
+    }
+    (pf32, pf64)
+}
+
+/// Typical install locations for a store on the current OS.
+/// This is empty if the store doesn't exist on the current OS
+/// or if there's no typical location (e.g., for `Store::Other`).
+pub fn default_root_templates(store: Store) -> Vec<String> {
+    if cfg!(target_os = "windows") {
+        let (pf32, pf64) = program_files();
+        let in_program_files = |folder: &str| vec![format!("{}/{}", pf32, folder), format!("{}/{}", pf64, folder)];
+        match store {
+            Store::Steam => in_program_files("Steam"),
+            Store::Epic => in_program_files("Epic Games"),
+            Store::Gog => vec!["C:/GOG Games".to_string()],
+            Store::GogGalaxy => in_program_files("GOG Galaxy/Games"),
+            Store::Uplay => in_program_files("Ubisoft/Ubisoft Game Launcher"),
+            Store::Origin => in_program_files("Origin Games"),
+            Store::Ea => in_program_files("EA Games"),
+            Store::Microsoft => in_program_files("WindowsApps"),
+            Store::Prime => {
+                let mut templates = vec!["C:/Amazon Games/Library".to_string()];
+                if let Some(local) = dirs::data_local_dir() {
+                    templates.push(format!("{}/Amazon Games/Library", local.display()).replace('\\', "/"));
+                }
+                templates
+            }
+            Store::OtherHome | Store::OtherWine | Store::Other => vec![],
+        }
+    } else if cfg!(target_os = "macos") {
+        match store {
+            Store::Steam => vec!["~/Library/Application Support/Steam".to_string()],
+            Store::Gog => vec!["~/GOG Games".to_string()],
+            _ => vec![],
+        }
+    } else {
+        match store {
+            Store::Steam => vec!["~/.steam/steam".to_string()],
+            Store::Gog => vec!["~/GOG Games".to_string()],
+            _ => vec![],
+        }
+    }
+}
+
+/// The library folders that contain the given game install folders,
+/// like the ones registered by the EA app or recorded by Amazon Games.
+#[allow(dead_code)]
+fn library_roots(install_dirs: &[String]) -> Vec<String> {
+    let mut roots = vec![];
+    for dir in install_dirs {
+        let normalized = dir.replace('\\', "/");
+        let normalized = normalized.trim().trim_end_matches('/');
+        if let Some((prefix, _)) = normalized.rsplit_once('/') {
+            if prefix.is_empty() || crate::path::is_raw_path_relative(prefix) {
+                continue;
+            }
+            if !roots.iter().any(|x: &String| x.eq_ignore_ascii_case(prefix)) {
+                roots.push(prefix.to_string());
+            }
+        }
+    }
+    roots
+}
+
+/// Where the Amazon Games app records its installed games, relative to the local app data folder.
+#[allow(dead_code)]
+const PRIME_INSTALL_DATABASE: &str = "Amazon Games/Data/Games/Sql/GameInstallInfo.sqlite";
+
+/// The library folders with games from the Amazon Games app, based on its database of installs.
+/// Since each install's folder is recorded there, this also finds games
+/// that were installed to (or moved to) a library other than the default one.
+#[allow(dead_code)]
+fn prime_library_roots(database: &std::path::Path) -> Vec<String> {
+    let install_dirs = (|| -> rusqlite::Result<Vec<String>> {
+        let db = rusqlite::Connection::open_with_flags(database, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        let mut statement = db.prepare("SELECT InstallDirectory FROM DbSet WHERE Installed = 1")?;
+        let rows = statement.query_map([], |row| row.get::<_, String>(0))?;
+        Ok(rows.flatten().collect())
+    })();
+
+    match install_dirs {
+        Ok(install_dirs) => library_roots(&install_dirs),
+        Err(e) => {
+            log::debug!("Unable to read Amazon Games installs from {:?}: {}", database, e);
+            vec![]
+        }
+    }
+}
+
+/// Where the Flatpak version of Bottles keeps its data, relative to the home folder.
+/// Inside of the sandbox, this folder is seen as `/var/data`.
+#[cfg(target_os = "linux")]
+const BOTTLES_FLATPAK_DATA: &str = ".var/app/com.usebottles.bottles/data";
+
+/// The parts of a bottle's `bottle.yml` that we need.
+#[cfg(target_os = "linux")]
+#[derive(Debug, Default, serde::Deserialize)]
+struct BottleConfig {
+    #[serde(rename = "Name", default)]
+    name: String,
+    #[serde(rename = "Path", default)]
+    path: String,
+    /// The bottle is somewhere other than the Bottles data folder, and `Path` is absolute.
+    #[serde(rename = "Custom_Path", default)]
+    custom_path: bool,
+    #[serde(rename = "External_Programs", default)]
+    programs: std::collections::HashMap<String, BottleProgram>,
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Debug, Default, serde::Deserialize)]
+struct BottleProgram {
+    #[serde(default)]
+    name: String,
+}
+
+/// A Wine prefix managed by Bottles, along with the programs that were added to it.
+#[cfg(target_os = "linux")]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+struct Bottle {
+    name: String,
+    prefix: String,
+    programs: Vec<String>,
+}
+
+/// Turn a path as seen inside of the Bottles sandbox into the real one.
+/// The sandbox's `/var/data` is the app's data folder, and `/run/host` is the host's root.
+#[cfg(target_os = "linux")]
+fn unsandbox_bottles_path(data_dir: &std::path::Path, path: &str) -> String {
+    let under = |prefix: &str| {
+        path.strip_prefix(prefix)
+            .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+    };
+    if let Some(rest) = under("/var/data") {
+        format!("{}{}", crate::path::render_pathbuf(data_dir), rest)
+    } else if let Some(rest) = under("/run/host") {
+        if rest.is_empty() {
+            "/".to_string()
+        } else {
+            rest.to_string()
+        }
+    } else {
+        path.to_string()
+    }
+}
+
+/// The bottles that Bottles keeps under its data folder (like `~/.local/share`),
+/// based on the `bottle.yml` in each one.
+/// A bottle with a custom location is found through the link that Bottles leaves in its data folder,
+/// but its prefix is taken from where `bottle.yml` says it is.
+#[cfg(target_os = "linux")]
+fn bottles(data_dir: &std::path::Path) -> Vec<Bottle> {
+    let entries = match std::fs::read_dir(data_dir.join("bottles/bottles")) {
+        Ok(x) => x,
+        Err(_) => return vec![],
+    };
+
+    let mut bottles = vec![];
+    for entry in entries.flatten() {
+        let folder = entry.path();
+        let config = match std::fs::read_to_string(folder.join("bottle.yml"))
+            .map_err(|e| e.to_string())
+            .and_then(|x| serde_yaml::from_str::<BottleConfig>(&x).map_err(|e| e.to_string()))
+        {
+            Ok(x) => x,
+            Err(e) => {
+                log::debug!("Unable to read bottle config in {:?}: {}", folder, e);
+                continue;
+            }
+        };
+
+        let prefix = if config.custom_path && config.path.starts_with('/') {
+            unsandbox_bottles_path(data_dir, &config.path)
+        } else {
+            crate::path::render_pathbuf(&folder)
+        };
+        let mut programs: Vec<_> = config.programs.into_values().map(|x| x.name).collect();
+        programs.sort();
+        bottles.push(Bottle {
+            name: config.name,
+            prefix,
+            programs,
+        });
+    }
+    bottles.sort_by(|a, b| a.prefix.cmp(&b.prefix));
+    bottles
+}
+
+/// Where GOG Galaxy records its installed games, relative to the ProgramData folder.
+#[allow(dead_code)]
+const GOG_GALAXY_DATABASE: &str = "GOG.com/Galaxy/storage/galaxy-2.0.db";
+
+/// The library folders with games from GOG Galaxy, based on its database of installs,
+/// so that games on other drives are found too.
+/// Galaxy keeps the database locked while it's running, so this opens it as immutable,
+/// which reads it without taking a lock. If it still can't be read,
+/// only the default locations are checked.
+#[allow(dead_code)]
+fn gog_galaxy_library_roots(database: &std::path::Path) -> Vec<String> {
+    let install_dirs = (|| -> rusqlite::Result<Vec<String>> {
+        let path = database.display().to_string().replace('\\', "/");
+        let uri = format!(
+            "file://{}{}?immutable=1",
+            if path.starts_with('/') { "" } else { "/" },
+            path.replace('%', "%25").replace('?', "%3f").replace('#', "%23"),
+        );
+        let db = rusqlite::Connection::open_with_flags(
+            uri,
+            rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_URI,
+        )?;
+        let mut statement = db.prepare("SELECT installationPath FROM InstalledBaseProducts")?;
+        let rows = statement.query_map([], |row| row.get::<_, String>(0))?;
+        Ok(rows.flatten().collect())
+    })();
+
+    match install_dirs {
+        Ok(install_dirs) => library_roots(&install_dirs),
+        Err(e) => {
+            log::debug!("Unable to read GOG Galaxy installs from {:?}: {}", database, e);
+            vec![]
+        }
+    }
+}
+
+impl Config {
+    fn file() -> std::path::PathBuf {
+        let mut path = app_dir();
+        path.push("config.yaml");
+        path
+    }
+
+    /// Settings from environment variables only apply to the current run,
+    /// so the file keeps its own values for those.
+    pub fn save(&self) {
+        let stored = Self::load_file().ok();
+        let mut config = self.clone();
+        config.reset_env_overrides(stored.clone().unwrap_or_default(), |name| std::env::var(name).ok());
+        let new_content = serde_yaml::to_string(&config).unwrap();
+
+        if let Some(old) = stored {
+            let old_content = serde_yaml::to_string(&old).unwrap();
+            if old_content == new_content {
+                return;
+            }
+        }
+
+        if std::fs::create_dir_all(app_dir()).is_ok() {
+            std::fs::write(Self::file(), new_content.as_bytes()).unwrap();
+        }
+    }
+
+    /// Load the config file, then apply any overrides from environment variables.
+    pub fn load() -> Result<Self, Error> {
+        let mut config = Self::load_file()?;
+        config.apply_env_overrides(|name| std::env::var(name).ok())?;
+        Ok(config)
+    }
+
+    /// Like `load`, but without falling back to a starter config when there's no file yet.
+    /// This only reads, so it's safe for checking the config without changing anything.
+    pub fn load_existing() -> Result<Option<Self>, Error> {
+        let mut config = match Self::read_file(&Self::file())? {
+            Some(x) => x,
+            None => return Ok(None),
+        };
+        config.apply_env_overrides(|name| std::env::var(name).ok())?;
+        Ok(Some(config))
+    }
+
+    fn load_file() -> Result<Self, Error> {
+        match Self::read_file(&Self::file())? {
+            Some(config) => Ok(config),
+            None => {
+                let mut starter = Self::default();
+                starter.add_common_roots();
+                Ok(starter)
+            }
+        }
+    }
+
+    fn read_file(file: &std::path::Path) -> Result<Option<Self>, Error> {
+        if !file.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(file).map_err(|e| Error::ConfigInvalid { why: e.to_string() })?;
+        let mut config = Self::load_from_string(&content)?;
+        config.resolve_relative_to_portable_dir();
+        Ok(Some(config))
+    }
+
+    /// `var` looks up an environment variable. Empty values count as unset.
+    fn apply_env_overrides(&mut self, var: impl Fn(&str) -> Option<String>) -> Result<(), Error> {
+        for env in ENV_OVERRIDES {
+            if let Some(value) = var(env.name).filter(|x| !x.is_empty()) {
+                (env.apply)(self, &value).map_err(|why| Error::ConfigInvalid {
+                    why: format!("{}: {}", env.name, why),
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    fn reset_env_overrides(&mut self, stored: Self, var: impl Fn(&str) -> Option<String>) {
+        for env in ENV_OVERRIDES {
+            if var(env.name).filter(|x| !x.is_empty()).is_some() {
+                (env.reset)(self, &stored);
+            }
+        }
+    }
+
+    /// In portable mode, relative paths in the config are relative to the executable,
+    /// so that they keep working when the drive letter or mount point changes.
+    fn resolve_relative_to_portable_dir(&mut self) {
+        let basis = match portable_dir() {
+            Some(x) => Some(render_pathbuf(&x)),
+            None => return,
+        };
+        self.backup.path.set_basis(basis.clone());
+        self.restore.path.set_basis(basis.clone());
+        for target in &mut self.backup.extra_targets {
+            target.path.set_basis(basis.clone());
+        }
+        if let Some(path) = &mut self.manifest.path {
+            path.set_basis(basis.clone());
+        }
+        for root in &mut self.roots {
+            root.path.set_basis(basis.clone());
+        }
+        for redirect in self
+            .restore
+            .redirects
+            .iter_mut()
+            .chain(self.restore.game_redirects.values_mut().flatten())
+        {
+            redirect.source.set_basis(basis.clone());
+            redirect.target.set_basis(basis.clone());
+        }
+        for profile in self.profiles.values_mut() {
+            if let Some(path) = &mut profile.path {
+                path.set_basis(basis.clone());
+            }
+        }
+        for filter in self.filters_mut() {
+            for path in filter.excluded_roots.values_mut().flatten() {
+                path.set_basis(basis.clone());
+            }
         }
     }
-}
 
-impl Default for RestoreConfig {
-    fn default() -> Self {
-        Self {
-            path: default_backup_dir(),
-            ignored_games: std::collections::HashSet::new(),
-            redirects: vec![],
-            sort: Default::default(),
+    fn filters_mut(&mut self) -> impl Iterator<Item = &mut BackupFilter> {
+        std::iter::once(&mut self.backup.filter).chain(self.profiles.values_mut().filter_map(|x| x.filter.as_mut()))
+    }
+
+    /// Each game's excluded roots must be among the configured roots.
+    fn validate(&self) -> Result<(), Error> {
+        let filters =
+            std::iter::once(&self.backup.filter).chain(self.profiles.values().filter_map(|x| x.filter.as_ref()));
+        let levels = std::iter::once(&self.backup.compression_level)
+            .chain(self.profiles.values().map(|x| &x.compression_level))
+            .flatten();
+        for level in levels {
+            if *level > 9 {
+                return Err(Error::ConfigInvalid {
+                    why: format!("The compression level {} is not between 0 and 9", level),
+                });
+            }
+        }
+        for filter in filters {
+            for (game, paths) in &filter.excluded_roots {
+                for path in paths {
+                    if !self.roots.iter().any(|x| x.path.interpret() == path.interpret()) {
+                        return Err(Error::ConfigInvalid {
+                            why: format!(
+                                "The excluded root {} for {} is not one of the configured roots",
+                                path.render(),
+                                game
+                            ),
+                        });
+                    }
+                }
+            }
         }
+        Ok(())
     }
-}
 
-impl Config {
-    fn file() -> std::path::PathBuf {
-        let mut path = app_dir();
-        path.push("config.yaml");
-        path
+    /// Find an earlier root of the same store that overlaps with the one at `index`,
+    /// which would cause the same saves to be scanned twice.
+    /// Roots of different stores are scanned for different paths, so they can overlap on purpose,
+    /// like a Steam root inside of a home folder root.
+    pub fn find_root_overlap(&self, index: usize) -> Option<(usize, RootOverlap)> {
+        let root = self.roots.get(index)?;
+        let case_insensitive = crate::prelude::CASE_INSENSITIVE_OS;
+        self.roots.iter().take(index).enumerate().find_map(|(i, other)| {
+            if other.store != root.store {
+                return None;
+            }
+            let inside = other.path.contains(&root.path, case_insensitive);
+            let contains = root.path.contains(&other.path, case_insensitive);
+            match (inside, contains) {
+                (true, true) => Some((i, RootOverlap::Duplicate)),
+                (true, false) => Some((i, RootOverlap::Inside)),
+                (false, true) => Some((i, RootOverlap::Contains)),
+                (false, false) => None,
+            }
+        })
     }
 
-    pub fn save(&self) {
-        let new_content = serde_yaml::to_string(&self).unwrap();
+    /// Find the settings that differ from the default config,
+    /// comparing them the same way that they're written to the config file.
+    pub fn diff_from_default(&self) -> Vec<ConfigDiff> {
+        let default = serde_json::to_value(Self::default()).unwrap_or_default();
+        let value = serde_json::to_value(self).unwrap_or_default();
+        let mut diffs = vec![];
+        diff_values("", &default, &value, &mut diffs);
+        diffs
+    }
 
-        if let Ok(old) = Self::load() {
-            let old_content = serde_yaml::to_string(&old).unwrap();
-            if old_content == new_content {
-                return;
+    /// Forget any excluded roots that are no longer configured, like after removing a root.
+    pub fn prune_excluded_roots(&mut self) {
+        let roots: Vec<_> = self.roots.iter().map(|x| x.path.interpret()).collect();
+        for filter in self.filters_mut() {
+            for paths in filter.excluded_roots.values_mut() {
+                paths.retain(|x| roots.contains(&x.interpret()));
             }
+            filter.excluded_roots.retain(|_, paths| !paths.is_empty());
         }
+    }
 
-        if std::fs::create_dir_all(app_dir()).is_ok() {
-            std::fs::write(Self::file(), new_content.as_bytes()).unwrap();
+    /// Get the config as it applies while the named profile is active.
+    /// The base config itself is left as-is, so it can still be saved normally.
+    pub fn with_profile(&self, name: &str) -> Result<Self, Error> {
+        let profile = self
+            .profiles
+            .get(name)
+            .ok_or_else(|| Error::UnknownProfile { name: name.to_string() })?;
+
+        let mut config = self.clone();
+        if let Some(path) = &profile.path {
+            config.backup.path = path.clone();
+            config.restore.path = path.clone();
+        }
+        if let Some(retention) = &profile.retention {
+            config.backup.retention = retention.clone();
+        }
+        if let Some(filter) = &profile.filter {
+            config.backup.filter = filter.clone();
+        }
+        if let Some(level) = profile.compression_level {
+            config.backup.compression_level = Some(level);
         }
+        if let Some(signature) = &profile.signature {
+            config.signature = signature.clone();
+        }
+        Ok(config)
     }
 
-    pub fn load() -> Result<Self, Error> {
-        if !std::path::Path::new(&Self::file()).exists() {
-            let mut starter = Self::default();
-            starter.add_common_roots();
-            return Ok(starter);
+    pub fn with_profile_choice(&self, choice: &ProfileChoice) -> Result<Self, Error> {
+        match choice.name() {
+            Some(name) => self.with_profile(name),
+            None => Ok(self.clone()),
         }
-        let content = std::fs::read_to_string(Self::file()).unwrap();
-        Self::load_from_string(&content)
     }
 
     pub fn load_from_string(content: &str) -> Result<Self, Error> {
-        serde_yaml::from_str(content).map_err(|e| Error::ConfigInvalid { why: format!("{}", e) })
-    }
-
-    pub fn find_missing_roots(&self) -> Vec<RootsConfig> {
-        let mut pf32 = "C:/Program Files (x86)".to_string();
-        let mut pf64 = "C:/Program Files".to_string();
-        if let Ok(x) = std::env::var("ProgramFiles(x86)") {
-            pf32 = x.trim_end_matches("[\\/]").to_string();
-        } else if let Ok(x) = std::env::var("PROGRAMFILES") {
-            pf32 = x.trim_end_matches("[\\/]").to_string();
-        }
-        if let Ok(x) = std::env::var("ProgramW6432") {
-            pf64 = x.trim_end_matches("[\\/]").to_string();
-        }
-
-        let candidates = vec![
-            // Steam:
-            (format!("{}/Steam", pf32), Store::Steam),
-            (format!("{}/Steam", pf64), Store::Steam),
-            ("~/.steam/steam".to_string(), Store::Steam),
-            ("~/Library/Application Support/Steam".to_string(), Store::Steam),
-            // Epic:
-            (format!("{}/Epic Games", pf32), Store::Epic),
-            (format!("{}/Epic Games", pf64), Store::Epic),
-            // GOG:
-            ("C:/GOG Games".to_string(), Store::Gog),
-            ("~/GOG Games".to_string(), Store::Gog),
-            // GOG Galaxy:
-            (format!("{}/GOG Galaxy/Games", pf32), Store::GogGalaxy),
-            (format!("{}/GOG Galaxy/Games", pf64), Store::GogGalaxy),
-            // Uplay:
-            (format!("{}/Ubisoft/Ubisoft Game Launcher", pf32), Store::Uplay),
-            (format!("{}/Ubisoft/Ubisoft Game Launcher", pf64), Store::Uplay),
-            // Origin:
-            (format!("{}/Origin Games", pf32), Store::Origin),
-            (format!("{}/Origin Games", pf64), Store::Origin),
-            // Microsoft:
-            (format!("{}/WindowsApps", pf32), Store::Microsoft),
-            (format!("{}/WindowsApps", pf64), Store::Microsoft),
-            // Prime Gaming:
-            ("C:/Amazon Games/Library".to_string(), Store::Prime),
-        ];
+        let config: Self = serde_yaml::from_str(content).map_err(|e| Error::ConfigInvalid { why: format!("{}", e) })?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn find_missing_roots(&self) -> Vec<RootCandidate> {
+        let candidates: Vec<_> = Store::ALL
+            .iter()
+            .flat_map(|store| {
+                default_root_templates(*store)
+                    .into_iter()
+                    .map(|path| (path, *store, RootConfidence::Guess))
+            })
+            .collect();
 
         let detected_steam = match steamlocate::SteamDir::locate() {
             Some(mut steam_dir) => steam_dir
@@ -309,67 +1638,145 @@ impl Config {
                     pb.pop();
                     pb
                 })
-                .map(|pb| (pb.as_os_str().to_string_lossy().to_string(), Store::Steam))
+                .map(|pb| {
+                    (
+                        pb.as_os_str().to_string_lossy().to_string(),
+                        Store::Steam,
+                        RootConfidence::Certain,
+                    )
+                })
                 .collect(),
             None => vec![],
         };
 
         #[cfg(target_os = "windows")]
-        let detected_epic: Vec<(String, Store)> = winreg::RegKey::predef(winreg::enums::HKEY_CURRENT_USER)
-            .open_subkey(r"SOFTWARE\Epic Games\EOS")
-            .and_then(|subkey| {
-                #[derive(serde::Deserialize)]
-                struct EpicManifest {
-                    #[serde(rename = "InstallLocation")]
-                    install_location: String,
-                }
-
-                let mut install_dirs = vec![];
-                let manifest_dir = subkey.get_value::<String, &str>("ModSdkMetadataDir")?;
-                for entry in std::fs::read_dir(&manifest_dir)?.flatten() {
-                    if !entry.file_type()?.is_file() {
-                        continue;
+        let detected_epic: Vec<(String, Store, RootConfidence)> =
+            winreg::RegKey::predef(winreg::enums::HKEY_CURRENT_USER)
+                .open_subkey(r"SOFTWARE\Epic Games\EOS")
+                .and_then(|subkey| {
+                    #[derive(serde::Deserialize)]
+                    struct EpicManifest {
+                        #[serde(rename = "InstallLocation")]
+                        install_location: String,
                     }
-                    let content = std::fs::read_to_string(entry.path())?;
-                    let manifest = serde_json::from_str::<EpicManifest>(&content)?;
-                    let normalized = manifest.install_location.replace('\\', "/");
-                    if let Some((prefix, _)) = normalized.rsplit_once('/') {
-                        let prefix = prefix.trim();
-                        if crate::path::is_raw_path_relative(prefix) {
+
+                    let mut install_dirs = vec![];
+                    let manifest_dir = subkey.get_value::<String, &str>("ModSdkMetadataDir")?;
+                    for entry in std::fs::read_dir(&manifest_dir)?.flatten() {
+                        if !entry.file_type()?.is_file() {
                             continue;
                         }
-                        install_dirs.push(prefix.to_string());
+                        let content = std::fs::read_to_string(entry.path())?;
+                        let manifest = serde_json::from_str::<EpicManifest>(&content)?;
+                        let normalized = manifest.install_location.replace('\\', "/");
+                        if let Some((prefix, _)) = normalized.rsplit_once('/') {
+                            let prefix = prefix.trim();
+                            if crate::path::is_raw_path_relative(prefix) {
+                                continue;
+                            }
+                            install_dirs.push(prefix.to_string());
+                        }
                     }
-                }
-                Ok(install_dirs.iter().cloned().map(|x| (x, Store::Epic)).collect())
-            })
-            .unwrap_or_default();
+                    Ok(install_dirs
+                        .iter()
+                        .cloned()
+                        .map(|x| (x, Store::Epic, RootConfidence::Certain))
+                        .collect())
+                })
+                .unwrap_or_default();
         #[cfg(not(target_os = "windows"))]
         let detected_epic = vec![];
 
-        let mut checked = std::collections::HashSet::<StrictPath>::new();
-        let mut roots = vec![];
-        for (path, store) in [candidates, detected_steam, detected_epic].concat() {
-            let sp = StrictPath::new(path);
-            if self.roots.iter().any(|root| root.path.interpret() == sp.interpret())
-                || checked.contains(&sp.interpreted())
-            {
-                continue;
-            }
-            if sp.is_dir() {
-                roots.push(RootsConfig {
-                    path: sp.rendered(),
-                    store,
-                });
+        // The EA app registers each game's install folder, like Origin did.
+        #[cfg(target_os = "windows")]
+        let detected_ea: Vec<(String, Store, RootConfidence)> = {
+            let hklm = winreg::RegKey::predef(winreg::enums::HKEY_LOCAL_MACHINE);
+            let mut install_dirs = vec![];
+            for parent in [r"SOFTWARE\WOW6432Node\EA Games", r"SOFTWARE\EA Games"] {
+                if let Ok(games) = hklm.open_subkey(parent) {
+                    for game in games.enum_keys().flatten() {
+                        if let Ok(dir) = games
+                            .open_subkey(&game)
+                            .and_then(|x| x.get_value::<String, &str>("Install Dir"))
+                        {
+                            install_dirs.push(dir);
+                        }
+                    }
+                }
             }
-            checked.insert(sp.interpreted());
-        }
+            library_roots(&install_dirs)
+                .into_iter()
+                .map(|x| (x, Store::Ea, RootConfidence::Likely))
+                .collect()
+        };
+        #[cfg(not(target_os = "windows"))]
+        let detected_ea = vec![];
 
-        roots
+        #[cfg(target_os = "windows")]
+        let detected_prime: Vec<(String, Store, RootConfidence)> = match dirs::data_local_dir() {
+            Some(local) => prime_library_roots(&local.join(PRIME_INSTALL_DATABASE))
+                .into_iter()
+                .map(|x| (x, Store::Prime, RootConfidence::Certain))
+                .collect(),
+            None => vec![],
+        };
+        #[cfg(not(target_os = "windows"))]
+        let detected_prime = vec![];
+
+        #[cfg(target_os = "windows")]
+        let detected_gog_galaxy: Vec<(String, Store, RootConfidence)> = {
+            let program_data = std::env::var("PROGRAMDATA").unwrap_or_else(|_| "C:/ProgramData".to_string());
+            gog_galaxy_library_roots(&std::path::Path::new(&program_data).join(GOG_GALAXY_DATABASE))
+                .into_iter()
+                .map(|x| (x, Store::GogGalaxy, RootConfidence::Certain))
+                .collect()
+        };
+        #[cfg(not(target_os = "windows"))]
+        let detected_gog_galaxy = vec![];
+
+        #[cfg(target_os = "linux")]
+        let detected_bottles: Vec<(String, Store, RootConfidence)> =
+            [dirs::home_dir().map(|x| x.join(BOTTLES_FLATPAK_DATA)), dirs::data_dir()]
+                .into_iter()
+                .flatten()
+                .flat_map(|x| bottles(&x))
+                .map(|bottle| {
+                    log::debug!(
+                        "Found bottle {} at {} with programs: {:?}",
+                        bottle.name,
+                        bottle.prefix,
+                        bottle.programs
+                    );
+                    (bottle.prefix, Store::OtherWine, RootConfidence::Certain)
+                })
+                .collect();
+        #[cfg(not(target_os = "linux"))]
+        let detected_bottles = vec![];
+
+        // A folder that's already claimed (e.g., as an Origin root) isn't added again for the EA app.
+        merge_root_candidates(
+            &self.roots,
+            [
+                candidates,
+                detected_steam,
+                detected_epic,
+                detected_ea,
+                detected_prime,
+                detected_gog_galaxy,
+                detected_bottles,
+            ]
+            .concat(),
+        )
     }
 
     pub fn add_common_roots(&mut self) {
-        self.roots.extend(self.find_missing_roots());
+        self.roots
+            .extend(self.find_missing_roots().into_iter().map(|candidate| candidate.root));
+    }
+
+    /// Roots that don't meet their requirements right now, like an unmounted drive.
+    pub fn unavailable_roots(&self) -> Vec<RootsConfig> {
+        self.roots.iter().filter(|x| !x.is_available()).cloned().collect()
     }
 
     pub fn is_game_enabled_for_backup(&self, name: &str) -> bool {
@@ -388,6 +1795,10 @@ impl Config {
         !self.restore.ignored_games.contains(name)
     }
 
+    pub fn is_game_frozen_for_restore(&self, name: &str) -> bool {
+        self.restore.frozen_games.contains(name)
+    }
+
     pub fn enable_game_for_restore(&mut self, name: &str) {
         self.restore.ignored_games.remove(name);
     }
@@ -408,12 +1819,37 @@ impl Config {
         self.restore.redirects.to_vec()
     }
 
+    /// The redirects to apply for one game: its own, followed by the global ones.
+    pub fn get_redirects_for_game(&self, game: &str) -> Vec<RedirectConfig> {
+        self.restore
+            .game_redirects
+            .get(game)
+            .into_iter()
+            .flatten()
+            .chain(self.restore.redirects.iter())
+            .cloned()
+            .collect()
+    }
+
+    /// Games with their own redirects that aren't in the manifest or custom games.
+    /// Those redirects are kept, but they don't apply to anything.
+    pub fn unknown_redirect_games(&self, manifest: &Manifest) -> Vec<String> {
+        self.restore
+            .game_redirects
+            .keys()
+            .filter(|game| !manifest.0.contains_key(*game) && !self.custom_games.iter().any(|x| &x.name == *game))
+            .cloned()
+            .collect()
+    }
+
     pub fn add_custom_game(&mut self) {
         self.custom_games.push(CustomGame {
             name: "".to_string(),
             ignore: false,
             files: vec![],
             registry: vec![],
+            registry_filters: Default::default(),
+            cloud_only: false,
         });
     }
 
@@ -612,7 +2048,8 @@ impl ToggledRegistry {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use maplit::hashset;
+    use crate::path::TempPath;
+    use maplit::{btreemap, hashmap, hashset};
     use pretty_assertions::assert_eq;
 
     fn s(text: &str) -> String {
@@ -640,6 +2077,10 @@ mod tests {
                 manifest: ManifestConfig {
                     url: s("example.com"),
                     etag: None,
+                    delta_url: None,
+                    path: None,
+                    refresh_interval: None,
+                    secondary: vec![],
                 },
                 roots: vec![],
                 backup: BackupConfig {
@@ -655,14 +2096,34 @@ mod tests {
                     toggled_registry: Default::default(),
                     sort: Default::default(),
                     retention: Retention::default(),
+                    permission_denied: Default::default(),
+                    alternate_streams: Default::default(),
+                    comparison: Default::default(),
+                    grouping: Default::default(),
+                    extra_targets: vec![],
+                    portable_paths: false,
+                    inactivity: Default::default(),
+                    schedule_interval_hours: None,
+                    compression_level: None,
                 },
                 restore: RestoreConfig {
                     path: StrictPath::new(s("~/restore")),
                     ignored_games: std::collections::HashSet::new(),
+                    frozen_games: Default::default(),
                     redirects: vec![],
                     sort: Default::default(),
+                    preserve_timestamps: false,
+                    undo_snapshot: false,
+                    check_running_games: false,
+                    game_redirects: Default::default(),
+                    mode: Default::default(),
+                    skip_identical: true,
                 },
                 custom_games: vec![],
+                runtime: Default::default(),
+                badge_order: vec![],
+                profiles: Default::default(),
+                signature: Default::default(),
             },
             config,
         );
@@ -710,6 +2171,7 @@ mod tests {
                   - Custom Registry 1
                   - Custom Registry 2
                   - Custom Registry 2
+                cloudOnly: true
             "#,
         )
         .unwrap();
@@ -719,15 +2181,21 @@ mod tests {
                 manifest: ManifestConfig {
                     url: s("example.com"),
                     etag: Some(s("foo")),
+                    delta_url: None,
+                    path: None,
+                    refresh_interval: None,
+                    secondary: vec![],
                 },
                 roots: vec![
                     RootsConfig {
                         path: StrictPath::new(s("~/steam")),
                         store: Store::Steam,
+                        require: Default::default(),
                     },
                     RootsConfig {
                         path: StrictPath::new(s("~/other")),
                         store: Store::Other,
+                        require: Default::default(),
                     },
                 ],
                 backup: BackupConfig {
@@ -746,6 +2214,15 @@ mod tests {
                     toggled_registry: Default::default(),
                     sort: Default::default(),
                     retention: Retention::default(),
+                    permission_denied: Default::default(),
+                    alternate_streams: Default::default(),
+                    comparison: Default::default(),
+                    grouping: Default::default(),
+                    extra_targets: vec![],
+                    portable_paths: false,
+                    inactivity: Default::default(),
+                    schedule_interval_hours: None,
+                    compression_level: None,
                 },
                 restore: RestoreConfig {
                     path: StrictPath::new(s("~/restore")),
@@ -753,11 +2230,18 @@ mod tests {
                         s("Restore Game 1"),
                         s("Restore Game 2"),
                     },
+                    frozen_games: Default::default(),
                     redirects: vec![RedirectConfig {
                         source: StrictPath::new(s("~/old")),
                         target: StrictPath::new(s("~/new")),
                     }],
                     sort: Default::default(),
+                    preserve_timestamps: false,
+                    undo_snapshot: false,
+                    check_running_games: false,
+                    game_redirects: Default::default(),
+                    mode: Default::default(),
+                    skip_identical: true,
                 },
                 custom_games: vec![
                     CustomGame {
@@ -765,14 +2249,22 @@ mod tests {
                         ignore: false,
                         files: vec![],
                         registry: vec![],
+                        registry_filters: Default::default(),
+                        cloud_only: false,
                     },
                     CustomGame {
                         name: s("Custom Game 2"),
                         ignore: false,
                         files: vec![s("Custom File 1"), s("Custom File 2"), s("Custom File 2"),],
                         registry: vec![s("Custom Registry 1"), s("Custom Registry 2"), s("Custom Registry 2"),],
+                        registry_filters: Default::default(),
+                        cloud_only: true,
                     },
                 ],
+                runtime: Default::default(),
+                badge_order: vec![],
+                profiles: Default::default(),
+                signature: Default::default(),
             },
             config,
         );
@@ -804,10 +2296,15 @@ mod tests {
                 manifest: ManifestConfig {
                     url: s("example.com"),
                     etag: None,
+                    delta_url: None,
+                    path: None,
+                    refresh_interval: None,
+                    secondary: vec![],
                 },
                 roots: vec![RootsConfig {
                     path: StrictPath::new(s("~/other")),
                     store: Store::Other,
+                    require: Default::default(),
                 }],
                 backup: BackupConfig {
                     path: StrictPath::new(s("~/backup")),
@@ -822,19 +2319,279 @@ mod tests {
                     toggled_registry: Default::default(),
                     sort: Default::default(),
                     retention: Retention::default(),
+                    permission_denied: Default::default(),
+                    alternate_streams: Default::default(),
+                    comparison: Default::default(),
+                    grouping: Default::default(),
+                    extra_targets: vec![],
+                    portable_paths: false,
+                    inactivity: Default::default(),
+                    schedule_interval_hours: None,
+                    compression_level: None,
                 },
                 restore: RestoreConfig {
                     path: StrictPath::new(s("~/restore")),
                     ignored_games: std::collections::HashSet::new(),
+                    frozen_games: Default::default(),
                     redirects: vec![],
                     sort: Default::default(),
+                    preserve_timestamps: false,
+                    undo_snapshot: false,
+                    check_running_games: false,
+                    game_redirects: Default::default(),
+                    mode: Default::default(),
+                    skip_identical: true,
                 },
                 custom_games: vec![],
+                runtime: Default::default(),
+                badge_order: vec![],
+                profiles: Default::default(),
+                signature: Default::default(),
             },
             config,
         );
     }
 
+    #[test]
+    fn can_switch_profiles_without_changing_base_config() {
+        let base = Config::load_from_string(
+            r#"
+            manifest:
+              url: example.com
+              etag: null
+            roots:
+              - path: ~/steam
+                store: steam
+            backup:
+              path: ~/backup
+            restore:
+              path: ~/restore
+            profiles:
+              nas:
+                path: /mnt/nas/backup
+                retention:
+                  full: 2
+                  differential: 3
+                compressionLevel: 9
+                signature:
+                  key: /mnt/nas/signing.key
+                  verify: require
+              quick:
+                filter:
+                  excludeStoreScreenshots: true
+                compressionLevel: 1
+            "#,
+        )
+        .unwrap();
+        let original = base.clone();
+
+        let nas = base.with_profile("nas").unwrap();
+        assert_eq!(StrictPath::new(s("/mnt/nas/backup")), nas.backup.path);
+        assert_eq!(StrictPath::new(s("/mnt/nas/backup")), nas.restore.path);
+        assert_eq!(
+            Retention {
+                full: 2,
+                differential: 3
+            },
+            nas.backup.retention
+        );
+        assert_eq!(base.backup.filter, nas.backup.filter);
+        assert_eq!(base.roots, nas.roots);
+        assert_eq!(Some(9), nas.backup.compression_level);
+        assert_eq!(
+            SignatureConfig {
+                key: Some(StrictPath::new(s("/mnt/nas/signing.key"))),
+                verify: SignatureVerification::Require,
+                ..Default::default()
+            },
+            nas.signature
+        );
+
+        let quick = base.with_profile("quick").unwrap();
+        assert_eq!(StrictPath::new(s("~/backup")), quick.backup.path);
+        assert_eq!(Retention::default(), quick.backup.retention);
+        assert!(quick.backup.filter.exclude_store_screenshots);
+        assert_eq!(Some(1), quick.backup.compression_level);
+        assert_eq!(base.signature, quick.signature);
+
+        assert_eq!(
+            Err(Error::UnknownProfile { name: s("missing") }),
+            base.with_profile("missing")
+        );
+        assert_eq!(original, base);
+
+        assert_eq!(
+            Err(Error::ConfigInvalid {
+                why: s("The compression level 10 is not between 0 and 9")
+            }),
+            Config::load_from_string(
+                &serde_yaml::to_string(&original)
+                    .unwrap()
+                    .replace("compressionLevel: 9", "compressionLevel: 10")
+            )
+        );
+    }
+
+    #[test]
+    fn reading_config_file_does_not_create_it() {
+        let folder = TempPath::new("ludusavi-test-read-config");
+        let file = folder.joined("config.yaml").as_std_path_buf();
+
+        let missing = Config::read_file(&file);
+        let created = folder.exists();
+
+        std::fs::create_dir_all(folder.interpret()).unwrap();
+        std::fs::write(&file, serde_yaml::to_string(&Config::default()).unwrap()).unwrap();
+        let existing = Config::read_file(&file);
+
+        assert_eq!(Ok(None), missing);
+        assert!(!created);
+        assert_eq!(Ok(Some(Config::default())), existing);
+    }
+
+    #[test]
+    fn can_override_config_with_env_vars() {
+        let vars = |name: &str| match name {
+            "LUDUSAVI_BACKUP_TARGET" => Some(s("/env/backup")),
+            "LUDUSAVI_MANIFEST_URL" => Some(s("https://example.com/manifest.yaml")),
+            "LUDUSAVI_COPY_THREADS" => Some(s("")),
+            _ => None,
+        };
+
+        let mut config = Config::default();
+        let result = config.apply_env_overrides(vars);
+
+        assert_eq!(Ok(()), result);
+        assert_eq!(StrictPath::new(s("/env/backup")), config.backup.path);
+        assert_eq!(s("https://example.com/manifest.yaml"), config.manifest.url);
+        assert_eq!(Config::default().restore.path, config.restore.path);
+        assert_eq!(default_copy_threads(), config.runtime.copy_threads);
+    }
+
+    #[test]
+    fn rejects_invalid_env_overrides() {
+        let vars = |value: &'static str| move |name: &str| (name == "LUDUSAVI_COPY_THREADS").then(|| s(value));
+
+        let mut config = Config::default();
+        assert_eq!(Ok(()), config.apply_env_overrides(vars("8")));
+        assert_eq!(8, config.runtime.copy_threads);
+
+        assert_eq!(
+            Err(Error::ConfigInvalid {
+                why: s("LUDUSAVI_COPY_THREADS: Expected a whole number greater than 0, but got: 0")
+            }),
+            Config::default().apply_env_overrides(vars("0"))
+        );
+    }
+
+    #[test]
+    fn does_not_save_env_overrides() {
+        let stored = Config::default();
+        let mut config = stored.clone();
+        config.backup.path = StrictPath::new(s("/env/backup"));
+        config.restore.path = StrictPath::new(s("/edited/in/gui"));
+
+        config.reset_env_overrides(stored.clone(), |name| {
+            (name == "LUDUSAVI_BACKUP_TARGET").then(|| s("/env/backup"))
+        });
+
+        assert_eq!(stored.backup.path, config.backup.path);
+        assert_eq!(StrictPath::new(s("/edited/in/gui")), config.restore.path);
+    }
+
+    #[test]
+    fn validates_and_prunes_excluded_roots() {
+        let yaml = |excluded: &str| {
+            format!(
+                r#"
+                manifest:
+                  url: example.com
+                  etag: null
+                roots:
+                  - path: ~/steam
+                    store: steam
+                  - path: ~/gog
+                    store: gog
+                backup:
+                  path: ~/backup
+                  filter:
+                    excludedRoots:
+                      game: [{}]
+                restore:
+                  path: ~/restore
+                "#,
+                excluded
+            )
+        };
+
+        let mut config = Config::load_from_string(&yaml("~/gog")).unwrap();
+        let gog = RootsConfig {
+            path: StrictPath::new(s("~/gog")),
+            store: Store::Gog,
+            require: Default::default(),
+        };
+        assert!(config.backup.filter.is_root_excluded("game", &gog));
+        assert!(!config.backup.filter.is_root_excluded("other", &gog));
+        assert!(!config.backup.filter.is_root_excluded("game", &config.roots[0]));
+
+        assert!(matches!(
+            Config::load_from_string(&yaml("~/epic")),
+            Err(Error::ConfigInvalid { .. })
+        ));
+
+        config.roots.retain(|x| x.store != Store::Gog);
+        config.prune_excluded_roots();
+        assert!(config.backup.filter.excluded_roots.is_empty());
+    }
+
+    #[test]
+    fn can_parse_and_render_intervals() {
+        let parse = |x: &str| Interval::try_from(x.to_string());
+        let secs = std::time::Duration::from_secs;
+
+        assert_eq!(Ok(Interval(secs(90))), parse("90s"));
+        assert_eq!(Ok(Interval(secs(30 * 60))), parse("30m"));
+        assert_eq!(Ok(Interval(secs(24 * 60 * 60))), parse("24h"));
+        assert_eq!(Ok(Interval(secs(7 * 24 * 60 * 60))), parse("7d"));
+        assert!(parse("24").is_err());
+        assert!(parse("h").is_err());
+        assert!(parse("").is_err());
+        assert!(parse(&format!("{}d", u64::MAX)).is_err());
+
+        assert_eq!("90s", String::from(Interval(secs(90))));
+        assert_eq!("90m", String::from(Interval(secs(90 * 60))));
+        assert_eq!("1d", String::from(Interval(secs(24 * 60 * 60))));
+    }
+
+    #[test]
+    fn sort_keys_honor_both_directions() {
+        // Two games have the same size, so the name has to break the tie.
+        let games = vec![("b", 2), ("a", 2), ("c", 1)];
+        let expected = [
+            (SortKey::Name, vec!["a", "b", "c"]),
+            (SortKey::Size, vec!["c", "a", "b"]),
+        ];
+        assert_eq!(SortKey::ALL.len(), expected.len());
+
+        for (key, ascending) in expected {
+            for direction in SortDirection::ALL {
+                let mut sort = Sort { key, reversed: false };
+                sort.set_direction(*direction);
+                assert_eq!(*direction, sort.direction());
+
+                let mut sorted = games.clone();
+                sort.apply(&mut sorted, |(name, _)| name.to_string(), |(_, size)| *size);
+                let sorted: Vec<_> = sorted.into_iter().map(|(name, _)| name).collect();
+
+                let mut expected = ascending.clone();
+                if *direction == SortDirection::Descending {
+                    expected.reverse();
+                }
+                assert_eq!(expected, sorted, "{:?} {:?}", key, direction);
+            }
+        }
+    }
+
     #[test]
     fn can_be_serialized() {
         assert_eq!(
@@ -868,6 +2625,10 @@ backup:
   retention:
     full: 1
     differential: 0
+  permissionDenied: skip
+  alternateStreams: strip
+  comparison: sizeAndMtime
+  grouping: flat
 restore:
   path: ~/restore
   ignoredGames:
@@ -880,6 +2641,8 @@ restore:
   sort:
     key: name
     reversed: false
+  mode: additive
+  skipIdentical: true
 customGames:
   - name: Custom Game 1
     files: []
@@ -893,21 +2656,31 @@ customGames:
       - Custom Registry 1
       - Custom Registry 2
       - Custom Registry 2
+runtime:
+  copyThreads: 4
+  retryAttempts: 3
+  retryDelayMs: 100
 "#
             .trim(),
             serde_yaml::to_string(&Config {
                 manifest: ManifestConfig {
                     url: s("example.com"),
                     etag: Some(s("foo")),
+                    delta_url: None,
+                    path: None,
+                    refresh_interval: None,
+                    secondary: vec![],
                 },
                 roots: vec![
                     RootsConfig {
                         path: StrictPath::new(s("~/steam")),
                         store: Store::Steam,
+                        require: Default::default(),
                     },
                     RootsConfig {
                         path: StrictPath::new(s("~/other")),
                         store: Store::Other,
+                        require: Default::default(),
                     },
                 ],
                 backup: BackupConfig {
@@ -927,6 +2700,15 @@ customGames:
                     toggled_registry: Default::default(),
                     sort: Default::default(),
                     retention: Retention::default(),
+                    permission_denied: Default::default(),
+                    alternate_streams: Default::default(),
+                    comparison: Default::default(),
+                    grouping: Default::default(),
+                    extra_targets: vec![],
+                    portable_paths: false,
+                    inactivity: Default::default(),
+                    schedule_interval_hours: None,
+                    compression_level: None,
                 },
                 restore: RestoreConfig {
                     path: StrictPath::new(s("~/restore")),
@@ -935,11 +2717,18 @@ customGames:
                         s("Restore Game 1"),
                         s("Restore Game 2"),
                     },
+                    frozen_games: Default::default(),
                     redirects: vec![RedirectConfig {
                         source: StrictPath::new(s("~/old")),
                         target: StrictPath::new(s("~/new")),
                     }],
                     sort: Default::default(),
+                    preserve_timestamps: false,
+                    undo_snapshot: false,
+                    check_running_games: false,
+                    game_redirects: Default::default(),
+                    mode: Default::default(),
+                    skip_identical: true,
                 },
                 custom_games: vec![
                     CustomGame {
@@ -947,14 +2736,22 @@ customGames:
                         ignore: false,
                         files: vec![],
                         registry: vec![],
+                        registry_filters: Default::default(),
+                        cloud_only: false,
                     },
                     CustomGame {
                         name: s("Custom Game 2"),
                         ignore: false,
                         files: vec![s("Custom File 1"), s("Custom File 2"), s("Custom File 2"),],
                         registry: vec![s("Custom Registry 1"), s("Custom Registry 2"), s("Custom Registry 2"),],
+                        registry_filters: Default::default(),
+                        cloud_only: false,
                     },
                 ],
+                runtime: Default::default(),
+                badge_order: vec![],
+                profiles: Default::default(),
+                signature: Default::default(),
             })
             .unwrap()
             .trim(),
@@ -963,7 +2760,6 @@ customGames:
 
     mod ignored_paths {
         use super::*;
-        use maplit::*;
         use pretty_assertions::assert_eq;
 
         fn repo() -> String {
@@ -1091,7 +2887,6 @@ customGames:
 
     mod ignored_registry {
         use super::*;
-        use maplit::*;
         use pretty_assertions::assert_eq;
 
         fn verify_toggle_registry_bouncing(
@@ -1213,4 +3008,338 @@ customGames:
             );
         }
     }
+
+    #[test]
+    fn applies_game_redirects_before_global_ones() {
+        let redirect = |source: &str, target: &str| RedirectConfig {
+            source: StrictPath::new(s(source)),
+            target: StrictPath::new(s(target)),
+        };
+        let mut config = Config::default();
+        config.restore.redirects = vec![redirect("/old", "/global")];
+        config.restore.game_redirects = btreemap! {
+            s("game1") => vec![redirect("/old", "/game1")],
+            s("unknown") => vec![redirect("/old", "/unknown")],
+        };
+        let original = StrictPath::new(s("/old/save.dat"));
+        let target =
+            |game| crate::prelude::game_file_restoration_target(&original, &config.get_redirects_for_game(game)).0;
+
+        assert_eq!(StrictPath::new(s("/game1/save.dat")), target("game1"));
+        assert_eq!(StrictPath::new(s("/global/save.dat")), target("game2"));
+
+        let manifest = Manifest(hashmap! {
+            s("game1") => Default::default(),
+        });
+        assert_eq!(vec![s("unknown")], config.unknown_redirect_games(&manifest));
+    }
+
+    #[test]
+    fn can_merge_root_candidates_from_mixed_evidence() {
+        let repo_path = |path: &str| format!("{}/{}", env!("CARGO_MANIFEST_DIR"), path);
+        let root = |path: &str, store| RootsConfig {
+            path: StrictPath::new(repo_path(path)),
+            store,
+            require: Default::default(),
+        };
+        let candidate = |path: &str, store, confidence| RootCandidate {
+            root: RootsConfig {
+                path: StrictPath::new(repo_path(path)).rendered(),
+                store,
+                require: Default::default(),
+            },
+            confidence,
+        };
+
+        assert_eq!(
+            vec![
+                candidate("tests/root1", Store::Ea, RootConfidence::Likely),
+                candidate("tests/root2", Store::Steam, RootConfidence::Certain),
+            ],
+            merge_root_candidates(
+                &[root("tests/root3", Store::Other)],
+                vec![
+                    (repo_path("tests/root1"), Store::Origin, RootConfidence::Guess),
+                    (repo_path("tests/root2"), Store::Steam, RootConfidence::Guess),
+                    (repo_path("tests/root3"), Store::Gog, RootConfidence::Guess),
+                    (repo_path("tests/missing"), Store::Gog, RootConfidence::Guess),
+                    (repo_path("tests/root2"), Store::Steam, RootConfidence::Certain),
+                    (repo_path("tests/missing"), Store::Gog, RootConfidence::Certain),
+                    (repo_path("tests/root1"), Store::Ea, RootConfidence::Likely),
+                    (repo_path("tests/root1"), Store::Origin, RootConfidence::Likely),
+                ],
+            ),
+        );
+    }
+
+    #[test]
+    fn can_diff_config_from_default() {
+        let mut config = Config::default();
+        config.backup.path = StrictPath::new(s("/backups"));
+        config.backup.sort = Sort::default();
+        config.restore.preserve_timestamps = false;
+        config.roots.push(RootsConfig {
+            path: StrictPath::new(s("/steam")),
+            store: Store::Steam,
+            require: Default::default(),
+        });
+        config.custom_games.push(CustomGame {
+            name: s("custom"),
+            ignore: false,
+            files: vec![s("/saves")],
+            registry: vec![],
+            registry_filters: Default::default(),
+            cloud_only: false,
+        });
+
+        assert_eq!(
+            vec![
+                ConfigDiff::Changed {
+                    path: s("backup.path"),
+                    default: serde_json::Value::String(Config::default().backup.path.raw()),
+                    value: serde_json::json!("/backups"),
+                },
+                ConfigDiff::Added {
+                    path: s("customGames"),
+                    value: serde_json::json!({
+                        "name": "custom",
+                        "files": ["/saves"],
+                        "registry": [],
+                    }),
+                },
+                ConfigDiff::Added {
+                    path: s("roots"),
+                    value: serde_json::json!({ "path": "/steam", "store": "steam" }),
+                },
+            ],
+            config.diff_from_default(),
+        );
+        assert_eq!(Vec::<ConfigDiff>::new(), Config::default().diff_from_default());
+    }
+
+    #[test]
+    fn can_find_root_overlaps() {
+        let root = |path: &str, store: Store| RootsConfig {
+            path: StrictPath::new(s(path)),
+            store,
+            require: Default::default(),
+        };
+        let config = Config {
+            roots: vec![
+                root("/games", Store::Other),
+                root("/other", Store::Other),
+                root("/games/steam", Store::Other),
+                root("/", Store::Other),
+                root("/other", Store::Other),
+                root("/games/steam", Store::Steam),
+                root("/games", Store::OtherHome),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(None, config.find_root_overlap(0));
+        assert_eq!(None, config.find_root_overlap(1));
+        assert_eq!(Some((0, RootOverlap::Inside)), config.find_root_overlap(2));
+        assert_eq!(Some((0, RootOverlap::Contains)), config.find_root_overlap(3));
+        assert_eq!(Some((1, RootOverlap::Duplicate)), config.find_root_overlap(4));
+        assert_eq!(None, config.find_root_overlap(5));
+        assert_eq!(None, config.find_root_overlap(6));
+        assert_eq!(None, config.find_root_overlap(7));
+    }
+
+    #[test]
+    fn root_with_missing_marker_is_unavailable() {
+        let folder = TempPath::new("ludusavi-test-root-marker");
+        std::fs::create_dir_all(folder.interpret()).unwrap();
+        let config = Config {
+            roots: vec![
+                RootsConfig {
+                    path: folder.clone(),
+                    store: Store::Other,
+                    require: RootRequirement {
+                        marker: Some(s(".mounted")),
+                        mount_point: false,
+                    },
+                },
+                RootsConfig {
+                    path: folder.clone(),
+                    store: Store::Steam,
+                    require: Default::default(),
+                },
+            ],
+            ..Default::default()
+        };
+
+        let before = config.unavailable_roots();
+        std::fs::write(folder.joined(".mounted").interpret(), "").unwrap();
+        let after = config.unavailable_roots();
+
+        assert_eq!(vec![config.roots[0].clone()], before);
+        assert_eq!(Vec::<RootsConfig>::new(), after);
+    }
+
+    #[test]
+    fn can_find_ea_library_roots_from_registered_install_dirs() {
+        assert_eq!(
+            vec![s("C:/Program Files/EA Games"), s("D:/Games")],
+            library_roots(&[
+                s(r"C:\Program Files\EA Games\Mass Effect Legendary Edition\"),
+                s(r"C:\Program Files\EA Games\Battlefield 1"),
+                s("D:/Games/Unravel"),
+                s("d:/games/Dead Space"),
+                s("./game"),
+                s("game"),
+            ]),
+        );
+    }
+
+    #[test]
+    fn can_find_prime_library_roots_from_install_database() {
+        let folder = TempPath::new("ludusavi-test-prime");
+        std::fs::create_dir_all(folder.interpret()).unwrap();
+        let database = folder.joined("GameInstallInfo.sqlite").as_std_path_buf();
+        {
+            let db = rusqlite::Connection::open(&database).unwrap();
+            db.execute_batch(
+                r#"
+                CREATE TABLE DbSet (Id TEXT, InstallDirectory TEXT, ProductTitle TEXT, Installed INTEGER);
+                INSERT INTO DbSet VALUES ('a', 'C:\Amazon Games\Library\Game A', 'Game A', 1);
+                INSERT INTO DbSet VALUES ('b', 'E:\Custom Library\Game B', 'Game B', 1);
+                INSERT INTO DbSet VALUES ('c', 'F:\Uninstalled\Game C', 'Game C', 0);
+                "#,
+            )
+            .unwrap();
+        }
+
+        let roots = prime_library_roots(&database);
+        let missing = prime_library_roots(&folder.joined("missing.sqlite").as_std_path_buf());
+
+        assert_eq!(vec![s("C:/Amazon Games/Library"), s("E:/Custom Library")], roots);
+        assert_eq!(Vec::<String>::new(), missing);
+    }
+
+    #[test]
+    fn can_find_gog_galaxy_library_roots_from_locked_database() {
+        let folder = TempPath::new("ludusavi-test-gog-galaxy");
+        std::fs::create_dir_all(folder.interpret()).unwrap();
+        let database = folder.joined("galaxy-2.0.db").as_std_path_buf();
+        let db = rusqlite::Connection::open(&database).unwrap();
+        db.execute_batch(
+            r#"
+            CREATE TABLE InstalledBaseProducts (productId INTEGER, installationPath TEXT, installationDate TEXT);
+            INSERT INTO InstalledBaseProducts VALUES (1, 'C:\Program Files (x86)\GOG Galaxy\Games\Game A', '');
+            INSERT INTO InstalledBaseProducts VALUES (2, 'D:\GOG Library\Game B', '');
+            "#,
+        )
+        .unwrap();
+        // Like while Galaxy is running.
+        db.execute_batch("BEGIN EXCLUSIVE;").unwrap();
+
+        let roots = gog_galaxy_library_roots(&database);
+        let missing = gog_galaxy_library_roots(&folder.joined("missing.db").as_std_path_buf());
+        db.execute_batch("COMMIT;").unwrap();
+        drop(db);
+
+        assert_eq!(
+            vec![s("C:/Program Files (x86)/GOG Galaxy/Games"), s("D:/GOG Library")],
+            roots
+        );
+        assert_eq!(Vec::<String>::new(), missing);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn can_find_bottles_prefixes_from_bottle_configs() {
+        let folder = TempPath::new("ludusavi-test-bottles");
+        let data_dir = folder.joined("data");
+        let write = |bottle: &str, content: &str| {
+            let file = data_dir.joined(&format!("bottles/bottles/{}/bottle.yml", bottle));
+            file.create_parent_dir().unwrap();
+            std::fs::write(file.interpret(), content).unwrap();
+        };
+        write(
+            "Gaming",
+            r#"
+Name: Gaming
+Path: Gaming
+Custom_Path: false
+Runner: soda-7.0-9
+External_Programs:
+  0b2f4c1e:
+    executable: Celeste.exe
+    name: Celeste
+    path: /home/user/Games/Celeste/Celeste.exe
+  9d1e7a3b:
+    executable: Hades.exe
+    name: Hades
+    path: /home/user/Games/Hades/Hades.exe
+"#,
+        );
+        write(
+            "Elsewhere",
+            r#"
+Name: Elsewhere
+Path: /var/data/custom/Elsewhere
+Custom_Path: true
+"#,
+        );
+        write(
+            "External",
+            r#"
+Name: External
+Path: /run/host/mnt/games/External
+Custom_Path: true
+"#,
+        );
+        write("Broken", "Name: [");
+
+        let found = bottles(&data_dir.as_std_path_buf());
+        let missing = bottles(&folder.joined("missing").as_std_path_buf());
+
+        let data_dir = data_dir.render();
+        assert_eq!(
+            vec![
+                Bottle {
+                    name: s("External"),
+                    prefix: s("/mnt/games/External"),
+                    programs: vec![],
+                },
+                Bottle {
+                    name: s("Gaming"),
+                    prefix: format!("{}/bottles/bottles/Gaming", data_dir),
+                    programs: vec![s("Celeste"), s("Hades")],
+                },
+                Bottle {
+                    name: s("Elsewhere"),
+                    prefix: format!("{}/custom/Elsewhere", data_dir),
+                    programs: vec![],
+                },
+            ],
+            found
+        );
+        assert_eq!(Vec::<Bottle>::new(), missing);
+    }
+
+    #[test]
+    fn has_default_root_templates_for_major_stores() {
+        assert!(!default_root_templates(Store::Steam).is_empty());
+        assert!(!default_root_templates(Store::Gog).is_empty());
+        if cfg!(target_os = "windows") {
+            for store in [
+                Store::Ea,
+                Store::Epic,
+                Store::GogGalaxy,
+                Store::Origin,
+                Store::Uplay,
+                Store::Microsoft,
+            ] {
+                assert!(!default_root_templates(store).is_empty());
+            }
+        } else {
+            assert!(default_root_templates(Store::GogGalaxy).is_empty());
+        }
+        for store in [Store::OtherHome, Store::OtherWine, Store::Other] {
+            assert!(default_root_templates(store).is_empty());
+        }
+    }
 }
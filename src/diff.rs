@@ -0,0 +1,202 @@
+use crate::{
+    layout::BackupLayout,
+    prelude::{are_files_identical, ScannedFile, StrictPath},
+};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Serialize)]
+pub enum Change {
+    #[serde(rename = "added")]
+    Added,
+    #[serde(rename = "removed")]
+    Removed,
+    #[serde(rename = "modified")]
+    Modified,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize)]
+pub struct FileDiff {
+    pub change: Change,
+    /// Size in the first backup, if the file is there.
+    #[serde(rename = "beforeBytes", skip_serializing_if = "Option::is_none")]
+    pub before: Option<u64>,
+    /// Size in the second backup, if the file is there.
+    #[serde(rename = "afterBytes", skip_serializing_if = "Option::is_none")]
+    pub after: Option<u64>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize)]
+pub struct GameDiff {
+    /// `None` when the game is in both backups, but some of its files changed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub change: Option<Change>,
+    /// Keyed by each file's original location.
+    pub files: std::collections::BTreeMap<String, FileDiff>,
+}
+
+/// Only games with some difference are included.
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize)]
+pub struct BackupDiff {
+    pub games: std::collections::BTreeMap<String, GameDiff>,
+}
+
+fn restorable_files(layout: &BackupLayout, game: &str) -> std::collections::HashMap<String, ScannedFile> {
+    layout
+        .game_layout(game)
+        .restorable_files()
+        .into_iter()
+        .filter_map(|file| Some((file.original_path.as_ref()?.raw().replace('\\', "/"), file)))
+        .collect()
+}
+
+fn is_modified(before: &StrictPath, after: &StrictPath) -> bool {
+    !matches!(are_files_identical(before, after), Ok(true))
+}
+
+pub fn diff_backups(before: &BackupLayout, after: &BackupLayout) -> BackupDiff {
+    let before_names: std::collections::BTreeSet<_> = before.restorable_games().into_iter().collect();
+    let after_names: std::collections::BTreeSet<_> = after.restorable_games().into_iter().collect();
+
+    let mut diff = BackupDiff::default();
+    for name in before_names.union(&after_names).cloned() {
+        let in_before = before_names.contains(&name);
+        let in_after = after_names.contains(&name);
+        let before_files = if in_before {
+            restorable_files(before, &name)
+        } else {
+            Default::default()
+        };
+        let after_files = if in_after {
+            restorable_files(after, &name)
+        } else {
+            Default::default()
+        };
+
+        let mut files = std::collections::BTreeMap::new();
+        for (path, old) in &before_files {
+            match after_files.get(path) {
+                None => {
+                    files.insert(
+                        path.clone(),
+                        FileDiff {
+                            change: Change::Removed,
+                            before: Some(old.size),
+                            after: None,
+                        },
+                    );
+                }
+                Some(new) if old.size != new.size || is_modified(&old.path, &new.path) => {
+                    files.insert(
+                        path.clone(),
+                        FileDiff {
+                            change: Change::Modified,
+                            before: Some(old.size),
+                            after: Some(new.size),
+                        },
+                    );
+                }
+                Some(_) => {}
+            }
+        }
+        for (path, new) in &after_files {
+            if !before_files.contains_key(path) {
+                files.insert(
+                    path.clone(),
+                    FileDiff {
+                        change: Change::Added,
+                        before: None,
+                        after: Some(new.size),
+                    },
+                );
+            }
+        }
+
+        let change = match (in_before, in_after) {
+            (false, true) => Some(Change::Added),
+            (true, false) => Some(Change::Removed),
+            _ => None,
+        };
+        if change.is_some() || !files.is_empty() {
+            diff.games.insert(name, GameDiff { change, files });
+        }
+    }
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Retention;
+    use crate::path::TempPath;
+    use maplit::btreemap;
+    use pretty_assertions::assert_eq;
+
+    fn s(text: &str) -> String {
+        text.to_string()
+    }
+
+    fn write_backup(base: &StrictPath, game: &str, files: &[(&str, &str)]) {
+        let folder = base.joined(game);
+        std::fs::create_dir_all(folder.joined("drive-X").interpret()).unwrap();
+        let mapping = format!("name: {}\ndrives:\n  drive-X: 'X:'\n", game);
+        std::fs::write(folder.joined("mapping.yaml").interpret(), mapping).unwrap();
+        for (file, content) in files {
+            std::fs::write(folder.joined("drive-X").joined(file).interpret(), content).unwrap();
+        }
+    }
+
+    #[test]
+    fn can_diff_backups() {
+        let base = TempPath::new("ludusavi-test-diff");
+        let before = base.joined("before");
+        let after = base.joined("after");
+
+        write_backup(
+            &before,
+            "kept",
+            &[("same.txt", "a"), ("changed.txt", "a"), ("gone.txt", "a")],
+        );
+        write_backup(
+            &after,
+            "kept",
+            &[("same.txt", "a"), ("changed.txt", "bb"), ("new.txt", "a")],
+        );
+        write_backup(&before, "old", &[("file.txt", "a")]);
+        write_backup(&after, "new", &[("file.txt", "abc")]);
+        write_backup(&before, "unchanged", &[("file.txt", "a")]);
+        write_backup(&after, "unchanged", &[("file.txt", "a")]);
+
+        let diff = diff_backups(
+            &BackupLayout::new(before, Retention::default()),
+            &BackupLayout::new(after, Retention::default()),
+        );
+
+        assert_eq!(
+            BackupDiff {
+                games: btreemap! {
+                    s("kept") => GameDiff {
+                        change: None,
+                        files: btreemap! {
+                            s("X:/changed.txt") => FileDiff { change: Change::Modified, before: Some(1), after: Some(2) },
+                            s("X:/gone.txt") => FileDiff { change: Change::Removed, before: Some(1), after: None },
+                            s("X:/new.txt") => FileDiff { change: Change::Added, before: None, after: Some(1) },
+                        },
+                    },
+                    s("new") => GameDiff {
+                        change: Some(Change::Added),
+                        files: btreemap! {
+                            s("X:/file.txt") => FileDiff { change: Change::Added, before: None, after: Some(3) },
+                        },
+                    },
+                    s("old") => GameDiff {
+                        change: Some(Change::Removed),
+                        files: btreemap! {
+                            s("X:/file.txt") => FileDiff { change: Change::Removed, before: Some(1), after: None },
+                        },
+                    },
+                },
+            },
+            diff,
+        );
+    }
+}
@@ -0,0 +1,264 @@
+use crate::{
+    config::{Config, RootsConfig, SteamUser},
+    lang::Translator,
+    manifest::{Manifest, Store},
+    prelude::{manifest_override, Error, StrictPath, SKIP},
+    steam::user_folder_glob,
+};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CheckResult {
+    pub status: CheckStatus,
+    pub label: String,
+    /// How to fix the problem, if there is one.
+    pub hint: Option<String>,
+}
+
+impl CheckResult {
+    fn pass(label: String) -> Self {
+        Self {
+            status: CheckStatus::Pass,
+            label,
+            hint: None,
+        }
+    }
+
+    fn warn(label: String, hint: String) -> Self {
+        Self {
+            status: CheckStatus::Warn,
+            label,
+            hint: Some(hint),
+        }
+    }
+
+    fn fail(label: String, hint: String) -> Self {
+        Self {
+            status: CheckStatus::Fail,
+            label,
+            hint: Some(hint),
+        }
+    }
+}
+
+pub fn check_config(translator: &Translator) -> (Option<Config>, CheckResult) {
+    let label = translator.doctor_check_config();
+    match Config::load_existing() {
+        Ok(Some(config)) => (Some(config), CheckResult::pass(label)),
+        // Check the rest against the config that would be used, without saving it.
+        Ok(None) => (
+            Config::load().ok(),
+            CheckResult::warn(label, translator.doctor_hint_config_missing()),
+        ),
+        Err(e) => (None, CheckResult::fail(label, translator.handle_error(&e))),
+    }
+}
+
+pub fn check_manifest(translator: &Translator) -> CheckResult {
+    let label = translator.doctor_check_manifest();
+    if let Some(path) = manifest_override() {
+        return match Manifest::load_local(&path) {
+            Ok(_) => CheckResult::pass(label),
+            Err(e) => CheckResult::fail(label, translator.handle_error(&e)),
+        };
+    }
+    if !Manifest::file().exists() {
+        return CheckResult::warn(label, translator.doctor_hint_manifest_missing());
+    }
+    let content = match std::fs::read_to_string(Manifest::file()) {
+        Ok(x) => x,
+        Err(e) => {
+            return CheckResult::fail(
+                label,
+                translator.handle_error(&Error::ManifestInvalid { why: e.to_string() }),
+            )
+        }
+    };
+    match Manifest::load_from_string(&content) {
+        Ok(_) => CheckResult::pass(label),
+        Err(e) => CheckResult::fail(label, translator.handle_error(&e)),
+    }
+}
+
+/// When there are secondary manifests, make sure that they don't disagree about any games.
+/// This uses whatever main manifest is available already, without downloading it.
+pub fn check_manifest_conflicts(config: &Config, translator: &Translator) -> CheckResult {
+    let label = translator.doctor_check_manifest_conflicts();
+    let primary = match Manifest::local_file(config) {
+        Some(path) => Manifest::load_local(&path).unwrap_or_default(),
+        None => Manifest::load_cached().unwrap_or_default(),
+    };
+    match primary.with_secondary(config) {
+        Ok((_, conflicts)) if conflicts.is_empty() => CheckResult::pass(label),
+        Ok((_, conflicts)) => CheckResult::warn(label, translator.doctor_hint_manifest_conflicts(&conflicts)),
+        Err(e) => CheckResult::fail(label, translator.handle_error(&e)),
+    }
+}
+
+pub fn check_root(root: &RootsConfig, translator: &Translator) -> CheckResult {
+    let label = translator.doctor_check_root(&root.path);
+    let found = root.glob();
+    if found.is_empty() || found.iter().all(|x| !x.path.is_dir()) {
+        return CheckResult::fail(label, translator.doctor_hint_root_missing());
+    }
+    if found
+        .iter()
+        .filter(|x| x.path.is_dir())
+        .any(|x| std::fs::read_dir(x.path.interpret()).is_err())
+    {
+        return CheckResult::fail(label, translator.doctor_hint_root_unreadable());
+    }
+    CheckResult::pass(label)
+}
+
+/// When backups are limited to one Steam account, make sure that some Steam root has its saves.
+pub fn check_steam_user(user: &SteamUser, roots: &[RootsConfig], translator: &Translator) -> CheckResult {
+    let label = translator.doctor_check_steam_user(user);
+    let found = roots
+        .iter()
+        .flat_map(|x| x.glob())
+        .filter(|x| x.store == Store::Steam)
+        .any(|root| {
+            let folder = user_folder_glob(user, &root);
+            folder != SKIP && root.path.joined(&format!("userdata/{}", folder)).is_dir()
+        });
+    if found {
+        CheckResult::pass(label)
+    } else {
+        CheckResult::warn(label, translator.doctor_hint_steam_user_missing())
+    }
+}
+
+/// This doesn't modify the target. If it doesn't exist yet,
+/// then we check the closest folder that does exist instead.
+pub fn check_backup_target(target: &StrictPath, translator: &Translator) -> CheckResult {
+    let label = translator.doctor_check_backup_target(target);
+    let fail = || {
+        CheckResult::fail(
+            label.clone(),
+            translator.handle_error(&Error::CannotPrepareBackupTarget { path: target.clone() }),
+        )
+    };
+
+    let mut dir = target.as_std_path_buf();
+    loop {
+        if dir.is_dir() {
+            break;
+        }
+        if dir.exists() || !dir.pop() {
+            return fail();
+        }
+    }
+
+    let probe = dir.join(".ludusavi-write-test");
+    match std::fs::File::create(&probe) {
+        Ok(_) => {
+            let _ = std::fs::remove_file(&probe);
+            CheckResult::pass(label)
+        }
+        Err(_) => fail(),
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn check_registry(translator: &Translator) -> CheckResult {
+    let label = translator.doctor_check_registry();
+    match winreg::RegKey::predef(winreg::enums::HKEY_CURRENT_USER).open_subkey("Software") {
+        Ok(_) => CheckResult::pass(label),
+        Err(_) => CheckResult::fail(label, translator.handle_error(&Error::RegistryIssue)),
+    }
+}
+
+pub fn run_checks(translator: &Translator) -> Vec<CheckResult> {
+    let mut results = vec![];
+
+    let (config, config_result) = check_config(translator);
+    results.push(config_result);
+    results.push(check_manifest(translator));
+
+    if let Some(config) = config {
+        if !config.manifest.secondary.is_empty() {
+            results.push(check_manifest_conflicts(&config, translator));
+        }
+        for root in &config.roots {
+            results.push(check_root(root, translator));
+        }
+        if !config.backup.filter.steam_user.is_all() {
+            results.push(check_steam_user(
+                &config.backup.filter.steam_user,
+                &config.roots,
+                translator,
+            ));
+        }
+        results.push(check_backup_target(&config.backup.path, translator));
+    }
+
+    #[cfg(target_os = "windows")]
+    results.push(check_registry(translator));
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::TempPath;
+    use pretty_assertions::assert_eq;
+
+    fn s(text: &str) -> String {
+        text.to_string()
+    }
+
+    #[test]
+    fn fails_for_missing_root() {
+        let root = RootsConfig {
+            path: StrictPath::new(s("tests/nonexistent-root")),
+            store: Store::Other,
+            require: Default::default(),
+        };
+        let result = check_root(&root, &Translator::default());
+        assert_eq!(CheckStatus::Fail, result.status);
+        assert!(result.hint.is_some());
+    }
+
+    #[test]
+    fn passes_for_existing_root() {
+        let root = RootsConfig {
+            path: StrictPath::new(s("tests/root1")),
+            store: Store::Other,
+            require: Default::default(),
+        };
+        assert_eq!(CheckStatus::Pass, check_root(&root, &Translator::default()).status);
+    }
+
+    #[test]
+    fn checks_steam_user_folder() {
+        let root = TempPath::new("ludusavi-test-doctor-steam-user");
+        std::fs::create_dir_all(root.joined("userdata/111").interpret()).unwrap();
+        let roots = vec![RootsConfig {
+            path: root.clone(),
+            store: Store::Steam,
+            require: Default::default(),
+        }];
+        let known = check_steam_user(&SteamUser::Id(111), &roots, &Translator::default());
+        let unknown = check_steam_user(&SteamUser::Id(222), &roots, &Translator::default());
+
+        assert_eq!(CheckStatus::Pass, known.status);
+        assert_eq!(CheckStatus::Warn, unknown.status);
+    }
+
+    #[test]
+    fn fails_for_unwritable_backup_target() {
+        // A folder can't be created inside of a file.
+        let target = StrictPath::new(s("tests/root1/game1/subdir/file2.txt/backup"));
+        let result = check_backup_target(&target, &Translator::default());
+        assert_eq!(CheckStatus::Fail, result.status);
+        assert!(result.hint.is_some());
+    }
+}
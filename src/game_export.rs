@@ -0,0 +1,195 @@
+use crate::{
+    archive::{pack, staging_dir, unpack},
+    config::Config,
+    layout::BackupLayout,
+    path::StrictPath,
+    prelude::{prepare_backup_target, Error},
+    retry::Retry,
+    signature::{sign_backup, SigningKey},
+};
+
+const MANIFEST_FILE_NAME: &str = "export.yaml";
+const BACKUP_FOLDER_NAME: &str = "backup";
+
+/// Describes an archive made by `export_game`, so that it can be imported elsewhere.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GameExport {
+    pub name: String,
+    #[serde(rename = "ludusaviVersion")]
+    pub app_version: String,
+    pub exported: chrono::DateTime<chrono::Utc>,
+    /// The game has registry data, which is stored as YAML alongside its files.
+    /// Registry values may refer to paths that only exist on the exporting machine.
+    #[serde(default, skip_serializing_if = "crate::serialization::is_false")]
+    pub registry: bool,
+}
+
+/// Write the latest backup of one game from `source` into a self-contained archive,
+/// for sharing it without the rest of the backups.
+/// The archive is compressed or not based on its extension, like with `migrate_backup`.
+pub fn export_game(
+    source: &StrictPath,
+    name: &str,
+    archive: &StrictPath,
+    config: &Config,
+) -> Result<GameExport, Error> {
+    let from = BackupLayout::new(source.clone(), config.backup.retention.clone());
+    if !from.restorable_games().iter().any(|x| x == name) {
+        return Err(Error::CliUnrecognizedGames {
+            games: vec![name.to_string()],
+        });
+    }
+
+    let fail = || Error::GameCannotBeExported { path: archive.clone() };
+    let retry = Retry::new(&config.runtime);
+    let staging = staging_dir("export-game");
+    let _ = staging.remove();
+    let to = BackupLayout::new(staging.joined(BACKUP_FOLDER_NAME), config.backup.retention.clone());
+    let folder = from
+        .migrate_game(name, &to, true, config.runtime.copy_threads, &retry)
+        .map_err(|_| fail())?;
+    // The rebuilt backup no longer matches the old signature.
+    if let Some(key) = SigningKey::load(&config.signature)? {
+        sign_backup(&folder, &key).map_err(|_| fail())?;
+    }
+
+    let export = GameExport {
+        name: name.to_string(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported: chrono::Utc::now(),
+        registry: to.game_layout(name).registry_file().is_file(),
+    };
+    std::fs::write(
+        staging.joined(MANIFEST_FILE_NAME).interpret(),
+        serde_yaml::to_string(&export).unwrap(),
+    )
+    .map_err(|_| fail())?;
+    pack(&staging, archive, config.backup.compression_level).map_err(|_| fail())?;
+    Ok(export)
+}
+
+/// Add the game from an archive made by `export_game` to the backups in `target`,
+/// where its folder is placed by the current grouping.
+/// If the game already has backups there, this fails, unless `force` is set,
+/// in which case they're replaced.
+pub fn import_game(
+    archive: &StrictPath,
+    target: &StrictPath,
+    config: &Config,
+    force: bool,
+) -> Result<GameExport, Error> {
+    let fail = || Error::GameCannotBeImported { path: archive.clone() };
+    let staging = staging_dir("import-game");
+    unpack(archive, &staging).map_err(|_| fail())?;
+    let export: GameExport = std::fs::read_to_string(staging.joined(MANIFEST_FILE_NAME).interpret())
+        .ok()
+        .and_then(|x| serde_yaml::from_str(&x).ok())
+        .ok_or_else(fail)?;
+
+    let from = BackupLayout::new(staging.joined(BACKUP_FOLDER_NAME), config.backup.retention.clone());
+    if !from.restorable_games().contains(&export.name) {
+        return Err(fail());
+    }
+
+    prepare_backup_target(target, true)?;
+    let layout = || {
+        BackupLayout::new(target.clone(), config.backup.retention.clone())
+            .grouped(config.backup.grouping, &config.roots)
+    };
+    let mut to = layout();
+    if let Some(existing) = to.existing_game_folder(&export.name) {
+        if !force {
+            return Err(Error::CliBackupTargetExists { path: existing });
+        }
+        existing
+            .remove()
+            .map_err(|_| Error::CannotPrepareBackupTarget { path: existing.clone() })?;
+        // Reload so that the new folder is placed by the current grouping.
+        to = layout();
+    }
+
+    from.migrate_game(
+        &export.name,
+        &to,
+        false,
+        config.runtime.copy_threads,
+        &Retry::new(&config.runtime),
+    )
+    .map_err(|_| fail())?;
+    to.record_grouping();
+    Ok(export)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::TempPath;
+    use crate::{
+        config::Retention,
+        prelude::{ScanInfo, ScannedFile},
+    };
+    use maplit::*;
+    use pretty_assertions::assert_eq;
+
+    fn restorable(base: &StrictPath, name: &str) -> Vec<(Option<StrictPath>, u64)> {
+        let mut files: Vec<_> = BackupLayout::new(base.clone(), Retention::default())
+            .game_layout(name)
+            .restorable_files()
+            .into_iter()
+            .map(|x| (x.original_path, x.size))
+            .collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn can_round_trip_one_game_through_archive() {
+        let base = TempPath::new("ludusavi-test-game-export");
+        let source = base.joined("backup");
+        let target = base.joined("imported");
+        let archive = base.joined("game1.tar.gz");
+        let file1 = base.joined("saves/file1.txt");
+        let file2 = base.joined("saves/sub/file2.txt");
+        file2.create_parent_dir().unwrap();
+        std::fs::write(file1.interpret(), "a").unwrap();
+        std::fs::write(file2.interpret(), "bb").unwrap();
+
+        for (name, files) in [
+            (
+                "game1",
+                hashset! { ScannedFile::new(file1.render(), 1), ScannedFile::new(file2.render(), 2) },
+            ),
+            ("game2", hashset! { ScannedFile::new(file1.render(), 1) }),
+        ] {
+            let scan = ScanInfo {
+                game_name: name.to_string(),
+                found_files: files,
+                ..Default::default()
+            };
+            BackupLayout::new(source.clone(), Retention::default())
+                .game_layout(name)
+                .back_up(&scan, &chrono::Utc::now(), 1, &Retry::default());
+        }
+
+        let config = Config::default();
+        let exported = export_game(&source, "game1", &archive, &config).unwrap();
+        let imported = import_game(&archive, &target, &config, false).unwrap();
+        let conflict = import_game(&archive, &target, &config, false);
+        let forced = import_game(&archive, &target, &config, true);
+        let unknown = export_game(&source, "game3", &archive, &config);
+
+        let expected = restorable(&source, "game1");
+        let actual = restorable(&target, "game1");
+        let games = BackupLayout::new(target.clone(), Retention::default()).restorable_games();
+
+        assert_eq!(exported, imported);
+        assert_eq!("game1", imported.name);
+        assert!(!imported.registry);
+        assert_eq!(2, actual.len());
+        assert_eq!(expected, actual);
+        assert_eq!(vec!["game1".to_string()], games);
+        assert!(matches!(conflict, Err(Error::CliBackupTargetExists { .. })));
+        assert!(forced.is_ok());
+        assert!(matches!(unknown, Err(Error::CliUnrecognizedGames { .. })));
+    }
+}
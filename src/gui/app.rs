@@ -1,5 +1,5 @@
 use crate::{
-    config::{Config, CustomGame, RootsConfig},
+    config::{default_root_templates, Config, CustomGame, ProfileChoice, RestoreMode, RootCandidate, RootsConfig},
     gui::{
         backup_screen::BackupScreenComponent,
         common::*,
@@ -9,6 +9,7 @@ use crate::{
         game_list::GameListEntry,
         modal::ModalComponent,
         modal::ModalTheme,
+        notification::Notification,
         other_screen::OtherScreenComponent,
         redirect_editor::RedirectEditorRow,
         restore_screen::RestoreScreenComponent,
@@ -16,14 +17,20 @@ use crate::{
         style,
     },
     lang::Translator,
-    layout::BackupLayout,
+    layout::{BackupLayout, PathAnchors},
+    lock::TargetLock,
     manifest::{Manifest, Store},
     prelude::{
-        app_dir, back_up_game, prepare_backup_target, restore_game, scan_game_for_backup, scan_game_for_restoration,
-        Error, InstallDirRanking, OperationStepDecision, StrictPath,
+        app_dir, back_up_game_to_targets, is_held_back_by_unavailable_roots, prepare_backup_target, restore_game,
+        scan_game_for_backup_with_anchors, scan_game_for_restoration, Error, InstallDirRanking, OperationStepDecision,
+        RestoreOptions, ScanHeartbeat, StrictPath,
     },
-    registry_compat::RegistryItem,
+    registry_compat::{is_reg_file, RegistryItem},
+    retry::Retry,
+    scan_cache::{scan_with_cache, ScanCache},
     shortcuts::Shortcut,
+    signature::{check_restore, sign_game_backups, SigningKey},
+    undo::UndoSnapshot,
 };
 
 use iced::{
@@ -43,9 +50,14 @@ pub fn get_key_pressed(event: iced::keyboard::Event) -> Option<(KeyCode, Modifie
 #[derive(Default)]
 pub struct App {
     config: Config,
+    /// Applied on top of `config` for each operation, but never saved into it.
+    profile: ProfileChoice,
     manifest: Manifest,
     translator: Translator,
     operation: Option<OngoingOperation>,
+    backup_locks: Vec<TargetLock>,
+    /// Shared by the games of an ongoing backup scan, then saved when it's done.
+    scan_cache: Option<std::sync::Arc<std::sync::Mutex<ScanCache>>>,
     screen: Screen,
     modal_theme: Option<ModalTheme>,
     modal: ModalComponent,
@@ -59,31 +71,103 @@ pub struct App {
     other_screen: OtherScreenComponent,
     operation_should_cancel: std::sync::Arc<std::sync::atomic::AtomicBool>,
     progress: DisappearingProgress,
+    notification: Notification,
+    /// Problems that don't belong to any one game entry, like signing failures or
+    /// signature warnings, to show once the operation is done.
+    operation_errors: std::sync::Arc<std::sync::Mutex<Vec<Error>>>,
 }
 
 impl App {
+    /// Show a notification and hide it again after a few seconds.
+    fn notify(&mut self, text: String) -> Command<Message> {
+        let id = self.notification.show(text);
+        Command::perform(
+            async { std::thread::sleep(std::time::Duration::from_secs(3)) },
+            move |_| Message::ClearNotification(id),
+        )
+    }
+
     fn start_backup(&mut self, preview: bool, games: Option<Vec<String>>) -> Command<Message> {
         if self.operation.is_some() {
             return Command::none();
         }
 
-        let backup_path = &self.config.backup.path;
+        let config = match self.config.with_profile_choice(&self.profile) {
+            Ok(x) => x,
+            Err(e) => {
+                self.modal_theme = Some(ModalTheme::Error { variant: e });
+                return Command::none();
+            }
+        };
+
+        let signing_key = if preview {
+            None
+        } else {
+            match SigningKey::load(&config.signature) {
+                Ok(x) => x,
+                Err(e) => {
+                    self.modal_theme = Some(ModalTheme::Error { variant: e });
+                    return Command::none();
+                }
+            }
+        };
+        let signing_key = std::sync::Arc::new(signing_key);
+
+        let backup_path = config.backup.path.clone();
+        let mut layouts = vec![];
+        let mut skipped = vec![];
         if !preview {
-            if let Err(e) = prepare_backup_target(
-                backup_path,
-                if games.is_some() {
-                    true
-                } else {
-                    self.config.backup.merge
-                },
-            ) {
+            let merge = if games.is_some() { true } else { config.backup.merge };
+            match TargetLock::acquire(&backup_path, false) {
+                Ok(lock) => self.backup_locks = vec![lock],
+                Err(e) => {
+                    self.modal_theme = Some(ModalTheme::Error { variant: e });
+                    return Command::none();
+                }
+            }
+            if let Err(e) = prepare_backup_target(&backup_path, merge) {
+                self.backup_locks.clear();
                 self.modal_theme = Some(ModalTheme::Error { variant: e });
                 return Command::none();
             }
+            for (target, retention) in config.backup.targets().into_iter().skip(1) {
+                // An extra target that can't be used is skipped, so the others still get the backup.
+                let prepared = TargetLock::acquire(&target, false).and_then(|lock| {
+                    prepare_backup_target(&target, merge)?;
+                    Ok(lock)
+                });
+                match prepared {
+                    Ok(lock) => {
+                        self.backup_locks.push(lock);
+                        layouts.push(
+                            BackupLayout::new(target, retention)
+                                .grouped(config.backup.grouping, &config.roots)
+                                .with_provenance(&config)
+                                .with_portable_paths(&config)
+                                .with_alternate_streams(config.backup.alternate_streams)
+                                .with_comparison(config.backup.comparison),
+                        );
+                    }
+                    Err(e) => skipped.push(Error::BackupTargetSkipped {
+                        path: target,
+                        origin: Box::new(e),
+                    }),
+                }
+            }
         }
+        // This comes after preparing the target, so that recreating it can apply a new grouping.
+        layouts.insert(
+            0,
+            BackupLayout::new(backup_path.clone(), config.backup.retention.clone())
+                .grouped(config.backup.grouping, &config.roots)
+                .with_provenance(&config)
+                .with_portable_paths(&config)
+                .with_alternate_streams(config.backup.alternate_streams)
+                .with_comparison(config.backup.comparison),
+        );
 
         let mut all_games = self.manifest.clone();
-        for custom_game in &self.config.custom_games {
+        for custom_game in &config.custom_games {
             if custom_game.ignore {
                 continue;
             }
@@ -114,6 +198,7 @@ impl App {
             self.backup_screen.duplicate_detector.clear();
         }
         self.modal_theme = None;
+        *self.operation_errors.lock().unwrap() = skipped;
         self.progress.current = 0.0;
         self.progress.max = all_games.0.len() as f32;
 
@@ -123,58 +208,123 @@ impl App {
             OngoingOperation::Backup
         });
 
-        let config = std::sync::Arc::new(self.config.clone());
-        let layout = std::sync::Arc::new(BackupLayout::new(backup_path.clone(), config.backup.retention.clone()));
-        let filter = std::sync::Arc::new(self.config.backup.filter.clone());
-        let ranking = std::sync::Arc::new(InstallDirRanking::scan(&self.config.roots, &all_games, &subjects));
+        let config = std::sync::Arc::new(config);
+        let layouts = std::sync::Arc::new(layouts);
+        let filter = std::sync::Arc::new(config.backup.filter.clone());
+        // A root that isn't really there (like an unmounted drive) would just look empty.
+        let unavailable_roots = std::sync::Arc::new(config.unavailable_roots());
+        self.operation_errors
+            .lock()
+            .unwrap()
+            .extend(unavailable_roots.iter().map(|root| Error::RootUnavailable {
+                path: root.path.clone(),
+            }));
+        let roots = std::sync::Arc::new(
+            config
+                .roots
+                .iter()
+                .filter(|x| !unavailable_roots.contains(x))
+                .cloned()
+                .collect::<Vec<_>>(),
+        );
+        let ranking = std::sync::Arc::new(InstallDirRanking::scan(&roots, &all_games, &subjects));
+        // Shared by all games so that the copy rate limit applies to the whole operation.
+        let retry = Retry::new(&config.runtime).cancellable(self.operation_should_cancel.clone());
+        let scan_cache = std::sync::Arc::new(std::sync::Mutex::new(ScanCache::load(&config, &None)));
+        self.scan_cache = Some(scan_cache.clone());
+        // Rescanning specific games is usually meant to pick up something new.
+        let reuse_scans = preview && games.is_none();
 
         let mut commands: Vec<Command<Message>> = vec![];
         for key in subjects {
             let game = all_games.0[&key].clone();
             let config = config.clone();
-            let layout = layout.clone();
+            let layouts = layouts.clone();
             let filter = filter.clone();
             let ranking = ranking.clone();
+            let roots = roots.clone();
+            let unavailable_roots = unavailable_roots.clone();
             let steam_id = game.steam.as_ref().and_then(|x| x.id);
             let cancel_flag = self.operation_should_cancel.clone();
-            let merge = self.config.backup.merge;
+            let retry = retry.clone();
+            let scan_cache = scan_cache.clone();
+            let signing_key = signing_key.clone();
+            let operation_errors = self.operation_errors.clone();
+            let merge = config.backup.merge;
             commands.push(Command::perform(
                 async move {
                     if key.trim().is_empty() {
-                        return (None, None, OperationStepDecision::Ignored);
+                        return (None, None, None, OperationStepDecision::Ignored);
                     }
                     if cancel_flag.load(std::sync::atomic::Ordering::Relaxed) {
                         // TODO: https://github.com/hecrj/iced/issues/436
                         std::thread::sleep(std::time::Duration::from_millis(1));
-                        return (None, None, OperationStepDecision::Cancelled);
+                        return (None, None, None, OperationStepDecision::Cancelled);
                     }
 
-                    let scan_info = scan_game_for_backup(
-                        &game,
-                        &key,
-                        &config.roots,
-                        &StrictPath::from_std_path_buf(&app_dir()),
-                        &steam_id,
-                        &filter,
-                        &None,
-                        &ranking,
-                        &config.backup.toggled_paths,
-                        &config.backup.toggled_registry,
-                    );
+                    let scan_info = scan_with_cache(&scan_cache, &key, reuse_scans, || {
+                        scan_game_for_backup_with_anchors(
+                            &game,
+                            &key,
+                            &roots,
+                            &StrictPath::from_std_path_buf(&app_dir()),
+                            &steam_id,
+                            &filter,
+                            &None,
+                            &ranking,
+                            &config.backup.toggled_paths,
+                            &config.backup.toggled_registry,
+                            &mut ScanHeartbeat::none(),
+                        )
+                    });
                     if !config.is_game_enabled_for_backup(&key) {
-                        return (Some(scan_info), None, OperationStepDecision::Ignored);
+                        return (
+                            Some(scan_info),
+                            None,
+                            layouts[0].existing_game_folder(&key),
+                            OperationStepDecision::Ignored,
+                        );
+                    }
+                    if is_held_back_by_unavailable_roots(&layouts[0], &key, &unavailable_roots) {
+                        return (
+                            Some(scan_info),
+                            None,
+                            layouts[0].existing_game_folder(&key),
+                            OperationStepDecision::RootUnavailable,
+                        );
                     }
 
                     let backup_info = if !preview {
-                        Some(back_up_game(&scan_info, &key, &layout, merge, &chrono::Utc::now()))
+                        let mut backup_info = back_up_game_to_targets(
+                            &scan_info,
+                            &key,
+                            &layouts,
+                            merge,
+                            &chrono::Utc::now(),
+                            config.runtime.copy_threads,
+                            &retry,
+                            config.backup.permission_denied,
+                        );
+                        if let Some(signing_key) = signing_key.as_ref() {
+                            let errors = sign_game_backups(&layouts, &key, signing_key, &mut backup_info);
+                            operation_errors.lock().unwrap().extend(errors);
+                        }
+                        Some(backup_info)
                     } else {
                         None
                     };
-                    (Some(scan_info), backup_info, OperationStepDecision::Processed)
+                    let backup_dir = layouts[0].existing_game_folder(&key);
+                    (
+                        Some(scan_info),
+                        backup_info,
+                        backup_dir,
+                        OperationStepDecision::Processed,
+                    )
                 },
-                move |(scan_info, backup_info, decision)| Message::BackupStep {
+                move |(scan_info, backup_info, backup_dir, decision)| Message::BackupStep {
                     scan_info,
                     backup_info,
+                    backup_dir,
                     decision,
                     preview,
                 },
@@ -189,22 +339,55 @@ impl App {
             return Command::none();
         }
 
-        let restore_path = &self.config.restore.path;
+        let config = match self.config.with_profile_choice(&self.profile) {
+            Ok(x) => x,
+            Err(e) => {
+                self.modal_theme = Some(ModalTheme::Error { variant: e });
+                return Command::none();
+            }
+        };
+
+        let restore_path = config.restore.path.clone();
         if !restore_path.is_dir() {
             self.modal_theme = Some(ModalTheme::Error {
-                variant: Error::RestorationSourceInvalid {
-                    path: restore_path.clone(),
-                },
+                variant: Error::RestorationSourceInvalid { path: restore_path },
             });
             return Command::none();
         }
 
-        let config = std::sync::Arc::new(self.config.clone());
-        let layout = std::sync::Arc::new(BackupLayout::new(restore_path.clone(), config.backup.retention.clone()));
+        let config = std::sync::Arc::new(config);
+        let layout = std::sync::Arc::new(
+            BackupLayout::new(restore_path, config.backup.retention.clone())
+                .with_anchors(PathAnchors::current(&config.roots)),
+        );
         let mut restorables = layout.restorable_games();
+        if let Some(games) = &games {
+            restorables.retain(|v| games.contains(v));
+        }
+        // Warnings don't stop the restore, so they're shown once it's done.
+        let signature_warnings = match check_restore(&layout, &restorables, &config.signature) {
+            Ok(x) => x,
+            Err(e) => {
+                self.modal_theme = Some(ModalTheme::Error { variant: e });
+                return Command::none();
+            }
+        };
+        if !preview && config.restore.check_running_games {
+            let running = crate::process::running_process_names();
+            if let Err(e) = crate::process::check_restore(&self.manifest, &restorables, &running, false) {
+                self.modal_theme = Some(ModalTheme::Error { variant: e });
+                return Command::none();
+            }
+        }
+        let undo = if !preview && config.restore.undo_snapshot {
+            let undo = UndoSnapshot::default();
+            undo.clear();
+            Some(undo)
+        } else {
+            None
+        };
 
         if let Some(games) = games {
-            restorables.retain(|v| games.contains(v));
             self.restore_screen
                 .log
                 .entries
@@ -214,6 +397,7 @@ impl App {
             self.restore_screen.duplicate_detector.clear();
         }
         self.modal_theme = None;
+        *self.operation_errors.lock().unwrap() = signature_warnings;
 
         if restorables.is_empty() {
             return Command::none();
@@ -227,34 +411,71 @@ impl App {
         self.progress.current = 0.0;
         self.progress.max = restorables.len() as f32;
 
+        let options = RestoreOptions {
+            preserve_timestamps: config.restore.preserve_timestamps,
+            // Removing files needs a confirmation that lists them, which only the CLI has.
+            mode: RestoreMode::Additive,
+            copy_threads: config.runtime.copy_threads,
+            // Shared by all games so that the copy rate limit applies to the whole operation.
+            retry: Retry::new(&config.runtime).cancellable(self.operation_should_cancel.clone()),
+            undo,
+            skip_identical: config.restore.skip_identical,
+            ..Default::default()
+        };
+
         let mut commands: Vec<Command<Message>> = vec![];
         for name in restorables {
             let config = config.clone();
             let layout = layout.clone();
             let cancel_flag = self.operation_should_cancel.clone();
+            let options = options.clone();
             commands.push(Command::perform(
                 async move {
                     if cancel_flag.load(std::sync::atomic::Ordering::Relaxed) {
                         // TODO: https://github.com/hecrj/iced/issues/436
                         std::thread::sleep(std::time::Duration::from_millis(1));
-                        return (None, None, OperationStepDecision::Cancelled);
+                        return (None, None, None, OperationStepDecision::Cancelled);
                     }
 
                     let scan_info = scan_game_for_restoration(&name, &layout);
                     if !config.is_game_enabled_for_restore(&name) {
-                        return (Some(scan_info), None, OperationStepDecision::Ignored);
+                        return (
+                            Some(scan_info),
+                            None,
+                            layout.existing_game_folder(&name),
+                            OperationStepDecision::Ignored,
+                        );
+                    }
+                    if config.is_game_frozen_for_restore(&name) {
+                        return (
+                            Some(scan_info),
+                            None,
+                            layout.existing_game_folder(&name),
+                            OperationStepDecision::Frozen,
+                        );
                     }
 
                     let backup_info = if !preview {
-                        Some(restore_game(&scan_info, &config.get_redirects()))
+                        Some(restore_game(
+                            &scan_info,
+                            &config.get_redirects_for_game(&name),
+                            &options,
+                        ))
                     } else {
                         None
                     };
-                    (Some(scan_info), backup_info, OperationStepDecision::Processed)
+                    let backup_dir = layout.existing_game_folder(&name);
+                    (
+                        Some(scan_info),
+                        backup_info,
+                        backup_dir,
+                        OperationStepDecision::Processed,
+                    )
                 },
-                move |(scan_info, backup_info, decision)| Message::RestoreStep {
+                move |(scan_info, backup_info, backup_dir, decision)| Message::RestoreStep {
                     scan_info,
                     backup_info,
+                    backup_dir,
                     decision,
                 },
             ));
@@ -262,6 +483,29 @@ impl App {
 
         Command::batch(commands)
     }
+
+    /// What to show when an operation is done, if any game failed or there were other problems.
+    fn completion_modal(&self, screen: Screen) -> Option<ModalTheme> {
+        let log = match screen {
+            Screen::Restore => &self.restore_screen.log,
+            _ => &self.backup_screen.log,
+        };
+        let mut errors = std::mem::take(&mut *self.operation_errors.lock().unwrap());
+        if log
+            .entries
+            .iter()
+            .any(|x| x.backup_info.as_ref().map(|x| !x.successful()).unwrap_or(false))
+        {
+            errors.insert(0, Error::SomeEntriesFailed);
+        }
+        match errors.len() {
+            0 => None,
+            1 => Some(ModalTheme::Error {
+                variant: errors.remove(0),
+            }),
+            _ => Some(ModalTheme::Errors { variants: errors }),
+        }
+    }
 }
 
 impl Application for App {
@@ -279,7 +523,9 @@ impl Application for App {
                 Config::default()
             }
         };
-        let manifest = match Manifest::load(&mut config, true) {
+        // With a refresh interval, only check for updates once the cache is old enough.
+        let update = config.manifest.refresh_interval.is_none();
+        let manifest = match Manifest::load(&mut config, update) {
             Ok(x) => x,
             Err(x) => {
                 modal_theme = Some(ModalTheme::Error { variant: x });
@@ -290,6 +536,10 @@ impl Application for App {
             }
         };
 
+        for game in config.unknown_redirect_games(&manifest) {
+            log::warn!("Redirects are configured for unknown game: {}", game);
+        }
+
         (
             Self {
                 backup_screen: BackupScreenComponent::new(&config),
@@ -314,6 +564,7 @@ impl Application for App {
         match message {
             Message::Idle => {
                 self.operation = None;
+                self.backup_locks.clear();
                 self.modal_theme = None;
                 self.progress.current = 0.0;
                 self.progress.max = 0.0;
@@ -323,7 +574,14 @@ impl Application for App {
             }
             Message::Ignore => Command::none(),
             Message::ConfirmBackupStart { games } => {
-                self.modal_theme = Some(ModalTheme::ConfirmBackup { games });
+                // A single game is always merged, so the target keeps its grouping.
+                let merge = games.is_some() || self.config.backup.merge;
+                let grouping_kept = merge
+                    && BackupLayout::new(self.config.backup.path.clone(), self.config.backup.retention.clone())
+                        .grouped(self.config.backup.grouping, &self.config.roots)
+                        .grouping()
+                        != self.config.backup.grouping;
+                self.modal_theme = Some(ModalTheme::ConfirmBackup { games, grouping_kept });
                 Command::none()
             }
             Message::ConfirmRestoreStart { games } => {
@@ -335,19 +593,23 @@ impl Application for App {
             Message::BackupStep {
                 scan_info,
                 backup_info,
+                backup_dir,
                 decision: _,
                 preview,
             } => {
                 self.progress.current += 1.0;
                 if let Some(scan_info) = scan_info {
-                    if scan_info.found_anything() {
+                    if scan_info.found_anything() || scan_info.cloud_only {
                         self.backup_screen.duplicate_detector.add_game(&scan_info);
-                        self.backup_screen
-                            .recent_found_games
-                            .insert(scan_info.game_name.clone());
+                        if scan_info.found_anything() {
+                            self.backup_screen
+                                .recent_found_games
+                                .insert(scan_info.game_name.clone());
+                        }
                         self.backup_screen.log.entries.push(GameListEntry {
                             scan_info,
                             backup_info,
+                            backup_dir,
                             ..Default::default()
                         });
                         self.backup_screen.log.sort(&self.config.backup.sort);
@@ -362,6 +624,7 @@ impl Application for App {
             Message::RestoreStep {
                 scan_info,
                 backup_info,
+                backup_dir,
                 decision: _,
             } => {
                 self.progress.current += 1.0;
@@ -371,6 +634,7 @@ impl Application for App {
                         self.restore_screen.log.entries.push(GameListEntry {
                             scan_info,
                             backup_info,
+                            backup_dir,
                             ..Default::default()
                         });
                         self.restore_screen.log.sort(&self.config.restore.sort);
@@ -403,31 +667,30 @@ impl Application for App {
                 Command::none()
             }
             Message::BackupComplete { preview } => {
-                for entry in &self.backup_screen.log.entries {
-                    if let Some(backup_info) = &entry.backup_info {
-                        if !backup_info.successful() {
-                            self.modal_theme = Some(ModalTheme::Error {
-                                variant: Error::SomeEntriesFailed,
-                            });
-                            return Command::none();
-                        }
+                let mut clear_notification = Command::none();
+                if let Some(scan_cache) = self.scan_cache.take() {
+                    let scan_cache = scan_cache.lock().unwrap();
+                    scan_cache.save();
+                    if scan_cache.hits() > 0 {
+                        clear_notification = self.notify(self.translator.scan_cache_used(scan_cache.hits()));
                     }
                 }
+                if let Some(theme) = self.completion_modal(Screen::Backup) {
+                    self.modal_theme = Some(theme);
+                    return clear_notification;
+                }
                 if !preview {
                     self.backup_screen.recent_found_games.clear();
                 }
-                Command::perform(async move {}, move |_| Message::Idle)
+                Command::batch(vec![
+                    clear_notification,
+                    Command::perform(async move {}, move |_| Message::Idle),
+                ])
             }
             Message::RestoreComplete => {
-                for entry in &self.restore_screen.log.entries {
-                    if let Some(backup_info) = &entry.backup_info {
-                        if !backup_info.successful() {
-                            self.modal_theme = Some(ModalTheme::Error {
-                                variant: Error::SomeEntriesFailed,
-                            });
-                            return Command::none();
-                        }
-                    }
+                if let Some(theme) = self.completion_modal(Screen::Restore) {
+                    self.modal_theme = Some(theme);
+                    return Command::none();
                 }
                 Command::perform(async move {}, move |_| Message::Idle)
             }
@@ -459,6 +722,10 @@ impl Application for App {
                 self.config.save();
                 Command::none()
             }
+            Message::SelectedProfile(choice) => {
+                self.profile = choice;
+                Command::none()
+            }
             Message::FindRoots => {
                 let missing = self.config.find_missing_roots();
                 if missing.is_empty() {
@@ -469,7 +736,7 @@ impl Application for App {
                 Command::none()
             }
             Message::ConfirmAddMissingRoots(missing) => {
-                for root in missing {
+                for RootCandidate { root, .. } in missing {
                     let mut row = RootEditorRow::default();
                     row.text_history.push(&root.path.render());
                     self.backup_screen.root_editor.rows.push(row);
@@ -485,6 +752,7 @@ impl Application for App {
                         self.config.roots.push(RootsConfig {
                             path: StrictPath::default(),
                             store: Store::Other,
+                            require: Default::default(),
                         });
                     }
                     EditAction::Change(index, value) => {
@@ -494,6 +762,7 @@ impl Application for App {
                     EditAction::Remove(index) => {
                         self.backup_screen.root_editor.rows.remove(index);
                         self.config.roots.remove(index);
+                        self.config.prune_excluded_roots();
                     }
                 }
                 self.config.save();
@@ -501,6 +770,18 @@ impl Application for App {
             }
             Message::SelectedRootStore(index, store) => {
                 self.config.roots[index].store = store;
+                if self.config.roots[index].path.raw().trim().is_empty() {
+                    // Prefill a likely location, preferring one that actually exists.
+                    let templates = default_root_templates(store);
+                    if let Some(template) = templates
+                        .iter()
+                        .find(|x| StrictPath::new(x.to_string()).is_dir())
+                        .or_else(|| templates.first())
+                    {
+                        self.backup_screen.root_editor.rows[index].text_history.push(template);
+                        self.config.roots[index].path.reset(template.clone());
+                    }
+                }
                 self.config.save();
                 Command::none()
             }
@@ -584,6 +865,7 @@ impl Application for App {
                 Command::none()
             }
             Message::EditedCustomGameRegistry(game_index, action) => {
+                let check_file = matches!(&action, EditAction::Change(_, value) if is_reg_file(value));
                 match action {
                     EditAction::Add => {
                         self.custom_games_screen.games_editor.entries[game_index]
@@ -605,6 +887,11 @@ impl Application for App {
                     }
                 }
                 self.config.save();
+                if check_file {
+                    if let Err(e) = self.config.custom_games[game_index].check_registry_files() {
+                        self.modal_theme = Some(ModalTheme::Error { variant: e });
+                    }
+                }
                 Command::none()
             }
             Message::EditedExcludeOtherOsData(enabled) => {
@@ -617,6 +904,16 @@ impl Application for App {
                 self.config.save();
                 Command::none()
             }
+            Message::EditedExcludeHiddenFiles(enabled) => {
+                self.config.backup.filter.exclude_hidden_files = enabled;
+                self.config.save();
+                Command::none()
+            }
+            Message::EditedTagIncluded(tag, included) => {
+                self.config.backup.filter.set_tag_excluded(tag, !included);
+                self.config.save();
+                Command::none()
+            }
             Message::EditedBackupFilterIgnoredPath(action) => {
                 match action {
                     EditAction::Add => {
@@ -786,6 +1083,30 @@ impl Application for App {
                 }
                 Command::none()
             }
+            Message::EditedSearchScope { screen, value } => {
+                match screen {
+                    Screen::Backup => {
+                        self.backup_screen.log.search.scope = value;
+                    }
+                    Screen::Restore => {
+                        self.restore_screen.log.search.scope = value;
+                    }
+                    _ => {}
+                }
+                Command::none()
+            }
+            Message::EditedSearchFailedOnly { screen, value } => {
+                match screen {
+                    Screen::Backup => {
+                        self.backup_screen.log.search.failed_only = value;
+                    }
+                    Screen::Restore => {
+                        self.restore_screen.log.search.failed_only = value;
+                    }
+                    _ => {}
+                }
+                Command::none()
+            }
             Message::EditedSortKey { screen, value } => {
                 match screen {
                     Screen::Backup => {
@@ -801,14 +1122,14 @@ impl Application for App {
                 self.config.save();
                 Command::none()
             }
-            Message::EditedSortReversed { screen, value } => {
+            Message::EditedSortDirection { screen, value } => {
                 match screen {
                     Screen::Backup => {
-                        self.config.backup.sort.reversed = value;
+                        self.config.backup.sort.set_direction(value);
                         self.backup_screen.log.sort(&self.config.backup.sort);
                     }
                     Screen::Restore => {
-                        self.config.restore.sort.reversed = value;
+                        self.config.restore.sort.set_direction(value);
                         self.restore_screen.log.sort(&self.config.restore.sort);
                     }
                     _ => {}
@@ -903,6 +1224,15 @@ impl Application for App {
                         ignore: false,
                         files: standard.files.clone().unwrap_or_default().keys().cloned().collect(),
                         registry: standard.registry.clone().unwrap_or_default().keys().cloned().collect(),
+                        registry_filters: standard
+                            .registry
+                            .clone()
+                            .unwrap_or_default()
+                            .into_iter()
+                            .filter(|(_, entry)| !entry.filter.is_empty())
+                            .map(|(key, entry)| (key, entry.filter))
+                            .collect(),
+                        cloud_only: standard.cloud_only,
                     }
                 } else {
                     CustomGame {
@@ -910,6 +1240,8 @@ impl Application for App {
                         ignore: false,
                         files: vec![],
                         registry: vec![],
+                        registry_filters: Default::default(),
+                        cloud_only: false,
                     }
                 };
 
@@ -941,6 +1273,26 @@ impl Application for App {
                 });
                 Command::none()
             }
+            Message::CopyToClipboard(text) => Command::batch(vec![
+                iced::clipboard::write(text.clone()),
+                // Writing doesn't report failures, like when there's no clipboard, so check that it took.
+                iced::clipboard::read(move |copied| Message::CopiedToClipboard {
+                    copied: copied.as_ref() == Some(&text),
+                    text: text.clone(),
+                }),
+            ]),
+            Message::CopiedToClipboard { text, copied } => {
+                let message = if copied {
+                    self.translator.copied_to_clipboard(&text)
+                } else {
+                    self.translator.unable_to_copy_to_clipboard(&text)
+                };
+                self.notify(message)
+            }
+            Message::ClearNotification(id) => {
+                self.notification.clear(id);
+                Command::none()
+            }
             Message::OpenWiki { game } => {
                 let url = format!("https://www.pcgamingwiki.com/wiki/{}", game.replace(' ', "_"));
                 let url2 = url.clone();
@@ -1207,14 +1559,20 @@ impl Application for App {
             )
             .push(
                 match self.screen {
-                    Screen::Backup => {
-                        self.backup_screen
-                            .view(&self.config, &self.manifest, &self.translator, &self.operation)
-                    }
-                    Screen::Restore => {
-                        self.restore_screen
-                            .view(&self.config, &self.manifest, &self.translator, &self.operation)
-                    }
+                    Screen::Backup => self.backup_screen.view(
+                        &self.config,
+                        &self.profile,
+                        &self.manifest,
+                        &self.translator,
+                        &self.operation,
+                    ),
+                    Screen::Restore => self.restore_screen.view(
+                        &self.config,
+                        &self.profile,
+                        &self.manifest,
+                        &self.translator,
+                        &self.operation,
+                    ),
                     Screen::CustomGames => {
                         self.custom_games_screen
                             .view(&self.config, &self.translator, &self.operation)
@@ -1224,6 +1582,7 @@ impl Application for App {
                 .padding([0, 5, 5, 5])
                 .height(Length::FillPortion(10_000)),
             )
+            .push_some(|| self.notification.view())
             .push(self.progress.view())
             .into()
     }
@@ -1,9 +1,9 @@
 use crate::{
-    config::Config,
+    config::{Config, ProfileChoice},
     gui::{
         common::*,
         game_list::GameList,
-        icon::Icon,
+        icon::{labeled, Icon},
         root_editor::{RootEditor, RootEditorRow},
         style,
     },
@@ -14,8 +14,10 @@ use crate::{
 };
 
 use iced::{
-    alignment::Horizontal as HorizontalAlignment, button, text_input, Alignment, Button, Checkbox, Column, Container,
-    Length, Row, Text, TextInput,
+    alignment::Horizontal as HorizontalAlignment,
+    button,
+    pick_list::{self, PickList},
+    text_input, Alignment, Button, Checkbox, Column, Container, Element, Length, Row, Text, TextInput,
 };
 
 #[derive(Default)]
@@ -30,6 +32,7 @@ pub struct BackupScreenComponent {
     pub backup_target_input: text_input::State,
     pub backup_target_history: TextHistory,
     backup_target_browse_button: button::State,
+    profile_state: pick_list::State<ProfileChoice>,
     pub root_editor: RootEditor,
     pub recent_found_games: std::collections::HashSet<String>,
     pub duplicate_detector: DuplicateDetector,
@@ -54,10 +57,16 @@ impl BackupScreenComponent {
     pub fn view(
         &mut self,
         config: &Config,
+        profile: &ProfileChoice,
         manifest: &Manifest,
         translator: &Translator,
         operation: &Option<OngoingOperation>,
     ) -> Container<Message> {
+        let status = self.log.compute_operation_status(config, false);
+        let scanned = !self.log.entries.is_empty();
+        let nothing_selected = scanned && status.processed_games == 0;
+        let estimated_bytes = self.log.estimated_backup_bytes(config, &self.duplicate_detector);
+
         Container::new(
             Column::new()
                 .align_items(Alignment::Center)
@@ -92,8 +101,8 @@ impl BackupScreenComponent {
                                 _ => style::Button::Disabled,
                             }),
                         )
-                        .push(
-                            Button::new(
+                        .push({
+                            let button = Button::new(
                                 &mut self.start_button,
                                 Text::new(match operation {
                                     Some(OngoingOperation::Backup) => translator.cancel_button(),
@@ -103,17 +112,24 @@ impl BackupScreenComponent {
                                 .horizontal_alignment(HorizontalAlignment::Center),
                             )
                             .on_press(match operation {
+                                None if nothing_selected => Message::Ignore,
                                 None => Message::ConfirmBackupStart { games: None },
                                 Some(OngoingOperation::Backup) => Message::CancelOperation,
                                 _ => Message::Ignore,
                             })
                             .width(Length::Units(125))
                             .style(match operation {
+                                None if nothing_selected => style::Button::Disabled,
                                 None => style::Button::Primary,
                                 Some(OngoingOperation::Backup) => style::Button::Negative,
                                 _ => style::Button::Disabled,
-                            }),
-                        )
+                            });
+                            if operation.is_none() && nothing_selected {
+                                Element::from(labeled(button, translator.no_games_selected()))
+                            } else {
+                                Element::from(button)
+                            }
+                        })
                         .push(
                             Button::new(
                                 &mut self.add_root_button,
@@ -153,7 +169,7 @@ impl BackupScreenComponent {
                             .width(Length::Units(125))
                             .style(style::Button::Primary)
                         })
-                        .push(
+                        .push(labeled(
                             Button::new(&mut self.toggle_search_button, Icon::Search.as_text())
                                 .on_press(Message::ToggleSearch { screen: Screen::Backup })
                                 .style(if self.log.search.show {
@@ -161,18 +177,39 @@ impl BackupScreenComponent {
                                 } else {
                                     style::Button::Primary
                                 }),
-                        ),
+                            translator.search_button(),
+                        )),
                 )
                 .push(make_status_row(
                     translator,
-                    &self.log.compute_operation_status(config, false),
+                    &status,
                     self.duplicate_detector.any_duplicates(),
                 ))
+                .push_if(
+                    || scanned,
+                    || Text::new(translator.estimated_backup_size(estimated_bytes)),
+                )
+                .push_if(
+                    || !status.warnings.is_empty(),
+                    || make_warnings_panel(translator, &status),
+                )
                 .push(
                     Row::new()
                         .padding([0, 20, 0, 20])
                         .spacing(20)
                         .align_items(Alignment::Center)
+                        .push_if(|| !config.profiles.is_empty(), || Text::new(translator.profile_label()))
+                        .push_if(
+                            || !config.profiles.is_empty(),
+                            || {
+                                PickList::new(
+                                    &mut self.profile_state,
+                                    ProfileChoice::all(config),
+                                    Some(profile.clone()),
+                                    Message::SelectedProfile,
+                                )
+                            },
+                        )
                         .push(Text::new(translator.backup_target_label()))
                         .push(
                             TextInput::new(
@@ -191,6 +228,7 @@ impl BackupScreenComponent {
                                     &translator.full_retention(),
                                     1..=9,
                                     Message::EditedFullRetention,
+                                    translator,
                                 )
                             },
                         )
@@ -202,6 +240,7 @@ impl BackupScreenComponent {
                                     &translator.differential_retention(),
                                     0..=9,
                                     Message::EditedDiffRetention,
+                                    translator,
                                 )
                             },
                         )
@@ -210,7 +249,7 @@ impl BackupScreenComponent {
                             translator.backup_merge_label(),
                             Message::EditedBackupMerge,
                         ))
-                        .push(
+                        .push(labeled(
                             Button::new(&mut self.backup_target_browse_button, Icon::FolderOpen.as_text())
                                 .on_press(match operation {
                                     None => Message::BrowseDir(BrowseSubject::BackupTarget),
@@ -220,7 +259,8 @@ impl BackupScreenComponent {
                                     None => style::Button::Primary,
                                     Some(_) => style::Button::Disabled,
                                 }),
-                        ),
+                            translator.browse_button(),
+                        )),
                 )
                 .push(self.root_editor.view(config, translator, operation))
                 .push(
@@ -1,13 +1,13 @@
 use crate::{
-    config::{RootsConfig, SortKey},
+    config::{ProfileChoice, RootCandidate, SearchScope, SortDirection, SortKey},
     gui::badge::Badge,
     lang::Translator,
-    manifest::Store,
+    manifest::{Store, Tag},
     prelude::{BackupInfo, OperationStatus, OperationStepDecision, RegistryItem, ScanInfo, StrictPath},
     shortcuts::{Shortcut, TextHistory},
 };
 
-use iced::{Alignment, Row, Text};
+use iced::{Alignment, Column, Row, Text};
 
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -30,12 +30,14 @@ pub enum Message {
     BackupStep {
         scan_info: Option<ScanInfo>,
         backup_info: Option<BackupInfo>,
+        backup_dir: Option<StrictPath>,
         decision: OperationStepDecision,
         preview: bool,
     },
     RestoreStep {
         scan_info: Option<ScanInfo>,
         backup_info: Option<BackupInfo>,
+        backup_dir: Option<StrictPath>,
         decision: OperationStepDecision,
     },
     CancelOperation,
@@ -50,8 +52,9 @@ pub enum Message {
     EditedBackupTarget(String),
     EditedBackupMerge(bool),
     EditedRestoreSource(String),
+    SelectedProfile(ProfileChoice),
     FindRoots,
-    ConfirmAddMissingRoots(Vec<RootsConfig>),
+    ConfirmAddMissingRoots(Vec<RootCandidate>),
     EditedRoot(EditAction),
     SelectedRootStore(usize, Store),
     EditedRedirect(EditAction, Option<RedirectEditActionField>),
@@ -60,6 +63,8 @@ pub enum Message {
     EditedCustomGameRegistry(usize, EditAction),
     EditedExcludeOtherOsData(bool),
     EditedExcludeStoreScreenshots(bool),
+    EditedExcludeHiddenFiles(bool),
+    EditedTagIncluded(Tag, bool),
     EditedBackupFilterIgnoredPath(EditAction),
     EditedBackupFilterIgnoredRegistry(EditAction),
     SwitchScreen(Screen),
@@ -96,13 +101,21 @@ pub enum Message {
         screen: Screen,
         value: String,
     },
+    EditedSearchScope {
+        screen: Screen,
+        value: SearchScope,
+    },
+    EditedSearchFailedOnly {
+        screen: Screen,
+        value: bool,
+    },
     EditedSortKey {
         screen: Screen,
         value: SortKey,
     },
-    EditedSortReversed {
+    EditedSortDirection {
         screen: Screen,
-        value: bool,
+        value: SortDirection,
     },
     BrowseDir(BrowseSubject),
     BrowseDirFailure,
@@ -117,6 +130,12 @@ pub enum Message {
     OpenDirFailure {
         path: StrictPath,
     },
+    CopyToClipboard(String),
+    CopiedToClipboard {
+        text: String,
+        copied: bool,
+    },
+    ClearNotification(usize),
     OpenWiki {
         game: String,
     },
@@ -232,6 +251,18 @@ pub fn make_status_row<'a>(
         )
 }
 
+/// Warnings from the last operation, so that they aren't lost among the games.
+pub fn make_warnings_panel<'a>(translator: &Translator, status: &OperationStatus) -> Column<'a, Message> {
+    let mut column = Column::new()
+        .padding([0, 20, 0, 20])
+        .spacing(5)
+        .push(Text::new(translator.warnings_header()));
+    for (warning, count) in status.collapsed_warnings() {
+        column = column.push(Text::new(translator.warning_with_count(warning, count)));
+    }
+    column
+}
+
 pub trait IcedExtension<'a> {
     fn push_if<E>(self, condition: impl FnOnce() -> bool, element: impl FnOnce() -> E) -> Self
     where
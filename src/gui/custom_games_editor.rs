@@ -4,7 +4,7 @@ use crate::{
         common::Message,
         common::OngoingOperation,
         common::{BrowseSubject, EditAction},
-        icon::Icon,
+        icon::{labeled, Icon},
         style,
     },
     lang::Translator,
@@ -102,11 +102,12 @@ impl CustomGamesEditor {
                                             .width(Length::Fill)
                                             .padding(5),
                                         )
-                                        .push(
+                                        .push(labeled(
                                             Button::new(&mut x.remove_button_state, Icon::Delete.as_text())
                                                 .on_press(Message::EditedCustomGame(EditAction::Remove(i)))
                                                 .style(style::Button::Negative),
-                                        ),
+                                            translator.remove_button(),
+                                        )),
                                 )
                                 .push(
                                     Row::new()
@@ -137,7 +138,7 @@ impl CustomGamesEditor {
                                                                 )
                                                                 .padding(5),
                                                             )
-                                                            .push(
+                                                            .push(labeled(
                                                                 Button::new(
                                                                     &mut xx.browse_button_state,
                                                                     Icon::FolderOpen.as_text(),
@@ -152,8 +153,9 @@ impl CustomGamesEditor {
                                                                     None => style::Button::Primary,
                                                                     Some(_) => style::Button::Disabled,
                                                                 }),
-                                                            )
-                                                            .push(
+                                                                translator.browse_button(),
+                                                            ))
+                                                            .push(labeled(
                                                                 Button::new(
                                                                     &mut xx.button_state,
                                                                     Icon::RemoveCircle.as_text(),
@@ -163,17 +165,19 @@ impl CustomGamesEditor {
                                                                     EditAction::Remove(ii),
                                                                 ))
                                                                 .style(style::Button::Negative),
-                                                            ),
+                                                                translator.remove_button(),
+                                                            )),
                                                     )
                                                 })
-                                                .push(
+                                                .push(labeled(
                                                     Button::new(
                                                         &mut x.add_file_button_state,
                                                         Icon::AddCircle.as_text(),
                                                     )
                                                     .on_press(Message::EditedCustomGameFile(i, EditAction::Add))
                                                     .style(style::Button::Primary),
-                                                ),
+                                                    translator.add_button(),
+                                                )),
                                         ),
                                 )
                                 .push(
@@ -205,7 +209,7 @@ impl CustomGamesEditor {
                                                                 )
                                                                 .padding(5),
                                                             )
-                                                            .push(
+                                                            .push(labeled(
                                                                 Button::new(
                                                                     &mut xx.button_state,
                                                                     Icon::RemoveCircle.as_text(),
@@ -215,17 +219,19 @@ impl CustomGamesEditor {
                                                                     EditAction::Remove(ii),
                                                                 ))
                                                                 .style(style::Button::Negative),
-                                                            ),
+                                                                translator.remove_button(),
+                                                            )),
                                                     )
                                                 })
-                                                .push(
+                                                .push(labeled(
                                                     Button::new(
                                                         &mut x.add_registry_button_state,
                                                         Icon::AddCircle.as_text(),
                                                     )
                                                     .on_press(Message::EditedCustomGameRegistry(i, EditAction::Add))
                                                     .style(style::Button::Primary),
-                                                ),
+                                                    translator.add_button(),
+                                                )),
                                         ),
                                 ),
                         )
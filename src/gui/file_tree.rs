@@ -3,7 +3,7 @@ use crate::{
     gui::{
         badge::Badge,
         common::{IcedExtension, Message},
-        icon::Icon,
+        icon::{labeled, Icon},
         style,
     },
     lang::Translator,
@@ -36,11 +36,13 @@ struct FileTreeNode {
     expand_button: button::State,
     expanded: bool,
     open_button: button::State,
+    copy_button: button::State,
     path: Option<FileTreeNodePath>,
     nodes: std::collections::BTreeMap<String, FileTreeNode>,
     successful: bool,
     ignored: bool,
     duplicated: bool,
+    system: bool,
     redirected_from: Option<StrictPath>,
     node_type: FileTreeNodeType,
 }
@@ -106,8 +108,19 @@ impl FileTreeNode {
         };
 
         if self.nodes.is_empty() {
+            let duplicated = self.duplicated;
+            let successful = self.successful;
+            let system = self.system;
+            let redirected_from = self.redirected_from.clone();
+            let rendered_path = self.path.as_ref().map(|path| match path {
+                FileTreeNodePath::File(path) => path.render(),
+                FileTreeNodePath::Registry(path) => path.render(),
+            });
+            let enabler = make_enabler();
+
             return Container::new(
                 Row::new()
+                    .align_items(Alignment::Center)
                     .padding([0, 0, 0, 35 * level])
                     .push(
                         Icon::SubdirectoryArrowRight
@@ -117,20 +130,39 @@ impl FileTreeNode {
                             .size(25),
                     )
                     .push(Space::new(Length::Units(10), Length::Shrink))
-                    .push_some(make_enabler)
+                    .push_some(|| enabler)
                     .push(Text::new(label))
                     .push_if(
-                        || self.duplicated,
+                        || duplicated,
                         || Badge::new(&translator.badge_duplicated()).left_margin(15).view(),
                     )
                     .push_if(
-                        || !self.successful,
+                        || !successful,
                         || Badge::new(&translator.badge_failed()).left_margin(15).view(),
                     )
+                    .push_if(
+                        || system,
+                        || Badge::new(&translator.badge_system()).left_margin(15).view(),
+                    )
                     .push_some(|| {
-                        self.redirected_from
+                        redirected_from
                             .as_ref()
                             .map(|r| Badge::new(&translator.badge_redirected_from(r)).left_margin(15).view())
+                    })
+                    .push(Space::new(Length::Units(10), Length::Shrink))
+                    .push_some(|| {
+                        rendered_path.map(|path| {
+                            labeled(
+                                Button::new(
+                                    &mut self.copy_button,
+                                    Icon::ContentCopy.as_text().width(Length::Shrink).size(15),
+                                )
+                                .on_press(Message::CopyToClipboard(path))
+                                .style(style::Button::Primary)
+                                .height(Length::Units(25)),
+                                translator.copy_path_button(),
+                            )
+                        })
                     }),
             );
         } else if self.nodes.len() == 1 {
@@ -180,7 +212,7 @@ impl FileTreeNode {
                         .push(Space::new(Length::Units(10), Length::Shrink))
                         .push_some(|| {
                             if let Some(FileTreeNodePath::File(path)) = &self.path {
-                                return Some(
+                                return Some(labeled(
                                     Button::new(
                                         &mut self.open_button,
                                         Icon::OpenInNew.as_text().width(Length::Shrink).size(15),
@@ -188,7 +220,8 @@ impl FileTreeNode {
                                     .on_press(Message::OpenDir { path: path.clone() })
                                     .style(style::Button::Primary)
                                     .height(Length::Units(25)),
-                                );
+                                    translator.open_folder_button(),
+                                ));
                             }
                             None
                         }),
@@ -209,6 +242,7 @@ impl FileTreeNode {
         prefix_keys: &[T],
         successful: bool,
         duplicated: bool,
+        system: bool,
         redirected_from: Option<StrictPath>,
     ) -> &mut Self {
         let node_type = self.node_type.clone();
@@ -239,6 +273,7 @@ impl FileTreeNode {
 
         node.successful = successful;
         node.duplicated = duplicated;
+        node.system = system;
         node.redirected_from = redirected_from;
 
         node
@@ -296,10 +331,11 @@ impl FileTree {
     ) -> Self {
         let mut nodes = std::collections::BTreeMap::<String, FileTreeNode>::new();
 
+        let redirects = config.get_redirects_for_game(&scan_info.game_name);
         for item in scan_info.found_files.iter() {
             let mut redirected_from = None;
             let path_to_show = if let Some(original_path) = &item.original_path {
-                let (target, original_target) = game_file_restoration_target(original_path, &config.get_redirects());
+                let (target, original_target) = game_file_restoration_target(original_path, &redirects);
                 redirected_from = original_target;
                 target.clone()
             } else {
@@ -324,6 +360,7 @@ impl FileTree {
                     &[components[0]],
                     successful,
                     duplicate_detector.is_file_duplicated(item),
+                    item.system,
                     redirected_from,
                 );
         }
@@ -345,6 +382,7 @@ impl FileTree {
                     &components[0..1],
                     successful,
                     duplicate_detector.is_registry_duplicated(&item.path),
+                    false,
                     None,
                 );
         }
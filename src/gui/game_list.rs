@@ -1,19 +1,18 @@
 use crate::{
-    config::{Config, Sort, SortKey, ToggledPaths, ToggledRegistry},
+    config::{Config, RootsConfig, Sort, ToggledPaths, ToggledRegistry},
     gui::{
         badge::Badge,
         common::{IcedExtension, Message, Screen},
         file_tree::FileTree,
-        icon::Icon,
+        icon::{labeled, Icon},
         search::SearchComponent,
         style,
     },
     lang::Translator,
-    manifest::Manifest,
-    prelude::{BackupInfo, DuplicateDetector, OperationStatus, ScanInfo},
+    manifest::{Manifest, Store},
+    prelude::{BackupInfo, DuplicateDetector, OperationStatus, ScanInfo, StrictPath},
 };
 
-use fuzzy_matcher::FuzzyMatcher;
 use iced::{
     alignment::Horizontal as HorizontalAlignment, button, scrollable, Alignment, Button, Checkbox, Column, Container,
     Length, Row, Scrollable, Space, Text,
@@ -25,16 +24,26 @@ use super::common::OngoingOperation;
 pub struct GameListEntry {
     pub scan_info: ScanInfo,
     pub backup_info: Option<BackupInfo>,
+    /// Where this game's backups are stored, if it has any.
+    pub backup_dir: Option<StrictPath>,
     pub expand_button: button::State,
     pub wiki_button: button::State,
     pub customize_button: button::State,
     pub operate_button: button::State,
+    pub backup_dir_button: button::State,
     pub expanded: bool,
     pub tree: FileTree,
     pub duplicates: usize,
 }
 
 impl GameListEntry {
+    fn successful(&self) -> bool {
+        match &self.backup_info {
+            Some(x) => x.successful(),
+            _ => true,
+        }
+    }
+
     fn view(
         &mut self,
         restoring: bool,
@@ -44,10 +53,7 @@ impl GameListEntry {
         duplicate_detector: &DuplicateDetector,
         operation: &Option<OngoingOperation>,
     ) -> Container<Message> {
-        let successful = match &self.backup_info {
-            Some(x) => x.successful(),
-            _ => true,
-        };
+        let successful = self.successful();
 
         let duplicates = duplicate_detector.count_duplicates_for(&self.scan_info.game_name);
         if self.expanded {
@@ -120,6 +126,10 @@ impl GameListEntry {
                             || !successful,
                             || Badge::new(&translator.badge_failed()).left_margin(15).view(),
                         )
+                        .push_if(
+                            || self.scan_info.cloud_only,
+                            || Badge::new(&translator.badge_cloud_only()).left_margin(15).view(),
+                        )
                         .push(Space::new(
                             Length::Units(if restoring { 0 } else { 15 }),
                             Length::Shrink,
@@ -127,7 +137,7 @@ impl GameListEntry {
                         .push_if(
                             || !restoring,
                             || {
-                                Container::new(
+                                Container::new(labeled(
                                     Button::new(
                                         &mut self.customize_button,
                                         Icon::Edit.as_text().width(Length::Units(45)),
@@ -145,11 +155,12 @@ impl GameListEntry {
                                         style::Button::Primary
                                     })
                                     .padding(2),
-                                )
+                                    translator.customize_button(),
+                                ))
                             },
                         )
                         .push(Space::new(Length::Units(15), Length::Shrink))
-                        .push(Container::new(
+                        .push(Container::new(labeled(
                             Button::new(
                                 &mut self.operate_button,
                                 Icon::PlayCircleOutline.as_text().width(Length::Units(45)),
@@ -167,9 +178,32 @@ impl GameListEntry {
                                 style::Button::Primary
                             })
                             .padding(2),
-                        ))
+                            if restoring {
+                                translator.restore_button()
+                            } else {
+                                translator.backup_button()
+                            },
+                        )))
                         .push(Space::new(Length::Units(15), Length::Shrink))
-                        .push(Container::new(
+                        .push(Container::new(labeled(
+                            Button::new(
+                                &mut self.backup_dir_button,
+                                Icon::FolderOpen.as_text().width(Length::Units(45)),
+                            )
+                            .on_press(match &self.backup_dir {
+                                Some(path) => Message::OpenDir { path: path.clone() },
+                                None => Message::Ignore,
+                            })
+                            .style(if self.backup_dir.is_some() {
+                                style::Button::Primary
+                            } else {
+                                style::Button::Disabled
+                            })
+                            .padding(2),
+                            translator.open_backup_folder_button(),
+                        )))
+                        .push(Space::new(Length::Units(15), Length::Shrink))
+                        .push(Container::new(labeled(
                             Button::new(&mut self.wiki_button, Icon::Language.as_text().width(Length::Units(45)))
                                 .on_press(if customized_pure {
                                     Message::Ignore
@@ -184,7 +218,8 @@ impl GameListEntry {
                                     style::Button::Primary
                                 })
                                 .padding(2),
-                        ))
+                            translator.open_wiki_button(),
+                        )))
                         .push(
                             Container::new(Text::new(
                                 translator.adjusted_size(self.scan_info.sum_bytes(&self.backup_info)),
@@ -194,7 +229,15 @@ impl GameListEntry {
                         ),
                 )
                 .push_if(
-                    || self.expanded,
+                    || self.expanded && self.scan_info.cloud_only,
+                    || Text::new(translator.cloud_only_notice()),
+                )
+                .push_if(
+                    || self.expanded && !restoring && !self.scan_info.cloud_only,
+                    || save_locations(translator, &self.scan_info, &config.roots),
+                )
+                .push_if(
+                    || self.expanded && !self.scan_info.cloud_only,
                     || {
                         self.tree
                             .view(translator, &self.scan_info.game_name, config, restoring)
@@ -206,6 +249,44 @@ impl GameListEntry {
     }
 }
 
+/// Where the scan found each of a game's files, to check before backing it up.
+fn save_locations(translator: &Translator, scan_info: &ScanInfo, roots: &[RootsConfig]) -> Column<'static, Message> {
+    if !scan_info.found_anything() {
+        return Column::new().push(Text::new(translator.save_locations_unscanned_notice()));
+    }
+
+    let roots: Vec<_> = roots.iter().flat_map(|x| x.glob()).collect();
+    let mut files: Vec<_> = scan_info.found_files.iter().map(|x| (x.path.render(), x)).collect();
+    files.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    files.into_iter().fold(
+        Column::new()
+            .spacing(5)
+            .width(Length::Fill)
+            .push(Text::new(translator.save_locations_header())),
+        |parent, (rendered, file)| {
+            let store = roots
+                .iter()
+                .filter(|root| root.path.is_prefix_of(&file.path))
+                .max_by_key(|root| root.path.render().len())
+                .map(|root| root.store)
+                .unwrap_or(Store::Other);
+            parent.push(
+                Row::new()
+                    .spacing(15)
+                    .align_items(Alignment::Center)
+                    .push(Text::new(rendered).width(Length::Fill))
+                    .push(Badge::new(&translator.store(&store)).view())
+                    .push(
+                        Container::new(Text::new(translator.item_size(file.size)))
+                            .width(Length::Units(115))
+                            .center_x(),
+                    ),
+            )
+        },
+    )
+}
+
 #[derive(Default)]
 pub struct GameList {
     pub entries: Vec<GameListEntry>,
@@ -223,8 +304,14 @@ impl GameList {
         duplicate_detector: &DuplicateDetector,
         operation: &Option<OngoingOperation>,
     ) -> Container<Message> {
-        let use_search = self.search.show;
-        let search_game_name = self.search.game_name.clone();
+        let failed_only = self.failed_only();
+        let total_games = self.entries.len();
+        let visible: Vec<_> = self
+            .entries
+            .iter()
+            .map(|x| (!failed_only || !x.successful()) && self.search.matches(&x.scan_info, &config.roots, translator))
+            .collect();
+        let shown_games = visible.iter().filter(|x| **x).count();
 
         Container::new(
             Column::new()
@@ -236,6 +323,8 @@ impl GameList {
                     } else {
                         &config.backup.sort
                     },
+                    total_games,
+                    shown_games,
                 ))
                 .push({
                     self.entries.iter_mut().enumerate().fold(
@@ -244,12 +333,8 @@ impl GameList {
                             .padding([0, 15, 5, 15])
                             .spacing(10)
                             .style(style::Scrollable),
-                        |parent: Scrollable<'_, Message>, (_i, x)| {
-                            if !use_search
-                                || fuzzy_matcher::skim::SkimMatcherV2::default()
-                                    .fuzzy_match(&x.scan_info.game_name, &search_game_name)
-                                    .is_some()
-                            {
+                        |parent: Scrollable<'_, Message>, (i, x)| {
+                            if visible[i] {
                                 parent.push(x.view(
                                     restoring,
                                     translator,
@@ -277,9 +362,19 @@ impl GameList {
         })
     }
 
+    /// Whether to only show games that had failures.
+    fn failed_only(&self) -> bool {
+        self.search.show && self.search.failed_only
+    }
+
     pub fn compute_operation_status(&self, config: &Config, restoring: bool) -> OperationStatus {
+        let failed_only = self.failed_only();
         let mut status = OperationStatus::default();
-        for entry in self.entries.iter() {
+        for entry in self
+            .entries
+            .iter()
+            .filter(|x| !x.scan_info.cloud_only && (!failed_only || !x.successful()))
+        {
             status.total_games += 1;
             status.total_bytes += entry.scan_info.total_possible_bytes();
             if (restoring && config.is_game_enabled_for_restore(&entry.scan_info.game_name))
@@ -287,11 +382,25 @@ impl GameList {
             {
                 status.processed_games += 1;
                 status.processed_bytes += entry.scan_info.sum_bytes(&None);
+                if let Some(backup_info) = &entry.backup_info {
+                    status.add_warnings(&entry.scan_info.game_name, backup_info);
+                }
             }
         }
         status
     }
 
+    /// Estimated size of the next backup, using the file sizes from the last scan,
+    /// so that it can be updated whenever the selection changes without scanning again.
+    pub fn estimated_backup_bytes(&self, config: &Config, duplicate_detector: &DuplicateDetector) -> u64 {
+        duplicate_detector.deduplicated_bytes(
+            self.entries
+                .iter()
+                .filter(|x| !x.scan_info.cloud_only && config.is_game_enabled_for_backup(&x.scan_info.game_name))
+                .map(|x| &x.scan_info),
+        )
+    }
+
     pub fn update_ignored(&mut self, game: &str, ignored_paths: &ToggledPaths, ignored_registry: &ToggledRegistry) {
         for item in self.entries.iter_mut() {
             if item.scan_info.game_name == game {
@@ -302,14 +411,10 @@ impl GameList {
     }
 
     pub fn sort(&mut self, sort: &Sort) {
-        match sort.key {
-            SortKey::Name => self.entries.sort_by_key(|x| x.scan_info.game_name.clone()),
-            SortKey::Size => self
-                .entries
-                .sort_by_key(|x| (x.scan_info.sum_bytes(&x.backup_info), x.scan_info.game_name.clone())),
-        }
-        if sort.reversed {
-            self.entries.reverse();
-        }
+        sort.apply(
+            &mut self.entries,
+            |x| x.scan_info.game_name.clone(),
+            |x| x.scan_info.sum_bytes(&x.backup_info),
+        );
     }
 }
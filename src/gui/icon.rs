@@ -1,4 +1,5 @@
-use iced::{alignment::Horizontal as HorizontalAlignment, Font, Length, Text};
+use crate::gui::{common::Message, style};
+use iced::{alignment::Horizontal as HorizontalAlignment, tooltip, Button, Font, Length, Text, Tooltip};
 
 const ICONS: Font = Font::External {
     name: "Material Icons",
@@ -8,6 +9,7 @@ const ICONS: Font = Font::External {
 pub enum Icon {
     Add,
     AddCircle,
+    ContentCopy,
     Edit,
     FolderOpen,
     KeyboardArrowRight,
@@ -27,6 +29,7 @@ impl Icon {
         let character = match self {
             Self::Add => '\u{E145}',
             Self::AddCircle => '\u{E147}',
+            Self::ContentCopy => '\u{E14D}',
             Self::Edit => '\u{E150}',
             Self::FolderOpen => '\u{E2C8}',
             Self::KeyboardArrowRight => '\u{E315}',
@@ -46,3 +49,14 @@ impl Icon {
             .horizontal_alignment(HorizontalAlignment::Center)
     }
 }
+
+/// Give an icon-only button a text label, since the icon alone doesn't say what it does.
+/// The label is only shown on hover. Accessible names and keyboard focus order are out of scope
+/// while we're on Iced 0.4, which has no accessibility tree and no focusable buttons.
+pub fn labeled<'a>(button: Button<'a, Message>, label: String) -> Tooltip<'a, Message> {
+    Tooltip::new(button, label, tooltip::Position::Top)
+        .size(16)
+        .gap(5)
+        .padding(5)
+        .style(style::Container::Tooltip)
+}
@@ -4,7 +4,7 @@ use crate::{
         common::Message,
         common::OngoingOperation,
         common::{BrowseSubject, EditAction},
-        icon::Icon,
+        icon::{labeled, Icon},
         style,
     },
     lang::Translator,
@@ -108,7 +108,7 @@ impl IgnoredItemsEditor {
                                                         )
                                                         .padding(5),
                                                     )
-                                                    .push(
+                                                    .push(labeled(
                                                         Button::new(
                                                             &mut xx.browse_button_state,
                                                             Icon::FolderOpen.as_text(),
@@ -123,24 +123,27 @@ impl IgnoredItemsEditor {
                                                             None => style::Button::Primary,
                                                             Some(_) => style::Button::Disabled,
                                                         }),
-                                                    )
-                                                    .push(
+                                                        translator.browse_button(),
+                                                    ))
+                                                    .push(labeled(
                                                         Button::new(&mut xx.button_state, Icon::RemoveCircle.as_text())
                                                             .on_press(Message::EditedBackupFilterIgnoredPath(
                                                                 EditAction::Remove(ii),
                                                             ))
                                                             .style(style::Button::Negative),
-                                                    ),
+                                                        translator.remove_button(),
+                                                    )),
                                             )
                                         })
-                                        .push(
+                                        .push(labeled(
                                             Button::new(
                                                 &mut self.entry.add_file_button_state,
                                                 Icon::AddCircle.as_text(),
                                             )
                                             .on_press(Message::EditedBackupFilterIgnoredPath(EditAction::Add))
                                             .style(style::Button::Primary),
-                                        ),
+                                            translator.add_button(),
+                                        )),
                                 ),
                         )
                         .push(
@@ -172,23 +175,25 @@ impl IgnoredItemsEditor {
                                                         )
                                                         .padding(5),
                                                     )
-                                                    .push(
+                                                    .push(labeled(
                                                         Button::new(&mut xx.button_state, Icon::RemoveCircle.as_text())
                                                             .on_press(Message::EditedBackupFilterIgnoredRegistry(
                                                                 EditAction::Remove(ii),
                                                             ))
                                                             .style(style::Button::Negative),
-                                                    ),
+                                                        translator.remove_button(),
+                                                    )),
                                             )
                                         })
-                                        .push(
+                                        .push(labeled(
                                             Button::new(
                                                 &mut self.entry.add_registry_button_state,
                                                 Icon::AddCircle.as_text(),
                                             )
                                             .on_press(Message::EditedBackupFilterIgnoredRegistry(EditAction::Add))
                                             .style(style::Button::Primary),
-                                        ),
+                                            translator.add_button(),
+                                        )),
                                 ),
                         ),
                 )
@@ -1,5 +1,5 @@
 use crate::{
-    config::{Config, RootsConfig},
+    config::{Config, RestoreMode, RootCandidate},
     gui::{common::Message, style},
     lang::Translator,
     prelude::Error,
@@ -17,17 +17,28 @@ pub enum ModalVariant {
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ModalTheme {
-    Error { variant: Error },
-    ConfirmBackup { games: Option<Vec<String>> },
-    ConfirmRestore { games: Option<Vec<String>> },
+    Error {
+        variant: Error,
+    },
+    Errors {
+        variants: Vec<Error>,
+    },
+    /// `grouping_kept` is for when the target already uses a different grouping than the config.
+    ConfirmBackup {
+        games: Option<Vec<String>>,
+        grouping_kept: bool,
+    },
+    ConfirmRestore {
+        games: Option<Vec<String>>,
+    },
     NoMissingRoots,
-    ConfirmAddMissingRoots(Vec<RootsConfig>),
+    ConfirmAddMissingRoots(Vec<RootCandidate>),
 }
 
 impl ModalTheme {
     pub fn variant(&self) -> ModalVariant {
         match self {
-            Self::Error { .. } | Self::NoMissingRoots => ModalVariant::Info,
+            Self::Error { .. } | Self::Errors { .. } | Self::NoMissingRoots => ModalVariant::Info,
             Self::ConfirmBackup { .. } | Self::ConfirmRestore { .. } | Self::ConfirmAddMissingRoots(..) => {
                 ModalVariant::Confirm
             }
@@ -37,10 +48,26 @@ impl ModalTheme {
     pub fn text(&self, config: &Config, translator: &Translator) -> String {
         match self {
             Self::Error { variant } => translator.handle_error(variant),
-            Self::ConfirmBackup { .. } => {
-                translator.modal_confirm_backup(&config.backup.path, config.backup.path.exists(), config.backup.merge)
+            Self::Errors { variants } => variants
+                .iter()
+                .map(|x| translator.handle_error(x))
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+            Self::ConfirmBackup { grouping_kept, .. } => {
+                let text = translator.modal_confirm_backup(
+                    &config.backup.path,
+                    config.backup.path.exists(),
+                    config.backup.merge,
+                );
+                if *grouping_kept {
+                    format!("{}\n\n{}", text, translator.backup_grouping_kept(&config.backup.path))
+                } else {
+                    text
+                }
+            }
+            Self::ConfirmRestore { .. } => {
+                translator.modal_confirm_restore(&config.restore.path, config.restore.mode == RestoreMode::Mirror)
             }
-            Self::ConfirmRestore { .. } => translator.modal_confirm_restore(&config.restore.path),
             Self::NoMissingRoots => translator.no_missing_roots(),
             Self::ConfirmAddMissingRoots(missing) => translator.confirm_add_missing_roots(missing),
         }
@@ -48,8 +75,8 @@ impl ModalTheme {
 
     pub fn message(&self) -> Message {
         match self {
-            Self::Error { .. } | Self::NoMissingRoots => Message::Idle,
-            Self::ConfirmBackup { games } => Message::BackupStart {
+            Self::Error { .. } | Self::Errors { .. } | Self::NoMissingRoots => Message::Idle,
+            Self::ConfirmBackup { games, .. } => Message::BackupStart {
                 preview: false,
                 games: games.clone(),
             },
@@ -0,0 +1,48 @@
+use iced::Text;
+
+/// A short message at the bottom of the window, which goes away after a few seconds.
+/// Each one gets a new ID, so that the timer for an older message doesn't clear a newer one.
+#[derive(Default)]
+pub struct Notification {
+    text: Option<String>,
+    id: usize,
+}
+
+impl Notification {
+    /// Show this text and return the ID to pass to `clear` once it's been shown long enough.
+    pub fn show(&mut self, text: String) -> usize {
+        self.id = self.id.wrapping_add(1);
+        self.text = Some(text);
+        self.id
+    }
+
+    /// Hide the notification, unless another one has been shown since.
+    pub fn clear(&mut self, id: usize) {
+        if id == self.id {
+            self.text = None;
+        }
+    }
+
+    pub fn view(&self) -> Option<Text> {
+        self.text.as_ref().map(|x| Text::new(x).size(16))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn only_clears_latest_notification() {
+        let mut notification = Notification::default();
+        let first = notification.show("first".to_string());
+        let second = notification.show("second".to_string());
+
+        notification.clear(first);
+        assert_eq!(Some(&"second".to_string()), notification.text.as_ref());
+
+        notification.clear(second);
+        assert_eq!(None, notification.text);
+    }
+}
@@ -1,4 +1,11 @@
-use crate::gui::{common::Message, icon::Icon, style};
+use crate::{
+    gui::{
+        common::Message,
+        icon::{labeled, Icon},
+        style,
+    },
+    lang::Translator,
+};
 use iced::{
     button::{self, Button},
     Alignment, Container, Length, Row, Text,
@@ -18,6 +25,7 @@ impl NumberInput {
         label: &str,
         range: RangeInclusive<u8>,
         change: fn(u8) -> Message,
+        translator: &Translator,
     ) -> Container<Message> {
         Container::new(
             Row::new()
@@ -27,19 +35,21 @@ impl NumberInput {
                 .push(Text::new(value.to_string()))
                 .push({
                     let button = Button::new(&mut self.down_state, Icon::Remove.as_text().width(Length::Shrink));
-                    if &value > range.start() {
+                    let button = if &value > range.start() {
                         button.on_press((change)(value - 1)).style(style::Button::Negative)
                     } else {
                         button.style(style::Button::Disabled)
-                    }
+                    };
+                    labeled(button, translator.decrease_button())
                 })
                 .push({
                     let button = Button::new(&mut self.up_state, Icon::Add.as_text().width(Length::Shrink));
-                    if &value < range.end() {
+                    let button = if &value < range.end() {
                         button.on_press((change)(value + 1)).style(style::Button::Primary)
                     } else {
                         button.style(style::Button::Disabled)
-                    }
+                    };
+                    labeled(button, translator.increase_button())
                 }),
         )
     }
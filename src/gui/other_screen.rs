@@ -6,6 +6,7 @@ use crate::{
         style,
     },
     lang::Translator,
+    manifest::Tag,
 };
 
 use iced::{scrollable, Checkbox, Column, Container, Length, Scrollable, Text};
@@ -48,6 +49,21 @@ impl OtherScreenComponent {
                             translator.explanation_for_exclude_store_screenshots(),
                             Message::EditedExcludeStoreScreenshots,
                         ))
+                        .push(Checkbox::new(
+                            config.backup.filter.exclude_hidden_files,
+                            translator.explanation_for_exclude_hidden_files(),
+                            Message::EditedExcludeHiddenFiles,
+                        ))
+                        .push(Checkbox::new(
+                            !config.backup.filter.excluded_tags.contains(&Tag::Save),
+                            translator.explanation_for_included_tag(&Tag::Save),
+                            |included| Message::EditedTagIncluded(Tag::Save, included),
+                        ))
+                        .push(Checkbox::new(
+                            !config.backup.filter.excluded_tags.contains(&Tag::Config),
+                            translator.explanation_for_included_tag(&Tag::Config),
+                            |included| Message::EditedTagIncluded(Tag::Config, included),
+                        ))
                         .push(
                             Column::new().push(Text::new(translator.ignored_items_label())).push(
                                 self.ignored_items_editor
@@ -3,7 +3,7 @@ use crate::{
     gui::{
         common::{BrowseSubject, EditAction, RedirectEditActionField},
         common::{Message, OngoingOperation},
-        icon::Icon,
+        icon::{labeled, Icon},
         style,
     },
     lang::Translator,
@@ -69,11 +69,12 @@ impl RedirectEditor {
                         Row::new()
                             .padding([0, 20, 0, 20])
                             .spacing(20)
-                            .push(
+                            .push(labeled(
                                 Button::new(&mut x.button_state, Icon::RemoveCircle.as_text())
                                     .on_press(Message::EditedRedirect(EditAction::Remove(i), None))
                                     .style(style::Button::Negative),
-                            )
+                                translator.remove_button(),
+                            ))
                             .push(
                                 TextInput::new(
                                     &mut x.source_text_state,
@@ -89,7 +90,7 @@ impl RedirectEditor {
                                 .width(Length::FillPortion(3))
                                 .padding(5),
                             )
-                            .push(
+                            .push(labeled(
                                 Button::new(&mut x.source_browse_button_state, Icon::FolderOpen.as_text())
                                     .on_press(match operation {
                                         None => Message::BrowseDir(BrowseSubject::RedirectSource(i)),
@@ -99,7 +100,8 @@ impl RedirectEditor {
                                         None => style::Button::Primary,
                                         Some(_) => style::Button::Disabled,
                                     }),
-                            )
+                                translator.browse_button(),
+                            ))
                             .push(
                                 TextInput::new(
                                     &mut x.target_text_state,
@@ -115,7 +117,7 @@ impl RedirectEditor {
                                 .width(Length::FillPortion(3))
                                 .padding(5),
                             )
-                            .push(
+                            .push(labeled(
                                 Button::new(&mut x.target_browse_button_state, Icon::FolderOpen.as_text())
                                     .on_press(match operation {
                                         None => Message::BrowseDir(BrowseSubject::RedirectTarget(i)),
@@ -125,7 +127,8 @@ impl RedirectEditor {
                                         None => style::Button::Primary,
                                         Some(_) => style::Button::Disabled,
                                     }),
-                            ),
+                                translator.browse_button(),
+                            )),
                     )
                 },
             )
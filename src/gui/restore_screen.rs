@@ -1,10 +1,10 @@
 use crate::{
-    config::Config,
+    config::{Config, ProfileChoice},
     gui::{
         common::OngoingOperation,
-        common::{make_status_row, BrowseSubject, EditAction, Message, Screen},
+        common::{make_status_row, make_warnings_panel, BrowseSubject, EditAction, IcedExtension, Message, Screen},
         game_list::GameList,
-        icon::Icon,
+        icon::{labeled, Icon},
         redirect_editor::{RedirectEditor, RedirectEditorRow},
         style,
     },
@@ -15,8 +15,10 @@ use crate::{
 };
 
 use iced::{
-    alignment::Horizontal as HorizontalAlignment, button, text_input, Alignment, Button, Column, Container, Length,
-    Row, Text, TextInput,
+    alignment::Horizontal as HorizontalAlignment,
+    button,
+    pick_list::{self, PickList},
+    text_input, Alignment, Button, Column, Container, Length, Row, Text, TextInput,
 };
 
 #[derive(Default)]
@@ -30,6 +32,7 @@ pub struct RestoreScreenComponent {
     pub restore_source_input: text_input::State,
     pub restore_source_history: TextHistory,
     restore_source_browse_button: button::State,
+    profile_state: pick_list::State<ProfileChoice>,
     pub redirect_editor: RedirectEditor,
     pub duplicate_detector: DuplicateDetector,
 }
@@ -53,10 +56,13 @@ impl RestoreScreenComponent {
     pub fn view(
         &mut self,
         config: &Config,
+        profile: &ProfileChoice,
         manifest: &Manifest,
         translator: &Translator,
         operation: &Option<OngoingOperation>,
     ) -> Container<Message> {
+        let status = self.log.compute_operation_status(config, true);
+
         Container::new(
             Column::new()
                 .align_items(Alignment::Center)
@@ -142,7 +148,7 @@ impl RestoreScreenComponent {
                             .width(Length::Units(125))
                             .style(style::Button::Primary)
                         })
-                        .push(
+                        .push(labeled(
                             Button::new(&mut self.toggle_search_button, Icon::Search.as_text())
                                 .on_press(Message::ToggleSearch {
                                     screen: Screen::Restore,
@@ -152,18 +158,35 @@ impl RestoreScreenComponent {
                                 } else {
                                     style::Button::Primary
                                 }),
-                        ),
+                            translator.search_button(),
+                        )),
                 )
                 .push(make_status_row(
                     translator,
-                    &self.log.compute_operation_status(config, true),
+                    &status,
                     self.duplicate_detector.any_duplicates(),
                 ))
+                .push_if(
+                    || !status.warnings.is_empty(),
+                    || make_warnings_panel(translator, &status),
+                )
                 .push(
                     Row::new()
                         .padding([0, 20, 0, 20])
                         .spacing(20)
                         .align_items(Alignment::Center)
+                        .push_if(|| !config.profiles.is_empty(), || Text::new(translator.profile_label()))
+                        .push_if(
+                            || !config.profiles.is_empty(),
+                            || {
+                                PickList::new(
+                                    &mut self.profile_state,
+                                    ProfileChoice::all(config),
+                                    Some(profile.clone()),
+                                    Message::SelectedProfile,
+                                )
+                            },
+                        )
                         .push(Text::new(translator.restore_source_label()))
                         .push(
                             TextInput::new(
@@ -174,7 +197,7 @@ impl RestoreScreenComponent {
                             )
                             .padding(5),
                         )
-                        .push(
+                        .push(labeled(
                             Button::new(&mut self.restore_source_browse_button, Icon::FolderOpen.as_text())
                                 .on_press(match operation {
                                     None => Message::BrowseDir(BrowseSubject::RestoreSource),
@@ -184,7 +207,8 @@ impl RestoreScreenComponent {
                                     None => style::Button::Primary,
                                     Some(_) => style::Button::Disabled,
                                 }),
-                        ),
+                            translator.browse_button(),
+                        )),
                 )
                 .push(self.redirect_editor.view(config, translator, operation))
                 .push(
@@ -1,9 +1,9 @@
 use crate::{
-    config::Config,
+    config::{Config, RootOverlap},
     gui::{
         common::{BrowseSubject, EditAction},
         common::{Message, OngoingOperation},
-        icon::Icon,
+        icon::{labeled, Icon},
         style,
     },
     lang::Translator,
@@ -12,13 +12,16 @@ use crate::{
 };
 
 use iced::{
-    button, pick_list, scrollable, text_input, Button, Container, Length, PickList, Row, Scrollable, Text, TextInput,
+    alignment::Horizontal as HorizontalAlignment, button, pick_list, scrollable, text_input, Alignment, Button, Column,
+    Container, Length, PickList, Row, Scrollable, Text, TextInput,
 };
 
 #[derive(Default)]
 pub struct RootEditorRow {
     button_state: button::State,
     browse_button_state: button::State,
+    merge_button_state: button::State,
+    skip_button_state: button::State,
     pub text_state: text_input::State,
     pub text_history: TextHistory,
     pick_list: pick_list::State<Store>,
@@ -47,6 +50,7 @@ impl RootEditor {
         operation: &Option<OngoingOperation>,
     ) -> Container<Message> {
         let roots = config.roots.clone();
+        let overlaps: Vec<_> = (0..roots.len()).map(|i| config.find_root_overlap(i)).collect();
         if roots.is_empty() {
             Container::new(Text::new(translator.no_roots_are_configured()))
         } else {
@@ -64,40 +68,83 @@ impl RootEditor {
                         .spacing(5)
                         .style(style::Scrollable),
                     |parent: Scrollable<'_, Message>, (i, x)| {
-                        parent.push(
-                            Row::new()
-                                .padding([0, 20, 0, 20])
-                                .spacing(20)
-                                .push(
-                                    Button::new(&mut x.button_state, Icon::RemoveCircle.as_text())
-                                        .on_press(Message::EditedRoot(EditAction::Remove(i)))
-                                        .style(style::Button::Negative),
-                                )
-                                .push(
-                                    TextInput::new(&mut x.text_state, "", &roots[i].path.raw(), move |v| {
-                                        Message::EditedRoot(EditAction::Change(i, v))
+                        let row = Row::new()
+                            .padding([0, 20, 0, 20])
+                            .spacing(20)
+                            .push(labeled(
+                                Button::new(&mut x.button_state, Icon::RemoveCircle.as_text())
+                                    .on_press(Message::EditedRoot(EditAction::Remove(i)))
+                                    .style(style::Button::Negative),
+                                translator.remove_button(),
+                            ))
+                            .push(
+                                TextInput::new(&mut x.text_state, "", &roots[i].path.raw(), move |v| {
+                                    Message::EditedRoot(EditAction::Change(i, v))
+                                })
+                                .width(Length::FillPortion(3))
+                                .padding(5),
+                            )
+                            .push(PickList::new(
+                                &mut x.pick_list,
+                                Store::ALL,
+                                Some(roots[i].store),
+                                move |v| Message::SelectedRootStore(i, v),
+                            ))
+                            .push(labeled(
+                                Button::new(&mut x.browse_button_state, Icon::FolderOpen.as_text())
+                                    .on_press(match operation {
+                                        None => Message::BrowseDir(BrowseSubject::Root(i)),
+                                        Some(_) => Message::Ignore,
                                     })
-                                    .width(Length::FillPortion(3))
-                                    .padding(5),
+                                    .style(match operation {
+                                        None => style::Button::Primary,
+                                        Some(_) => style::Button::Disabled,
+                                    }),
+                                translator.browse_button(),
+                            ));
+
+                        match overlaps[i] {
+                            None => parent.push(row),
+                            Some((j, overlap)) => {
+                                // Merging keeps whichever root covers more, while skipping drops this one.
+                                let narrower = match overlap {
+                                    RootOverlap::Duplicate | RootOverlap::Inside => i,
+                                    RootOverlap::Contains => j,
+                                };
+                                parent.push(
+                                    Column::new().spacing(5).push(row).push(
+                                        Row::new()
+                                            .padding([0, 20, 0, 20])
+                                            .spacing(20)
+                                            .align_items(Alignment::Center)
+                                            .push(
+                                                Text::new(translator.root_overlap(&overlap, &roots[j].path))
+                                                    .width(Length::Fill),
+                                            )
+                                            .push(
+                                                Button::new(
+                                                    &mut x.merge_button_state,
+                                                    Text::new(translator.merge_roots_button())
+                                                        .horizontal_alignment(HorizontalAlignment::Center),
+                                                )
+                                                .on_press(Message::EditedRoot(EditAction::Remove(narrower)))
+                                                .width(Length::Units(80))
+                                                .style(style::Button::Primary),
+                                            )
+                                            .push(
+                                                Button::new(
+                                                    &mut x.skip_button_state,
+                                                    Text::new(translator.skip_root_button())
+                                                        .horizontal_alignment(HorizontalAlignment::Center),
+                                                )
+                                                .on_press(Message::EditedRoot(EditAction::Remove(i)))
+                                                .width(Length::Units(80))
+                                                .style(style::Button::Negative),
+                                            ),
+                                    ),
                                 )
-                                .push(PickList::new(
-                                    &mut x.pick_list,
-                                    Store::ALL,
-                                    Some(roots[i].store),
-                                    move |v| Message::SelectedRootStore(i, v),
-                                ))
-                                .push(
-                                    Button::new(&mut x.browse_button_state, Icon::FolderOpen.as_text())
-                                        .on_press(match operation {
-                                            None => Message::BrowseDir(BrowseSubject::Root(i)),
-                                            Some(_) => Message::Ignore,
-                                        })
-                                        .style(match operation {
-                                            None => style::Button::Primary,
-                                            Some(_) => style::Button::Disabled,
-                                        }),
-                                ),
-                        )
+                            }
+                        }
                     },
                 )
             })
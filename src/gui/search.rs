@@ -1,10 +1,12 @@
 use crate::{
-    config::{Sort, SortKey},
-    gui::common::{Message, Screen},
+    config::{RootsConfig, SearchScope, Sort, SortDirection, SortKey},
+    gui::common::{IcedExtension, Message, Screen},
     lang::Translator,
+    prelude::ScanInfo,
     shortcuts::TextHistory,
 };
 
+use fuzzy_matcher::FuzzyMatcher;
 use iced::{
     pick_list::{self, PickList},
     text_input, Alignment, Checkbox, Container, Length, Row, Space, Text, TextInput,
@@ -16,14 +18,70 @@ pub struct SearchComponent {
     pub game_name: String,
     pub game_name_input: text_input::State,
     pub game_name_history: TextHistory,
+    pub scope: SearchScope,
+    pub scope_state: pick_list::State<SearchScope>,
+    pub failed_only: bool,
     pub sort_key_state: pick_list::State<SortKey>,
+    pub sort_direction_state: pick_list::State<SortDirection>,
 }
 
 impl SearchComponent {
-    pub fn view(&mut self, screen: Screen, translator: &Translator, sort: &Sort) -> Container<Message> {
+    pub fn matches(&self, scan_info: &ScanInfo, roots: &[RootsConfig], translator: &Translator) -> bool {
+        // An empty query doesn't narrow anything down, whichever scope it's for.
+        if !self.show || self.game_name.trim().is_empty() {
+            return true;
+        }
+
+        let query = self.game_name.to_lowercase();
+        let by_name = || {
+            fuzzy_matcher::skim::SkimMatcherV2::default()
+                .fuzzy_match(&scan_info.game_name, &self.game_name)
+                .is_some()
+        };
+        let by_store = || {
+            roots.iter().any(|root| {
+                translator.store(&root.store).to_lowercase().contains(&query)
+                    && scan_info.found_files.iter().any(|file| {
+                        root.path
+                            .is_prefix_of(file.original_path.as_ref().unwrap_or(&file.path))
+                    })
+            })
+        };
+        let by_path = || {
+            if scan_info.found_files.is_empty() {
+                // We don't know any paths for this game, so name is the best we can do.
+                return by_name();
+            }
+            scan_info.found_files.iter().any(|file| {
+                file.original_path
+                    .as_ref()
+                    .unwrap_or(&file.path)
+                    .render()
+                    .to_lowercase()
+                    .contains(&query)
+            })
+        };
+
+        match self.scope {
+            SearchScope::Name => by_name(),
+            SearchScope::Store => by_store(),
+            SearchScope::Path => by_path(),
+            SearchScope::All => by_name() || by_store() || by_path(),
+        }
+    }
+
+    pub fn view(
+        &mut self,
+        screen: Screen,
+        translator: &Translator,
+        sort: &Sort,
+        total_games: usize,
+        shown_games: usize,
+    ) -> Container<Message> {
         if !self.show {
             return Container::new(Space::new(Length::Shrink, Length::Shrink));
         }
+        let failed_only = self.failed_only;
         Container::new(
             Row::new()
                 .padding([0, 20, 20, 20])
@@ -39,6 +97,12 @@ impl SearchComponent {
                     )
                     .padding(5),
                 )
+                .push(PickList::new(
+                    &mut self.scope_state,
+                    SearchScope::ALL,
+                    Some(self.scope),
+                    move |value| Message::EditedSearchScope { screen, value },
+                ))
                 .push(Text::new(translator.sort_label()))
                 .push(PickList::new(
                     &mut self.sort_key_state,
@@ -46,9 +110,94 @@ impl SearchComponent {
                     Some(sort.key),
                     move |value| Message::EditedSortKey { screen, value },
                 ))
-                .push(Checkbox::new(sort.reversed, translator.sort_reversed(), move |value| {
-                    Message::EditedSortReversed { screen, value }
-                })),
+                .push(PickList::new(
+                    &mut self.sort_direction_state,
+                    SortDirection::ALL,
+                    Some(sort.direction()),
+                    move |value| Message::EditedSortDirection { screen, value },
+                ))
+                .push(Checkbox::new(
+                    failed_only,
+                    translator.search_failed_only(),
+                    move |value| Message::EditedSearchFailedOnly { screen, value },
+                ))
+                .push_if(
+                    || failed_only,
+                    || Text::new(translator.processed_subset(total_games, shown_games)),
+                ),
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{manifest::Store, prelude::ScannedFile, prelude::StrictPath};
+    use maplit::*;
+
+    fn search(scope: SearchScope, query: &str) -> SearchComponent {
+        SearchComponent {
+            show: true,
+            game_name: query.to_string(),
+            scope,
+            ..Default::default()
+        }
+    }
+
+    fn game() -> ScanInfo {
+        ScanInfo {
+            game_name: "Some Game".to_string(),
+            found_files: hashset! {
+                ScannedFile::new("/steam/some-game/save.dat", 1),
+            },
+            ..Default::default()
+        }
+    }
+
+    fn roots() -> Vec<RootsConfig> {
+        vec![RootsConfig {
+            path: StrictPath::new("/steam".to_string()),
+            store: Store::Steam,
+            require: Default::default(),
+        }]
+    }
+
+    fn matches(scope: SearchScope, query: &str) -> bool {
+        search(scope, query).matches(&game(), &roots(), &Translator::default())
+    }
+
+    #[test]
+    fn empty_query_matches_everything_in_any_scope() {
+        for scope in SearchScope::ALL {
+            assert!(matches(*scope, ""));
+            assert!(matches(*scope, "  "));
+            assert!(search(*scope, "").matches(&ScanInfo::default(), &[], &Translator::default()));
+        }
+    }
+
+    #[test]
+    fn can_match_by_name() {
+        assert!(matches(SearchScope::Name, "sgame"));
+        assert!(!matches(SearchScope::Name, "steam"));
+    }
+
+    #[test]
+    fn can_match_by_store() {
+        assert!(matches(SearchScope::Store, "steam"));
+        assert!(!matches(SearchScope::Store, "gog"));
+    }
+
+    #[test]
+    fn can_match_by_path() {
+        assert!(matches(SearchScope::Path, "save.dat"));
+        assert!(!matches(SearchScope::Path, "other.dat"));
+    }
+
+    #[test]
+    fn can_match_by_any_scope() {
+        assert!(matches(SearchScope::All, "sgame"));
+        assert!(matches(SearchScope::All, "steam"));
+        assert!(matches(SearchScope::All, "save.dat"));
+        assert!(!matches(SearchScope::All, "xyz"));
+    }
+}
@@ -81,13 +81,14 @@ pub enum Container {
     ModalBackground,
     GameListEntry,
     Badge,
+    Tooltip,
 }
 
 impl container::StyleSheet for Container {
     fn style(&self) -> container::Style {
         container::Style {
             background: match self {
-                Self::ModalBackground => Some(Background::Color(Color::from_rgb8(230, 230, 230))),
+                Self::ModalBackground | Self::Tooltip => Some(Background::Color(Color::from_rgb8(230, 230, 230))),
                 _ => None,
             },
             border_color: match self {
@@ -95,11 +96,12 @@ impl container::StyleSheet for Container {
                 _ => Color::BLACK,
             },
             border_width: match self {
-                Self::GameListEntry | Self::Badge => 1.0,
+                Self::GameListEntry | Self::Badge | Self::Tooltip => 1.0,
                 _ => 0.0,
             },
             border_radius: match self {
                 Self::GameListEntry | Self::Badge => 10.0,
+                Self::Tooltip => 5.0,
                 _ => 0.0,
             },
             ..container::Style::default()
@@ -0,0 +1,194 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use crate::prelude::{StrictPath, CASE_INSENSITIVE_OS};
+
+/// Name of the files that list patterns to skip in the folder where they're placed,
+/// using the same syntax as `.gitignore`.
+pub const IGNORE_FILE_NAME: &str = ".ludusaviignore";
+
+#[derive(Clone, Debug)]
+struct Rule {
+    pattern: glob::Pattern,
+    /// Starts with `!`, so a match re-includes the path.
+    negated: bool,
+    /// Ends with `/`, so it only matches folders.
+    dir_only: bool,
+    /// Contains a `/` before the end, so it matches the path relative to the ignore file's folder
+    /// instead of just the name.
+    anchored: bool,
+}
+
+impl Rule {
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line.strip_prefix('\\').unwrap_or(line)),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let anchored = line.contains('/');
+        let line = line.trim_start_matches('/');
+        if line.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            pattern: glob::Pattern::new(line).ok()?,
+            negated,
+            dir_only,
+            anchored,
+        })
+    }
+
+    fn matches(&self, relative: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        let options = glob::MatchOptions {
+            case_sensitive: !CASE_INSENSITIVE_OS,
+            require_literal_separator: true,
+            require_literal_leading_dot: false,
+        };
+        if self.anchored {
+            self.pattern.matches_with(relative, options)
+        } else {
+            let name = relative.rsplit('/').next().unwrap_or(relative);
+            self.pattern.matches_with(name, options)
+        }
+    }
+}
+
+/// Applies the `.ludusaviignore` files found while scanning a folder.
+/// Each file's patterns are relative to its own folder,
+/// and a nested file's patterns are checked after (and so can override) its parents'.
+#[derive(Clone, Debug, Default)]
+pub struct IgnoreFiles {
+    /// By folder, or `None` if the folder has no ignore file.
+    rules: HashMap<PathBuf, Option<Vec<Rule>>>,
+}
+
+impl IgnoreFiles {
+    fn rules_in(&mut self, folder: &Path) -> &[Rule] {
+        self.rules
+            .entry(folder.to_path_buf())
+            .or_insert_with(|| {
+                std::fs::read_to_string(folder.join(IGNORE_FILE_NAME))
+                    .ok()
+                    .map(|content| content.lines().filter_map(Rule::parse).collect())
+            })
+            .as_deref()
+            .unwrap_or_default()
+    }
+
+    /// The ignore files that have been read so far,
+    /// so that the scan cache can tell when they change.
+    pub fn files(&self) -> Vec<StrictPath> {
+        let mut files: Vec<_> = self
+            .rules
+            .iter()
+            .filter(|(_, rules)| rules.is_some())
+            .map(|(folder, _)| StrictPath::from(folder.join(IGNORE_FILE_NAME)))
+            .collect();
+        files.sort_by_key(|x| x.render());
+        files
+    }
+
+    /// Whether `file` should be skipped, considering the ignore files in `root` and any folders
+    /// between it and `file`. As with Git, once a folder is excluded,
+    /// nothing inside of it can be re-included.
+    pub fn is_ignored(&mut self, root: &Path, file: &Path) -> bool {
+        let relative = match file.strip_prefix(root) {
+            Ok(x) => x,
+            Err(_) => return false,
+        };
+        let parts: Vec<_> = relative
+            .components()
+            .map(|x| x.as_os_str().to_string_lossy().to_string())
+            .collect();
+
+        for depth in 1..=parts.len() {
+            let is_dir = depth < parts.len();
+            let mut ignored = false;
+            for base in 0..depth {
+                let folder = parts[..base].iter().fold(root.to_path_buf(), |acc, x| acc.join(x));
+                let relative = parts[base..depth].join("/");
+                for rule in self.rules_in(&folder) {
+                    if rule.matches(&relative, is_dir) {
+                        ignored = !rule.negated;
+                    }
+                }
+            }
+            if ignored {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::TempPath;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn can_apply_nested_ignore_files_with_negation() {
+        let folder = TempPath::new("ludusavi-test-ignore-file");
+        let root = folder.as_std_path_buf();
+        for file in [
+            "save.dat",
+            "debug.log",
+            "cache/data.bin",
+            "slots/keep.log",
+            "slots/slot1.dat",
+            "slots/slot1.bak",
+            "slots/old/slot0.dat",
+            "shaders/a.bin",
+        ] {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "a").unwrap();
+        }
+        std::fs::write(root.join(IGNORE_FILE_NAME), "# Comment\n*.log\ncache/\n/shaders\n").unwrap();
+        std::fs::write(root.join("slots").join(IGNORE_FILE_NAME), "!keep.log\n*.bak\nold/\n").unwrap();
+
+        let mut ignore_files = IgnoreFiles::default();
+        let mut check = |file: &str| ignore_files.is_ignored(&root, &root.join(file));
+        let ignored: Vec<_> = [
+            "save.dat",
+            "debug.log",
+            "cache/data.bin",
+            "slots/keep.log",
+            "slots/slot1.dat",
+            "slots/slot1.bak",
+            "slots/old/slot0.dat",
+            "shaders/a.bin",
+        ]
+        .into_iter()
+        .filter(|x| check(x))
+        .collect();
+
+        assert_eq!(
+            vec![
+                "debug.log",
+                "cache/data.bin",
+                "slots/slot1.bak",
+                "slots/old/slot0.dat",
+                "shaders/a.bin",
+            ],
+            ignored,
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    path::StrictPath,
+    prelude::{state_dir, ScanInfo},
+};
+
+const DEFERRALS_FILE_NAME: &str = "deferred-backups.json";
+
+/// Whether any of the game's files (other than ignored ones) were modified
+/// less than `threshold` before `now`, so the game may still be writing them.
+pub fn changed_recently(scan_info: &ScanInfo, threshold: std::time::Duration, now: std::time::SystemTime) -> bool {
+    scan_info
+        .found_files
+        .iter()
+        .filter(|x| !x.ignored)
+        .filter_map(|x| x.path.metadata().and_then(|m| m.modified()).ok())
+        // A time in the future is treated as a fresh change.
+        .any(|modified| now.duration_since(modified).map(|x| x < threshold).unwrap_or(true))
+}
+
+/// What to do with a game whose saves may still be changing.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Deferral {
+    BackUp,
+    Defer,
+    /// The game has been deferred too many times in a row, so back it up anyway.
+    Force,
+}
+
+/// How many backups in a row each game has been deferred,
+/// so that a game whose files never stop changing still gets backed up eventually.
+#[derive(Debug, Default)]
+pub struct Deferrals {
+    file: StrictPath,
+    counts: BTreeMap<String, u32>,
+}
+
+impl Deferrals {
+    pub fn load() -> Self {
+        Self::load_from(StrictPath::from(state_dir()).joined(DEFERRALS_FILE_NAME))
+    }
+
+    fn load_from(file: StrictPath) -> Self {
+        let counts = std::fs::read_to_string(file.interpret())
+            .ok()
+            .and_then(|x| serde_json::from_str(&x).ok())
+            .unwrap_or_default();
+        Self { file, counts }
+    }
+
+    pub fn save(&self) {
+        let result = self
+            .file
+            .as_std_path_buf()
+            .parent()
+            .map(std::fs::create_dir_all)
+            .unwrap_or(Ok(()))
+            .and_then(|_| std::fs::write(self.file.interpret(), serde_json::to_string(&self.counts).unwrap()));
+        if let Err(e) = result {
+            log::warn!("Unable to save deferred backups to {}: {}", self.file.render(), e);
+        }
+    }
+
+    /// Decide whether to back up this game now, and count it if it's deferred.
+    pub fn decide(&mut self, name: &str, changed_recently: bool, max_defers: u32) -> Deferral {
+        if !changed_recently {
+            self.counts.remove(name);
+            return Deferral::BackUp;
+        }
+
+        let count = self.counts.entry(name.to_string()).or_insert(0);
+        if *count >= max_defers {
+            self.counts.remove(name);
+            Deferral::Force
+        } else {
+            *count += 1;
+            Deferral::Defer
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::TempPath;
+    use crate::prelude::ScannedFile;
+    use maplit::*;
+    use pretty_assertions::assert_eq;
+
+    fn s(text: &str) -> String {
+        text.to_string()
+    }
+
+    #[test]
+    fn can_tell_recent_changes_from_old_ones() {
+        let folder = TempPath::new("ludusavi-test-inactivity");
+        std::fs::create_dir_all(folder.interpret()).unwrap();
+        let old = folder.joined("old.dat");
+        let recent = folder.joined("recent.dat");
+        std::fs::write(old.interpret(), "a").unwrap();
+        std::fs::write(recent.interpret(), "a").unwrap();
+
+        let now = std::time::SystemTime::now();
+        let hour = std::time::Duration::from_secs(60 * 60);
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(old.interpret())
+            .unwrap()
+            .set_modified(now - hour)
+            .unwrap();
+
+        let scan = |files| ScanInfo {
+            game_name: s("game"),
+            found_files: files,
+            ..Default::default()
+        };
+        let ten_minutes = std::time::Duration::from_secs(10 * 60);
+        let only_old = changed_recently(&scan(hashset! { ScannedFile::new(old.render(), 1) }), ten_minutes, now);
+        let with_recent = changed_recently(
+            &scan(hashset! { ScannedFile::new(old.render(), 1), ScannedFile::new(recent.render(), 1) }),
+            ten_minutes,
+            now,
+        );
+        let with_ignored_recent = changed_recently(
+            &scan(hashset! {
+                ScannedFile::new(old.render(), 1),
+                ScannedFile::new(recent.render(), 1).ignored(),
+            }),
+            ten_minutes,
+            now,
+        );
+
+        assert!(!only_old);
+        assert!(with_recent);
+        assert!(!with_ignored_recent);
+    }
+
+    #[test]
+    fn forces_backup_after_max_deferrals() {
+        let mut deferrals = Deferrals::default();
+
+        assert_eq!(Deferral::Defer, deferrals.decide("game", true, 2));
+        assert_eq!(Deferral::Defer, deferrals.decide("game", true, 2));
+        assert_eq!(Deferral::Force, deferrals.decide("game", true, 2));
+        assert_eq!(Deferral::Defer, deferrals.decide("game", true, 2));
+        assert_eq!(Deferral::BackUp, deferrals.decide("game", false, 2));
+        assert_eq!(Deferral::Defer, deferrals.decide("game", true, 2));
+        assert_eq!(Deferral::Force, deferrals.decide("other", true, 0));
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{manifest::Os, prelude::get_os};
+
+/// User folders that Windows lets you move elsewhere, like into OneDrive.
+/// When that happens, they're no longer under the home folder,
+/// so we have to ask Windows where they are.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum KnownFolder {
+    Documents,
+    SavedGames,
+}
+
+impl KnownFolder {
+    const ALL: &'static [Self] = &[Self::Documents, Self::SavedGames];
+
+    /// The default location in manifest paths.
+    fn template(&self) -> &'static str {
+        match self {
+            Self::Documents => "<home>/Documents",
+            Self::SavedGames => "<home>/Saved Games",
+        }
+    }
+
+    /// Where the folder currently is, following any redirection.
+    #[cfg(target_os = "windows")]
+    pub fn path(&self) -> Option<std::path::PathBuf> {
+        use std::os::windows::ffi::OsStringExt;
+        use winapi::um::{combaseapi::CoTaskMemFree, knownfolders, shlobj::SHGetKnownFolderPath};
+
+        let id = match self {
+            Self::Documents => &knownfolders::FOLDERID_Documents,
+            Self::SavedGames => &knownfolders::FOLDERID_SavedGames,
+        };
+
+        let mut raw: *mut u16 = std::ptr::null_mut();
+        // The buffer must be freed even if the call fails.
+        unsafe {
+            let result = SHGetKnownFolderPath(id, 0, std::ptr::null_mut(), &mut raw);
+            let path = if result == winapi::shared::winerror::S_OK && !raw.is_null() {
+                let mut len = 0;
+                while *raw.add(len) != 0 {
+                    len += 1;
+                }
+                let wide = std::slice::from_raw_parts(raw, len);
+                Some(std::path::PathBuf::from(std::ffi::OsString::from_wide(wide)))
+            } else {
+                None
+            };
+            CoTaskMemFree(raw as *mut _);
+            path
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    pub fn path(&self) -> Option<std::path::PathBuf> {
+        None
+    }
+}
+
+/// Point any default known folder locations in a manifest path
+/// to where the folders actually are. On other OSes, this does nothing.
+pub fn resolve_known_folders(path: &str) -> String {
+    resolve_known_folders_with(path, |folder| folder.path())
+}
+
+fn resolve_known_folders_with(path: &str, lookup: impl Fn(KnownFolder) -> Option<std::path::PathBuf>) -> String {
+    if get_os() != Os::Windows {
+        return path.to_string();
+    }
+
+    for folder in KnownFolder::ALL {
+        let template = folder.template();
+        let rest = match path.strip_prefix(template) {
+            Some(rest) if rest.is_empty() || rest.starts_with('/') => rest,
+            _ => continue,
+        };
+        if let Some(actual) = lookup(*folder) {
+            return format!("{}{}", actual.to_string_lossy(), rest);
+        }
+    }
+    path.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn onedrive(folder: KnownFolder) -> Option<std::path::PathBuf> {
+        match folder {
+            KnownFolder::Documents => Some("C:/Users/me/OneDrive/Documents".into()),
+            KnownFolder::SavedGames => None,
+        }
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn can_resolve_redirected_known_folder() {
+        assert_eq!(
+            "C:/Users/me/OneDrive/Documents/Game/save.dat",
+            resolve_known_folders_with("<home>/Documents/Game/save.dat", onedrive),
+        );
+        assert_eq!(
+            "C:/Users/me/OneDrive/Documents",
+            resolve_known_folders_with("<home>/Documents", onedrive),
+        );
+        // Only whole folder names are redirected.
+        assert_eq!(
+            "<home>/Documents2/save.dat",
+            resolve_known_folders_with("<home>/Documents2/save.dat", onedrive),
+        );
+        // Folders that Windows can't find are left as-is.
+        assert_eq!(
+            "<home>/Saved Games/Game/save.dat",
+            resolve_known_folders_with("<home>/Saved Games/Game/save.dat", onedrive),
+        );
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn ignores_known_folders_on_other_os() {
+        assert_eq!(
+            "<home>/Documents/Game/save.dat",
+            resolve_known_folders_with("<home>/Documents/Game/save.dat", onedrive),
+        );
+    }
+}
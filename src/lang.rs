@@ -1,23 +1,62 @@
-use byte_unit::Byte;
-use fluent::{bundle::FluentBundle, FluentArgs, FluentResource};
+use byte_unit::{Byte, ByteUnit};
+use fluent::{bundle::FluentBundle, FluentArgs, FluentResource, FluentValue};
+use fluent_syntax::ast::{Expression, InlineExpression, Pattern, PatternElement};
 use intl_memoizer::concurrent::IntlLangMemoizer;
 use once_cell::sync::Lazy;
 use regex::Regex;
-use std::sync::Mutex;
+use std::collections::BTreeSet;
 use unic_langid::LanguageIdentifier;
 
 use crate::{
-    config::SortKey,
-    manifest::Store,
-    prelude::{Error, OperationStatus, OperationStepDecision, StrictPath},
+    attention::{AttentionReport, Concern},
+    config::{
+        BadgeKind, ConfigDiff, ProfileChoice, RootCandidate, RootConfidence, RootOverlap, RootsConfig, SearchScope,
+        SortDirection, SortKey, SteamUser,
+    },
+    diff::{Change, FileDiff},
+    doctor::CheckStatus,
+    layout::BackupProvenance,
+    manifest::{ManifestChanges, ManifestConflict, ManifestIssue, ManifestIssueKind, Store, Tag},
+    ownership::OwnershipMismatch,
+    prelude::{
+        Error, ErrorReport, FailureReason, OperationStatus, OperationStepDecision, PathCheck, PathDiagnosis,
+        RegistryValueCounts, RestoreAction, SkipReason, StrictPath, TargetStatus, Warning,
+    },
+    schedule::ScheduleState,
+    verify_sample::SampleResult,
+    wgs::WgsContainer,
 };
 
+const ADDED: &str = "added";
+const AFTER: &str = "after";
+const AGE: &str = "age";
+const AVAILABLE_GAMES: &str = "available-games";
+const BACKED_UP: &str = "backed-up";
+const BEFORE: &str = "before";
+const CHANGED: &str = "changed";
+const COUNT: &str = "count";
+const ID: &str = "id";
+const NAME: &str = "name";
 const PATH: &str = "path";
 const PATH_ACTION: &str = "path-action";
+const MISMATCHED: &str = "mismatched";
+const PERCENT: &str = "percent";
 const PROCESSED_GAMES: &str = "processed-games";
 const PROCESSED_SIZE: &str = "processed-size";
+const REASONS: &str = "reasons";
+const REMOVED: &str = "removed";
+const SIZE: &str = "size";
+const SKIPPED: &str = "skipped";
+const TIME: &str = "time";
+const TOTAL: &str = "total";
 const TOTAL_GAMES: &str = "total-games";
 const TOTAL_SIZE: &str = "total-size";
+const VERSION: &str = "version";
+
+/// Decimal places for sizes that sum up an operation.
+const TOTAL_SIZE_DECIMALS: usize = 2;
+/// Decimal places for sizes of individual files in a list.
+const ITEM_SIZE_DECIMALS: usize = 0;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Language {
@@ -36,21 +75,143 @@ impl Language {
 #[derive(Clone, Copy, Debug, Default)]
 pub struct Translator {}
 
-static BUNDLE: Lazy<Mutex<FluentBundle<FluentResource, IntlLangMemoizer>>> = Lazy::new(|| {
-    let ftl = include_str!("../lang/en-US.ftl").to_owned();
-    let res = FluentResource::try_new(ftl).expect("Failed to parse Fluent file content.");
+/// Which badges to show on a line item in the CLI output.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LineItemBadges {
+    pub failed: bool,
+    pub denied: bool,
+    pub ignored: bool,
+    pub duplicated: bool,
+    pub mismatched: bool,
+    pub partial: bool,
+    pub system: bool,
+    pub ownership: bool,
+    pub restore_action: Option<RestoreAction>,
+}
+
+/// Every locale that's embedded in the program.
+const BUNDLED_LOCALES: &[(Language, &str)] = &[(Language::English, include_str!("../lang/en-US.ftl"))];
 
-    let language_id: LanguageIdentifier = Language::English.id().parse().unwrap();
+/// The concurrent memoizer makes the bundle safe to share, so translating doesn't need a lock.
+static BUNDLE: Lazy<FluentBundle<FluentResource, IntlLangMemoizer>> = Lazy::new(|| {
+    let (language, ftl) = BUNDLED_LOCALES[0];
+    let res = FluentResource::try_new(ftl.to_owned()).expect("Failed to parse Fluent file content.");
+
+    let language_id: LanguageIdentifier = language.id().parse().unwrap();
     let mut bundle = FluentBundle::new_concurrent(vec![language_id]);
     bundle.set_use_isolating(false);
+    bundle.set_formatter(Some(format_number));
 
     bundle
         .add_resource(res)
         .expect("Failed to add Fluent resources to the bundle.");
 
-    Mutex::new(bundle)
+    bundle
 });
 
+/// How a locale groups the digits of large numbers, like the thousands in "12,345".
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct DigitGrouping {
+    separator: &'static str,
+    /// Goes between the integer and the fraction, like the comma in German "1.234,5".
+    decimal: &'static str,
+    /// Numbers with fewer digits than this aren't grouped, like "1234" in Spanish.
+    min_digits: usize,
+}
+
+impl DigitGrouping {
+    /// Based on the CLDR data for each language.
+    fn of(language_id: &str) -> Self {
+        let (separator, decimal, min_digits) = match language_id {
+            "de-CH" => ("\u{2019}", ".", 4),
+            "pt-PT" => ("\u{a0}", ",", 5),
+            _ => match language_id.split('-').next().unwrap_or_default() {
+                "es" => (".", ",", 5),
+                "pl" => ("\u{a0}", ",", 5),
+                "de" | "da" | "el" | "id" | "it" | "nl" | "pt" | "ro" | "tr" | "vi" => (".", ",", 4),
+                "fr" => ("\u{202f}", ",", 4),
+                "cs" | "fi" | "hu" | "nb" | "ru" | "sk" | "sv" | "uk" => ("\u{a0}", ",", 4),
+                _ => (",", ".", 4),
+            },
+        };
+        Self {
+            separator,
+            decimal,
+            min_digits,
+        }
+    }
+
+    fn apply(&self, number: &str) -> String {
+        let (sign, unsigned) = match number.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", number),
+        };
+        let (integer, fraction) = match unsigned.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (unsigned, None),
+        };
+        if !integer.chars().all(|x| x.is_ascii_digit()) {
+            return number.to_string();
+        }
+
+        let mut grouped = String::new();
+        for (i, digit) in integer.chars().enumerate() {
+            if integer.len() >= self.min_digits && i > 0 && (integer.len() - i) % 3 == 0 {
+                grouped.push_str(self.separator);
+            }
+            grouped.push(digit);
+        }
+        match fraction {
+            Some(fraction) => format!("{}{}{}{}", sign, grouped, self.decimal, fraction),
+            None => format!("{}{}", sign, grouped),
+        }
+    }
+}
+
+/// Only these arguments are counts or sizes, so any other number, like an ID, is shown as-is.
+const GROUPED_ARGS: &[&str] = &[
+    ADDED,
+    AVAILABLE_GAMES,
+    BACKED_UP,
+    CHANGED,
+    COUNT,
+    MISMATCHED,
+    PROCESSED_GAMES,
+    PROCESSED_SIZE,
+    REMOVED,
+    SIZE,
+    SKIPPED,
+    TOTAL,
+    TOTAL_GAMES,
+    TOTAL_SIZE,
+];
+
+/// Turn off grouping for numbers that aren't in `GROUPED_ARGS`.
+fn with_grouping_opt_in<'a>(args: &'a FluentArgs) -> FluentArgs<'a> {
+    let mut adjusted = FluentArgs::new();
+    for (key, value) in args.iter() {
+        match value {
+            FluentValue::Number(number) if !GROUPED_ARGS.contains(&key) => {
+                let mut number = number.clone();
+                number.options.use_grouping = false;
+                adjusted.set(key, FluentValue::Number(number));
+            }
+            _ => adjusted.set(key, value.clone()),
+        }
+    }
+    adjusted
+}
+
+/// Fluent doesn't group digits on its own, so numbers are formatted here for the bundled language.
+fn format_number(value: &FluentValue, _memoizer: &IntlLangMemoizer) -> Option<String> {
+    match value {
+        FluentValue::Number(number) if number.options.use_grouping => {
+            Some(DigitGrouping::of(&BUNDLED_LOCALES[0].0.id()).apply(&number.as_string()))
+        }
+        _ => None,
+    }
+}
+
 static RE_EXTRA_SPACES: Lazy<Regex> = Lazy::new(|| Regex::new(r#"([^\r\n ]) {2,}"#).unwrap());
 static RE_EXTRA_LINES: Lazy<Regex> = Lazy::new(|| Regex::new(r#"([^\r\n ])[\r\n]([^\r\n ])"#).unwrap());
 static RE_EXTRA_PARAGRAPHS: Lazy<Regex> = Lazy::new(|| Regex::new(r#"([^\r\n ])[\r\n]{2,}([^\r\n ])"#).unwrap());
@@ -60,10 +221,7 @@ fn translate(id: &str) -> String {
 }
 
 fn translate_args(id: &str, args: &FluentArgs) -> String {
-    let bundle = match BUNDLE.lock() {
-        Ok(x) => x,
-        Err(_) => return "fluent-cannot-lock".to_string(),
-    };
+    let bundle = &*BUNDLE;
 
     let parts: Vec<&str> = id.splitn(2, '.').collect();
     let (name, attr) = if parts.len() < 2 {
@@ -88,7 +246,32 @@ fn translate_args(id: &str, args: &FluentArgs) -> String {
         },
     };
     let mut errors = vec![];
-    let value = bundle.format_pattern(pattern, Some(args), &mut errors);
+    let args = with_grouping_opt_in(args);
+    let value = bundle.format_pattern(pattern, Some(&args), &mut errors);
+
+    // `translator_and_bundled_locales_agree` checks this for every method ahead of time,
+    // so this catches anything it can't see, like a key that's chosen at runtime.
+    if cfg!(debug_assertions) {
+        let check = ArgumentCheck::new(bundle, pattern, &args);
+        debug_assert!(
+            check.missing.is_empty(),
+            "Translation {} is missing arguments: {:?}",
+            id,
+            check.missing
+        );
+        // Some methods share one set of arguments between several variants,
+        // so this is only worth a closer look if the message looks wrong.
+        for variable in &check.unused {
+            log::debug!("Translation {} does not use argument ${}", id, variable);
+        }
+        // Fluent reports each missing argument too, so only check for anything else it found.
+        debug_assert!(
+            errors.len() <= check.missing.len() + check.optional_missing.len(),
+            "Translation {} had errors: {:?}",
+            id,
+            errors
+        );
+    }
 
     RE_EXTRA_PARAGRAPHS
         .replace_all(
@@ -98,6 +281,124 @@ fn translate_args(id: &str, args: &FluentArgs) -> String {
         .to_string()
 }
 
+/// How a Fluent pattern uses variables and other messages.
+#[derive(Debug, Default)]
+struct PatternUsage {
+    variables: BTreeSet<String>,
+    /// Variables that only pick a variant. Fluent uses the default variant when they're missing.
+    selectors: BTreeSet<String>,
+    /// Messages used within the pattern, like `message` or `message.attribute`.
+    references: BTreeSet<String>,
+}
+
+impl PatternUsage {
+    fn of(pattern: &Pattern<&str>) -> Self {
+        let mut usage = Self::default();
+        usage.visit_pattern(pattern);
+        usage.selectors.retain(|x| !usage.variables.contains(x));
+        usage
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern<&str>) {
+        for element in &pattern.elements {
+            if let PatternElement::Placeable { expression } = element {
+                self.visit_expression(expression);
+            }
+        }
+    }
+
+    fn visit_expression(&mut self, expression: &Expression<&str>) {
+        match expression {
+            Expression::Select { selector, variants } => {
+                match selector {
+                    InlineExpression::VariableReference { id } => {
+                        self.selectors.insert(id.name.to_string());
+                    }
+                    _ => self.visit_inline(selector),
+                }
+                for variant in variants {
+                    self.visit_pattern(&variant.value);
+                }
+            }
+            Expression::Inline(inline) => self.visit_inline(inline),
+        }
+    }
+
+    fn visit_inline(&mut self, inline: &InlineExpression<&str>) {
+        match inline {
+            InlineExpression::VariableReference { id } => {
+                self.variables.insert(id.name.to_string());
+            }
+            InlineExpression::MessageReference { id, attribute } => {
+                self.references.insert(match attribute {
+                    Some(attribute) => format!("{}.{}", id.name, attribute.name),
+                    None => id.name.to_string(),
+                });
+            }
+            InlineExpression::Placeable { expression } => self.visit_expression(expression),
+            _ => {}
+        }
+    }
+
+    fn extend(&mut self, other: Self) {
+        self.variables.extend(other.variables);
+        self.selectors.extend(other.selectors);
+        self.references.extend(other.references);
+        self.selectors.retain(|x| !self.variables.contains(x));
+    }
+}
+
+/// Compares the arguments passed for a message with the variables that it
+/// (and any message it references) actually uses.
+#[derive(Debug, Default, Eq, PartialEq)]
+struct ArgumentCheck {
+    missing: BTreeSet<String>,
+    unused: BTreeSet<String>,
+    /// Selectors without an argument, which are fine since they have a default variant.
+    optional_missing: BTreeSet<String>,
+}
+
+impl ArgumentCheck {
+    fn new(
+        bundle: &FluentBundle<FluentResource, IntlLangMemoizer>,
+        pattern: &Pattern<&str>,
+        args: &FluentArgs,
+    ) -> Self {
+        let mut usage = PatternUsage::of(pattern);
+        let mut pending: Vec<_> = usage.references.iter().cloned().collect();
+        let mut seen = BTreeSet::new();
+        while let Some(reference) = pending.pop() {
+            if !seen.insert(reference.clone()) {
+                continue;
+            }
+            let (name, attr) = match reference.split_once('.') {
+                Some((name, attr)) => (name, Some(attr)),
+                None => (reference.as_str(), None),
+            };
+            let referenced = bundle.get_message(name).and_then(|message| match attr {
+                Some(attr) => message.get_attribute(attr).map(|x| x.value()),
+                None => message.value(),
+            });
+            if let Some(referenced) = referenced {
+                let referenced = PatternUsage::of(referenced);
+                pending.extend(referenced.references.iter().cloned());
+                usage.extend(referenced);
+            }
+        }
+
+        let supplied: BTreeSet<_> = args.iter().map(|(name, _)| name.to_string()).collect();
+        Self {
+            missing: usage.variables.difference(&supplied).cloned().collect(),
+            unused: supplied
+                .iter()
+                .filter(|x| !usage.variables.contains(*x) && !usage.selectors.contains(*x))
+                .cloned()
+                .collect(),
+            optional_missing: usage.selectors.difference(&supplied).cloned().collect(),
+        }
+    }
+}
+
 impl Translator {
     pub fn window_title(&self) -> String {
         let name = translate("ludusavi");
@@ -111,18 +412,42 @@ impl Translator {
     pub fn handle_error(&self, error: &Error) -> String {
         match error {
             Error::ConfigInvalid { why } => self.config_is_invalid(why),
+            Error::UnknownProfile { name } => self.unknown_profile(name),
             Error::ManifestInvalid { why } => self.manifest_is_invalid(why),
             Error::ManifestCannotBeUpdated => self.manifest_cannot_be_updated(),
+            Error::ManifestNotFound { path } => self.manifest_not_found(path),
             Error::CliBackupTargetExists { path } => self.cli_backup_target_exists(path),
             Error::CliUnrecognizedGames { games } => self.cli_unrecognized_games(games),
             Error::CliUnableToRequestConfirmation => self.cli_unable_to_request_confirmation(),
             Error::SomeEntriesFailed => self.some_entries_failed(),
+            Error::SomeChecksFailed => self.some_checks_failed(),
             Error::CannotPrepareBackupTarget { path } => self.cannot_prepare_backup_target(path),
+            Error::BackupTargetLocked { path, stale } => self.backup_target_locked(path, *stale),
             Error::RestorationSourceInvalid { path } => self.restoration_source_is_invalid(path),
+            Error::BackupSignatureInvalid { path } => self.backup_signature_invalid(path),
+            Error::BackupSigningFailed { path } => self.backup_signing_failed(path),
+            Error::SignatureKeyInvalid { origin } => self.signature_key_invalid(origin),
+            Error::SignatureKeyMissing => self.signature_key_missing(),
+            Error::RootUnavailable { path } => self.root_unavailable(path),
+            Error::BackupTargetSkipped { path, origin } => {
+                format!("{}\n{}", self.backup_target_skipped(path), self.handle_error(origin))
+            }
+            Error::BackupCaseCollision { path } => self.backup_case_collision(path),
+            Error::BackupMigrationOverlap { path } => self.backup_migration_overlap(path),
             Error::RegistryIssue => self.registry_issue(),
+            Error::RegistryFileInvalid { path, why } => self.registry_file_is_invalid(path, why),
             Error::UnableToBrowseFileSystem => self.unable_to_browse_file_system(),
             Error::UnableToOpenDir(path) => self.unable_to_open_dir(path),
             Error::UnableToOpenUrl(url) => self.unable_to_open_url(url),
+            Error::CannotPrepareUndoSnapshot { path } => self.cannot_prepare_undo_snapshot(path),
+            Error::NoUndoSnapshot => self.no_undo_snapshot(),
+            Error::PortableDirNotWritable { path } => self.portable_dir_not_writable(path),
+            Error::GameIsRunning { games } => self.game_is_running(games),
+            Error::SettingsCannotBeExported { path } => self.settings_cannot_be_exported(path),
+            Error::SettingsCannotBeImported { path } => self.settings_cannot_be_imported(path),
+            Error::GameCannotBeExported { path } => self.game_cannot_be_exported(path),
+            Error::GameCannotBeImported { path } => self.game_cannot_be_imported(path),
+            Error::TerminalUiFailed { .. } => self.terminal_ui_failed(),
         }
     }
 
@@ -144,6 +469,210 @@ impl Translator {
         translate_args("cli-confirm-restoration", &args)
     }
 
+    pub fn cli_confirm_mirror_removal(&self, count: usize) -> String {
+        let mut args = FluentArgs::new();
+        args.set(COUNT, count);
+        translate_args("cli-confirm-mirror-removal", &args)
+    }
+
+    pub fn cli_confirm_undo_restore(&self) -> String {
+        translate("cli-confirm-undo-restore")
+    }
+
+    pub fn cli_undo_restore_summary(&self, count: usize) -> String {
+        let mut args = FluentArgs::new();
+        args.set(COUNT, count);
+        translate_args("cli-undo-restore-summary", &args)
+    }
+
+    pub fn cli_scan_cache_used(&self, count: usize) -> String {
+        let mut args = FluentArgs::new();
+        args.set(COUNT, count);
+        translate_args("cli-scan-cache-used", &args)
+    }
+
+    pub fn cli_confirm_clean(&self, path: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        translate_args("cli-confirm-clean", &args)
+    }
+
+    pub fn cli_clean_nothing(&self) -> String {
+        translate("cli-clean-nothing")
+    }
+
+    pub fn cli_clean_summary(&self, count: usize, bytes: u64, dry_run: bool) -> String {
+        let mut args = FluentArgs::new();
+        args.set(COUNT, count);
+        args.set(TOTAL_SIZE, self.adjusted_size(bytes));
+        if dry_run {
+            translate_args("cli-clean-summary-dry-run", &args)
+        } else {
+            translate_args("cli-clean-summary", &args)
+        }
+    }
+
+    pub fn cli_attention_nothing(&self) -> String {
+        translate("cli-attention-nothing")
+    }
+
+    /// When backups last ran, and when the next one is due if there's an interval.
+    /// `next` is `Some(None)` when there's an interval but no successful backup yet.
+    pub fn cli_schedule_status(
+        &self,
+        state: &ScheduleState,
+        next: Option<Option<chrono::DateTime<chrono::Utc>>>,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> String {
+        let time = |x: &chrono::DateTime<chrono::Utc>| x.to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        let never = translate("schedule-never");
+        let mut lines = vec![];
+
+        let mut args = FluentArgs::new();
+        args.set(TIME, state.last_run.as_ref().map(time).unwrap_or_else(|| never.clone()));
+        let mut line = translate_args("schedule-last-run", &args);
+        if state.last_run.is_some() && !state.last_run_succeeded {
+            line = format!("{} ({})", line, translate("schedule-failed"));
+        }
+        lines.push(line);
+
+        let mut args = FluentArgs::new();
+        args.set(TIME, state.last_success.as_ref().map(time).unwrap_or(never));
+        lines.push(translate_args("schedule-last-success", &args));
+
+        if let Some(next) = next {
+            let mut args = FluentArgs::new();
+            match next {
+                Some(next) if next > now => args.set(TIME, time(&next)),
+                _ => args.set(TIME, translate("schedule-due-now")),
+            }
+            lines.push(translate_args("schedule-next", &args));
+        }
+
+        lines.join("\n")
+    }
+
+    /// Each Xbox container with its save files, or a note if it can't be read.
+    pub fn cli_xbox_saves(&self, containers: &[WgsContainer]) -> String {
+        if containers.is_empty() {
+            return translate("cli-no-xbox-saves");
+        }
+        let mut lines = vec![];
+        for container in containers {
+            if container.supported {
+                lines.push(container.name.clone());
+            } else {
+                lines.push(format!(
+                    "{} [{}]",
+                    container.name,
+                    translate("cli-xbox-container-unsupported")
+                ));
+            }
+            for file in &container.files {
+                lines.push(format!("  - {}: {}", file.name, file.path.render()));
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// A file from an Xbox container, along with the name that the game knows it by.
+    pub fn xbox_save_file(&self, path: &StrictPath, name: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        args.set(NAME, name);
+        translate_args("xbox-save-file", &args)
+    }
+
+    /// Each of a game's backups, with what produced it.
+    pub fn cli_backup_provenance(&self, name: &str, backups: &[BackupProvenance]) -> String {
+        let mut lines = vec![format!("{}:", name)];
+        for backup in backups {
+            lines.push(format!(
+                "  - {} ({}):",
+                backup.name,
+                backup.when.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+            ));
+            let provenance = match &backup.provenance {
+                Some(x) => x,
+                None => {
+                    lines.push(format!("      {}", translate("cli-provenance-unknown")));
+                    continue;
+                }
+            };
+
+            let mut args = FluentArgs::new();
+            args.set(VERSION, provenance.app_version.as_str());
+            lines.push(format!("      {}", translate_args("cli-provenance-version", &args)));
+            let mut args = FluentArgs::new();
+            args.set(PATH, provenance.manifest.as_str());
+            lines.push(format!("      {}", translate_args("cli-provenance-manifest", &args)));
+            if let Some(etag) = &provenance.manifest_etag {
+                let mut args = FluentArgs::new();
+                args.set(ID, etag.as_str());
+                lines.push(format!(
+                    "      {}",
+                    translate_args("cli-provenance-manifest-etag", &args)
+                ));
+            }
+            let mut args = FluentArgs::new();
+            args.set(ID, provenance.config_hash.as_str());
+            lines.push(format!("      {}", translate_args("cli-provenance-config-hash", &args)));
+        }
+        lines.join("\n")
+    }
+
+    fn badge_concern(&self, concern: Concern) -> String {
+        match concern {
+            Concern::Failed => translate("badge-failed"),
+            Concern::Corrupted => translate("badge-corrupted"),
+            Concern::Orphaned => translate("badge-orphaned"),
+            Concern::NeverBackedUp => translate("badge-never-backed-up"),
+        }
+    }
+
+    pub fn cli_attention_game(&self, name: &str, concerns: &std::collections::BTreeSet<Concern>) -> String {
+        let badges: Vec<_> = concerns
+            .iter()
+            .map(|x| format!("[{}]", self.badge_concern(*x)))
+            .collect();
+        format!("{} {}", name, badges.join(" "))
+    }
+
+    pub fn cli_attention_summary(&self, report: &AttentionReport) -> String {
+        Concern::ALL
+            .iter()
+            .map(|x| format!("{}: {}", self.badge_concern(*x), report.count(*x)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn cli_diff_no_changes(&self) -> String {
+        translate("cli-diff-no-changes")
+    }
+
+    pub fn cli_diff_game(&self, name: &str, change: &Option<Change>) -> String {
+        match change {
+            Some(Change::Added) => format!("{} [{}]", name, translate("badge-diff-added")),
+            Some(Change::Removed) => format!("{} [{}]", name, translate("badge-diff-removed")),
+            Some(Change::Modified) | None => name.to_string(),
+        }
+    }
+
+    pub fn cli_diff_file(&self, path: &str, file: &FileDiff) -> String {
+        let size = |bytes: Option<u64>| bytes.map(|x| self.item_size(x)).unwrap_or_default();
+        match file.change {
+            Change::Added => format!("[{}] {} [{}]", translate("badge-diff-added"), path, size(file.after)),
+            Change::Removed => format!("[{}] {} [{}]", translate("badge-diff-removed"), path, size(file.before)),
+            Change::Modified => format!(
+                "[{}] {} [{} -> {}]",
+                translate("badge-diff-modified"),
+                path,
+                size(file.before),
+                size(file.after)
+            ),
+        }
+    }
+
     pub fn cli_unable_to_request_confirmation(&self) -> String {
         #[cfg(target_os = "windows")]
         let extra_note = translate("cli-unable-to-request-confirmation.winpty-workaround");
@@ -154,10 +683,26 @@ impl Translator {
         format!("{} {}", translate("cli-unable-to-request-confirmation"), extra_note)
     }
 
+    pub fn cli_unable_to_write_output_file(&self, path: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        translate_args("cli-unable-to-write-output-file", &args)
+    }
+
+    pub fn cli_unknown_redirect_game(&self, game: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set(NAME, game);
+        translate_args("cli-unknown-redirect-game", &args)
+    }
+
     pub fn some_entries_failed(&self) -> String {
         translate("some-entries-failed")
     }
 
+    pub fn some_checks_failed(&self) -> String {
+        translate("some-checks-failed")
+    }
+
     fn label(&self, text: &str) -> String {
         format!("[{}]", text)
     }
@@ -166,6 +711,22 @@ impl Translator {
         self.label(&self.badge_failed())
     }
 
+    pub fn label_mismatched(&self) -> String {
+        self.label(&self.badge_mismatched())
+    }
+
+    pub fn label_denied(&self) -> String {
+        self.label(&self.badge_denied())
+    }
+
+    pub fn label_cloud_only(&self) -> String {
+        self.label(&self.badge_cloud_only())
+    }
+
+    pub fn label_empty(&self) -> String {
+        self.label(&translate("badge-empty"))
+    }
+
     pub fn label_duplicates(&self) -> String {
         self.label(&self.badge_duplicates())
     }
@@ -178,10 +739,42 @@ impl Translator {
         self.label(&self.badge_ignored())
     }
 
+    pub fn label_system(&self) -> String {
+        self.label(&self.badge_system())
+    }
+
+    pub fn label_ownership(&self) -> String {
+        self.label(&translate("badge-ownership"))
+    }
+
     pub fn badge_failed(&self) -> String {
         translate("badge-failed")
     }
 
+    pub fn badge_mismatched(&self) -> String {
+        translate("badge-mismatched")
+    }
+
+    pub fn badge_denied(&self) -> String {
+        translate("badge-denied")
+    }
+
+    pub fn badge_cloud_only(&self) -> String {
+        translate("badge-cloud-only")
+    }
+
+    pub fn cloud_only_notice(&self) -> String {
+        translate("cloud-only-notice")
+    }
+
+    pub fn save_locations_header(&self) -> String {
+        translate("save-locations-header")
+    }
+
+    pub fn save_locations_unscanned_notice(&self) -> String {
+        translate("save-locations-unscanned-notice")
+    }
+
     pub fn badge_duplicates(&self) -> String {
         translate("badge-duplicates")
     }
@@ -194,6 +787,18 @@ impl Translator {
         translate("badge-ignored")
     }
 
+    pub fn badge_system(&self) -> String {
+        translate("badge-system")
+    }
+
+    pub fn badge_restore_action(&self, action: &RestoreAction) -> String {
+        translate(match action {
+            RestoreAction::Create => "badge-restore-create",
+            RestoreAction::Overwrite => "badge-restore-overwrite",
+            RestoreAction::Skip => "badge-restore-skip",
+        })
+    }
+
     pub fn badge_redirected_from(&self, original: &StrictPath) -> String {
         let mut args = FluentArgs::new();
         args.set(PATH, original.render());
@@ -206,13 +811,24 @@ impl Translator {
         bytes: u64,
         decision: &OperationStepDecision,
         duplicated: bool,
+        badge_order: &[BadgeKind],
     ) -> String {
         let mut labels = vec![];
-        if *decision == OperationStepDecision::Ignored {
-            labels.push(self.label_ignored());
-        }
-        if duplicated {
-            labels.push(self.label_duplicates());
+        for badge in BadgeKind::ordered(badge_order) {
+            match badge {
+                BadgeKind::Ignored if *decision == OperationStepDecision::Ignored => labels.push(self.label_ignored()),
+                BadgeKind::Ignored if *decision == OperationStepDecision::Deferred => {
+                    labels.push(self.label(&translate("badge-deferred")))
+                }
+                BadgeKind::Ignored if *decision == OperationStepDecision::RootUnavailable => {
+                    labels.push(self.label(&translate("badge-root-unavailable")))
+                }
+                BadgeKind::Ignored if *decision == OperationStepDecision::Frozen => {
+                    labels.push(self.label(&translate("badge-frozen")))
+                }
+                BadgeKind::Duplicated if duplicated => labels.push(self.label_duplicates()),
+                _ => {}
+            }
         }
 
         if labels.is_empty() {
@@ -222,26 +838,134 @@ impl Translator {
         }
     }
 
-    pub fn cli_game_line_item(&self, item: &str, successful: bool, ignored: bool, duplicated: bool) -> String {
+    pub fn cli_game_frozen(&self, name: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set(NAME, name);
+        translate_args("cli-game-frozen", &args)
+    }
+
+    pub fn cli_archive_with_merge(&self) -> String {
+        translate("cli-archive-with-merge")
+    }
+
+    pub fn cli_archive_with_differential(&self) -> String {
+        translate("cli-archive-with-differential")
+    }
+
+    pub fn backup_grouping_kept(&self, target: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, target.render());
+        translate_args("backup-grouping-kept", &args)
+    }
+
+    pub fn root_unavailable(&self, path: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        translate_args("root-unavailable", &args)
+    }
+
+    pub fn backup_target_skipped(&self, path: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        translate_args("backup-target-skipped", &args)
+    }
+
+    pub fn cli_game_cloud_only(&self, name: &str) -> String {
+        format!("{} {}:\n  {}", name, self.label_cloud_only(), self.cloud_only_notice())
+    }
+
+    pub fn cli_game_empty(&self, name: &str) -> String {
+        format!(
+            "{} [{}] {}:\n  {}",
+            name,
+            self.adjusted_size(0),
+            self.label_empty(),
+            translate("empty-game-notice")
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn cli_game_line_item(&self, item: &str, badges: &LineItemBadges, badge_order: &[BadgeKind]) -> String {
         let mut parts = vec![];
-        if !successful {
-            parts.push(self.label_failed());
-        }
-        if ignored {
-            parts.push(self.label_ignored());
-        }
-        if duplicated {
-            parts.push(self.label_duplicated());
+        for badge in BadgeKind::ordered(badge_order) {
+            match badge {
+                BadgeKind::Failed if badges.failed => parts.push(self.label_failed()),
+                BadgeKind::Denied if badges.denied => parts.push(self.label_denied()),
+                BadgeKind::Ignored if badges.ignored => parts.push(self.label_ignored()),
+                BadgeKind::Duplicated if badges.duplicated => parts.push(self.label_duplicated()),
+                BadgeKind::Mismatched if badges.mismatched => parts.push(self.label_mismatched()),
+                BadgeKind::Partial if badges.partial => parts.push(self.label(&translate("badge-partial"))),
+                BadgeKind::System if badges.system => parts.push(self.label_system()),
+                BadgeKind::Ownership if badges.ownership => parts.push(self.label_ownership()),
+                BadgeKind::RestoreAction => {
+                    if let Some(restore_action) = &badges.restore_action {
+                        parts.push(self.label(&self.badge_restore_action(restore_action)));
+                    }
+                }
+                _ => {}
+            }
         }
         parts.push(item.to_string());
 
         format!("  - {}", parts.join(" "))
     }
 
+    /// A short explanation of why a file failed.
+    /// Anything we don't have a message for falls back to the OS's own description.
+    pub fn failure_reason(&self, reason: &FailureReason) -> String {
+        if reason.is_disk_full() {
+            return translate("failure-reason-disk-full");
+        }
+        match reason.kind {
+            std::io::ErrorKind::PermissionDenied => translate("failure-reason-permission-denied"),
+            std::io::ErrorKind::NotFound => translate("failure-reason-not-found"),
+            std::io::ErrorKind::AlreadyExists => translate("failure-reason-already-exists"),
+            std::io::ErrorKind::TimedOut => translate("failure-reason-timed-out"),
+            _ => reason.message.clone(),
+        }
+    }
+
+    pub fn cli_game_line_item_removed(&self, item: &str) -> String {
+        format!("  - {} {}", self.label(&translate("badge-removed")), item)
+    }
+
+    pub fn cli_game_line_item_failure(&self, line: &str, reason: &FailureReason) -> String {
+        format!("{} ({})", line, self.failure_reason(reason))
+    }
+
+    /// Explain how restoring a file would change its owner or mode.
+    /// Modes are shown in octal, like `chmod` takes them.
+    pub fn cli_game_line_item_ownership(&self, line: &str, mismatch: &OwnershipMismatch) -> String {
+        let mut details = vec![];
+        if let Some((before, after)) = mismatch.owner {
+            let mut args = FluentArgs::new();
+            args.set(BEFORE, before.to_string());
+            args.set(AFTER, after.to_string());
+            details.push(translate_args("ownership-owner-changed", &args));
+        }
+        if let Some((before, after)) = mismatch.mode {
+            let mut args = FluentArgs::new();
+            args.set(BEFORE, format!("{:o}", before));
+            args.set(AFTER, format!("{:o}", after));
+            details.push(translate_args("ownership-mode-changed", &args));
+        }
+        if mismatch.unprivileged {
+            details.push(translate("ownership-unprivileged"));
+        }
+        format!("{} ({})", line, details.join("; "))
+    }
+
     pub fn cli_game_line_item_redirected(&self, item: &str) -> String {
         let mut args = FluentArgs::new();
         args.set(PATH, item);
-        translate_args("cli-game-line-redirected-from", &args)
+        translate_args("cli-game-line-item-redirected", &args)
+    }
+
+    pub fn cli_game_registry_values(&self, counts: &RegistryValueCounts) -> String {
+        let mut args = FluentArgs::new();
+        args.set(BACKED_UP, counts.backed_up);
+        args.set(SKIPPED, counts.skipped);
+        format!("  {}", translate_args("cli-game-registry-values", &args))
     }
 
     pub fn cli_summary(&self, status: &OperationStatus, location: &StrictPath) -> String {
@@ -253,12 +977,198 @@ impl Translator {
         args.set(PROCESSED_SIZE, self.adjusted_size(status.processed_bytes));
 
         if status.processed_all() {
-            translate_args("cli-summary.succeeded", &args)
+            match status.available_games {
+                Some(available) if available > status.total_games => {
+                    args.set(AVAILABLE_GAMES, available);
+                    translate_args("cli-summary.selected", &args)
+                }
+                _ => translate_args("cli-summary.succeeded", &args),
+            }
         } else {
             translate_args("cli-summary.failed", &args)
         }
     }
 
+    pub fn cli_no_games_found(&self) -> String {
+        translate("cli-no-games-found")
+    }
+
+    pub fn cli_all_games_ignored(&self) -> String {
+        translate("cli-all-games-ignored")
+    }
+
+    pub fn cli_skipped_identical_summary(&self, count: usize) -> String {
+        let mut args = FluentArgs::new();
+        args.set(COUNT, count);
+        translate_args("cli-summary-skipped-identical", &args)
+    }
+
+    pub fn cli_copied_summary(&self, bytes: u64) -> String {
+        let mut args = FluentArgs::new();
+        args.set(SIZE, self.adjusted_size(bytes));
+        translate_args("cli-summary-copied", &args)
+    }
+
+    /// How one of several backup targets fared, like "Target /mnt/nas: 3 games".
+    pub fn cli_target_summary(&self, target: &str, status: &TargetStatus) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, target);
+        args.set(PROCESSED_GAMES, status.processed_games);
+        args.set(COUNT, status.failed_games);
+        if status.unusable {
+            translate_args("cli-summary-target-unusable", &args)
+        } else if status.successful() {
+            translate_args("cli-summary-target-succeeded", &args)
+        } else {
+            translate_args("cli-summary-target-failed", &args)
+        }
+    }
+
+    pub fn cli_dry_run_summary(&self) -> String {
+        translate("cli-summary-dry-run")
+    }
+
+    /// How many games were skipped for each reason, like "Skipped: 3 ignored, 2 deferred".
+    /// Reasons without any games are left out, and so is the whole line if there are none.
+    pub fn skip_summary(&self, skipped: &std::collections::BTreeMap<SkipReason, usize>) -> Option<String> {
+        let reasons: Vec<_> = skipped
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .map(|(reason, count)| {
+                let mut args = FluentArgs::new();
+                args.set(COUNT, *count);
+                match reason {
+                    SkipReason::Ignored => translate_args("skip-reason-ignored", &args),
+                    SkipReason::Deferred => translate_args("skip-reason-deferred", &args),
+                    SkipReason::Cancelled => translate_args("skip-reason-cancelled", &args),
+                    SkipReason::RootUnavailable => translate_args("skip-reason-root-unavailable", &args),
+                    SkipReason::Frozen => translate_args("skip-reason-frozen", &args),
+                }
+            })
+            .collect();
+        if reasons.is_empty() {
+            return None;
+        }
+
+        let mut args = FluentArgs::new();
+        args.set(REASONS, reasons.join(", "));
+        Some(translate_args("cli-summary-skipped", &args))
+    }
+
+    pub fn warning(&self, warning: &Warning) -> String {
+        let mut args = FluentArgs::new();
+        match warning {
+            Warning::DeniedFileSkipped { game } => {
+                args.set(NAME, game.as_str());
+                translate_args("warning-denied-file-skipped", &args)
+            }
+            Warning::DeferralLimitReached { game } => {
+                args.set(NAME, game.as_str());
+                translate_args("warning-deferral-limit-reached", &args)
+            }
+            Warning::AlternateStreamsStripped { game } => {
+                args.set(NAME, game.as_str());
+                translate_args("warning-alternate-streams-stripped", &args)
+            }
+            Warning::AlternateStreamsUnsupported { game } => {
+                args.set(NAME, game.as_str());
+                translate_args("warning-alternate-streams-unsupported", &args)
+            }
+            Warning::CaseNormalized { game } => {
+                args.set(NAME, game.as_str());
+                translate_args("warning-case-normalized", &args)
+            }
+            Warning::WeakIdenticalCheck { game } => {
+                args.set(NAME, game.as_str());
+                translate_args("warning-weak-identical-check", &args)
+            }
+        }
+    }
+
+    /// Identical warnings are shown once, with how many times they happened.
+    pub fn warning_with_count(&self, warning: &Warning, count: usize) -> String {
+        let text = self.warning(warning);
+        if count > 1 {
+            let mut args = FluentArgs::new();
+            args.set(COUNT, count);
+            format!("{} {}", text, translate_args("warning-repeated", &args))
+        } else {
+            text
+        }
+    }
+
+    pub fn warnings_header(&self) -> String {
+        translate("warnings-header")
+    }
+
+    pub fn cli_warnings(&self, status: &OperationStatus) -> String {
+        let mut lines = vec![self.warnings_header()];
+        for (warning, count) in status.collapsed_warnings() {
+            lines.push(format!("  - {}", self.warning_with_count(warning, count)));
+        }
+        lines.join("\n")
+    }
+
+    /// Everything that failed, grouped by reason, with large groups cut down to a sample.
+    pub fn cli_error_report(&self, report: &ErrorReport) -> String {
+        let mut lines = vec![translate("error-report-header")];
+        for group in &report.groups {
+            let reason = match &group.reason {
+                Some(reason) => self.failure_reason(reason),
+                None => translate("failure-reason-unknown"),
+            };
+            if group.collapsed() {
+                let mut args = FluentArgs::new();
+                args.set(COUNT, group.count);
+                args.set(TOTAL_GAMES, group.games.len());
+                lines.push(format!(
+                    "  {}: {}",
+                    reason,
+                    translate_args("error-report-collapsed", &args)
+                ));
+            } else {
+                lines.push(format!("  {}:", reason));
+            }
+            for entry in &group.sample {
+                lines.push(format!("    - {}: {}", entry.game, entry.path));
+            }
+        }
+        lines.join("\n")
+    }
+
+    pub fn cli_verify_sample(&self, result: &SampleResult) -> String {
+        let mut args = FluentArgs::new();
+        args.set(COUNT, result.checked);
+        args.set(TOTAL, result.total);
+        args.set(ID, result.seed.to_string());
+        args.set(PERCENT, format!("{:.1}", result.extrapolated_percent()));
+        if result.mismatched > 0 {
+            args.set(MISMATCHED, result.mismatched);
+            translate_args("cli-verify-sample-failed", &args)
+        } else if result.checked < result.total {
+            translate_args("cli-verify-sample-passed", &args)
+        } else {
+            translate_args("cli-verify-sample-complete", &args)
+        }
+    }
+
+    pub fn cli_estimate_files_summary(&self, count: usize) -> String {
+        let mut args = FluentArgs::new();
+        args.set(COUNT, count);
+        translate_args("cli-summary-estimate-files", &args)
+    }
+
+    pub fn cli_estimate_summary(&self) -> String {
+        translate("cli-summary-estimate")
+    }
+
+    pub fn cli_scan_heartbeat(&self, name: &str, count: usize) -> String {
+        let mut args = FluentArgs::new();
+        args.set(NAME, name);
+        args.set(COUNT, count);
+        translate_args("cli-scan-heartbeat", &args)
+    }
+
     pub fn backup_button(&self) -> String {
         translate("button-backup")
     }
@@ -299,17 +1209,126 @@ impl Translator {
         translate("no-missing-roots")
     }
 
-    pub fn confirm_add_missing_roots(&self, roots: &[crate::config::RootsConfig]) -> String {
-        use std::fmt::Write;
-        let mut msg = translate("confirm-add-missing-roots") + "\n";
+    pub fn merge_roots_button(&self) -> String {
+        translate("button-merge-roots")
+    }
+
+    pub fn skip_root_button(&self) -> String {
+        translate("button-skip-root")
+    }
+
+    pub fn root_overlap(&self, overlap: &RootOverlap, other: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, other.render());
+        match overlap {
+            RootOverlap::Duplicate => translate_args("root-overlap-duplicate", &args),
+            RootOverlap::Inside => translate_args("root-overlap-inside", &args),
+            RootOverlap::Contains => translate_args("root-overlap-contains", &args),
+        }
+    }
+
+    pub fn confirm_add_missing_roots(&self, roots: &[RootCandidate]) -> String {
+        use std::fmt::Write;
+        let mut msg = translate("confirm-add-missing-roots") + "\n";
 
-        for root in roots {
-            let _ = &write!(msg, "\n[{}] {}", self.store(&root.store), root.path.render());
+        for RootCandidate { root, confidence } in roots {
+            let _ = &write!(
+                msg,
+                "\n[{}] {} ({})",
+                self.store(&root.store),
+                root.path.render(),
+                self.root_confidence(confidence)
+            );
         }
 
         msg
     }
 
+    pub fn cli_config_diff(&self, diffs: &[ConfigDiff]) -> String {
+        if diffs.is_empty() {
+            return translate("cli-config-same-as-default");
+        }
+        diffs
+            .iter()
+            .map(|diff| match diff {
+                ConfigDiff::Changed { path, default, value } => format!("{}: {} -> {}", path, default, value),
+                ConfigDiff::Added { path, value } => format!("{}: + {}", path, value),
+                ConfigDiff::Removed { path, value } => format!("{}: - {}", path, value),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn cli_found_roots(&self, roots: &[RootCandidate]) -> String {
+        if roots.is_empty() {
+            return self.no_missing_roots();
+        }
+        roots
+            .iter()
+            .map(|RootCandidate { root, confidence }| {
+                format!(
+                    "[{}] {} ({})",
+                    self.store(&root.store),
+                    root.path.render(),
+                    self.root_confidence(confidence)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn root_confidence(&self, confidence: &RootConfidence) -> String {
+        translate(match confidence {
+            RootConfidence::Certain => "root-confidence-certain",
+            RootConfidence::Likely => "root-confidence-likely",
+            RootConfidence::Guess => "root-confidence-guess",
+        })
+    }
+
+    pub fn browse_button(&self) -> String {
+        translate("button-browse")
+    }
+
+    pub fn add_button(&self) -> String {
+        translate("button-add")
+    }
+
+    pub fn remove_button(&self) -> String {
+        translate("button-remove")
+    }
+
+    pub fn search_button(&self) -> String {
+        translate("button-search")
+    }
+
+    pub fn customize_button(&self) -> String {
+        translate("button-customize")
+    }
+
+    pub fn open_backup_folder_button(&self) -> String {
+        translate("button-open-backup-folder")
+    }
+
+    pub fn open_wiki_button(&self) -> String {
+        translate("button-open-wiki")
+    }
+
+    pub fn open_folder_button(&self) -> String {
+        translate("button-open-folder")
+    }
+
+    pub fn copy_path_button(&self) -> String {
+        translate("button-copy-path")
+    }
+
+    pub fn increase_button(&self) -> String {
+        translate("button-increase")
+    }
+
+    pub fn decrease_button(&self) -> String {
+        translate("button-decrease")
+    }
+
     pub fn add_redirect_button(&self) -> String {
         translate("button-add-redirect")
     }
@@ -358,6 +1377,18 @@ impl Translator {
         format!("{}\n{}", translate("config-is-invalid"), why)
     }
 
+    pub fn registry_file_is_invalid(&self, path: &StrictPath, why: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        format!("{}\n{}", translate_args("registry-file-is-invalid", &args), why)
+    }
+
+    pub fn unknown_profile(&self, name: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set(NAME, name);
+        translate_args("unknown-profile", &args)
+    }
+
     pub fn manifest_is_invalid(&self, why: &str) -> String {
         format!("{}\n{}", translate("manifest-is-invalid"), why)
     }
@@ -366,18 +1397,68 @@ impl Translator {
         translate("manifest-cannot-be-updated")
     }
 
+    pub fn manifest_not_found(&self, path: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        translate_args("manifest-not-found", &args)
+    }
+
     pub fn cannot_prepare_backup_target(&self, target: &StrictPath) -> String {
         let mut args = FluentArgs::new();
         args.set(PATH, target.render());
         translate_args("cannot-prepare-backup-target", &args)
     }
 
+    pub fn backup_target_locked(&self, lock_file: &StrictPath, stale: bool) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, lock_file.render());
+        if stale {
+            translate_args("backup-target-locked-stale", &args)
+        } else {
+            translate_args("backup-target-locked", &args)
+        }
+    }
+
     pub fn restoration_source_is_invalid(&self, source: &StrictPath) -> String {
         let mut args = FluentArgs::new();
         args.set(PATH, source.render());
         translate_args("restoration-source-is-invalid", &args)
     }
 
+    pub fn backup_case_collision(&self, path: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        translate_args("backup-case-collision", &args)
+    }
+
+    pub fn backup_migration_overlap(&self, path: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        translate_args("backup-migration-overlap", &args)
+    }
+
+    pub fn backup_signature_invalid(&self, path: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        translate_args("backup-signature-invalid", &args)
+    }
+
+    pub fn backup_signing_failed(&self, path: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        translate_args("backup-signing-failed", &args)
+    }
+
+    pub fn signature_key_invalid(&self, origin: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, origin);
+        translate_args("signature-key-invalid", &args)
+    }
+
+    pub fn signature_key_missing(&self) -> String {
+        translate("signature-key-missing")
+    }
+
     pub fn registry_issue(&self) -> String {
         translate("registry-issue")
     }
@@ -390,14 +1471,391 @@ impl Translator {
         format!("{}\n\n{}", translate("unable-to-open-directory"), path.render())
     }
 
+    pub fn cannot_prepare_undo_snapshot(&self, path: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        translate_args("cannot-prepare-undo-snapshot", &args)
+    }
+
+    pub fn no_undo_snapshot(&self) -> String {
+        translate("no-undo-snapshot")
+    }
+
+    pub fn doctor_status(&self, status: CheckStatus) -> String {
+        match status {
+            CheckStatus::Pass => self.label(&translate("badge-pass")),
+            CheckStatus::Warn => self.label(&translate("badge-warning")),
+            CheckStatus::Fail => self.label_failed(),
+        }
+    }
+
+    pub fn doctor_check_config(&self) -> String {
+        translate("doctor-check-config")
+    }
+
+    pub fn doctor_check_manifest(&self) -> String {
+        translate("doctor-check-manifest")
+    }
+
+    pub fn doctor_check_root(&self, path: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.raw());
+        translate_args("doctor-check-root", &args)
+    }
+
+    pub fn doctor_check_backup_target(&self, path: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        translate_args("doctor-check-backup-target", &args)
+    }
+
+    pub fn doctor_check_manifest_conflicts(&self) -> String {
+        translate("doctor-check-manifest-conflicts")
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn doctor_check_registry(&self) -> String {
+        translate("doctor-check-registry")
+    }
+
+    pub fn doctor_check_steam_user(&self, user: &SteamUser) -> String {
+        let mut args = FluentArgs::new();
+        args.set(NAME, self.steam_user(user));
+        translate_args("doctor-check-steam-user", &args)
+    }
+
+    pub fn doctor_hint_steam_user_missing(&self) -> String {
+        translate("doctor-hint-steam-user-missing")
+    }
+
+    pub fn doctor_hint_manifest_conflicts(&self, conflicts: &[ManifestConflict]) -> String {
+        let mut args = FluentArgs::new();
+        args.set(
+            NAME,
+            conflicts.iter().map(|x| x.game.as_str()).collect::<Vec<_>>().join(", "),
+        );
+        translate_args("doctor-hint-manifest-conflicts", &args)
+    }
+
+    pub fn doctor_hint_config_missing(&self) -> String {
+        translate("doctor-hint-config-missing")
+    }
+
+    pub fn doctor_hint_manifest_missing(&self) -> String {
+        translate("doctor-hint-manifest-missing")
+    }
+
+    pub fn doctor_hint_root_missing(&self) -> String {
+        translate("doctor-hint-root-missing")
+    }
+
+    pub fn doctor_hint_root_unreadable(&self) -> String {
+        translate("doctor-hint-root-unreadable")
+    }
+
+    pub fn find_game_manifest(&self) -> String {
+        translate("find-game-manifest")
+    }
+
+    pub fn find_game_roots(&self) -> String {
+        translate("find-game-roots")
+    }
+
+    pub fn find_game_root(&self, root: &RootsConfig, install_dir: &Option<String>) -> String {
+        let install_dir = match install_dir {
+            Some(name) => {
+                let mut args = FluentArgs::new();
+                args.set(NAME, name.as_str());
+                translate_args("find-game-root-install-dir", &args)
+            }
+            None => translate("find-game-root-no-install-dir"),
+        };
+        format!(
+            "  - {} {} ({})",
+            self.label(&self.store(&root.store)),
+            root.path.render(),
+            install_dir
+        )
+    }
+
+    pub fn find_game_paths(&self) -> String {
+        translate("find-game-paths")
+    }
+
+    pub fn find_game_path(&self, diagnosis: &PathDiagnosis) -> String {
+        let reason = match diagnosis.check {
+            PathCheck::Unresolved => translate("find-game-path-unresolved"),
+            PathCheck::OtherOs => translate("find-game-path-other-os"),
+            PathCheck::Ignored => translate("find-game-path-ignored"),
+            PathCheck::NoMatch => translate("find-game-path-no-match"),
+            PathCheck::Matched(count) => {
+                let mut args = FluentArgs::new();
+                args.set(COUNT, count);
+                translate_args("find-game-path-matched", &args)
+            }
+        };
+        match &diagnosis.candidate {
+            Some(candidate) => format!("    - {}: {}", candidate.render(), reason),
+            None => format!(
+                "    - {} {}: {}",
+                self.label(&self.store(&diagnosis.root.store)),
+                diagnosis.root.path.render(),
+                reason
+            ),
+        }
+    }
+
+    pub fn find_game_nothing_found(&self) -> String {
+        translate("find-game-nothing-found")
+    }
+
+    pub fn manifest_updated(&self, changes: &ManifestChanges) -> String {
+        if changes.initial {
+            return translate("manifest-initial");
+        }
+        let mut args = FluentArgs::new();
+        args.set(ADDED, changes.added.len());
+        args.set(CHANGED, changes.changed.len());
+        args.set(REMOVED, changes.removed.len());
+        translate_args("manifest-updated", &args)
+    }
+
+    /// The summary of a manifest update, followed by each game that it affected.
+    pub fn cli_manifest_changes(&self, changes: Option<&ManifestChanges>) -> String {
+        let changes = match changes {
+            Some(x) => x,
+            None => return translate("cli-manifest-changes-none"),
+        };
+        let mut lines = vec![self.manifest_updated(changes)];
+        let groups = [
+            (&changes.added, translate("badge-diff-added")),
+            (&changes.changed, translate("badge-diff-modified")),
+            (&changes.removed, translate("badge-diff-removed")),
+        ];
+        for (names, badge) in groups {
+            let label = self.label(&badge);
+            lines.extend(names.iter().map(|name| format!("  {} {}", label, name)));
+        }
+        lines.join("\n")
+    }
+
+    pub fn cli_manifest_valid(&self) -> String {
+        translate("cli-manifest-valid")
+    }
+
+    /// One line per issue, for the details of `manifest_is_invalid`.
+    pub fn manifest_issues(&self, issues: &[ManifestIssue]) -> String {
+        issues
+            .iter()
+            .map(|issue| {
+                let why = match &issue.kind {
+                    ManifestIssueKind::UnknownPlaceholder(name) => {
+                        let mut args = FluentArgs::new();
+                        args.set(NAME, name.as_str());
+                        translate_args("manifest-issue-unknown-placeholder", &args)
+                    }
+                    ManifestIssueKind::MalformedPlaceholder => translate("manifest-issue-malformed-placeholder"),
+                    ManifestIssueKind::MalformedGlob(e) => {
+                        format!("{} ({})", translate("manifest-issue-malformed-glob"), e)
+                    }
+                };
+                format!("  - {}: {} ({})", issue.game, issue.path, why)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn manifest_conflict(&self, conflict: &ManifestConflict) -> String {
+        let mut args = FluentArgs::new();
+        args.set(NAME, conflict.game.as_str());
+        args.set(PATH, conflict.sources.first().cloned().unwrap_or_default());
+        translate_args("manifest-conflict", &args)
+    }
+
+    pub fn manifest_cached(&self, age: std::time::Duration) -> String {
+        let seconds = age.as_secs();
+        let age = match seconds {
+            86_400.. => format!("{}d", seconds / 86_400),
+            3_600.. => format!("{}h", seconds / 3_600),
+            60.. => format!("{}m", seconds / 60),
+            _ => format!("{}s", seconds),
+        };
+        let mut args = FluentArgs::new();
+        args.set(AGE, age);
+        translate_args("manifest-cached", &args)
+    }
+
+    pub fn portable_dir_not_writable(&self, path: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        translate_args("portable-dir-not-writable", &args)
+    }
+
+    pub fn settings_cannot_be_exported(&self, path: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        translate_args("settings-cannot-be-exported", &args)
+    }
+
+    pub fn settings_cannot_be_imported(&self, path: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        translate_args("settings-cannot-be-imported", &args)
+    }
+
+    pub fn cli_settings_exported(&self, path: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        translate_args("cli-settings-exported", &args)
+    }
+
+    pub fn cli_settings_imported(&self, path: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        translate_args("cli-settings-imported", &args)
+    }
+
+    pub fn game_cannot_be_exported(&self, path: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        translate_args("game-cannot-be-exported", &args)
+    }
+
+    pub fn game_cannot_be_imported(&self, path: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        translate_args("game-cannot-be-imported", &args)
+    }
+
+    pub fn terminal_ui_failed(&self) -> String {
+        translate("terminal-ui-failed")
+    }
+
+    pub fn tui_help(&self) -> String {
+        translate("tui-help")
+    }
+
+    pub fn tui_filter_help(&self) -> String {
+        translate("tui-filter-help")
+    }
+
+    pub fn tui_filter(&self, filter: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set(NAME, filter);
+        translate_args("tui-filter", &args)
+    }
+
+    pub fn tui_selected(&self, count: usize, total: usize) -> String {
+        let mut args = FluentArgs::new();
+        args.set(COUNT, count);
+        args.set(TOTAL, total);
+        translate_args("tui-selected", &args)
+    }
+
+    pub fn tui_no_games(&self) -> String {
+        translate("tui-no-games")
+    }
+
+    pub fn tui_confirm_hint(&self) -> String {
+        translate("tui-confirm-hint")
+    }
+
+    pub fn tui_press_enter(&self) -> String {
+        translate("tui-press-enter")
+    }
+
+    pub fn tui_fallback_mode(&self) -> String {
+        translate("tui-fallback-mode")
+    }
+
+    pub fn tui_fallback_filter(&self) -> String {
+        translate("tui-fallback-filter")
+    }
+
+    pub fn tui_fallback_select(&self) -> String {
+        translate("tui-fallback-select")
+    }
+
+    pub fn tui_fallback_action(&self) -> String {
+        translate("tui-fallback-action")
+    }
+
+    pub fn tui_fallback_confirm(&self) -> String {
+        translate("tui-fallback-confirm")
+    }
+
+    pub fn cli_game_exported(&self, game: &str, path: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(NAME, game);
+        args.set(PATH, path.render());
+        translate_args("cli-game-exported", &args)
+    }
+
+    pub fn cli_game_imported(&self, game: &str, path: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(NAME, game);
+        args.set(PATH, path.render());
+        translate_args("cli-game-imported", &args)
+    }
+
+    pub fn cli_imported_registry_warning(&self) -> String {
+        translate("cli-imported-registry-warning")
+    }
+
+    pub fn game_is_running(&self, games: &[String]) -> String {
+        let prefix = translate("game-is-running");
+        let lines: Vec<_> = games.iter().map(|x| format!("  - {}", x)).collect();
+        format!("{}\n{}", prefix, lines.join("\n"))
+    }
+
+    pub fn copied_to_clipboard(&self, text: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, text);
+        translate_args("copied-to-clipboard", &args)
+    }
+
+    pub fn unable_to_copy_to_clipboard(&self, text: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, text);
+        translate_args("unable-to-copy-to-clipboard", &args)
+    }
+
+    pub fn scan_cache_used(&self, count: usize) -> String {
+        let mut args = FluentArgs::new();
+        args.set(COUNT, count);
+        translate_args("scan-cache-used", &args)
+    }
+
+    pub fn estimated_backup_size(&self, bytes: u64) -> String {
+        let mut args = FluentArgs::new();
+        args.set(TOTAL_SIZE, self.adjusted_size(bytes));
+        translate_args("estimated-backup-size", &args)
+    }
+
+    pub fn no_games_selected(&self) -> String {
+        translate("no-games-selected")
+    }
+
     pub fn unable_to_open_url(&self, url: &str) -> String {
         format!("{}\n\n{}", translate("unable-to-open-url"), url)
     }
 
     pub fn adjusted_size(&self, bytes: u64) -> String {
-        let byte = Byte::from_bytes(bytes.into());
-        let adjusted_byte = byte.get_appropriate_unit(true);
-        adjusted_byte.to_string()
+        self.adjusted_size_precision(bytes, TOTAL_SIZE_DECIMALS)
+    }
+
+    /// The size of one file in a list, which doesn't need the precision of a total.
+    pub fn item_size(&self, bytes: u64) -> String {
+        self.adjusted_size_precision(bytes, ITEM_SIZE_DECIMALS)
+    }
+
+    /// Sizes under 1 KiB are always shown as whole bytes.
+    pub fn adjusted_size_precision(&self, bytes: u64, decimals: usize) -> String {
+        let adjusted_byte = Byte::from_bytes(bytes.into()).get_appropriate_unit(true);
+        match adjusted_byte.get_unit() {
+            ByteUnit::B => format!("{} B", bytes),
+            unit => format!("{:.*} {}", decimals, adjusted_byte.get_value(), unit),
+        }
     }
 
     pub fn processed_games(&self, status: &OperationStatus) -> String {
@@ -460,6 +1918,7 @@ impl Translator {
 
     pub fn store(&self, store: &Store) -> String {
         translate(match store {
+            Store::Ea => "store-ea",
             Store::Epic => "store-epic",
             Store::Gog => "store-gog",
             Store::GogGalaxy => "store-gog-galaxy",
@@ -481,8 +1940,35 @@ impl Translator {
         })
     }
 
-    pub fn sort_reversed(&self) -> String {
-        translate("sort-reversed")
+    pub fn profile_label(&self) -> String {
+        translate("field-profile")
+    }
+
+    pub fn profile_choice(&self, choice: &ProfileChoice) -> String {
+        match choice {
+            ProfileChoice::Base => translate("profile-base"),
+            ProfileChoice::Named(name) => name.clone(),
+        }
+    }
+
+    pub fn sort_direction(&self, direction: &SortDirection) -> String {
+        translate(match direction {
+            SortDirection::Ascending => "sort-ascending",
+            SortDirection::Descending => "sort-descending",
+        })
+    }
+
+    pub fn search_scope(&self, scope: &SearchScope) -> String {
+        translate(match scope {
+            SearchScope::Name => "search-scope-name",
+            SearchScope::Store => "search-scope-store",
+            SearchScope::Path => "search-scope-path",
+            SearchScope::All => "search-scope-all",
+        })
+    }
+
+    pub fn search_failed_only(&self) -> String {
+        translate("search-failed-only")
     }
 
     pub fn redirect_source_placeholder(&self) -> String {
@@ -509,6 +1995,30 @@ impl Translator {
         translate("explanation-for-exclude-store-screenshots")
     }
 
+    pub fn steam_user(&self, user: &SteamUser) -> String {
+        match user {
+            SteamUser::All => translate("steam-user-all"),
+            SteamUser::Current => translate("steam-user-current"),
+            SteamUser::Id(id) => {
+                let mut args = FluentArgs::new();
+                args.set(ID, id.to_string());
+                translate_args("steam-user-id", &args)
+            }
+        }
+    }
+
+    pub fn explanation_for_exclude_hidden_files(&self) -> String {
+        translate("explanation-for-exclude-hidden-files")
+    }
+
+    pub fn explanation_for_included_tag(&self, tag: &Tag) -> String {
+        translate(match tag {
+            Tag::Save => "explanation-for-included-tag-save",
+            Tag::Config => "explanation-for-included-tag-config",
+            Tag::Other => "explanation-for-included-tag-other",
+        })
+    }
+
     pub fn ignored_items_label(&self) -> String {
         translate("field-backup-excluded-items")
     }
@@ -535,9 +2045,400 @@ impl Translator {
         translate_args("confirm-backup", &args)
     }
 
-    pub fn modal_confirm_restore(&self, source: &StrictPath) -> String {
+    /// `mirror_ignored` is for when the config asks for mirror mode, but the restore won't use it.
+    pub fn modal_confirm_restore(&self, source: &StrictPath, mirror_ignored: bool) -> String {
         let mut args = FluentArgs::new();
         args.set(PATH, source.render());
-        translate_args("confirm-restore", &args)
+        let text = translate_args("confirm-restore", &args);
+        if mirror_ignored {
+            format!("{}\n\n{}", text, translate("confirm-restore-mirror-unsupported"))
+        } else {
+            text
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluent_syntax::ast::Entry;
+    use pretty_assertions::assert_eq;
+    use std::collections::BTreeMap;
+
+    /// Every message and attribute in an FTL file, keyed like `message` or `message.attribute`.
+    fn parse_ftl(ftl: &str) -> BTreeMap<String, PatternUsage> {
+        let resource = fluent_syntax::parser::parse(ftl).expect("Failed to parse Fluent file content.");
+        let mut entries = BTreeMap::new();
+        for entry in &resource.body {
+            if let Entry::Message(message) = entry {
+                entries.insert(
+                    message.id.name.to_string(),
+                    message.value.as_ref().map(PatternUsage::of).unwrap_or_default(),
+                );
+                for attribute in &message.attributes {
+                    entries.insert(
+                        format!("{}.{}", message.id.name, attribute.id.name),
+                        PatternUsage::of(&attribute.value),
+                    );
+                }
+            }
+        }
+        entries
+    }
+
+    /// How an entry uses variables, including through any messages that it references.
+    fn usage(entries: &BTreeMap<String, PatternUsage>, key: &str) -> PatternUsage {
+        let mut usage = PatternUsage::default();
+        let mut pending = vec![key.to_string()];
+        let mut seen = BTreeSet::new();
+        while let Some(key) = pending.pop() {
+            if !seen.insert(key.clone()) {
+                continue;
+            }
+            if let Some(entry) = entries.get(&key) {
+                usage.variables.extend(entry.variables.iter().cloned());
+                usage.selectors.extend(entry.selectors.iter().cloned());
+                pending.extend(entry.references.iter().cloned());
+            }
+        }
+        usage.selectors.retain(|x| !usage.variables.contains(x));
+        usage
+    }
+
+    /// Each `Translator` method's name, with the keys that it looks up
+    /// and the names of the arguments that it passes to them.
+    fn translator_usage() -> Vec<(String, BTreeSet<String>, BTreeSet<String>)> {
+        let source = include_str!("lang.rs");
+        let consts: BTreeMap<_, _> = Regex::new(r#"const ([A-Z_]+): &str = "([^"]+)";"#)
+            .unwrap()
+            .captures_iter(source)
+            .map(|x| (x[1].to_string(), x[2].to_string()))
+            .collect();
+
+        let start = source.find("impl Translator {").unwrap();
+        let end = source.find("\n#[cfg(test)]\nmod tests").unwrap();
+        let direct_key = Regex::new(r#"translate(?:_args)?\(\s*"([^"]+)""#).unwrap();
+        let match_block = Regex::new(r#"(?s)translate\(match .*?\n\s*\}\)"#).unwrap();
+        let match_key = Regex::new(r#"=> "([^"]+)""#).unwrap();
+        let argument = Regex::new(r#"args\.set\(\s*([A-Z_]+)"#).unwrap();
+
+        Regex::new(r#"\n    (?:pub )?fn "#)
+            .unwrap()
+            .split(&source[start..end])
+            .skip(1)
+            .map(|method| {
+                let name = method.split('(').next().unwrap().to_string();
+                let mut keys: BTreeSet<_> = direct_key.captures_iter(method).map(|x| x[1].to_string()).collect();
+                for block in match_block.find_iter(method) {
+                    keys.extend(match_key.captures_iter(block.as_str()).map(|x| x[1].to_string()));
+                }
+                let arguments = argument.captures_iter(method).map(|x| consts[&x[1]].clone()).collect();
+                (name, keys, arguments)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn translator_and_bundled_locales_agree() {
+        let methods = translator_usage();
+        assert!(methods.len() > 100, "{}", methods.len());
+
+        for (language, ftl) in BUNDLED_LOCALES {
+            let entries = parse_ftl(ftl);
+            let mut problems = vec![];
+            let mut used = BTreeSet::new();
+
+            for (method, keys, arguments) in &methods {
+                let mut needed = PatternUsage::default();
+                for key in keys {
+                    if entries.contains_key(key) {
+                        used.insert(key.clone());
+                        needed.extend(usage(&entries, key));
+                    } else {
+                        problems.push(format!("{}: missing {}", method, key));
+                    }
+                }
+                for variable in needed.variables.difference(arguments) {
+                    problems.push(format!("{}: doesn't pass ${}", method, variable));
+                }
+                for variable in arguments {
+                    if !needed.variables.contains(variable) && !needed.selectors.contains(variable) {
+                        problems.push(format!("{}: passes unused ${}", method, variable));
+                    }
+                }
+            }
+
+            // Messages can also be used from other messages, and a message
+            // with only attributes is used through those attributes.
+            for entry in entries.values() {
+                used.extend(entry.references.iter().cloned());
+            }
+            let used_messages: BTreeSet<_> = used.iter().map(|x| x.split('.').next().unwrap().to_string()).collect();
+            for key in entries.keys() {
+                let orphan = match key.split_once('.') {
+                    Some(_) => !used.contains(key),
+                    None => !used_messages.contains(key),
+                };
+                if orphan {
+                    problems.push(format!("unused {}", key));
+                }
+            }
+
+            assert_eq!(Vec::<String>::new(), problems, "{}", language.id());
+        }
+    }
+
+    fn check(id: &str, args: &FluentArgs) -> ArgumentCheck {
+        let pattern = BUNDLE.get_message(id).unwrap().value().unwrap();
+        ArgumentCheck::new(&BUNDLE, pattern, args)
+    }
+
+    #[test]
+    fn accepts_matching_arguments_without_optional_selector() {
+        let mut args = FluentArgs::new();
+        args.set(PATH, "/backup");
+        assert_eq!(
+            ArgumentCheck {
+                optional_missing: btreeset(&["path-action"]),
+                ..Default::default()
+            },
+            check("confirm-backup", &args),
+        );
+
+        args.set(PATH_ACTION, "merge");
+        assert_eq!(ArgumentCheck::default(), check("confirm-backup", &args));
+    }
+
+    #[test]
+    fn flags_missing_and_unused_arguments() {
+        let mut args = FluentArgs::new();
+        args.set(PATH, "/backup");
+        assert_eq!(
+            ArgumentCheck {
+                missing: btreeset(&["count"]),
+                unused: btreeset(&["path"]),
+                ..Default::default()
+            },
+            check("cli-undo-restore-summary", &args),
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Translation cli-undo-restore-summary is missing arguments")]
+    fn rejects_missing_arguments_when_translating() {
+        translate_args("cli-undo-restore-summary", &FluentArgs::new());
+    }
+
+    fn btreeset(items: &[&str]) -> BTreeSet<String> {
+        items.iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn groups_digits_of_large_counts_by_locale() {
+        let group = |language: &str, number: &str| DigitGrouping::of(language).apply(number);
+
+        assert_eq!("999", group("en-US", "999"));
+        assert_eq!("1,000", group("en-US", "1000"));
+        assert_eq!("12,345", group("en-US", "12345"));
+        assert_eq!("-1,234,567.5", group("en-US", "-1234567.5"));
+        assert_eq!("12.345", group("de-DE", "12345"));
+        assert_eq!("12\u{2019}345", group("de-CH", "12345"));
+        assert_eq!("12\u{202f}345", group("fr-FR", "12345"));
+        assert_eq!("1\u{a0}234\u{a0}567", group("ru", "1234567"));
+        assert_eq!("1234", group("es-ES", "1234"));
+        assert_eq!("12.345", group("es-ES", "12345"));
+        assert_eq!("1.234,5", group("de-DE", "1234.5"));
+        assert_eq!("1234,5", group("es-ES", "1234.5"));
+        assert_eq!("1\u{2019}234.5", group("de-CH", "1234.5"));
+
+        assert_eq!(
+            "Files reverted: 12,345",
+            Translator::default().cli_undo_restore_summary(12345)
+        );
+        assert_eq!(
+            "User 123456789",
+            Translator::default().steam_user(&SteamUser::Id(123456789))
+        );
+
+        let mut args = FluentArgs::new();
+        args.set(COUNT, 12345);
+        args.set(ID, 12345);
+        let grouping = |value: Option<&FluentValue>| match value {
+            Some(FluentValue::Number(number)) => Some(number.options.use_grouping),
+            _ => None,
+        };
+        let args = with_grouping_opt_in(&args);
+        assert_eq!(Some(true), grouping(args.get(COUNT)));
+        assert_eq!(Some(false), grouping(args.get(ID)));
+    }
+
+    #[test]
+    fn can_translate_from_many_threads_at_once() {
+        let expected = Translator::default().cli_undo_restore_summary(3);
+        let threads: Vec<_> = (0..16)
+            .map(|_| {
+                std::thread::spawn(|| {
+                    (0..500)
+                        .map(|_| Translator::default().cli_undo_restore_summary(3))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        assert_eq!("Files reverted: 3", expected);
+        for thread in threads {
+            for translated in thread.join().unwrap() {
+                assert_eq!(expected, translated);
+            }
+        }
+    }
+
+    #[test]
+    fn can_render_sizes_with_chosen_precision() {
+        let translator = Translator::default();
+        assert_eq!("1.56 KiB", translator.adjusted_size_precision(1600, 2));
+        assert_eq!("1.6 KiB", translator.adjusted_size_precision(1600, 1));
+        assert_eq!("2 KiB", translator.adjusted_size_precision(1600, 0));
+        assert_eq!("3.000 MiB", translator.adjusted_size_precision(3 * 1024 * 1024, 3));
+        assert_eq!("150.00 KiB", translator.adjusted_size(150 * 1024));
+        assert_eq!("150 KiB", translator.item_size(150 * 1024));
+    }
+
+    #[test]
+    fn renders_sizes_under_one_kibibyte_as_whole_bytes() {
+        let translator = Translator::default();
+        assert_eq!("0 B", translator.adjusted_size_precision(0, 2));
+        assert_eq!("1023 B", translator.adjusted_size_precision(1023, 3));
+        assert_eq!("1023 B", translator.adjusted_size(1023));
+    }
+
+    #[test]
+    fn explains_why_files_failed() {
+        let translator = Translator::default();
+        let reason = |error: std::io::Error| translator.failure_reason(&FailureReason::from(&error));
+
+        assert_eq!(
+            "permission denied",
+            reason(std::io::Error::from(std::io::ErrorKind::PermissionDenied))
+        );
+        assert_eq!("not found", reason(std::io::Error::from(std::io::ErrorKind::NotFound)));
+        assert_eq!("timed out", reason(std::io::Error::from(std::io::ErrorKind::TimedOut)));
+        assert_eq!(
+            "disk full",
+            reason(std::io::Error::from_raw_os_error(if cfg!(target_os = "windows") {
+                112
+            } else {
+                28
+            }))
+        );
+        assert_eq!("something unusual", reason(std::io::Error::other("something unusual")));
+    }
+
+    #[test]
+    fn explains_why_backup_target_was_skipped() {
+        let translator = Translator::default();
+        let path = StrictPath::new("/backup".to_string());
+
+        assert_eq!(
+            format!(
+                "{}\n{}",
+                translator.backup_target_skipped(&path),
+                translator.cannot_prepare_backup_target(&path)
+            ),
+            translator.handle_error(&Error::BackupTargetSkipped {
+                path: path.clone(),
+                origin: Box::new(Error::CannotPrepareBackupTarget { path: path.clone() }),
+            })
+        );
+    }
+
+    #[test]
+    fn can_list_manifest_changes() {
+        let translator = Translator::default();
+        let changes = ManifestChanges {
+            initial: false,
+            added: ["game1".to_string(), "game2".to_string()].into_iter().collect(),
+            changed: ["game3".to_string()].into_iter().collect(),
+            removed: Default::default(),
+        };
+        assert_eq!(
+            "Manifest updated: +2 games, 1 changed, 0 removed\n  [ADDED] game1\n  [ADDED] game2\n  [MODIFIED] game3",
+            translator.cli_manifest_changes(Some(&changes))
+        );
+        assert_eq!(
+            "Downloaded the manifest for the first time.",
+            translator.cli_manifest_changes(Some(&ManifestChanges {
+                initial: true,
+                ..Default::default()
+            }))
+        );
+        assert_eq!(
+            "The manifest hasn't been updated yet.",
+            translator.cli_manifest_changes(None)
+        );
+    }
+
+    #[test]
+    fn can_show_backup_provenance() {
+        use crate::layout::Provenance;
+
+        let when = chrono::NaiveDate::from_ymd(2000, 1, 2)
+            .and_hms(3, 4, 5)
+            .and_local_timezone(chrono::Utc)
+            .unwrap();
+        assert_eq!(
+            r#"
+game:
+  - . (2000-01-02T03:04:05Z):
+      Unknown provenance (made before Ludusavi recorded this)
+  - diff-20000102T030405Z (2000-01-02T03:04:05Z):
+      Ludusavi version: 1.2.3
+      Manifest: https://example.com/manifest.yaml
+      Manifest ETag: abc
+      Config hash: 123
+"#
+            .trim(),
+            Translator::default().cli_backup_provenance(
+                "game",
+                &[
+                    BackupProvenance {
+                        name: ".".to_string(),
+                        when,
+                        provenance: None,
+                    },
+                    BackupProvenance {
+                        name: "diff-20000102T030405Z".to_string(),
+                        when,
+                        provenance: Some(Provenance {
+                            app_version: "1.2.3".to_string(),
+                            manifest: "https://example.com/manifest.yaml".to_string(),
+                            manifest_etag: Some("abc".to_string()),
+                            config_hash: "123".to_string(),
+                        }),
+                    },
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn can_explain_manifest_issues() {
+        let translator = Translator::default();
+        assert_eq!(
+            "  - game1: <winAppdata>/Game1 (unknown placeholder <winAppdata>)\n  - game2: <home/saves (\"<\" or \">\" outside of a placeholder)",
+            translator.manifest_issues(&[
+                ManifestIssue {
+                    game: "game1".to_string(),
+                    path: "<winAppdata>/Game1".to_string(),
+                    kind: ManifestIssueKind::UnknownPlaceholder("<winAppdata>".to_string()),
+                },
+                ManifestIssue {
+                    game: "game2".to_string(),
+                    path: "<home/saves".to_string(),
+                    kind: ManifestIssueKind::MalformedPlaceholder,
+                },
+            ])
+        );
     }
 }
@@ -3,14 +3,20 @@ use std::collections::{HashSet, VecDeque};
 use chrono::{Datelike, Timelike};
 
 use crate::{
-    config::Retention,
+    alternate_stream,
+    config::{AlternateStreamPolicy, BackupGrouping, ComparisonStrategy, Config, Retention, RootsConfig},
+    manifest::{Manifest, Store},
     path::StrictPath,
-    prelude::{BackupInfo, ScanInfo, ScannedFile, ScannedRegistry},
+    prelude::{
+        are_files_equivalent, run_with_bounded_threads, BackupInfo, Error, FailureReason, ScanInfo, ScannedFile,
+        ScannedRegistry, Warning, CASE_INSENSITIVE_OS,
+    },
+    retry::Retry,
 };
 
 const SAFE: &str = "_";
 
-fn encode_base64_for_folder(name: &str) -> String {
+pub fn encode_base64_for_folder(name: &str) -> String {
     base64::encode(&name).replace('/', SAFE)
 }
 
@@ -49,6 +55,13 @@ fn escape_folder_name(name: &str) -> String {
 pub struct FullBackup {
     pub name: String,
     pub when: chrono::DateTime<chrono::Utc>,
+    /// Backups from older versions don't have this.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+    /// How files were compared to decide what went into this backup's differential backups.
+    /// Backups from older versions don't have this.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comparison: Option<ComparisonStrategy>,
     pub children: Vec<DifferentialBackup>,
 }
 
@@ -75,6 +88,9 @@ pub struct BackupOmission {
 pub struct DifferentialBackup {
     pub name: String,
     pub when: chrono::DateTime<chrono::Utc>,
+    /// Backups from older versions don't have this.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
     pub omit: BackupOmission,
 }
 
@@ -88,6 +104,51 @@ impl DifferentialBackup {
     }
 }
 
+/// What produced a backup, to help explain why two backups of the same saves differ.
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Provenance {
+    #[serde(rename = "ludusaviVersion")]
+    pub app_version: String,
+    /// The manifest's URL, or the path of a local manifest file.
+    pub manifest: String,
+    #[serde(default, rename = "manifestEtag", skip_serializing_if = "Option::is_none")]
+    pub manifest_etag: Option<String>,
+    /// SHA-256 of the config, not counting the manifest's ETag,
+    /// since that's already recorded on its own.
+    #[serde(rename = "configHash")]
+    pub config_hash: String,
+}
+
+impl Provenance {
+    pub fn of(config: &Config) -> Self {
+        use sha2::Digest;
+
+        let (manifest, manifest_etag) = match Manifest::local_file(config) {
+            Some(path) => (path.render(), None),
+            None => (config.manifest.url.clone(), config.manifest.etag.clone()),
+        };
+
+        let mut hashed = config.clone();
+        hashed.manifest.etag = None;
+        let content = serde_yaml::to_string(&hashed).unwrap_or_default();
+
+        Self {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            manifest,
+            manifest_etag,
+            config_hash: format!("{:x}", sha2::Sha256::digest(content.as_bytes())),
+        }
+    }
+}
+
+/// What produced one of a game's backups.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize)]
+pub struct BackupProvenance {
+    pub name: String,
+    pub when: chrono::DateTime<chrono::Utc>,
+    pub provenance: Option<Provenance>,
+}
+
 fn default_backup_list() -> VecDeque<FullBackup> {
     VecDeque::from(vec![FullBackup {
         name: ".".to_string(),
@@ -103,6 +164,126 @@ pub struct IndividualMapping {
     pub drives: std::collections::HashMap<String, String>,
     #[serde(default = "default_backup_list")]
     pub backups: VecDeque<FullBackup>,
+    /// Files that were stored under a different name to avoid a collision
+    /// on a case-insensitive target. Each key is the original path within
+    /// a backup (e.g., `drive-C/Saves/File.txt`), and each value is the stored path.
+    #[serde(
+        default,
+        rename = "caseRenames",
+        skip_serializing_if = "std::collections::BTreeMap::is_empty"
+    )]
+    pub case_renames: std::collections::BTreeMap<String, String>,
+    /// Where each file came from, relative to a known location,
+    /// for restoring on a machine where that location is different.
+    /// The keys are the same as in `case_renames`.
+    #[serde(
+        default,
+        rename = "portablePaths",
+        skip_serializing_if = "std::collections::BTreeMap::is_empty"
+    )]
+    pub portable_paths: std::collections::BTreeMap<String, PortablePath>,
+    /// Names of the NTFS alternate data streams that were kept with each file.
+    /// The keys are the same as in `case_renames`.
+    #[serde(
+        default,
+        rename = "alternateStreams",
+        skip_serializing_if = "std::collections::BTreeMap::is_empty"
+    )]
+    pub alternate_streams: std::collections::BTreeMap<String, Vec<String>>,
+    /// How big each file was once it had been completely written,
+    /// so that restore can tell if a backup was interrupted partway through a file.
+    /// Each key is the stored path within the game's folder (e.g., `backup-1/drive-C/Saves/File.txt`).
+    #[serde(
+        default,
+        rename = "fileSizes",
+        skip_serializing_if = "std::collections::BTreeMap::is_empty"
+    )]
+    pub file_sizes: std::collections::BTreeMap<String, u64>,
+    /// For files from an Xbox container, the name that the game knows each one by (`<container>/<file>`),
+    /// since the files themselves are named with opaque GUIDs.
+    /// The keys are the same as in `case_renames`.
+    #[serde(
+        default,
+        rename = "xboxSaves",
+        skip_serializing_if = "std::collections::BTreeMap::is_empty"
+    )]
+    pub xbox_saves: std::collections::BTreeMap<String, String>,
+}
+
+/// A backed up file's original location, in terms of the machine that made the backup.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum PortablePath {
+    /// Within the user's home folder.
+    Home { relative: String },
+    /// Within a root of this store.
+    Root { store: Store, relative: String },
+    /// Outside of any known location, so it can only be restored to the same place.
+    Absolute,
+}
+
+/// Known locations that a backed up file can be recorded relative to.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PathAnchors {
+    pub home: Option<StrictPath>,
+    pub roots: Vec<RootsConfig>,
+}
+
+impl PathAnchors {
+    /// The current user's home folder and the configured roots.
+    pub fn current(roots: &[RootsConfig]) -> Self {
+        Self {
+            home: dirs::home_dir().map(|x| StrictPath::from_std_path_buf(&x)),
+            roots: roots.iter().flat_map(|x| x.glob()).collect(),
+        }
+    }
+
+    /// Describe `path` by the most specific location that contains it.
+    fn portable(&self, path: &StrictPath) -> PortablePath {
+        let mut nearest: Option<(usize, PortablePath)> = None;
+        let mut consider = |anchor: &StrictPath, portable: &dyn Fn(String) -> PortablePath| {
+            if let Some(relative) = anchor.suffix_of(path) {
+                let depth = anchor.render().len();
+                if nearest.as_ref().map(|(x, _)| depth > *x).unwrap_or(true) {
+                    nearest = Some((depth, portable(relative)));
+                }
+            }
+        };
+
+        if let Some(home) = &self.home {
+            consider(home, &|relative| PortablePath::Home { relative });
+        }
+        for root in &self.roots {
+            consider(&root.path, &|relative| PortablePath::Root {
+                store: root.store,
+                relative,
+            });
+        }
+
+        nearest.map(|(_, x)| x).unwrap_or(PortablePath::Absolute)
+    }
+
+    /// Where a file that was backed up from `original` belongs on this machine.
+    fn reconstruct(&self, portable: &PortablePath, original: &StrictPath) -> StrictPath {
+        match portable {
+            PortablePath::Home { relative } => match &self.home {
+                Some(home) => home.joined(relative),
+                None => original.clone(),
+            },
+            PortablePath::Root { store, relative } => {
+                let roots: Vec<_> = self.roots.iter().filter(|x| x.store == *store).collect();
+                if roots.iter().any(|x| x.path.is_prefix_of(original)) {
+                    // The same root still exists here.
+                    return original.clone();
+                }
+                match roots.first() {
+                    Some(root) => root.path.joined(relative),
+                    None => original.clone(),
+                }
+            }
+            PortablePath::Absolute => original.clone(),
+        }
+    }
 }
 
 impl IndividualMapping {
@@ -139,12 +320,62 @@ impl IndividualMapping {
     }
 
     pub fn game_file(&mut self, base: &StrictPath, original_file: &StrictPath, backup: &str) -> StrictPath {
+        StrictPath::relative(self.stored_file(original_file, backup), Some(base.interpret()))
+    }
+
+    /// Where a file is stored within the game's folder, which is how `file_sizes` is keyed.
+    fn stored_file(&mut self, original_file: &StrictPath, backup: &str) -> String {
+        let relative = self.game_file_relative(original_file);
+        let stored = self.case_renames.get(&relative).unwrap_or(&relative);
+        format!("{}/{}", backup, stored)
+    }
+
+    /// Whether a stored file isn't the size it was when it was completely written.
+    /// Files from older versions don't have a recorded size, so they can't be checked.
+    fn is_partial_file(&self, stored: &str, actual_size: u64) -> bool {
+        self.file_sizes
+            .get(stored)
+            .map(|expected| *expected != actual_size)
+            .unwrap_or(false)
+    }
+
+    /// Forget the sizes of files in backups that have been removed.
+    fn prune_file_sizes(&mut self) {
+        let names: HashSet<_> = self
+            .backups
+            .iter()
+            .flat_map(|full| std::iter::once(&full.name).chain(full.children.iter().map(|diff| &diff.name)))
+            .cloned()
+            .collect();
+        self.file_sizes.retain(|stored, _| {
+            stored
+                .split_once('/')
+                .map(|(backup, _)| names.contains(backup))
+                .unwrap_or(false)
+        });
+    }
+
+    /// How a backed up file's location was recorded portably, if it was.
+    fn portable_path(&self, original_file: &StrictPath) -> Option<&PortablePath> {
+        let (drive, plain_path) = original_file.split_drive();
+        self.reversed_drives()
+            .get(&drive)
+            .and_then(|folder| self.portable_paths.get(&format!("{}/{}", folder, plain_path)))
+    }
+
+    /// Where a backed up file belongs on this machine, if its location was recorded portably.
+    fn portable_target(&self, original_file: &StrictPath, anchors: &PathAnchors) -> StrictPath {
+        match self.portable_path(original_file) {
+            Some(portable) => anchors.reconstruct(portable, original_file),
+            None => original_file.clone(),
+        }
+    }
+
+    /// Where a file goes within a backup, before any case renaming.
+    fn game_file_relative(&mut self, original_file: &StrictPath) -> String {
         let (drive, plain_path) = original_file.split_drive();
         let drive_folder = self.drive_folder_name(&drive);
-        StrictPath::relative(
-            format!("{}/{}/{}", backup, drive_folder, plain_path),
-            Some(base.interpret()),
-        )
+        format!("{}/{}", drive_folder, plain_path)
     }
 
     fn latest_backup(&self) -> Option<(&FullBackup, Option<&DifferentialBackup>)> {
@@ -228,6 +459,83 @@ impl IndividualMapping {
     }
 }
 
+/// Check whether a folder ignores letter case in file names, like on exFAT or NTFS.
+/// The folder has to exist, since the check writes a file there.
+fn is_case_insensitive(folder: &StrictPath) -> std::io::Result<bool> {
+    let probe = folder.joined(".ludusavi-case-probe");
+    std::fs::write(probe.interpret(), "")?;
+    let insensitive = folder.joined(".LUDUSAVI-CASE-PROBE").is_file();
+    let _ = probe.remove();
+    Ok(insensitive)
+}
+
+/// A short fingerprint of the exact spelling of a name.
+/// This uses FNV-1a so that it stays the same across runs and Rust versions,
+/// and it's all lowercase so that it's safe on a case-insensitive target.
+fn case_fingerprint(name: &str) -> String {
+    let mut hash: u32 = 0x811c9dc5;
+    for byte in name.bytes() {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x01000193);
+    }
+    format!("{:08x}", hash)
+}
+
+/// Find new names for paths that would collide on a case-insensitive target.
+/// Each path component that's spelled differently elsewhere gets a suffix
+/// based on its own spelling, so the same file always gets the same name.
+/// Paths that would still collide after renaming are returned as errors.
+fn plan_case_renames(paths: &[String]) -> std::collections::BTreeMap<String, Result<String, Error>> {
+    let mut spellings = std::collections::HashMap::<String, HashSet<String>>::new();
+    for path in paths {
+        let parts: Vec<_> = path.split('/').collect();
+        for i in 0..parts.len() {
+            let prefix = parts[..=i].join("/");
+            spellings.entry(prefix.to_lowercase()).or_default().insert(prefix);
+        }
+    }
+
+    let mut renames = std::collections::BTreeMap::new();
+    for path in paths {
+        let parts: Vec<_> = path.split('/').collect();
+        let mut stored = vec![];
+        for (i, part) in parts.iter().enumerate() {
+            let prefix = parts[..=i].join("/").to_lowercase();
+            // Drive folders have to keep the names from the mapping.
+            if i > 0 && spellings[&prefix].len() > 1 {
+                stored.push(format!("{}.{}", part, case_fingerprint(part)));
+            } else {
+                stored.push(part.to_string());
+            }
+        }
+        let stored = stored.join("/");
+        if &stored != path {
+            renames.insert(path.clone(), stored);
+        }
+    }
+
+    let mut final_names = std::collections::HashMap::<String, usize>::new();
+    for path in paths {
+        *final_names
+            .entry(renames.get(path).unwrap_or(path).to_lowercase())
+            .or_default() += 1;
+    }
+
+    renames
+        .into_iter()
+        .map(|(original, stored)| {
+            if final_names[&stored.to_lowercase()] > 1 {
+                let error = Error::BackupCaseCollision {
+                    path: StrictPath::new(original.clone()),
+                };
+                (original, Err(error))
+            } else {
+                (original, Ok(stored))
+            }
+        })
+        .collect()
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 struct BackupPlan {
     kind: BackupKind,
@@ -243,6 +551,16 @@ pub struct GameLayout {
     mapping: IndividualMapping,
     #[allow(dead_code)]
     retention: Retention,
+    /// Whether the backup target ignores letter case in file names.
+    /// When unset, this is detected from the target itself.
+    case_insensitive: Option<bool>,
+    /// Recorded with each new backup.
+    provenance: Option<Provenance>,
+    /// When set, new backups record where each file came from relative to these,
+    /// and restored files are put back relative to these.
+    anchors: Option<PathAnchors>,
+    alternate_streams: AlternateStreamPolicy,
+    comparison: ComparisonStrategy,
 }
 
 impl GameLayout {
@@ -252,6 +570,11 @@ impl GameLayout {
             path,
             mapping: IndividualMapping::load(&mapping)?,
             retention,
+            case_insensitive: None,
+            provenance: None,
+            anchors: None,
+            alternate_streams: Default::default(),
+            comparison: Default::default(),
         })
     }
 
@@ -259,6 +582,26 @@ impl GameLayout {
         self.mapping.save(&Self::mapping_file(&self.path))
     }
 
+    /// Every backup of the game, oldest first, with whatever produced it.
+    pub fn backup_provenance(&self) -> Vec<BackupProvenance> {
+        let mut backups = vec![];
+        for full in &self.mapping.backups {
+            backups.push(BackupProvenance {
+                name: full.name.clone(),
+                when: full.when,
+                provenance: full.provenance.clone(),
+            });
+            for diff in &full.children {
+                backups.push(BackupProvenance {
+                    name: diff.name.clone(),
+                    when: diff.when,
+                    provenance: diff.provenance.clone(),
+                });
+            }
+        }
+        backups
+    }
+
     pub fn restorable_files(&self) -> std::collections::HashSet<ScannedFile> {
         let mut files = std::collections::HashSet::new();
 
@@ -285,12 +628,28 @@ impl GameLayout {
             }
         }
 
-        files
+        match &self.anchors {
+            Some(anchors) if !self.mapping.portable_paths.is_empty() => files
+                .into_iter()
+                .map(|mut file| {
+                    file.original_path = file.original_path.map(|x| self.mapping.portable_target(&x, anchors));
+                    file
+                })
+                .collect(),
+            _ => files,
+        }
     }
 
     fn restorable_files_in(&self, backup: &str) -> std::collections::HashSet<ScannedFile> {
         let mut files = std::collections::HashSet::new();
-        for drive_dir in walkdir::WalkDir::new(self.path.joined(backup).interpret())
+        let backup_dir = self.path.joined(backup).interpret();
+        let case_originals: std::collections::HashMap<_, _> = self
+            .mapping
+            .case_renames
+            .iter()
+            .map(|(original, stored)| (stored.as_str(), original.as_str()))
+            .collect();
+        for drive_dir in walkdir::WalkDir::new(&backup_dir)
             .max_depth(1)
             .follow_links(false)
             .into_iter()
@@ -310,21 +669,52 @@ impl GameLayout {
                 .filter(|x| x.file_type().is_file())
             {
                 let raw_file = file.path().display().to_string();
-                let original_path = Some(StrictPath::new(raw_file.replace(&raw_drive_dir, drive_mapping)));
+                let stored = file
+                    .path()
+                    .strip_prefix(&backup_dir)
+                    .ok()
+                    .map(|x| x.to_string_lossy().replace('\\', "/"));
+                let original_path = match stored.as_ref().and_then(|x| case_originals.get(x.as_str())) {
+                    Some(original) => {
+                        let (_, plain_path) = original.split_once('/').unwrap_or_default();
+                        Some(StrictPath::new(format!("{}/{}", drive_mapping, plain_path)))
+                    }
+                    None => Some(StrictPath::new(raw_file.replace(&raw_drive_dir, drive_mapping))),
+                };
+                let size = match file.metadata() {
+                    Ok(m) => m.len(),
+                    _ => 0,
+                };
+                let xbox_name = stored
+                    .as_ref()
+                    .map(|x| case_originals.get(x.as_str()).copied().unwrap_or(x))
+                    .and_then(|x| self.mapping.xbox_saves.get(x))
+                    .cloned();
+                let partial = stored
+                    .map(|x| self.mapping.is_partial_file(&format!("{}/{}", backup, x), size))
+                    .unwrap_or(false);
                 files.insert(ScannedFile {
                     path: StrictPath::new(raw_file),
-                    size: match file.metadata() {
-                        Ok(m) => m.len(),
-                        _ => 0,
-                    },
+                    size,
                     original_path,
                     ignored: false,
+                    system: false,
+                    partial,
+                    xbox_name,
                 });
             }
         }
         files
     }
 
+    /// Whether the latest backup has any files that came from inside these roots.
+    pub fn has_files_within(&self, roots: &[RootsConfig]) -> bool {
+        self.restorable_files()
+            .iter()
+            .filter_map(|x| x.original_path.as_ref())
+            .any(|file| roots.iter().any(|root| root.path.contains(file, CASE_INSENSITIVE_OS)))
+    }
+
     #[allow(dead_code)]
     pub fn registry_file(&self) -> StrictPath {
         match self.mapping.latest_backup() {
@@ -352,6 +742,22 @@ impl GameLayout {
         (full as u8, differential as u8)
     }
 
+    /// Whether a backed up file still matches the original, according to the comparison strategy.
+    fn unchanged(&self, stored: &StrictPath, original: &StrictPath) -> bool {
+        are_files_equivalent(stored, original, self.comparison)
+    }
+
+    /// Whether the latest full backup decided what changed in a different way,
+    /// in which case a new differential backup wouldn't line up with it.
+    /// Backups from older versions didn't record this, so they're assumed to match.
+    fn comparison_changed(&self) -> bool {
+        self.mapping
+            .latest_backup()
+            .and_then(|(full, _)| full.comparison)
+            .map(|x| x != self.comparison)
+            .unwrap_or(false)
+    }
+
     fn need_backup(&self, scan: &ScanInfo) -> bool {
         let mut mapping = self.mapping.clone();
 
@@ -368,7 +774,7 @@ impl GameLayout {
                 if diff.omits_file(&scanned.path) {
                     return true;
                 } else if stored_diff.is_file() {
-                    if stored_diff.same_content(&scanned.path) {
+                    if self.unchanged(&stored_diff, &scanned.path) {
                         continue;
                     } else {
                         return true;
@@ -377,7 +783,7 @@ impl GameLayout {
             }
 
             let stored_full = mapping.game_file(&self.path, &scanned.path, &full.name);
-            if !stored_full.is_file() || !stored_full.same_content(&scanned.path) {
+            if !stored_full.is_file() || !self.unchanged(&stored_full, &scanned.path) {
                 return true;
             }
         }
@@ -499,7 +905,7 @@ impl GameLayout {
         };
 
         let (fulls, diffs) = self.count_backups();
-        plan.kind = if fulls > 0 && diffs < self.retention.differential {
+        plan.kind = if fulls > 0 && diffs < self.retention.differential && !self.comparison_changed() {
             BackupKind::Differential
         } else {
             BackupKind::Full
@@ -515,6 +921,8 @@ impl GameLayout {
                 plan.mapping.backups.push_back(FullBackup {
                     name: plan.name.clone(),
                     when: *now,
+                    provenance: self.provenance.clone(),
+                    comparison: Some(self.comparison),
                     children: Default::default(),
                 });
                 while plan.mapping.backups.len() as u8 > self.retention.full {
@@ -525,6 +933,7 @@ impl GameLayout {
                 let new = DifferentialBackup {
                     name: plan.name.clone(),
                     when: *now,
+                    provenance: self.provenance.clone(),
                     omit: Default::default(),
                 };
                 if let Some(latest_full) = plan.mapping.latest_full_backup_mut() {
@@ -541,7 +950,7 @@ impl GameLayout {
             if plan.kind == BackupKind::Differential {
                 if let Some(latest_full) = plan.mapping.backups.back().cloned() {
                     let stored = plan.mapping.game_file(&self.path, &file.path, &latest_full.name);
-                    if stored.same_content(&file.path) {
+                    if self.unchanged(&stored, &file.path) {
                         continue;
                     }
                 }
@@ -573,7 +982,7 @@ impl GameLayout {
             use crate::registry::Hives;
 
             let mut hives = Hives::default();
-            let (found, _) = hives.incorporate(&scan.found_registry_keys);
+            let (found, _, _) = hives.incorporate(&scan.found_registry_keys);
 
             match plan.kind {
                 BackupKind::Full => {
@@ -610,35 +1019,146 @@ impl GameLayout {
         Some(plan)
     }
 
-    fn execute_backup(&mut self, plan: BackupPlan) -> BackupInfo {
+    /// On a first backup, the game folder doesn't exist yet, so it's created in order to probe it.
+    fn detect_case_insensitive(&self) -> std::io::Result<bool> {
+        if let Some(insensitive) = self.case_insensitive {
+            return Ok(insensitive);
+        }
+        std::fs::create_dir_all(self.path.interpret())?;
+        is_case_insensitive(&self.path)
+    }
+
+    /// Each file is copied from its `path` and stored by its `original`,
+    /// so this can also copy files that are already in another backup.
+    fn execute_backup(&mut self, plan: BackupPlan, copy_threads: usize, retry: &Retry) -> BackupInfo {
         let mut backup_info = BackupInfo::default();
         self.mapping = plan.mapping;
 
-        let mut relevant_files = vec![];
+        if plan.kind == BackupKind::Full {
+            self.mapping.portable_paths.clear();
+            self.mapping.alternate_streams.clear();
+            self.mapping.xbox_saves.clear();
+            self.mapping.prune_file_sizes();
+        }
         for file in &plan.files {
-            let target_file = self.mapping.game_file(&self.path, &file.path, &plan.name);
-            if file.path.same_content(&target_file) {
-                relevant_files.push(target_file);
-                continue;
+            if let Some(name) = &file.xbox_name {
+                let relative = self.mapping.game_file_relative(file.original());
+                self.mapping.xbox_saves.insert(relative, name.clone());
+            }
+        }
+        if let Some(anchors) = &self.anchors {
+            for file in &plan.files {
+                let relative = self.mapping.game_file_relative(file.original());
+                self.mapping
+                    .portable_paths
+                    .insert(relative, anchors.portable(file.original()));
             }
-            if target_file.create_parent_dir().is_err() {
+        }
+
+        let mut collisions = HashSet::new();
+        if self.detect_case_insensitive().unwrap_or(false) {
+            let relative: Vec<_> = plan
+                .files
+                .iter()
+                .map(|file| (file, self.mapping.game_file_relative(file.original())))
+                .collect();
+            let renames = plan_case_renames(&relative.iter().map(|(_, x)| x.clone()).collect::<Vec<_>>());
+            for (file, original) in relative {
+                match renames.get(&original) {
+                    None => {}
+                    Some(Ok(stored)) => {
+                        // Keep any earlier name so that differential backups line up.
+                        self.mapping
+                            .case_renames
+                            .entry(original)
+                            .or_insert_with(|| stored.clone());
+                    }
+                    Some(Err(_)) => {
+                        collisions.insert(file);
+                    }
+                }
+            }
+        }
+
+        let mut relevant_files = vec![];
+        let mut copies = vec![];
+        for file in &plan.files {
+            if collisions.contains(file) {
                 backup_info.failed_files.insert(file.clone());
                 continue;
             }
-            if std::fs::copy(&file.path.interpret(), &target_file.interpret()).is_err() {
-                backup_info.failed_files.insert(file.clone());
+            let target_file = self.mapping.game_file(&self.path, file.original(), &plan.name);
+            if self.unchanged(&target_file, &file.path) {
+                relevant_files.push(target_file);
                 continue;
             }
-            relevant_files.push(target_file);
+            copies.push((file, target_file));
         }
 
+        let policy = self.alternate_streams;
+        let copied_bytes = std::sync::atomic::AtomicU64::new(0);
+        let copied = run_with_bounded_threads(
+            &copies,
+            copy_threads,
+            |(file, target_file)| -> std::io::Result<(u64, alternate_stream::Outcome)> {
+                target_file.create_parent_dir()?;
+                let written = retry.copy(&file.path, target_file)?;
+                copied_bytes.fetch_add(written, std::sync::atomic::Ordering::Relaxed);
+                // Writing the streams counts as modifying the file, so this goes before the timestamps.
+                let streams = alternate_stream::back_up(policy, &file.path, target_file);
+                // Keep the original timestamps so that they can be restored later.
+                let _ = target_file.copy_timestamps_from(&file.path);
+                Ok((written, streams))
+            },
+        );
+        for ((file, target_file), copied) in copies.into_iter().zip(copied) {
+            match copied {
+                Ok((written, streams)) => {
+                    // Only recorded once the copy finished, so an interrupted file won't match.
+                    let stored = self.mapping.stored_file(file.original(), &plan.name);
+                    self.mapping.file_sizes.insert(stored, written);
+                    let relative = self.mapping.game_file_relative(file.original());
+                    self.mapping.alternate_streams.remove(&relative);
+                    match streams {
+                        alternate_stream::Outcome::None => {}
+                        alternate_stream::Outcome::Kept(names) => {
+                            self.mapping.alternate_streams.insert(relative, names);
+                        }
+                        alternate_stream::Outcome::Stripped => {
+                            backup_info.warnings.push(Warning::AlternateStreamsStripped {
+                                game: self.mapping.name.clone(),
+                            });
+                        }
+                        alternate_stream::Outcome::Unsupported => {
+                            backup_info.warnings.push(Warning::AlternateStreamsUnsupported {
+                                game: self.mapping.name.clone(),
+                            });
+                        }
+                    }
+                    relevant_files.push(target_file)
+                }
+                Err(e) => {
+                    // Anything left behind by the failed copy will be caught as partial.
+                    let stored = self.mapping.stored_file(file.original(), &plan.name);
+                    self.mapping.file_sizes.insert(stored, file.size);
+                    backup_info.failed_files.insert(file.clone());
+                    backup_info
+                        .failure_reasons
+                        .insert(file.clone(), FailureReason::from(&e));
+                }
+            }
+        }
+        backup_info.copied_bytes = copied_bytes.into_inner();
+
         #[cfg(target_os = "windows")]
         {
             use crate::registry::Hives;
             let target_registry_file = self.registry_file_in(&plan.name);
 
             if !plan.registry.is_empty() {
-                let hives = Hives::from(&plan.registry);
+                let mut hives = Hives::default();
+                let (_, _, counts) = hives.incorporate(&plan.registry);
+                backup_info.registry_values = counts;
                 hives.save(&target_registry_file);
             } else {
                 let _ = target_registry_file.remove();
@@ -657,13 +1177,90 @@ impl GameLayout {
         backup_info
     }
 
-    pub fn back_up(&mut self, scan: &ScanInfo, now: &chrono::DateTime<chrono::Utc>) -> BackupInfo {
+    pub fn back_up(
+        &mut self,
+        scan: &ScanInfo,
+        now: &chrono::DateTime<chrono::Utc>,
+        copy_threads: usize,
+        retry: &Retry,
+    ) -> BackupInfo {
         match self.plan_backup(scan, now) {
             None => BackupInfo::default(),
-            Some(plan) => self.execute_backup(plan),
+            Some(plan) => self.execute_backup(plan, copy_threads, retry),
         }
     }
 
+    /// Replace this game's backups with a single full backup of the latest state in `source`.
+    /// This keeps when that state was backed up and what produced it.
+    /// The files are copied the same way as for a new backup,
+    /// so they're renamed if the target needs it.
+    fn adopt_latest(&mut self, source: &GameLayout, copy_threads: usize, retry: &Retry) -> std::io::Result<()> {
+        let (when, provenance, comparison) = match source.mapping.latest_backup() {
+            None => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "the game has no backups to migrate",
+                ))
+            }
+            Some((full, None)) => (full.when, full.provenance.clone(), full.comparison),
+            Some((full, Some(diff))) => (diff.when, diff.provenance.clone(), full.comparison),
+        };
+        let name = self.generate_full_backup_name(&when);
+        let mut mapping = self.mapping.clone();
+        mapping.backups = VecDeque::from(vec![FullBackup {
+            name: name.clone(),
+            when,
+            provenance,
+            comparison,
+            children: vec![],
+        }]);
+
+        let files = source.restorable_files();
+        if let Some(file) = files.iter().find(|x| x.original_path.is_none()) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("unknown original location for {}", file.path.render()),
+            ));
+        }
+        let plan = BackupPlan {
+            kind: BackupKind::Full,
+            mapping,
+            name: name.clone(),
+            files: files.iter().cloned().collect(),
+            registry: Default::default(),
+        };
+        let backup_info = self.execute_backup(plan, copy_threads, retry);
+        if let Some(file) = backup_info.failed_files.iter().next() {
+            return Err(match backup_info.failure_reasons.get(file) {
+                Some(reason) => std::io::Error::new(reason.kind, reason.message.clone()),
+                None => std::io::Error::new(
+                    std::io::ErrorKind::AlreadyExists,
+                    format!(
+                        "{} can't be given a unique name on the target",
+                        file.original().render()
+                    ),
+                ),
+            });
+        }
+
+        for file in &files {
+            if let Some(portable) = source.mapping.portable_path(file.original()) {
+                let relative = self.mapping.game_file_relative(file.original());
+                self.mapping.portable_paths.insert(relative, portable.clone());
+            }
+        }
+
+        let registry = source.registry_file();
+        if registry.is_file() {
+            let target_registry = self.registry_file_in(&name);
+            target_registry.create_parent_dir()?;
+            retry.copy(&registry, &target_registry)?;
+        }
+
+        self.save();
+        Ok(())
+    }
+
     fn mapping_file(path: &StrictPath) -> StrictPath {
         path.joined("mapping.yaml")
     }
@@ -704,6 +1301,27 @@ impl GameLayout {
     }
 }
 
+/// Copy every file in `source` to the same place in `target`.
+fn copy_folder(source: &StrictPath, target: &StrictPath, retry: &Retry) -> std::io::Result<()> {
+    let source_root = source.interpret();
+    for file in walkdir::WalkDir::new(&source_root).follow_links(false) {
+        let file = file?;
+        if !file.file_type().is_file() {
+            continue;
+        }
+        let relative = file
+            .path()
+            .strip_prefix(&source_root)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+        let target_file = StrictPath::from_std_path_buf(&target.as_std_path_buf().join(relative));
+        let source_file = StrictPath::from(&file);
+        target_file.create_parent_dir()?;
+        retry.copy(&source_file, &target_file)?;
+        let _ = target_file.copy_timestamps_from(&source_file);
+    }
+    Ok(())
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 enum BackupKind {
     #[default]
@@ -711,31 +1329,128 @@ enum BackupKind {
     Differential,
 }
 
+/// Recorded in the backup target, so that the game folders can be found again later.
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+struct LayoutMetadata {
+    #[serde(default)]
+    grouping: BackupGrouping,
+}
+
+impl LayoutMetadata {
+    fn file(base: &StrictPath) -> StrictPath {
+        base.joined("layout.yaml")
+    }
+
+    fn load(base: &StrictPath) -> Option<Self> {
+        let content = std::fs::read_to_string(Self::file(base).interpret()).ok()?;
+        serde_yaml::from_str(&content).ok()
+    }
+
+    fn save(&self, base: &StrictPath) {
+        let new_content = serde_yaml::to_string(self).unwrap();
+        let _ = std::fs::write(Self::file(base).interpret(), new_content.as_bytes());
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct BackupLayout {
     pub base: StrictPath,
     games: std::collections::HashMap<String, StrictPath>,
     retention: Retention,
+    grouping: BackupGrouping,
+    /// For deciding which store a new game folder goes under.
+    roots: Vec<RootsConfig>,
+    /// Recorded with each new backup.
+    provenance: Option<Provenance>,
+    /// For portable paths.
+    anchors: Option<PathAnchors>,
+    alternate_streams: AlternateStreamPolicy,
+    comparison: ComparisonStrategy,
 }
 
 impl BackupLayout {
     pub fn new(base: StrictPath, retention: Retention) -> Self {
-        let games = Self::load(&base);
-        Self { base, games, retention }
+        let grouping = LayoutMetadata::load(&base).unwrap_or_default().grouping;
+        let games = Self::load(&base, grouping);
+        Self {
+            base,
+            games,
+            retention,
+            grouping,
+            roots: vec![],
+            provenance: None,
+            anchors: None,
+            alternate_streams: Default::default(),
+            comparison: Default::default(),
+        }
+    }
+
+    /// Record what produced each new backup, according to `config`.
+    pub fn with_provenance(mut self, config: &Config) -> Self {
+        self.provenance = Some(Provenance::of(config));
+        self
+    }
+
+    /// Record each backed up file relative to `anchors`, and restore files relative to them.
+    pub fn with_anchors(mut self, anchors: PathAnchors) -> Self {
+        self.anchors = Some(anchors);
+        self
+    }
+
+    /// Record each backed up file relative to the home folder and roots,
+    /// if `config` asks for portable paths.
+    pub fn with_portable_paths(self, config: &Config) -> Self {
+        if config.backup.portable_paths {
+            self.with_anchors(PathAnchors::current(&config.roots))
+        } else {
+            self
+        }
+    }
+
+    /// Keep or strip the alternate data streams of backed up files.
+    pub fn with_alternate_streams(mut self, policy: AlternateStreamPolicy) -> Self {
+        self.alternate_streams = policy;
+        self
+    }
+
+    /// Decide whether files have changed since the last backup according to `strategy`.
+    pub fn with_comparison(mut self, strategy: ComparisonStrategy) -> Self {
+        self.comparison = strategy;
+        self
+    }
+
+    /// Arrange new game folders according to `grouping`.
+    /// A target keeps the grouping that it already has, though,
+    /// since changing it would leave the existing game folders where they are.
+    pub fn grouped(mut self, grouping: BackupGrouping, roots: &[RootsConfig]) -> Self {
+        if LayoutMetadata::load(&self.base).is_none() && self.games.is_empty() {
+            self.grouping = grouping;
+        }
+        self.roots = roots.to_vec();
+        self
+    }
+
+    /// The grouping that new game folders will follow,
+    /// which may differ from the requested one if the target already has backups.
+    pub fn grouping(&self) -> BackupGrouping {
+        self.grouping
+    }
+
+    /// Record the grouping in the target, so that the game folders can be found later.
+    /// A flat target doesn't need this.
+    pub fn record_grouping(&self) {
+        if self.grouping != BackupGrouping::Flat && !LayoutMetadata::file(&self.base).is_file() {
+            LayoutMetadata {
+                grouping: self.grouping,
+            }
+            .save(&self.base);
+        }
     }
 
-    pub fn load(base: &StrictPath) -> std::collections::HashMap<String, StrictPath> {
+    pub fn load(base: &StrictPath, grouping: BackupGrouping) -> std::collections::HashMap<String, StrictPath> {
         let mut overall = std::collections::HashMap::new();
 
-        for game_dir in walkdir::WalkDir::new(base.interpret())
-            .max_depth(1)
-            .follow_links(false)
-            .into_iter()
-            .skip(1) // the base path itself
-            .filter_map(|e| e.ok())
-            .filter(|x| x.file_type().is_dir())
-        {
-            let game_dir = StrictPath::from(&game_dir);
+        for game_dir in Self::game_dirs(base, grouping) {
             let mapping_file = game_dir.joined("mapping.yaml");
             if mapping_file.is_file() {
                 if let Ok(mapping) = IndividualMapping::load(&mapping_file) {
@@ -747,24 +1462,91 @@ impl BackupLayout {
         overall
     }
 
+    /// Folders that may contain a game's backups.
+    /// When grouped by store, this looks inside of each store's folder.
+    fn game_dirs(base: &StrictPath, grouping: BackupGrouping) -> Vec<StrictPath> {
+        let subfolders = |parent: &StrictPath| -> Vec<StrictPath> {
+            walkdir::WalkDir::new(parent.interpret())
+                .max_depth(1)
+                .follow_links(false)
+                .into_iter()
+                .skip(1) // the parent path itself
+                .filter_map(|e| e.ok())
+                .filter(|x| x.file_type().is_dir())
+                .map(|x| StrictPath::from(&x))
+                .collect()
+        };
+
+        let mut dirs = vec![];
+        for dir in subfolders(base) {
+            let is_store_folder = grouping == BackupGrouping::ByStore
+                && !dir.joined("mapping.yaml").is_file()
+                && dir
+                    .as_std_path_buf()
+                    .file_name()
+                    .map(|name| Store::ALL.iter().any(|x| name == x.folder_name()))
+                    .unwrap_or(false);
+            if is_store_folder {
+                dirs.extend(subfolders(&dir));
+            } else {
+                dirs.push(dir);
+            }
+        }
+        dirs
+    }
+
     pub fn game_layout(&self, name: &str) -> GameLayout {
-        let path = self.game_folder(name);
+        self.game_layout_at(self.game_folder(name), name)
+    }
 
-        match GameLayout::load(path.clone(), self.retention.clone()) {
+    /// Like `game_layout`, but a game without a backup yet is placed according to the grouping.
+    pub fn game_layout_for_backup(&self, name: &str, scan: &ScanInfo) -> GameLayout {
+        let path = match self.grouping {
+            BackupGrouping::Flat => self.game_folder(name),
+            BackupGrouping::ByStore => {
+                let store = scan.primary_store(&self.roots);
+                self.game_folder_in(&self.base.joined(store.folder_name()), name)
+            }
+        };
+        self.game_layout_at(path, name)
+    }
+
+    fn game_layout_at(&self, path: StrictPath, name: &str) -> GameLayout {
+        let mut layout = match GameLayout::load(path.clone(), self.retention.clone()) {
             Ok(x) => x,
             Err(_) => GameLayout {
                 path,
                 mapping: IndividualMapping::new(name.to_string()),
                 retention: self.retention.clone(),
+                case_insensitive: None,
+                provenance: None,
+                anchors: None,
+                alternate_streams: Default::default(),
+                comparison: Default::default(),
             },
-        }
+        };
+        layout.provenance = self.provenance.clone();
+        layout.anchors = self.anchors.clone();
+        layout.alternate_streams = self.alternate_streams;
+        layout.comparison = self.comparison;
+        layout
     }
 
     fn generate_total_rename(original_name: &str) -> String {
         format!("ludusavi-renamed-{}", encode_base64_for_folder(original_name))
     }
 
+    /// The folder containing a game's backups, if any exist yet.
+    pub fn existing_game_folder(&self, game_name: &str) -> Option<StrictPath> {
+        let folder = self.game_folder(game_name);
+        folder.is_dir().then_some(folder)
+    }
+
     pub fn game_folder(&self, game_name: &str) -> StrictPath {
+        self.game_folder_in(&self.base, game_name)
+    }
+
+    fn game_folder_in(&self, parent: &StrictPath, game_name: &str) -> StrictPath {
         match self.games.get::<str>(game_name) {
             Some(game) => game.clone(),
             None => {
@@ -775,19 +1557,99 @@ impl BackupLayout {
                     safe_name = Self::generate_total_rename(game_name);
                 }
 
-                self.base.joined(&safe_name)
+                parent.joined(&safe_name)
             }
         }
     }
 
+    /// Copy a game's backups into `target`, where its folder is placed by the target's grouping.
+    /// With `flatten`, only the latest state is kept, as a single full backup.
+    /// Otherwise, the whole history is kept as is.
+    /// This returns the game's folder in `target`.
+    pub fn migrate_game(
+        &self,
+        name: &str,
+        target: &BackupLayout,
+        flatten: bool,
+        copy_threads: usize,
+        retry: &Retry,
+    ) -> std::io::Result<StrictPath> {
+        let source = self.game_layout(name);
+
+        // When grouping by store, this depends on where the files originally came from.
+        let scan = ScanInfo {
+            game_name: name.to_string(),
+            found_files: source
+                .restorable_files()
+                .into_iter()
+                .filter_map(|x| {
+                    let original = x.original_path?;
+                    Some(ScannedFile {
+                        path: original,
+                        original_path: None,
+                        ..x
+                    })
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let mut migrated = target.game_layout_for_backup(name, &scan);
+
+        if flatten {
+            migrated.adopt_latest(&source, copy_threads, retry)?;
+        } else {
+            copy_folder(&source.path, &migrated.path, retry)?;
+        }
+        Ok(migrated.path)
+    }
+
     pub fn restorable_games(&self) -> Vec<String> {
         self.games.keys().cloned().collect()
     }
+
+    /// Game folders that can be deleted without losing any backups:
+    /// those without a valid mapping, like from an interrupted backup,
+    /// and those whose mapping doesn't record any backups.
+    /// A folder with only registry data is kept, since its mapping still records the backup.
+    pub fn find_prunable_folders(&self) -> Vec<PrunableFolder> {
+        let mut prunable = vec![];
+
+        for path in Self::game_dirs(&self.base, self.grouping) {
+            let recorded = match IndividualMapping::load(&path.joined("mapping.yaml")) {
+                Ok(mapping) => !mapping.backups.is_empty(),
+                Err(_) => false,
+            };
+            if recorded {
+                continue;
+            }
+
+            let bytes = walkdir::WalkDir::new(path.interpret())
+                .follow_links(false)
+                .into_iter()
+                .filter_map(|e| e.ok())
+                .filter(|x| x.file_type().is_file())
+                .filter_map(|x| x.metadata().ok())
+                .map(|x| x.len())
+                .sum();
+            prunable.push(PrunableFolder { path, bytes });
+        }
+
+        prunable.sort_by_key(|x| x.path.interpret());
+        prunable
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PrunableFolder {
+    pub path: StrictPath,
+    /// Total size of any leftover files inside.
+    pub bytes: u64,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::path::TempPath;
     use maplit::*;
 
     fn repo() -> String {
@@ -827,6 +1689,11 @@ mod tests {
                 path: StrictPath::new(path.to_string()),
                 mapping: IndividualMapping::new(name.to_string()),
                 retention: Retention::default(),
+                case_insensitive: None,
+                provenance: None,
+                anchors: None,
+                alternate_streams: Default::default(),
+                comparison: Default::default(),
             }
         }
 
@@ -854,6 +1721,48 @@ mod tests {
             );
         }
 
+        #[test]
+        fn only_finds_existing_game_folder_when_backups_exist() {
+            assert_eq!(
+                Some(layout().game_folder("game3")),
+                layout().existing_game_folder("game3")
+            );
+            assert_eq!(None, layout().existing_game_folder("nonexistent"));
+        }
+
+        #[test]
+        fn can_find_prunable_folders() {
+            let base = TempPath::new("ludusavi-test-prune");
+            let write = |file: &str, content: &str| {
+                let file = base.joined(file);
+                file.create_parent_dir().unwrap();
+                std::fs::write(file.interpret(), content).unwrap();
+            };
+
+            std::fs::create_dir_all(base.joined("empty").interpret()).unwrap();
+            write("interrupted/drive-X/file.txt", "abc");
+            write("unrecorded/mapping.yaml", "name: unrecorded\ndrives: {}\nbackups: []\n");
+            write("registry-only/mapping.yaml", "name: registry-only\ndrives: {}\n");
+            write("registry-only/registry.yaml", "");
+            write("normal/mapping.yaml", "name: normal\ndrives:\n  drive-X: 'X:'\n");
+            write("normal/drive-X/file.txt", "abc");
+
+            let prunable: Vec<_> = BackupLayout::new(base.clone(), Retention::default())
+                .find_prunable_folders()
+                .into_iter()
+                .map(|x| (x.path.render(), x.bytes))
+                .collect();
+
+            assert_eq!(
+                vec![
+                    (base.joined("empty").render(), 0),
+                    (base.joined("interrupted").render(), 3),
+                    (base.joined("unrecorded").render(), 40),
+                ],
+                prunable,
+            );
+        }
+
         #[test]
         fn can_determine_game_folder_that_does_not_exist_without_rename() {
             assert_eq!(
@@ -976,11 +1885,17 @@ mod tests {
                 found_files: hashset! {},
                 found_registry_keys: hashset! {},
                 registry_file: None,
+                cloud_only: false,
             };
             let layout = GameLayout {
                 path: StrictPath::new(format!("{}/tests/backup/game1", repo())),
                 mapping: IndividualMapping::new("game1".to_string()),
                 retention: Retention::default(),
+                case_insensitive: None,
+                provenance: None,
+                anchors: None,
+                alternate_streams: Default::default(),
+                comparison: ComparisonStrategy::Hash,
             };
             assert_eq!(None, layout.plan_backup(&scan, &now()),);
         }
@@ -995,11 +1910,17 @@ mod tests {
                 },
                 found_registry_keys: hashset! {},
                 registry_file: None,
+                cloud_only: false,
             };
             let layout = GameLayout {
                 path: StrictPath::new(format!("{}/tests/backup/game1", repo())),
                 mapping: IndividualMapping::new("game1".to_string()),
                 retention: Retention::default(),
+                case_insensitive: None,
+                provenance: None,
+                anchors: None,
+                alternate_streams: Default::default(),
+                comparison: ComparisonStrategy::Hash,
             };
             assert_eq!(
                 Some(BackupPlan {
@@ -1011,8 +1932,15 @@ mod tests {
                         backups: VecDeque::from(vec![FullBackup {
                             name: ".".to_string(),
                             when: now(),
+                            provenance: None,
+                            comparison: Some(ComparisonStrategy::Hash),
                             children: vec![],
                         }]),
+                        case_renames: Default::default(),
+                        portable_paths: Default::default(),
+                        alternate_streams: Default::default(),
+                        file_sizes: Default::default(),
+                        xbox_saves: Default::default(),
                     },
                     name: ".".to_string(),
                     files: scan.found_files.clone(),
@@ -1032,6 +1960,7 @@ mod tests {
                 },
                 found_registry_keys: hashset! {},
                 registry_file: None,
+                cloud_only: false,
             };
             let layout = GameLayout {
                 path: StrictPath::new(format!("{}/tests/backup/game1", repo())),
@@ -1041,13 +1970,25 @@ mod tests {
                     backups: VecDeque::from_iter(vec![FullBackup {
                         name: ".".to_string(),
                         when: past(),
+                        provenance: None,
+                        comparison: None,
                         children: vec![],
                     }]),
+                    case_renames: Default::default(),
+                    portable_paths: Default::default(),
+                    alternate_streams: Default::default(),
+                    file_sizes: Default::default(),
+                    xbox_saves: Default::default(),
                 },
                 retention: Retention {
                     full: 1,
                     differential: 0,
                 },
+                case_insensitive: None,
+                provenance: None,
+                anchors: None,
+                alternate_streams: Default::default(),
+                comparison: ComparisonStrategy::Hash,
             };
             assert_eq!(
                 Some(BackupPlan {
@@ -1058,8 +1999,15 @@ mod tests {
                         backups: VecDeque::from(vec![FullBackup {
                             name: ".".to_string(),
                             when: now(),
+                            provenance: None,
+                            comparison: Some(ComparisonStrategy::Hash),
                             children: vec![],
                         }]),
+                        case_renames: Default::default(),
+                        portable_paths: Default::default(),
+                        alternate_streams: Default::default(),
+                        file_sizes: Default::default(),
+                        xbox_saves: Default::default(),
                     },
                     name: ".".to_string(),
                     files: scan.found_files.clone(),
@@ -1079,6 +2027,7 @@ mod tests {
                 },
                 found_registry_keys: hashset! {},
                 registry_file: None,
+                cloud_only: false,
             };
             let layout = GameLayout {
                 path: StrictPath::new(format!("{}/tests/backup/game1", repo())),
@@ -1088,13 +2037,25 @@ mod tests {
                     backups: VecDeque::from_iter(vec![FullBackup {
                         name: ".".to_string(),
                         when: past(),
+                        provenance: None,
+                        comparison: None,
                         children: vec![],
                     }]),
+                    case_renames: Default::default(),
+                    portable_paths: Default::default(),
+                    alternate_streams: Default::default(),
+                    file_sizes: Default::default(),
+                    xbox_saves: Default::default(),
                 },
                 retention: Retention {
                     full: 2,
                     differential: 0,
                 },
+                case_insensitive: None,
+                provenance: None,
+                anchors: None,
+                alternate_streams: Default::default(),
+                comparison: ComparisonStrategy::Hash,
             };
             assert_eq!(
                 Some(BackupPlan {
@@ -1106,14 +2067,23 @@ mod tests {
                             FullBackup {
                                 name: ".".to_string(),
                                 when: past(),
+                                provenance: None,
+                                comparison: None,
                                 children: vec![],
                             },
                             FullBackup {
                                 name: format!("full-{}", now_str()),
                                 when: now(),
+                                provenance: None,
+                                comparison: Some(ComparisonStrategy::Hash),
                                 children: vec![],
                             },
                         ]),
+                        case_renames: Default::default(),
+                        portable_paths: Default::default(),
+                        alternate_streams: Default::default(),
+                        file_sizes: Default::default(),
+                        xbox_saves: Default::default(),
                     },
                     name: format!("full-{}", now_str()),
                     files: scan.found_files.clone(),
@@ -1133,6 +2103,7 @@ mod tests {
                 },
                 found_registry_keys: hashset! {},
                 registry_file: None,
+                cloud_only: false,
             };
             let layout = GameLayout {
                 path: StrictPath::new(format!("{}/tests/backup/game1", repo())),
@@ -1143,19 +2114,33 @@ mod tests {
                         FullBackup {
                             name: ".".to_string(),
                             when: past(),
+                            provenance: None,
+                            comparison: None,
                             children: vec![],
                         },
                         FullBackup {
                             name: format!("full-{}", past2_str()),
                             when: past2(),
+                            provenance: None,
+                            comparison: None,
                             children: vec![],
                         },
                     ]),
+                    case_renames: Default::default(),
+                    portable_paths: Default::default(),
+                    alternate_streams: Default::default(),
+                    file_sizes: Default::default(),
+                    xbox_saves: Default::default(),
                 },
                 retention: Retention {
                     full: 2,
                     differential: 0,
                 },
+                case_insensitive: None,
+                provenance: None,
+                anchors: None,
+                alternate_streams: Default::default(),
+                comparison: ComparisonStrategy::Hash,
             };
             assert_eq!(
                 Some(BackupPlan {
@@ -1167,14 +2152,23 @@ mod tests {
                             FullBackup {
                                 name: format!("full-{}", past2_str()),
                                 when: past2(),
+                                provenance: None,
+                                comparison: None,
                                 children: vec![],
                             },
                             FullBackup {
                                 name: format!("full-{}", now_str()),
                                 when: now(),
+                                provenance: None,
+                                comparison: Some(ComparisonStrategy::Hash),
                                 children: vec![],
                             },
                         ]),
+                        case_renames: Default::default(),
+                        portable_paths: Default::default(),
+                        alternate_streams: Default::default(),
+                        file_sizes: Default::default(),
+                        xbox_saves: Default::default(),
                     },
                     name: format!("full-{}", now_str()),
                     files: scan.found_files.clone(),
@@ -1194,6 +2188,7 @@ mod tests {
                 },
                 found_registry_keys: hashset! {},
                 registry_file: None,
+                cloud_only: false,
             };
             let layout = GameLayout {
                 path: StrictPath::new(format!("{}/tests/backup/game1", repo())),
@@ -1203,13 +2198,25 @@ mod tests {
                     backups: VecDeque::from_iter(vec![FullBackup {
                         name: ".".to_string(),
                         when: past(),
+                        provenance: None,
+                        comparison: None,
                         children: vec![],
                     }]),
+                    case_renames: Default::default(),
+                    portable_paths: Default::default(),
+                    alternate_streams: Default::default(),
+                    file_sizes: Default::default(),
+                    xbox_saves: Default::default(),
                 },
                 retention: Retention {
                     full: 1,
                     differential: 1,
                 },
+                case_insensitive: None,
+                provenance: None,
+                anchors: None,
+                alternate_streams: Default::default(),
+                comparison: ComparisonStrategy::Hash,
             };
             assert_eq!(
                 Some(BackupPlan {
@@ -1220,12 +2227,20 @@ mod tests {
                         backups: VecDeque::from(vec![FullBackup {
                             name: ".".to_string(),
                             when: past(),
+                            provenance: None,
+                            comparison: None,
                             children: vec![DifferentialBackup {
                                 name: format!("diff-{}", now_str()),
                                 when: now(),
+                                provenance: None,
                                 omit: Default::default(),
                             },],
                         },]),
+                        case_renames: Default::default(),
+                        portable_paths: Default::default(),
+                        alternate_streams: Default::default(),
+                        file_sizes: Default::default(),
+                        xbox_saves: Default::default(),
                     },
                     name: format!("diff-{}", now_str()),
                     files: scan.found_files.clone(),
@@ -1245,6 +2260,7 @@ mod tests {
                 },
                 found_registry_keys: hashset! {},
                 registry_file: None,
+                cloud_only: false,
             };
             let layout = GameLayout {
                 path: StrictPath::new(format!("{}/tests/backup/game1", repo())),
@@ -1254,17 +2270,30 @@ mod tests {
                     backups: VecDeque::from(vec![FullBackup {
                         name: ".".to_string(),
                         when: past(),
+                        provenance: None,
+                        comparison: None,
                         children: vec![DifferentialBackup {
                             name: format!("diff-{}", past2_str()),
                             when: past2(),
+                            provenance: None,
                             omit: Default::default(),
                         }],
                     }]),
+                    case_renames: Default::default(),
+                    portable_paths: Default::default(),
+                    alternate_streams: Default::default(),
+                    file_sizes: Default::default(),
+                    xbox_saves: Default::default(),
                 },
                 retention: Retention {
                     full: 2,
                     differential: 1,
                 },
+                case_insensitive: None,
+                provenance: None,
+                anchors: None,
+                alternate_streams: Default::default(),
+                comparison: ComparisonStrategy::Hash,
             };
             assert_eq!(
                 Some(BackupPlan {
@@ -1276,18 +2305,28 @@ mod tests {
                             FullBackup {
                                 name: ".".to_string(),
                                 when: past(),
+                                provenance: None,
+                                comparison: None,
                                 children: vec![DifferentialBackup {
                                     name: format!("diff-{}", past2_str()),
                                     when: past2(),
+                                    provenance: None,
                                     omit: Default::default(),
                                 },],
                             },
                             FullBackup {
                                 name: format!("full-{}", now_str()),
                                 when: now(),
+                                provenance: None,
+                                comparison: Some(ComparisonStrategy::Hash),
                                 children: vec![],
                             },
                         ]),
+                        case_renames: Default::default(),
+                        portable_paths: Default::default(),
+                        alternate_streams: Default::default(),
+                        file_sizes: Default::default(),
+                        xbox_saves: Default::default(),
                     },
                     name: format!("full-{}", now_str()),
                     files: scan.found_files.clone(),
@@ -1307,6 +2346,7 @@ mod tests {
                 },
                 found_registry_keys: hashset! {},
                 registry_file: None,
+                cloud_only: false,
             };
             let layout = GameLayout {
                 path: StrictPath::new(format!("{}/tests/backup/game1", repo())),
@@ -1316,17 +2356,30 @@ mod tests {
                     backups: VecDeque::from(vec![FullBackup {
                         name: format!("full-{}", past_str()),
                         when: past(),
+                        provenance: None,
+                        comparison: None,
                         children: vec![DifferentialBackup {
                             name: format!("diff-{}", past2_str()),
                             when: past2(),
+                            provenance: None,
                             omit: Default::default(),
                         }],
                     }]),
+                    case_renames: Default::default(),
+                    portable_paths: Default::default(),
+                    alternate_streams: Default::default(),
+                    file_sizes: Default::default(),
+                    xbox_saves: Default::default(),
                 },
                 retention: Retention {
                     full: 1,
                     differential: 1,
                 },
+                case_insensitive: None,
+                provenance: None,
+                anchors: None,
+                alternate_streams: Default::default(),
+                comparison: ComparisonStrategy::Hash,
             };
             assert_eq!(
                 Some(BackupPlan {
@@ -1337,8 +2390,15 @@ mod tests {
                         backups: VecDeque::from(vec![FullBackup {
                             name: ".".to_string(),
                             when: now(),
+                            provenance: None,
+                            comparison: Some(ComparisonStrategy::Hash),
                             children: vec![],
                         },]),
+                        case_renames: Default::default(),
+                        portable_paths: Default::default(),
+                        alternate_streams: Default::default(),
+                        file_sizes: Default::default(),
+                        xbox_saves: Default::default(),
                     },
                     name: ".".to_string(),
                     files: scan.found_files.clone(),
@@ -1347,5 +2407,427 @@ mod tests {
                 layout.plan_backup(&scan, &now()),
             );
         }
+
+        #[test]
+        fn can_plan_case_renames() {
+            assert_eq!(
+                btreemap! {
+                    "drive-X/Save.txt".to_string() => Ok("drive-X/Save.txt.384030be".to_string()),
+                    "drive-X/save.txt".to_string() => Ok("drive-X/save.txt.9727159e".to_string()),
+                },
+                plan_case_renames(&[
+                    "drive-X/Save.txt".to_string(),
+                    "drive-X/save.txt".to_string(),
+                    "drive-X/other.txt".to_string(),
+                ]),
+            );
+        }
+
+        #[test]
+        fn can_back_up_files_differing_only_by_case_to_case_insensitive_target() {
+            let base = TempPath::new("ludusavi-test-case-insensitive");
+            std::fs::create_dir_all(base.joined("source").interpret()).unwrap();
+            std::fs::create_dir_all(base.joined("backup").interpret()).unwrap();
+            let upper = base.joined("source/Save.txt");
+            let lower = base.joined("source/save.txt");
+            std::fs::write(upper.interpret(), "a").unwrap();
+            std::fs::write(lower.interpret(), "bb").unwrap();
+
+            let scan = ScanInfo {
+                game_name: "game1".to_string(),
+                found_files: hashset! {
+                    ScannedFile::new(upper.render(), 1),
+                    ScannedFile::new(lower.render(), 2),
+                },
+                found_registry_keys: hashset! {},
+                registry_file: None,
+                cloud_only: false,
+            };
+            let mut layout = GameLayout {
+                path: base.joined("backup"),
+                mapping: IndividualMapping::new("game1".to_string()),
+                retention: Retention::default(),
+                case_insensitive: Some(true),
+                provenance: None,
+                anchors: None,
+                alternate_streams: Default::default(),
+                comparison: ComparisonStrategy::Hash,
+            };
+            let backup_info = layout.back_up(&scan, &now(), 1, &crate::retry::Retry::default());
+
+            let mut restorable: Vec<_> = layout
+                .restorable_files()
+                .into_iter()
+                .map(|x| (x.original_path.unwrap().render(), x.size))
+                .collect();
+            restorable.sort();
+            let renames = layout.mapping.case_renames.len();
+
+            assert!(backup_info.successful());
+            assert_eq!(2, renames);
+            assert_eq!(vec![(upper.render(), 1), (lower.render(), 2)], restorable);
+        }
+
+        #[test]
+        fn can_flatten_files_differing_only_by_case_into_case_insensitive_target() {
+            let base = TempPath::new("ludusavi-test-case-insensitive-adopt");
+            std::fs::create_dir_all(base.joined("source").interpret()).unwrap();
+            let upper = base.joined("source/Save.txt");
+            let lower = base.joined("source/save.txt");
+            std::fs::write(upper.interpret(), "a").unwrap();
+            std::fs::write(lower.interpret(), "bb").unwrap();
+
+            let scan = ScanInfo {
+                game_name: "game1".to_string(),
+                found_files: hashset! {
+                    ScannedFile::new(upper.render(), 1),
+                    ScannedFile::new(lower.render(), 2),
+                },
+                ..Default::default()
+            };
+            let layout_at = |path: &str, case_insensitive| GameLayout {
+                path: base.joined(path),
+                mapping: IndividualMapping::new("game1".to_string()),
+                retention: Retention::default(),
+                case_insensitive: Some(case_insensitive),
+                provenance: None,
+                anchors: None,
+                alternate_streams: Default::default(),
+                comparison: ComparisonStrategy::Hash,
+            };
+            let mut source = layout_at("backup", false);
+            source.back_up(&scan, &now(), 1, &crate::retry::Retry::default());
+            let mut adopted = layout_at("flat", true);
+            let result = adopted.adopt_latest(&source, 1, &crate::retry::Retry::default());
+
+            let mut restorable: Vec<_> = adopted
+                .restorable_files()
+                .into_iter()
+                .map(|x| (x.original_path.unwrap().render(), x.size))
+                .collect();
+            restorable.sort();
+            let renames = adopted.mapping.case_renames.len();
+
+            assert!(result.is_ok());
+            assert_eq!(2, renames);
+            assert_eq!(vec![(upper.render(), 1), (lower.render(), 2)], restorable);
+        }
+
+        #[test]
+        fn detects_case_sensitivity_of_new_game_folder() {
+            let base = TempPath::new("ludusavi-test-case-probe");
+            std::fs::create_dir_all(base.joined("source").interpret()).unwrap();
+            std::fs::create_dir_all(base.joined("backup").interpret()).unwrap();
+            let upper = base.joined("source/Save.txt");
+            let lower = base.joined("source/save.txt");
+            std::fs::write(upper.interpret(), "a").unwrap();
+            std::fs::write(lower.interpret(), "bb").unwrap();
+            let insensitive = is_case_insensitive(&base.joined("backup")).unwrap();
+
+            let scan = ScanInfo {
+                game_name: "game1".to_string(),
+                found_files: hashset! {
+                    ScannedFile::new(upper.render(), 1),
+                    ScannedFile::new(lower.render(), 2),
+                },
+                ..Default::default()
+            };
+            let mut layout = GameLayout {
+                path: base.joined("backup/game1"),
+                mapping: IndividualMapping::new("game1".to_string()),
+                retention: Retention::default(),
+                case_insensitive: None,
+                provenance: None,
+                anchors: None,
+                alternate_streams: Default::default(),
+                comparison: ComparisonStrategy::Hash,
+            };
+            let detected = layout.detect_case_insensitive();
+            let backup_info = layout.back_up(&scan, &now(), 1, &crate::retry::Retry::default());
+
+            let mut restorable: Vec<_> = layout
+                .restorable_files()
+                .into_iter()
+                .map(|x| (x.original_path.unwrap().render(), x.size))
+                .collect();
+            restorable.sort();
+            let renames = layout.mapping.case_renames.len();
+            let probe_left = base.joined("backup/game1/.ludusavi-case-probe").exists();
+
+            assert_eq!(insensitive, detected.unwrap());
+            assert!(backup_info.successful());
+            assert_eq!(if insensitive { 2 } else { 0 }, renames);
+            assert_eq!(vec![(upper.render(), 1), (lower.render(), 2)], restorable);
+            assert!(!probe_left);
+        }
+
+        #[test]
+        fn existing_target_keeps_its_grouping() {
+            let base = TempPath::new("ludusavi-test-grouping-kept");
+            std::fs::create_dir_all(base.joined("source").interpret()).unwrap();
+            let file = base.joined("source/save.txt");
+            std::fs::write(file.interpret(), "a").unwrap();
+
+            let scan = ScanInfo {
+                game_name: "game1".to_string(),
+                found_files: hashset! {
+                    ScannedFile::new(file.render(), 1),
+                },
+                ..Default::default()
+            };
+            BackupLayout::new(base.joined("flat"), Retention::default())
+                .game_layout("game1")
+                .back_up(&scan, &now(), 1, &crate::retry::Retry::default());
+
+            let existing = BackupLayout::new(base.joined("flat"), Retention::default())
+                .grouped(BackupGrouping::ByStore, &[])
+                .grouping();
+            let fresh = BackupLayout::new(base.joined("fresh"), Retention::default())
+                .grouped(BackupGrouping::ByStore, &[])
+                .grouping();
+
+            assert_eq!(BackupGrouping::Flat, existing);
+            assert_eq!(BackupGrouping::ByStore, fresh);
+        }
+
+        #[test]
+        fn records_provenance_with_each_backup() {
+            let base = TempPath::new("ludusavi-test-provenance");
+            std::fs::create_dir_all(base.joined("source").interpret()).unwrap();
+            let file = base.joined("source/save.txt");
+            std::fs::write(file.interpret(), "a").unwrap();
+
+            let scan = ScanInfo {
+                game_name: "game1".to_string(),
+                found_files: hashset! {
+                    ScannedFile::new(file.render(), 1),
+                },
+                ..Default::default()
+            };
+            let mut config = Config::default();
+            config.manifest.etag = Some("abc".to_string());
+            let provenance = Provenance::of(&config);
+
+            let layout = BackupLayout::new(base.joined("backup"), Retention::default()).with_provenance(&config);
+            let backup_info = layout
+                .game_layout("game1")
+                .back_up(&scan, &now(), 1, &crate::retry::Retry::default());
+
+            let reloaded = BackupLayout::new(base.joined("backup"), Retention::default())
+                .game_layout("game1")
+                .backup_provenance();
+
+            assert!(backup_info.successful());
+            assert_eq!(Some("abc".to_string()), provenance.manifest_etag);
+            assert_eq!(
+                vec![BackupProvenance {
+                    name: ".".to_string(),
+                    when: now(),
+                    provenance: Some(provenance),
+                }],
+                reloaded
+            );
+        }
+
+        #[test]
+        fn refuses_to_restore_partial_files() {
+            let base = TempPath::new("ludusavi-test-partial");
+            std::fs::create_dir_all(base.joined("source").interpret()).unwrap();
+            let full = base.joined("source/full.txt");
+            let truncated = base.joined("source/truncated.txt");
+            let empty = base.joined("source/empty.txt");
+            std::fs::write(full.interpret(), "abc").unwrap();
+            std::fs::write(truncated.interpret(), "abc").unwrap();
+            std::fs::write(empty.interpret(), "").unwrap();
+
+            let scan = ScanInfo {
+                game_name: "game1".to_string(),
+                found_files: hashset! {
+                    ScannedFile::new(full.render(), 3),
+                    ScannedFile::new(truncated.render(), 3),
+                    ScannedFile::new(empty.render(), 0),
+                },
+                ..Default::default()
+            };
+            let mut layout = BackupLayout::new(base.joined("backup"), Retention::default()).game_layout("game1");
+            let backup_info = layout.back_up(&scan, &now(), 1, &crate::retry::Retry::default());
+
+            // Like a backup that was interrupted partway through writing this file.
+            let stored = layout.mapping.game_file(&layout.path, &truncated, ".");
+            std::fs::write(stored.interpret(), "a").unwrap();
+            let _ = base.joined("source").remove();
+
+            let restorable = BackupLayout::new(base.joined("backup"), Retention::default())
+                .game_layout("game1")
+                .restorable_files();
+            let partial: Vec<_> = restorable
+                .iter()
+                .filter(|x| x.partial)
+                .map(|x| x.original_path.as_ref().unwrap().render())
+                .collect();
+            let restore_info = crate::prelude::restore_game(
+                &ScanInfo {
+                    game_name: "game1".to_string(),
+                    found_files: restorable,
+                    ..Default::default()
+                },
+                &[],
+                &crate::prelude::RestoreOptions::default(),
+            );
+            let restored = [full.is_file(), truncated.is_file(), empty.is_file()];
+
+            assert!(backup_info.successful());
+            assert_eq!(vec![truncated.render()], partial);
+            assert_eq!(1, restore_info.failed_files.len());
+            assert_eq!([true, false, true], restored);
+        }
+
+        #[test]
+        fn keeps_xbox_save_names_for_restore() {
+            let base = TempPath::new("ludusavi-test-xbox-names");
+            std::fs::create_dir_all(base.joined("source").interpret()).unwrap();
+            let save = base.joined("source/0A1B2C3D");
+            let other = base.joined("source/other.txt");
+            std::fs::write(save.interpret(), "abc").unwrap();
+            std::fs::write(other.interpret(), "abc").unwrap();
+
+            let scan = ScanInfo {
+                game_name: "game1".to_string(),
+                found_files: hashset! {
+                    ScannedFile {
+                        xbox_name: Some("profile/save.dat".to_string()),
+                        ..ScannedFile::new(save.render(), 3)
+                    },
+                    ScannedFile::new(other.render(), 3),
+                },
+                ..Default::default()
+            };
+            let mut layout = BackupLayout::new(base.joined("backup"), Retention::default()).game_layout("game1");
+            layout.back_up(&scan, &now(), 1, &crate::retry::Retry::default());
+
+            let restorable = BackupLayout::new(base.joined("backup"), Retention::default())
+                .game_layout("game1")
+                .restorable_files();
+
+            let named: HashMap<_, _> = restorable
+                .into_iter()
+                .map(|x| (x.original_path.unwrap().render(), x.xbox_name))
+                .collect();
+            assert_eq!(
+                hashmap! {
+                    save.render() => Some("profile/save.dat".to_string()),
+                    other.render() => None,
+                },
+                named,
+            );
+        }
+
+        #[test]
+        fn forces_full_backup_when_comparison_changes() {
+            let base = TempPath::new("ludusavi-test-comparison-change");
+            let file = base.joined("source/save.txt");
+            file.create_parent_dir().unwrap();
+            std::fs::write(file.interpret(), "abc").unwrap();
+
+            let scan = ScanInfo {
+                game_name: "game1".to_string(),
+                found_files: hashset! { ScannedFile::new(file.render(), 3) },
+                ..Default::default()
+            };
+            let retention = Retention {
+                full: 1,
+                differential: 1,
+            };
+            let layout = |strategy| {
+                BackupLayout::new(base.joined("backup"), retention.clone())
+                    .with_comparison(strategy)
+                    .game_layout("game1")
+            };
+            layout(ComparisonStrategy::Hash).back_up(&scan, &past(), 1, &crate::retry::Retry::default());
+            std::fs::write(file.interpret(), "abcd").unwrap();
+
+            let same = layout(ComparisonStrategy::Hash)
+                .plan_backup(&scan, &now())
+                .map(|x| x.kind);
+            let changed = layout(ComparisonStrategy::SizeOnly)
+                .plan_backup(&scan, &now())
+                .map(|x| x.kind);
+            let recorded = layout(ComparisonStrategy::Hash)
+                .mapping
+                .latest_backup()
+                .and_then(|(full, _)| full.comparison);
+
+            assert_eq!(Some(BackupKind::Differential), same);
+            assert_eq!(Some(BackupKind::Full), changed);
+            assert_eq!(Some(ComparisonStrategy::Hash), recorded);
+        }
+
+        #[test]
+        fn reports_unknown_provenance_for_older_backups() {
+            let layout = GameLayout::load(
+                StrictPath::new(format!("{}/tests/backup/game1", repo())),
+                Retention::default(),
+            )
+            .unwrap();
+            assert!(layout.backup_provenance().iter().all(|x| x.provenance.is_none()));
+        }
+
+        #[test]
+        fn can_restore_portable_paths_for_another_home_and_root() {
+            let base = TempPath::new("ludusavi-test-portable");
+            let files = [
+                base.joined("alice/.config/game/save.txt"),
+                base.joined("steam-a/userdata/settings.txt"),
+                base.joined("elsewhere/loose.txt"),
+            ];
+            for file in &files {
+                file.create_parent_dir().unwrap();
+                std::fs::write(file.interpret(), "a").unwrap();
+            }
+
+            let scan = ScanInfo {
+                game_name: "game1".to_string(),
+                found_files: files.iter().map(|x| ScannedFile::new(x.render(), 1)).collect(),
+                ..Default::default()
+            };
+            let before = PathAnchors {
+                home: Some(base.joined("alice")),
+                roots: vec![RootsConfig {
+                    path: base.joined("steam-a"),
+                    store: Store::Steam,
+                    require: Default::default(),
+                }],
+            };
+            let after = PathAnchors {
+                home: Some(base.joined("bob")),
+                roots: vec![RootsConfig {
+                    path: base.joined("steam-b"),
+                    store: Store::Steam,
+                    require: Default::default(),
+                }],
+            };
+
+            let backup_info = BackupLayout::new(base.joined("backup"), Retention::default())
+                .with_anchors(before)
+                .game_layout("game1")
+                .back_up(&scan, &now(), 1, &crate::retry::Retry::default());
+            let restorable: HashSet<_> = BackupLayout::new(base.joined("backup"), Retention::default())
+                .with_anchors(after)
+                .game_layout("game1")
+                .restorable_files()
+                .into_iter()
+                .map(|x| x.original_path.unwrap().render())
+                .collect();
+
+            assert!(backup_info.successful());
+            assert_eq!(
+                hashset! {
+                    base.joined("bob/.config/game/save.txt").render(),
+                    base.joined("steam-b/userdata/settings.txt").render(),
+                    base.joined("elsewhere/loose.txt").render(),
+                },
+                restorable
+            );
+        }
     }
 }
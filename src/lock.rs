@@ -0,0 +1,176 @@
+use crate::{path::StrictPath, prelude::Error};
+
+pub const LOCK_FILE_NAME: &str = ".ludusavi.lock";
+
+/// A lock older than this is considered stale even if we can't tell
+/// whether the process that created it is still running.
+const STALE_AFTER_HOURS: i64 = 24;
+
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LockInfo {
+    pub pid: u32,
+    pub acquired: chrono::DateTime<chrono::Utc>,
+}
+
+impl LockInfo {
+    fn current() -> Self {
+        Self {
+            pid: std::process::id(),
+            acquired: chrono::Utc::now(),
+        }
+    }
+
+    fn load(file: &StrictPath) -> Option<Self> {
+        let content = std::fs::read_to_string(file.interpret()).ok()?;
+        serde_yaml::from_str(&content).ok()
+    }
+
+    /// Whether the process that created the lock seems to be gone.
+    pub fn is_stale(&self, now: &chrono::DateTime<chrono::Utc>) -> bool {
+        if *now - self.acquired > chrono::Duration::hours(STALE_AFTER_HOURS) {
+            return true;
+        }
+        matches!(is_process_running(self.pid), Some(false))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn is_process_running(pid: u32) -> Option<bool> {
+    Some(std::path::Path::new(&format!("/proc/{}", pid)).exists())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn is_process_running(_pid: u32) -> Option<bool> {
+    None
+}
+
+/// Marks a backup target as in use so that another instance doesn't write
+/// to it at the same time. The lock is released when this is dropped.
+#[derive(Debug)]
+pub struct TargetLock {
+    file: StrictPath,
+}
+
+impl TargetLock {
+    /// If the target is already locked, this fails, unless the lock is stale
+    /// and `force` is set, in which case the old lock is replaced.
+    pub fn acquire(target: &StrictPath, force: bool) -> Result<Self, Error> {
+        let file = target.joined(LOCK_FILE_NAME);
+        std::fs::create_dir_all(target.interpret())
+            .map_err(|_| Error::CannotPrepareBackupTarget { path: target.clone() })?;
+
+        if Self::try_create(&file).is_ok() {
+            return Ok(Self { file });
+        }
+
+        let stale = match LockInfo::load(&file) {
+            Some(info) => info.is_stale(&chrono::Utc::now()),
+            // Unreadable or corrupt, so it can't belong to a working instance.
+            None => true,
+        };
+        if !(stale && force) {
+            return Err(Error::BackupTargetLocked { path: file, stale });
+        }
+
+        file.remove()
+            .map_err(|_| Error::CannotPrepareBackupTarget { path: target.clone() })?;
+        // Another instance may have taken over in the meantime.
+        Self::try_create(&file).map_err(|_| Error::BackupTargetLocked {
+            path: file.clone(),
+            stale: false,
+        })?;
+        Ok(Self { file })
+    }
+
+    fn try_create(file: &StrictPath) -> std::io::Result<()> {
+        use std::io::Write;
+
+        let mut handle = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(file.interpret())?;
+        let content = serde_yaml::to_string(&LockInfo::current()).unwrap_or_default();
+        handle.write_all(content.as_bytes())
+    }
+}
+
+impl Drop for TargetLock {
+    fn drop(&mut self) {
+        let _ = self.file.remove();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::TempPath;
+    use pretty_assertions::assert_eq;
+
+    fn now() -> chrono::DateTime<chrono::Utc> {
+        chrono::NaiveDate::from_ymd(2000, 1, 2)
+            .and_hms(3, 4, 5)
+            .and_local_timezone(chrono::Utc)
+            .unwrap()
+    }
+
+    fn test_dir(name: &str) -> TempPath {
+        TempPath::new(name)
+    }
+
+    #[test]
+    fn second_lock_on_same_target_fails() {
+        let target = test_dir("ludusavi-test-lock-held");
+
+        let first = TargetLock::acquire(&target, false).unwrap();
+        assert!(target.joined(LOCK_FILE_NAME).is_file());
+
+        // A live lock can't be overridden, even with force.
+        for force in [false, true] {
+            assert_eq!(
+                Err(Error::BackupTargetLocked {
+                    path: target.joined(LOCK_FILE_NAME),
+                    stale: false,
+                }),
+                TargetLock::acquire(&target, force).map(|_| ()),
+            );
+        }
+
+        drop(first);
+        assert!(!target.joined(LOCK_FILE_NAME).exists());
+        assert!(TargetLock::acquire(&target, false).is_ok());
+    }
+
+    #[test]
+    fn stale_lock_requires_force() {
+        let target = test_dir("ludusavi-test-lock-stale");
+        std::fs::create_dir_all(target.interpret()).unwrap();
+        let stale = LockInfo {
+            pid: std::process::id(),
+            acquired: chrono::Utc::now() - chrono::Duration::hours(STALE_AFTER_HOURS + 1),
+        };
+        std::fs::write(
+            target.joined(LOCK_FILE_NAME).interpret(),
+            serde_yaml::to_string(&stale).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            Err(Error::BackupTargetLocked {
+                path: target.joined(LOCK_FILE_NAME),
+                stale: true,
+            }),
+            TargetLock::acquire(&target, false).map(|_| ()),
+        );
+        assert!(TargetLock::acquire(&target, true).is_ok());
+    }
+
+    #[test]
+    fn lock_is_stale_after_a_while() {
+        let info = LockInfo {
+            pid: std::process::id(),
+            acquired: now(),
+        };
+        assert!(!info.is_stale(&(now() + chrono::Duration::hours(1))));
+        assert!(info.is_stale(&(now() + chrono::Duration::hours(STALE_AFTER_HOURS + 1))));
+    }
+}
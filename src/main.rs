@@ -1,22 +1,94 @@
+mod alternate_stream;
+mod anonymize;
+mod archive;
+mod attention;
 mod cli;
 mod config;
+mod diff;
+mod doctor;
+mod game_export;
 mod gui;
+mod ignore_file;
+mod inactivity;
+mod known_folder;
 mod lang;
 mod layout;
+mod lock;
 mod manifest;
+mod migrate;
+mod ownership;
 mod path;
 mod prelude;
+mod process;
 mod registry_compat;
+mod retry;
+mod scan_cache;
+mod schedule;
 mod serialization;
+mod settings;
 mod shortcuts;
+mod signature;
+mod steam;
+mod throttle;
+mod tui;
+mod undo;
+mod verify_sample;
+mod wgs;
+mod wine;
 
 #[cfg(target_os = "windows")]
 mod registry;
 
+/// Write log messages to a file in the state folder, keeping the last few files.
+/// The level is `warn` unless `RUST_LOG` says otherwise.
+fn prepare_logging() -> Result<flexi_logger::LoggerHandle, flexi_logger::FlexiLoggerError> {
+    flexi_logger::Logger::try_with_env_or_str("ludusavi=warn")?
+        .log_to_file(
+            flexi_logger::FileSpec::default()
+                .directory(prelude::state_dir())
+                .basename("ludusavi")
+                .suppress_timestamp(),
+        )
+        .rotate(
+            flexi_logger::Criterion::Size(1024 * 1024 * 10),
+            flexi_logger::Naming::Numbers,
+            flexi_logger::Cleanup::KeepLogFiles(4),
+        )
+        .format_for_files(flexi_logger::detailed_format)
+        .use_utc()
+        .start()
+}
+
 fn main() {
+    let args = cli::parse_cli();
+    if args.portable {
+        prelude::set_portable();
+    }
+    if let Some(manifest) = &args.manifest {
+        prelude::set_manifest_override(manifest);
+    }
+    if args.refresh_manifest {
+        prelude::set_manifest_refresh();
+    }
+    prelude::set_app_dir_overrides(
+        args.config_dir.as_ref().map(|x| x.interpret().into()),
+        args.data_dir.as_ref().map(|x| x.interpret().into()),
+    );
+    if let Err(e) = prelude::check_portable_dir() {
+        eprintln!("{}", crate::lang::Translator::default().handle_error(&e));
+        std::process::exit(1);
+    }
     prelude::migrate_legacy_config();
 
-    let args = cli::parse_cli();
+    // The handle has to be kept until the end, or else the logger would stop.
+    let _logger = match prepare_logging() {
+        Ok(x) => Some(x),
+        Err(e) => {
+            eprintln!("Unable to set up the log file: {}", e);
+            None
+        }
+    };
+
     match args.sub {
         None => {
             #[cfg(target_os = "windows")]
@@ -35,7 +107,7 @@ fn main() {
             gui::run_gui();
         }
         Some(sub) => {
-            if let Err(e) = cli::run_cli(sub) {
+            if let Err(e) = cli::run_cli(sub, args.profile) {
                 let translator = crate::lang::Translator::default();
                 eprintln!("\n{}", translator.handle_error(&e));
                 std::process::exit(1);
@@ -1,7 +1,10 @@
 use crate::{
-    config::{Config, CustomGame},
-    prelude::{app_dir, Error, StrictPath},
+    config::{Config, CustomGame, Interval},
+    prelude::{data_dir, manifest_override, manifest_refresh_forced, Error, StrictPath},
+    registry_compat::{is_reg_file, load_reg_file, RegistryFilter},
 };
+use once_cell::sync::Lazy;
+use regex::Regex;
 
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum Os {
@@ -23,6 +26,9 @@ impl Default for Os {
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum Store {
+    /// The EA app, which replaced Origin.
+    #[serde(rename = "ea")]
+    Ea,
     #[serde(rename = "epic")]
     Epic,
     #[serde(rename = "gog")]
@@ -49,6 +55,7 @@ pub enum Store {
 
 impl Store {
     pub const ALL: &'static [Self] = &[
+        Store::Ea,
         Store::Epic,
         Store::Gog,
         Store::GogGalaxy,
@@ -61,6 +68,25 @@ impl Store {
         Store::OtherWine,
         Store::Other,
     ];
+
+    /// A stable, ASCII-only name for grouping backups by store.
+    /// Unlike the display name, this doesn't change with the language.
+    pub fn folder_name(&self) -> &'static str {
+        match self {
+            Store::Ea => "Ea",
+            Store::Epic => "Epic",
+            Store::Gog => "Gog",
+            Store::GogGalaxy => "GogGalaxy",
+            Store::Microsoft => "Microsoft",
+            Store::Origin => "Origin",
+            Store::Prime => "Prime",
+            Store::Steam => "Steam",
+            Store::Uplay => "Uplay",
+            Store::OtherHome => "OtherHome",
+            Store::OtherWine => "OtherWine",
+            Store::Other => "Other",
+        }
+    }
 }
 
 impl Default for Store {
@@ -77,7 +103,7 @@ impl std::fmt::Display for Store {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum Tag {
     #[serde(rename = "save")]
     Save,
@@ -103,6 +129,16 @@ pub struct Game {
     pub install_dir: Option<std::collections::HashMap<String, GameInstallDirEntry>>,
     pub registry: Option<std::collections::HashMap<String, GameRegistryEntry>>,
     pub steam: Option<SteamMetadata>,
+    /// How to start the game, keyed by the path to each of its executables.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub launch: Option<std::collections::HashMap<String, Vec<GameLaunchEntry>>>,
+    /// The game only keeps its saves in a proprietary cloud, with nothing on disk.
+    #[serde(
+        default,
+        rename = "cloudOnly",
+        skip_serializing_if = "crate::serialization::is_false"
+    )]
+    pub cloud_only: bool,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -114,10 +150,15 @@ pub struct GameFileEntry {
 #[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct GameInstallDirEntry {}
 
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GameLaunchEntry {}
+
 #[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct GameRegistryEntry {
     pub tags: Option<Vec<Tag>>,
     pub when: Option<Vec<GameRegistryConstraint>>,
+    #[serde(flatten)]
+    pub filter: RegistryFilter,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -141,10 +182,22 @@ impl From<CustomGame> for Game {
         let file_tuples = item.files.iter().map(|x| (x.to_string(), GameFileEntry::default()));
         let files: std::collections::HashMap<_, _> = file_tuples.collect();
 
-        let registry_tuples = item
-            .registry
-            .iter()
-            .map(|x| (x.to_string(), GameRegistryEntry::default()));
+        let registry_tuples = item.registry.iter().flat_map(|x| {
+            let entry = GameRegistryEntry {
+                filter: item.registry_filters.get(x).cloned().unwrap_or_default(),
+                ..Default::default()
+            };
+            if !is_reg_file(x) {
+                return vec![(x.to_string(), entry)];
+            }
+            match load_reg_file(&StrictPath::new(x.trim().to_string())) {
+                Ok(keys) => keys.iter().map(|x| (x.raw(), entry.clone())).collect(),
+                Err(e) => {
+                    log::warn!("Skipping registry file for custom game {}: {:?}", item.name, e);
+                    vec![]
+                }
+            }
+        });
         let registry: std::collections::HashMap<_, _> = registry_tuples.collect();
 
         Self {
@@ -152,20 +205,152 @@ impl From<CustomGame> for Game {
             install_dir: None,
             registry: Some(registry),
             steam: None,
+            launch: None,
+            cloud_only: item.cloud_only,
         }
     }
 }
 
-impl Manifest {
+impl Game {
+    /// The file and registry paths, which are what matter when comparing definitions.
+    fn paths(&self) -> (std::collections::BTreeSet<&String>, std::collections::BTreeSet<&String>) {
+        (
+            self.files.iter().flat_map(|x| x.keys()).collect(),
+            self.registry.iter().flat_map(|x| x.keys()).collect(),
+        )
+    }
+
+    /// The file names of the game's executables, from its launch entries.
+    pub fn executable_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = vec![];
+        for path in self.launch.iter().flat_map(|x| x.keys()) {
+            let name = path.rsplit(['/', '\\']).next().unwrap_or_default().trim();
+            if !name.is_empty() && !names.iter().any(|x| x.eq_ignore_ascii_case(name)) {
+                names.push(name.to_string());
+            }
+        }
+        names
+    }
+}
+
+/// Changes from one version of the manifest to another,
+/// so that the whole file doesn't need to be downloaded again.
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ManifestDelta {
+    /// ETag of the manifest version that this applies to.
+    pub base: String,
+    /// ETag of the manifest version that results from applying this.
+    pub etag: Option<String>,
+    #[serde(default)]
+    pub upsert: std::collections::HashMap<String, Game>,
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+/// Which games were added, changed, or removed by a manifest update.
+/// The latest one is saved, so that it can be shown again later.
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ManifestChanges {
+    /// There was no earlier manifest to compare against,
+    /// so every game would otherwise count as added.
+    #[serde(default, skip_serializing_if = "crate::serialization::is_false")]
+    pub initial: bool,
+    #[serde(default)]
+    pub added: std::collections::BTreeSet<String>,
+    #[serde(default)]
+    pub changed: std::collections::BTreeSet<String>,
+    #[serde(default)]
+    pub removed: std::collections::BTreeSet<String>,
+}
+
+impl ManifestChanges {
+    pub fn between(old: &Manifest, new: &Manifest) -> Self {
+        let mut changes = Self::default();
+        for (name, game) in &new.0 {
+            match old.0.get(name) {
+                None => {
+                    changes.added.insert(name.clone());
+                }
+                Some(old_game) if old_game != game => {
+                    changes.changed.insert(name.clone());
+                }
+                _ => {}
+            }
+        }
+        changes.removed = old
+            .0
+            .keys()
+            .filter(|name| !new.0.contains_key(*name))
+            .cloned()
+            .collect();
+        changes
+    }
+
+    fn initial() -> Self {
+        Self {
+            initial: true,
+            ..Default::default()
+        }
+    }
+
     fn file() -> std::path::PathBuf {
-        let mut path = app_dir();
+        let mut path = data_dir();
+        path.push("manifest-changes.json");
+        path
+    }
+
+    /// The changes from the most recent update, if there has been one.
+    pub fn load() -> Option<Self> {
+        let content = std::fs::read_to_string(Self::file()).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn save(&self) {
+        if let Ok(content) = serde_json::to_string(self) {
+            let _ = std::fs::write(Self::file(), content.as_bytes());
+        }
+    }
+}
+
+impl Manifest {
+    pub fn file() -> std::path::PathBuf {
+        let mut path = data_dir();
         path.push("manifest.yaml");
         path
     }
 
+    /// A manifest file that the user provided, which is never downloaded or updated.
+    pub fn local_file(config: &Config) -> Option<StrictPath> {
+        manifest_override().or_else(|| config.manifest.path.clone())
+    }
+
+    /// With `update` or `--refresh-manifest`, this checks for a new version regardless of the refresh interval.
     pub fn load(config: &mut Config, update: bool) -> Result<Self, Error> {
-        if update || !StrictPath::from_std_path_buf(&Self::file()).exists() {
-            Self::update(config)?;
+        Self::load_with_conflicts(config, update).map(|(manifest, _)| manifest)
+    }
+
+    /// Like `load`, but also report the games that the secondary manifests define differently.
+    pub fn load_with_conflicts(config: &mut Config, update: bool) -> Result<(Self, Vec<ManifestConflict>), Error> {
+        let primary = Self::load_primary(config, update)?;
+        primary.with_secondary(config)
+    }
+
+    fn load_primary(config: &mut Config, update: bool) -> Result<Self, Error> {
+        if let Some(path) = Self::local_file(config) {
+            return Self::load_local(&path);
+        }
+        let updated = if update || manifest_refresh_forced() || !StrictPath::from_std_path_buf(&Self::file()).exists() {
+            Self::update_and_load(config)?.1
+        } else if config.manifest.refresh_interval.is_some()
+            && Self::fresh_cache_age(config, std::time::SystemTime::now()).is_none()
+        {
+            // The cache is still usable if we're offline.
+            Self::update_and_load(config).ok().and_then(|(_, manifest)| manifest)
+        } else {
+            None
+        };
+        if let Some(manifest) = updated {
+            return Ok(manifest);
         }
         let content = std::fs::read_to_string(Self::file()).unwrap();
         Self::load_from_string(&content)
@@ -175,37 +360,229 @@ impl Manifest {
         serde_yaml::from_str(content).map_err(|e| Error::ManifestInvalid { why: format!("{}", e) })
     }
 
-    pub fn update(config: &mut Config) -> Result<(), Error> {
+    pub fn load_local(path: &StrictPath) -> Result<Self, Error> {
+        let content =
+            std::fs::read_to_string(path.interpret()).map_err(|_| Error::ManifestNotFound { path: path.clone() })?;
+        Self::load_from_string(&content)
+    }
+
+    /// How long ago the cached manifest was downloaded, as long as that's within
+    /// the configured refresh interval, so that it can be used without checking for updates.
+    pub fn fresh_cache_age(config: &Config, now: std::time::SystemTime) -> Option<std::time::Duration> {
+        if Self::local_file(config).is_some() {
+            return None;
+        }
+        let interval = config.manifest.refresh_interval?;
+        let modified = std::fs::metadata(Self::file()).ok()?.modified().ok()?;
+        fresh_age(interval, modified, now)
+    }
+
+    /// Where the main manifest comes from, for reporting conflicts.
+    pub fn primary_source(config: &Config) -> String {
+        match Self::local_file(config) {
+            Some(path) => path.render(),
+            None => config.manifest.url.clone(),
+        }
+    }
+
+    /// Merge the manifests from `manifest.secondary` after this one.
+    pub fn with_secondary(self, config: &Config) -> Result<(Self, Vec<ManifestConflict>), Error> {
+        if config.manifest.secondary.is_empty() {
+            return Ok((self, vec![]));
+        }
+        let mut sources = vec![(Self::primary_source(config), self)];
+        for path in &config.manifest.secondary {
+            sources.push((path.render(), Self::load_local(path)?));
+        }
+        Ok(Self::merge(sources))
+    }
+
+    /// Combine manifests in order of precedence: when more than one defines a game,
+    /// the first definition is used. Definitions with the same paths are simply deduplicated,
+    /// but ones with different paths are reported as conflicts.
+    pub fn merge(sources: Vec<(String, Self)>) -> (Self, Vec<ManifestConflict>) {
+        let mut merged = Self::default();
+        let mut origins = std::collections::HashMap::<String, Vec<String>>::new();
+        let mut conflicted = std::collections::HashSet::new();
+
+        for (source, manifest) in sources {
+            for (name, game) in manifest.0 {
+                match merged.0.get(&name) {
+                    Some(existing) => {
+                        if existing.paths() != game.paths() {
+                            conflicted.insert(name.clone());
+                        }
+                        origins.entry(name).or_default().push(source.clone());
+                    }
+                    None => {
+                        origins.insert(name.clone(), vec![source.clone()]);
+                        merged.0.insert(name, game);
+                    }
+                }
+            }
+        }
+
+        let mut conflicts: Vec<_> = conflicted
+            .into_iter()
+            .map(|game| ManifestConflict {
+                sources: origins.remove(&game).unwrap_or_default(),
+                game,
+            })
+            .collect();
+        conflicts.sort();
+        (merged, conflicts)
+    }
+
+    pub fn load_cached() -> Option<Self> {
+        let content = std::fs::read_to_string(Self::file()).ok()?;
+        Self::load_from_string(&content).ok()
+    }
+
+    /// Returns what changed, or `None` if the manifest was already up to date.
+    pub fn update(config: &mut Config) -> Result<Option<ManifestChanges>, Error> {
+        Self::update_and_load(config).map(|(changes, _)| changes)
+    }
+
+    /// Like `update`, but also returns the current manifest if it had to be parsed along the way,
+    /// so that loading it afterward doesn't need to parse the file again.
+    fn update_and_load(config: &mut Config) -> Result<(Option<ManifestChanges>, Option<Self>), Error> {
+        if Self::local_file(config).is_some() {
+            return Ok((None, None));
+        }
+
+        let cached = if StrictPath::from_std_path_buf(&Self::file()).exists() {
+            Self::load_cached()
+        } else {
+            None
+        };
+
+        match cached.as_ref().map(|cached| Self::update_from_delta(config, cached)) {
+            Some(Ok(Some((changes, updated)))) => return Ok((Some(changes), Some(updated))),
+            Some(Ok(None)) => return Ok((None, cached)),
+            Some(Err(_)) | None => {
+                // Fall back to a full download.
+            }
+        }
+
         let mut req = reqwest::blocking::Client::new().get(&config.manifest.url);
         if let Some(etag) = &config.manifest.etag {
-            if StrictPath::from_std_path_buf(&Self::file()).exists() {
+            if cached.is_some() {
                 req = req.header(reqwest::header::IF_NONE_MATCH, etag);
             }
         }
         let mut res = req.send().map_err(|_e| Error::ManifestCannotBeUpdated)?;
         match res.status() {
             reqwest::StatusCode::OK => {
-                std::fs::create_dir_all(app_dir()).map_err(|_| Error::ManifestCannotBeUpdated)?;
+                std::fs::create_dir_all(data_dir()).map_err(|_| Error::ManifestCannotBeUpdated)?;
                 let mut file = std::fs::File::create(Self::file()).map_err(|_| Error::ManifestCannotBeUpdated)?;
                 res.copy_to(&mut file).map_err(|_| Error::ManifestCannotBeUpdated)?;
 
                 if let Some(etag) = res.headers().get(reqwest::header::ETAG) {
-                    match &config.manifest.etag {
-                        Some(old_etag) if etag == old_etag => (),
-                        _ => {
-                            config.manifest.etag = Some(String::from_utf8_lossy(etag.as_bytes()).to_string());
-                            config.save();
-                        }
-                    }
+                    Self::save_etag(config, String::from_utf8_lossy(etag.as_bytes()).to_string());
+                }
+
+                let updated = Self::load_cached();
+                let changes = match &cached {
+                    Some(old) => ManifestChanges::between(old, updated.as_ref().unwrap_or(&Self::default())),
+                    None => ManifestChanges::initial(),
+                };
+                changes.save();
+                Ok((Some(changes), updated))
+            }
+            reqwest::StatusCode::NOT_MODIFIED => {
+                Self::mark_checked();
+                Ok((None, cached))
+            }
+            _ => Err(Error::ManifestCannotBeUpdated),
+        }
+    }
+
+    /// The cache's age is based on when it was last modified,
+    /// so bump that when we find out that it's still current.
+    fn mark_checked() {
+        if let Ok(file) = std::fs::File::options().write(true).open(Self::file()) {
+            let _ = file.set_modified(std::time::SystemTime::now());
+        }
+    }
+
+    /// Only fetch the changes since the cached version, if the host supports it.
+    /// This fails if there's no delta URL, if the host doesn't know the cached version
+    /// (e.g., because it's too old), or if anything else goes wrong.
+    /// Returns what changed along with the updated manifest, or `None` if the cache was already up to date.
+    fn update_from_delta(config: &mut Config, cached: &Self) -> Result<Option<(ManifestChanges, Self)>, Error> {
+        let (delta_url, etag) = match (&config.manifest.delta_url, &config.manifest.etag) {
+            (Some(delta_url), Some(etag)) => (delta_url.clone(), etag.clone()),
+            _ => return Err(Error::ManifestCannotBeUpdated),
+        };
+
+        let res = reqwest::blocking::Client::new()
+            .get(&delta_url)
+            .header(reqwest::header::IF_NONE_MATCH, &etag)
+            .send()
+            .map_err(|_e| Error::ManifestCannotBeUpdated)?;
+        match res.status() {
+            reqwest::StatusCode::OK => {
+                let content = res.text().map_err(|_| Error::ManifestCannotBeUpdated)?;
+                let delta: ManifestDelta =
+                    serde_yaml::from_str(&content).map_err(|_| Error::ManifestCannotBeUpdated)?;
+                if delta.base != etag {
+                    return Err(Error::ManifestCannotBeUpdated);
                 }
 
-                Ok(())
+                let mut updated = cached.clone();
+                let new_etag = delta.etag.clone();
+                let changes = updated.apply_delta(delta);
+                let serialized = serde_yaml::to_string(&updated).map_err(|_| Error::ManifestCannotBeUpdated)?;
+                std::fs::write(Self::file(), serialized.as_bytes()).map_err(|_| Error::ManifestCannotBeUpdated)?;
+
+                // Without a new ETag, we couldn't request the next delta,
+                // so forget the old one to force a full download next time.
+                match new_etag {
+                    Some(new_etag) => Self::save_etag(config, new_etag),
+                    None => {
+                        config.manifest.etag = None;
+                        config.save();
+                    }
+                }
+                changes.save();
+                Ok(Some((changes, updated)))
+            }
+            reqwest::StatusCode::NOT_MODIFIED => {
+                Self::mark_checked();
+                Ok(None)
             }
-            reqwest::StatusCode::NOT_MODIFIED => Ok(()),
             _ => Err(Error::ManifestCannotBeUpdated),
         }
     }
 
+    fn save_etag(config: &mut Config, etag: String) {
+        if config.manifest.etag.as_ref() != Some(&etag) {
+            config.manifest.etag = Some(etag);
+            config.save();
+        }
+    }
+
+    pub fn apply_delta(&mut self, delta: ManifestDelta) -> ManifestChanges {
+        let mut changes = ManifestChanges::default();
+        for (name, game) in delta.upsert {
+            match self.0.insert(name.clone(), game.clone()) {
+                None => {
+                    changes.added.insert(name);
+                }
+                Some(old) if old != game => {
+                    changes.changed.insert(name);
+                }
+                _ => {}
+            }
+        }
+        for name in delta.remove {
+            if self.0.remove(&name).is_some() {
+                changes.removed.insert(name);
+            }
+        }
+        changes
+    }
+
     pub fn map_steam_ids_to_names(&self) -> std::collections::HashMap<u32, String> {
         self.0
             .iter()
@@ -222,15 +599,108 @@ impl Manifest {
         if let Some(existing) = self.0.get(&name) {
             game.steam = existing.steam.clone();
             game.install_dir = existing.install_dir.clone();
+            game.cloud_only = game.cloud_only || existing.cloud_only;
         }
         self.0.insert(name, game);
     }
+
+    /// Look for file paths that wouldn't work in a scan,
+    /// like ones with placeholders that Ludusavi doesn't know about.
+    pub fn find_issues(&self) -> Vec<ManifestIssue> {
+        let mut issues = vec![];
+        for (name, game) in &self.0 {
+            for path in game.files.iter().flat_map(|x| x.keys()) {
+                if let Some(kind) = check_path_template(path) {
+                    issues.push(ManifestIssue {
+                        game: name.clone(),
+                        path: path.clone(),
+                        kind,
+                    });
+                }
+            }
+        }
+        issues.sort();
+        issues
+    }
+}
+
+/// Placeholders that can appear in the manifest's file paths.
+pub const PATH_PLACEHOLDERS: &[&str] = &[
+    "<root>",
+    "<game>",
+    "<base>",
+    "<home>",
+    "<storeUserId>",
+    "<osUserName>",
+    "<winAppData>",
+    "<winLocalAppData>",
+    "<winDocuments>",
+    "<winPublic>",
+    "<winProgramData>",
+    "<winDir>",
+    "<xdgData>",
+    "<xdgConfig>",
+    "<regHkcu>",
+    "<regHklm>",
+];
+
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum ManifestIssueKind {
+    UnknownPlaceholder(String),
+    /// A `<` or `>` that isn't part of a placeholder.
+    MalformedPlaceholder,
+    MalformedGlob(String),
+}
+
+/// A game that more than one manifest defines with different paths.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct ManifestConflict {
+    pub game: String,
+    /// Every manifest that defines the game, in order of precedence.
+    /// The first one's definition is the one that's used.
+    pub sources: Vec<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct ManifestIssue {
+    pub game: String,
+    pub path: String,
+    pub kind: ManifestIssueKind,
+}
+
+static RE_PLACEHOLDER: Lazy<Regex> = Lazy::new(|| Regex::new("<[^<>]*>").unwrap());
+
+fn check_path_template(path: &str) -> Option<ManifestIssueKind> {
+    if let Some(unknown) = RE_PLACEHOLDER
+        .find_iter(path)
+        .map(|x| x.as_str())
+        .find(|x| !PATH_PLACEHOLDERS.contains(x))
+    {
+        return Some(ManifestIssueKind::UnknownPlaceholder(unknown.to_string()));
+    }
+    if RE_PLACEHOLDER.replace_all(path, "").contains(['<', '>']) {
+        return Some(ManifestIssueKind::MalformedPlaceholder);
+    }
+    if let Err(e) = glob::Pattern::new(path) {
+        return Some(ManifestIssueKind::MalformedGlob(e.msg.to_string()));
+    }
+    None
+}
+
+/// A modification time from the future, like after the clock was changed, counts as brand new.
+fn fresh_age(
+    interval: Interval,
+    modified: std::time::SystemTime,
+    now: std::time::SystemTime,
+) -> Option<std::time::Duration> {
+    let age = now.duration_since(modified).unwrap_or_default();
+    (age < interval.0).then_some(age)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use maplit::hashmap;
+    use maplit::{btreeset, hashmap};
     use pretty_assertions::assert_eq;
 
     fn s(text: &str) -> String {
@@ -252,6 +722,8 @@ mod tests {
                 install_dir: None,
                 registry: None,
                 steam: None,
+                launch: None,
+                cloud_only: false,
             },
             manifest.0["game"],
         );
@@ -279,6 +751,9 @@ mod tests {
                     - config
               steam:
                 id: 123
+              launch:
+                <base>/game.exe: [{}]
+              cloudOnly: true
             "#,
         )
         .unwrap();
@@ -306,10 +781,15 @@ mod tests {
                                 store: Some(Store::Epic),
                             }
                         ]),
-                        tags: Some(vec![Tag::Config])
+                        tags: Some(vec![Tag::Config]),
+                        ..Default::default()
                     },
                 }),
                 steam: Some(SteamMetadata { id: Some(123) }),
+                launch: Some(hashmap! {
+                    s("<base>/game.exe") => vec![GameLaunchEntry {}],
+                }),
+                cloud_only: true,
             },
             manifest.0["game"],
         );
@@ -471,6 +951,82 @@ mod tests {
             .is_empty());
     }
 
+    #[test]
+    fn can_parse_game_with_registry_filter() {
+        let manifest = Manifest::load_from_string(
+            r#"
+            game:
+              registry:
+                foo:
+                  includeValues: [Save*]
+                  excludeSubkeys: [Cache]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            RegistryFilter {
+                include_values: vec![s("Save*")],
+                exclude_values: vec![],
+                exclude_subkeys: vec![s("Cache")],
+            },
+            manifest.0["game"].registry.as_ref().unwrap()["foo"].filter,
+        );
+    }
+
+    fn validation_fixture(name: &str) -> Result<Vec<ManifestIssue>, Error> {
+        Manifest::load_local(&StrictPath::new(format!(
+            "{}/tests/manifest-validation/{}.yaml",
+            env!("CARGO_MANIFEST_DIR"),
+            name
+        )))
+        .map(|x| x.find_issues())
+    }
+
+    #[test]
+    fn finds_no_issues_in_valid_manifest() {
+        assert_eq!(Vec::<ManifestIssue>::new(), validation_fixture("valid").unwrap());
+    }
+
+    #[test]
+    fn finds_unknown_and_malformed_placeholders() {
+        assert_eq!(
+            vec![
+                ManifestIssue {
+                    game: s("game1"),
+                    path: s("<winAppdata>/Game1"),
+                    kind: ManifestIssueKind::UnknownPlaceholder(s("<winAppdata>")),
+                },
+                ManifestIssue {
+                    game: s("game2"),
+                    path: s("<home/saves"),
+                    kind: ManifestIssueKind::MalformedPlaceholder,
+                },
+            ],
+            validation_fixture("invalid-placeholder").unwrap(),
+        );
+    }
+
+    #[test]
+    fn finds_malformed_globs() {
+        let issues = validation_fixture("invalid-glob").unwrap();
+        assert_eq!(
+            vec![s("<base>/saves/***.sav"), s("<base>/saves/[abc")],
+            issues.iter().map(|x| x.path.clone()).collect::<Vec<_>>(),
+        );
+        assert!(issues
+            .iter()
+            .all(|x| matches!(x.kind, ManifestIssueKind::MalformedGlob(_))));
+    }
+
+    #[test]
+    fn reports_where_a_manifest_cannot_be_parsed() {
+        match validation_fixture("invalid-yaml") {
+            Err(Error::ManifestInvalid { why }) => assert!(why.contains("line"), "{}", why),
+            x => panic!("Unexpected result: {:?}", x),
+        }
+    }
+
     #[test]
     fn can_parse_game_with_minimal_steam() {
         let manifest = Manifest::load_from_string(
@@ -483,4 +1039,186 @@ mod tests {
 
         assert_eq!(&SteamMetadata { id: None }, manifest.0["game"].steam.as_ref().unwrap());
     }
+
+    #[test]
+    fn can_apply_delta_to_manifest() {
+        let mut manifest = Manifest::load_from_string(
+            r#"
+            removed:
+              steam:
+                id: 1
+            changed:
+              steam:
+                id: 2
+            unchanged:
+              steam:
+                id: 3
+            "#,
+        )
+        .unwrap();
+        let original = manifest.clone();
+        let delta: ManifestDelta = serde_yaml::from_str(
+            r#"
+            base: v1
+            etag: v2
+            upsert:
+              changed:
+                steam:
+                  id: 20
+              unchanged:
+                steam:
+                  id: 3
+              added:
+                steam:
+                  id: 4
+            remove:
+              - removed
+              - unknown
+            "#,
+        )
+        .unwrap();
+        let expected = Manifest::load_from_string(
+            r#"
+            changed:
+              steam:
+                id: 20
+            unchanged:
+              steam:
+                id: 3
+            added:
+              steam:
+                id: 4
+            "#,
+        )
+        .unwrap();
+
+        let changes = manifest.apply_delta(delta);
+
+        assert_eq!(expected, manifest);
+        let expected_changes = ManifestChanges {
+            initial: false,
+            added: btreeset! {s("added")},
+            changed: btreeset! {s("changed")},
+            removed: btreeset! {s("removed")},
+        };
+        assert_eq!(expected_changes, changes);
+        assert_eq!(expected_changes, ManifestChanges::between(&original, &manifest));
+    }
+
+    #[test]
+    fn can_list_games_that_changed_between_manifests() {
+        let old = Manifest::load_from_string(
+            r#"
+            kept: {}
+            dropped: {}
+            updated:
+              steam:
+                id: 1
+            "#,
+        )
+        .unwrap();
+        let new = Manifest::load_from_string(
+            r#"
+            kept: {}
+            updated:
+              steam:
+                id: 2
+            new1: {}
+            new2: {}
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            ManifestChanges {
+                initial: false,
+                added: btreeset! {s("new1"), s("new2")},
+                changed: btreeset! {s("updated")},
+                removed: btreeset! {s("dropped")},
+            },
+            ManifestChanges::between(&old, &new),
+        );
+        assert_eq!(ManifestChanges::default(), ManifestChanges::between(&new, &new));
+    }
+
+    #[test]
+    fn uses_cache_only_within_refresh_interval() {
+        let interval = Interval(std::time::Duration::from_secs(24 * 60 * 60));
+        let downloaded = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+        let hours = |x: u64| std::time::Duration::from_secs(x * 60 * 60);
+
+        assert_eq!(Some(hours(5)), fresh_age(interval, downloaded, downloaded + hours(5)));
+        assert_eq!(None, fresh_age(interval, downloaded, downloaded + hours(24)));
+        assert_eq!(None, fresh_age(interval, downloaded, downloaded + hours(48)));
+        assert_eq!(
+            Some(std::time::Duration::ZERO),
+            fresh_age(interval, downloaded, downloaded - hours(1))
+        );
+    }
+
+    #[test]
+    fn can_load_local_manifest_without_downloading() {
+        let mut config = Config::default();
+        config.manifest.url = s("http://localhost:1/unreachable.yaml");
+        // Relative to the current directory, which is the repository during tests.
+        config.manifest.path = Some(StrictPath::new(s("tests/manifest.yaml")));
+
+        assert_eq!(Ok(None), Manifest::update(&mut config));
+        let manifest = Manifest::load(&mut config, true).unwrap();
+        assert_eq!(vec![&s("local-game")], manifest.0.keys().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn reports_missing_local_manifest_by_path() {
+        let path = StrictPath::new(s("tests/missing-manifest.yaml"));
+        assert_eq!(
+            Err(Error::ManifestNotFound { path: path.clone() }),
+            Manifest::load_local(&path)
+        );
+    }
+
+    #[test]
+    fn reports_conflicting_games_but_not_identical_duplicates() {
+        let main = Manifest::load_from_string(
+            r#"
+            game1:
+              files:
+                <base>/main.sav: {}
+            game2:
+              files:
+                <base>/shared.sav: {}
+            "#,
+        )
+        .unwrap();
+        let extra = Manifest::load_from_string(
+            r#"
+            game1:
+              files:
+                <base>/extra.sav: {}
+            game2:
+              files:
+                <base>/shared.sav:
+                  tags: [save]
+            game3:
+              files:
+                <base>/new.sav: {}
+            "#,
+        )
+        .unwrap();
+
+        let (merged, conflicts) =
+            Manifest::merge(vec![(s("main.yaml"), main.clone()), (s("extra.yaml"), extra.clone())]);
+
+        assert_eq!(
+            vec![ManifestConflict {
+                game: s("game1"),
+                sources: vec![s("main.yaml"), s("extra.yaml")],
+            }],
+            conflicts
+        );
+        assert_eq!(main.0["game1"], merged.0["game1"]);
+        assert_eq!(main.0["game2"], merged.0["game2"]);
+        assert_eq!(extra.0["game3"], merged.0["game3"]);
+        assert_eq!(3, merged.0.len());
+    }
 }
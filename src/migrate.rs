@@ -0,0 +1,295 @@
+use crate::{
+    archive::{has_archive_name, is_archive, pack, staging_dir, unpack},
+    config::Config,
+    layout::BackupLayout,
+    path::StrictPath,
+    prelude::{prepare_backup_target, Error, OperationStatus},
+    retry::Retry,
+    signature::{sign_backup, SigningKey},
+};
+
+/// Copy the backups in `source` (a folder or archive) to `target`,
+/// with the game folders arranged according to `config`.
+/// The target is written as an archive if its name looks like one,
+/// and that archive is compressed or not based on its extension.
+/// With `flatten`, each game only keeps its latest state, as a single full backup,
+/// which is signed again if there's a signing key.
+/// `on_game` is called with each game's name and the result of migrating it.
+/// The target is cleared first, so it can't overlap with the source.
+pub fn migrate_backup(
+    source: &StrictPath,
+    target: &StrictPath,
+    config: &Config,
+    flatten: bool,
+    mut on_game: impl FnMut(&str, &std::io::Result<()>),
+) -> Result<OperationStatus, Error> {
+    if overlaps(source, target) {
+        return Err(Error::BackupMigrationOverlap { path: target.clone() });
+    }
+
+    let staged_source = if is_archive(source) {
+        let dir = staging_dir("migrate-from");
+        unpack(source, &dir)?;
+        Some(dir)
+    } else {
+        None
+    };
+    let staged_target = has_archive_name(target).then(|| staging_dir("migrate-to"));
+
+    let status = migrate_folder(
+        staged_source.as_deref().unwrap_or(source),
+        staged_target.as_deref().unwrap_or(target),
+        config,
+        flatten,
+        &mut on_game,
+    )?;
+    if let Some(dir) = &staged_target {
+        pack(dir, target, config.backup.compression_level)?;
+    }
+    Ok(status)
+}
+
+fn migrate_folder(
+    source: &StrictPath,
+    target: &StrictPath,
+    config: &Config,
+    flatten: bool,
+    on_game: &mut impl FnMut(&str, &std::io::Result<()>),
+) -> Result<OperationStatus, Error> {
+    prepare_backup_target(target, false)?;
+    let signing_key = if flatten {
+        SigningKey::load(&config.signature)?
+    } else {
+        None
+    };
+    let retry = Retry::new(&config.runtime);
+
+    let from = BackupLayout::new(source.clone(), config.backup.retention.clone());
+    let to = BackupLayout::new(target.clone(), config.backup.retention.clone())
+        .grouped(config.backup.grouping, &config.roots);
+
+    let mut games = from.restorable_games();
+    games.sort();
+
+    let mut status = OperationStatus::default();
+    for name in &games {
+        let bytes: u64 = from.game_layout(name).restorable_files().iter().map(|x| x.size).sum();
+        status.total_games += 1;
+        status.total_bytes += bytes;
+
+        let migrated = from
+            .migrate_game(name, &to, flatten, config.runtime.copy_threads, &retry)
+            .and_then(|folder| match &signing_key {
+                // The rebuilt backup no longer matches the old signature.
+                Some(key) => sign_backup(&folder, key),
+                None => Ok(()),
+            });
+        if migrated.is_ok() {
+            status.processed_games += 1;
+            status.processed_bytes += bytes;
+        }
+        on_game(name, &migrated);
+    }
+    to.record_grouping();
+
+    Ok(status)
+}
+
+/// Whether the paths are the same or one is inside the other, once symlinks are resolved.
+/// Parts of a path that don't exist yet are kept as they are.
+fn overlaps(first: &StrictPath, second: &StrictPath) -> bool {
+    let first = resolve(first);
+    let second = resolve(second);
+    first.starts_with(&second) || second.starts_with(&first)
+}
+
+fn resolve(path: &StrictPath) -> std::path::PathBuf {
+    let full = std::path::PathBuf::from(path.interpret());
+    let mut existing = full.as_path();
+    let mut missing = vec![];
+    while !existing.exists() {
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                existing = parent;
+            }
+            _ => break,
+        }
+    }
+    let mut resolved = std::fs::canonicalize(existing).unwrap_or_else(|_| existing.to_path_buf());
+    for name in missing.into_iter().rev() {
+        resolved.push(name);
+    }
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::TempPath;
+    use crate::{
+        config::{BackupGrouping, Retention},
+        prelude::{ScanInfo, ScannedFile},
+    };
+    use maplit::*;
+    use pretty_assertions::assert_eq;
+
+    fn repo() -> String {
+        env!("CARGO_MANIFEST_DIR").to_string()
+    }
+
+    /// Each game's name and restorable files, with their original paths and sizes.
+    type Restorable = Vec<(String, Vec<(Option<StrictPath>, u64)>)>;
+
+    fn restorable(base: &StrictPath) -> Restorable {
+        let layout = BackupLayout::new(base.clone(), Retention::default());
+        let mut games: Vec<_> = layout
+            .restorable_games()
+            .into_iter()
+            .map(|name| {
+                let mut files: Vec<_> = layout
+                    .game_layout(&name)
+                    .restorable_files()
+                    .into_iter()
+                    .map(|x| (x.original_path, x.size))
+                    .collect();
+                files.sort();
+                (name, files)
+            })
+            .collect();
+        games.sort();
+        games
+    }
+
+    #[test]
+    fn can_round_trip_backup_through_compressed_archive() {
+        let source = StrictPath::new(format!("{}/tests/backup", repo()));
+        let archive = TempPath::new("ludusavi-test-migrate.tar.gz");
+        let unpacked = TempPath::new("ludusavi-test-migrate");
+        let config = Config::default();
+
+        let packed_status = migrate_backup(&source, &archive, &config, false, |_, _| {}).unwrap();
+        assert!(archive.is_file());
+        let unpacked_status = migrate_backup(&archive, &unpacked, &config, false, |_, _| {}).unwrap();
+
+        let expected = restorable(&source);
+        let actual = restorable(&unpacked);
+
+        assert!(packed_status.processed_all());
+        assert!(unpacked_status.processed_all());
+        assert_eq!(expected.len(), packed_status.total_games);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn refuses_to_migrate_between_overlapping_folders() {
+        let base = TempPath::new("ludusavi-test-migrate-overlap");
+        let source = base.joined("backup");
+        let file = source.joined("game1/mapping.yaml");
+        file.create_parent_dir().unwrap();
+        std::fs::write(file.interpret(), "name: game1").unwrap();
+        let config = Config::default();
+
+        let results: Vec<_> = [source.clone(), source.joined("inner"), base.clone()]
+            .iter()
+            .map(|target| migrate_backup(&source, target, &config, false, |_, _| {}).err())
+            .collect();
+        let kept = file.is_file();
+
+        assert_eq!(
+            vec![
+                Some(Error::BackupMigrationOverlap { path: source.clone() }),
+                Some(Error::BackupMigrationOverlap {
+                    path: source.joined("inner")
+                }),
+                Some(Error::BackupMigrationOverlap { path: base.clone() }),
+            ],
+            results
+        );
+        assert!(kept);
+    }
+
+    #[test]
+    fn can_flatten_differential_backups_while_changing_grouping() {
+        let base = TempPath::new("ludusavi-test-migrate-flatten");
+        let source = base.joined("backup");
+        let target = base.joined("flat");
+        let file1 = base.joined("saves/file1.txt");
+        let file2 = base.joined("saves/file2.txt");
+        file1.create_parent_dir().unwrap();
+        std::fs::write(file1.interpret(), "a").unwrap();
+        std::fs::write(file2.interpret(), "b").unwrap();
+
+        let retention = Retention {
+            full: 1,
+            differential: 1,
+        };
+        let when = |hour| {
+            chrono::NaiveDate::from_ymd(2000, 1, 2)
+                .and_hms(hour, 4, 5)
+                .and_local_timezone(chrono::Utc)
+                .unwrap()
+        };
+        let back_up = |size2, hour| {
+            let scan = ScanInfo {
+                game_name: "game1".to_string(),
+                found_files: hashset! {
+                    ScannedFile::new(file1.render(), 1),
+                    ScannedFile::new(file2.render(), size2),
+                },
+                ..Default::default()
+            };
+            BackupLayout::new(source.clone(), retention.clone())
+                .game_layout("game1")
+                .back_up(&scan, &when(hour), 1, &Retry::default())
+        };
+        assert!(back_up(1, 1).successful());
+        std::fs::write(file2.interpret(), "bb").unwrap();
+        assert!(back_up(2, 2).successful());
+
+        let mut config = Config::default();
+        config.backup.grouping = BackupGrouping::ByStore;
+        let mut migrated = vec![];
+        let status = migrate_backup(&source, &target, &config, true, |name, result| {
+            migrated.push((name.to_string(), result.is_ok()))
+        })
+        .unwrap();
+
+        let expected = restorable(&source);
+        let actual = restorable(&target);
+        let history = BackupLayout::new(target.clone(), Retention::default())
+            .game_layout("game1")
+            .backup_provenance();
+        let grouped = target.joined("Other/game1/mapping.yaml").is_file();
+
+        assert!(status.processed_all());
+        assert_eq!(vec![("game1".to_string(), true)], migrated);
+        assert_eq!(expected, actual);
+        assert_eq!(vec![when(2)], history.iter().map(|x| x.when).collect::<Vec<_>>());
+        assert!(grouped);
+    }
+
+    #[test]
+    fn signs_flattened_backups_again() {
+        let source = StrictPath::new(format!("{}/tests/backup", repo()));
+        let base = TempPath::new("ludusavi-test-migrate-sign");
+        let target = base.joined("backup");
+        let key_file = base.joined("signing.key");
+        key_file.create_parent_dir().unwrap();
+        std::fs::write(key_file.interpret(), "AQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHyA=").unwrap();
+
+        let mut config = Config::default();
+        config.signature = crate::config::SignatureConfig {
+            key: Some(key_file.clone()),
+            verify: crate::config::SignatureVerification::Require,
+            ..Default::default()
+        };
+        let status = migrate_backup(&source, &target, &config, true, |_, _| {}).unwrap();
+
+        let layout = BackupLayout::new(target.clone(), Retention::default());
+        let checked = crate::signature::check_restore(&layout, &layout.restorable_games(), &config.signature);
+
+        assert!(status.processed_all());
+        assert_eq!(Ok(vec![]), checked);
+    }
+}
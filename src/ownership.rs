@@ -0,0 +1,119 @@
+use crate::path::StrictPath;
+
+/// How restoring a file would leave its owner or permissions different from what's there now.
+/// This is only checked on Unix, where several users can share the same save locations.
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize)]
+pub struct OwnershipMismatch {
+    /// For a new file, the owner of the folder it goes in and the user who would own the file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<(u32, u32)>,
+    /// For an existing file, its current mode and the mode from the backup.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<(u32, u32)>,
+    /// Whether this process can't change ownership, so an owner mismatch would need root to fix.
+    #[serde(skip_serializing_if = "crate::serialization::is_false")]
+    pub unprivileged: bool,
+}
+
+/// The effective user of this process, who owns any files that it creates.
+#[cfg(unix)]
+pub fn current_user() -> u32 {
+    // Safety: This has no preconditions and can't fail.
+    unsafe { libc::geteuid() }
+}
+
+/// Compare `target` as it is now against what restoring `source` there would produce,
+/// if `user` does the restore.
+/// Copying keeps the owner of a file that already exists but replaces its mode,
+/// while a new file belongs to whoever creates it.
+#[cfg(unix)]
+pub fn check(source: &StrictPath, target: &StrictPath, user: u32) -> Option<OwnershipMismatch> {
+    use std::os::unix::fs::MetadataExt;
+
+    let mut mismatch = OwnershipMismatch::default();
+
+    if target.is_file() {
+        let current = target.metadata().ok()?.mode() & 0o7777;
+        let restored = source.metadata().ok()?.mode() & 0o7777;
+        if current != restored {
+            mismatch.mode = Some((current, restored));
+        }
+    } else {
+        let folder = target
+            .as_std_path_buf()
+            .ancestors()
+            .skip(1)
+            .find(|x| x.is_dir())
+            .map(|x| x.to_path_buf())?;
+        let owner = std::fs::metadata(folder).ok()?.uid();
+        if owner != user {
+            mismatch.owner = Some((owner, user));
+            mismatch.unprivileged = user != 0;
+        }
+    }
+
+    (mismatch != OwnershipMismatch::default()).then_some(mismatch)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::path::TempPath;
+    use pretty_assertions::assert_eq;
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+    fn set_mode(path: &StrictPath, mode: u32) {
+        std::fs::set_permissions(path.interpret(), std::fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    #[test]
+    fn can_check_ownership_for_restore() {
+        let folder = TempPath::new("ludusavi-test-ownership");
+        std::fs::create_dir_all(folder.interpret()).unwrap();
+        let owner = folder.metadata().unwrap().uid();
+        let other = if owner == 0 { 1000 } else { owner + 1 };
+
+        let source = folder.joined("source.dat");
+        let existing = folder.joined("existing.dat");
+        let new = folder.joined("new/new.dat");
+        std::fs::write(source.interpret(), "a").unwrap();
+        std::fs::write(existing.interpret(), "a").unwrap();
+        set_mode(&source, 0o600);
+        set_mode(&existing, 0o644);
+
+        let existing_mismatch = check(&source, &existing, owner);
+        let new_as_owner = check(&source, &new, owner);
+        let new_as_other = check(&source, &new, other);
+        let new_as_root = check(&source, &new, 0);
+        set_mode(&existing, 0o600);
+        let existing_same = check(&source, &existing, other);
+
+        assert_eq!(
+            Some(OwnershipMismatch {
+                mode: Some((0o644, 0o600)),
+                ..Default::default()
+            }),
+            existing_mismatch,
+        );
+        assert_eq!(None, existing_same);
+        assert_eq!(None, new_as_owner);
+        assert_eq!(
+            Some(OwnershipMismatch {
+                owner: Some((owner, other)),
+                unprivileged: true,
+                ..Default::default()
+            }),
+            new_as_other,
+        );
+        if owner != 0 {
+            assert_eq!(
+                Some(OwnershipMismatch {
+                    owner: Some((owner, 0)),
+                    unprivileged: false,
+                    ..Default::default()
+                }),
+                new_as_root,
+            );
+        }
+    }
+}
@@ -12,6 +12,8 @@ const ATYPICAL_SEPARATOR: &str = "\\";
 const UNC_PREFIX: &str = "\\\\";
 #[allow(dead_code)]
 const UNC_LOCAL_PREFIX: &str = "\\\\?\\";
+/// Extended-length form of a network path like `\\server\share`.
+const UNC_NETWORK_PREFIX: &str = "\\\\?\\UNC\\";
 
 fn parse_home(path: &str) -> String {
     if path == "~" || path.starts_with("~/") || path.starts_with("~\\") {
@@ -85,7 +87,8 @@ fn parse_dots(path: &str, basis: &str) -> String {
 }
 
 /// Convert a raw, possibly user-provided path into a suitable form for internal use.
-/// On Windows, this produces UNC paths.
+/// On Windows, this produces extended-length UNC paths, so that file operations
+/// aren't limited to 260 characters. Network paths use the `\\?\UNC\` form.
 fn interpret<P: Into<String>>(path: P, basis: &Option<String>) -> String {
     let normalized = normalize(&path.into());
     if normalized.is_empty() {
@@ -113,23 +116,27 @@ fn interpret<P: Into<String>>(path: P, basis: &Option<String>) -> String {
                     Some(b) => std::path::Path::new(&normalize(b)).to_path_buf(),
                 }),
             );
-            format!(
-                "{}{}",
-                if cfg!(target_os = "windows") && !dedotted.starts_with(UNC_LOCAL_PREFIX) {
-                    UNC_LOCAL_PREFIX
-                } else {
-                    ""
-                },
-                dedotted.replace(ATYPICAL_SEPARATOR, TYPICAL_SEPARATOR)
-            )
+            let dedotted = dedotted.replace(ATYPICAL_SEPARATOR, TYPICAL_SEPARATOR);
+            if !cfg!(target_os = "windows") || dedotted.starts_with(UNC_LOCAL_PREFIX) {
+                dedotted
+            } else if let Some(network) = dedotted.strip_prefix(UNC_PREFIX) {
+                format!("{}{}", UNC_NETWORK_PREFIX, network)
+            } else {
+                format!("{}{}", UNC_LOCAL_PREFIX, dedotted)
+            }
         }
     }
 }
 
 /// Convert a path into a nice form for display and storage.
-/// On Windows, this produces non-UNC paths.
+/// On Windows, this produces non-UNC paths, with network paths as `//server/share`.
 fn render<P: Into<String>>(path: P) -> String {
-    path.into().replace(UNC_LOCAL_PREFIX, "").replace('\\', "/")
+    let path = path.into();
+    let path = match path.strip_prefix(UNC_NETWORK_PREFIX) {
+        Some(network) => format!("{}{}", UNC_PREFIX, network),
+        None => path.replace(UNC_LOCAL_PREFIX, ""),
+    };
+    path.replace('\\', "/")
 }
 
 pub fn render_pathbuf(value: &std::path::Path) -> String {
@@ -177,6 +184,10 @@ impl StrictPath {
         self.raw = raw;
     }
 
+    pub fn set_basis(&mut self, basis: Option<String>) {
+        self.basis = basis;
+    }
+
     pub fn from_std_path_buf(path_buf: &std::path::Path) -> Self {
         Self::new(render_pathbuf(path_buf))
     }
@@ -254,7 +265,13 @@ impl StrictPath {
     pub fn split_drive(&self) -> (String, String) {
         let interpreted = self.interpret();
 
-        if let Some(stripped) = interpreted.strip_prefix(UNC_LOCAL_PREFIX) {
+        if let Some(stripped) = interpreted.strip_prefix(UNC_NETWORK_PREFIX) {
+            // Remote UNC path - the server takes the place of the drive:
+            let split: Vec<_> = stripped.splitn(2, '\\').collect();
+            if split.len() == 2 {
+                return (format!("{}{}", UNC_PREFIX, split[0]), split[1].replace('\\', "/"));
+            }
+        } else if let Some(stripped) = interpreted.strip_prefix(UNC_LOCAL_PREFIX) {
             // Local UNC path - simplify to a classic drive for user-friendliness:
             let split: Vec<_> = stripped.splitn(2, '\\').collect();
             if split.len() == 2 {
@@ -313,6 +330,22 @@ impl StrictPath {
         Ok(())
     }
 
+    /// Copy the modification and access times from another file onto this one.
+    /// Creation time is left alone since it can't be set portably, and any
+    /// sub-second precision is kept only as far as the file system supports it.
+    pub fn copy_timestamps_from(&self, source: &StrictPath) -> Result<(), ()> {
+        let metadata = source.metadata().map_err(|_| ())?;
+        let mut times = std::fs::FileTimes::new().set_modified(metadata.modified().map_err(|_| ())?);
+        if let Ok(accessed) = metadata.accessed() {
+            times = times.set_accessed(accessed);
+        }
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .open(self.interpret())
+            .map_err(|_| ())?;
+        file.set_times(times).map_err(|_| ())
+    }
+
     pub fn is_prefix_of(&self, other: &StrictPath) -> bool {
         let us_rendered = splittable(self);
         let them_rendered = splittable(other);
@@ -326,6 +359,25 @@ impl StrictPath {
         us_components.zip(them_components).all(|(us, them)| us == them)
     }
 
+    /// Whether `other` is this same folder or somewhere inside it.
+    /// With `case_insensitive`, letter case is ignored, like on Windows and Mac file systems.
+    pub fn contains(&self, other: &StrictPath, case_insensitive: bool) -> bool {
+        if self.raw().trim().is_empty() || other.raw().trim().is_empty() {
+            return false;
+        }
+        let fold = |x: String| if case_insensitive { x.to_lowercase() } else { x };
+        let us_rendered = fold(splittable(self));
+        let them_rendered = fold(splittable(other));
+
+        let us_components = us_rendered.split('/');
+        let them_components = them_rendered.split('/');
+
+        if us_components.clone().count() > them_components.clone().count() {
+            return false;
+        }
+        us_components.zip(them_components).all(|(us, them)| us == them)
+    }
+
     pub fn nearest_prefix(&self, others: Vec<StrictPath>) -> Option<StrictPath> {
         let us_rendered = splittable(self);
         let us_components = us_rendered.split('/');
@@ -349,6 +401,21 @@ impl StrictPath {
         nearest
     }
 
+    /// The rest of `other` after this path, if this is a prefix of it.
+    pub fn suffix_of(&self, other: &StrictPath) -> Option<String> {
+        if !self.is_prefix_of(other) {
+            return None;
+        }
+        let us_count = splittable(self).split('/').count();
+        Some(
+            splittable(other)
+                .split('/')
+                .skip(us_count)
+                .collect::<Vec<_>>()
+                .join("/"),
+        )
+    }
+
     pub fn glob(&self) -> Vec<StrictPath> {
         let options = glob::MatchOptions {
             case_sensitive: crate::prelude::CASE_INSENSITIVE_OS,
@@ -423,6 +490,37 @@ impl<'de> serde::Deserialize<'de> for StrictPath {
     }
 }
 
+/// A file or folder in the system temp folder for a test to work in.
+/// Anything left from an earlier run is removed up front, and it's removed again once this is dropped,
+/// so the test cleans up after itself even if an assertion fails.
+#[cfg(test)]
+pub struct TempPath(StrictPath);
+
+#[cfg(test)]
+impl TempPath {
+    pub fn new(name: &str) -> Self {
+        let path = StrictPath::from_std_path_buf(&std::env::temp_dir()).joined(name);
+        let _ = path.remove();
+        Self(path)
+    }
+}
+
+#[cfg(test)]
+impl std::ops::Deref for TempPath {
+    type Target = StrictPath;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        let _ = self.0.remove();
+    }
+}
+
 #[allow(dead_code)]
 pub fn is_raw_path_relative(path: &str) -> bool {
     let path = path.replace('\\', "/");
@@ -514,6 +612,40 @@ mod tests {
             assert_eq!("/foo/bar".to_string(), render("/foo/bar"));
             assert_eq!("/foo/bar/".to_string(), render("\\foo/bar/"));
             assert_eq!("C:/foo".to_string(), render("C:/foo"));
+            assert_eq!("C:/foo".to_string(), render(r#"\\?\C:\foo"#));
+            assert_eq!("//remote/share/foo".to_string(), render(r#"\\?\UNC\remote\share\foo"#));
+        }
+
+        #[test]
+        #[cfg(target_os = "windows")]
+        fn can_interpret_remote_unc_path() {
+            assert_eq!(
+                r#"\\?\UNC\remote\share\foo"#.to_string(),
+                interpret(r#"\\remote\share\foo"#, &None)
+            );
+            assert_eq!(
+                r#"\\?\UNC\remote\share\foo"#.to_string(),
+                interpret(r#"\\?\UNC\remote\share\foo"#, &None)
+            );
+        }
+
+        #[test]
+        #[cfg(target_os = "windows")]
+        fn can_operate_on_path_longer_than_260_characters() {
+            let base = TempPath::new("ludusavi-test-long-path");
+            let file = base.joined(&format!("{}/{}/save.dat", "a".repeat(150), "b".repeat(150)));
+            assert!(file.render().len() > 260);
+            assert!(!file.render().contains('?'));
+
+            file.create_parent_dir().unwrap();
+            std::fs::write(file.interpret(), "foo").unwrap();
+            let found = file.is_file();
+            let size = file.metadata().map(|x| x.len()).unwrap_or_default();
+            base.remove().unwrap();
+
+            assert!(found);
+            assert_eq!(3, size);
+            assert!(!base.exists());
         }
 
         #[test]
@@ -739,12 +871,8 @@ mod tests {
         #[test]
         #[cfg(target_os = "windows")]
         fn can_split_drive_for_remote_unc_path() {
-            // TODO: Should be `\\remote` and `foo\bar`.
-            // Despite this, when backing up to a machine-local network share,
-            // it gets resolved to the actual local drive and therefore works.
-            // Unsure about behavior for a remote network share at this time.
             assert_eq!(
-                (s(""), s("/remote/foo/bar")),
+                (s(r#"\\remote"#), s("foo/bar")),
                 StrictPath::new(s(r#"\\remote\foo\bar"#)).split_drive()
             );
         }
@@ -765,6 +893,17 @@ mod tests {
             assert!(!StrictPath::new(s("")).is_prefix_of(&StrictPath::new(s("/foo"))));
         }
 
+        #[test]
+        fn contains() {
+            assert!(StrictPath::new(s("/foo")).contains(&StrictPath::new(s("/foo")), false));
+            assert!(StrictPath::new(s("/foo")).contains(&StrictPath::new(s("/foo/bar")), false));
+            assert!(!StrictPath::new(s("/foo/bar")).contains(&StrictPath::new(s("/foo")), false));
+            assert!(!StrictPath::new(s("/foo")).contains(&StrictPath::new(s("/fooo")), false));
+            assert!(!StrictPath::new(s("/foo")).contains(&StrictPath::new(s("/FOO/bar")), false));
+            assert!(StrictPath::new(s("/foo")).contains(&StrictPath::new(s("/FOO/bar")), true));
+            assert!(!StrictPath::new(s("")).contains(&StrictPath::new(s("")), false));
+        }
+
         #[test]
         #[cfg(target_os = "windows")]
         fn is_prefix_of_with_windows_drive_letters() {
@@ -801,5 +940,18 @@ mod tests {
                 ])
             );
         }
+
+        #[test]
+        fn suffix_of() {
+            assert_eq!(
+                Some(s("bar/baz")),
+                StrictPath::new(s("/foo")).suffix_of(&StrictPath::new(s("/foo/bar/baz")))
+            );
+            assert_eq!(
+                None,
+                StrictPath::new(s("/fo")).suffix_of(&StrictPath::new(s("/foo/bar")))
+            );
+            assert_eq!(None, StrictPath::new(s("/foo")).suffix_of(&StrictPath::new(s("/foo"))));
+        }
     }
 }
@@ -1,24 +1,45 @@
 use crate::{
-    config::{BackupFilter, RedirectConfig, RootsConfig, ToggledPaths, ToggledRegistry},
+    config::{
+        BackupFilter, ComparisonStrategy, PermissionDeniedPolicy, RedirectConfig, RestoreMode, RootsConfig,
+        ToggledPaths, ToggledRegistry,
+    },
+    ignore_file::IgnoreFiles,
+    known_folder::{resolve_known_folders, KnownFolder},
     layout::BackupLayout,
     manifest::{Game, GameFileConstraint, Os, Store},
+    ownership::OwnershipMismatch,
+    retry::Retry,
+    steam::user_folder_glob,
+    undo::UndoSnapshot,
+    wine::ShellFolders,
 };
 use fuzzy_matcher::FuzzyMatcher;
 use rayon::prelude::*;
 use std::io::Read;
 
 pub use crate::path::StrictPath;
-pub use crate::registry_compat::RegistryItem;
+pub use crate::registry_compat::{RegistryFilter, RegistryItem, RegistryValueCounts};
 
 const WINDOWS: bool = cfg!(target_os = "windows");
 const MAC: bool = cfg!(target_os = "macos");
 const LINUX: bool = cfg!(target_os = "linux");
 pub const CASE_INSENSITIVE_OS: bool = WINDOWS || MAC;
-const SKIP: &str = "<skip>";
+pub const SKIP: &str = "<skip>";
 const APP_DIR_NAME: &str = "ludusavi";
 const PORTABLE_FLAG_FILE_NAME: &str = "ludusavi.portable";
 const MIGRATION_FLAG_FILE_NAME: &str = ".flag_migrated_legacy_config";
 
+static CONFIG_DIR_OVERRIDE: once_cell::sync::OnceCell<std::path::PathBuf> = once_cell::sync::OnceCell::new();
+static DATA_DIR_OVERRIDE: once_cell::sync::OnceCell<std::path::PathBuf> = once_cell::sync::OnceCell::new();
+static PORTABLE_OVERRIDE: once_cell::sync::OnceCell<()> = once_cell::sync::OnceCell::new();
+static MANIFEST_OVERRIDE: once_cell::sync::OnceCell<StrictPath> = once_cell::sync::OnceCell::new();
+static MANIFEST_REFRESH: once_cell::sync::OnceCell<()> = once_cell::sync::OnceCell::new();
+/// Thread pools for copying files, by how many threads they have,
+/// so that each game doesn't have to start its own.
+static COPY_POOLS: once_cell::sync::Lazy<
+    std::sync::Mutex<std::collections::HashMap<usize, std::sync::Arc<rayon::ThreadPool>>>,
+> = once_cell::sync::Lazy::new(Default::default);
+
 #[derive(Clone, Debug, PartialEq, thiserror::Error)]
 pub enum Error {
     #[error("The manifest file is invalid: {why:?}")]
@@ -27,9 +48,15 @@ pub enum Error {
     #[error("Unable to download an update to the manifest file")]
     ManifestCannotBeUpdated,
 
+    #[error("The manifest file does not exist")]
+    ManifestNotFound { path: StrictPath },
+
     #[error("The config file is invalid: {why:?}")]
     ConfigInvalid { why: String },
 
+    #[error("No profile with this name")]
+    UnknownProfile { name: String },
+
     #[error("Target already exists")]
     CliBackupTargetExists { path: StrictPath },
 
@@ -42,16 +69,51 @@ pub enum Error {
     #[error("Some entries failed")]
     SomeEntriesFailed,
 
+    #[error("Some checks failed")]
+    SomeChecksFailed,
+
     #[error("Cannot prepare the backup target")]
     CannotPrepareBackupTarget { path: StrictPath },
 
+    #[error("The backup target is locked by another run")]
+    BackupTargetLocked { path: StrictPath, stale: bool },
+
     #[error("Cannot prepare the backup target")]
     RestorationSourceInvalid { path: StrictPath },
 
+    #[error("Files would collide on a case-insensitive backup target")]
+    BackupCaseCollision { path: StrictPath },
+
+    #[error("The migration target overlaps with the source")]
+    BackupMigrationOverlap { path: StrictPath },
+
+    #[error("The backup's signature is missing or invalid")]
+    BackupSignatureInvalid { path: StrictPath },
+
+    #[error("Unable to sign the backup")]
+    BackupSigningFailed { path: StrictPath },
+
+    /// `origin` is the environment variable or file that the key came from.
+    #[error("The signing key is invalid")]
+    SignatureKeyInvalid { origin: String },
+
+    #[error("No key to verify signatures with")]
+    SignatureKeyMissing,
+
+    #[error("A root is unavailable")]
+    RootUnavailable { path: StrictPath },
+
+    /// An extra backup target that can't be used, so the others get the backup without it.
+    #[error("Skipping a backup target")]
+    BackupTargetSkipped { path: StrictPath, origin: Box<Error> },
+
     #[allow(dead_code)]
     #[error("Error while working with the registry")]
     RegistryIssue,
 
+    #[error("The registry file is invalid: {why:?}")]
+    RegistryFileInvalid { path: StrictPath, why: String },
+
     #[error("Unable to browse file system")]
     UnableToBrowseFileSystem,
 
@@ -60,6 +122,33 @@ pub enum Error {
 
     #[error("Unable to open URL")]
     UnableToOpenUrl(String),
+
+    #[error("Cannot prepare the undo snapshot")]
+    CannotPrepareUndoSnapshot { path: StrictPath },
+
+    #[error("No restore to undo")]
+    NoUndoSnapshot,
+
+    #[error("Portable directory is not writable")]
+    PortableDirNotWritable { path: StrictPath },
+
+    #[error("Some games are running")]
+    GameIsRunning { games: Vec<String> },
+
+    #[error("Unable to export settings")]
+    SettingsCannotBeExported { path: StrictPath },
+
+    #[error("Unable to import settings")]
+    SettingsCannotBeImported { path: StrictPath },
+
+    #[error("Unable to export game")]
+    GameCannotBeExported { path: StrictPath },
+
+    #[error("Unable to import game")]
+    GameCannotBeImported { path: StrictPath },
+
+    #[error("The terminal interface failed: {why:?}")]
+    TerminalUiFailed { why: String },
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
@@ -69,6 +158,22 @@ pub struct ScannedFile {
     /// This is the restoration target path, without redirects applied.
     pub original_path: Option<StrictPath>,
     pub ignored: bool,
+    /// Windows marks this as a system file.
+    pub system: bool,
+    /// During restore, the backed up copy isn't the size that was recorded when it was written,
+    /// most likely because the backup was interrupted partway through the file.
+    pub partial: bool,
+    /// For a file in an Xbox container, the name that the game knows it by,
+    /// since the file itself is named with an opaque GUID.
+    pub xbox_name: Option<String>,
+}
+
+impl ScannedFile {
+    /// Where the file belongs on the system: its `original_path` if it's in a backup,
+    /// or else its own path.
+    pub fn original(&self) -> &StrictPath {
+        self.original_path.as_ref().unwrap_or(&self.path)
+    }
 }
 
 #[cfg(test)]
@@ -79,6 +184,9 @@ impl ScannedFile {
             size,
             original_path: None,
             ignored: false,
+            system: false,
+            partial: false,
+            xbox_name: None,
         }
     }
 
@@ -92,6 +200,8 @@ impl ScannedFile {
 pub struct ScannedRegistry {
     pub path: RegistryItem,
     pub ignored: bool,
+    /// Which of the key's values to back up.
+    pub filter: RegistryFilter,
 }
 
 #[cfg(test)]
@@ -100,6 +210,7 @@ impl ScannedRegistry {
         Self {
             path: RegistryItem::new(path.to_string()),
             ignored: false,
+            filter: Default::default(),
         }
     }
 
@@ -115,6 +226,9 @@ pub struct ScanInfo {
     pub found_files: std::collections::HashSet<ScannedFile>,
     pub found_registry_keys: std::collections::HashSet<ScannedRegistry>,
     pub registry_file: Option<StrictPath>,
+    /// The game is known to only keep its saves in the cloud,
+    /// and nothing was found locally.
+    pub cloud_only: bool,
 }
 
 impl ScanInfo {
@@ -178,17 +292,199 @@ impl ScanInfo {
         self.found_files.iter().filter(|x| !x.ignored).count()
             + self.found_registry_keys.iter().filter(|x| !x.ignored).count()
     }
+
+    /// The store whose roots contain the game's saves.
+    /// If saves are found under the roots of several stores, the earliest one in `Store::ALL` wins,
+    /// so that the choice doesn't depend on the order of the roots.
+    /// Saves outside of every root, like in the home folder, count as `Store::Other`.
+    pub fn primary_store(&self, roots: &[RootsConfig]) -> Store {
+        let roots: Vec<_> = roots.iter().flat_map(|x| x.glob()).collect();
+        let stores: std::collections::HashSet<_> = self
+            .found_files
+            .iter()
+            .filter(|x| !x.ignored)
+            .flat_map(|file| {
+                let file = file.path.as_std_path_buf();
+                roots
+                    .iter()
+                    .filter(move |root| file.starts_with(root.path.as_std_path_buf()))
+                    .map(|root| root.store)
+            })
+            .collect();
+        Store::ALL
+            .iter()
+            .find(|x| stores.contains(*x))
+            .copied()
+            .unwrap_or(Store::Other)
+    }
+}
+
+/// Why a file couldn't be processed, as reported by the OS.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FailureReason {
+    pub kind: std::io::ErrorKind,
+    pub os_code: Option<i32>,
+    pub message: String,
+}
+
+impl FailureReason {
+    /// There's no stable `ErrorKind` for this, so we check the OS error code.
+    pub fn is_disk_full(&self) -> bool {
+        match self.os_code {
+            // ERROR_HANDLE_DISK_FULL, ERROR_DISK_FULL
+            Some(code) if cfg!(target_os = "windows") => code == 39 || code == 112,
+            // ENOSPC
+            Some(code) => code == 28,
+            None => false,
+        }
+    }
+}
+
+impl From<&std::io::Error> for FailureReason {
+    fn from(error: &std::io::Error) -> Self {
+        Self {
+            kind: error.kind(),
+            os_code: error.raw_os_error(),
+            message: error.to_string(),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct BackupInfo {
     pub failed_files: std::collections::HashSet<ScannedFile>,
     pub failed_registry: std::collections::HashSet<RegistryItem>,
+    /// For restorations, what was done (or would be done) with each file.
+    pub restore_actions: std::collections::HashMap<ScannedFile, RestoreAction>,
+    /// For verified restorations, files whose written content didn't match the backup.
+    /// These are also included in `failed_files`.
+    pub mismatched_files: std::collections::HashSet<ScannedFile>,
+    /// For mirrored restorations, files that were (or would be) removed
+    /// because they're in the game's save folders but not in the backup.
+    /// Any that couldn't be removed are in `failed_files` instead.
+    pub removed_files: std::collections::HashSet<StrictPath>,
+    /// For backups, files that couldn't be read because of their permissions.
+    /// These are also included in `failed_files`.
+    pub denied_files: std::collections::HashSet<ScannedFile>,
+    /// Whether the game still counts as successful when only `denied_files` failed.
+    pub tolerate_denied: bool,
+    /// Why some of the `failed_files` failed, when the OS gave a reason.
+    pub failure_reasons: std::collections::HashMap<ScannedFile, FailureReason>,
+    /// For backups, how many registry values were saved or left out by the entries' filters.
+    pub registry_values: RegistryValueCounts,
+    /// For restore previews, files whose owner or mode would differ from what's there now.
+    pub ownership_mismatches: std::collections::HashMap<ScannedFile, OwnershipMismatch>,
+    /// Problems that didn't make the game fail, besides the ones that can be found from the other fields.
+    pub warnings: Vec<Warning>,
+    /// How many bytes were written by copying files, across all targets.
+    pub copied_bytes: u64,
+    /// For backups, whether each target received the game without any failures.
+    pub targets: std::collections::BTreeMap<StrictPath, bool>,
 }
 
 impl BackupInfo {
     pub fn successful(&self) -> bool {
-        self.failed_files.is_empty() && self.failed_registry.is_empty()
+        self.failed_registry.is_empty() && self.failed_files.iter().all(|x| self.failure_tolerated(x))
+    }
+
+    /// Whether a failed file still allows the game as a whole to count as successful.
+    pub fn failure_tolerated(&self, file: &ScannedFile) -> bool {
+        self.tolerate_denied && self.denied_files.contains(file)
+    }
+
+    /// Failed files that aren't part of the scan, like extra files that a mirrored
+    /// restore couldn't remove or a signature that couldn't be written.
+    pub fn failed_removals<'a>(&'a self, scan_info: &'a ScanInfo) -> impl Iterator<Item = &'a ScannedFile> {
+        self.failed_files.iter().filter(|x| !scan_info.found_files.contains(*x))
+    }
+
+    /// Combine the results of writing the same game to another target.
+    /// A file counts as failed if it failed for any target.
+    pub fn merge(&mut self, other: Self) {
+        self.failed_files.extend(other.failed_files);
+        self.failed_registry.extend(other.failed_registry);
+        self.restore_actions.extend(other.restore_actions);
+        self.mismatched_files.extend(other.mismatched_files);
+        self.removed_files.extend(other.removed_files);
+        self.denied_files.extend(other.denied_files);
+        self.failure_reasons.extend(other.failure_reasons);
+        self.ownership_mismatches.extend(other.ownership_mismatches);
+        self.warnings.extend(other.warnings);
+        self.copied_bytes += other.copied_bytes;
+        self.targets.extend(other.targets);
+        // The same values are written to every target, so there's nothing to add up.
+        if self.registry_values == RegistryValueCounts::default() {
+            self.registry_values = other.registry_values;
+        }
+        self.tolerate_denied = self.tolerate_denied && other.tolerate_denied;
+    }
+}
+
+/// A file or registry key that made its game fail.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize)]
+pub struct FailedEntry {
+    pub game: String,
+    pub path: String,
+}
+
+/// Entries that failed for the same reason.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FailureGroup {
+    /// This is `None` when the OS didn't give a reason, like for registry keys.
+    pub reason: Option<FailureReason>,
+    pub games: std::collections::BTreeSet<String>,
+    pub count: usize,
+    /// The first entries that failed, up to `ErrorReport::MAX_LISTED`.
+    pub sample: Vec<FailedEntry>,
+}
+
+impl FailureGroup {
+    /// Whether there were too many entries to list them all.
+    pub fn collapsed(&self) -> bool {
+        self.count > self.sample.len()
+    }
+}
+
+/// Every entry that failed during an operation, grouped by reason,
+/// so that they can be reviewed together once it's done.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ErrorReport {
+    /// In the order that each reason first came up.
+    pub groups: Vec<FailureGroup>,
+}
+
+impl ErrorReport {
+    /// Past this, entries with the same reason (like from a whole unreadable root)
+    /// are only counted, with the first few kept as a sample.
+    pub const MAX_LISTED: usize = 5;
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    pub fn add(&mut self, game: &str, path: String, reason: Option<&FailureReason>) {
+        let index = match self.groups.iter().position(|x| x.reason.as_ref() == reason) {
+            Some(index) => index,
+            None => {
+                self.groups.push(FailureGroup {
+                    reason: reason.cloned(),
+                    games: Default::default(),
+                    count: 0,
+                    sample: vec![],
+                });
+                self.groups.len() - 1
+            }
+        };
+
+        let group = &mut self.groups[index];
+        group.count += 1;
+        group.games.insert(game.to_string());
+        if group.sample.len() < Self::MAX_LISTED {
+            group.sample.push(FailedEntry {
+                game: game.to_string(),
+                path,
+            });
+        }
     }
 }
 
@@ -202,6 +498,52 @@ pub struct OperationStatus {
     pub processed_games: usize,
     #[serde(rename = "processedBytes")]
     pub processed_bytes: u64,
+    /// How many bytes were actually written by copying files, across all targets.
+    /// Files that were already up to date aren't copied, so this can be less than `processed_bytes`.
+    #[serde(rename = "copiedBytes", skip_serializing_if = "crate::serialization::is_zero")]
+    pub copied_bytes: u64,
+    #[serde(
+        rename = "skippedIdenticalFiles",
+        skip_serializing_if = "crate::serialization::is_zero"
+    )]
+    pub skipped_identical_files: usize,
+    #[serde(rename = "deferredGames", skip_serializing_if = "crate::serialization::is_zero")]
+    pub deferred_games: usize,
+    /// How many games were left out for each reason.
+    #[serde(skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub skipped: std::collections::BTreeMap<SkipReason, usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<Warning>,
+    /// How each backup target fared, by its rendered path.
+    /// This is only interesting when there's more than one.
+    #[serde(skip_serializing_if = "has_one_target_at_most")]
+    pub targets: std::collections::BTreeMap<String, TargetStatus>,
+    /// How many games there were to choose from, if only some were selected,
+    /// so that the others aren't mistaken for failures.
+    #[serde(rename = "availableGames", skip_serializing_if = "Option::is_none")]
+    pub available_games: Option<usize>,
+}
+
+fn has_one_target_at_most(targets: &std::collections::BTreeMap<String, TargetStatus>) -> bool {
+    targets.len() <= 1
+}
+
+/// How one of the backup targets fared over the whole operation.
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize)]
+pub struct TargetStatus {
+    #[serde(rename = "processedGames")]
+    pub processed_games: usize,
+    #[serde(rename = "failedGames", skip_serializing_if = "crate::serialization::is_zero")]
+    pub failed_games: usize,
+    /// The target couldn't be prepared, so nothing was written to it.
+    #[serde(skip_serializing_if = "crate::serialization::is_false")]
+    pub unusable: bool,
+}
+
+impl TargetStatus {
+    pub fn successful(&self) -> bool {
+        !self.unusable && self.failed_games == 0
+    }
 }
 
 impl OperationStatus {
@@ -211,7 +553,68 @@ impl OperationStatus {
         if processed {
             self.processed_games += 1;
             self.processed_bytes += scan_info.sum_bytes(backup_info);
+            if let Some(backup_info) = backup_info {
+                self.copied_bytes += backup_info.copied_bytes;
+                self.skipped_identical_files += backup_info
+                    .restore_actions
+                    .values()
+                    .filter(|x| **x == RestoreAction::Skip)
+                    .count();
+                self.add_warnings(&scan_info.game_name, backup_info);
+                for (target, successful) in &backup_info.targets {
+                    let status = self.targets.entry(target.render()).or_default();
+                    status.processed_games += 1;
+                    if !successful {
+                        status.failed_games += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Record a backup target that couldn't be used at all.
+    pub fn add_unusable_target(&mut self, target: &StrictPath) {
+        self.targets.entry(target.render()).or_default().unusable = true;
+    }
+
+    /// Count a game that wasn't processed, by why it wasn't.
+    pub fn add_skip(&mut self, decision: &OperationStepDecision) {
+        let reason = match decision {
+            OperationStepDecision::Processed => return,
+            OperationStepDecision::Cancelled => SkipReason::Cancelled,
+            OperationStepDecision::Ignored => SkipReason::Ignored,
+            OperationStepDecision::RootUnavailable => SkipReason::RootUnavailable,
+            OperationStepDecision::Frozen => SkipReason::Frozen,
+            OperationStepDecision::Deferred => {
+                self.deferred_games += 1;
+                SkipReason::Deferred
+            }
+        };
+        *self.skipped.entry(reason).or_insert(0) += 1;
+    }
+
+    pub fn add_warnings(&mut self, game: &str, backup_info: &BackupInfo) {
+        for _ in backup_info
+            .failed_files
+            .iter()
+            .filter(|x| backup_info.failure_tolerated(x))
+        {
+            self.warnings
+                .push(Warning::DeniedFileSkipped { game: game.to_string() });
+        }
+        self.warnings.extend(backup_info.warnings.iter().cloned());
+    }
+
+    /// Each distinct warning with how many times it happened, in the order they first came up.
+    pub fn collapsed_warnings(&self) -> Vec<(&Warning, usize)> {
+        let mut collapsed: Vec<(&Warning, usize)> = vec![];
+        for warning in &self.warnings {
+            match collapsed.iter_mut().find(|(x, _)| *x == warning) {
+                Some((_, count)) => *count += 1,
+                None => collapsed.push((warning, 1)),
+            }
         }
+        collapsed
     }
 
     pub fn processed_all(&self) -> bool {
@@ -227,11 +630,71 @@ impl OperationStatus {
     }
 }
 
+/// A problem that didn't stop the operation, but that would be easy to miss
+/// in the list of files.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize)]
+#[serde(tag = "kind")]
+pub enum Warning {
+    /// A file couldn't be read because of its permissions, so it was left out,
+    /// but the game still counts as successful.
+    #[serde(rename = "deniedFileSkipped")]
+    DeniedFileSkipped { game: String },
+    /// A game was backed up while its saves were still changing,
+    /// because it had already been deferred too many times in a row.
+    #[serde(rename = "deferralLimitReached")]
+    DeferralLimitReached { game: String },
+    /// A file had NTFS alternate data streams, but they were left out of the backup.
+    #[serde(rename = "alternateStreamsStripped")]
+    AlternateStreamsStripped { game: String },
+    /// A file had NTFS alternate data streams to keep,
+    /// but they were skipped because the destination can't store them.
+    #[serde(rename = "alternateStreamsUnsupported")]
+    AlternateStreamsUnsupported { game: String },
+    /// An existing file had the same name as a restored file, except for its case,
+    /// so it was renamed to match the backup.
+    #[serde(rename = "caseNormalized")]
+    CaseNormalized { game: String },
+    /// A file couldn't be read to compare it with the backup,
+    /// so it was only compared by size and modification time.
+    #[serde(rename = "weakIdenticalCheck")]
+    WeakIdenticalCheck { game: String },
+}
+
+/// Why a game was left out of an operation.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd, serde::Serialize)]
+pub enum SkipReason {
+    #[serde(rename = "ignored")]
+    Ignored,
+    #[serde(rename = "deferred")]
+    Deferred,
+    #[serde(rename = "cancelled")]
+    Cancelled,
+    #[serde(rename = "rootUnavailable")]
+    RootUnavailable,
+    #[serde(rename = "frozen")]
+    Frozen,
+}
+
+/// What restoring a file does to its target location.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Serialize)]
+pub enum RestoreAction {
+    Create,
+    Overwrite,
+    Skip,
+}
+
 #[derive(Debug, Clone, PartialEq, serde::Serialize)]
 pub enum OperationStepDecision {
     Processed,
     Cancelled,
     Ignored,
+    /// The game's files changed too recently, so it'll be backed up later.
+    Deferred,
+    /// The game's last backup has files from a root that isn't available right now,
+    /// so backing it up again could replace them with nothing.
+    RootUnavailable,
+    /// The game is on the restore freeze list, so its current saves are kept.
+    Frozen,
 }
 
 impl Default for OperationStepDecision {
@@ -240,17 +703,140 @@ impl Default for OperationStepDecision {
     }
 }
 
-pub fn app_dir() -> std::path::PathBuf {
-    if let Ok(mut flag) = std::env::current_exe() {
+/// Use custom locations instead of the standard ones (e.g., for testing).
+/// This must be called before anything else looks up the directories.
+pub fn set_app_dir_overrides(config_dir: Option<std::path::PathBuf>, data_dir: Option<std::path::PathBuf>) {
+    if let Some(config_dir) = config_dir {
+        let _ = CONFIG_DIR_OVERRIDE.set(config_dir);
+    }
+    if let Some(data_dir) = data_dir {
+        let _ = DATA_DIR_OVERRIDE.set(data_dir);
+    }
+}
+
+/// Act as if the portable flag file exists.
+pub fn set_portable() {
+    let _ = PORTABLE_OVERRIDE.set(());
+}
+
+/// Use this local manifest file instead of downloading one.
+/// A relative path is resolved against the current directory.
+pub fn set_manifest_override(path: &StrictPath) {
+    let _ = MANIFEST_OVERRIDE.set(path.interpreted());
+}
+
+pub fn manifest_override() -> Option<StrictPath> {
+    MANIFEST_OVERRIDE.get().cloned()
+}
+
+/// Check for a manifest update when loading it, even if the cache is recent.
+pub fn set_manifest_refresh() {
+    let _ = MANIFEST_REFRESH.set(());
+}
+
+pub fn manifest_refresh_forced() -> bool {
+    MANIFEST_REFRESH.get().is_some()
+}
+
+/// In portable mode, this is the folder containing the executable,
+/// and everything will be stored relative to it.
+pub fn portable_dir() -> Option<std::path::PathBuf> {
+    let mut flag = std::env::current_exe().ok()?;
+    flag.pop();
+    flag.push(PORTABLE_FLAG_FILE_NAME);
+    if PORTABLE_OVERRIDE.get().is_some() || flag.exists() {
         flag.pop();
-        flag.push(PORTABLE_FLAG_FILE_NAME);
-        if flag.exists() {
-            flag.pop();
-            return flag;
+        Some(flag)
+    } else {
+        None
+    }
+}
+
+/// In portable mode, make sure that we can actually write next to the executable,
+/// rather than quietly storing everything somewhere else.
+pub fn check_portable_dir() -> Result<(), Error> {
+    let dir = match portable_dir() {
+        Some(x) => x,
+        None => return Ok(()),
+    };
+    let probe = dir.join(".ludusavi-write-test");
+    match std::fs::File::create(&probe) {
+        Ok(_) => {
+            let _ = std::fs::remove_file(&probe);
+            Ok(())
         }
+        Err(_) => Err(Error::PortableDirNotWritable {
+            path: StrictPath::from_std_path_buf(&dir),
+        }),
+    }
+}
+
+/// Resolve an XDG base directory, falling back to the spec's default under the home folder
+/// when the variable is unset or not absolute (which the spec says to ignore).
+fn xdg_base_dir(
+    var: impl Fn(&str) -> Option<std::ffi::OsString>,
+    env_var: &str,
+    default_in_home: &str,
+) -> Option<std::path::PathBuf> {
+    match var(env_var).map(std::path::PathBuf::from) {
+        Some(path) if path.is_absolute() => Some(path),
+        _ => dirs::home_dir().map(|home| home.join(default_in_home)),
+    }
+}
+
+/// Where to keep the config file.
+pub fn app_dir() -> std::path::PathBuf {
+    if let Some(path) = CONFIG_DIR_OVERRIDE.get() {
+        return path.clone();
+    }
+    if let Some(path) = portable_dir() {
+        return path;
+    }
+
+    let mut path = if LINUX {
+        xdg_base_dir(|x| std::env::var_os(x), "XDG_CONFIG_HOME", ".config")
+    } else {
+        dirs::config_dir()
+    }
+    .unwrap();
+    path.push(APP_DIR_NAME);
+    path
+}
+
+/// Where to keep downloaded data, like the manifest.
+pub fn data_dir() -> std::path::PathBuf {
+    if let Some(path) = DATA_DIR_OVERRIDE.get() {
+        return path.clone();
+    }
+    if let Some(path) = portable_dir() {
+        return path;
+    }
+
+    let mut path = if LINUX {
+        xdg_base_dir(|x| std::env::var_os(x), "XDG_DATA_HOME", ".local/share")
+    } else {
+        dirs::data_dir()
+    }
+    .unwrap();
+    path.push(APP_DIR_NAME);
+    path
+}
+
+/// Where to keep state from previous runs, like the undo snapshot.
+pub fn state_dir() -> std::path::PathBuf {
+    if let Some(path) = DATA_DIR_OVERRIDE.get() {
+        return path.clone();
+    }
+    if let Some(path) = portable_dir() {
+        return path;
     }
 
-    let mut path = dirs::config_dir().unwrap();
+    let mut path = if LINUX {
+        xdg_base_dir(|x| std::env::var_os(x), "XDG_STATE_HOME", ".local/state")
+    } else {
+        dirs::data_local_dir()
+    }
+    .unwrap();
     path.push(APP_DIR_NAME);
     path
 }
@@ -263,6 +849,10 @@ pub fn app_dir() -> std::path::PathBuf {
 /// then later launches Ludusavi with a custom XDG_CONFIG_HOME, so the
 /// `standard_app_dir` no longer exists, but the `legacy_app_dir` does.
 pub fn migrate_legacy_config() {
+    if CONFIG_DIR_OVERRIDE.get().is_some() {
+        return;
+    }
+
     let standard_app_dir = app_dir();
     let mut standard_migration_flag_file = standard_app_dir.clone();
     standard_migration_flag_file.push(MIGRATION_FLAG_FILE_NAME);
@@ -314,6 +904,49 @@ pub fn game_file_restoration_target(
     }
 }
 
+/// Files that are already at a restoration target, but with a different case in their names.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CaseConflict {
+    None,
+    /// This file should be renamed to the target's case before writing it,
+    /// or else it would keep its old name.
+    Rename(StrictPath),
+    /// More than one file matches, so renaming one would collide with another.
+    Ambiguous(Vec<StrictPath>),
+}
+
+/// Look for files in the target's folder whose names only differ from it by case.
+/// This only matters when the file system ignores case,
+/// since otherwise they're just different files.
+pub fn find_case_conflict(target: &StrictPath, case_insensitive: bool) -> CaseConflict {
+    if !case_insensitive {
+        return CaseConflict::None;
+    }
+    let target = target.as_std_path_buf();
+    let (parent, name) = match (target.parent(), target.file_name()) {
+        (Some(parent), Some(name)) => (parent, name.to_string_lossy().to_lowercase()),
+        _ => return CaseConflict::None,
+    };
+    let entries = match std::fs::read_dir(parent) {
+        Ok(x) => x,
+        Err(_) => return CaseConflict::None,
+    };
+
+    let mut matches: Vec<_> = entries
+        .filter_map(|x| x.ok())
+        .filter(|x| x.file_name().to_string_lossy().to_lowercase() == name)
+        .map(|x| x.path())
+        .collect();
+    matches.sort();
+
+    match matches.as_slice() {
+        [] => CaseConflict::None,
+        [existing] if existing.file_name() == target.file_name() => CaseConflict::None,
+        [existing] => CaseConflict::Rename(StrictPath::from(existing.clone())),
+        _ => CaseConflict::Ambiguous(matches.into_iter().map(StrictPath::from).collect()),
+    }
+}
+
 pub fn get_os() -> Os {
     if LINUX {
         Os::Linux
@@ -358,6 +991,8 @@ pub fn parse_paths(
     install_dir: &Option<String>,
     steam_id: &Option<u32>,
     manifest_dir: &StrictPath,
+    wine_folders: &Option<ShellFolders>,
+    steam_user: &str,
 ) -> std::collections::HashSet<StrictPath> {
     let mut paths = std::collections::HashSet::new();
 
@@ -367,7 +1002,8 @@ pub fn parse_paths(
     };
 
     paths.insert(
-        path.replace("<root>", &root.path.interpret())
+        resolve_known_folders(path)
+            .replace("<root>", &root.path.interpret())
             .replace("<game>", install_dir)
             .replace(
                 "<base>",
@@ -380,11 +1016,14 @@ pub fn parse_paths(
                 "<home>",
                 &dirs::home_dir().unwrap_or_else(|| SKIP.into()).to_string_lossy(),
             )
-            .replace("<storeUserId>", "*")
+            .replace("<storeUserId>", steam_user)
             .replace("<osUserName>", &whoami::username())
             .replace("<winAppData>", &check_windows_path(dirs::data_dir()))
             .replace("<winLocalAppData>", &check_windows_path(dirs::data_local_dir()))
-            .replace("<winDocuments>", &check_windows_path(dirs::document_dir()))
+            .replace(
+                "<winDocuments>",
+                &check_windows_path(KnownFolder::Documents.path().or_else(dirs::document_dir)),
+            )
             .replace("<winPublic>", &check_windows_path(dirs::public_dir()))
             .replace("<winProgramData>", &check_windows_path_str("C:/Windows/ProgramData"))
             .replace("<winDir>", &check_windows_path_str("C:/Windows"))
@@ -419,73 +1058,80 @@ pub fn parse_paths(
             root.path.interpret(),
             steam_id.unwrap()
         );
-        let path2 = path
-            .replace("<root>", &root.path.interpret())
-            .replace("<game>", install_dir)
-            .replace(
-                "<base>",
-                &format!("{}/steamapps/common/{}", root.path.interpret(), install_dir),
-            )
-            .replace("<home>", &format!("{}/users/steamuser", prefix))
-            .replace("<storeUserId>", "*")
-            .replace("<osUserName>", "steamuser")
-            .replace("<winPublic>", &format!("{}/users/Public", prefix))
-            .replace("<winProgramData>", &format!("{}/ProgramData", prefix))
-            .replace("<winDir>", &format!("{}/windows", prefix))
-            .replace("<xdgData>", &check_nonwindows_path(dirs::data_dir()))
-            .replace("<xdgConfig>", &check_nonwindows_path(dirs::config_dir()))
-            .replace("<regHkcu>", SKIP)
-            .replace("<regHklm>", SKIP);
-        paths.insert(
-            path2
-                .replace("<winDocuments>", &format!("{}/users/steamuser/Documents", prefix))
-                .replace("<winAppData>", &format!("{}/users/steamuser/AppData/Roaming", prefix))
-                .replace(
-                    "<winLocalAppData>",
-                    &format!("{}/users/steamuser/AppData/Local", prefix),
-                ),
-        );
-        paths.insert(
-            path2
-                .replace("<winDocuments>", &format!("{}/users/steamuser/My Documents", prefix))
-                .replace("<winAppData>", &format!("{}/users/steamuser/Application Data", prefix))
+        // Saves may also be in folders that the prefix redirects elsewhere.
+        let redirected = wine_folders.as_ref().map(|x| x.apply(path));
+        for path in std::iter::once(path).chain(redirected.as_deref()) {
+            let path2 = path
+                .replace("<root>", &root.path.interpret())
+                .replace("<game>", install_dir)
                 .replace(
-                    "<winLocalAppData>",
-                    &format!("{}/users/steamuser/Local Settings/Application Data", prefix),
-                ),
-        );
+                    "<base>",
+                    &format!("{}/steamapps/common/{}", root.path.interpret(), install_dir),
+                )
+                .replace("<home>", &format!("{}/users/steamuser", prefix))
+                .replace("<storeUserId>", steam_user)
+                .replace("<osUserName>", "steamuser")
+                .replace("<winPublic>", &format!("{}/users/Public", prefix))
+                .replace("<winProgramData>", &format!("{}/ProgramData", prefix))
+                .replace("<winDir>", &format!("{}/windows", prefix))
+                .replace("<xdgData>", &check_nonwindows_path(dirs::data_dir()))
+                .replace("<xdgConfig>", &check_nonwindows_path(dirs::config_dir()))
+                .replace("<regHkcu>", SKIP)
+                .replace("<regHklm>", SKIP);
+            paths.insert(
+                path2
+                    .replace("<winDocuments>", &format!("{}/users/steamuser/Documents", prefix))
+                    .replace("<winAppData>", &format!("{}/users/steamuser/AppData/Roaming", prefix))
+                    .replace(
+                        "<winLocalAppData>",
+                        &format!("{}/users/steamuser/AppData/Local", prefix),
+                    ),
+            );
+            paths.insert(
+                path2
+                    .replace("<winDocuments>", &format!("{}/users/steamuser/My Documents", prefix))
+                    .replace("<winAppData>", &format!("{}/users/steamuser/Application Data", prefix))
+                    .replace(
+                        "<winLocalAppData>",
+                        &format!("{}/users/steamuser/Local Settings/Application Data", prefix),
+                    ),
+            );
+        }
     }
     if root.store == Store::OtherWine {
         let prefix = format!("{}/drive_*", root.path.interpret());
-        let path2 = path
-            .replace("<root>", &root.path.interpret())
-            .replace("<game>", install_dir)
-            .replace("<base>", &format!("{}/{}", root.path.interpret(), install_dir))
-            .replace("<home>", &format!("{}/users/*", prefix))
-            .replace("<storeUserId>", "*")
-            .replace("<osUserName>", "*")
-            .replace("<winPublic>", &format!("{}/users/Public", prefix))
-            .replace("<winProgramData>", &format!("{}/ProgramData", prefix))
-            .replace("<winDir>", &format!("{}/windows", prefix))
-            .replace("<xdgData>", &check_nonwindows_path(dirs::data_dir()))
-            .replace("<xdgConfig>", &check_nonwindows_path(dirs::config_dir()))
-            .replace("<regHkcu>", SKIP)
-            .replace("<regHklm>", SKIP);
-        paths.insert(
-            path2
-                .replace("<winDocuments>", &format!("{}/users/*/Documents", prefix))
-                .replace("<winAppData>", &format!("{}/users/*/AppData/Roaming", prefix))
-                .replace("<winLocalAppData>", &format!("{}/users/*/AppData/Local", prefix)),
-        );
-        paths.insert(
-            path2
-                .replace("<winDocuments>", &format!("{}/users/*/My Documents", prefix))
-                .replace("<winAppData>", &format!("{}/users/*/Application Data", prefix))
-                .replace(
-                    "<winLocalAppData>",
-                    &format!("{}/users/*/Local Settings/Application Data", prefix),
-                ),
-        );
+        let redirected = wine_folders.as_ref().map(|x| x.apply(path));
+        for path in std::iter::once(path).chain(redirected.as_deref()) {
+            let path2 = path
+                .replace("<root>", &root.path.interpret())
+                .replace("<game>", install_dir)
+                .replace("<base>", &format!("{}/{}", root.path.interpret(), install_dir))
+                .replace("<home>", &format!("{}/users/*", prefix))
+                .replace("<storeUserId>", "*")
+                .replace("<osUserName>", "*")
+                .replace("<winPublic>", &format!("{}/users/Public", prefix))
+                .replace("<winProgramData>", &format!("{}/ProgramData", prefix))
+                .replace("<winDir>", &format!("{}/windows", prefix))
+                .replace("<xdgData>", &check_nonwindows_path(dirs::data_dir()))
+                .replace("<xdgConfig>", &check_nonwindows_path(dirs::config_dir()))
+                .replace("<regHkcu>", SKIP)
+                .replace("<regHklm>", SKIP);
+            paths.insert(
+                path2
+                    .replace("<winDocuments>", &format!("{}/users/*/Documents", prefix))
+                    .replace("<winAppData>", &format!("{}/users/*/AppData/Roaming", prefix))
+                    .replace("<winLocalAppData>", &format!("{}/users/*/AppData/Local", prefix)),
+            );
+            paths.insert(
+                path2
+                    .replace("<winDocuments>", &format!("{}/users/*/My Documents", prefix))
+                    .replace("<winAppData>", &format!("{}/users/*/Application Data", prefix))
+                    .replace(
+                        "<winLocalAppData>",
+                        &format!("{}/users/*/Local Settings/Application Data", prefix),
+                    ),
+            );
+        }
     }
 
     paths
@@ -504,6 +1150,46 @@ fn glob_any(path: &StrictPath) -> Result<glob::Paths, ()> {
     Ok(entries)
 }
 
+/// Hidden and system files, as far as the current OS has those concepts.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct FileAttributes {
+    pub hidden: bool,
+    pub system: bool,
+}
+
+impl FileAttributes {
+    #[cfg(target_os = "windows")]
+    pub fn of(path: &StrictPath) -> Self {
+        use std::os::windows::fs::MetadataExt;
+        const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+        const FILE_ATTRIBUTE_SYSTEM: u32 = 0x4;
+
+        match path.metadata() {
+            Ok(metadata) => {
+                let attributes = metadata.file_attributes();
+                Self {
+                    hidden: attributes & FILE_ATTRIBUTE_HIDDEN != 0,
+                    system: attributes & FILE_ATTRIBUTE_SYSTEM != 0,
+                }
+            }
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Only the file's own name counts, since saves are often inside hidden folders like `~/.local`.
+    #[cfg(not(target_os = "windows"))]
+    pub fn of(path: &StrictPath) -> Self {
+        Self {
+            hidden: path
+                .as_std_path_buf()
+                .file_name()
+                .map(|x| x.to_string_lossy().starts_with('.'))
+                .unwrap_or(false),
+            system: false,
+        }
+    }
+}
+
 fn should_exclude_as_other_os_data(constraints: &[GameFileConstraint], host: Os, maybe_proton: bool) -> bool {
     let constrained = !constraints.is_empty();
     let unconstrained_by_os = constraints.iter().any(|x| x.os == None);
@@ -513,28 +1199,48 @@ fn should_exclude_as_other_os_data(constraints: &[GameFileConstraint], host: Os,
 }
 
 #[derive(Clone, Default)]
-pub struct InstallDirRanking(std::collections::HashMap<(RootsConfig, String), (i64, String)>);
+pub struct InstallDirRanking {
+    dirs: std::collections::HashMap<(RootsConfig, String), (i64, String)>,
+    /// Every game in a Wine root shares its user folders,
+    /// so they're read along with the installation directories, once per operation.
+    wine_folders: std::collections::HashMap<RootsConfig, Option<ShellFolders>>,
+}
 
 impl InstallDirRanking {
     /// Get the installation directory for some root/game combination.
     pub fn get(&self, root: &RootsConfig, name: &str) -> Option<String> {
-        self.0.get(&(root.to_owned(), name.to_owned())).and_then(|candidate| {
-            if candidate.0 == i64::MAX {
-                return Some(candidate.1.to_owned());
-            }
-            for other in self.0.values() {
-                if other.0 > candidate.0 {
-                    return None;
+        self.dirs
+            .get(&(root.to_owned(), name.to_owned()))
+            .and_then(|candidate| {
+                if candidate.0 == i64::MAX {
+                    return Some(candidate.1.to_owned());
                 }
-            }
-            Some(candidate.1.to_owned())
-        })
+                for other in self.dirs.values() {
+                    if other.0 > candidate.0 {
+                        return None;
+                    }
+                }
+                Some(candidate.1.to_owned())
+            })
+    }
+
+    /// Get the user folders for a game in some root, like from `ShellFolders::for_root`.
+    /// A game's own Proton prefix, or a Wine root that wasn't scanned, is read on the spot.
+    pub fn wine_folders(&self, root: &RootsConfig, steam_id: &Option<u32>) -> Option<ShellFolders> {
+        match self.wine_folders.get(root) {
+            Some(folders) => folders.clone(),
+            None => ShellFolders::for_root(root, steam_id),
+        }
     }
 
     pub fn scan(roots: &[RootsConfig], manifest: &crate::manifest::Manifest, subjects: &[String]) -> Self {
         let mut ranking = Self::default();
         for root in roots.iter().flat_map(|x| x.glob()) {
             ranking.scan_root(&root, manifest, subjects);
+            if root.store == Store::OtherWine {
+                let folders = ShellFolders::for_root(&root, &None);
+                ranking.wine_folders.insert(root, folders);
+            }
         }
         ranking
     }
@@ -593,12 +1299,53 @@ impl InstallDirRanking {
             .collect();
 
         for (score, name, subdir) in scores {
-            self.0
+            self.dirs
                 .insert((root.clone(), name.to_owned()), (score, subdir.to_owned()));
         }
     }
 }
 
+/// Reports how many files have been found so far while scanning a single game,
+/// so that a game with a huge save folder doesn't look like it's stuck.
+/// Calls are at least `interval` apart, so games with lots of tiny files don't flood the output.
+pub struct ScanHeartbeat<'a> {
+    interval: std::time::Duration,
+    last: std::time::Instant,
+    found: usize,
+    callback: Option<Box<dyn FnMut(usize) + 'a>>,
+}
+
+impl<'a> ScanHeartbeat<'a> {
+    pub fn new(interval: std::time::Duration, callback: impl FnMut(usize) + 'a) -> Self {
+        Self {
+            interval,
+            last: std::time::Instant::now(),
+            found: 0,
+            callback: Some(Box::new(callback)),
+        }
+    }
+
+    pub fn none() -> Self {
+        Self {
+            interval: std::time::Duration::ZERO,
+            last: std::time::Instant::now(),
+            found: 0,
+            callback: None,
+        }
+    }
+
+    fn tick(&mut self) {
+        self.found += 1;
+        if let Some(callback) = &mut self.callback {
+            let now = std::time::Instant::now();
+            if now.duration_since(self.last) >= self.interval {
+                self.last = now;
+                callback(self.found);
+            }
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn scan_game_for_backup(
     game: &Game,
@@ -610,11 +1357,44 @@ pub fn scan_game_for_backup(
     wine_prefix: &Option<StrictPath>,
     ranking: &InstallDirRanking,
     ignored_paths: &ToggledPaths,
-    #[allow(unused_variables)] ignored_registry: &ToggledRegistry,
+    ignored_registry: &ToggledRegistry,
 ) -> ScanInfo {
-    let mut found_files = std::collections::HashSet::new();
-    #[allow(unused_mut)]
-    let mut found_registry_keys = std::collections::HashSet::new();
+    scan_game_for_backup_with_anchors(
+        game,
+        name,
+        roots,
+        manifest_dir,
+        steam_id,
+        filter,
+        wine_prefix,
+        ranking,
+        ignored_paths,
+        ignored_registry,
+        &mut ScanHeartbeat::none(),
+    )
+    .0
+}
+
+/// Like `scan_game_for_backup`, but also returns the folders whose modification times
+/// show whether scanning again could turn up anything different (see `ScanCache`).
+/// There are none when the result can't be reused, like when it comes from the registry.
+#[allow(clippy::too_many_arguments)]
+pub fn scan_game_for_backup_with_anchors(
+    game: &Game,
+    name: &str,
+    roots: &[RootsConfig],
+    manifest_dir: &StrictPath,
+    steam_id: &Option<u32>,
+    filter: &BackupFilter,
+    wine_prefix: &Option<StrictPath>,
+    ranking: &InstallDirRanking,
+    ignored_paths: &ToggledPaths,
+    #[allow(unused_variables)] ignored_registry: &ToggledRegistry,
+    heartbeat: &mut ScanHeartbeat,
+) -> (ScanInfo, Option<Vec<StrictPath>>) {
+    let mut found_files = std::collections::HashSet::new();
+    #[allow(unused_mut)]
+    let mut found_registry_keys = std::collections::HashSet::new();
 
     let mut paths_to_check = std::collections::HashSet::<StrictPath>::new();
 
@@ -622,6 +1402,7 @@ pub fn scan_game_for_backup(
     let mut roots_to_check: Vec<RootsConfig> = vec![RootsConfig {
         path: StrictPath::new(SKIP.to_string()),
         store: Store::Other,
+        require: Default::default(),
     }];
     roots_to_check.extend(roots.iter().cloned());
 
@@ -629,6 +1410,7 @@ pub fn scan_game_for_backup(
         roots_to_check.push(RootsConfig {
             path: wp.clone(),
             store: Store::OtherWine,
+            require: Default::default(),
         });
 
         // We can add this for Wine prefixes from the CLI because they're
@@ -640,16 +1422,22 @@ pub fn scan_game_for_backup(
         ));
     }
 
-    for root in roots_to_check.iter().flat_map(|x| x.glob()) {
-        if root.path.raw().trim().is_empty() {
+    for root in roots_to_check
+        .iter()
+        .filter(|x| !filter.is_root_excluded(name, x))
+        .flat_map(|x| x.glob())
+    {
+        if root.path.raw().trim().is_empty() || filter.is_root_excluded(name, &root) {
             continue;
         }
         if let Some(files) = &game.files {
             let maybe_proton = get_os() == Os::Linux && root.store == Store::Steam && steam_id.is_some();
             let install_dir = ranking.get(&root, name);
+            let wine_folders = ranking.wine_folders(&root, steam_id);
+            let steam_user = user_folder_glob(&filter.steam_user, &root);
 
             for (raw_path, path_info) in files {
-                if raw_path.trim().is_empty() {
+                if raw_path.trim().is_empty() || filter.is_tag_excluded(&path_info.tags) {
                     continue;
                 }
                 if filter.exclude_other_os_data {
@@ -659,7 +1447,15 @@ pub fn scan_game_for_backup(
                         }
                     }
                 }
-                let candidates = parse_paths(raw_path, &root, &install_dir, steam_id, manifest_dir);
+                let candidates = parse_paths(
+                    raw_path,
+                    &root,
+                    &install_dir,
+                    steam_id,
+                    manifest_dir,
+                    &wine_folders,
+                    &steam_user,
+                );
                 for candidate in candidates {
                     if candidate.raw().contains(SKIP) {
                         continue;
@@ -669,9 +1465,16 @@ pub fn scan_game_for_backup(
             }
         }
         if root.store == Store::Steam && steam_id.is_some() {
+            let steam_user = user_folder_glob(&filter.steam_user, &root);
+
             // Cloud saves:
             paths_to_check.insert(StrictPath::relative(
-                format!("{}/userdata/*/{}/remote/", root.path.interpret(), &steam_id.unwrap()),
+                format!(
+                    "{}/userdata/{}/{}/remote/",
+                    root.path.interpret(),
+                    steam_user,
+                    &steam_id.unwrap()
+                ),
                 Some(manifest_dir.interpret()),
             ));
 
@@ -679,8 +1482,9 @@ pub fn scan_game_for_backup(
             if !filter.exclude_store_screenshots {
                 paths_to_check.insert(StrictPath::relative(
                     format!(
-                        "{}/userdata/*/760/remote/{}/screenshots/*.*",
+                        "{}/userdata/{}/760/remote/{}/screenshots/*.*",
                         root.path.interpret(),
+                        steam_user,
                         &steam_id.unwrap()
                     ),
                     Some(manifest_dir.interpret()),
@@ -702,6 +1506,9 @@ pub fn scan_game_for_backup(
         }
     }
 
+    let mut anchors = crate::scan_cache::anchors(&paths_to_check, &roots_to_check);
+    let mut ignore_files = IgnoreFiles::default();
+
     for path in paths_to_check {
         if filter.is_path_ignored(&path) {
             continue;
@@ -716,8 +1523,19 @@ pub fn scan_game_for_backup(
                 if filter.is_path_ignored(&p) {
                     continue;
                 }
+                let std_path = p.as_std_path_buf();
+                if let Some(parent) = std_path.parent() {
+                    if ignore_files.is_ignored(parent, &std_path) {
+                        continue;
+                    }
+                }
+                let attributes = FileAttributes::of(&p);
+                if filter.is_hidden_excluded(&attributes) {
+                    continue;
+                }
                 let ignored = ignored_paths.is_ignored(name, &p);
                 let metadata = p.metadata();
+                heartbeat.tick();
                 found_files.insert(ScannedFile {
                     path: p,
                     size: match metadata {
@@ -726,21 +1544,33 @@ pub fn scan_game_for_backup(
                     },
                     original_path: None,
                     ignored,
+                    system: attributes.system,
+                    partial: false,
+                    xbox_name: None,
                 });
             } else if p.is_dir() {
-                for child in walkdir::WalkDir::new(p.as_std_path_buf())
+                let root = p.as_std_path_buf();
+                for child in walkdir::WalkDir::new(&root)
                     .max_depth(100)
                     .follow_links(true)
                     .into_iter()
                     .filter_map(|e| e.ok())
                 {
                     if child.file_type().is_file() {
+                        if ignore_files.is_ignored(&root, child.path()) {
+                            continue;
+                        }
                         let child = StrictPath::from(&child).rendered();
                         if filter.is_path_ignored(&child) {
                             continue;
                         }
+                        let attributes = FileAttributes::of(&child);
+                        if filter.is_hidden_excluded(&attributes) {
+                            continue;
+                        }
                         let ignored = ignored_paths.is_ignored(name, &child);
                         let metadata = child.metadata();
+                        heartbeat.tick();
                         found_files.insert(ScannedFile {
                             path: child,
                             size: match metadata {
@@ -749,6 +1579,9 @@ pub fn scan_game_for_backup(
                             },
                             original_path: None,
                             ignored,
+                            system: attributes.system,
+                            partial: false,
+                            xbox_name: None,
                         });
                     }
                 }
@@ -759,23 +1592,201 @@ pub fn scan_game_for_backup(
     #[cfg(target_os = "windows")]
     {
         if let Some(registry) = &game.registry {
-            for key in registry.keys() {
-                if key.trim().is_empty() {
+            for (key, entry) in registry {
+                if key.trim().is_empty() || filter.is_tag_excluded(&entry.tags) {
                     continue;
                 }
-                for scanned in crate::registry::scan_registry(name, key, filter, ignored_registry).unwrap_or_default() {
+                for scanned in crate::registry::scan_registry(name, key, filter, ignored_registry, &entry.filter)
+                    .unwrap_or_default()
+                {
                     found_registry_keys.insert(scanned);
                 }
             }
         }
     }
 
-    ScanInfo {
-        game_name: name.to_string(),
-        found_files,
-        found_registry_keys,
-        registry_file: None,
+    let found_files = crate::wgs::name_files(found_files);
+
+    let anchors = if cfg!(target_os = "windows") && game.registry.is_some() {
+        None
+    } else {
+        anchors.extend(crate::scan_cache::file_anchors(&found_files));
+        anchors.extend(ignore_files.files());
+        Some(anchors)
+    };
+
+    (
+        ScanInfo {
+            game_name: name.to_string(),
+            cloud_only: game.cloud_only && found_files.is_empty() && found_registry_keys.is_empty(),
+            found_files,
+            found_registry_keys,
+            registry_file: None,
+        },
+        anchors,
+    )
+}
+
+/// Why a path from the manifest did or didn't turn up anything during a scan.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PathCheck {
+    /// Some placeholder doesn't apply for this root (e.g., the install folder wasn't found).
+    Unresolved,
+    /// The path is only for another OS.
+    OtherOs,
+    /// The path is excluded by the backup filter.
+    Ignored,
+    NoMatch,
+    /// How many files or folders the path matched.
+    Matched(usize),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PathDiagnosis {
+    /// The path as written in the manifest.
+    pub raw: String,
+    pub root: RootsConfig,
+    /// The path after filling in the placeholders, if they could be.
+    pub candidate: Option<StrictPath>,
+    pub check: PathCheck,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RootDiagnosis {
+    pub root: RootsConfig,
+    pub install_dir: Option<String>,
+}
+
+/// Details of how a single game would be scanned for backup, for troubleshooting.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GameDiagnosis {
+    pub roots: Vec<RootDiagnosis>,
+    pub paths: Vec<PathDiagnosis>,
+    pub scan_info: ScanInfo,
+}
+
+/// This follows the same steps as `scan_game_for_backup`, but records
+/// what happened with each path along the way.
+#[allow(clippy::too_many_arguments)]
+pub fn diagnose_game_for_backup(
+    game: &Game,
+    name: &str,
+    roots: &[RootsConfig],
+    manifest_dir: &StrictPath,
+    steam_id: &Option<u32>,
+    filter: &BackupFilter,
+    wine_prefix: &Option<StrictPath>,
+    ranking: &InstallDirRanking,
+    ignored_paths: &ToggledPaths,
+    ignored_registry: &ToggledRegistry,
+) -> GameDiagnosis {
+    let mut diagnosis = GameDiagnosis {
+        scan_info: scan_game_for_backup(
+            game,
+            name,
+            roots,
+            manifest_dir,
+            steam_id,
+            filter,
+            wine_prefix,
+            ranking,
+            ignored_paths,
+            ignored_registry,
+        ),
+        ..Default::default()
+    };
+
+    let mut roots_to_check: Vec<RootsConfig> = roots.to_vec();
+    if let Some(wp) = wine_prefix {
+        roots_to_check.push(RootsConfig {
+            path: wp.clone(),
+            store: Store::OtherWine,
+            require: Default::default(),
+        });
+    }
+
+    let mut seen = std::collections::HashSet::<StrictPath>::new();
+    for root in roots_to_check
+        .iter()
+        .filter(|x| !filter.is_root_excluded(name, x))
+        .flat_map(|x| x.glob())
+    {
+        if root.path.raw().trim().is_empty() || filter.is_root_excluded(name, &root) {
+            continue;
+        }
+        let install_dir = ranking.get(&root, name);
+        diagnosis.roots.push(RootDiagnosis {
+            root: root.clone(),
+            install_dir: install_dir.clone(),
+        });
+
+        let files = match &game.files {
+            Some(x) => x,
+            None => continue,
+        };
+        let maybe_proton = get_os() == Os::Linux && root.store == Store::Steam && steam_id.is_some();
+        let wine_folders = ranking.wine_folders(&root, steam_id);
+        let steam_user = user_folder_glob(&filter.steam_user, &root);
+
+        let mut raw_paths: Vec<_> = files.iter().filter(|(k, _)| !k.trim().is_empty()).collect();
+        raw_paths.sort_by_key(|(k, _)| k.to_string());
+        for (raw_path, path_info) in raw_paths {
+            let mut diagnose = |candidate: Option<StrictPath>, check: PathCheck| {
+                diagnosis.paths.push(PathDiagnosis {
+                    raw: raw_path.to_string(),
+                    root: root.clone(),
+                    candidate,
+                    check,
+                })
+            };
+
+            if filter.is_tag_excluded(&path_info.tags) {
+                diagnose(None, PathCheck::Ignored);
+                continue;
+            }
+            if filter.exclude_other_os_data {
+                if let Some(constraints) = &path_info.when {
+                    if should_exclude_as_other_os_data(constraints, get_os(), maybe_proton) {
+                        diagnose(None, PathCheck::OtherOs);
+                        continue;
+                    }
+                }
+            }
+
+            let mut candidates: Vec<_> = parse_paths(
+                raw_path,
+                &root,
+                &install_dir,
+                steam_id,
+                manifest_dir,
+                &wine_folders,
+                &steam_user,
+            )
+            .into_iter()
+            .collect();
+            candidates.sort();
+            for candidate in candidates {
+                if candidate.raw().contains(SKIP) {
+                    diagnose(None, PathCheck::Unresolved);
+                    continue;
+                }
+                if !seen.insert(candidate.clone()) {
+                    continue;
+                }
+                let check = if filter.is_path_ignored(&candidate) {
+                    PathCheck::Ignored
+                } else {
+                    match glob_any(&candidate).map(|x| x.filter_map(|r| r.ok()).count()) {
+                        Ok(0) | Err(_) => PathCheck::NoMatch,
+                        Ok(count) => PathCheck::Matched(count),
+                    }
+                };
+                diagnose(Some(candidate), check);
+            }
+        }
     }
+
+    diagnosis
 }
 
 pub fn scan_game_for_restoration(name: &str, layout: &BackupLayout) -> ScanInfo {
@@ -800,6 +1811,7 @@ pub fn scan_game_for_restoration(name: &str, layout: &BackupLayout) -> ScanInfo
                     found_registry_keys.insert(ScannedRegistry {
                         path: RegistryItem::new(format!("{}/{}", hive_name, key_name).replace('\\', "/")),
                         ignored: false,
+                        filter: Default::default(),
                     });
                 }
             }
@@ -811,14 +1823,30 @@ pub fn scan_game_for_restoration(name: &str, layout: &BackupLayout) -> ScanInfo
         found_files,
         found_registry_keys,
         registry_file,
+        cloud_only: false,
     }
 }
 
+/// When not merging, everything in the target is removed except for the lock file,
+/// so that the target stays locked while it's being recreated.
 pub fn prepare_backup_target(target: &StrictPath, merge: bool) -> Result<(), Error> {
     if !merge {
-        target
-            .remove()
-            .map_err(|_| Error::CannotPrepareBackupTarget { path: target.clone() })?;
+        if target.is_dir() {
+            let entries = std::fs::read_dir(target.interpret())
+                .map_err(|_| Error::CannotPrepareBackupTarget { path: target.clone() })?;
+            for entry in entries.filter_map(|x| x.ok()) {
+                if entry.file_name() == crate::lock::LOCK_FILE_NAME {
+                    continue;
+                }
+                StrictPath::from(entry.path())
+                    .remove()
+                    .map_err(|_| Error::CannotPrepareBackupTarget { path: target.clone() })?;
+            }
+        } else {
+            target
+                .remove()
+                .map_err(|_| Error::CannotPrepareBackupTarget { path: target.clone() })?;
+        }
     } else if target.exists() && !target.is_dir() {
         return Err(Error::CannotPrepareBackupTarget { path: target.clone() });
     }
@@ -829,7 +1857,21 @@ pub fn prepare_backup_target(target: &StrictPath, merge: bool) -> Result<(), Err
     Ok(())
 }
 
+/// Every file whose contents have been read for a comparison,
+/// so that tests can check which operations avoid reading them.
+#[cfg(test)]
+pub static CONTENT_READS: once_cell::sync::Lazy<std::sync::Mutex<Vec<StrictPath>>> =
+    once_cell::sync::Lazy::new(Default::default);
+
 pub fn are_files_identical(file1: &StrictPath, file2: &StrictPath) -> Result<bool, Box<dyn std::error::Error>> {
+    // Avoid reading the contents when we can already tell that they differ.
+    if file1.metadata()?.len() != file2.metadata()?.len() {
+        return Ok(false);
+    }
+
+    #[cfg(test)]
+    CONTENT_READS.lock().unwrap().extend([file1.clone(), file2.clone()]);
+
     let f1 = std::fs::File::open(file1.interpret())?;
     let mut f1r = std::io::BufReader::new(f1);
     let f2 = std::fs::File::open(file2.interpret())?;
@@ -851,21 +1893,153 @@ pub fn are_files_identical(file1: &StrictPath, file2: &StrictPath) -> Result<boo
     Ok(true)
 }
 
-pub fn back_up_game(
+/// Whether two files should be considered the same, according to `strategy`.
+/// If either one can't be read, they're considered different.
+pub fn are_files_equivalent(file1: &StrictPath, file2: &StrictPath, strategy: ComparisonStrategy) -> bool {
+    let (metadata1, metadata2) = match (file1.metadata(), file2.metadata()) {
+        (Ok(x), Ok(y)) => (x, y),
+        _ => return false,
+    };
+    if metadata1.len() != metadata2.len() {
+        return false;
+    }
+
+    match strategy {
+        ComparisonStrategy::SizeOnly => true,
+        ComparisonStrategy::SizeAndMtime => match (metadata1.modified(), metadata2.modified()) {
+            (Ok(x), Ok(y)) => x == y,
+            _ => false,
+        },
+        ComparisonStrategy::Hash => file1.same_content(file2),
+    }
+}
+
+/// Process some items using up to `threads` workers at the same time,
+/// returning the results in the same order as the items.
+pub fn run_with_bounded_threads<T, R, F>(items: &[T], threads: usize, work: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync + Send,
+{
+    if threads <= 1 || items.len() <= 1 {
+        return items.iter().map(&work).collect();
+    }
+    match copy_pool(threads) {
+        Some(pool) => pool.install(|| items.par_iter().map(&work).collect()),
+        None => items.iter().map(&work).collect(),
+    }
+}
+
+fn copy_pool(threads: usize) -> Option<std::sync::Arc<rayon::ThreadPool>> {
+    let mut pools = COPY_POOLS.lock().ok()?;
+    if let Some(pool) = pools.get(&threads) {
+        return Some(pool.clone());
+    }
+    let pool = std::sync::Arc::new(rayon::ThreadPoolBuilder::new().num_threads(threads).build().ok()?);
+    pools.insert(threads, pool.clone());
+    Some(pool)
+}
+
+fn find_denied_files(info: &ScanInfo) -> std::collections::HashSet<ScannedFile> {
+    info.found_files
+        .iter()
+        .filter(|x| !x.ignored)
+        .filter(|x| matches!(std::fs::File::open(x.path.interpret()), Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied))
+        .cloned()
+        .collect()
+}
+
+/// Whether to leave a game's backup alone because its latest backup has files
+/// from roots that aren't available right now. Backing it up anyway would treat
+/// those saves as deleted, and retention could then prune the backups that still have them.
+pub fn is_held_back_by_unavailable_roots(layout: &BackupLayout, name: &str, unavailable_roots: &[RootsConfig]) -> bool {
+    !unavailable_roots.is_empty() && layout.game_layout(name).has_files_within(unavailable_roots)
+}
+
+/// Write the game to each of `layouts`, checking the scan for denied files only once.
+/// A failure in one target doesn't stop the others. Each layout applies its own retention.
+#[allow(clippy::too_many_arguments)]
+pub fn back_up_game_to_targets(
+    info: &ScanInfo,
+    name: &str,
+    layouts: &[BackupLayout],
+    merge: bool,
+    now: &chrono::DateTime<chrono::Utc>,
+    copy_threads: usize,
+    retry: &Retry,
+    permission_denied: PermissionDeniedPolicy,
+) -> BackupInfo {
+    let denied = find_denied_files(info);
+    let mut combined: Option<BackupInfo> = None;
+    for layout in layouts {
+        let mut backup_info = back_up_game_except_denied(
+            info,
+            name,
+            layout,
+            merge,
+            now,
+            copy_threads,
+            retry,
+            permission_denied,
+            denied.clone(),
+        );
+        backup_info
+            .targets
+            .insert(layout.base.clone(), backup_info.successful());
+        match combined.as_mut() {
+            Some(x) => x.merge(backup_info),
+            None => combined = Some(backup_info),
+        }
+    }
+    combined.unwrap_or_default()
+}
+
+/// Files in `denied` are left out of the backup. Depending on the policy,
+/// the rest of the game is either backed up as usual or not at all.
+/// Either way, if every file is denied, then the existing backup is kept.
+#[allow(clippy::too_many_arguments)]
+fn back_up_game_except_denied(
     info: &ScanInfo,
     name: &str,
-    layout: &BackupLayout,
+    target: &BackupLayout,
     merge: bool,
     now: &chrono::DateTime<chrono::Utc>,
+    copy_threads: usize,
+    retry: &Retry,
+    permission_denied: PermissionDeniedPolicy,
+    denied: std::collections::HashSet<ScannedFile>,
 ) -> BackupInfo {
-    let mut layout = layout.game_layout(name);
+    let mut layout = target.game_layout_for_backup(name, info);
+
+    let mut allowed = info.clone();
+    allowed.found_files = info
+        .found_files
+        .iter()
+        .map(|x| {
+            let mut y = x.clone();
+            y.ignored = y.ignored || denied.contains(x);
+            y
+        })
+        .collect();
+
+    let abort = match permission_denied {
+        PermissionDeniedPolicy::Skip => false,
+        PermissionDeniedPolicy::Fail => !denied.is_empty(),
+    };
 
-    let able_to_prepare = info.found_anything_processable()
+    let able_to_prepare = !abort
+        && allowed.found_anything_processable()
         && (merge || (layout.path.unset_readonly().is_ok() && layout.path.remove().is_ok()))
         && std::fs::create_dir_all(layout.path.interpret()).is_ok();
 
     if able_to_prepare {
-        layout.back_up(info, now)
+        target.record_grouping();
+        let mut backup_info = layout.back_up(&allowed, now, copy_threads, retry);
+        backup_info.failed_files.extend(denied.iter().cloned());
+        backup_info.denied_files = denied;
+        backup_info.tolerate_denied = true;
+        backup_info
     } else {
         let mut backup_info = BackupInfo::default();
 
@@ -881,61 +2055,353 @@ pub fn back_up_game(
             }
             backup_info.failed_registry.insert(reg_path.path.clone());
         }
+        backup_info.denied_files = denied;
 
         backup_info
     }
 }
 
-pub fn restore_game(info: &ScanInfo, redirects: &[RedirectConfig]) -> BackupInfo {
+/// Files in the folders that a restore writes to, but that the backup doesn't have.
+/// Only `saves` are considered, which should be the files that currently match
+/// the game's save paths, so that unrelated files next to a save (e.g., in the home folder)
+/// are left alone. Only those folders themselves are checked, not their subfolders,
+/// so anywhere that the backup doesn't write to is left alone.
+pub fn find_extra_files(info: &ScanInfo, redirects: &[RedirectConfig], saves: &[StrictPath]) -> Vec<StrictPath> {
+    let normalize = |path: &StrictPath| {
+        let path = path.interpret();
+        if CASE_INSENSITIVE_OS {
+            path.to_lowercase()
+        } else {
+            path
+        }
+    };
+
+    let targets: Vec<_> = info
+        .found_files
+        .iter()
+        .filter_map(|x| x.original_path.as_ref())
+        .map(|x| game_file_restoration_target(x, redirects).0)
+        .collect();
+    let known: std::collections::HashSet<_> = targets.iter().map(normalize).collect();
+    let folders: std::collections::HashSet<_> = targets
+        .iter()
+        .filter_map(|x| x.as_std_path_buf().parent().map(|x| StrictPath::from(x.to_path_buf())))
+        .map(|x| normalize(&x))
+        .collect();
+
+    let mut extra: Vec<_> = saves
+        .iter()
+        .filter(|file| file.is_file() && !known.contains(&normalize(file)))
+        .filter(|file| {
+            file.as_std_path_buf()
+                .parent()
+                .map(|x| folders.contains(&normalize(&StrictPath::from(x.to_path_buf()))))
+                .unwrap_or(false)
+        })
+        .cloned()
+        .collect();
+    extra.sort();
+    extra.dedup();
+    extra
+}
+
+/// How to restore files, which is the same for every game in one restore.
+#[derive(Clone, Debug)]
+pub struct RestoreOptions {
+    /// Only determine what would happen to each file, without making any changes on disk,
+    /// and (on Unix) which files would end up with a different owner or mode.
+    pub dry_run: bool,
+    /// Keep the timestamps of the backed up copies instead of the time of restoration.
+    pub preserve_timestamps: bool,
+    /// Check each written file against its backed up copy.
+    /// Files that don't match are reported in `BackupInfo::mismatched_files`.
+    pub verify: bool,
+    /// With `RestoreMode::Mirror`, files from `find_extra_files` are removed
+    /// once everything else has been written.
+    pub mode: RestoreMode,
+    /// The files that currently match each game's save paths, by game name.
+    /// These are the only files that `RestoreMode::Mirror` may remove.
+    pub current_saves: std::collections::HashMap<String, Vec<StrictPath>>,
+    /// How many files to copy at the same time. Up to 1 copies them one by one.
+    pub copy_threads: usize,
+    pub retry: Retry,
+    /// Where to save files before they're overwritten or removed.
+    /// Any file that can't be saved is left alone.
+    pub undo: Option<UndoSnapshot>,
+    /// Don't write files that already match the backup.
+    pub skip_identical: bool,
+}
+
+impl Default for RestoreOptions {
+    fn default() -> Self {
+        Self {
+            dry_run: false,
+            preserve_timestamps: false,
+            verify: false,
+            mode: Default::default(),
+            current_saves: Default::default(),
+            copy_threads: 0,
+            retry: Default::default(),
+            undo: None,
+            skip_identical: true,
+        }
+    }
+}
+
+pub fn restore_game(info: &ScanInfo, redirects: &[RedirectConfig], options: &RestoreOptions) -> BackupInfo {
+    let RestoreOptions {
+        dry_run,
+        preserve_timestamps,
+        verify,
+        mode,
+        ref current_saves,
+        copy_threads,
+        ref retry,
+        ref undo,
+        skip_identical,
+    } = *options;
     let mut failed_files = std::collections::HashSet::new();
     let failed_registry = std::collections::HashSet::new();
-
-    'outer: for file in &info.found_files {
+    let mut restore_actions = std::collections::HashMap::new();
+    let mut failure_reasons = std::collections::HashMap::new();
+    #[allow(unused_mut)]
+    let mut ownership_mismatches = std::collections::HashMap::new();
+    let mut copies = vec![];
+    let mut warnings = vec![];
+    let mut game_undo = undo.as_ref().map(|x| x.game(&info.game_name));
+    #[cfg(unix)]
+    let user = crate::ownership::current_user();
+
+    for file in &info.found_files {
         let original_path = match &file.original_path {
             Some(x) => x,
             None => continue,
         };
+        if file.partial {
+            // Writing it would replace a good save with a truncated one.
+            failed_files.insert(file.clone());
+            continue;
+        }
         let (target, _) = game_file_restoration_target(original_path, redirects);
 
-        if target.exists() {
+        let renamed_from = match find_case_conflict(&target, CASE_INSENSITIVE_OS) {
+            CaseConflict::None => None,
+            CaseConflict::Rename(existing) => Some(existing),
+            CaseConflict::Ambiguous(_) => {
+                // Writing the file could replace any of them, so leave them alone.
+                failed_files.insert(file.clone());
+                failure_reasons.insert(
+                    file.clone(),
+                    FailureReason::from(&std::io::Error::from(std::io::ErrorKind::AlreadyExists)),
+                );
+                continue;
+            }
+        };
+
+        let action = if !target.exists() {
+            RestoreAction::Create
+        } else if !skip_identical {
+            RestoreAction::Overwrite
+        } else {
             match are_files_identical(&file.path, &target) {
-                Ok(true) => continue,
-                Ok(false) => (),
+                Ok(true) => RestoreAction::Skip,
+                Ok(false) => RestoreAction::Overwrite,
                 Err(_) => {
+                    // The contents couldn't be compared, so settle for a weaker check.
+                    warnings.push(Warning::WeakIdenticalCheck {
+                        game: info.game_name.clone(),
+                    });
+                    if are_files_equivalent(&file.path, &target, ComparisonStrategy::SizeAndMtime) {
+                        RestoreAction::Skip
+                    } else {
+                        RestoreAction::Overwrite
+                    }
+                }
+            }
+        };
+        restore_actions.insert(file.clone(), action);
+
+        #[cfg(unix)]
+        if dry_run && action != RestoreAction::Skip {
+            if let Some(mismatch) = crate::ownership::check(&file.path, &target, user) {
+                ownership_mismatches.insert(file.clone(), mismatch);
+            }
+        }
+
+        if let Some(existing) = renamed_from.filter(|_| !dry_run) {
+            if let Err(e) = std::fs::rename(existing.interpret(), target.interpret()) {
+                failed_files.insert(file.clone());
+                failure_reasons.insert(file.clone(), FailureReason::from(&e));
+                continue;
+            }
+            warnings.push(Warning::CaseNormalized {
+                game: info.game_name.clone(),
+            });
+        }
+
+        if dry_run || action == RestoreAction::Skip {
+            continue;
+        }
+        if let Some(game_undo) = &mut game_undo {
+            if action == RestoreAction::Overwrite {
+                if !game_undo.save_overwritten(&target) {
                     failed_files.insert(file.clone());
                     continue;
                 }
+            } else {
+                game_undo.note_created(&target);
             }
         }
+        copies.push((file, target));
+    }
 
-        if target.create_parent_dir().is_err() {
-            failed_files.insert(file.clone());
-            continue;
+    let mut removed_files = std::collections::HashSet::new();
+    let mut removals = vec![];
+    if mode == RestoreMode::Mirror {
+        let saves = current_saves
+            .get(&info.game_name)
+            .map(|x| x.as_slice())
+            .unwrap_or_default();
+        for file in find_extra_files(info, redirects, saves) {
+            if dry_run {
+                removed_files.insert(file);
+                continue;
+            }
+            if let Some(game_undo) = &mut game_undo {
+                if !game_undo.save_overwritten(&file) {
+                    continue;
+                }
+            }
+            removals.push(file);
+        }
+    }
+
+    if let Some(game_undo) = &game_undo {
+        if game_undo.save().is_err() {
+            // Without a record, we couldn't undo the changes, so don't make them.
+            failed_files.extend(copies.drain(..).map(|(file, _)| file.clone()));
+            removals.clear();
         }
-        for i in 0..99 {
-            if target.unset_readonly().is_ok() && std::fs::copy(&file.path.interpret(), &target.interpret()).is_ok() {
-                continue 'outer;
+    }
+
+    let copied_bytes = std::sync::atomic::AtomicU64::new(0);
+    let copied = run_with_bounded_threads(
+        &copies,
+        copy_threads,
+        |(file, target)| -> std::io::Result<crate::alternate_stream::Outcome> {
+            target.create_parent_dir()?;
+            // If this fails, then so will the copy, with a more useful error.
+            let _ = target.unset_readonly();
+            // A file that's busy, like one shared by several games in a collection,
+            // is retried along with other transient errors.
+            let written = retry.copy(&file.path, target)?;
+            copied_bytes.fetch_add(written, std::sync::atomic::Ordering::Relaxed);
+            let streams = crate::alternate_stream::restore(&file.path, target);
+            if preserve_timestamps {
+                // Not all file systems support this, so it's best effort.
+                let _ = target.copy_timestamps_from(&file.path);
+            }
+            Ok(streams)
+        },
+    );
+    for ((file, _), copied) in copies.iter().zip(copied) {
+        match copied {
+            Ok(crate::alternate_stream::Outcome::Unsupported) => {
+                warnings.push(Warning::AlternateStreamsUnsupported {
+                    game: info.game_name.clone(),
+                });
+            }
+            Ok(_) => {}
+            Err(error) => {
+                failed_files.insert((*file).clone());
+                failure_reasons.insert((*file).clone(), FailureReason::from(&error));
+            }
+        }
+    }
+
+    for file in removals {
+        match file.remove() {
+            Ok(_) => {
+                removed_files.insert(file);
+            }
+            Err(e) => {
+                let failed = ScannedFile {
+                    path: file,
+                    size: 0,
+                    original_path: None,
+                    ignored: false,
+                    system: false,
+                    partial: false,
+                    xbox_name: None,
+                };
+                if let Some(e) = e.downcast_ref::<std::io::Error>() {
+                    failure_reasons.insert(failed.clone(), FailureReason::from(e));
+                }
+                failed_files.insert(failed);
             }
-            // File might be busy, especially if multiple games share a file,
-            // like in a collection, so retry after a delay:
-            std::thread::sleep(std::time::Duration::from_millis(i * info.game_name.len() as u64));
         }
-        failed_files.insert(file.clone());
     }
 
     #[cfg(target_os = "windows")]
     {
         if let Some(registry_file) = &info.registry_file {
-            if let Some(hives) = crate::registry::Hives::load(registry_file) {
-                // TODO: Track failed keys.
-                let _ = hives.restore();
+            if !dry_run {
+                if let Some(hives) = crate::registry::Hives::load(registry_file) {
+                    // TODO: Track failed keys.
+                    let _ = hives.restore();
+                }
             }
         }
     }
 
-    BackupInfo {
+    let mut backup_info = BackupInfo {
         failed_files,
         failed_registry,
+        restore_actions,
+        removed_files,
+        failure_reasons,
+        ownership_mismatches,
+        warnings,
+        copied_bytes: copied_bytes.into_inner(),
+        ..Default::default()
+    };
+    if verify && !dry_run {
+        // This happens before returning, before anything else (like the game) can touch the files.
+        verify_restored_files(info, redirects, &mut backup_info, copy_threads);
+    }
+    backup_info
+}
+
+/// Check the files that were written by `restore_game` against their backed up copies.
+/// Any that don't match are added to `mismatched_files` and `failed_files`.
+pub fn verify_restored_files(
+    info: &ScanInfo,
+    redirects: &[RedirectConfig],
+    backup_info: &mut BackupInfo,
+    copy_threads: usize,
+) {
+    let written: Vec<_> = info
+        .found_files
+        .iter()
+        .filter(|file| {
+            matches!(
+                backup_info.restore_actions.get(*file),
+                Some(RestoreAction::Create | RestoreAction::Overwrite)
+            ) && !backup_info.failed_files.contains(*file)
+        })
+        .filter_map(|file| {
+            let (target, _) = game_file_restoration_target(file.original_path.as_ref()?, redirects);
+            Some((file, target))
+        })
+        .collect();
+    let verified = run_with_bounded_threads(&written, copy_threads, |(file, target)| {
+        matches!(are_files_identical(&file.path, target), Ok(true))
+    });
+    for ((file, _), verified) in written.into_iter().zip(verified) {
+        if !verified {
+            backup_info.mismatched_files.insert(file.clone());
+            backup_info.failed_files.insert(file.clone());
+        }
     }
 }
 
@@ -993,6 +2459,18 @@ impl DuplicateDetector {
         self.file(file).len() > 1
     }
 
+    /// Total size of the files that would be backed up for these games,
+    /// counting a file only once even if several of the games share it.
+    pub fn deduplicated_bytes<'a>(&self, games: impl IntoIterator<Item = &'a ScanInfo>) -> u64 {
+        let mut seen = std::collections::HashSet::new();
+        games
+            .into_iter()
+            .flat_map(|x| x.found_files.iter())
+            .filter(|x| !x.ignored && seen.insert(self.pick_path(x)))
+            .map(|x| x.size)
+            .sum()
+    }
+
     pub fn registry(&self, path: &RegistryItem) -> std::collections::HashSet<String> {
         match self.registry.get(path) {
             Some(games) => games.clone(),
@@ -1071,11 +2549,36 @@ pub fn fuzzy_match(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{Config, Retention};
-    use crate::manifest::Manifest;
+    use crate::config::{BackupGrouping, Config, Retention, SteamUser};
+    use crate::manifest::{Manifest, Tag};
+    use crate::path::TempPath;
     use maplit::*;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn resolves_app_dirs_from_xdg_env_vars() {
+        let var = |name: &str| match name {
+            "XDG_CONFIG_HOME" => Some(std::ffi::OsString::from("/tmp/xdg-config")),
+            "XDG_STATE_HOME" => Some(std::ffi::OsString::from("relative/is/ignored")),
+            _ => None,
+        };
+        let home = dirs::home_dir().unwrap();
+
+        assert_eq!(
+            Some(std::path::PathBuf::from("/tmp/xdg-config")),
+            xdg_base_dir(var, "XDG_CONFIG_HOME", ".config")
+        );
+        assert_eq!(
+            Some(home.join(".local/share")),
+            xdg_base_dir(var, "XDG_DATA_HOME", ".local/share")
+        );
+        assert_eq!(
+            Some(home.join(".local/state")),
+            xdg_base_dir(var, "XDG_STATE_HOME", ".local/state")
+        );
+    }
+
     #[test]
     fn fuzzy_matching() {
         let matcher = make_fuzzy_matcher();
@@ -1259,16 +2762,244 @@ mod tests {
     }
 
     #[test]
-    fn can_scan_game_for_backup_with_file_matches() {
-        assert_eq!(
-            ScanInfo {
-                game_name: s("game1"),
-                found_files: hashset! {
-                    ScannedFile::new(format!("{}/tests/root1/game1/subdir/file2.txt", repo()), 2),
-                    ScannedFile::new(format!("{}/tests/root2/game1/file1.txt", repo()), 1),
-                },
-                found_registry_keys: hashset! {},
+    fn can_restrict_backup_scan_to_one_steam_user() {
+        let root = TempPath::new("ludusavi-test-steam-users");
+        for user in ["111", "222"] {
+            std::fs::create_dir_all(root.joined(&format!("userdata/{}", user)).interpret()).unwrap();
+            std::fs::write(root.joined(&format!("userdata/{}/save.dat", user)).interpret(), "a").unwrap();
+        }
+        std::fs::create_dir_all(root.joined("config").interpret()).unwrap();
+        std::fs::write(
+            root.joined("config/loginusers.vdf").interpret(),
+            "\"users\"\n{\n  \"76561197960265950\"\n  {\n    \"MostRecent\" \"1\"\n  }\n}\n",
+        )
+        .unwrap();
+
+        let manifest = Manifest::load_from_string(
+            r#"
+            game:
+              files:
+                <root>/userdata/<storeUserId>/save.dat: {}
+            "#,
+        )
+        .unwrap();
+        let roots = vec![RootsConfig {
+            path: root.clone(),
+            store: Store::Steam,
+            require: Default::default(),
+        }];
+        let scan = |steam_user| {
+            scan_game_for_backup(
+                &manifest.0["game"],
+                "game",
+                &roots,
+                &StrictPath::new(repo()),
+                &None,
+                &BackupFilter {
+                    steam_user,
+                    ..Default::default()
+                },
+                &None,
+                &InstallDirRanking::default(),
+                &ToggledPaths::default(),
+                &ToggledRegistry::default(),
+            )
+            .found_files
+        };
+        let user = |id: &str| ScannedFile::new(root.joined(&format!("userdata/{}/save.dat", id)).render(), 1);
+
+        let all = scan(SteamUser::All);
+        let one = scan(SteamUser::Id(111));
+        let unknown = scan(SteamUser::Id(333));
+        let current = scan(SteamUser::Current);
+        std::fs::remove_file(root.joined("config/loginusers.vdf").interpret()).unwrap();
+        let undetected = scan(SteamUser::Current);
+
+        assert_eq!(hashset! { user("111"), user("222") }, all);
+        assert_eq!(hashset! { user("111") }, one);
+        assert_eq!(hashset! {}, unknown);
+        assert_eq!(hashset! { user("222") }, current);
+        assert_eq!(hashset! {}, undetected);
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn can_include_or_exclude_hidden_files_in_backup_scan() {
+        let folder = TempPath::new("ludusavi-test-hidden-files");
+        std::fs::create_dir_all(folder.joined(".config").interpret()).unwrap();
+        std::fs::write(folder.joined("save.dat").interpret(), "a").unwrap();
+        std::fs::write(folder.joined(".hidden.dat").interpret(), "bb").unwrap();
+        std::fs::write(folder.joined(".config/settings.ini").interpret(), "ccc").unwrap();
+
+        let manifest = Manifest::load_from_string(
+            r#"
+            game:
+              files:
+                <root>: {}
+            "#,
+        )
+        .unwrap();
+        let roots = vec![RootsConfig {
+            path: folder.clone(),
+            store: Store::Other,
+            require: Default::default(),
+        }];
+        let scan = |exclude_hidden_files| {
+            scan_game_for_backup(
+                &manifest.0["game"],
+                "game",
+                &roots,
+                &StrictPath::new(repo()),
+                &None,
+                &BackupFilter {
+                    exclude_hidden_files,
+                    ..Default::default()
+                },
+                &None,
+                &InstallDirRanking::default(),
+                &ToggledPaths::default(),
+                &ToggledRegistry::default(),
+            )
+            .found_files
+        };
+        let included = scan(false);
+        let excluded = scan(true);
+
+        assert_eq!(
+            hashset! {
+                ScannedFile::new(folder.joined("save.dat").render(), 1),
+                ScannedFile::new(folder.joined(".hidden.dat").render(), 2),
+                ScannedFile::new(folder.joined(".config/settings.ini").render(), 3),
+            },
+            included,
+        );
+        // Files in hidden folders are still included, since that's where many saves are.
+        assert_eq!(
+            hashset! {
+                ScannedFile::new(folder.joined("save.dat").render(), 1),
+                ScannedFile::new(folder.joined(".config/settings.ini").render(), 3),
+            },
+            excluded,
+        );
+    }
+
+    #[test]
+    fn can_send_heartbeats_while_scanning_game_with_many_files() {
+        let folder = TempPath::new("ludusavi-test-heartbeat");
+        std::fs::create_dir_all(folder.joined("saves").interpret()).unwrap();
+        for i in 0..50 {
+            std::fs::write(folder.joined(&format!("saves/{}.dat", i)).interpret(), "a").unwrap();
+        }
+
+        let manifest = Manifest::load_from_string(
+            r#"
+            game:
+              files:
+                <root>/saves: {}
+            "#,
+        )
+        .unwrap();
+        let roots = vec![RootsConfig {
+            path: folder.clone(),
+            store: Store::Other,
+            require: Default::default(),
+        }];
+        let scan = |interval| {
+            let mut heartbeats = vec![];
+            let found = scan_game_for_backup_with_anchors(
+                &manifest.0["game"],
+                "game",
+                &roots,
+                &StrictPath::new(repo()),
+                &None,
+                &BackupFilter::default(),
+                &None,
+                &InstallDirRanking::default(),
+                &ToggledPaths::default(),
+                &ToggledRegistry::default(),
+                &mut ScanHeartbeat::new(interval, |found| heartbeats.push(found)),
+            )
+            .0
+            .found_files
+            .len();
+            (found, heartbeats)
+        };
+        let unlimited = scan(std::time::Duration::ZERO);
+        let limited = scan(std::time::Duration::from_secs(3600));
+
+        assert_eq!((50, (1..=50).collect::<Vec<_>>()), unlimited);
+        assert_eq!((50, vec![]), limited);
+    }
+
+    #[test]
+    fn can_exclude_roots_from_backup_scan_of_one_game() {
+        let base = TempPath::new("ludusavi-test-excluded-roots");
+        for root in ["steam", "gog"] {
+            std::fs::create_dir_all(base.joined(&format!("{}/game", root)).interpret()).unwrap();
+            std::fs::write(base.joined(&format!("{}/game/save.dat", root)).interpret(), "a").unwrap();
+        }
+
+        let manifest = Manifest::load_from_string(
+            r#"
+            game:
+              files:
+                <root>/game/save.dat: {}
+            "#,
+        )
+        .unwrap();
+        let roots = vec![
+            RootsConfig {
+                path: base.joined("steam"),
+                store: Store::Steam,
+                require: Default::default(),
+            },
+            RootsConfig {
+                path: base.joined("gog"),
+                store: Store::Gog,
+                require: Default::default(),
+            },
+        ];
+        let scan = |excluded: Vec<StrictPath>| {
+            scan_game_for_backup(
+                &manifest.0["game"],
+                "game",
+                &roots,
+                &StrictPath::new(repo()),
+                &None,
+                &BackupFilter {
+                    excluded_roots: btreemap! { s("game") => excluded },
+                    ..Default::default()
+                },
+                &None,
+                &InstallDirRanking::default(),
+                &ToggledPaths::default(),
+                &ToggledRegistry::default(),
+            )
+            .found_files
+        };
+        let save = |root: &str| ScannedFile::new(base.joined(&format!("{}/game/save.dat", root)).render(), 1);
+
+        let both = scan(vec![]);
+        let one = scan(vec![base.joined("gog")]);
+        let none = scan(vec![base.joined("steam"), base.joined("gog")]);
+
+        assert_eq!(hashset! { save("steam"), save("gog") }, both);
+        assert_eq!(hashset! { save("steam") }, one);
+        assert_eq!(hashset! {}, none);
+    }
+
+    #[test]
+    fn can_scan_game_for_backup_with_file_matches() {
+        assert_eq!(
+            ScanInfo {
+                game_name: s("game1"),
+                found_files: hashset! {
+                    ScannedFile::new(format!("{}/tests/root1/game1/subdir/file2.txt", repo()), 2),
+                    ScannedFile::new(format!("{}/tests/root2/game1/file1.txt", repo()), 1),
+                },
+                found_registry_keys: hashset! {},
                 registry_file: None,
+                cloud_only: false,
             },
             scan_game_for_backup(
                 &manifest().0["game1"],
@@ -1292,6 +3023,7 @@ mod tests {
                 },
                 found_registry_keys: hashset! {},
                 registry_file: None,
+                cloud_only: false,
             },
             scan_game_for_backup(
                 &manifest().0["game 2"],
@@ -1308,11 +3040,93 @@ mod tests {
         );
     }
 
+    #[test]
+    fn can_scan_game_for_backup_with_excluded_tags() {
+        let manifest = Manifest::load_from_string(
+            r#"
+            game1:
+              files:
+                <base>/file1.txt:
+                  tags: [save]
+                <base>/subdir:
+                  tags: [config]
+            "#,
+        )
+        .unwrap();
+        let scan = |excluded_tags: Vec<Tag>| {
+            scan_game_for_backup(
+                &manifest.0["game1"],
+                "game1",
+                &config().roots,
+                &StrictPath::new(repo()),
+                &None,
+                &BackupFilter {
+                    excluded_tags,
+                    ..Default::default()
+                },
+                &None,
+                &InstallDirRanking::scan(&config().roots, &manifest, &["game1".to_string()]),
+                &ToggledPaths::default(),
+                &ToggledRegistry::default(),
+            )
+        };
+
+        assert_eq!(
+            hashset! {
+                ScannedFile::new(format!("{}/tests/root2/game1/file1.txt", repo()), 1),
+            },
+            scan(vec![Tag::Config]).found_files
+        );
+
+        // With every category excluded, the game is skipped like any other with nothing found.
+        assert!(!scan(vec![Tag::Save, Tag::Config]).found_anything());
+    }
+
+    #[test]
+    fn can_diagnose_game_for_backup() {
+        let diagnosis = diagnose_game_for_backup(
+            &manifest().0["game1"],
+            "game1",
+            &config().roots,
+            &StrictPath::new(repo()),
+            &None,
+            &BackupFilter::default(),
+            &None,
+            &InstallDirRanking::scan(&config().roots, &manifest(), &["game1".to_string()]),
+            &ToggledPaths::default(),
+            &ToggledRegistry::default(),
+        );
+
+        assert_eq!(
+            vec![Some(s("game1")), Some(s("game1"))],
+            diagnosis
+                .roots
+                .iter()
+                .map(|x| x.install_dir.clone())
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            vec![
+                (format!("{}/tests/root1/game1/file1.txt", repo()), PathCheck::NoMatch),
+                (format!("{}/tests/root1/game1/subdir", repo()), PathCheck::Matched(1)),
+                (format!("{}/tests/root2/game1/file1.txt", repo()), PathCheck::Matched(1)),
+                (format!("{}/tests/root2/game1/subdir", repo()), PathCheck::NoMatch),
+            ],
+            diagnosis
+                .paths
+                .iter()
+                .map(|x| (x.candidate.as_ref().unwrap().render(), x.check.clone()))
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(2, diagnosis.scan_info.found_files.len());
+    }
+
     #[test]
     fn can_scan_game_for_backup_deduplicating_symlinks() {
         let roots = &[RootsConfig {
             path: StrictPath::new(format!("{}/tests/root3", repo())),
             store: Store::Other,
+            require: Default::default(),
         }];
         assert_eq!(
             ScanInfo {
@@ -1322,6 +3136,7 @@ mod tests {
                 },
                 found_registry_keys: hashset! {},
                 registry_file: None,
+                cloud_only: false,
             },
             scan_game_for_backup(
                 &manifest().0["game5"],
@@ -1343,6 +3158,7 @@ mod tests {
         let roots = &[RootsConfig {
             path: StrictPath::new(format!("{}/tests/root3", repo())),
             store: Store::Other,
+            require: Default::default(),
         }];
         assert_eq!(
             ScanInfo {
@@ -1352,6 +3168,7 @@ mod tests {
                 },
                 found_registry_keys: hashset! {},
                 registry_file: None,
+                cloud_only: false,
             },
             scan_game_for_backup(
                 &manifest().0["game 2"],
@@ -1374,6 +3191,7 @@ mod tests {
         let roots = &[RootsConfig {
             path: StrictPath::new(format!("{}/tests/home", repo())),
             store: Store::OtherHome,
+            require: Default::default(),
         }];
         assert_eq!(
             ScanInfo {
@@ -1386,6 +3204,7 @@ mod tests {
                 },
                 found_registry_keys: hashset! {},
                 registry_file: None,
+                cloud_only: false,
             },
             scan_game_for_backup(
                 &manifest().0["game4"],
@@ -1408,6 +3227,7 @@ mod tests {
         let roots = &[RootsConfig {
             path: StrictPath::new(format!("{}/tests/home", repo())),
             store: Store::OtherHome,
+            require: Default::default(),
         }];
         assert_eq!(
             ScanInfo {
@@ -1419,6 +3239,7 @@ mod tests {
                 },
                 found_registry_keys: hashset! {},
                 registry_file: None,
+                cloud_only: false,
             },
             scan_game_for_backup(
                 &manifest().0["game4"],
@@ -1446,6 +3267,7 @@ mod tests {
                 },
                 found_registry_keys: hashset! {},
                 registry_file: None,
+                cloud_only: false,
             },
             scan_game_for_backup(
                 &manifest().0["game4"],
@@ -1462,6 +3284,64 @@ mod tests {
         );
     }
 
+    #[test]
+    fn can_scan_game_for_backup_with_file_matches_in_redirected_wine_folder() {
+        assert_eq!(
+            ScanInfo {
+                game_name: s("game4"),
+                found_files: hashset! {
+                    ScannedFile::new(
+                        format!(
+                            "{}/tests/wine-prefix-redirect/drive_c/users/anyone/Custom Docs/winDocuments.txt",
+                            repo()
+                        ),
+                        0
+                    ),
+                    ScannedFile::new(format!("{}/tests/wine-prefix-redirect/user.reg", repo()), 229),
+                },
+                found_registry_keys: hashset! {},
+                registry_file: None,
+                cloud_only: false,
+            },
+            scan_game_for_backup(
+                &manifest().0["game4"],
+                "game4",
+                &config().roots,
+                &StrictPath::new(repo()),
+                &None,
+                &BackupFilter::default(),
+                &Some(StrictPath::new(format!("{}/tests/wine-prefix-redirect", repo()))),
+                &InstallDirRanking::scan(&config().roots, &manifest(), &["game4".to_string()]),
+                &ToggledPaths::default(),
+                &ToggledRegistry::default(),
+            ),
+        );
+    }
+
+    #[test]
+    fn reads_wine_root_folders_once_per_operation() {
+        let prefix = TempPath::new("ludusavi-test-wine-folders-once");
+        std::fs::create_dir_all(prefix.interpret()).unwrap();
+        std::fs::copy(
+            format!("{}/tests/wine-prefix-redirect/user.reg", repo()),
+            prefix.joined("user.reg").interpret(),
+        )
+        .unwrap();
+        let root = RootsConfig {
+            path: prefix.clone(),
+            store: Store::OtherWine,
+            require: Default::default(),
+        };
+
+        let ranking = InstallDirRanking::scan(std::slice::from_ref(&root), &manifest(), &["game4".to_string()]);
+        let loaded = ShellFolders::for_root(&root, &None);
+        std::fs::remove_file(prefix.joined("user.reg").interpret()).unwrap();
+        let cached = ranking.wine_folders(&root, &None);
+
+        assert!(loaded.is_some());
+        assert_eq!(loaded, cached);
+    }
+
     #[test]
     fn can_scan_game_for_backup_with_file_matches_and_ignores() {
         let cases = [
@@ -1508,6 +3388,7 @@ mod tests {
                     found_files: found,
                     found_registry_keys: hashset! {},
                     registry_file: None,
+                    cloud_only: false,
                 },
                 scan_game_for_backup(
                     &manifest().0["game1"],
@@ -1536,6 +3417,7 @@ mod tests {
                     ScannedRegistry::new("HKEY_CURRENT_USER/Software/Ludusavi/game3")
                 },
                 registry_file: None,
+                cloud_only: false,
             },
             scan_game_for_backup(
                 &manifest().0["game3"],
@@ -1565,6 +3447,7 @@ mod tests {
                     ScannedRegistry::new("HKEY_CURRENT_USER/Software/Ludusavi/other"),
                 },
                 registry_file: None,
+                cloud_only: false,
             },
             scan_game_for_backup(
                 &manifest().0["game3-outer"],
@@ -1631,6 +3514,7 @@ mod tests {
                     found_files: hashset! {},
                     found_registry_keys: found,
                     registry_file: None,
+                    cloud_only: false,
                 },
                 scan_game_for_backup(
                     &manifest().0["game3-outer"],
@@ -1671,12 +3555,18 @@ mod tests {
                         size: 1,
                         original_path: Some(StrictPath::new(s(if cfg!(target_os = "windows") { "X:\\file1.txt" } else { "X:/file1.txt" }))),
                         ignored: false,
+                        system: false,
+                        partial: false,
+                        xbox_name: None,
                     },
                     ScannedFile {
                         path: make_path("file2.txt"),
                         size: 2,
                         original_path: Some(StrictPath::new(s(if cfg!(target_os = "windows") { "X:\\file2.txt" } else { "X:/file2.txt" }))),
                         ignored: false,
+                        system: false,
+                        partial: false,
+                        xbox_name: None,
                     },
                 },
                 ..Default::default()
@@ -1731,6 +3621,801 @@ mod tests {
         }
     }
 
+    #[test]
+    fn can_restore_game_in_dry_run_mode_without_writing() {
+        let layout = BackupLayout::new(
+            StrictPath::new(format!("{}/tests/backup", repo())),
+            Retention::default(),
+        );
+        let scan_info = scan_game_for_restoration("game1", &layout);
+        let target = StrictPath::new(format!("{}/tests/restore-dry-run", repo()));
+        let redirects = vec![RedirectConfig {
+            source: StrictPath::new(s("X:")),
+            target: target.clone(),
+        }];
+
+        let backup_info = restore_game(
+            &scan_info,
+            &redirects,
+            &RestoreOptions {
+                dry_run: true,
+                ..Default::default()
+            },
+        );
+
+        assert!(!target.exists());
+        assert!(backup_info.successful());
+        assert_eq!(2, backup_info.restore_actions.len());
+        assert!(backup_info
+            .restore_actions
+            .values()
+            .all(|x| *x == RestoreAction::Create));
+    }
+
+    fn back_up_with_denied(name: &str, policy: PermissionDeniedPolicy, deny_all: bool) -> BackupInfo {
+        let base = TempPath::new(name);
+        std::fs::create_dir_all(base.joined("game").interpret()).unwrap();
+        std::fs::write(base.joined("game/allowed.txt").interpret(), "a").unwrap();
+        std::fs::write(base.joined("game/denied.txt").interpret(), "d").unwrap();
+
+        let allowed = ScannedFile::new(base.joined("game/allowed.txt").render(), 1);
+        let denied = ScannedFile::new(base.joined("game/denied.txt").render(), 1);
+        let scan_info = ScanInfo {
+            game_name: s("game"),
+            found_files: hashset! { allowed.clone(), denied.clone() },
+            ..Default::default()
+        };
+
+        back_up_game_except_denied(
+            &scan_info,
+            "game",
+            &BackupLayout::new(base.joined("backup"), Retention::default()),
+            true,
+            &chrono::Utc::now(),
+            1,
+            &Retry::default(),
+            policy,
+            if deny_all {
+                hashset! { allowed, denied }
+            } else {
+                hashset! { denied }
+            },
+        )
+    }
+
+    #[test]
+    fn can_back_up_game_to_multiple_targets() {
+        let base = TempPath::new("ludusavi-test-multiple-targets");
+        std::fs::create_dir_all(base.joined("game").interpret()).unwrap();
+        std::fs::write(base.joined("game/file1.txt").interpret(), "a").unwrap();
+        std::fs::write(base.joined("game/file2.txt").interpret(), "bb").unwrap();
+
+        let scan_info = ScanInfo {
+            game_name: s("game"),
+            found_files: hashset! {
+                ScannedFile::new(base.joined("game/file1.txt").render(), 1),
+                ScannedFile::new(base.joined("game/file2.txt").render(), 2),
+            },
+            ..Default::default()
+        };
+        let layouts = vec![
+            BackupLayout::new(base.joined("first"), Retention::default()),
+            BackupLayout::new(base.joined("second"), Retention::default()),
+        ];
+
+        let backup_info = back_up_game_to_targets(
+            &scan_info,
+            "game",
+            &layouts,
+            true,
+            &chrono::Utc::now(),
+            1,
+            &Retry::default(),
+            PermissionDeniedPolicy::Skip,
+        );
+        let restorable: Vec<_> = layouts
+            .iter()
+            .map(|x| x.game_layout("game").restorable_files().len())
+            .collect();
+
+        assert!(backup_info.successful());
+        assert_eq!(vec![2, 2], restorable);
+        assert_eq!(
+            btreemap! { base.joined("first") => true, base.joined("second") => true },
+            backup_info.targets
+        );
+    }
+
+    #[test]
+    fn can_back_up_game_to_multiple_targets_with_different_retention() {
+        let base = TempPath::new("ludusavi-test-multiple-targets-retention");
+        std::fs::create_dir_all(base.joined("game").interpret()).unwrap();
+
+        let scan_info = ScanInfo {
+            game_name: s("game"),
+            found_files: hashset! {
+                ScannedFile::new(base.joined("game/file1.txt").render(), 1),
+            },
+            ..Default::default()
+        };
+        let layouts = vec![
+            BackupLayout::new(
+                base.joined("first"),
+                Retention {
+                    full: 1,
+                    differential: 0,
+                },
+            ),
+            BackupLayout::new(
+                base.joined("second"),
+                Retention {
+                    full: 2,
+                    differential: 0,
+                },
+            ),
+        ];
+
+        let now = chrono::Utc::now();
+        for (content, when) in [("a", now), ("b", now + chrono::Duration::seconds(1))] {
+            std::fs::write(base.joined("game/file1.txt").interpret(), content).unwrap();
+            let backup_info = back_up_game_to_targets(
+                &scan_info,
+                "game",
+                &layouts,
+                true,
+                &when,
+                1,
+                &Retry::default(),
+                PermissionDeniedPolicy::Skip,
+            );
+            assert!(backup_info.successful());
+        }
+        let backups: Vec<_> = layouts
+            .iter()
+            .map(|x| BackupLayout::new(x.base.clone(), Retention::default()))
+            .map(|x| x.game_layout("game").backup_provenance().len())
+            .collect();
+
+        assert_eq!(vec![1, 2], backups);
+    }
+
+    #[test]
+    fn holds_back_backup_while_root_of_prior_backup_is_unavailable() {
+        let base = TempPath::new("ludusavi-test-unavailable-root");
+        std::fs::create_dir_all(base.joined("drive/game").interpret()).unwrap();
+        std::fs::write(base.joined("drive/.mounted").interpret(), "").unwrap();
+        std::fs::write(base.joined("drive/game/file1.txt").interpret(), "a").unwrap();
+        let roots = [RootsConfig {
+            path: base.joined("drive"),
+            store: Store::Other,
+            require: crate::config::RootRequirement {
+                marker: Some(s(".mounted")),
+                mount_point: false,
+            },
+        }];
+        let layout = BackupLayout::new(
+            base.joined("backup"),
+            Retention {
+                full: 1,
+                differential: 0,
+            },
+        );
+
+        // Like a backup run, but the scan only finds what's there right now.
+        let run = |when: chrono::DateTime<chrono::Utc>| {
+            let unavailable: Vec<_> = roots.iter().filter(|x| !x.is_available()).cloned().collect();
+            if is_held_back_by_unavailable_roots(&layout, "game", &unavailable) {
+                return false;
+            }
+            let file = base.joined("drive/game/file1.txt");
+            let scan_info = ScanInfo {
+                game_name: s("game"),
+                found_files: if file.is_file() {
+                    hashset! { ScannedFile::new(file.render(), 1) }
+                } else {
+                    hashset! {}
+                },
+                ..Default::default()
+            };
+            back_up_game_to_targets(
+                &scan_info,
+                "game",
+                std::slice::from_ref(&layout),
+                false,
+                &when,
+                1,
+                &Retry::default(),
+                PermissionDeniedPolicy::Skip,
+            );
+            true
+        };
+        let backed_up_files = || {
+            let layout = BackupLayout::new(base.joined("backup"), Retention::default());
+            let game = layout.game_layout("game");
+            (
+                game.backup_provenance().len(),
+                game.restorable_files()
+                    .into_iter()
+                    .filter_map(|x| x.original_path)
+                    .collect::<Vec<_>>(),
+            )
+        };
+
+        let now = chrono::Utc::now();
+        assert!(run(now));
+        let before = backed_up_files();
+        assert_eq!((1, vec![base.joined("drive/game/file1.txt")]), before);
+
+        // The drive is unmounted, leaving an empty folder behind.
+        base.joined("drive/.mounted").remove().unwrap();
+        base.joined("drive/game").remove().unwrap();
+        let held_back = !run(now + chrono::Duration::seconds(1));
+        let during = backed_up_files();
+
+        // An unrelated root being unavailable doesn't hold the game back.
+        let unrelated = vec![RootsConfig {
+            path: base.joined("other"),
+            store: Store::Other,
+            require: Default::default(),
+        }];
+        let held_back_by_unrelated = is_held_back_by_unavailable_roots(&layout, "game", &unrelated);
+
+        assert!(held_back);
+        assert_eq!(before, during);
+        assert!(!held_back_by_unrelated);
+    }
+
+    #[test]
+    fn can_back_up_and_find_game_grouped_by_store() {
+        let base = TempPath::new("ludusavi-test-grouped-by-store");
+        std::fs::create_dir_all(base.joined("steam/game").interpret()).unwrap();
+        std::fs::write(base.joined("steam/game/file1.txt").interpret(), "a").unwrap();
+        let roots = vec![RootsConfig {
+            path: base.joined("steam"),
+            store: Store::Steam,
+            require: Default::default(),
+        }];
+
+        let scan_info = ScanInfo {
+            game_name: s("game"),
+            found_files: hashset! {
+                ScannedFile::new(base.joined("steam/game/file1.txt").render(), 1),
+            },
+            ..Default::default()
+        };
+        let layout =
+            BackupLayout::new(base.joined("backup"), Retention::default()).grouped(BackupGrouping::ByStore, &roots);
+        let backup_info = back_up_game_to_targets(
+            &scan_info,
+            "game",
+            &[layout],
+            true,
+            &chrono::Utc::now(),
+            1,
+            &Retry::default(),
+            PermissionDeniedPolicy::Skip,
+        );
+
+        let reloaded = BackupLayout::new(base.joined("backup"), Retention::default());
+        let grouped = base.joined("backup/Steam/game").is_dir();
+        let folder = reloaded.existing_game_folder("game").map(|x| x.interpret());
+        let restorable = reloaded.game_layout("game").restorable_files().len();
+        let prunable = reloaded.find_prunable_folders();
+
+        assert!(backup_info.successful());
+        assert!(grouped);
+        assert_eq!(Some(base.joined("backup/Steam/game").interpret()), folder);
+        assert_eq!(1, restorable);
+        assert_eq!(Vec::<crate::layout::PrunableFolder>::new(), prunable);
+    }
+
+    #[test]
+    fn picks_the_same_primary_store_regardless_of_root_order() {
+        // Roots may be globs, so they have to exist to be matched.
+        let base = TempPath::new("ludusavi-test-primary-store");
+        std::fs::create_dir_all(base.joined("steam").interpret()).unwrap();
+        std::fs::create_dir_all(base.joined("gog").interpret()).unwrap();
+
+        let scan_info = ScanInfo {
+            game_name: s("game"),
+            found_files: hashset! {
+                ScannedFile::new(base.joined("steam/game/save.dat").render(), 1),
+                ScannedFile::new(base.joined("gog/game/save.dat").render(), 1),
+            },
+            ..Default::default()
+        };
+        let steam = RootsConfig {
+            path: base.joined("steam"),
+            store: Store::Steam,
+            require: Default::default(),
+        };
+        let gog = RootsConfig {
+            path: base.joined("gog"),
+            store: Store::Gog,
+            require: Default::default(),
+        };
+
+        let steam_first = scan_info.primary_store(&[steam.clone(), gog.clone()]);
+        let gog_first = scan_info.primary_store(&[gog, steam]);
+        let none = scan_info.primary_store(&[]);
+
+        assert_eq!(Store::Gog, steam_first);
+        assert_eq!(Store::Gog, gog_first);
+        assert_eq!(Store::Other, none);
+    }
+
+    #[test]
+    fn can_back_up_game_while_skipping_denied_files() {
+        let backup_info = back_up_with_denied("ludusavi-test-denied-skip", PermissionDeniedPolicy::Skip, false);
+        assert_eq!(1, backup_info.failed_files.len());
+        assert_eq!(backup_info.failed_files, backup_info.denied_files);
+        assert!(backup_info.successful());
+    }
+
+    #[test]
+    fn fails_to_back_up_game_when_all_files_are_denied() {
+        let backup_info = back_up_with_denied("ludusavi-test-denied-all", PermissionDeniedPolicy::Skip, true);
+        assert_eq!(2, backup_info.failed_files.len());
+        assert!(!backup_info.successful());
+    }
+
+    #[test]
+    fn fails_to_back_up_game_with_denied_files_when_policy_is_fail() {
+        let backup_info = back_up_with_denied("ludusavi-test-denied-fail", PermissionDeniedPolicy::Fail, false);
+        assert_eq!(2, backup_info.failed_files.len());
+        assert_eq!(1, backup_info.denied_files.len());
+        assert!(!backup_info.successful());
+    }
+
+    #[test]
+    fn can_restore_game_with_many_files_using_parallel_copies() {
+        let base = TempPath::new("ludusavi-test-parallel-copy");
+        let source = base.joined("source");
+        let target = base.joined("target");
+        std::fs::create_dir_all(source.interpret()).unwrap();
+
+        let mut found_files = std::collections::HashSet::new();
+        for i in 0..100 {
+            let file = source.joined(&format!("file{}.txt", i));
+            std::fs::write(file.interpret(), "x".repeat(i)).unwrap();
+            found_files.insert(ScannedFile {
+                path: file,
+                size: i as u64,
+                original_path: Some(target.joined(&format!("file{}.txt", i))),
+                ignored: false,
+                system: false,
+                partial: false,
+                xbox_name: None,
+            });
+        }
+        let scan_info = ScanInfo {
+            game_name: s("game"),
+            found_files,
+            ..Default::default()
+        };
+
+        let backup_info = restore_game(
+            &scan_info,
+            &[],
+            &RestoreOptions {
+                copy_threads: 4,
+                ..Default::default()
+            },
+        );
+
+        assert!(backup_info.successful());
+        assert_eq!((0..100).sum::<u64>(), backup_info.copied_bytes);
+        assert_eq!((0..100).sum::<u64>(), scan_info.sum_bytes(&Some(backup_info)));
+        for i in 0..100 {
+            assert!(target.joined(&format!("file{}.txt", i)).is_file());
+        }
+    }
+
+    #[test]
+    fn can_restore_game_while_preserving_timestamps() {
+        let base = TempPath::new("ludusavi-test-preserve-timestamps");
+        let source = base.joined("source.txt");
+        let target = base.joined("target.txt");
+        std::fs::create_dir_all(base.interpret()).unwrap();
+        std::fs::write(source.interpret(), "foo").unwrap();
+        let original = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_500_000_000);
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(source.interpret())
+            .unwrap()
+            .set_modified(original)
+            .unwrap();
+
+        let scan_info = ScanInfo {
+            game_name: s("game"),
+            found_files: hashset! {
+                ScannedFile {
+                    path: source,
+                    size: 3,
+                    original_path: Some(target.clone()),
+                    ignored: false,
+                    system: false,
+                    partial: false,
+                    xbox_name: None,
+                },
+            },
+            ..Default::default()
+        };
+
+        let backup_info = restore_game(
+            &scan_info,
+            &[],
+            &RestoreOptions {
+                preserve_timestamps: true,
+                ..Default::default()
+            },
+        );
+
+        assert!(backup_info.successful());
+        let restored = target.metadata().unwrap().modified().unwrap();
+        let difference = match restored.duration_since(original) {
+            Ok(x) => x,
+            Err(e) => e.duration(),
+        };
+        assert!(difference.as_secs() <= 1);
+    }
+
+    #[test]
+    fn can_restore_game_and_skip_identical_files() {
+        let base = TempPath::new("ludusavi-test-skip-identical");
+        std::fs::create_dir_all(base.interpret()).unwrap();
+        for name in ["same", "changed", "source-same", "source-changed"] {
+            std::fs::write(
+                base.joined(name).interpret(),
+                if name == "changed" { "bar" } else { "foo" },
+            )
+            .unwrap();
+        }
+
+        let same = ScannedFile {
+            path: base.joined("source-same"),
+            size: 3,
+            original_path: Some(base.joined("same")),
+            ignored: false,
+            system: false,
+            partial: false,
+            xbox_name: None,
+        };
+        let changed = ScannedFile {
+            path: base.joined("source-changed"),
+            size: 3,
+            original_path: Some(base.joined("changed")),
+            ignored: false,
+            system: false,
+            partial: false,
+            xbox_name: None,
+        };
+        let scan_info = ScanInfo {
+            game_name: s("game"),
+            found_files: hashset! { same.clone(), changed.clone() },
+            ..Default::default()
+        };
+
+        let backup_info = restore_game(&scan_info, &[], &RestoreOptions::default());
+
+        assert_eq!(Some(&RestoreAction::Skip), backup_info.restore_actions.get(&same));
+        assert_eq!(
+            Some(&RestoreAction::Overwrite),
+            backup_info.restore_actions.get(&changed)
+        );
+        let mut status = OperationStatus::default();
+        status.add_game(&scan_info, &Some(backup_info), true);
+        assert_eq!(1, status.skipped_identical_files);
+    }
+
+    #[test]
+    fn can_restore_game_without_skipping_identical_files() {
+        let base = TempPath::new("ludusavi-test-no-skip-identical");
+        std::fs::create_dir_all(base.interpret()).unwrap();
+        std::fs::write(base.joined("same").interpret(), "foo").unwrap();
+        std::fs::write(base.joined("source-same").interpret(), "foo").unwrap();
+
+        let same = ScannedFile {
+            path: base.joined("source-same"),
+            size: 3,
+            original_path: Some(base.joined("same")),
+            ignored: false,
+            system: false,
+            partial: false,
+            xbox_name: None,
+        };
+        let scan_info = ScanInfo {
+            game_name: s("game"),
+            found_files: hashset! { same.clone() },
+            ..Default::default()
+        };
+
+        let backup_info = restore_game(
+            &scan_info,
+            &[],
+            &RestoreOptions {
+                skip_identical: false,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(Some(&RestoreAction::Overwrite), backup_info.restore_actions.get(&same));
+        assert!(backup_info.warnings.is_empty());
+    }
+
+    #[test]
+    fn only_mirror_restore_removes_files_missing_from_backup() {
+        let base = TempPath::new("ludusavi-test-restore-mode");
+        let restore = |mode: RestoreMode, dry_run: bool| {
+            let _ = base.remove();
+            std::fs::create_dir_all(base.joined("backup").interpret()).unwrap();
+            std::fs::create_dir_all(base.joined("game/nested").interpret()).unwrap();
+            std::fs::write(base.joined("backup/save.txt").interpret(), "new").unwrap();
+            std::fs::write(base.joined("game/extra.txt").interpret(), "old").unwrap();
+            std::fs::write(base.joined("game/nested/other.txt").interpret(), "old").unwrap();
+
+            let scan_info = ScanInfo {
+                game_name: s("game"),
+                found_files: hashset! {
+                    ScannedFile {
+                        path: base.joined("backup/save.txt"),
+                        size: 3,
+                        original_path: Some(base.joined("game/save.txt")),
+                        ignored: false,
+                        system: false,
+                        partial: false,
+                        xbox_name: None,
+                    },
+                },
+                ..Default::default()
+            };
+            let backup_info = restore_game(
+                &scan_info,
+                &[],
+                &RestoreOptions {
+                    dry_run,
+                    mode,
+                    current_saves: hashmap! {
+                        s("game") => vec![
+                            base.joined("game/extra.txt"),
+                            base.joined("game/nested/other.txt"),
+                        ],
+                    },
+                    ..Default::default()
+                },
+            );
+            let removed: Vec<_> = backup_info.removed_files.iter().map(|x| x.render()).collect();
+            let remaining = (
+                base.joined("game/save.txt").is_file(),
+                base.joined("game/extra.txt").is_file(),
+                base.joined("game/nested/other.txt").is_file(),
+            );
+            (backup_info, removed, remaining)
+        };
+
+        let (_, additive_removed, additive_remaining) = restore(RestoreMode::Additive, false);
+        let (_, preview_removed, preview_remaining) = restore(RestoreMode::Mirror, true);
+        let (mirror, mirror_removed, mirror_remaining) = restore(RestoreMode::Mirror, false);
+        let extra = base.joined("game/extra.txt").render();
+
+        assert_eq!(Vec::<String>::new(), additive_removed);
+        assert_eq!((true, true, true), additive_remaining);
+        assert_eq!(vec![extra.clone()], preview_removed);
+        assert_eq!((false, true, true), preview_remaining);
+        assert!(mirror.successful());
+        assert_eq!(vec![extra], mirror_removed);
+        assert_eq!((true, false, true), mirror_remaining);
+    }
+
+    #[test]
+    fn mirror_restore_only_removes_files_matching_save_paths() {
+        let base = TempPath::new("ludusavi-test-restore-mirror-home");
+        std::fs::create_dir_all(base.joined("backup").interpret()).unwrap();
+        std::fs::create_dir_all(base.joined("home").interpret()).unwrap();
+        std::fs::write(base.joined("backup/slot1.sav").interpret(), "new").unwrap();
+        std::fs::write(base.joined("home/slot2.sav").interpret(), "old").unwrap();
+        std::fs::write(base.joined("home/.bashrc").interpret(), "unrelated").unwrap();
+        std::fs::write(base.joined("home/notes.txt").interpret(), "unrelated").unwrap();
+
+        // The save sits directly in the home folder, next to the user's other files.
+        let manifest = Manifest::load_from_string("game:\n  files:\n    <root>/*.sav: {}").unwrap();
+        let roots = vec![RootsConfig {
+            path: base.joined("home"),
+            store: Store::Other,
+            require: Default::default(),
+        }];
+        let saves: Vec<_> = scan_game_for_backup(
+            &manifest.0["game"],
+            "game",
+            &roots,
+            &StrictPath::new(repo()),
+            &None,
+            &BackupFilter::default(),
+            &None,
+            &InstallDirRanking::default(),
+            &ToggledPaths::default(),
+            &ToggledRegistry::default(),
+        )
+        .found_files
+        .into_iter()
+        .map(|x| x.path)
+        .collect();
+
+        let scan_info = ScanInfo {
+            game_name: s("game"),
+            found_files: hashset! {
+                ScannedFile {
+                    path: base.joined("backup/slot1.sav"),
+                    size: 3,
+                    original_path: Some(base.joined("home/slot1.sav")),
+                    ignored: false,
+                    system: false,
+                    partial: false,
+                    xbox_name: None,
+                },
+            },
+            ..Default::default()
+        };
+        let backup_info = restore_game(
+            &scan_info,
+            &[],
+            &RestoreOptions {
+                mode: RestoreMode::Mirror,
+                current_saves: hashmap! { s("game") => saves },
+                ..Default::default()
+            },
+        );
+        let removed: Vec<_> = backup_info.removed_files.iter().map(|x| x.render()).collect();
+        let remaining = (
+            base.joined("home/slot1.sav").is_file(),
+            base.joined("home/slot2.sav").is_file(),
+            base.joined("home/.bashrc").is_file(),
+            base.joined("home/notes.txt").is_file(),
+        );
+        let slot2 = base.joined("home/slot2.sav").render();
+
+        assert!(backup_info.successful());
+        assert_eq!(vec![slot2], removed);
+        assert_eq!((true, false, true, true), remaining);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn mirror_restore_reports_files_that_cannot_be_removed() {
+        use std::os::unix::fs::PermissionsExt;
+
+        if crate::ownership::current_user() == 0 {
+            // Root can remove files from a read-only folder.
+            return;
+        }
+
+        let base = TempPath::new("ludusavi-test-restore-mirror-failure");
+        std::fs::create_dir_all(base.joined("backup").interpret()).unwrap();
+        std::fs::create_dir_all(base.joined("game/locked").interpret()).unwrap();
+        std::fs::write(base.joined("backup/save.txt").interpret(), "new").unwrap();
+        // The saved file is already up to date, so only the removal needs to write to the folder.
+        std::fs::write(base.joined("game/locked/save.txt").interpret(), "new").unwrap();
+        std::fs::write(base.joined("game/locked/extra.txt").interpret(), "old").unwrap();
+        let set_mode = |mode: u32| {
+            std::fs::set_permissions(
+                base.joined("game/locked").interpret(),
+                std::fs::Permissions::from_mode(mode),
+            )
+            .unwrap();
+        };
+        set_mode(0o555);
+
+        let scan_info = ScanInfo {
+            game_name: s("game"),
+            found_files: hashset! {
+                ScannedFile {
+                    path: base.joined("backup/save.txt"),
+                    size: 3,
+                    original_path: Some(base.joined("game/locked/save.txt")),
+                    ignored: false,
+                    system: false,
+                    partial: false,
+                    xbox_name: None,
+                },
+            },
+            ..Default::default()
+        };
+        let backup_info = restore_game(
+            &scan_info,
+            &[],
+            &RestoreOptions {
+                mode: RestoreMode::Mirror,
+                current_saves: hashmap! {
+                    s("game") => vec![base.joined("game/locked/extra.txt")],
+                },
+                ..Default::default()
+            },
+        );
+        let extra = base.joined("game/locked/extra.txt");
+        let still_there = extra.is_file();
+        set_mode(0o755);
+
+        assert!(still_there);
+        assert!(!backup_info.successful());
+        assert!(backup_info.removed_files.is_empty());
+        let failed: Vec<_> = backup_info.failed_removals(&scan_info).collect();
+        assert_eq!(vec![&ScannedFile::new(extra.render(), 0)], failed);
+        assert_eq!(
+            Some(std::io::ErrorKind::PermissionDenied),
+            backup_info.failure_reasons.get(failed[0]).map(|x| x.kind)
+        );
+    }
+
+    #[test]
+    fn can_restore_game_with_verification() {
+        let base = TempPath::new("ludusavi-test-verify-restore");
+        std::fs::create_dir_all(base.interpret()).unwrap();
+        std::fs::write(base.joined("source").interpret(), "foo").unwrap();
+
+        let file = ScannedFile {
+            path: base.joined("source"),
+            size: 3,
+            original_path: Some(base.joined("target")),
+            ignored: false,
+            system: false,
+            partial: false,
+            xbox_name: None,
+        };
+        let scan_info = ScanInfo {
+            game_name: s("game"),
+            found_files: hashset! { file },
+            ..Default::default()
+        };
+
+        let backup_info = restore_game(
+            &scan_info,
+            &[],
+            &RestoreOptions {
+                verify: true,
+                ..Default::default()
+            },
+        );
+
+        assert!(backup_info.successful());
+        assert!(backup_info.mismatched_files.is_empty());
+        assert_eq!(
+            "foo",
+            std::fs::read_to_string(base.joined("target").interpret()).unwrap()
+        );
+    }
+
+    #[test]
+    fn reports_corrupted_files_when_verifying_restore() {
+        let base = TempPath::new("ludusavi-test-verify-restore-corrupted");
+        std::fs::create_dir_all(base.interpret()).unwrap();
+        std::fs::write(base.joined("source").interpret(), "foo").unwrap();
+
+        let file = ScannedFile {
+            path: base.joined("source"),
+            size: 3,
+            original_path: Some(base.joined("target")),
+            ignored: false,
+            system: false,
+            partial: false,
+            xbox_name: None,
+        };
+        let scan_info = ScanInfo {
+            game_name: s("game"),
+            found_files: hashset! { file.clone() },
+            ..Default::default()
+        };
+
+        let mut backup_info = restore_game(&scan_info, &[], &RestoreOptions::default());
+        assert!(backup_info.successful());
+        // Like a disk that didn't write what it was given.
+        std::fs::write(base.joined("target").interpret(), "bar").unwrap();
+        verify_restored_files(&scan_info, &[], &mut backup_info, 1);
+
+        assert!(!backup_info.successful());
+        assert_eq!(hashset! { file.clone() }, backup_info.mismatched_files);
+        assert!(backup_info.failed_files.contains(&file));
+    }
+
     #[test]
     fn checks_if_files_are_identical() {
         assert!(are_files_identical(
@@ -1750,6 +4435,131 @@ mod tests {
         .is_err());
     }
 
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn finds_case_conflicts_on_simulated_case_insensitive_target() {
+        // Linux allows names that only differ by case, so we can set up what
+        // a case-insensitive file system would treat as the same file.
+        let folder = TempPath::new("ludusavi-test-case-conflict");
+        std::fs::create_dir_all(folder.interpret()).unwrap();
+        std::fs::write(folder.joined("Save.DAT").interpret(), "old").unwrap();
+
+        let target = folder.joined("save.dat");
+        let before_rename = find_case_conflict(&target, true);
+        let case_sensitive = find_case_conflict(&target, false);
+        let exact = find_case_conflict(&folder.joined("Save.DAT"), true);
+        let missing = find_case_conflict(&folder.joined("other.dat"), true);
+        std::fs::write(folder.joined("SAVE.dat").interpret(), "other").unwrap();
+        let ambiguous = find_case_conflict(&target, true);
+
+        assert_eq!(CaseConflict::Rename(folder.joined("Save.DAT")), before_rename);
+        assert_eq!(CaseConflict::None, case_sensitive);
+        assert_eq!(CaseConflict::None, exact);
+        assert_eq!(CaseConflict::None, missing);
+        assert_eq!(
+            CaseConflict::Ambiguous(vec![folder.joined("SAVE.dat"), folder.joined("Save.DAT")]),
+            ambiguous,
+        );
+    }
+
+    #[test]
+    fn groups_failures_by_reason_and_collapses_large_groups() {
+        let denied = FailureReason {
+            kind: std::io::ErrorKind::PermissionDenied,
+            os_code: Some(13),
+            message: "Permission denied (os error 13)".to_string(),
+        };
+        let full = FailureReason {
+            kind: std::io::ErrorKind::Other,
+            os_code: Some(28),
+            message: "No space left on device (os error 28)".to_string(),
+        };
+
+        let mut report = ErrorReport::default();
+        for i in 0..8 {
+            let game = if i < 6 { "game1" } else { "game2" };
+            report.add(game, format!("/root/file{}", i), Some(&denied));
+        }
+        report.add("game2", "/other/file".to_string(), Some(&full));
+        report.add("game3", "HKEY_CURRENT_USER/key".to_string(), None);
+
+        let entry = |game: &str, path: &str| FailedEntry {
+            game: game.to_string(),
+            path: path.to_string(),
+        };
+        assert_eq!(
+            vec![
+                FailureGroup {
+                    reason: Some(denied),
+                    games: btreeset! { "game1".to_string(), "game2".to_string() },
+                    count: 8,
+                    sample: (0..ErrorReport::MAX_LISTED)
+                        .map(|i| entry("game1", &format!("/root/file{}", i)))
+                        .collect(),
+                },
+                FailureGroup {
+                    reason: Some(full),
+                    games: btreeset! { "game2".to_string() },
+                    count: 1,
+                    sample: vec![entry("game2", "/other/file")],
+                },
+                FailureGroup {
+                    reason: None,
+                    games: btreeset! { "game3".to_string() },
+                    count: 1,
+                    sample: vec![entry("game3", "HKEY_CURRENT_USER/key")],
+                },
+            ],
+            report.groups,
+        );
+        assert!(report.groups[0].collapsed());
+        assert!(!report.groups[1].collapsed());
+    }
+
+    #[test]
+    fn compares_files_according_to_strategy() {
+        let base = TempPath::new("ludusavi-test-comparison");
+        std::fs::create_dir_all(base.interpret()).unwrap();
+        let set_modified = |file: &StrictPath, secs: u64| {
+            std::fs::File::options()
+                .write(true)
+                .open(file.interpret())
+                .unwrap()
+                .set_modified(std::time::UNIX_EPOCH + std::time::Duration::from_secs(secs))
+                .unwrap();
+        };
+
+        let original = base.joined("original.txt");
+        let copy = base.joined("copy.txt");
+        let same_time = base.joined("same-time.txt");
+        let new_time = base.joined("new-time.txt");
+        let resized = base.joined("resized.txt");
+        std::fs::write(original.interpret(), "abc").unwrap();
+        std::fs::write(copy.interpret(), "abc").unwrap();
+        std::fs::write(same_time.interpret(), "xyz").unwrap();
+        std::fs::write(new_time.interpret(), "xyz").unwrap();
+        std::fs::write(resized.interpret(), "abcd").unwrap();
+        set_modified(&original, 1_000_000);
+        for file in [&copy, &same_time, &resized] {
+            set_modified(file, 1_000_000);
+        }
+        set_modified(&new_time, 2_000_000);
+
+        let check = |strategy| {
+            [&copy, &same_time, &new_time, &resized]
+                .into_iter()
+                .map(|x| are_files_equivalent(&original, x, strategy))
+                .collect::<Vec<_>>()
+        };
+        let size_only = check(ComparisonStrategy::SizeOnly);
+        let size_and_mtime = check(ComparisonStrategy::SizeAndMtime);
+        let hash = check(ComparisonStrategy::Hash);
+
+        assert_eq!(vec![true, true, true, false], size_only);
+        assert_eq!(vec![true, true, false, false], size_and_mtime);
+        assert_eq!(vec![true, false, false, false], hash);
+    }
+
     mod duplicate_detector {
         use super::*;
         use pretty_assertions::assert_eq;
@@ -1794,6 +4604,30 @@ mod tests {
             assert_eq!(hashset! { game2 }, detector.registry(&RegistryItem::new(reg2)));
         }
 
+        #[test]
+        fn counts_duplicated_files_once_in_size_estimate() {
+            let detector = DuplicateDetector::default();
+
+            let game1 = ScanInfo {
+                game_name: s("game1"),
+                found_files: hashset! {
+                    ScannedFile::new("file1.txt", 1),
+                    ScannedFile::new("file2.txt", 2),
+                    ScannedFile::new("file3.txt", 4).ignored(),
+                },
+                ..Default::default()
+            };
+            let game2 = ScanInfo {
+                game_name: s("game2"),
+                found_files: hashset! { ScannedFile::new("file1.txt", 1), ScannedFile::new("file4.txt", 8) },
+                ..Default::default()
+            };
+
+            assert_eq!(11, detector.deduplicated_bytes([&game1, &game2]));
+            assert_eq!(9, detector.deduplicated_bytes([&game2]));
+            assert_eq!(0, detector.deduplicated_bytes(std::iter::empty()));
+        }
+
         #[test]
         fn can_add_games_in_restore_mode() {
             let mut detector = DuplicateDetector::default();
@@ -1805,12 +4639,18 @@ mod tests {
                 size: 1,
                 original_path: Some(StrictPath::new(s("file1.txt"))),
                 ignored: false,
+                system: false,
+                partial: false,
+                xbox_name: None,
             };
             let file1b = ScannedFile {
                 path: StrictPath::new(s("file1b.txt")),
                 size: 1,
                 original_path: Some(StrictPath::new(s("file1.txt"))),
                 ignored: false,
+                system: false,
+                partial: false,
+                xbox_name: None,
             };
 
             detector.add_game(&ScanInfo {
@@ -1831,6 +4671,9 @@ mod tests {
                 size: 1,
                 original_path: None,
                 ignored: false,
+                system: false,
+                partial: false,
+                xbox_name: None,
             }));
 
             assert!(detector.is_file_duplicated(&file1b));
@@ -1840,6 +4683,9 @@ mod tests {
                 size: 1,
                 original_path: None,
                 ignored: false,
+                system: false,
+                partial: false,
+                xbox_name: None,
             }));
         }
     }
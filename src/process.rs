@@ -0,0 +1,113 @@
+use crate::{manifest::Manifest, prelude::Error};
+use sysinfo::{ProcessExt, System, SystemExt};
+
+/// Names of the processes that are currently running.
+/// This includes both the process name and its executable's file name,
+/// since the former is truncated on Linux and the latter is the loader for Wine games.
+pub fn running_process_names() -> Vec<String> {
+    let mut system = System::new();
+    system.refresh_processes();
+    system
+        .processes()
+        .values()
+        .flat_map(|process| {
+            let exe = process.exe().file_name().map(|x| x.to_string_lossy().to_string());
+            std::iter::once(process.name().to_string()).chain(exe)
+        })
+        .collect()
+}
+
+/// Find which of these games are running, based on the executables in their manifest entries.
+/// Names are compared without case, since Windows and Mac don't distinguish them.
+pub fn find_running_games(manifest: &Manifest, games: &[String], running: &[String]) -> Vec<String> {
+    let running: std::collections::HashSet<_> = running.iter().map(|x| x.to_lowercase()).collect();
+    games
+        .iter()
+        .filter(|game| {
+            manifest
+                .0
+                .get(*game)
+                .map(|entry| {
+                    entry
+                        .executable_names()
+                        .iter()
+                        .any(|exe| running.contains(&exe.to_lowercase()))
+                })
+                .unwrap_or(false)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Make sure that none of these games are running before restoring them.
+/// With `force`, any running games are returned as a warning instead of refusing the restore.
+pub fn check_restore(
+    manifest: &Manifest,
+    games: &[String],
+    running: &[String],
+    force: bool,
+) -> Result<Option<Error>, Error> {
+    let running = find_running_games(manifest, games, running);
+    if running.is_empty() {
+        return Ok(None);
+    }
+    let error = Error::GameIsRunning { games: running };
+    if force {
+        Ok(Some(error))
+    } else {
+        Err(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn s(text: &str) -> String {
+        text.to_string()
+    }
+
+    fn manifest() -> Manifest {
+        Manifest::load_from_string(
+            r#"
+            game1:
+              launch:
+                <base>/Game1.exe: [{}]
+                <base>/bin/game1-launcher: [{}]
+            game2:
+              launch:
+                <base>\Game2.exe: [{}]
+            game3: {}
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn can_find_running_games_by_any_executable_ignoring_case() {
+        let games = vec![s("game1"), s("game2"), s("game3"), s("unknown")];
+
+        assert_eq!(
+            vec![s("game1"), s("game2")],
+            find_running_games(&manifest(), &games, &[s("GAME1-LAUNCHER"), s("game2.exe"), s("game3")]),
+        );
+        assert_eq!(
+            Vec::<String>::new(),
+            find_running_games(&manifest(), &games, &[s("Game1"), s("explorer.exe")]),
+        );
+    }
+
+    #[test]
+    fn can_check_restore_with_running_games() {
+        let games = vec![s("game1"), s("game2")];
+        let running = vec![s("Game1.exe")];
+        let error = Error::GameIsRunning {
+            games: vec![s("game1")],
+        };
+
+        assert_eq!(Ok(None), check_restore(&manifest(), &games, &[], false));
+        assert_eq!(Err(error.clone()), check_restore(&manifest(), &games, &running, false));
+        assert_eq!(Ok(Some(error)), check_restore(&manifest(), &games, &running, true));
+    }
+}
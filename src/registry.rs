@@ -2,7 +2,7 @@ use std::collections::HashSet;
 
 use crate::{
     config::{BackupFilter, ToggledRegistry},
-    prelude::{Error, RegistryItem, ScanInfo, ScannedRegistry, StrictPath},
+    prelude::{Error, RegistryFilter, RegistryItem, RegistryValueCounts, ScanInfo, ScannedRegistry, StrictPath},
 };
 use winreg::types::{FromRegValue, ToRegValue};
 
@@ -40,26 +40,29 @@ pub fn scan_registry(
     path: &str,
     filter: &BackupFilter,
     toggled: &ToggledRegistry,
+    values: &RegistryFilter,
 ) -> Result<Vec<ScannedRegistry>, Error> {
     let path = RegistryItem::new(path.to_string());
 
     let (hive_name, key) = path.split_hive().ok_or(Error::RegistryIssue)?;
-    let hive = get_hkey_from_name(&hive_name).ok_or(Error::RegistryIssue)?;
 
-    scan_registry_key(game, hive, &hive_name, &key, filter, toggled)
+    scan_registry_key(game, &hive_name, &key, "", filter, toggled, values)
 }
 
+/// `relative` is the subkey's path under the registry entry that we started from.
 fn scan_registry_key(
     game: &str,
-    hive: winreg::HKEY,
     hive_name: &str,
     key: &str,
+    relative: &str,
     filter: &BackupFilter,
     toggled: &ToggledRegistry,
+    values: &RegistryFilter,
 ) -> Result<Vec<ScannedRegistry>, Error> {
     let mut found = vec![];
     let path = RegistryItem::new(format!("{}\\{}", hive_name, key));
 
+    let hive = get_hkey_from_name(hive_name).ok_or(Error::RegistryIssue)?;
     let subkey = winreg::RegKey::predef(hive)
         .open_subkey(key)
         .map_err(|_| Error::RegistryIssue)?;
@@ -68,12 +71,29 @@ fn scan_registry_key(
         found.push(ScannedRegistry {
             path: path.rendered(),
             ignored: toggled.is_ignored(game, &path),
+            filter: values.clone(),
         });
 
         for name in subkey.enum_keys().filter_map(|x| x.ok()) {
+            let child = if relative.is_empty() {
+                name.clone()
+            } else {
+                format!("{}\\{}", relative, name)
+            };
+            if !values.keeps_subkey(&child) {
+                continue;
+            }
             found.extend(
-                scan_registry_key(game, hive, hive_name, &format!("{}\\{}", key, name), filter, toggled)
-                    .unwrap_or_default(),
+                scan_registry_key(
+                    game,
+                    hive_name,
+                    &format!("{}\\{}", key, name),
+                    &child,
+                    filter,
+                    toggled,
+                    values,
+                )
+                .unwrap_or_default(),
             );
         }
     }
@@ -110,44 +130,59 @@ impl Hives {
         serde_yaml::to_string(self).unwrap()
     }
 
-    pub fn incorporate(&mut self, scan: &HashSet<ScannedRegistry>) -> (bool, HashSet<RegistryItem>) {
+    pub fn incorporate(
+        &mut self,
+        scan: &HashSet<ScannedRegistry>,
+    ) -> (bool, HashSet<RegistryItem>, RegistryValueCounts) {
         let mut failed = HashSet::new();
         let mut found = false;
+        let mut counts = RegistryValueCounts::default();
 
         for scanned in scan {
             if scanned.ignored {
                 continue;
             }
 
-            match self.store_key_from_full_path(&scanned.path.raw()) {
+            match self.store_key_from_full_path(&scanned.path.raw(), &scanned.filter) {
                 Err(_) => {
                     failed.insert(scanned.path.clone());
                 }
-                Ok(_) => {
+                Ok(stored) => {
                     found = true;
+                    counts.backed_up += stored.backed_up;
+                    counts.skipped += stored.skipped;
                 }
             }
         }
 
-        (found, failed)
+        (found, failed, counts)
     }
 
     pub fn same_content(&self, other: &Self) -> bool {
         self.0 == other.0
     }
 
-    pub fn store_key_from_full_path(&mut self, path: &str) -> Result<(), Error> {
+    pub fn store_key_from_full_path(
+        &mut self,
+        path: &str,
+        filter: &RegistryFilter,
+    ) -> Result<RegistryValueCounts, Error> {
         let path = RegistryItem::new(path.to_string()).interpreted();
 
         let (hive_name, key) = path.split_hive().ok_or(Error::RegistryIssue)?;
         let hive = get_hkey_from_name(&hive_name).ok_or(Error::RegistryIssue)?;
 
-        self.store_key(hive, &hive_name, &key)?;
-
-        Ok(())
+        self.store_key(hive, &hive_name, &key, filter)
     }
 
-    pub fn store_key(&mut self, hive: winreg::HKEY, hive_name: &str, key: &str) -> Result<(), Error> {
+    /// The key is stored even if the filter leaves none of its values.
+    pub fn store_key(
+        &mut self,
+        hive: winreg::HKEY,
+        hive_name: &str,
+        key: &str,
+        filter: &RegistryFilter,
+    ) -> Result<RegistryValueCounts, Error> {
         let subkey = winreg::RegKey::predef(hive)
             .open_subkey(key)
             .map_err(|_| Error::RegistryIssue)?;
@@ -158,7 +193,8 @@ impl Hives {
             .0
             .entry(key.to_string())
             .or_insert_with(Default::default);
-        for (name, value) in subkey.enum_values().filter_map(|x| x.ok()) {
+        let (values, counts) = filter.select_values(subkey.enum_values().filter_map(|x| x.ok()));
+        for (name, value) in values {
             let entry = Entry::from(value);
             if entry.is_set() {
                 self.0
@@ -173,7 +209,7 @@ impl Hives {
             }
         }
 
-        Ok(())
+        Ok(counts)
     }
 
     pub fn restore(&self) -> Result<(), Error> {
@@ -315,7 +351,7 @@ mod tests {
     fn can_store_key_from_full_path_of_leaf_key_with_values() {
         let mut hives = Hives::default();
         hives
-            .store_key_from_full_path("HKEY_CURRENT_USER/Software/Ludusavi/game3")
+            .store_key_from_full_path("HKEY_CURRENT_USER/Software/Ludusavi/game3", &RegistryFilter::default())
             .unwrap();
         assert_eq!(
             Hives(hashmap! {
@@ -348,11 +384,78 @@ mod tests {
         );
     }
 
+    #[test]
+    fn can_store_key_from_full_path_with_filtered_values() {
+        let mut hives = Hives::default();
+        let counts = hives
+            .store_key_from_full_path(
+                "HKEY_CURRENT_USER/Software/Ludusavi/game3",
+                &RegistryFilter {
+                    exclude_values: vec![s("*sz")],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            Hives(hashmap! {
+                s("HKEY_CURRENT_USER") => Keys(hashmap! {
+                    s("Software\\Ludusavi\\game3") => Entries(hashmap! {
+                        s("dword") => Entry {
+                            dword: Some(1),
+                            ..Default::default()
+                        },
+                        s("qword") => Entry {
+                            qword: Some(2),
+                            ..Default::default()
+                        },
+                    })
+                })
+            }),
+            hives,
+        );
+        assert_eq!(
+            RegistryValueCounts {
+                backed_up: 2,
+                skipped: 3,
+            },
+            counts
+        );
+    }
+
+    #[test]
+    fn can_store_key_from_full_path_when_filter_excludes_every_value() {
+        let mut hives = Hives::default();
+        let counts = hives
+            .store_key_from_full_path(
+                "HKEY_CURRENT_USER/Software/Ludusavi/game3",
+                &RegistryFilter {
+                    include_values: vec![s("missing")],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            Hives(hashmap! {
+                s("HKEY_CURRENT_USER") => Keys(hashmap! {
+                    s("Software\\Ludusavi\\game3") => Entries::default()
+                })
+            }),
+            hives,
+        );
+        assert_eq!(
+            RegistryValueCounts {
+                backed_up: 0,
+                skipped: 5,
+            },
+            counts
+        );
+    }
+
     #[test]
     fn can_store_key_from_full_path_of_leaf_key_without_values() {
         let mut hives = Hives::default();
         hives
-            .store_key_from_full_path("HKEY_CURRENT_USER/Software/Ludusavi/other")
+            .store_key_from_full_path("HKEY_CURRENT_USER/Software/Ludusavi/other", &RegistryFilter::default())
             .unwrap();
         assert_eq!(
             Hives(hashmap! {
@@ -368,7 +471,7 @@ mod tests {
     fn can_store_key_from_full_path_of_parent_key_without_values() {
         let mut hives = Hives::default();
         hives
-            .store_key_from_full_path("HKEY_CURRENT_USER/Software/Ludusavi")
+            .store_key_from_full_path("HKEY_CURRENT_USER/Software/Ludusavi", &RegistryFilter::default())
             .unwrap();
         assert_eq!(
             Hives(hashmap! {
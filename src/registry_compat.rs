@@ -1,3 +1,5 @@
+use crate::prelude::{Error, StrictPath};
+
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct RegistryItem {
     raw: String,
@@ -86,6 +88,145 @@ impl RegistryItem {
     }
 }
 
+/// Whether a custom game's registry entry refers to a `.reg` file instead of a key.
+pub fn is_reg_file(entry: &str) -> bool {
+    let entry = entry.trim();
+    !entry.starts_with("HKEY_") && entry.to_lowercase().ends_with(".reg")
+}
+
+/// Read the keys from a `.reg` file, as exported by the Registry Editor.
+pub fn load_reg_file(file: &StrictPath) -> Result<Vec<RegistryItem>, Error> {
+    let invalid = |why: String| Error::RegistryFileInvalid {
+        path: file.clone(),
+        why,
+    };
+    let bytes = std::fs::read(file.interpret()).map_err(|e| invalid(e.to_string()))?;
+    parse_reg_file(&decode_reg_file(&bytes)).map_err(invalid)
+}
+
+/// The Registry Editor exports as UTF-16 with a byte order mark,
+/// but files written by hand are usually UTF-8.
+fn decode_reg_file(bytes: &[u8]) -> String {
+    match bytes {
+        [0xFF, 0xFE, rest @ ..] => {
+            let units: Vec<_> = rest.chunks_exact(2).map(|x| u16::from_le_bytes([x[0], x[1]])).collect();
+            String::from_utf16_lossy(&units)
+        }
+        _ => String::from_utf8_lossy(bytes)
+            .trim_start_matches('\u{feff}')
+            .to_string(),
+    }
+}
+
+/// Only the keys matter for a backup, so values are skipped.
+/// Deletions (`[-HKEY_...]`) are skipped too, since there's nothing to back up for them.
+/// Keys inside of another listed key are left out, since backing up a key includes its subkeys.
+pub fn parse_reg_file(content: &str) -> Result<Vec<RegistryItem>, String> {
+    let mut lines = content.lines().map(|x| x.trim());
+    match lines.next() {
+        Some("Windows Registry Editor Version 5.00" | "REGEDIT4") => {}
+        _ => return Err("The file does not start with a Registry Editor header".to_string()),
+    }
+
+    let mut keys = vec![];
+    for line in lines {
+        let key = match line.strip_prefix('[').and_then(|x| x.strip_suffix(']')) {
+            Some(x) => x.trim(),
+            None => continue,
+        };
+        if key.starts_with('-') {
+            continue;
+        }
+        if !key.starts_with("HKEY_") {
+            return Err(format!("Unrecognized key: {}", key));
+        }
+        keys.push(RegistryItem::new(key.to_string()).rendered());
+    }
+
+    keys.sort();
+    keys.dedup();
+    Ok(keys
+        .iter()
+        .filter(|key| !keys.iter().any(|other| other.is_prefix_of(key)))
+        .cloned()
+        .collect())
+}
+
+/// Narrows down what gets backed up from a registry entry.
+/// Patterns are globs, matched without regard to case like the registry itself.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, serde::Serialize, serde::Deserialize)]
+pub struct RegistryFilter {
+    /// If not empty, only values whose names match one of these are backed up.
+    #[serde(default, rename = "includeValues", skip_serializing_if = "Vec::is_empty")]
+    pub include_values: Vec<String>,
+    #[serde(default, rename = "excludeValues", skip_serializing_if = "Vec::is_empty")]
+    pub exclude_values: Vec<String>,
+    /// Subkeys to leave out along with everything under them,
+    /// relative to the entry's key (e.g., `Cache` or `*/Temp`).
+    #[serde(default, rename = "excludeSubkeys", skip_serializing_if = "Vec::is_empty")]
+    pub exclude_subkeys: Vec<String>,
+}
+
+/// How many values were backed up from the registry,
+/// and how many were left out by a `RegistryFilter`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Serialize)]
+pub struct RegistryValueCounts {
+    #[serde(rename = "backedUp")]
+    pub backed_up: usize,
+    pub skipped: usize,
+}
+
+impl RegistryFilter {
+    pub fn is_empty(&self) -> bool {
+        self.include_values.is_empty() && self.exclude_values.is_empty() && self.exclude_subkeys.is_empty()
+    }
+
+    #[allow(dead_code)]
+    fn matches_any(patterns: &[String], name: &str) -> bool {
+        let options = glob::MatchOptions {
+            case_sensitive: false,
+            require_literal_separator: true,
+            require_literal_leading_dot: false,
+        };
+        patterns.iter().any(|pattern| match glob::Pattern::new(pattern) {
+            Ok(pattern) => pattern.matches_with(name, options),
+            Err(_) => pattern.eq_ignore_ascii_case(name),
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn keeps_value(&self, name: &str) -> bool {
+        (self.include_values.is_empty() || Self::matches_any(&self.include_values, name))
+            && !Self::matches_any(&self.exclude_values, name)
+    }
+
+    /// `subkey` is relative to the entry's key, in either slash style.
+    #[allow(dead_code)]
+    pub fn keeps_subkey(&self, subkey: &str) -> bool {
+        !Self::matches_any(&self.exclude_subkeys, &RegistryItem::new(subkey.to_string()).render())
+    }
+
+    /// Pick out the values to back up from those in a key.
+    /// If every value is filtered out, the key is still backed up, just empty.
+    #[allow(dead_code)]
+    pub fn select_values<N: AsRef<str>, T>(
+        &self,
+        values: impl IntoIterator<Item = (N, T)>,
+    ) -> (Vec<(N, T)>, RegistryValueCounts) {
+        let mut counts = RegistryValueCounts::default();
+        let mut kept = vec![];
+        for (name, value) in values {
+            if self.keeps_value(name.as_ref()) {
+                counts.backed_up += 1;
+                kept.push((name, value));
+            } else {
+                counts.skipped += 1;
+            }
+        }
+        (kept, counts)
+    }
+}
+
 // Based on:
 // https://github.com/serde-rs/serde/issues/751#issuecomment-277580700
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -147,4 +288,106 @@ mod tests {
             ])
         );
     }
+
+    #[test]
+    fn can_parse_reg_file() {
+        let content = r#"Windows Registry Editor Version 5.00
+
+[HKEY_CURRENT_USER\Software\Ludusavi]
+"Name"="value"
+"Binary"=hex:00,01,\
+  02,03
+
+[HKEY_CURRENT_USER\Software\Ludusavi\Nested]
+"Deleted"=-
+
+[-HKEY_CURRENT_USER\Software\Removed]
+
+[HKEY_LOCAL_MACHINE\Software\Other]
+"#;
+        assert_eq!(
+            Ok(vec![
+                RegistryItem::new(s("HKEY_CURRENT_USER/Software/Ludusavi")),
+                RegistryItem::new(s("HKEY_LOCAL_MACHINE/Software/Other")),
+            ]),
+            parse_reg_file(content)
+        );
+    }
+
+    #[test]
+    fn rejects_reg_file_without_header() {
+        assert!(parse_reg_file("[HKEY_CURRENT_USER\\Software\\Ludusavi]").is_err());
+        assert!(parse_reg_file("REGEDIT4\n[Software\\Ludusavi]").is_err());
+    }
+
+    #[test]
+    fn can_decode_utf16_reg_file() {
+        let bytes: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain("REGEDIT4".encode_utf16().flat_map(|x| x.to_le_bytes()))
+            .collect();
+        assert_eq!("REGEDIT4", decode_reg_file(&bytes));
+    }
+
+    #[test]
+    fn can_tell_reg_files_from_keys() {
+        assert!(is_reg_file("C:/saves/game.reg"));
+        assert!(is_reg_file(" ~/Game.REG "));
+        assert!(!is_reg_file("HKEY_CURRENT_USER/Software/Game.reg"));
+        assert!(!is_reg_file("HKEY_CURRENT_USER/Software/Game"));
+    }
+
+    #[test]
+    fn can_filter_specific_value_names() {
+        let filter = RegistryFilter {
+            include_values: vec![s("Save*"), s("settings")],
+            exclude_values: vec![s("*Backup")],
+            ..Default::default()
+        };
+        let (kept, counts) = filter.select_values(vec![
+            ("SaveSlot1", 1),
+            ("SAVESLOT2", 2),
+            ("SaveBackup", 3),
+            ("Settings", 4),
+            ("WindowX", 5),
+        ]);
+        assert_eq!(vec![("SaveSlot1", 1), ("SAVESLOT2", 2), ("Settings", 4)], kept);
+        assert_eq!(
+            RegistryValueCounts {
+                backed_up: 3,
+                skipped: 2,
+            },
+            counts
+        );
+    }
+
+    #[test]
+    fn filtering_out_every_value_leaves_an_empty_key() {
+        let filter = RegistryFilter {
+            exclude_values: vec![s("*")],
+            ..Default::default()
+        };
+        let (kept, counts) = filter.select_values(vec![("foo", 1), ("bar", 2)]);
+        assert!(kept.is_empty());
+        assert_eq!(
+            RegistryValueCounts {
+                backed_up: 0,
+                skipped: 2,
+            },
+            counts
+        );
+    }
+
+    #[test]
+    fn can_filter_subkeys() {
+        let filter = RegistryFilter {
+            exclude_subkeys: vec![s("cache"), s("*/Temp")],
+            ..Default::default()
+        };
+        assert!(!filter.keeps_subkey("Cache"));
+        assert!(!filter.keeps_subkey("Profiles\\Temp"));
+        assert!(filter.keeps_subkey("Profiles"));
+        assert!(filter.keeps_subkey("Profiles/Cache"));
+        assert!(filter.keeps_subkey("Temp"));
+    }
 }
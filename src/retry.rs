@@ -0,0 +1,228 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use crate::{config::RuntimeConfig, path::StrictPath, throttle::Throttle};
+
+/// Retries file operations that fail for reasons that may go away on their own,
+/// like a network drive timing out.
+/// Clones share the same copy throttle, if any.
+#[derive(Clone, Debug, Default)]
+pub struct Retry {
+    attempts: u32,
+    base_delay: std::time::Duration,
+    cancel: Option<Arc<AtomicBool>>,
+    throttle: Option<Arc<Throttle>>,
+    reflinks: bool,
+}
+
+impl Retry {
+    pub fn new(config: &RuntimeConfig) -> Self {
+        Self {
+            attempts: config.retry_attempts,
+            base_delay: std::time::Duration::from_millis(config.retry_delay_ms),
+            cancel: None,
+            throttle: config
+                .copy_rate_limit
+                .map(|x| Arc::new(Throttle::from_megabytes_per_second(x))),
+            reflinks: config.use_reflinks,
+        }
+    }
+
+    /// Stop retrying as soon as this flag is set.
+    pub fn cancellable(mut self, flag: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(flag);
+        self
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.as_ref().map(|x| x.load(Ordering::Relaxed)).unwrap_or(false)
+    }
+
+    /// Copy a file like `std::fs::copy`, retrying transient errors
+    /// and staying under the configured rate limit.
+    /// When reflinks are enabled and the file system supports them, the target shares
+    /// the source's data until either one is written, so later changes to the source
+    /// still don't affect the backup.
+    pub fn copy(&self, source: &StrictPath, target: &StrictPath) -> std::io::Result<u64> {
+        if self.reflinks {
+            // This fails without touching anything when the target already exists,
+            // the file system doesn't support it, or the files are on different devices.
+            match reflink_copy::reflink(source.interpret(), target.interpret()) {
+                Ok(()) => {
+                    log::debug!("Copied with reflink: {} -> {}", source.render(), target.render());
+                    return target.metadata().map(|x| x.len());
+                }
+                Err(e) => {
+                    log::trace!("Unable to reflink ({}), copying instead: {}", e, source.render());
+                }
+            }
+        }
+
+        self.run(&source.render(), || match &self.throttle {
+            Some(throttle) => {
+                crate::throttle::copy(&source.as_std_path_buf(), &target.as_std_path_buf(), throttle, || {
+                    self.is_cancelled()
+                })
+            }
+            None => std::fs::copy(source.interpret(), target.interpret()),
+        })
+    }
+
+    /// Run `operation`, retrying transient errors with an exponential backoff.
+    /// Any other error is returned right away.
+    pub fn run<T>(&self, label: &str, mut operation: impl FnMut() -> std::io::Result<T>) -> std::io::Result<T> {
+        let mut retried = 0;
+        loop {
+            match operation() {
+                Ok(x) => {
+                    if retried > 0 {
+                        log::debug!("Succeeded after {} retries: {}", retried, label);
+                    }
+                    return Ok(x);
+                }
+                Err(e) if retried < self.attempts && is_transient(&e) && !self.is_cancelled() => {
+                    log::debug!("Retrying after error ({}): {}", e, label);
+                    std::thread::sleep(self.base_delay * 2u32.saturating_pow(retried));
+                    retried += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Whether an error may not happen again if we try the same thing later.
+fn is_transient(error: &std::io::Error) -> bool {
+    if matches!(
+        error.kind(),
+        std::io::ErrorKind::TimedOut | std::io::ErrorKind::Interrupted | std::io::ErrorKind::WouldBlock
+    ) {
+        return true;
+    }
+    // A file that's busy in another program may be released soon.
+    // There's no stable `ErrorKind` for this, so we check the OS error code.
+    match error.raw_os_error() {
+        // ERROR_SHARING_VIOLATION, ERROR_LOCK_VIOLATION
+        Some(code) if cfg!(target_os = "windows") => code == 32 || code == 33,
+        // ETXTBSY
+        Some(code) => code == 26,
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::TempPath;
+    use pretty_assertions::assert_eq;
+
+    fn retry() -> Retry {
+        Retry {
+            attempts: 3,
+            base_delay: std::time::Duration::ZERO,
+            cancel: None,
+            throttle: None,
+            reflinks: false,
+        }
+    }
+
+    /// Fails with `kind` for the first `failures` calls, then succeeds.
+    struct FlakyIo {
+        failures: usize,
+        kind: std::io::ErrorKind,
+        calls: std::cell::Cell<usize>,
+    }
+
+    impl FlakyIo {
+        fn new(failures: usize, kind: std::io::ErrorKind) -> Self {
+            Self {
+                failures,
+                kind,
+                calls: Default::default(),
+            }
+        }
+
+        fn call(&self) -> std::io::Result<()> {
+            let call = self.calls.get();
+            self.calls.set(call + 1);
+            if call < self.failures {
+                Err(std::io::Error::from(self.kind))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    fn run(retry: &Retry, failures: usize, kind: std::io::ErrorKind) -> (std::io::Result<()>, usize) {
+        let io = FlakyIo::new(failures, kind);
+        let result = retry.run("test", || io.call());
+        (result, io.calls.get())
+    }
+
+    #[test]
+    fn retries_transient_error_until_success() {
+        let (result, calls) = run(&retry(), 1, std::io::ErrorKind::TimedOut);
+        assert!(result.is_ok());
+        assert_eq!(2, calls);
+    }
+
+    #[test]
+    fn gives_up_after_configured_attempts() {
+        let (result, calls) = run(&retry(), 10, std::io::ErrorKind::Interrupted);
+        assert_eq!(Some(std::io::ErrorKind::Interrupted), result.err().map(|e| e.kind()));
+        assert_eq!(4, calls);
+    }
+
+    #[test]
+    fn retries_busy_file() {
+        let busy = if cfg!(target_os = "windows") { 32 } else { 26 };
+        assert!(is_transient(&std::io::Error::from_raw_os_error(busy)));
+        assert!(!is_transient(&std::io::Error::from_raw_os_error(2)));
+    }
+
+    #[test]
+    fn does_not_retry_permanent_error() {
+        let (result, calls) = run(&retry(), 1, std::io::ErrorKind::NotFound);
+        assert!(result.is_err());
+        assert_eq!(1, calls);
+
+        let (result, calls) = run(&retry(), 1, std::io::ErrorKind::PermissionDenied);
+        assert!(result.is_err());
+        assert_eq!(1, calls);
+    }
+
+    #[test]
+    fn copies_stay_independent_of_source_with_or_without_reflinks() {
+        let folder = TempPath::new("ludusavi-test-reflink");
+        std::fs::create_dir_all(folder.interpret()).unwrap();
+        let source = folder.joined("source.dat");
+        std::fs::write(source.interpret(), "old").unwrap();
+
+        for reflinks in [false, true] {
+            let target = folder.joined(&format!("target-{}.dat", reflinks));
+            let retry = Retry { reflinks, ..retry() };
+            assert_eq!(3, retry.copy(&source, &target).unwrap());
+            assert_eq!("old", std::fs::read_to_string(target.interpret()).unwrap());
+
+            // Overwriting an existing target falls back to a normal copy.
+            assert_eq!(3, retry.copy(&source, &target).unwrap());
+        }
+
+        std::fs::write(source.interpret(), "new").unwrap();
+        let copied = std::fs::read_to_string(folder.joined("target-false.dat").interpret()).unwrap();
+        let reflinked = std::fs::read_to_string(folder.joined("target-true.dat").interpret()).unwrap();
+
+        assert_eq!("old", copied);
+        assert_eq!("old", reflinked);
+    }
+
+    #[test]
+    fn does_not_retry_when_cancelled() {
+        let retry = retry().cancellable(Arc::new(AtomicBool::new(true)));
+        let (result, calls) = run(&retry, 1, std::io::ErrorKind::TimedOut);
+        assert!(result.is_err());
+        assert_eq!(1, calls);
+    }
+}
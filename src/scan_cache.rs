@@ -0,0 +1,451 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    hash::{Hash, Hasher},
+    sync::Mutex,
+};
+
+use crate::{
+    config::{Config, RootsConfig},
+    manifest::{Manifest, Store},
+    path::StrictPath,
+    prelude::{manifest_override, state_dir, ScanInfo, ScannedFile, SKIP},
+};
+
+const CACHE_FILE_NAME: &str = "scan-cache.json";
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+struct CachedFile {
+    /// StrictPath in rendered form.
+    path: String,
+    size: u64,
+    /// Nanoseconds since the epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    modified: Option<u64>,
+    #[serde(default, skip_serializing_if = "crate::serialization::is_false")]
+    ignored: bool,
+    #[serde(default, skip_serializing_if = "crate::serialization::is_false")]
+    system: bool,
+    #[serde(default, rename = "xboxName", skip_serializing_if = "Option::is_none")]
+    xbox_name: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+struct CachedGame {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    files: Vec<CachedFile>,
+    #[serde(
+        default,
+        rename = "cloudOnly",
+        skip_serializing_if = "crate::serialization::is_false"
+    )]
+    cloud_only: bool,
+    /// Folders that the result depends on, with their modification times
+    /// in nanoseconds since the epoch (or none if the folder didn't exist).
+    #[serde(default)]
+    folders: BTreeMap<String, Option<u64>>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+struct CacheFile {
+    /// Fingerprint of the settings that the scans were done with.
+    settings: String,
+    #[serde(default)]
+    games: BTreeMap<String, CachedGame>,
+}
+
+/// Results of earlier backup scans, so that a repeated preview doesn't need to
+/// search the disk again for games whose folders haven't changed.
+#[derive(Debug, Default)]
+pub struct ScanCache {
+    file: StrictPath,
+    data: CacheFile,
+    hits: usize,
+}
+
+impl ScanCache {
+    /// Load the cache, discarding it if it was made with different settings.
+    pub fn load(config: &Config, wine_prefix: &Option<StrictPath>) -> Self {
+        Self::load_from(
+            StrictPath::from(state_dir()).joined(CACHE_FILE_NAME),
+            fingerprint(config, wine_prefix),
+        )
+    }
+
+    fn load_from(file: StrictPath, settings: String) -> Self {
+        let data = std::fs::read_to_string(file.interpret())
+            .ok()
+            .and_then(|x| serde_json::from_str::<CacheFile>(&x).ok())
+            .filter(|x| x.settings == settings)
+            .unwrap_or(CacheFile {
+                settings,
+                games: BTreeMap::new(),
+            });
+        Self { file, data, hits: 0 }
+    }
+
+    pub fn save(&self) {
+        let result = self
+            .file
+            .as_std_path_buf()
+            .parent()
+            .map(std::fs::create_dir_all)
+            .unwrap_or(Ok(()))
+            .and_then(|_| std::fs::write(self.file.interpret(), serde_json::to_string(&self.data).unwrap()));
+        if let Err(e) = result {
+            log::warn!("Unable to save scan cache to {}: {}", self.file.render(), e);
+        }
+    }
+
+    /// How many scans have been served from the cache since it was loaded.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// The cached scan of this game, unless any folder it depends on has changed since,
+    /// or any of its files has a different size or modification time.
+    /// Editing a file in place doesn't change its folder, so the files are checked too.
+    pub fn get(&mut self, name: &str) -> Option<ScanInfo> {
+        let cached = self.data.games.get(name)?;
+        if cached
+            .folders
+            .iter()
+            .any(|(folder, time)| modified_time(&StrictPath::new(folder.clone())) != *time)
+        {
+            return None;
+        }
+        if cached.files.iter().any(|file| {
+            let path = StrictPath::new(file.path.clone());
+            std::fs::metadata(path.interpret()).map(|x| x.len()).ok() != Some(file.size)
+                || modified_time(&path) != file.modified
+        }) {
+            return None;
+        }
+
+        self.hits += 1;
+        Some(ScanInfo {
+            game_name: name.to_string(),
+            found_files: cached
+                .files
+                .iter()
+                .map(|x| ScannedFile {
+                    path: StrictPath::new(x.path.clone()),
+                    size: x.size,
+                    original_path: None,
+                    ignored: x.ignored,
+                    system: x.system,
+                    partial: false,
+                    xbox_name: x.xbox_name.clone(),
+                })
+                .collect(),
+            cloud_only: cached.cloud_only,
+            ..Default::default()
+        })
+    }
+
+    pub fn insert(&mut self, scan_info: &ScanInfo, anchors: &[StrictPath]) {
+        let mut files: Vec<_> = scan_info
+            .found_files
+            .iter()
+            .map(|x| CachedFile {
+                path: x.path.render(),
+                size: x.size,
+                modified: modified_time(&x.path),
+                ignored: x.ignored,
+                system: x.system,
+                xbox_name: x.xbox_name.clone(),
+            })
+            .collect();
+        files.sort_by(|x, y| x.path.cmp(&y.path));
+
+        self.data.games.insert(
+            scan_info.game_name.clone(),
+            CachedGame {
+                files,
+                cloud_only: scan_info.cloud_only,
+                folders: anchors.iter().map(|x| (x.render(), modified_time(x))).collect(),
+            },
+        );
+    }
+}
+
+/// Reuse the cached scan of a game if `reuse` is set and it's still fresh.
+/// Otherwise, run `scan` and remember the result for next time.
+pub fn scan_with_cache(
+    cache: &Mutex<ScanCache>,
+    name: &str,
+    reuse: bool,
+    scan: impl FnOnce() -> (ScanInfo, Option<Vec<StrictPath>>),
+) -> ScanInfo {
+    if reuse {
+        if let Some(scan_info) = cache.lock().unwrap().get(name) {
+            return scan_info;
+        }
+    }
+
+    let (scan_info, anchors) = scan();
+    match anchors {
+        Some(anchors) => cache.lock().unwrap().insert(&scan_info, &anchors),
+        None => {
+            cache.lock().unwrap().data.games.remove(name);
+        }
+    }
+    scan_info
+}
+
+/// Anything in the config (or the manifest) that affects what a scan finds.
+fn fingerprint(config: &Config, wine_prefix: &Option<StrictPath>) -> String {
+    let manifest_file = manifest_override()
+        .or_else(|| config.manifest.path.clone())
+        .unwrap_or_else(|| StrictPath::from(Manifest::file()));
+    let settings = serde_json::json!({
+        "manifest": manifest_file.render(),
+        "manifestModified": modified_time(&manifest_file),
+        "roots": config.roots,
+        "filter": config.backup.filter,
+        "toggledPaths": config.backup.toggled_paths,
+        "toggledRegistry": config.backup.toggled_registry,
+        "customGames": config.custom_games,
+        "winePrefix": wine_prefix,
+    });
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    settings.to_string().hash(&mut hasher);
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+fn modified_time(path: &StrictPath) -> Option<u64> {
+    std::fs::metadata(path.interpret())
+        .and_then(|x| x.modified())
+        .ok()
+        .and_then(|x| x.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|x| x.as_nanos() as u64)
+}
+
+/// For each path that a scan will check, the deepest folder along it that exists now.
+/// Creating the next part of the path changes that folder's modification time.
+/// Install folders are found by listing the roots, so those are included as well.
+pub fn anchors(paths_to_check: &HashSet<StrictPath>, roots: &[RootsConfig]) -> Vec<StrictPath> {
+    let mut folders = HashSet::new();
+
+    for path in paths_to_check {
+        let interpreted = path.interpret();
+        let mut folder = std::path::PathBuf::new();
+        for component in std::path::Path::new(&interpreted).components() {
+            if component.as_os_str().to_string_lossy().contains(&['*', '?', '['][..]) {
+                break;
+            }
+            folder.push(component);
+        }
+        while !folder.is_dir() {
+            if !folder.pop() {
+                break;
+            }
+        }
+        if !folder.as_os_str().is_empty() {
+            folders.insert(StrictPath::from_std_path_buf(&folder).render());
+        }
+    }
+
+    for root in roots {
+        if root.path.raw().contains(SKIP) {
+            continue;
+        }
+        folders.insert(root.path.render());
+        if root.store == Store::Steam {
+            folders.insert(root.path.joined("steamapps/common").render());
+        }
+    }
+
+    let mut folders: Vec<_> = folders.into_iter().collect();
+    folders.sort();
+    folders.into_iter().map(StrictPath::new).collect()
+}
+
+/// The folders directly containing the files that were found, so that new or removed files are noticed.
+pub fn file_anchors(found_files: &HashSet<ScannedFile>) -> Vec<StrictPath> {
+    let mut folders: Vec<_> = found_files
+        .iter()
+        .filter_map(|x| x.path.as_std_path_buf().parent().map(|x| StrictPath::from(x).render()))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    folders.sort();
+    folders.into_iter().map(StrictPath::new).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::TempPath;
+    use pretty_assertions::assert_eq;
+
+    fn folder(name: &str) -> TempPath {
+        let folder = TempPath::new(name);
+        std::fs::create_dir_all(folder.joined("saves").interpret()).unwrap();
+        std::fs::write(folder.joined("saves/file1.txt").interpret(), "1").unwrap();
+        folder
+    }
+
+    /// Scans `saves`, counting how many times the disk was actually searched.
+    fn scan(cache: &Mutex<ScanCache>, base: &StrictPath, reuse: bool, scans: &mut usize) -> ScanInfo {
+        scan_with_cache(cache, "game1", reuse, || {
+            *scans += 1;
+            let saves = base.joined("saves");
+            let found_files: HashSet<_> = std::fs::read_dir(saves.interpret())
+                .unwrap()
+                .filter_map(|x| x.ok())
+                .map(|x| ScannedFile::new(StrictPath::from(x.path()).render(), x.metadata().unwrap().len()))
+                .collect();
+            let mut anchors = anchors(&HashSet::from([saves.joined("*.txt")]), &[]);
+            anchors.extend(file_anchors(&found_files));
+            (
+                ScanInfo {
+                    game_name: "game1".to_string(),
+                    found_files,
+                    ..Default::default()
+                },
+                Some(anchors),
+            )
+        })
+    }
+
+    #[test]
+    fn reuses_scan_while_folders_are_unchanged() {
+        let base = folder("ludusavi-test-scan-cache-hit");
+        let cache_file = base.joined(CACHE_FILE_NAME);
+        let mut scans = 0;
+
+        let cache = Mutex::new(ScanCache::load_from(cache_file.clone(), "settings".to_string()));
+        let first = scan(&cache, &base, true, &mut scans);
+        cache.lock().unwrap().save();
+
+        let cache = Mutex::new(ScanCache::load_from(cache_file, "settings".to_string()));
+        let second = scan(&cache, &base, true, &mut scans);
+        let hits = cache.lock().unwrap().hits();
+
+        assert_eq!(1, scans);
+        assert_eq!(1, hits);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn scans_again_when_folder_changes() {
+        let base = folder("ludusavi-test-scan-cache-changed");
+        let mut scans = 0;
+
+        let cache = Mutex::new(ScanCache::load_from(
+            base.joined(CACHE_FILE_NAME),
+            "settings".to_string(),
+        ));
+        scan(&cache, &base, true, &mut scans);
+        std::fs::write(base.joined("saves/file2.txt").interpret(), "2").unwrap();
+        let second = scan(&cache, &base, true, &mut scans);
+
+        assert_eq!(2, scans);
+        assert_eq!(0, cache.lock().unwrap().hits());
+        assert_eq!(2, second.found_files.len());
+    }
+
+    #[test]
+    fn scans_again_when_file_changes_in_place() {
+        let base = folder("ludusavi-test-scan-cache-file-changed");
+        let file = base.joined("saves/file1.txt");
+        let mut scans = 0;
+
+        let cache = Mutex::new(ScanCache::load_from(
+            base.joined(CACHE_FILE_NAME),
+            "settings".to_string(),
+        ));
+        scan(&cache, &base, true, &mut scans);
+
+        // Same size, but a different modification time.
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(file.interpret())
+            .unwrap()
+            .set_modified(std::time::UNIX_EPOCH + std::time::Duration::from_secs(1))
+            .unwrap();
+        scan(&cache, &base, true, &mut scans);
+
+        // Different size, keeping the same modification time.
+        std::fs::write(file.interpret(), "22").unwrap();
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(file.interpret())
+            .unwrap()
+            .set_modified(std::time::UNIX_EPOCH + std::time::Duration::from_secs(1))
+            .unwrap();
+        scan(&cache, &base, true, &mut scans);
+
+        assert_eq!(3, scans);
+        assert_eq!(0, cache.lock().unwrap().hits());
+    }
+
+    #[test]
+    fn scans_again_when_folder_is_created() {
+        let base = folder("ludusavi-test-scan-cache-created");
+        let mut scans = 0;
+
+        let cache = Mutex::new(ScanCache::load_from(
+            base.joined(CACHE_FILE_NAME),
+            "settings".to_string(),
+        ));
+        let deeper = || {
+            anchors(
+                &HashSet::from([base.joined("missing/saves/*.txt")]),
+                &[RootsConfig {
+                    path: base.clone(),
+                    store: Store::Other,
+                    require: Default::default(),
+                }],
+            )
+        };
+        let no_scan = ScanInfo {
+            game_name: "game1".to_string(),
+            ..Default::default()
+        };
+        scan_with_cache(&cache, "game1", true, || {
+            scans += 1;
+            (no_scan.clone(), Some(deeper()))
+        });
+        std::fs::create_dir_all(base.joined("missing").interpret()).unwrap();
+        scan_with_cache(&cache, "game1", true, || {
+            scans += 1;
+            (no_scan.clone(), Some(deeper()))
+        });
+
+        assert_eq!(2, scans);
+    }
+
+    #[test]
+    fn scans_again_when_settings_change_or_reuse_is_off() {
+        let base = folder("ludusavi-test-scan-cache-miss");
+        let cache_file = base.joined(CACHE_FILE_NAME);
+        let mut scans = 0;
+
+        let cache = Mutex::new(ScanCache::load_from(cache_file.clone(), "settings".to_string()));
+        scan(&cache, &base, true, &mut scans);
+        scan(&cache, &base, false, &mut scans);
+        cache.lock().unwrap().save();
+
+        let cache = Mutex::new(ScanCache::load_from(cache_file, "other settings".to_string()));
+        scan(&cache, &base, true, &mut scans);
+
+        assert_eq!(3, scans);
+        assert_eq!(0, cache.lock().unwrap().hits());
+    }
+
+    #[test]
+    fn does_not_cache_scan_without_anchors() {
+        let mut scans = 0;
+        let cache = Mutex::new(ScanCache::default());
+        for _ in 0..2 {
+            scan_with_cache(&cache, "game1", true, || {
+                scans += 1;
+                (ScanInfo::default(), None)
+            });
+        }
+        assert_eq!(2, scans);
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{path::StrictPath, prelude::state_dir};
+
+const SCHEDULE_FILE_NAME: &str = "schedule.json";
+
+/// When backups last ran, for anything that runs Ludusavi on a schedule (like cron or a systemd timer).
+/// Ludusavi doesn't schedule anything itself.
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ScheduleState {
+    #[serde(default, rename = "lastRun", skip_serializing_if = "Option::is_none")]
+    pub last_run: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default, rename = "lastRunSucceeded")]
+    pub last_run_succeeded: bool,
+    #[serde(default, rename = "lastSuccess", skip_serializing_if = "Option::is_none")]
+    pub last_success: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl ScheduleState {
+    fn file() -> StrictPath {
+        StrictPath::from(state_dir()).joined(SCHEDULE_FILE_NAME)
+    }
+
+    pub fn load() -> Self {
+        Self::load_from(&Self::file())
+    }
+
+    fn load_from(file: &StrictPath) -> Self {
+        std::fs::read_to_string(file.interpret())
+            .ok()
+            .and_then(|x| serde_json::from_str(&x).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        self.save_to(&Self::file())
+    }
+
+    fn save_to(&self, file: &StrictPath) {
+        let result = file
+            .create_parent_dir()
+            .and_then(|_| std::fs::write(file.interpret(), serde_json::to_string(self).unwrap()));
+        if let Err(e) = result {
+            log::warn!("Unable to save backup schedule to {}: {}", file.render(), e);
+        }
+    }
+
+    /// A failed run is recorded as the last run, but the last success stays the same.
+    pub fn record_run(&mut self, now: chrono::DateTime<chrono::Utc>, succeeded: bool) {
+        self.last_run = Some(now);
+        self.last_run_succeeded = succeeded;
+        if succeeded {
+            self.last_success = Some(now);
+        }
+    }
+
+    /// When the next backup should happen, `interval_hours` after the last success.
+    /// Without any success yet, that's right away, so this is `None`.
+    pub fn next_recommended(&self, interval_hours: u32) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.last_success
+            .map(|x| x + chrono::Duration::hours(interval_hours as i64))
+    }
+
+    /// Record a backup run that just finished.
+    pub fn record(succeeded: bool) {
+        let mut state = Self::load();
+        state.record_run(chrono::Utc::now(), succeeded);
+        state.save();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::TempPath;
+    use pretty_assertions::assert_eq;
+
+    fn at(hour: u32) -> chrono::DateTime<chrono::Utc> {
+        chrono::NaiveDate::from_ymd(2000, 1, 2)
+            .and_hms(hour, 0, 0)
+            .and_local_timezone(chrono::Utc)
+            .unwrap()
+    }
+
+    #[test]
+    fn failed_run_does_not_advance_last_success() {
+        let file = TempPath::new("ludusavi-test-schedule.json");
+
+        let mut state = ScheduleState::load_from(&file);
+        assert_eq!(None, state.next_recommended(24));
+
+        state.record_run(at(1), true);
+        state.record_run(at(5), false);
+        state.save_to(&file);
+        let loaded = ScheduleState::load_from(&file);
+
+        assert_eq!(
+            ScheduleState {
+                last_run: Some(at(5)),
+                last_run_succeeded: false,
+                last_success: Some(at(1)),
+            },
+            loaded,
+        );
+        assert_eq!(Some(at(7)), loaded.next_recommended(6));
+    }
+}
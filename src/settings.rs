@@ -0,0 +1,274 @@
+use crate::{
+    archive::{pack_files, read_files},
+    config::Config,
+    manifest::Manifest,
+    prelude::{app_dir, Error, StrictPath},
+};
+
+const CONFIG_FILE_NAME: &str = "config.yaml";
+const MANIFEST_FILE_NAME: &str = "manifest.yaml";
+
+/// Where Ludusavi keeps its own settings, as opposed to game saves.
+#[derive(Clone, Debug)]
+pub struct SettingsFiles {
+    pub config: StrictPath,
+    pub manifest: StrictPath,
+}
+
+impl SettingsFiles {
+    pub fn current() -> Self {
+        Self {
+            config: StrictPath::from_std_path_buf(&app_dir()).joined(CONFIG_FILE_NAME),
+            manifest: StrictPath::from_std_path_buf(&Manifest::file()),
+        }
+    }
+}
+
+/// Bundle the config file and the cached manifest into one archive,
+/// for moving Ludusavi to another machine.
+pub fn export(files: &SettingsFiles, archive: &StrictPath) -> Result<(), Error> {
+    let mut entries = vec![(&files.config, CONFIG_FILE_NAME)];
+    if files.manifest.is_file() {
+        entries.push((&files.manifest, MANIFEST_FILE_NAME));
+    }
+    if pack_files(&entries, archive).is_err() {
+        // Don't leave a truncated archive that looks usable.
+        let _ = archive.remove();
+        return Err(Error::SettingsCannotBeExported { path: archive.clone() });
+    }
+    Ok(())
+}
+
+/// Apply settings from an archive made by `export`.
+/// The imported config is checked before anything is written.
+///
+/// With `replace`, the imported config takes the place of the current one,
+/// along with its manifest. Otherwise, its roots, custom games, ignored games, and redirects
+/// are added to the current config, and its manifest is only used if there isn't one already.
+///
+/// Either way, the current backup and restore folders are kept,
+/// so that importing never points Ludusavi at a different set of backups or writes over them.
+pub fn import(archive: &StrictPath, files: &SettingsFiles, replace: bool) -> Result<(), Error> {
+    let fail = || Error::SettingsCannotBeImported { path: archive.clone() };
+
+    let mut contents = read_files(archive, &[CONFIG_FILE_NAME, MANIFEST_FILE_NAME]).map_err(|_| fail())?;
+    let content = contents
+        .remove(CONFIG_FILE_NAME)
+        .and_then(|x| String::from_utf8(x).ok())
+        .ok_or_else(fail)?;
+    let imported = Config::load_from_string(&content)?;
+
+    let current = match std::fs::read_to_string(files.config.interpret()) {
+        Ok(content) => Config::load_from_string(&content)?,
+        Err(_) => Config::default(),
+    };
+
+    let manifest = contents
+        .remove(MANIFEST_FILE_NAME)
+        .filter(|_| replace || !files.manifest.is_file());
+    let use_manifest = manifest.is_some();
+
+    let mut config = if replace {
+        let mut config = imported;
+        config.backup.path = current.backup.path.clone();
+        config.backup.extra_targets = current.backup.extra_targets.clone();
+        config.restore.path = current.restore.path.clone();
+        if !use_manifest {
+            // The ETag would describe a manifest that we don't have.
+            config.manifest.etag = current.manifest.etag.clone();
+        }
+        config
+    } else {
+        merge(current, imported)
+    };
+    if use_manifest && !replace {
+        // Make sure the imported manifest isn't mistaken for an up-to-date one.
+        config.manifest.etag = None;
+    }
+
+    files.config.create_parent_dir().map_err(|_| fail())?;
+    std::fs::write(files.config.interpret(), serde_yaml::to_string(&config).unwrap()).map_err(|_| fail())?;
+    if let Some(manifest) = manifest {
+        files.manifest.create_parent_dir().map_err(|_| fail())?;
+        std::fs::write(files.manifest.interpret(), manifest).map_err(|_| fail())?;
+    }
+    Ok(())
+}
+
+/// Add the lists from `imported` to `current`, keeping all of `current`'s other settings.
+/// A custom game with the same name as an existing one replaces it.
+fn merge(mut current: Config, imported: Config) -> Config {
+    for root in imported.roots {
+        if !current
+            .roots
+            .iter()
+            .any(|x| x.path.interpret() == root.path.interpret())
+        {
+            current.roots.push(root);
+        }
+    }
+
+    for game in imported.custom_games {
+        match current.custom_games.iter_mut().find(|x| x.name == game.name) {
+            Some(existing) => *existing = game,
+            None => current.custom_games.push(game),
+        }
+    }
+
+    current.backup.ignored_games.extend(imported.backup.ignored_games);
+    current.restore.ignored_games.extend(imported.restore.ignored_games);
+    current.restore.frozen_games.extend(imported.restore.frozen_games);
+
+    for redirect in imported.restore.redirects {
+        if !current.restore.redirects.contains(&redirect) {
+            current.restore.redirects.push(redirect);
+        }
+    }
+    for (game, redirects) in imported.restore.game_redirects {
+        let existing = current.restore.game_redirects.entry(game).or_default();
+        for redirect in redirects {
+            if !existing.contains(&redirect) {
+                existing.push(redirect);
+            }
+        }
+    }
+
+    current
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{CustomGame, RedirectConfig, RootsConfig};
+    use crate::manifest::Store;
+    use crate::path::TempPath;
+    use pretty_assertions::assert_eq;
+
+    fn s(text: &str) -> String {
+        text.to_string()
+    }
+
+    fn files(folder: &StrictPath) -> SettingsFiles {
+        SettingsFiles {
+            config: folder.joined("config/config.yaml"),
+            manifest: folder.joined("data/manifest.yaml"),
+        }
+    }
+
+    fn custom_game(name: &str, file: &str) -> CustomGame {
+        CustomGame {
+            name: s(name),
+            ignore: false,
+            files: vec![s(file)],
+            registry: vec![],
+            registry_filters: Default::default(),
+            cloud_only: false,
+        }
+    }
+
+    fn write_config(files: &SettingsFiles, config: &Config) {
+        files.config.create_parent_dir().unwrap();
+        std::fs::write(files.config.interpret(), serde_yaml::to_string(config).unwrap()).unwrap();
+    }
+
+    fn read_config(files: &SettingsFiles) -> Config {
+        Config::load_from_string(&std::fs::read_to_string(files.config.interpret()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn can_export_and_import_settings_into_fresh_config_dir() {
+        let folder = TempPath::new("ludusavi-test-settings");
+        let old = files(&folder.joined("old"));
+        let new = files(&folder.joined("new"));
+        let archive = folder.joined("settings.tar.gz");
+
+        let mut config = Config::default();
+        config.roots.push(RootsConfig {
+            path: StrictPath::new(s("/games/steam")),
+            store: Store::Steam,
+            require: Default::default(),
+        });
+        config.custom_games.push(custom_game("Custom", "<home>/custom.sav"));
+        config.restore.redirects.push(RedirectConfig {
+            source: StrictPath::new(s("/old")),
+            target: StrictPath::new(s("/new")),
+        });
+        config.backup.path = StrictPath::new(s("/old-machine/backups"));
+        write_config(&old, &config);
+        old.manifest.create_parent_dir().unwrap();
+        std::fs::write(old.manifest.interpret(), "game: {}\n").unwrap();
+
+        let mut fresh = Config::default();
+        fresh.backup.path = StrictPath::new(s("/new-machine/backups"));
+        write_config(&new, &fresh);
+
+        export(&old, &archive).unwrap();
+        import(&archive, &new, false).unwrap();
+        let imported = read_config(&new);
+        let manifest = std::fs::read_to_string(new.manifest.interpret()).unwrap();
+
+        assert_eq!(config.roots, imported.roots);
+        assert_eq!(config.custom_games, imported.custom_games);
+        assert_eq!(config.restore.redirects, imported.restore.redirects);
+        assert_eq!(StrictPath::new(s("/new-machine/backups")), imported.backup.path);
+        assert_eq!("game: {}\n", manifest);
+    }
+
+    #[test]
+    fn can_merge_or_replace_imported_settings() {
+        let folder = TempPath::new("ludusavi-test-settings-modes");
+        let old = files(&folder.joined("old"));
+        let new = files(&folder.joined("new"));
+        let archive = folder.joined("settings.tar");
+
+        let mut config = Config::default();
+        config.custom_games.push(custom_game("Shared", "<home>/imported.sav"));
+        config.custom_games.push(custom_game("Imported", "<home>/imported.sav"));
+        config.runtime.copy_threads = 1;
+        write_config(&old, &config);
+
+        let mut current = Config::default();
+        current.custom_games.push(custom_game("Shared", "<home>/current.sav"));
+        current.custom_games.push(custom_game("Current", "<home>/current.sav"));
+        current.backup.path = StrictPath::new(s("/current/backups"));
+        write_config(&new, &current);
+
+        export(&old, &archive).unwrap();
+        import(&archive, &new, false).unwrap();
+        let merged = read_config(&new);
+        write_config(&new, &current);
+        import(&archive, &new, true).unwrap();
+        let replaced = read_config(&new);
+
+        assert_eq!(
+            vec![
+                custom_game("Shared", "<home>/imported.sav"),
+                custom_game("Current", "<home>/current.sav"),
+                custom_game("Imported", "<home>/imported.sav"),
+            ],
+            merged.custom_games,
+        );
+        assert_eq!(current.runtime.copy_threads, merged.runtime.copy_threads);
+
+        assert_eq!(config.custom_games, replaced.custom_games);
+        assert_eq!(1, replaced.runtime.copy_threads);
+        assert_eq!(StrictPath::new(s("/current/backups")), replaced.backup.path);
+    }
+
+    #[test]
+    fn rejects_invalid_imported_config_without_writing_anything() {
+        let folder = TempPath::new("ludusavi-test-settings-invalid");
+        let old = files(&folder.joined("old"));
+        let new = files(&folder.joined("new"));
+        let archive = folder.joined("settings.tar");
+
+        old.config.create_parent_dir().unwrap();
+        std::fs::write(old.config.interpret(), "roots: [").unwrap();
+        export(&old, &archive).unwrap();
+        let result = import(&archive, &new, true);
+        let written = new.config.exists();
+
+        assert!(matches!(result, Err(Error::ConfigInvalid { .. })));
+        assert!(!written);
+    }
+}
@@ -0,0 +1,432 @@
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signature, Signer, Verifier};
+
+use crate::{
+    config::{SignatureConfig, SignatureVerification},
+    layout::BackupLayout,
+    prelude::{BackupInfo, Error, FailureReason, ScannedFile, StrictPath},
+    verify_sample::digest,
+};
+
+/// Takes precedence over `signature.key` in the config.
+const KEY_ENV: &str = "LUDUSAVI_SIGNING_KEY";
+/// Takes precedence over `signature.publicKey` in the config.
+const PUBLIC_KEY_ENV: &str = "LUDUSAVI_SIGNING_PUBLIC_KEY";
+/// Lists the SHA-256 hash of every other file in the game's folder, like `sha256sum` does.
+const SIGNED_FILE: &str = "checksums.sha256";
+const SIGNATURE_FILE: &str = "checksums.sha256.sig";
+
+/// An ed25519 key, stored as the base64 encoding of its 32-byte secret.
+pub struct SigningKey(Keypair);
+
+impl SigningKey {
+    pub fn from_base64(text: &str) -> Option<Self> {
+        let bytes = base64::decode(text.trim()).ok()?;
+        let secret = SecretKey::from_bytes(&bytes).ok()?;
+        let public = PublicKey::from(&secret);
+        Some(Self(Keypair { secret, public }))
+    }
+
+    /// Look for a key in the environment, then in the file set in the config.
+    pub fn load(config: &SignatureConfig) -> Result<Option<Self>, Error> {
+        load_key(KEY_ENV, config.key.as_ref(), Self::from_base64)
+    }
+
+    pub fn verifying_key(&self) -> VerifyingKey {
+        VerifyingKey(self.0.public)
+    }
+
+    fn sign(&self, content: &[u8]) -> String {
+        base64::encode(self.0.sign(content).to_bytes())
+    }
+}
+
+/// The public half of a `SigningKey`, stored as the base64 encoding of its 32 bytes.
+/// This is all that's needed to check signatures, so a machine that only restores
+/// doesn't have to hold the secret.
+pub struct VerifyingKey(PublicKey);
+
+impl VerifyingKey {
+    pub fn from_base64(text: &str) -> Option<Self> {
+        let bytes = base64::decode(text.trim()).ok()?;
+        PublicKey::from_bytes(&bytes).ok().map(Self)
+    }
+
+    /// Look for a public key in the environment, then in the file set in the config,
+    /// and finally derive it from the signing key if there is one.
+    pub fn load(config: &SignatureConfig) -> Result<Option<Self>, Error> {
+        match load_key(PUBLIC_KEY_ENV, config.public_key.as_ref(), Self::from_base64)? {
+            Some(x) => Ok(Some(x)),
+            None => Ok(SigningKey::load(config)?.map(|x| x.verifying_key())),
+        }
+    }
+
+    pub fn to_base64(&self) -> String {
+        base64::encode(self.0.as_bytes())
+    }
+
+    fn verify(&self, content: &[u8], signature: &str) -> bool {
+        let signature = match base64::decode(signature.trim())
+            .ok()
+            .and_then(|x| Signature::try_from(x.as_slice()).ok())
+        {
+            Some(x) => x,
+            None => return false,
+        };
+        self.0.verify(content, &signature).is_ok()
+    }
+}
+
+/// Read a key from an environment variable or else a file,
+/// naming whichever one it came from if it can't be used.
+fn load_key<T>(env: &str, file: Option<&StrictPath>, parse: fn(&str) -> Option<T>) -> Result<Option<T>, Error> {
+    if let Ok(text) = std::env::var(env) {
+        return parse(&text).map(Some).ok_or_else(|| Error::SignatureKeyInvalid {
+            origin: env.to_string(),
+        });
+    }
+    match file {
+        Some(path) => {
+            let invalid = || Error::SignatureKeyInvalid { origin: path.render() };
+            let text = std::fs::read_to_string(path.interpret()).map_err(|_| invalid())?;
+            parse(&text).map(Some).ok_or_else(invalid)
+        }
+        None => Ok(None),
+    }
+}
+
+/// The hash of each file in a game's backup folder, by its path relative to the folder.
+fn hash_files(game_folder: &StrictPath) -> std::io::Result<std::collections::BTreeMap<String, String>> {
+    let root = game_folder.interpret();
+    let mut hashes = std::collections::BTreeMap::new();
+    for file in walkdir::WalkDir::new(&root).follow_links(false) {
+        let file = file?;
+        if !file.file_type().is_file() {
+            continue;
+        }
+        let relative = file
+            .path()
+            .strip_prefix(&root)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?
+            .to_string_lossy()
+            .replace('\\', "/");
+        if relative == SIGNED_FILE || relative == SIGNATURE_FILE {
+            continue;
+        }
+        let hash: String = digest(&StrictPath::from(&file))?
+            .iter()
+            .map(|x| format!("{:02x}", x))
+            .collect();
+        hashes.insert(relative, hash);
+    }
+    Ok(hashes)
+}
+
+fn render_checksums(hashes: &std::collections::BTreeMap<String, String>) -> String {
+    hashes
+        .iter()
+        .map(|(file, hash)| format!("{}  {}\n", hash, file))
+        .collect()
+}
+
+fn parse_checksums(content: &str) -> std::collections::BTreeMap<String, String> {
+    content
+        .lines()
+        .filter_map(|line| line.split_once("  "))
+        .map(|(hash, file)| (file.to_string(), hash.to_string()))
+        .collect()
+}
+
+/// Record the hash of every file in a game's backup folder and sign that list.
+pub fn sign_backup(game_folder: &StrictPath, key: &SigningKey) -> std::io::Result<()> {
+    let content = render_checksums(&hash_files(game_folder)?);
+    std::fs::write(game_folder.joined(SIGNED_FILE).interpret(), &content)?;
+    std::fs::write(
+        game_folder.joined(SIGNATURE_FILE).interpret(),
+        key.sign(content.as_bytes()),
+    )
+}
+
+/// Sign a game's backup in each target that has one.
+/// Where that fails, the signature is recorded as a failed file,
+/// so the game and that target count as failed.
+pub fn sign_game_backups(layouts: &[BackupLayout], game: &str, key: &SigningKey, info: &mut BackupInfo) -> Vec<Error> {
+    let mut errors = vec![];
+    for layout in layouts {
+        let folder = match layout.existing_game_folder(game) {
+            Some(x) => x,
+            None => continue,
+        };
+        if let Err(e) = sign_backup(&folder, key) {
+            let file = ScannedFile {
+                path: folder.joined(SIGNATURE_FILE),
+                size: 0,
+                original_path: None,
+                ignored: false,
+                system: false,
+                partial: false,
+                xbox_name: None,
+            };
+            info.failure_reasons.insert(file.clone(), FailureReason::from(&e));
+            info.failed_files.insert(file);
+            info.targets.insert(layout.base.clone(), false);
+            errors.push(Error::BackupSigningFailed { path: folder });
+        }
+    }
+    errors
+}
+
+/// Check that a game's list of hashes has a valid signature and that every file still matches it.
+/// A file that was changed, added, or removed fails with its own path.
+/// A backup without any signature, like one made before signing was enabled, fails too.
+pub fn verify_backup(game_folder: &StrictPath, key: &VerifyingKey) -> Result<(), Error> {
+    let fail = |path: StrictPath| Error::BackupSignatureInvalid { path };
+    let content =
+        std::fs::read_to_string(game_folder.joined(SIGNED_FILE).interpret()).map_err(|_| fail(game_folder.clone()))?;
+    let signature = std::fs::read_to_string(game_folder.joined(SIGNATURE_FILE).interpret())
+        .map_err(|_| fail(game_folder.clone()))?;
+    if !key.verify(content.as_bytes(), &signature) {
+        return Err(fail(game_folder.clone()));
+    }
+
+    let signed = parse_checksums(&content);
+    let actual = hash_files(game_folder).map_err(|_| fail(game_folder.clone()))?;
+    match signed
+        .keys()
+        .chain(actual.keys())
+        .find(|file| signed.get(*file) != actual.get(*file))
+    {
+        Some(file) => Err(fail(game_folder.joined(file))),
+        None => Ok(()),
+    }
+}
+
+/// Verify the backups of these games as configured before restoring them.
+/// In warning mode, any problems are returned instead of refusing the restore.
+pub fn check_restore(layout: &BackupLayout, games: &[String], config: &SignatureConfig) -> Result<Vec<Error>, Error> {
+    if config.verify == SignatureVerification::Off {
+        return Ok(vec![]);
+    }
+    let key = match VerifyingKey::load(config)? {
+        Some(x) => x,
+        None => return Err(Error::SignatureKeyMissing),
+    };
+
+    let mut warnings = vec![];
+    for game in games {
+        let folder = match layout.existing_game_folder(game) {
+            Some(x) => x,
+            None => continue,
+        };
+        if let Err(e) = verify_backup(&folder, &key) {
+            match config.verify {
+                SignatureVerification::Require => return Err(e),
+                _ => warnings.push(e),
+            }
+        }
+    }
+    Ok(warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::TempPath;
+    use pretty_assertions::assert_eq;
+
+    const KEY: &str = "AQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHyA=";
+    const OTHER_KEY: &str = "ICEiIyQlJicoKSorLC0uLzAxMjM0NTY3ODk6Ozw9Pj8=";
+
+    fn game_folder(name: &str) -> TempPath {
+        let folder = TempPath::new(name);
+        std::fs::create_dir_all(folder.joined("backup-1/drive-0").interpret()).unwrap();
+        std::fs::write(folder.joined("mapping.yaml").interpret(), "name: game\n").unwrap();
+        std::fs::write(folder.joined("backup-1/drive-0/save.dat").interpret(), "a").unwrap();
+        folder
+    }
+
+    #[test]
+    fn can_sign_and_verify_backup() {
+        let folder = game_folder("ludusavi-test-signature-round-trip");
+        let key = SigningKey::from_base64(KEY).unwrap();
+
+        sign_backup(&folder, &key).unwrap();
+        let result = verify_backup(&folder, &key.verifying_key());
+
+        assert_eq!(Ok(()), result);
+    }
+
+    #[test]
+    fn rejects_tampered_backup() {
+        let folder = game_folder("ludusavi-test-signature-tampered");
+        let key = SigningKey::from_base64(KEY).unwrap();
+
+        sign_backup(&folder, &key).unwrap();
+        std::fs::write(folder.joined(SIGNED_FILE).interpret(), "").unwrap();
+        let result = verify_backup(&folder, &key.verifying_key());
+
+        assert_eq!(Err(Error::BackupSignatureInvalid { path: folder.clone() }), result);
+    }
+
+    #[test]
+    fn rejects_backup_with_tampered_file() {
+        let folder = game_folder("ludusavi-test-signature-tampered-file");
+        let key = SigningKey::from_base64(KEY).unwrap();
+
+        sign_backup(&folder, &key).unwrap();
+        std::fs::write(folder.joined("backup-1/drive-0/save.dat").interpret(), "b").unwrap();
+        let result = verify_backup(&folder, &key.verifying_key());
+
+        assert_eq!(
+            Err(Error::BackupSignatureInvalid {
+                path: folder.joined("backup-1/drive-0/save.dat")
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn rejects_backup_with_added_or_removed_file() {
+        let folder = game_folder("ludusavi-test-signature-added-file");
+        let key = SigningKey::from_base64(KEY).unwrap();
+
+        sign_backup(&folder, &key).unwrap();
+        std::fs::write(folder.joined("backup-1/drive-0/extra.dat").interpret(), "a").unwrap();
+        let added = verify_backup(&folder, &key.verifying_key());
+        std::fs::remove_file(folder.joined("backup-1/drive-0/extra.dat").interpret()).unwrap();
+        std::fs::remove_file(folder.joined("backup-1/drive-0/save.dat").interpret()).unwrap();
+        let removed = verify_backup(&folder, &key.verifying_key());
+
+        assert_eq!(
+            Err(Error::BackupSignatureInvalid {
+                path: folder.joined("backup-1/drive-0/extra.dat")
+            }),
+            added
+        );
+        assert_eq!(
+            Err(Error::BackupSignatureInvalid {
+                path: folder.joined("backup-1/drive-0/save.dat")
+            }),
+            removed
+        );
+    }
+
+    #[test]
+    fn rejects_backup_signed_with_other_key() {
+        let folder = game_folder("ludusavi-test-signature-other-key");
+
+        sign_backup(&folder, &SigningKey::from_base64(OTHER_KEY).unwrap()).unwrap();
+        let result = verify_backup(&folder, &SigningKey::from_base64(KEY).unwrap().verifying_key());
+
+        assert_eq!(Err(Error::BackupSignatureInvalid { path: folder.clone() }), result);
+    }
+
+    #[test]
+    fn checks_unsigned_backups_depending_on_mode() {
+        let layout = BackupLayout::new(
+            StrictPath::new(format!("{}/tests/backup", env!("CARGO_MANIFEST_DIR"))),
+            crate::config::Retention::default(),
+        );
+        let games = vec!["game1".to_string()];
+        let key_file = TempPath::new("ludusavi-test-signature-key");
+        std::fs::write(key_file.interpret(), KEY).unwrap();
+        let config = |verify| SignatureConfig {
+            key: Some(key_file.clone()),
+            verify,
+            ..Default::default()
+        };
+        let unsigned = Error::BackupSignatureInvalid {
+            path: layout.existing_game_folder("game1").unwrap(),
+        };
+
+        let off = check_restore(&layout, &games, &config(SignatureVerification::Off));
+        let warn = check_restore(&layout, &games, &config(SignatureVerification::Warn));
+        let require = check_restore(&layout, &games, &config(SignatureVerification::Require));
+
+        assert_eq!(Ok(vec![]), off);
+        assert_eq!(Ok(vec![unsigned.clone()]), warn);
+        assert_eq!(Err(unsigned), require);
+    }
+
+    #[test]
+    fn verifies_with_only_the_public_key() {
+        let folder = game_folder("ludusavi-test-signature-public-key");
+        let key = SigningKey::from_base64(KEY).unwrap();
+        sign_backup(&folder, &key).unwrap();
+        let public_key_file = TempPath::new("ludusavi-test-signature-public-key.pub");
+        std::fs::write(public_key_file.interpret(), key.verifying_key().to_base64()).unwrap();
+        let config = SignatureConfig {
+            public_key: Some(public_key_file.clone()),
+            verify: SignatureVerification::Require,
+            ..Default::default()
+        };
+
+        let loaded = VerifyingKey::load(&config).unwrap().unwrap();
+        let result = verify_backup(&folder, &loaded);
+
+        assert_eq!(key.verifying_key().to_base64(), loaded.to_base64());
+        assert_eq!(Ok(()), result);
+        assert!(SigningKey::load(&config).unwrap().is_none());
+    }
+
+    #[test]
+    fn names_where_an_invalid_key_came_from() {
+        let key_file = TempPath::new("ludusavi-test-signature-bad-key");
+        std::fs::write(key_file.interpret(), "not a key").unwrap();
+        let config = SignatureConfig {
+            public_key: Some(key_file.clone()),
+            verify: SignatureVerification::Warn,
+            ..Default::default()
+        };
+
+        let result = VerifyingKey::load(&config).map(|_| ());
+
+        assert_eq!(
+            Err(Error::SignatureKeyInvalid {
+                origin: key_file.render()
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn requires_a_key_to_verify() {
+        let layout = BackupLayout::new(
+            StrictPath::new(format!("{}/tests/backup", env!("CARGO_MANIFEST_DIR"))),
+            crate::config::Retention::default(),
+        );
+        let config = SignatureConfig {
+            verify: SignatureVerification::Warn,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            Err(Error::SignatureKeyMissing),
+            check_restore(&layout, &["game1".to_string()], &config)
+        );
+    }
+
+    #[test]
+    fn fails_game_when_signing_fails() {
+        let base = TempPath::new("ludusavi-test-signature-fails");
+        let layout = BackupLayout::new(base.clone(), crate::config::Retention::default());
+        let folder = layout.game_folder("game");
+        std::fs::create_dir_all(folder.joined(SIGNED_FILE).interpret()).unwrap();
+        let mut info = BackupInfo::default();
+
+        let errors = sign_game_backups(&[layout], "game", &SigningKey::from_base64(KEY).unwrap(), &mut info);
+
+        assert_eq!(vec![Error::BackupSigningFailed { path: folder.clone() }], errors);
+        assert!(!info.successful());
+        assert_eq!(Some(&false), info.targets.get(&base));
+        assert!(info
+            .failed_files
+            .iter()
+            .any(|x| x.path == folder.joined(SIGNATURE_FILE)));
+    }
+
+    #[test]
+    fn rejects_invalid_key() {
+        assert!(SigningKey::from_base64("not a key").is_none());
+        assert!(SigningKey::from_base64("AQID").is_none());
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{
+    config::{RootsConfig, SteamUser},
+    manifest::Store,
+    prelude::{StrictPath, SKIP},
+};
+
+/// `userdata` folders are named by account ID, which is a SteamID64 minus this.
+const STEAM_ID_64_BASE: u64 = 76_561_197_960_265_728;
+
+/// Find the account that most recently logged in to this Steam installation.
+pub fn current_user(root: &StrictPath) -> Option<u32> {
+    let content = std::fs::read_to_string(root.joined("config/loginusers.vdf").interpret()).ok()?;
+    most_recent_user(&content)
+}
+
+fn most_recent_user(vdf: &str) -> Option<u32> {
+    let mut user = None;
+    for line in vdf.lines() {
+        let tokens: Vec<_> = line.split('"').skip(1).step_by(2).collect();
+        match tokens.as_slice() {
+            [key] => user = key.parse::<u64>().ok(),
+            [key, "1"] if key.eq_ignore_ascii_case("MostRecent") => {
+                return user.and_then(|x| x.checked_sub(STEAM_ID_64_BASE)).map(|x| x as u32);
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// What to put in place of a `userdata` folder name in a glob for this root.
+/// If the current account can't be detected, this won't match anything,
+/// so that no other account's saves are picked up instead.
+pub fn user_folder_glob(filter: &SteamUser, root: &RootsConfig) -> String {
+    if root.store != Store::Steam {
+        return "*".to_string();
+    }
+    match filter {
+        SteamUser::All => "*".to_string(),
+        SteamUser::Current => current_user(&root.path)
+            .map(|x| x.to_string())
+            .unwrap_or_else(|| SKIP.to_string()),
+        SteamUser::Id(id) => id.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn can_find_most_recent_user() {
+        let vdf = r#"
+"users"
+{
+    "76561197960265729"
+    {
+        "AccountName"		"first"
+        "MostRecent"		"0"
+    }
+    "76561197960265730"
+    {
+        "AccountName"		"second"
+        "MostRecent"		"1"
+    }
+}
+"#;
+        assert_eq!(Some(2), most_recent_user(vdf));
+    }
+
+    #[test]
+    fn finds_no_user_without_most_recent_flag() {
+        let vdf = r#"
+"users"
+{
+    "76561197960265729"
+    {
+        "AccountName"		"first"
+        "MostRecent"		"0"
+    }
+}
+"#;
+        assert_eq!(None, most_recent_user(vdf));
+        assert_eq!(None, most_recent_user(""));
+    }
+}
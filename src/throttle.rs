@@ -0,0 +1,212 @@
+use std::io::{Read, Write};
+
+/// How much is read and written at a time when copying with a throttle.
+const CHUNK_BYTES: usize = 64 * 1024;
+/// Longest single sleep while waiting on the throttle, so that cancellation is noticed quickly.
+const MAX_SLEEP: std::time::Duration = std::time::Duration::from_millis(50);
+
+#[derive(Debug)]
+struct Bucket {
+    /// Negative when more has been used than is available, which has to be waited off.
+    tokens: f64,
+    last_refill: std::time::Instant,
+}
+
+impl Bucket {
+    /// Use up `bytes` as of `now`, and get how long to wait until they're available.
+    fn reserve(&mut self, bytes: usize, bytes_per_second: f64, now: std::time::Instant) -> std::time::Duration {
+        let refill = now.duration_since(self.last_refill).as_secs_f64() * bytes_per_second;
+        // Allow at most a second's worth of burst after sitting idle.
+        self.tokens = (self.tokens + refill).min(bytes_per_second);
+        self.last_refill = now;
+        self.tokens -= bytes as f64;
+        if self.tokens >= 0.0 {
+            std::time::Duration::ZERO
+        } else {
+            std::time::Duration::from_secs_f64(-self.tokens / bytes_per_second)
+        }
+    }
+}
+
+/// A token bucket that limits how many bytes per second all copies can use together.
+#[derive(Debug)]
+pub struct Throttle {
+    bytes_per_second: f64,
+    bucket: std::sync::Mutex<Bucket>,
+}
+
+impl Throttle {
+    pub fn new(bytes_per_second: u64) -> Self {
+        Self {
+            bytes_per_second: bytes_per_second.max(1) as f64,
+            bucket: std::sync::Mutex::new(Bucket {
+                tokens: 0.0,
+                last_refill: std::time::Instant::now(),
+            }),
+        }
+    }
+
+    pub fn from_megabytes_per_second(limit: u32) -> Self {
+        Self::new(limit as u64 * 1024 * 1024)
+    }
+
+    /// Use up `bytes`, waiting until they're available.
+    /// Returns false without waiting any longer once `is_cancelled` is true.
+    pub fn take(&self, bytes: usize, is_cancelled: impl Fn() -> bool) -> bool {
+        let mut wait = self
+            .bucket
+            .lock()
+            .unwrap()
+            .reserve(bytes, self.bytes_per_second, std::time::Instant::now());
+        if wait.is_zero() {
+            return true;
+        }
+
+        while !wait.is_zero() {
+            if is_cancelled() {
+                return false;
+            }
+            let step = wait.min(MAX_SLEEP);
+            std::thread::sleep(step);
+            wait -= step;
+        }
+        !is_cancelled()
+    }
+}
+
+/// Like `std::fs::copy`, but in chunks that each wait on the throttle.
+pub fn copy(
+    source: &std::path::Path,
+    target: &std::path::Path,
+    throttle: &Throttle,
+    is_cancelled: impl Fn() -> bool,
+) -> std::io::Result<u64> {
+    let mut reader = std::fs::File::open(source)?;
+    let permissions = reader.metadata()?.permissions();
+    let mut writer = std::fs::File::create(target)?;
+    let mut buffer = vec![0; CHUNK_BYTES];
+    let mut total = 0;
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        if !throttle.take(read, &is_cancelled) {
+            return Err(std::io::Error::other("Copy was cancelled"));
+        }
+        writer.write_all(&buffer[..read])?;
+        total += read as u64;
+    }
+    writer.flush()?;
+    drop(writer);
+    std::fs::set_permissions(target, permissions)?;
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::TempPath;
+
+    fn timed_copy(name: &str, bytes: usize, limit: u64) -> std::time::Duration {
+        let base = TempPath::new(name);
+        std::fs::create_dir_all(base.interpret()).unwrap();
+        let source = base.joined("source.bin");
+        let target = base.joined("target.bin");
+        std::fs::write(source.interpret(), vec![1u8; bytes]).unwrap();
+
+        let start = std::time::Instant::now();
+        let copied = copy(
+            &source.as_std_path_buf(),
+            &target.as_std_path_buf(),
+            &Throttle::new(limit),
+            || false,
+        );
+        let elapsed = start.elapsed();
+        let written = std::fs::read(target.interpret()).map(|x| x.len()).unwrap_or_default();
+
+        assert_eq!(bytes as u64, copied.unwrap());
+        assert_eq!(bytes, written);
+        elapsed
+    }
+
+    /// How long copying `bytes` in chunks would wait, starting with an empty bucket,
+    /// if each chunk is only taken once the wait for the previous one is over.
+    fn total_wait(bytes: usize, limit: u64) -> std::time::Duration {
+        let start = std::time::Instant::now();
+        let mut bucket = Bucket {
+            tokens: 0.0,
+            last_refill: start,
+        };
+        let mut now = start;
+        for _ in 0..bytes / CHUNK_BYTES {
+            now += bucket.reserve(CHUNK_BYTES, limit as f64, now);
+        }
+        now - start
+    }
+
+    #[test]
+    fn wait_scales_with_limit() {
+        let bytes = 512 * 1024;
+        let millis = |limit| total_wait(bytes, limit).as_secs_f64() * 1000.0;
+
+        assert!(
+            (millis(4 * 1024 * 1024) - 125.0).abs() < 1.0,
+            "{}",
+            millis(4 * 1024 * 1024)
+        );
+        assert!((millis(1024 * 1024) - 500.0).abs() < 1.0, "{}", millis(1024 * 1024));
+    }
+
+    #[test]
+    fn idle_time_only_allows_one_second_of_burst() {
+        let start = std::time::Instant::now();
+        let mut bucket = Bucket {
+            tokens: 0.0,
+            last_refill: start,
+        };
+        let later = start + std::time::Duration::from_secs(10);
+
+        assert_eq!(std::time::Duration::ZERO, bucket.reserve(1000, 1000.0, later));
+        assert_eq!(
+            std::time::Duration::from_millis(500),
+            bucket.reserve(500, 1000.0, later)
+        );
+    }
+
+    /// This depends on the machine's timing, so it's only for checking by hand.
+    /// Run with `cargo test -- --ignored copy_time_scales_with_limit`.
+    #[test]
+    #[ignore]
+    fn copy_time_scales_with_limit() {
+        let bytes = 512 * 1024;
+        let fast = timed_copy("ludusavi-test-throttle-fast", bytes, 4 * 1024 * 1024);
+        let slow = timed_copy("ludusavi-test-throttle-slow", bytes, 1024 * 1024);
+
+        // About 0.125 and 0.5 seconds, with room for a slow machine.
+        assert!(fast >= std::time::Duration::from_millis(100), "{:?}", fast);
+        assert!(slow >= std::time::Duration::from_millis(450), "{:?}", slow);
+        assert!(slow < std::time::Duration::from_millis(1500), "{:?}", slow);
+        assert!(slow > fast * 2, "{:?} vs {:?}", slow, fast);
+    }
+
+    #[test]
+    fn stops_waiting_promptly_when_cancelled() {
+        let throttle = Throttle::new(1);
+        let cancelled = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let canceller = {
+            let cancelled = cancelled.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(std::time::Duration::from_millis(100));
+                cancelled.store(true, std::sync::atomic::Ordering::Relaxed);
+            })
+        };
+
+        let start = std::time::Instant::now();
+        let taken = throttle.take(1024 * 1024, || cancelled.load(std::sync::atomic::Ordering::Relaxed));
+        canceller.join().unwrap();
+
+        assert!(!taken);
+        assert!(start.elapsed() < std::time::Duration::from_secs(1));
+    }
+}
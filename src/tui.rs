@@ -0,0 +1,727 @@
+use std::{
+    collections::BTreeSet,
+    io::{BufRead, Write},
+};
+
+use crossterm::{
+    event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    tty::IsTty,
+};
+use ratatui::{
+    backend::{Backend, CrosstermBackend},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::Spans,
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Tabs, Wrap},
+    Frame, Terminal,
+};
+
+use crate::{
+    cli::{run_cli, CliOutputFile, Subcommand},
+    config::Config,
+    lang::Translator,
+    prelude::Error,
+};
+
+/// How far Page Up and Page Down move the cursor.
+const PAGE_SIZE: usize = 10;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Mode {
+    Backup,
+    Restore,
+}
+
+impl Mode {
+    fn toggled(self) -> Self {
+        match self {
+            Self::Backup => Self::Restore,
+            Self::Restore => Self::Backup,
+        }
+    }
+}
+
+/// Which games are chosen from a list, and which ones are shown under the current filter.
+/// Games stay chosen when the filter hides them.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Selection {
+    games: Vec<String>,
+    chosen: BTreeSet<String>,
+    filter: String,
+    cursor: usize,
+}
+
+impl Selection {
+    pub fn new(mut games: Vec<String>) -> Self {
+        games.sort();
+        games.dedup();
+        Self {
+            games,
+            ..Default::default()
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.games.len()
+    }
+
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+
+    /// The games that match the filter, ignoring case.
+    pub fn visible(&self) -> Vec<&String> {
+        let filter = self.filter.to_lowercase();
+        self.games
+            .iter()
+            .filter(|x| filter.is_empty() || x.to_lowercase().contains(&filter))
+            .collect()
+    }
+
+    /// Where the cursor is among the visible games, if there are any.
+    pub fn cursor(&self) -> Option<usize> {
+        let count = self.visible().len();
+        (count > 0).then(|| self.cursor.min(count - 1))
+    }
+
+    pub fn current(&self) -> Option<&String> {
+        self.cursor().map(|i| self.visible()[i])
+    }
+
+    pub fn is_chosen(&self, game: &str) -> bool {
+        self.chosen.contains(game)
+    }
+
+    /// Every chosen game, including ones that the filter hides.
+    pub fn chosen(&self) -> Vec<String> {
+        self.chosen.iter().cloned().collect()
+    }
+
+    pub fn move_by(&mut self, delta: isize) {
+        let last = self.visible().len().saturating_sub(1);
+        let current = self.cursor().unwrap_or(0) as isize;
+        self.cursor = (current + delta).clamp(0, last as isize) as usize;
+    }
+
+    pub fn move_to_start(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn move_to_end(&mut self) {
+        self.cursor = self.visible().len().saturating_sub(1);
+    }
+
+    pub fn toggle_current(&mut self) {
+        if let Some(game) = self.current().cloned() {
+            if !self.chosen.remove(&game) {
+                self.chosen.insert(game);
+            }
+        }
+    }
+
+    /// Choose the visible game at this position, counting from 0.
+    pub fn choose_at(&mut self, index: usize) -> bool {
+        match self.visible().get(index) {
+            Some(game) => {
+                let game = game.to_string();
+                self.chosen.insert(game);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Choose all of the visible games, or if they're all chosen already, unchoose them.
+    pub fn toggle_visible(&mut self) {
+        let visible: Vec<_> = self.visible().into_iter().cloned().collect();
+        if visible.iter().all(|x| self.chosen.contains(x)) {
+            for game in visible {
+                self.chosen.remove(&game);
+            }
+        } else {
+            self.chosen.extend(visible);
+        }
+    }
+
+    pub fn set_filter(&mut self, filter: &str) {
+        self.filter = filter.to_string();
+        self.cursor = 0;
+    }
+
+    pub fn push_filter(&mut self, c: char) {
+        self.filter.push(c);
+        self.cursor = 0;
+    }
+
+    pub fn pop_filter(&mut self) {
+        self.filter.pop();
+        self.cursor = 0;
+    }
+}
+
+/// What the terminal loop should do after a key press.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Action {
+    None,
+    Quit,
+    Preview,
+    Run,
+}
+
+/// Everything about the interface that isn't rendering, so that it can be driven by key presses alone.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct App {
+    pub mode: Mode,
+    backup: Selection,
+    restore: Selection,
+    /// Typed characters go into the filter instead of being treated as commands.
+    pub filtering: bool,
+    /// Waiting for the user to confirm the operation.
+    pub confirming: bool,
+}
+
+impl App {
+    pub fn new(backup_games: Vec<String>, restore_games: Vec<String>) -> Self {
+        Self {
+            mode: Mode::Backup,
+            backup: Selection::new(backup_games),
+            restore: Selection::new(restore_games),
+            filtering: false,
+            confirming: false,
+        }
+    }
+
+    pub fn selection(&self) -> &Selection {
+        match self.mode {
+            Mode::Backup => &self.backup,
+            Mode::Restore => &self.restore,
+        }
+    }
+
+    pub fn selection_mut(&mut self) -> &mut Selection {
+        match self.mode {
+            Mode::Backup => &mut self.backup,
+            Mode::Restore => &mut self.restore,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Action::Quit;
+        }
+
+        if self.confirming {
+            self.confirming = false;
+            return match key.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => Action::Run,
+                _ => Action::None,
+            };
+        }
+
+        if self.filtering {
+            match key.code {
+                KeyCode::Char(c) => self.selection_mut().push_filter(c),
+                KeyCode::Backspace => self.selection_mut().pop_filter(),
+                KeyCode::Enter => self.filtering = false,
+                KeyCode::Esc => {
+                    self.filtering = false;
+                    self.selection_mut().set_filter("");
+                }
+                _ => {}
+            }
+            return Action::None;
+        }
+
+        let has_chosen = !self.selection().chosen.is_empty();
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.selection_mut().move_by(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.selection_mut().move_by(1),
+            KeyCode::PageUp => self.selection_mut().move_by(-(PAGE_SIZE as isize)),
+            KeyCode::PageDown => self.selection_mut().move_by(PAGE_SIZE as isize),
+            KeyCode::Home => self.selection_mut().move_to_start(),
+            KeyCode::End => self.selection_mut().move_to_end(),
+            KeyCode::Char(' ') => self.selection_mut().toggle_current(),
+            KeyCode::Char('a') => self.selection_mut().toggle_visible(),
+            KeyCode::Char('/') => self.filtering = true,
+            KeyCode::Tab | KeyCode::BackTab => self.mode = self.mode.toggled(),
+            KeyCode::Char('p') if has_chosen => return Action::Preview,
+            KeyCode::Enter if has_chosen => self.confirming = true,
+            KeyCode::Char('q') | KeyCode::Esc => return Action::Quit,
+            _ => {}
+        }
+        Action::None
+    }
+}
+
+/// The same subcommand that the CLI would run for these games.
+/// It isn't forced, so frozen games, running games, and the restore mode are handled
+/// the same way as in the CLI, which asks its own questions (like which files a mirror
+/// restore would remove). Like in the GUI, backing up chosen games merges them into the target.
+fn command(mode: Mode, games: Vec<String>, preview: bool) -> Subcommand {
+    match mode {
+        Mode::Backup => Subcommand::Backup {
+            preview,
+            estimate: false,
+            path: None,
+            archive: None,
+            force: false,
+            merge: true,
+            no_merge: false,
+            update: false,
+            try_update: false,
+            by_steam_id: false,
+            wine_prefix: None,
+            api: false,
+            format: None,
+            output: CliOutputFile::default(),
+            anonymize: false,
+            sort: None,
+            failed_only: false,
+            wrap: None,
+            no_cache: false,
+            include_empty_games: false,
+            games,
+        },
+        Mode::Restore => Subcommand::Restore {
+            preview,
+            dry_run: false,
+            preserve_timestamps: false,
+            verify_after_restore: false,
+            verify_sample: None,
+            verify_sample_seed: None,
+            mode: None,
+            skip_identical: false,
+            no_skip_identical: false,
+            path: None,
+            force: false,
+            interactive: false,
+            by_steam_id: false,
+            api: false,
+            format: None,
+            output: CliOutputFile::default(),
+            anonymize: false,
+            sort: None,
+            failed_only: false,
+            wrap: None,
+            games,
+        },
+    }
+}
+
+fn perform(app: &App, preview: bool, translator: &Translator, profile: &Option<String>) {
+    let sub = command(app.mode, app.selection().chosen(), preview);
+    if let Err(e) = run_cli(sub, profile.clone()) {
+        eprintln!("\n{}", translator.handle_error(&e));
+    }
+}
+
+fn confirmation(app: &App, config: &Config, translator: &Translator) -> String {
+    match app.mode {
+        Mode::Backup => {
+            let target = &config.backup.path;
+            translator.modal_confirm_backup(target, target.exists(), true)
+        }
+        Mode::Restore => translator.modal_confirm_restore(&config.restore.path, false),
+    }
+}
+
+/// A dumb terminal can't draw the interface, and neither can anything that isn't a terminal.
+fn is_dumb_terminal() -> bool {
+    std::env::var("TERM").map(|x| x == "dumb").unwrap_or(false)
+        || !std::io::stdin().is_tty()
+        || !std::io::stdout().is_tty()
+}
+
+/// Takes over the terminal for as long as it's alive,
+/// and puts it back to normal even if something goes wrong.
+struct Screen;
+
+impl Screen {
+    fn enter() -> std::io::Result<Self> {
+        crossterm::terminal::enable_raw_mode()?;
+        let screen = Self;
+        crossterm::execute!(
+            std::io::stdout(),
+            crossterm::terminal::EnterAlternateScreen,
+            crossterm::cursor::Hide
+        )?;
+        Ok(screen)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = crossterm::terminal::disable_raw_mode();
+        let _ = crossterm::execute!(
+            std::io::stdout(),
+            crossterm::terminal::LeaveAlternateScreen,
+            crossterm::cursor::Show
+        );
+    }
+}
+
+/// Run the interface over the current terminal, or ask plain questions if it can't be drawn.
+pub fn run(
+    backup_games: Vec<String>,
+    restore_games: Vec<String>,
+    config: &Config,
+    translator: &Translator,
+    profile: Option<String>,
+) -> Result<(), Error> {
+    let mut app = App::new(backup_games, restore_games);
+
+    if is_dumb_terminal() {
+        return run_prompts(&mut app, config, translator, &profile);
+    }
+    let screen = match Screen::enter() {
+        Ok(x) => x,
+        Err(e) => {
+            log::warn!("Unable to start the terminal interface: {}", e);
+            return run_prompts(&mut app, config, translator, &profile);
+        }
+    };
+
+    run_screen(screen, &mut app, config, translator, &profile)
+        .map_err(|e| Error::TerminalUiFailed { why: e.to_string() })
+}
+
+fn run_screen(
+    screen: Screen,
+    app: &mut App,
+    config: &Config,
+    translator: &Translator,
+    profile: &Option<String>,
+) -> std::io::Result<()> {
+    let mut screen = Some(screen);
+    let mut terminal = Terminal::new(CrosstermBackend::new(std::io::stdout()))?;
+    let mut list_state = ListState::default();
+
+    loop {
+        list_state.select(app.selection().cursor());
+        terminal.draw(|frame| draw(frame, app, &mut list_state, config, translator))?;
+
+        let key = match crossterm::event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => key,
+            Event::Resize(_, _) => {
+                terminal.autoresize()?;
+                continue;
+            }
+            _ => continue,
+        };
+
+        let preview = match app.handle_key(key) {
+            Action::None => continue,
+            Action::Quit => return Ok(()),
+            Action::Preview => true,
+            Action::Run => false,
+        };
+
+        // The operation prints its usual report, so it needs the normal terminal.
+        screen.take();
+        perform(app, preview, translator, profile);
+        print!("\n{} ", translator.tui_press_enter());
+        let _ = std::io::stdout().flush();
+        let _ = std::io::stdin().lock().read_line(&mut String::new());
+        screen = Some(Screen::enter()?);
+        terminal.clear()?;
+    }
+}
+
+fn draw<B: Backend>(
+    frame: &mut Frame<B>,
+    app: &App,
+    list_state: &mut ListState,
+    config: &Config,
+    translator: &Translator,
+) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3), Constraint::Length(4)].as_ref())
+        .split(frame.size());
+
+    let tabs = Tabs::new(vec![
+        Spans::from(translator.backup_button()),
+        Spans::from(translator.restore_button()),
+    ])
+    .select(match app.mode {
+        Mode::Backup => 0,
+        Mode::Restore => 1,
+    })
+    .block(Block::default().borders(Borders::ALL))
+    .highlight_style(Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED));
+    frame.render_widget(tabs, chunks[0]);
+
+    let selection = app.selection();
+    let visible = selection.visible();
+    let mut list_block = Block::default().borders(Borders::ALL);
+    if app.filtering || !selection.filter().is_empty() {
+        list_block = list_block.title(translator.tui_filter(selection.filter()));
+    }
+    if visible.is_empty() {
+        frame.render_widget(Paragraph::new(translator.tui_no_games()).block(list_block), chunks[1]);
+    } else {
+        let items: Vec<_> = visible
+            .iter()
+            .map(|game| {
+                let mark = if selection.is_chosen(game) { "[x]" } else { "[ ]" };
+                ListItem::new(format!("{} {}", mark, game))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(list_block)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, chunks[1], list_state);
+    }
+
+    let help = if app.filtering {
+        translator.tui_filter_help()
+    } else {
+        translator.tui_help()
+    };
+    let status = format!(
+        "{}\n{}",
+        translator.tui_selected(selection.chosen().len(), selection.total()),
+        help
+    );
+    frame.render_widget(
+        Paragraph::new(status)
+            .block(Block::default().borders(Borders::TOP))
+            .wrap(Wrap { trim: true }),
+        chunks[2],
+    );
+
+    if app.confirming {
+        let area = centered(frame.size(), 70, 50);
+        let text = format!(
+            "{}\n\n{}",
+            confirmation(app, config, translator),
+            translator.tui_confirm_hint()
+        );
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(text)
+                .block(Block::default().borders(Borders::ALL))
+                .wrap(Wrap { trim: false }),
+            area,
+        );
+    }
+}
+
+/// A box in the middle of `area`, taking up these percentages of its width and height.
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = (area.width as u32 * width as u32 / 100) as u16;
+    let height = (area.height as u32 * height as u32 / 100) as u16;
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+/// Read one answer, or `None` once there's no more input.
+fn prompt(question: &str) -> Option<String> {
+    print!("{} ", question);
+    let _ = std::io::stdout().flush();
+    let mut line = String::new();
+    match std::io::stdin().lock().read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(line.trim().to_string()),
+    }
+}
+
+/// The same steps as the interface, as a series of questions.
+fn run_prompts(app: &mut App, config: &Config, translator: &Translator, profile: &Option<String>) -> Result<(), Error> {
+    loop {
+        app.mode = match prompt(&translator.tui_fallback_mode()).as_deref() {
+            Some("b") | Some("B") => Mode::Backup,
+            Some("r") | Some("R") => Mode::Restore,
+            Some("q") | Some("Q") | None => return Ok(()),
+            Some(_) => continue,
+        };
+
+        let filter = match prompt(&translator.tui_fallback_filter()) {
+            Some(x) => x,
+            None => return Ok(()),
+        };
+        app.selection_mut().set_filter(&filter);
+        let visible = app.selection().visible();
+        if visible.is_empty() {
+            println!("{}", translator.tui_no_games());
+            continue;
+        }
+        for (i, game) in visible.iter().enumerate() {
+            println!("{:>5}. {}", i + 1, game);
+        }
+
+        let numbers = match prompt(&translator.tui_fallback_select()) {
+            Some(x) => x,
+            None => return Ok(()),
+        };
+        for number in numbers.split(|c: char| c.is_whitespace() || c == ',') {
+            if let Ok(number) = number.parse::<usize>() {
+                if number > 0 {
+                    app.selection_mut().choose_at(number - 1);
+                }
+            }
+        }
+        if app.selection().chosen().is_empty() {
+            continue;
+        }
+        println!(
+            "{}",
+            translator.tui_selected(app.selection().chosen().len(), app.selection().total())
+        );
+
+        loop {
+            match prompt(&translator.tui_fallback_action()).as_deref() {
+                Some("p") | Some("P") => perform(app, true, translator, profile),
+                Some("r") | Some("R") => {
+                    println!("{}", confirmation(app, config, translator));
+                    if let Some("y") | Some("Y") = prompt(&translator.tui_fallback_confirm()).as_deref() {
+                        perform(app, false, translator, profile);
+                    }
+                    return Ok(());
+                }
+                Some("q") | Some("Q") | None => return Ok(()),
+                Some(_) => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn s(text: &str) -> String {
+        text.to_string()
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn app() -> App {
+        App::new(
+            vec![s("Portal"), s("Celeste"), s("Portal 2"), s("Hades")],
+            vec![s("Celeste")],
+        )
+    }
+
+    #[test]
+    fn filter_keeps_hidden_games_chosen() {
+        let mut selection = Selection::new(vec![s("Portal"), s("Celeste"), s("Portal 2"), s("Hades")]);
+        assert_eq!(vec!["Celeste", "Hades", "Portal", "Portal 2"], selection.visible());
+
+        selection.move_by(1);
+        selection.toggle_current();
+        assert_eq!(vec![s("Hades")], selection.chosen());
+
+        selection.set_filter("PORT");
+        assert_eq!(vec!["Portal", "Portal 2"], selection.visible());
+        assert_eq!(Some(&s("Portal")), selection.current());
+
+        selection.toggle_visible();
+        assert_eq!(vec![s("Hades"), s("Portal"), s("Portal 2")], selection.chosen());
+        selection.toggle_visible();
+        assert_eq!(vec![s("Hades")], selection.chosen());
+
+        selection.set_filter("");
+        assert!(selection.choose_at(0));
+        assert!(!selection.choose_at(4));
+        assert_eq!(vec![s("Celeste"), s("Hades")], selection.chosen());
+    }
+
+    #[test]
+    fn cursor_stays_within_visible_games() {
+        let mut selection = Selection::new(vec![s("a"), s("b"), s("c")]);
+        selection.move_by(-1);
+        assert_eq!(Some(0), selection.cursor());
+        selection.move_by(10);
+        assert_eq!(Some(2), selection.cursor());
+        selection.move_to_start();
+        assert_eq!(Some(0), selection.cursor());
+        selection.move_to_end();
+        assert_eq!(Some(2), selection.cursor());
+
+        selection.set_filter("z");
+        assert_eq!(None, selection.cursor());
+        assert_eq!(None, selection.current());
+        selection.toggle_current();
+        assert_eq!(Vec::<String>::new(), selection.chosen());
+
+        assert_eq!(None, Selection::default().cursor());
+    }
+
+    #[test]
+    fn keys_drive_selection_and_confirmation() {
+        let mut app = app();
+
+        // Nothing is chosen yet, so there's nothing to run.
+        assert_eq!(Action::None, app.handle_key(key(KeyCode::Enter)));
+        assert!(!app.confirming);
+
+        assert_eq!(Action::None, app.handle_key(key(KeyCode::Char('/'))));
+        for c in "hades".chars() {
+            app.handle_key(key(KeyCode::Char(c)));
+        }
+        // While filtering, letters aren't commands.
+        app.handle_key(key(KeyCode::Char('q')));
+        app.handle_key(key(KeyCode::Backspace));
+        app.handle_key(key(KeyCode::Enter));
+        assert!(!app.filtering);
+        assert_eq!("hades", app.selection().filter());
+
+        app.handle_key(key(KeyCode::Char(' ')));
+        assert_eq!(vec![s("Hades")], app.selection().chosen());
+        assert_eq!(Action::Preview, app.handle_key(key(KeyCode::Char('p'))));
+
+        assert_eq!(Action::None, app.handle_key(key(KeyCode::Enter)));
+        assert!(app.confirming);
+        assert_eq!(Action::None, app.handle_key(key(KeyCode::Char('n'))));
+        assert!(!app.confirming);
+        app.handle_key(key(KeyCode::Enter));
+        assert_eq!(Action::Run, app.handle_key(key(KeyCode::Char('y'))));
+
+        // Each mode has its own selection.
+        app.handle_key(key(KeyCode::Tab));
+        assert_eq!(Mode::Restore, app.mode);
+        assert_eq!(Vec::<String>::new(), app.selection().chosen());
+        assert_eq!(vec!["Celeste"], app.selection().visible());
+        app.handle_key(key(KeyCode::Tab));
+        assert_eq!(vec![s("Hades")], app.selection().chosen());
+
+        assert_eq!(Action::Quit, app.handle_key(key(KeyCode::Char('q'))));
+        assert_eq!(
+            Action::Quit,
+            app.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL))
+        );
+    }
+
+    #[test]
+    fn commands_are_not_forced() {
+        for preview in [false, true] {
+            match command(Mode::Backup, vec![s("Hades")], preview) {
+                Subcommand::Backup {
+                    force, merge, games, ..
+                } => {
+                    assert!(!force);
+                    assert!(merge);
+                    assert_eq!(vec![s("Hades")], games);
+                }
+                other => panic!("unexpected command: {:?}", other),
+            }
+            match command(Mode::Restore, vec![s("Celeste")], preview) {
+                Subcommand::Restore { force, mode, games, .. } => {
+                    assert!(!force);
+                    // The config's restore mode applies, with the CLI's confirmation for mirroring.
+                    assert_eq!(None, mode);
+                    assert_eq!(vec![s("Celeste")], games);
+                }
+                other => panic!("unexpected command: {:?}", other),
+            }
+        }
+    }
+}
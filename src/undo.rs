@@ -0,0 +1,228 @@
+use crate::{
+    layout::encode_base64_for_folder,
+    path::StrictPath,
+    prelude::{state_dir, Error},
+};
+
+const RECORD_FILE_NAME: &str = "undo.yaml";
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct UndoFile {
+    /// Where the file was restored, in rendered form.
+    pub original: String,
+    /// Name of the saved copy within the game's undo folder.
+    pub saved: String,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct UndoRecord {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overwritten: Vec<UndoFile>,
+    /// Strings are StrictPath in rendered form.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub created: Vec<String>,
+}
+
+impl UndoRecord {
+    fn load(file: &StrictPath) -> Result<Self, ()> {
+        let content = std::fs::read_to_string(file.interpret()).map_err(|_| ())?;
+        serde_yaml::from_str(&content).map_err(|_| ())
+    }
+}
+
+/// Copies of the files replaced by the latest restore, so that it can be undone.
+/// Only one snapshot is kept at a time, so each restore replaces the last one.
+#[derive(Clone, Debug)]
+pub struct UndoSnapshot {
+    pub path: StrictPath,
+}
+
+impl Default for UndoSnapshot {
+    fn default() -> Self {
+        Self::new(StrictPath::from(state_dir()).joined("undo-restore"))
+    }
+}
+
+impl UndoSnapshot {
+    pub fn new(path: StrictPath) -> Self {
+        Self { path }
+    }
+
+    pub fn exists(&self) -> bool {
+        self.path.is_dir()
+    }
+
+    pub fn clear(&self) {
+        let _ = self.path.remove();
+    }
+
+    pub fn game(&self, game_name: &str) -> GameUndoSnapshot {
+        GameUndoSnapshot {
+            path: self.path.joined(&encode_base64_for_folder(game_name)),
+            record: UndoRecord::default(),
+        }
+    }
+
+    /// Put back the files from the snapshot and delete any files that the restore created.
+    /// Returns how many files were reverted. If everything succeeds, the snapshot is removed.
+    pub fn undo(&self) -> Result<usize, Vec<StrictPath>> {
+        let mut reverted = 0;
+        let mut failed = vec![];
+
+        for game_dir in walkdir::WalkDir::new(self.path.interpret())
+            .max_depth(1)
+            .follow_links(false)
+            .into_iter()
+            .skip(1) // the base path itself
+            .filter_map(|e| e.ok())
+            .filter(|x| x.file_type().is_dir())
+        {
+            let game_dir = StrictPath::from(&game_dir);
+            let record = match UndoRecord::load(&game_dir.joined(RECORD_FILE_NAME)) {
+                Ok(x) => x,
+                Err(_) => continue,
+            };
+
+            for created in &record.created {
+                let created = StrictPath::new(created.clone());
+                if !created.is_file() {
+                    continue;
+                }
+                if created.remove().is_ok() {
+                    reverted += 1;
+                } else {
+                    failed.push(created);
+                }
+            }
+
+            for file in &record.overwritten {
+                let original = StrictPath::new(file.original.clone());
+                let saved = game_dir.joined(&file.saved);
+                if original.create_parent_dir().is_ok()
+                    && original.unset_readonly().is_ok()
+                    && std::fs::copy(saved.interpret(), original.interpret()).is_ok()
+                {
+                    reverted += 1;
+                } else {
+                    failed.push(original);
+                }
+            }
+        }
+
+        if failed.is_empty() {
+            self.clear();
+            Ok(reverted)
+        } else {
+            Err(failed)
+        }
+    }
+}
+
+pub struct GameUndoSnapshot {
+    path: StrictPath,
+    record: UndoRecord,
+}
+
+impl GameUndoSnapshot {
+    /// Save a copy of a file that is about to be overwritten.
+    /// If this fails (e.g., because there isn't enough disk space),
+    /// then the file should not be overwritten.
+    pub fn save_overwritten(&mut self, target: &StrictPath) -> bool {
+        let saved = self.record.overwritten.len().to_string();
+        let saved_path = self.path.joined(&saved);
+        if saved_path.create_parent_dir().is_err() || std::fs::copy(target.interpret(), saved_path.interpret()).is_err()
+        {
+            let _ = saved_path.remove();
+            return false;
+        }
+        self.record.overwritten.push(UndoFile {
+            original: target.render(),
+            saved,
+        });
+        true
+    }
+
+    pub fn note_created(&mut self, target: &StrictPath) {
+        self.record.created.push(target.render());
+    }
+
+    pub fn save(&self) -> Result<(), Error> {
+        if self.record == UndoRecord::default() {
+            return Ok(());
+        }
+        let file = self.path.joined(RECORD_FILE_NAME);
+        let cannot_save = || Error::CannotPrepareUndoSnapshot {
+            path: self.path.clone(),
+        };
+        file.create_parent_dir().map_err(|_| cannot_save())?;
+        let content = serde_yaml::to_string(&self.record).map_err(|_| cannot_save())?;
+        std::fs::write(file.interpret(), content.as_bytes()).map_err(|_| cannot_save())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::TempPath;
+    use crate::prelude::{restore_game, RestoreOptions, ScanInfo, ScannedFile};
+    use maplit::*;
+
+    #[test]
+    fn can_undo_restore() {
+        let base = TempPath::new("ludusavi-test-undo-restore");
+        std::fs::create_dir_all(base.joined("backup").interpret()).unwrap();
+        std::fs::create_dir_all(base.joined("game").interpret()).unwrap();
+        std::fs::write(base.joined("backup/existing.txt").interpret(), "new").unwrap();
+        std::fs::write(base.joined("backup/created.txt").interpret(), "new").unwrap();
+        std::fs::write(base.joined("game/existing.txt").interpret(), "old").unwrap();
+
+        let scan_info = ScanInfo {
+            game_name: "game".to_string(),
+            found_files: hashset! {
+                ScannedFile {
+                    path: base.joined("backup/existing.txt"),
+                    size: 3,
+                    original_path: Some(base.joined("game/existing.txt")),
+                    ignored: false,
+                    system: false,
+                    partial: false,
+                    xbox_name: None,
+                },
+                ScannedFile {
+                    path: base.joined("backup/created.txt"),
+                    size: 3,
+                    original_path: Some(base.joined("game/created.txt")),
+                    ignored: false,
+                    system: false,
+                    partial: false,
+                    xbox_name: None,
+                },
+            },
+            ..Default::default()
+        };
+        let snapshot = UndoSnapshot::new(base.joined("undo"));
+
+        let backup_info = restore_game(
+            &scan_info,
+            &[],
+            &RestoreOptions {
+                undo: Some(snapshot.clone()),
+                ..Default::default()
+            },
+        );
+        assert!(backup_info.successful());
+        assert_eq!(
+            "new",
+            std::fs::read_to_string(base.joined("game/existing.txt").interpret()).unwrap()
+        );
+        assert!(base.joined("game/created.txt").is_file());
+
+        assert_eq!(Ok(2), snapshot.undo());
+        assert_eq!(
+            "old",
+            std::fs::read_to_string(base.joined("game/existing.txt").interpret()).unwrap()
+        );
+        assert!(!base.joined("game/created.txt").exists());
+        assert!(!snapshot.exists());
+    }
+}
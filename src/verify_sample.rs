@@ -0,0 +1,129 @@
+use sha2::Digest;
+
+use crate::path::StrictPath;
+
+/// The outcome of checking a sample of the restored files against the backup.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SampleResult {
+    /// How many files were restored, whether or not they were checked.
+    pub total: usize,
+    pub checked: usize,
+    pub mismatched: usize,
+    /// Passing this to `--verify-sample-seed` checks the same files again.
+    pub seed: u64,
+    /// Where each checked file was restored to, by game.
+    pub files: std::collections::BTreeMap<String, Vec<StrictPath>>,
+}
+
+impl SampleResult {
+    /// How much of all the restored files might not match, as a percentage.
+    /// When the whole sample matched, this is the most that could still be off with 95% confidence.
+    /// Otherwise, it's the share of the sample that didn't match.
+    pub fn extrapolated_percent(&self) -> f64 {
+        if self.checked == 0 {
+            0.0
+        } else if self.mismatched == 0 {
+            (1.0 - 0.05_f64.powf(1.0 / self.checked as f64)) * 100.0
+        } else {
+            self.mismatched as f64 / self.checked as f64 * 100.0
+        }
+    }
+}
+
+/// How many of `total` files to check for a sample of `percent` (from 0 to 100).
+/// A small percentage can round down to nothing, but there's always at least one file to check if any were restored.
+pub fn sample_size(total: usize, percent: f64) -> usize {
+    if total == 0 {
+        return 0;
+    }
+    ((total as f64 * percent / 100.0).round() as usize).clamp(1, total)
+}
+
+/// Pick a random sample of the items, which is the same for the same seed and keys.
+/// The order of `items` doesn't matter, since each one is ranked by a hash of its key.
+pub fn choose<T>(items: Vec<T>, percent: f64, seed: u64, key: impl Fn(&T) -> String) -> Vec<T> {
+    let size = sample_size(items.len(), percent);
+    let mut ranked: Vec<_> = items
+        .into_iter()
+        .map(|item| {
+            let mut hasher = sha2::Sha256::new();
+            hasher.update(seed.to_le_bytes());
+            hasher.update(key(&item).as_bytes());
+            (hasher.finalize().to_vec(), item)
+        })
+        .collect();
+    ranked.sort_by(|(a, _), (b, _)| a.cmp(b));
+    ranked.into_iter().take(size).map(|(_, item)| item).collect()
+}
+
+/// A seed for when none was given, so that each run checks different files.
+pub fn random_seed() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|x| x.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
+pub fn digest(file: &StrictPath) -> std::io::Result<Vec<u8>> {
+    use std::io::Read;
+
+    let mut reader = std::io::BufReader::new(std::fs::File::open(file.interpret())?);
+    let mut hasher = sha2::Sha256::new();
+    let mut buffer = [0; 8192];
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher.finalize().to_vec())
+}
+
+/// Whether the restored file has the same hash as its backup.
+/// If either one can't be read, it doesn't count as a match.
+pub fn matches(backup: &StrictPath, restored: &StrictPath) -> bool {
+    matches!((digest(backup), digest(restored)), (Ok(a), Ok(b)) if a == b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn selects_sample_size_from_percentage() {
+        assert_eq!(0, sample_size(0, 10.0));
+        assert_eq!(50, sample_size(1_000, 5.0));
+        assert_eq!(2, sample_size(3, 50.0));
+        assert_eq!(10, sample_size(10, 100.0));
+        // These would round down to nothing.
+        assert_eq!(1, sample_size(10, 1.0));
+        assert_eq!(1, sample_size(1, 0.1));
+
+        let items: Vec<_> = (0..100).map(|i| format!("/saves/file{}", i)).collect();
+        let sample = choose(items.clone(), 10.0, 42, |x| x.clone());
+        let reversed = choose(items.iter().rev().cloned().collect(), 10.0, 42, |x| x.clone());
+        let other_seed = choose(items, 10.0, 43, |x| x.clone());
+        assert_eq!(10, sample.len());
+        assert_eq!(sample, reversed);
+        assert_ne!(sample, other_seed);
+    }
+
+    #[test]
+    fn can_extrapolate_from_sample() {
+        let passed = SampleResult {
+            total: 1_000,
+            checked: 100,
+            mismatched: 0,
+            seed: 1,
+            ..Default::default()
+        };
+        let failed = SampleResult {
+            mismatched: 5,
+            ..passed.clone()
+        };
+        assert_eq!("3.0", format!("{:.1}", passed.extrapolated_percent()));
+        assert_eq!("5.0", format!("{:.1}", failed.extrapolated_percent()));
+    }
+}
@@ -0,0 +1,448 @@
+//! Microsoft Store games that use Xbox cloud saves keep them in "wgs" folders,
+//! like `%LOCALAPPDATA%/Packages/<package family name>/SystemAppData/wgs`.
+//! Each save file is stored under an opaque GUID, and an index maps those back to the names the game uses.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    manifest::Game,
+    prelude::{ScannedFile, StrictPath},
+};
+
+const INDEX_FILE: &str = "containers.index";
+/// The only container format that we know how to read.
+const CONTAINER_VERSION: u32 = 4;
+/// Names in a container file are a fixed number of UTF-16 characters.
+const CONTAINER_FILE_NAME_LENGTH: usize = 64;
+
+/// A save file as the game knows it, along with where its content is actually stored.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize)]
+pub struct WgsFile {
+    pub name: String,
+    pub path: StrictPath,
+}
+
+/// A group of save files from the index.
+/// Containers that can't be read, like ones that are encrypted or only in the cloud,
+/// are marked as unsupported and have no files.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize)]
+pub struct WgsContainer {
+    pub name: String,
+    pub supported: bool,
+    pub files: Vec<WgsFile>,
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    fn bytes(&mut self, count: usize) -> Option<&'a [u8]> {
+        let end = self.position.checked_add(count)?;
+        let bytes = self.data.get(self.position..end)?;
+        self.position = end;
+        Some(bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.bytes(1).map(|x| x[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.bytes(4).map(|x| u32::from_le_bytes([x[0], x[1], x[2], x[3]]))
+    }
+
+    fn utf16(&mut self, length: usize) -> Option<String> {
+        let units: Vec<u16> = self
+            .bytes(length.checked_mul(2)?)?
+            .chunks_exact(2)
+            .map(|x| u16::from_le_bytes([x[0], x[1]]))
+            .collect();
+        let text = String::from_utf16(&units).ok()?;
+        Some(text.trim_end_matches('\0').to_string())
+    }
+
+    fn prefixed_utf16(&mut self) -> Option<String> {
+        let length = self.u32()? as usize;
+        self.utf16(length)
+    }
+
+    /// GUIDs are stored in Windows' mixed-endian layout,
+    /// but their folder and file names are plain uppercase hex.
+    fn guid(&mut self) -> Option<String> {
+        let x = self.bytes(16)?;
+        let mut ordered = vec![x[3], x[2], x[1], x[0], x[5], x[4], x[7], x[6]];
+        ordered.extend_from_slice(&x[8..]);
+        Some(ordered.iter().map(|b| format!("{:02X}", b)).collect())
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct IndexEntry {
+    name: String,
+    number: u8,
+    folder: String,
+}
+
+fn parse_index(data: &[u8]) -> Option<Vec<IndexEntry>> {
+    let mut reader = Reader::new(data);
+    let _version = reader.u32()?;
+    let count = reader.u32()?;
+    reader.bytes(4)?;
+    let _package = reader.prefixed_utf16()?;
+    let _modified = reader.bytes(8)?;
+    reader.bytes(4)?;
+    let _id = reader.prefixed_utf16()?;
+    reader.bytes(8)?;
+
+    let mut entries = vec![];
+    for _ in 0..count {
+        let name = reader.prefixed_utf16()?;
+        let _cloud_name = reader.prefixed_utf16()?;
+        let _revision = reader.prefixed_utf16()?;
+        let number = reader.u8()?;
+        reader.bytes(4)?;
+        let folder = reader.guid()?;
+        let _modified = reader.bytes(8)?;
+        reader.bytes(8)?;
+        let _size = reader.bytes(8)?;
+        entries.push(IndexEntry { name, number, folder });
+    }
+    Some(entries)
+}
+
+/// Returns the name and GUID of each file in a container.
+fn parse_container(data: &[u8]) -> Option<Vec<(String, String)>> {
+    let mut reader = Reader::new(data);
+    if reader.u32()? != CONTAINER_VERSION {
+        return None;
+    }
+    let count = reader.u32()?;
+
+    let mut files = vec![];
+    for _ in 0..count {
+        let name = reader.utf16(CONTAINER_FILE_NAME_LENGTH)?;
+        let guid = reader.guid()?;
+        let _copy = reader.bytes(16)?;
+        files.push((name, guid));
+    }
+    Some(files)
+}
+
+fn read_container(user_dir: &StrictPath, entry: IndexEntry) -> WgsContainer {
+    let folder = user_dir.joined(&entry.folder);
+    let files = std::fs::read(folder.joined(&format!("container.{}", entry.number)).interpret())
+        .ok()
+        .and_then(|data| parse_container(&data))
+        .map(|files| {
+            files
+                .into_iter()
+                .map(|(name, guid)| WgsFile {
+                    name,
+                    path: folder.joined(&guid),
+                })
+                .collect::<Vec<_>>()
+        })
+        // Don't report any files unless all of their content is actually here.
+        .filter(|files| files.iter().all(|file| file.path.is_file()));
+
+    WgsContainer {
+        name: entry.name,
+        supported: files.is_some(),
+        files: files.unwrap_or_default(),
+    }
+}
+
+/// Read all of the containers from a package's `wgs` folder.
+/// There is one set of containers per Xbox account that has played the game.
+pub fn read_containers(wgs_dir: &StrictPath) -> Vec<WgsContainer> {
+    let mut user_dirs: Vec<_> = match std::fs::read_dir(wgs_dir.interpret()) {
+        Ok(entries) => entries
+            .flatten()
+            .filter(|x| x.path().join(INDEX_FILE).is_file())
+            .map(|x| wgs_dir.joined(&x.file_name().to_string_lossy()))
+            .collect(),
+        Err(_) => vec![],
+    };
+    user_dirs.sort();
+
+    let mut containers = vec![];
+    for user_dir in user_dirs {
+        let entries = std::fs::read(user_dir.joined(INDEX_FILE).interpret())
+            .ok()
+            .and_then(|data| parse_index(&data));
+        for entry in entries.unwrap_or_default() {
+            containers.push(read_container(&user_dir, entry));
+        }
+    }
+    containers
+}
+
+/// Fill in `xbox_name` for any scanned files that are stored in an Xbox container,
+/// like `<container>/<file>`. Each `wgs` folder's containers are only read once.
+pub fn name_files(files: HashSet<ScannedFile>) -> HashSet<ScannedFile> {
+    let mut names_by_folder: HashMap<String, HashMap<String, String>> = HashMap::new();
+    files
+        .into_iter()
+        .map(|mut file| {
+            let rendered = file.path.render();
+            let end = match rendered.to_ascii_lowercase().find("/systemappdata/wgs/") {
+                Some(x) => x,
+                None => return file,
+            };
+            let wgs_dir = &rendered[..end + "/SystemAppData/wgs".len()];
+            let names = names_by_folder.entry(wgs_dir.to_ascii_lowercase()).or_insert_with(|| {
+                read_containers(&StrictPath::new(wgs_dir.to_string()))
+                    .into_iter()
+                    .flat_map(|container| {
+                        container.files.into_iter().map(move |x| {
+                            (
+                                x.path.render().to_ascii_lowercase(),
+                                format!("{}/{}", container.name, x.name),
+                            )
+                        })
+                    })
+                    .collect()
+            });
+            file.xbox_name = names.get(&rendered.to_ascii_lowercase()).cloned();
+            file
+        })
+        .collect()
+}
+
+/// The package family names from a game's file entries, like `<winLocalAppData>/Packages/<name>/...`.
+pub fn package_family_names(game: &Game) -> Vec<String> {
+    let mut names: Vec<String> = vec![];
+    for path in game.files.iter().flat_map(|x| x.keys()) {
+        let normalized = path.replace('\\', "/");
+        let mut segments = normalized.split('/');
+        if !segments.any(|x| x.eq_ignore_ascii_case("Packages")) {
+            continue;
+        }
+        if let Some(name) = segments.next() {
+            let placeholder = name.is_empty() || name.contains(['<', '*', '?']);
+            if !placeholder && !names.contains(&name.to_string()) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+/// Find the containers for a game in the current user's Microsoft Store packages.
+pub fn find_containers(game: &Game) -> Vec<WgsContainer> {
+    let packages = match dirs::data_local_dir() {
+        Some(dir) => StrictPath::from_std_path_buf(&dir).joined("Packages"),
+        None => return vec![],
+    };
+    package_family_names(game)
+        .iter()
+        .flat_map(|name| read_containers(&packages.joined(&format!("{}/SystemAppData/wgs", name))))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::TempPath;
+    use maplit::*;
+    use pretty_assertions::assert_eq;
+
+    fn s(text: &str) -> String {
+        text.to_string()
+    }
+
+    fn utf16(text: &str) -> Vec<u8> {
+        text.encode_utf16().flat_map(|x| x.to_le_bytes()).collect()
+    }
+
+    fn prefixed_utf16(text: &str) -> Vec<u8> {
+        let mut bytes = (text.encode_utf16().count() as u32).to_le_bytes().to_vec();
+        bytes.extend(utf16(text));
+        bytes
+    }
+
+    /// The mixed-endian bytes for a GUID whose name is `0123456789ABCDEF` followed by `tail`.
+    fn guid(tail: u8) -> Vec<u8> {
+        let mut bytes = vec![0x67, 0x45, 0x23, 0x01, 0xAB, 0x89, 0xEF, 0xCD];
+        bytes.extend([0; 7]);
+        bytes.push(tail);
+        bytes
+    }
+
+    fn guid_name(tail: u8) -> String {
+        format!("0123456789ABCDEF00000000000000{:02X}", tail)
+    }
+
+    fn index(entries: &[(&str, u8, u8)]) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend(14u32.to_le_bytes());
+        bytes.extend((entries.len() as u32).to_le_bytes());
+        bytes.extend([0; 4]);
+        bytes.extend(prefixed_utf16("Example.Game_8wekyb3d8bbwe"));
+        bytes.extend([0; 8]);
+        bytes.extend([0; 4]);
+        bytes.extend(prefixed_utf16("id"));
+        bytes.extend([0; 8]);
+        for (name, number, folder) in entries {
+            bytes.extend(prefixed_utf16(name));
+            bytes.extend(prefixed_utf16(name));
+            bytes.extend(prefixed_utf16("\"0x8D9\""));
+            bytes.push(*number);
+            bytes.extend([0; 4]);
+            bytes.extend(guid(*folder));
+            bytes.extend([0; 24]);
+        }
+        bytes
+    }
+
+    fn container(version: u32, files: &[(&str, u8)]) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend(version.to_le_bytes());
+        bytes.extend((files.len() as u32).to_le_bytes());
+        for (name, file) in files {
+            let mut name = utf16(name);
+            name.resize(CONTAINER_FILE_NAME_LENGTH * 2, 0);
+            bytes.extend(name);
+            bytes.extend(guid(*file));
+            bytes.extend(guid(*file));
+        }
+        bytes
+    }
+
+    #[test]
+    fn can_parse_index() {
+        assert_eq!(
+            Some(vec![
+                IndexEntry {
+                    name: s("profile"),
+                    number: 3,
+                    folder: guid_name(1),
+                },
+                IndexEntry {
+                    name: s("slot1"),
+                    number: 1,
+                    folder: guid_name(2),
+                },
+            ]),
+            parse_index(&index(&[("profile", 3, 1), ("slot1", 1, 2)])),
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_index() {
+        let data = index(&[("profile", 3, 1)]);
+        assert_eq!(None, parse_index(&data[..data.len() - 1]));
+    }
+
+    #[test]
+    fn can_parse_container() {
+        assert_eq!(
+            Some(vec![(s("save.dat"), guid_name(5))]),
+            parse_container(&container(4, &[("save.dat", 5)])),
+        );
+        assert_eq!(None, parse_container(&container(5, &[("save.dat", 5)])));
+    }
+
+    #[test]
+    fn can_read_containers_and_report_unsupported_ones() {
+        let wgs = TempPath::new("ludusavi-test-wgs");
+        let user = wgs.joined("000900000ABCDEF0_0000000000000000000000006A0F4E8C");
+        let write = |path: &StrictPath, data: &[u8]| {
+            path.create_parent_dir().unwrap();
+            std::fs::write(path.interpret(), data).unwrap();
+        };
+        write(
+            &user.joined(INDEX_FILE),
+            &index(&[("profile", 1, 1), ("encrypted", 1, 2), ("roaming", 1, 3)]),
+        );
+        write(
+            &user.joined(&format!("{}/container.1", guid_name(1))),
+            &container(4, &[("save.dat", 5)]),
+        );
+        write(&user.joined(&format!("{}/{}", guid_name(1), guid_name(5))), b"data");
+        write(
+            &user.joined(&format!("{}/container.1", guid_name(2))),
+            &container(9, &[("save.dat", 6)]),
+        );
+
+        let containers = read_containers(&wgs);
+
+        assert_eq!(
+            vec![
+                WgsContainer {
+                    name: s("profile"),
+                    supported: true,
+                    files: vec![WgsFile {
+                        name: s("save.dat"),
+                        path: user.joined(&format!("{}/{}", guid_name(1), guid_name(5))),
+                    }],
+                },
+                WgsContainer {
+                    name: s("encrypted"),
+                    supported: false,
+                    files: vec![],
+                },
+                WgsContainer {
+                    name: s("roaming"),
+                    supported: false,
+                    files: vec![],
+                },
+            ],
+            containers,
+        );
+    }
+
+    #[test]
+    fn names_scanned_files_from_their_containers() {
+        let base = TempPath::new("ludusavi-test-wgs-names");
+        let user = base.joined("Packages/Game_abc/SystemAppData/wgs/000900000ABCDEF0_0000000000000000000000006A0F4E8C");
+        let write = |path: &StrictPath, data: &[u8]| {
+            path.create_parent_dir().unwrap();
+            std::fs::write(path.interpret(), data).unwrap();
+        };
+        write(&user.joined(INDEX_FILE), &index(&[("profile", 1, 1)]));
+        write(
+            &user.joined(&format!("{}/container.1", guid_name(1))),
+            &container(4, &[("save.dat", 5)]),
+        );
+        let save = user.joined(&format!("{}/{}", guid_name(1), guid_name(5)));
+        write(&save, b"data");
+        let other = base.joined("other.dat");
+        write(&other, b"data");
+
+        let files = name_files(hashset! {
+            ScannedFile::new(save.render(), 4),
+            ScannedFile::new(user.joined(INDEX_FILE).render(), 0),
+            ScannedFile::new(other.render(), 4),
+        });
+
+        let named: HashMap<_, _> = files.into_iter().map(|x| (x.path.render(), x.xbox_name)).collect();
+        assert_eq!(
+            hashmap! {
+                save.render() => Some(s("profile/save.dat")),
+                user.joined(INDEX_FILE).render() => None,
+                other.render() => None,
+            },
+            named,
+        );
+    }
+
+    #[test]
+    fn can_find_package_family_names() {
+        let game = Game {
+            files: Some(hashmap! {
+                s("<winLocalAppData>/Packages/Example.Game_8wekyb3d8bbwe/SystemAppData/wgs") => Default::default(),
+                s("<winLocalAppData>/Packages/*/LocalState") => Default::default(),
+                s("<winAppData>/Example") => Default::default(),
+            }),
+            ..Default::default()
+        };
+        assert_eq!(vec![s("Example.Game_8wekyb3d8bbwe")], package_family_names(&game));
+    }
+}
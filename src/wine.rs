@@ -0,0 +1,238 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::{
+    config::RootsConfig,
+    manifest::{Os, Store},
+    prelude::get_os,
+};
+
+const USER_SHELL_FOLDERS: &str = r"software\microsoft\windows\currentversion\explorer\user shell folders";
+const SHELL_FOLDERS: &str = r"software\microsoft\windows\currentversion\explorer\shell folders";
+const ENVIRONMENT: &[&str] = &["volatile environment", "environment"];
+
+static RE_USER_PROFILE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)%USERPROFILE%").unwrap());
+
+/// User folders that a Wine prefix's registry has moved away from their default locations.
+/// Each one is a host path inside of the prefix.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ShellFolders {
+    pub profile: Option<String>,
+    pub documents: Option<String>,
+    pub app_data: Option<String>,
+    pub local_app_data: Option<String>,
+}
+
+impl ShellFolders {
+    /// Find the prefix for a Wine root or a game's Proton prefix in a Steam root.
+    pub fn for_root(root: &RootsConfig, steam_id: &Option<u32>) -> Option<Self> {
+        match root.store {
+            Store::OtherWine => Self::load(&root.path.interpret()),
+            Store::Steam if get_os() == Os::Linux => steam_id
+                .and_then(|id| Self::load(&format!("{}/steamapps/compatdata/{}/pfx", root.path.interpret(), id))),
+            _ => None,
+        }
+    }
+
+    /// Read the redirects from the `user.reg` file in a prefix.
+    /// This returns nothing if the prefix uses the default locations.
+    pub fn load(prefix: &str) -> Option<Self> {
+        let content = std::fs::read_to_string(format!("{}/user.reg", prefix)).ok()?;
+        let folders = Self::from_registry(prefix, &content);
+        (folders != Self::default()).then_some(folders)
+    }
+
+    fn from_registry(prefix: &str, content: &str) -> Self {
+        let keys = parse_reg(content);
+        let value = |key: &str, name: &str| {
+            keys.get(key)
+                .and_then(|values| values.get(&name.to_lowercase()))
+                .map(|x| x.to_string())
+        };
+
+        let profile = ENVIRONMENT.iter().find_map(|key| value(key, "USERPROFILE"));
+        let folder = |name: &str, defaults: &[&str]| {
+            // `Shell Folders` has the expanded paths, but it's only a cache,
+            // so `User Shell Folders` takes precedence when both are present.
+            let raw = value(USER_SHELL_FOLDERS, name).or_else(|| value(SHELL_FOLDERS, name))?;
+            let expanded = match &profile {
+                Some(profile) => expand_user_profile(&raw, profile),
+                None => raw,
+            };
+            if is_default_location(&expanded, defaults) {
+                return None;
+            }
+            to_host_path(prefix, &expanded)
+        };
+
+        let documents = folder("Personal", &["Documents", "My Documents"]);
+        let app_data = folder("AppData", &[r"AppData\Roaming", "Application Data"]);
+        let local_app_data = folder("Local AppData", &[r"AppData\Local", r"Local Settings\Application Data"]);
+
+        Self {
+            // Any profile under `C:\users` is already covered by the usual checks.
+            profile: profile
+                .filter(|x| !x.to_lowercase().starts_with(r"c:\users\"))
+                .and_then(|x| to_host_path(prefix, &x)),
+            documents,
+            app_data,
+            local_app_data,
+        }
+    }
+
+    /// Resolve any placeholders in a manifest path that this prefix redirects,
+    /// leaving the rest to be filled in with the default locations.
+    pub fn apply(&self, path: &str) -> String {
+        let mut path = path.to_string();
+        for (placeholder, folder) in [
+            ("<winDocuments>", &self.documents),
+            ("<winAppData>", &self.app_data),
+            ("<winLocalAppData>", &self.local_app_data),
+            ("<home>", &self.profile),
+        ] {
+            if let Some(folder) = folder {
+                path = path.replace(placeholder, folder);
+            }
+        }
+        path
+    }
+}
+
+/// Whether a folder is where Wine would put it anyway, for any user.
+fn is_default_location(windows_path: &str, defaults: &[&str]) -> bool {
+    let path = windows_path.to_lowercase();
+    let path = path.trim_end_matches('\\');
+    match path.strip_prefix(r"c:\users\").and_then(|x| x.split_once('\\')) {
+        Some((_user, rest)) => defaults.iter().any(|x| x.to_lowercase() == rest),
+        None => false,
+    }
+}
+
+fn expand_user_profile(text: &str, profile: &str) -> String {
+    RE_USER_PROFILE.replacen(text, 1, regex::NoExpand(profile)).into_owned()
+}
+
+/// Convert a path like `C:\users\me\Documents` into the matching folder in the prefix.
+fn to_host_path(prefix: &str, windows_path: &str) -> Option<String> {
+    if windows_path.contains('%') {
+        // Some other variable that we can't expand.
+        return None;
+    }
+    let mut chars = windows_path.chars();
+    let letter = chars.next()?.to_ascii_lowercase();
+    if !letter.is_ascii_alphabetic() || chars.next()? != ':' {
+        return None;
+    }
+    let rest = chars.as_str().replace('\\', "/");
+    let rest = rest.trim_start_matches('/').trim_end_matches('/');
+
+    let drive = format!("{}/drive_{}", prefix, letter);
+    let drive = if std::path::Path::new(&drive).is_dir() {
+        drive
+    } else {
+        format!("{}/dosdevices/{}:", prefix, letter)
+    };
+    Some(format!("{}/{}", drive, rest))
+}
+
+/// Parse the keys and string values from a Wine registry file.
+/// Key paths and value names are lowercased, since the registry is case-insensitive.
+fn parse_reg(content: &str) -> std::collections::HashMap<String, std::collections::HashMap<String, String>> {
+    let mut keys = std::collections::HashMap::<String, std::collections::HashMap<String, String>>::new();
+    let mut current: Option<String> = None;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if let Some(header) = line.strip_prefix('[') {
+            current = header
+                .rsplit_once(']')
+                .map(|(key, _modified)| unescape(key).to_lowercase());
+            continue;
+        }
+        let key = match &current {
+            Some(x) => x,
+            None => continue,
+        };
+        let (name, value) = match line.strip_prefix('"').and_then(|x| x.split_once("\"=")) {
+            Some(x) => x,
+            None => continue,
+        };
+        // Expandable strings look like `str(2):"..."`.
+        let value = match value.split_once(':') {
+            Some((kind, rest)) if kind.starts_with("str(") => rest,
+            _ => value,
+        };
+        let value = match value.strip_prefix('"').and_then(|x| x.strip_suffix('"')) {
+            Some(x) => x,
+            None => continue,
+        };
+        keys.entry(key.clone())
+            .or_default()
+            .insert(unescape(name).to_lowercase(), unescape(value));
+    }
+
+    keys
+}
+
+fn unescape(text: &str) -> String {
+    text.replace(r"\\", "\\").replace("\\\"", "\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn can_read_redirected_folders() {
+        let content = r#"WINE REGISTRY Version 2
+;; All keys relative to \\User\\S-1-5-21-0-0-0-1000
+
+[Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\Shell Folders] 1600000000
+"AppData"="C:\\users\\me\\AppData\\Roaming"
+"Personal"="C:\\users\\me\\Stale Docs"
+
+[Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\User Shell Folders] 1600000000
+#time=1d6b0a0a0a0a0a0
+"Local AppData"=str(2):"D:\\Local"
+"Personal"=str(2):"%USERPROFILE%\\Custom Docs"
+
+[Volatile Environment] 1600000000
+"USERPROFILE"="C:\\users\\me"
+"#;
+
+        assert_eq!(
+            ShellFolders {
+                profile: None,
+                documents: Some("/prefix/dosdevices/c:/users/me/Custom Docs".to_string()),
+                app_data: None,
+                local_app_data: Some("/prefix/dosdevices/d:/Local".to_string()),
+            },
+            ShellFolders::from_registry("/prefix", content),
+        );
+    }
+
+    #[test]
+    fn ignores_default_folders() {
+        let content = r#"
+[Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\User Shell Folders] 1600000000
+"AppData"=str(2):"%USERPROFILE%\\Application Data"
+"Personal"=str(2):"%USERPROFILE%\\Documents"
+
+[Volatile Environment] 1600000000
+"USERPROFILE"="C:\\users\\steamuser"
+"#;
+
+        assert_eq!(ShellFolders::default(), ShellFolders::from_registry("/prefix", content));
+    }
+
+    #[test]
+    fn expands_user_profile_after_text_that_changes_length_when_lowercased() {
+        // `İ` is two bytes, but its lowercase form is three.
+        assert_eq!(
+            r"D:\İ\C:\users\me\Docs",
+            expand_user_profile(r"D:\İ\%userprofile%\Docs", r"C:\users\me")
+        );
+        assert_eq!(r"C:\users\$1", expand_user_profile(r"%USERPROFILE%", r"C:\users\$1"));
+    }
+}